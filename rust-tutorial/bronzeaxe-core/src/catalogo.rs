@@ -0,0 +1,1532 @@
+// ============================================================================
+// CATALOGO: IL MODELLO COMPLETO DEI REPERTI
+// ============================================================================
+// I tipi del catalogo di src/catalogo/ (reperti, componenti, periodi,
+// conservazione, persone, allegati, decorazioni, restauri e il resto),
+// senza std come il resto del crate. Le date sono quelle di chrono e la
+// serializzazione quella di serde, tutti e due senza le loro feature std.
+// I percorsi degli allegati sono `String` e non `PathBuf`, che esiste solo
+// con std: nel JSON non cambia nulla, un percorso si scrive come stringa.
+//
+// Qui stanno solo i dati e le regole che non toccano file o orologio;
+// l'inventario, gli archivi e il resto restano nella libreria con std, che
+// riesporta questi tipi da `catalogo::modelli`.
+// ============================================================================
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Materiale del reperto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Materiale {
+    Bronzo,
+    Ferro,
+    Oro,
+    Argento,
+    Ceramica,
+    Pietra,
+    Osso,
+    Altro(String),
+}
+
+impl fmt::Display for Materiale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Materiale::Bronzo => write!(f, "Bronzo"),
+            Materiale::Ferro => write!(f, "Ferro"),
+            Materiale::Oro => write!(f, "Oro"),
+            Materiale::Argento => write!(f, "Argento"),
+            Materiale::Ceramica => write!(f, "Ceramica"),
+            Materiale::Pietra => write!(f, "Pietra"),
+            Materiale::Osso => write!(f, "Osso"),
+            Materiale::Altro(s) => write!(f, "Altro: {}", s),
+        }
+    }
+}
+
+impl Materiale {
+    /// Il materiale nominato in un testo libero (una scheda, una frase
+    /// dettata): "bronzo", "br.", "terracotta"... La prima parola
+    /// riconosciuta vale.
+    ///
+    /// ```
+    /// use bronzeaxe_core::catalogo::Materiale;
+    ///
+    /// assert_eq!(Materiale::riconosci("Br. (lega di rame)"), Some(Materiale::Bronzo));
+    /// assert_eq!(Materiale::riconosci("impasto bruno"), Some(Materiale::Ceramica));
+    /// assert_eq!(Materiale::riconosci("lega di rame"), None);
+    /// ```
+    pub fn riconosci(testo: &str) -> Option<Materiale> {
+        testo.to_lowercase().split(|c: char| !c.is_alphanumeric()).find_map(|parola| {
+            let inizia = |prefissi: &[&str]| prefissi.iter().any(|p| parola.starts_with(p));
+            Some(match parola {
+                "br" | "bz" => Materiale::Bronzo,
+                "oro" | "au" => Materiale::Oro,
+                "ag" => Materiale::Argento,
+                _ if inizia(&["bronz"]) => Materiale::Bronzo,
+                _ if inizia(&["ferr"]) => Materiale::Ferro,
+                _ if inizia(&["argent"]) => Materiale::Argento,
+                _ if inizia(&["ceramic", "terracott", "impasto", "fittil"]) => Materiale::Ceramica,
+                _ if inizia(&["pietr", "selce", "litic"]) => Materiale::Pietra,
+                _ if inizia(&["oss", "corno"]) => Materiale::Osso,
+                _ => return None,
+            })
+        })
+    }
+}
+
+/// Parte di un reperto realizzata in un singolo materiale
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Componente {
+    pub parte: String,
+    pub materiale: Materiale,
+    /// Quota stimata del reperto, in percentuale
+    pub percentuale: Option<f64>,
+}
+
+impl Componente {
+    /// Reperto realizzato interamente in un solo materiale
+    pub fn intero(materiale: Materiale) -> Self {
+        Componente { parte: "intero".to_string(), materiale, percentuale: Some(100.0) }
+    }
+
+    pub fn parte(parte: &str, materiale: Materiale, percentuale: Option<f64>) -> Self {
+        Componente { parte: parte.to_string(), materiale, percentuale }
+    }
+}
+
+impl fmt::Display for Componente {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.parte, self.materiale)?;
+        if let Some(p) = self.percentuale {
+            write!(f, " (~{:.0}%)", p)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tecnica decorativa (vocabolario controllato)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TecnicaDecorativa {
+    Incisione,
+    Sbalzo,
+    Punzonatura,
+    Excisione,
+    Impressione,
+    Applicazione,
+}
+
+impl fmt::Display for TecnicaDecorativa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            TecnicaDecorativa::Incisione => "incisione",
+            TecnicaDecorativa::Sbalzo => "sbalzo",
+            TecnicaDecorativa::Punzonatura => "punzonatura",
+            TecnicaDecorativa::Excisione => "excisione",
+            TecnicaDecorativa::Impressione => "impressione",
+            TecnicaDecorativa::Applicazione => "applicazione",
+        };
+        write!(f, "{}", nome)
+    }
+}
+
+/// Motivo decorativo (vocabolario controllato)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MotivoDecorativo {
+    Spirali,
+    Cerchielli,
+    Linee,
+    Zigzag,
+    Triangoli,
+    Puntini,
+    Cordoni,
+    Barca,
+}
+
+impl fmt::Display for MotivoDecorativo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            MotivoDecorativo::Spirali => "spirali",
+            MotivoDecorativo::Cerchielli => "cerchielli",
+            MotivoDecorativo::Linee => "linee",
+            MotivoDecorativo::Zigzag => "zig-zag",
+            MotivoDecorativo::Triangoli => "triangoli",
+            MotivoDecorativo::Puntini => "puntini",
+            MotivoDecorativo::Cordoni => "cordoni",
+            MotivoDecorativo::Barca => "barca solare",
+        };
+        write!(f, "{}", nome)
+    }
+}
+
+/// Parte del reperto su cui si trova la decorazione
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PosizioneDecorazione {
+    Lama,
+    Tallone,
+    Immanicatura,
+    Arco,
+    Staffa,
+    Orlo,
+    Parete,
+    Fondo,
+    Ansa,
+}
+
+impl fmt::Display for PosizioneDecorazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            PosizioneDecorazione::Lama => "lama",
+            PosizioneDecorazione::Tallone => "tallone",
+            PosizioneDecorazione::Immanicatura => "immanicatura",
+            PosizioneDecorazione::Arco => "arco",
+            PosizioneDecorazione::Staffa => "staffa",
+            PosizioneDecorazione::Orlo => "orlo",
+            PosizioneDecorazione::Parete => "parete",
+            PosizioneDecorazione::Fondo => "fondo",
+            PosizioneDecorazione::Ansa => "ansa",
+        };
+        write!(f, "{}", nome)
+    }
+}
+
+/// Decorazione strutturata: tecnica, motivo e posizione
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Decorazione {
+    pub tecnica: TecnicaDecorativa,
+    pub motivo: MotivoDecorativo,
+    pub posizione: PosizioneDecorazione,
+}
+
+impl fmt::Display for Decorazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.motivo, self.tecnica, self.posizione)
+    }
+}
+
+/// Metodo con cui e stata fatta un'osservazione traceologica
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetodoOsservazione {
+    Macroscopica,
+    Stereomicroscopio,
+    MicroscopiaMetallografica,
+    Sem,
+    AnalisiResidui,
+}
+
+/// Interpretazione di una traccia d'uso o di un residuo
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterpretazioneTraccia {
+    Immanicatura,
+    Taglio,
+    Percussione,
+    Riaffilatura,
+    Martellatura,
+    ResiduoOrganico,
+    NonDiagnostica,
+}
+
+impl fmt::Display for InterpretazioneTraccia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            InterpretazioneTraccia::Immanicatura => "tracce di immanicatura",
+            InterpretazioneTraccia::Taglio => "uso da taglio",
+            InterpretazioneTraccia::Percussione => "uso da percussione",
+            InterpretazioneTraccia::Riaffilatura => "riaffilatura",
+            InterpretazioneTraccia::Martellatura => "martellatura del tagliente",
+            InterpretazioneTraccia::ResiduoOrganico => "residuo organico",
+            InterpretazioneTraccia::NonDiagnostica => "non diagnostica",
+        };
+        write!(f, "{}", nome)
+    }
+}
+
+/// Osservazione traceologica su una zona del reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Osservazione {
+    pub metodo: MetodoOsservazione,
+    /// Zona osservata (es. "tagliente", "tallone")
+    pub localizzazione: String,
+    pub interpretazione: InterpretazioneTraccia,
+    pub note: Option<String>,
+}
+
+impl fmt::Display for Osservazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} su {} ({:?})", self.interpretazione, self.localizzazione, self.metodo)?;
+        if let Some(note) = &self.note {
+            write!(f, " - {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Classe funzionale, indipendente dalla tipologia morfologica
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ClasseFunzionale {
+    Arma,
+    Utensile,
+    Ornamento,
+    Lingotto,
+    Votivo,
+}
+
+impl fmt::Display for ClasseFunzionale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nome = match self {
+            ClasseFunzionale::Arma => "Arma",
+            ClasseFunzionale::Utensile => "Utensile",
+            ClasseFunzionale::Ornamento => "Ornamento",
+            ClasseFunzionale::Lingotto => "Lingotto",
+            ClasseFunzionale::Votivo => "Votivo",
+        };
+        f.pad(nome)
+    }
+}
+
+/// Periodo storico (le varianti sono in ordine cronologico)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Periodo {
+    BronzoAntico,     // 2300-1700 a.C.
+    BronzoMedio,      // 1700-1350 a.C.
+    BronzoRecente,    // 1350-1200 a.C.
+    BronzoFinale,     // 1200-950 a.C.
+    PrimaEtaFerro,   // 950-750 a.C.
+    Sconosciuto,
+}
+
+impl Periodo {
+    /// Estremi cronologici in anni astronomici (negativi = a.C.)
+    pub fn anni(&self) -> Option<(i32, i32)> {
+        match self {
+            Periodo::BronzoAntico => Some((-2300, -1700)),
+            Periodo::BronzoMedio => Some((-1700, -1350)),
+            Periodo::BronzoRecente => Some((-1350, -1200)),
+            Periodo::BronzoFinale => Some((-1200, -950)),
+            Periodo::PrimaEtaFerro => Some((-950, -750)),
+            Periodo::Sconosciuto => None,
+        }
+    }
+}
+
+impl fmt::Display for Periodo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Periodo::BronzoAntico => write!(f, "Bronzo Antico (2300-1700 a.C.)"),
+            Periodo::BronzoMedio => write!(f, "Bronzo Medio (1700-1350 a.C.)"),
+            Periodo::BronzoRecente => write!(f, "Bronzo Recente (1350-1200 a.C.)"),
+            Periodo::BronzoFinale => write!(f, "Bronzo Finale (1200-950 a.C.)"),
+            Periodo::PrimaEtaFerro => write!(f, "Prima Eta del Ferro (950-750 a.C.)"),
+            Periodo::Sconosciuto => write!(f, "Periodo sconosciuto"),
+        }
+    }
+}
+
+/// Stato di conservazione
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Conservazione {
+    Integro,
+    Buono,
+    Discreto,
+    Frammentario,
+    Pessimo,
+}
+
+impl Conservazione {
+    pub fn punteggio(&self) -> u8 {
+        match self {
+            Conservazione::Integro => 5,
+            Conservazione::Buono => 4,
+            Conservazione::Discreto => 3,
+            Conservazione::Frammentario => 2,
+            Conservazione::Pessimo => 1,
+        }
+    }
+
+    /// Da maneggiare e imballare con cura: frammentario o in pessimo stato
+    pub fn fragile(&self) -> bool {
+        matches!(self, Conservazione::Frammentario | Conservazione::Pessimo)
+    }
+
+    /// Lo stato nominato in un testo libero: "buono", "frammentaria",
+    /// "lacunoso"...
+    pub fn riconosci(testo: &str) -> Option<Conservazione> {
+        const PREFISSI: [(&str, Conservazione); 10] = [
+            ("integr", Conservazione::Integro),
+            ("buon", Conservazione::Buono),
+            ("ottim", Conservazione::Buono),
+            ("discret", Conservazione::Discreto),
+            ("mediocr", Conservazione::Discreto),
+            ("framment", Conservazione::Frammentario),
+            ("lacunos", Conservazione::Frammentario),
+            ("pessim", Conservazione::Pessimo),
+            ("cattiv", Conservazione::Pessimo),
+            ("perdut", Conservazione::Pessimo),
+        ];
+        testo.to_lowercase().split(|c: char| !c.is_alphanumeric()).find_map(|parola| {
+            PREFISSI.iter().find(|(p, _)| parola.starts_with(p)).map(|(_, c)| c.clone())
+        })
+    }
+}
+
+impl fmt::Display for Conservazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conservazione::Integro => write!(f, "Integro"),
+            Conservazione::Buono => write!(f, "Buono"),
+            Conservazione::Discreto => write!(f, "Discreto"),
+            Conservazione::Frammentario => write!(f, "Frammentario"),
+            Conservazione::Pessimo => write!(f, "Pessimo"),
+        }
+    }
+}
+
+/// Coordinate geografiche
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub latitudine: f64,
+    pub longitudine: f64,
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:.4}, {:.4})", self.latitudine, self.longitudine)
+    }
+}
+
+/// Come sono state ottenute le coordinate di un reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrigineCoordinate {
+    /// Es. "GPX garmin_2024-06-12.gpx"
+    pub fonte: String,
+    /// Nome del waypoint o del punto traccia usato
+    pub punto: Option<String>,
+    /// Scarto temporale tra rinvenimento e punto GPS, in secondi
+    pub scarto_secondi: i64,
+}
+
+/// Quadrante di un quadrato di scavo
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Quadrante {
+    NO,
+    NE,
+    SO,
+    SE,
+}
+
+/// Posizione di rinvenimento nella griglia di scavo (es. quadrato "B4", quadrante NE)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PosizioneScavo {
+    pub quadrato: String,
+    pub quadrante: Option<Quadrante>,
+    /// Profondita dal piano di campagna, in cm
+    pub profondita_cm: Option<f64>,
+    /// Quota assoluta (m s.l.m.)
+    #[serde(default)]
+    pub quota_m: Option<f64>,
+}
+
+/// Campagna di scavo con i suoi estremi autorizzativi. I riferimenti
+/// possono arrivare in momenti diversi (il decreto di deposito e
+/// successivo allo scavo), ma servono tutti per pubblicare
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampagnaScavo {
+    /// Codice della campagna, es. "SAV-2019"
+    pub codice: String,
+    pub sito: String,
+    pub anno: i32,
+    /// Numero della concessione di scavo
+    pub concessione: Option<String>,
+    /// Estremi dell'autorizzazione ministeriale
+    pub autorizzazione_ministeriale: Option<String>,
+    /// Decreto di deposito dei materiali
+    pub decreto_deposito: Option<String>,
+}
+
+impl CampagnaScavo {
+    /// Riferimenti non ancora compilati
+    pub fn mancanti(&self) -> Vec<&'static str> {
+        let vuoto = |v: &Option<String>| v.as_deref().is_none_or(|s| s.trim().is_empty());
+        [
+            ("concessione", &self.concessione),
+            ("autorizzazione ministeriale", &self.autorizzazione_ministeriale),
+            ("decreto di deposito", &self.decreto_deposito),
+        ]
+        .into_iter()
+        .filter(|(_, v)| vuoto(v))
+        .map(|(nome, _)| nome)
+        .collect()
+    }
+}
+
+impl fmt::Display for PosizioneScavo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "q. {}", self.quadrato)?;
+        if let Some(q) = self.quadrante {
+            write!(f, " {:?}", q)?;
+        }
+        if let Some(p) = self.profondita_cm {
+            write!(f, ", -{:.0} cm", p)?;
+        }
+        if let Some(q) = self.quota_m {
+            write!(f, ", {:.2} m s.l.m.", q)?;
+        }
+        Ok(())
+    }
+}
+
+/// Misurazioni del reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Misurazioni {
+    pub lunghezza_cm: Option<f64>,
+    pub larghezza_cm: Option<f64>,
+    pub altezza_cm: Option<f64>,
+    pub peso_grammi: Option<f64>,
+}
+
+impl Misurazioni {
+    pub fn nuove() -> Self {
+        Misurazioni {
+            lunghezza_cm: None,
+            larghezza_cm: None,
+            altezza_cm: None,
+            peso_grammi: None,
+        }
+    }
+
+    pub fn con_dimensioni(mut self, l: f64, w: f64, h: f64) -> Self {
+        self.lunghezza_cm = Some(l);
+        self.larghezza_cm = Some(w);
+        self.altezza_cm = Some(h);
+        self
+    }
+
+    pub fn con_peso(mut self, p: f64) -> Self {
+        self.peso_grammi = Some(p);
+        self
+    }
+
+    pub fn volume_approssimativo(&self) -> Option<f64> {
+        match (self.lunghezza_cm, self.larghezza_cm, self.altezza_cm) {
+            (Some(l), Some(w), Some(h)) => Some(l * w * h),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Misurazioni {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parti = Vec::new();
+        if let Some(l) = self.lunghezza_cm {
+            parti.push(format!("L:{:.1}cm", l));
+        }
+        if let Some(w) = self.larghezza_cm {
+            parti.push(format!("W:{:.1}cm", w));
+        }
+        if let Some(h) = self.altezza_cm {
+            parti.push(format!("H:{:.1}cm", h));
+        }
+        if let Some(p) = self.peso_grammi {
+            parti.push(format!("{:.0}g", p));
+        }
+        if parti.is_empty() {
+            write!(f, "N/D")
+        } else {
+            write!(f, "{}", parti.join(", "))
+        }
+    }
+}
+
+/// Licenza d'uso di una scheda o di un file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Licenza {
+    Cc0,
+    CcBy,
+    CcBySa,
+    CcByNc,
+    CcByNcNd,
+    TuttiIDirittiRiservati,
+}
+
+impl fmt::Display for Licenza {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Licenza::Cc0 => write!(f, "CC0 1.0"),
+            Licenza::CcBy => write!(f, "CC BY 4.0"),
+            Licenza::CcBySa => write!(f, "CC BY-SA 4.0"),
+            Licenza::CcByNc => write!(f, "CC BY-NC 4.0"),
+            Licenza::CcByNcNd => write!(f, "CC BY-NC-ND 4.0"),
+            Licenza::TuttiIDirittiRiservati => write!(f, "Tutti i diritti riservati"),
+        }
+    }
+}
+
+/// Dichiarazione dei diritti: licenza, titolare ed eventuale embargo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diritti {
+    pub licenza: Licenza,
+    pub titolare: String,
+    pub embargo_fino_al: Option<NaiveDate>,
+}
+
+impl Diritti {
+    /// Vero se alla data indicata l'embargo non e ancora scaduto
+    pub fn in_embargo(&self, oggi: NaiveDate) -> bool {
+        self.embargo_fino_al.is_some_and(|fine| oggi < fine)
+    }
+}
+
+impl fmt::Display for Diritti {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(c) {} - {}", self.titolare, self.licenza)?;
+        if let Some(fine) = self.embargo_fino_al {
+            write!(f, " [embargo fino al {}]", fine.format("%d/%m/%Y"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formati 3D supportati per i modelli da fotogrammetria
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FormatoModello {
+    Obj,
+    Ply,
+    Glb,
+}
+
+impl fmt::Display for FormatoModello {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatoModello::Obj => write!(f, "OBJ"),
+            FormatoModello::Ply => write!(f, "PLY"),
+            FormatoModello::Glb => write!(f, "GLB"),
+        }
+    }
+}
+
+/// Metadati di un modello 3D
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Modello3D {
+    pub formato: FormatoModello,
+    pub triangoli: u32,
+    /// Riferimento metrico usato per scalare il modello (es. "scala 10 cm")
+    pub riferimento_scala: Option<String>,
+    pub software_acquisizione: String,
+}
+
+/// Formati di documento accettati come allegato
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FormatoDocumento {
+    Pdf,
+    Docx,
+}
+
+/// Natura del documento allegato
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CategoriaDocumento {
+    RelazioneScavo,
+    Analisi,
+    ContrattoPrestito,
+    Altro(String),
+}
+
+impl fmt::Display for CategoriaDocumento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CategoriaDocumento::RelazioneScavo => write!(f, "Relazione di scavo"),
+            CategoriaDocumento::Analisi => write!(f, "Analisi"),
+            CategoriaDocumento::ContrattoPrestito => write!(f, "Contratto di prestito"),
+            CategoriaDocumento::Altro(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Tipo di allegato
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub enum TipoAllegato {
+    #[default]
+    Generico,
+    Immagine,
+    Modello3D(Modello3D),
+    Documento {
+        formato: FormatoDocumento,
+        categoria: CategoriaDocumento,
+    },
+}
+
+/// File allegato a un reperto (foto, disegno, scansione)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Allegato {
+    pub percorso: String,
+    #[serde(default)]
+    pub tipo: TipoAllegato,
+    pub impronta_sha256: String,
+    pub dimensione_byte: u64,
+    #[serde(default)]
+    pub miniature: Option<Miniature>,
+    #[serde(default)]
+    pub diritti: Option<Diritti>,
+    /// Autore della foto o del disegno
+    #[serde(default)]
+    pub autore: Option<u32>,
+}
+
+/// Miniature generate accanto all'immagine originale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Miniature {
+    pub piccola: String,
+    pub media: String,
+}
+
+impl fmt::Display for Allegato {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} byte)", self.percorso, self.dimensione_byte)
+    }
+}
+
+/// Tappa nella storia di custodia/proprieta di un reperto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TipoEventoProvenienza {
+    Scavo,
+    Deposito,
+    IngressoMuseo,
+    Prestito,
+    Restituzione,
+    Acquisizione,
+}
+
+impl fmt::Display for TipoEventoProvenienza {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TipoEventoProvenienza::Scavo => write!(f, "Scavo"),
+            TipoEventoProvenienza::Deposito => write!(f, "Deposito"),
+            TipoEventoProvenienza::IngressoMuseo => write!(f, "Ingresso in museo"),
+            TipoEventoProvenienza::Prestito => write!(f, "Prestito"),
+            TipoEventoProvenienza::Restituzione => write!(f, "Restituzione"),
+            TipoEventoProvenienza::Acquisizione => write!(f, "Acquisizione"),
+        }
+    }
+}
+
+/// Evento della catena di provenienza
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventoProvenienza {
+    pub tipo: TipoEventoProvenienza,
+    pub data: NaiveDate,
+    /// Ente o persona che detiene il reperto dopo l'evento
+    pub detentore: String,
+    /// Riferimento al documento che attesta l'evento (verbale, decreto, contratto)
+    pub documento: Option<String>,
+}
+
+impl fmt::Display for EventoProvenienza {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} -> {}", self.data.format("%d/%m/%Y"), self.tipo, self.detentore)?;
+        if let Some(doc) = &self.documento {
+            write!(f, " [{}]", doc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Stima del valore economico di un reperto (ai fini assicurativi)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stima {
+    pub valore: f64,
+    /// Codice ISO 4217 (es. "EUR")
+    pub valuta: String,
+    pub data: NaiveDate,
+    pub perito: String,
+}
+
+impl fmt::Display for Stima {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {} ({}, {})", self.valore, self.valuta, self.data.format("%d/%m/%Y"), self.perito)
+    }
+}
+
+/// Esito di un controllo dello stato di conservazione
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlloConservazione {
+    pub data: NaiveDate,
+    pub conservazione: Conservazione,
+    pub campagna: String,
+}
+
+/// Motivo per cui un reperto esce definitivamente dall'inventario
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MotivoRadiazione {
+    /// Scarico inventariale autorizzato (cessione, trasferimento ad altro ente)
+    Scarico,
+    Smarrimento,
+    Furto,
+    Distruzione,
+}
+
+impl fmt::Display for MotivoRadiazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MotivoRadiazione::Scarico => write!(f, "Scarico inventariale"),
+            MotivoRadiazione::Smarrimento => write!(f, "Smarrimento"),
+            MotivoRadiazione::Furto => write!(f, "Furto"),
+            MotivoRadiazione::Distruzione => write!(f, "Distruzione"),
+        }
+    }
+}
+
+/// Dati della radiazione: la scheda resta come memoria del reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Radiazione {
+    pub motivo: MotivoRadiazione,
+    pub data: NaiveDate,
+    /// Decreto, verbale o denuncia che autorizza o attesta la radiazione
+    pub documento: String,
+    pub ultima_collocazione: Option<String>,
+}
+
+impl fmt::Display for Radiazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} il {} ({})", self.motivo, self.data.format("%d/%m/%Y"), self.documento)?;
+        if let Some(c) = &self.ultima_collocazione {
+            write!(f, ", ultima collocazione nota: {}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Spostamento fisico di un reperto (cassa -> vetrina -> laboratorio)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Movimento {
+    pub quando: NaiveDateTime,
+    pub da: Option<String>,
+    pub a: String,
+    pub responsabile: String,
+    pub motivo: Option<String>,
+}
+
+impl fmt::Display for Movimento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} (resp. {})",
+            self.quando.format("%d/%m/%Y %H:%M"),
+            self.da.as_deref().unwrap_or("?"),
+            self.a,
+            self.responsabile
+        )?;
+        if let Some(motivo) = &self.motivo {
+            write!(f, " - {}", motivo)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prodotto consumabile impiegato in un intervento (es. Paraloid B-72)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProdottoUsato {
+    pub prodotto: String,
+    pub lotto: String,
+    pub quantita: Option<String>,
+}
+
+impl fmt::Display for ProdottoUsato {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (lotto {})", self.prodotto, self.lotto)?;
+        if let Some(quantita) = &self.quantita {
+            write!(f, " {}", quantita)?;
+        }
+        Ok(())
+    }
+}
+
+/// Intervento conservativo o di restauro eseguito su un reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Intervento {
+    pub data: NaiveDate,
+    pub descrizione: String,
+    pub restauratore: String,
+    pub prodotti: Vec<ProdottoUsato>,
+}
+
+/// Ruolo di una persona nel progetto
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RuoloPersona {
+    Archeologo,
+    Restauratore,
+    Disegnatore,
+    Fotografo,
+}
+
+/// Persona coinvolta nello scavo o nella gestione della collezione
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Persona {
+    pub id: u32,
+    pub nome: String,
+    pub ruoli: Vec<RuoloPersona>,
+    /// iD ORCID nella forma 0000-0002-1825-0097
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
+    /// Permessi sul flusso di catalogazione quando la persona accede
+    /// all'inventario; nessuno se non ne ha
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruolo_utente: Option<RuoloUtente>,
+}
+
+/// Controlla formato e cifra di controllo (ISO 7064 MOD 11-2) di un iD ORCID
+pub fn orcid_valido(orcid: &str) -> bool {
+    let cifre: Vec<char> = orcid.chars().filter(|c| *c != '-').collect();
+    if orcid.len() != 19 || cifre.len() != 16 || orcid.split('-').count() != 4 {
+        return false;
+    }
+    let mut totale = 0u32;
+    for c in &cifre[..15] {
+        let Some(d) = c.to_digit(10) else {
+            return false;
+        };
+        totale = (totale + d) * 2;
+    }
+    let controllo = (12 - totale % 11) % 11;
+    let atteso = if controllo == 10 { 'X' } else { char::from_digit(controllo, 10).unwrap_or('?') };
+    cifre[15] == atteso
+}
+
+/// DOI in forma canonica: senza prefisso di risoluzione e in minuscolo
+pub fn normalizza_doi(doi: &str) -> String {
+    let doi = doi.trim();
+    let doi = ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "doi:"]
+        .iter()
+        .find_map(|p| doi.strip_prefix(p))
+        .unwrap_or(doi);
+    doi.to_lowercase()
+}
+
+/// Metadati bibliografici di un'opera identificata da DOI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Opera {
+    pub doi: String,
+    pub titolo: String,
+    /// Nella forma "Cognome, Nome"
+    pub autori: Vec<String>,
+    pub anno: Option<i32>,
+    /// Rivista o volume che contiene l'opera
+    pub contenitore: Option<String>,
+    pub volume: Option<String>,
+    pub pagine: Option<String>,
+    pub editore: Option<String>,
+}
+
+/// Voce della bibliografia di un reperto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiferimentoBibliografico {
+    /// Citazione come scritta dal catalogatore (es. "Carancini 1984, n. 312")
+    pub citazione: String,
+    pub doi: Option<String>,
+    /// Pagine, figure o tavole in cui compare il reperto
+    pub punto: Option<String>,
+    /// Metadati completi, se il DOI e stato risolto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opera: Option<Opera>,
+}
+
+impl fmt::Display for Persona {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ruoli: Vec<String> = self.ruoli.iter().map(|r| format!("{:?}", r)).collect();
+        write!(f, "{} ({})", self.nome, ruoli.join(", "))
+    }
+}
+
+/// Tipo di operazione annotata nel registro delle attivita
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operazione {
+    Creazione,
+    Modifica,
+    CambioStato,
+    Rimozione,
+    Radiazione,
+}
+
+/// Richiesta di rimozione di una scheda catalogata, in attesa di approvazione
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RichiestaRimozione {
+    pub numero: u32,
+    pub reperto: u32,
+    pub richiesta_da: u32,
+    pub quando: NaiveDateTime,
+}
+
+/// Voce del registro: chi ha fatto cosa, su quale reperto e quando
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoceRegistro {
+    pub quando: NaiveDateTime,
+    pub persona: u32,
+    pub reperto: u32,
+    pub operazione: Operazione,
+}
+
+/// Nota testuale, eventualmente attribuita a una persona
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "NotaSerializzata")]
+pub struct Nota {
+    pub testo: String,
+    pub autore: Option<u32>,
+}
+
+/// Le note dei file JSON precedenti sono semplici stringhe
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NotaSerializzata {
+    Testo(String),
+    Completa { testo: String, autore: Option<u32> },
+}
+
+impl From<NotaSerializzata> for Nota {
+    fn from(n: NotaSerializzata) -> Self {
+        match n {
+            NotaSerializzata::Testo(testo) => Nota { testo, autore: None },
+            NotaSerializzata::Completa { testo, autore } => Nota { testo, autore },
+        }
+    }
+}
+
+impl From<&str> for Nota {
+    fn from(testo: &str) -> Self {
+        Nota { testo: testo.to_string(), autore: None }
+    }
+}
+
+impl fmt::Display for Nota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.testo)
+    }
+}
+
+/// Stato della scheda nel flusso di catalogazione
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StatoCatalogazione {
+    #[default]
+    InIngresso,
+    Catalogato,
+    Verificato,
+    Pubblicato,
+}
+
+/// Ruolo dell'utente ai fini dei permessi sul flusso di catalogazione
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuoloUtente {
+    Catalogatore,
+    Revisore,
+    Responsabile,
+}
+
+impl StatoCatalogazione {
+    /// Ruoli autorizzati a portare una scheda da `self` a `verso`.
+    /// Un elenco vuoto significa che la transizione non e ammessa.
+    pub fn ruoli_per_transizione(self, verso: StatoCatalogazione) -> &'static [RuoloUtente] {
+        use RuoloUtente::*;
+        use StatoCatalogazione::*;
+        match (self, verso) {
+            (InIngresso, Catalogato) => &[Catalogatore, Responsabile],
+            (Catalogato, InIngresso) => &[Catalogatore, Revisore, Responsabile],
+            (Catalogato, Verificato) => &[Revisore, Responsabile],
+            (Verificato, Catalogato) => &[Revisore, Responsabile],
+            (Verificato, Pubblicato) => &[Responsabile],
+            (Pubblicato, Verificato) => &[Responsabile],
+            _ => &[],
+        }
+    }
+
+    /// Ruoli autorizzati a modificare una scheda in questo stato
+    pub fn ruoli_per_modifica(self) -> &'static [RuoloUtente] {
+        use RuoloUtente::*;
+        match self {
+            StatoCatalogazione::InIngresso | StatoCatalogazione::Catalogato => {
+                &[Catalogatore, Revisore, Responsabile]
+            }
+            StatoCatalogazione::Verificato => &[Revisore, Responsabile],
+            StatoCatalogazione::Pubblicato => &[Responsabile],
+        }
+    }
+}
+
+impl fmt::Display for StatoCatalogazione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatoCatalogazione::InIngresso => write!(f, "in ingresso"),
+            StatoCatalogazione::Catalogato => write!(f, "catalogato"),
+            StatoCatalogazione::Verificato => write!(f, "verificato"),
+            StatoCatalogazione::Pubblicato => write!(f, "pubblicato"),
+        }
+    }
+}
+
+/// Reperto archeologico - la struct principale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reperto {
+    /// Numero progressivo, unico solo in questo inventario
+    pub id: u32,
+    /// Identificativo globale (UUID o codice come "SAV-0001") assegnato
+    /// secondo la `StrategiaId` dell'inventario; resta uguale quando si
+    /// uniscono inventari di scavi diversi
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identificativo: Option<String>,
+    pub nome: String,
+    pub descrizione: String,
+    /// Componenti in ordine di importanza
+    pub componenti: Vec<Componente>,
+    #[serde(default)]
+    pub decorazioni: Vec<Decorazione>,
+    #[serde(default)]
+    pub classe_funzionale: Option<ClasseFunzionale>,
+    /// Tipo morfologico secondo la tipologia di riferimento
+    #[serde(default)]
+    pub tipo: Option<String>,
+    pub periodo: Periodo,
+    pub conservazione: Conservazione,
+    /// Parte conservata dell'oggetto originale, in percentuale
+    #[serde(default)]
+    pub completo_stimato_percento: Option<f64>,
+    pub sito: String,
+    pub coordinate: Option<Coordinate>,
+    #[serde(default)]
+    pub origine_coordinate: Option<OrigineCoordinate>,
+    /// Data e ora del rinvenimento (UTC), se registrate sul campo
+    #[serde(default)]
+    pub rinvenuto_il: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub posizione_scavo: Option<PosizioneScavo>,
+    pub misurazioni: Misurazioni,
+    pub note: Vec<Nota>,
+    #[serde(default)]
+    pub stato: StatoCatalogazione,
+    #[serde(default)]
+    pub allegati: Vec<Allegato>,
+    #[serde(default)]
+    pub diritti: Option<Diritti>,
+    /// Catena di provenienza in ordine cronologico
+    #[serde(default)]
+    pub provenienza: Vec<EventoProvenienza>,
+    #[serde(default)]
+    pub collezione: Option<String>,
+    /// Persona che ha rinvenuto il reperto
+    #[serde(default)]
+    pub rinvenuto_da: Option<u32>,
+    /// Collocazione fisica attuale (deposito, cassa, vetrina)
+    #[serde(default)]
+    pub collocazione: Option<String>,
+    /// Storico delle stime di valore
+    #[serde(default)]
+    pub stime: Vec<Stima>,
+    /// Storico dei controlli di conservazione
+    #[serde(default)]
+    pub controlli: Vec<ControlloConservazione>,
+    /// Storico delle movimentazioni, in ordine cronologico
+    #[serde(default)]
+    pub movimenti: Vec<Movimento>,
+    /// Interventi di restauro, in ordine cronologico
+    #[serde(default)]
+    pub interventi: Vec<Intervento>,
+    /// Osservazioni traceologiche
+    #[serde(default)]
+    pub osservazioni: Vec<Osservazione>,
+    /// Cresce a ogni modifica: serve a rilevare modifiche concorrenti
+    #[serde(default)]
+    pub versione: u64,
+    /// Presente solo per i reperti radiati dall'inventario
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radiazione: Option<Radiazione>,
+    /// Codice della campagna di scavo in cui e stato rinvenuto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campagna_scavo: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bibliografia: Vec<RiferimentoBibliografico>,
+}
+
+impl Reperto {
+    /// Nuovo reperto in un solo materiale; gli altri campi restano vuoti
+    pub fn nuovo(nome: &str, materiale: Materiale, periodo: Periodo, conservazione: Conservazione, sito: &str) -> Self {
+        Reperto {
+            id: 0,
+            identificativo: None,
+            nome: nome.to_string(),
+            descrizione: String::new(),
+            componenti: vec![Componente::intero(materiale)],
+            decorazioni: vec![],
+            classe_funzionale: None,
+            tipo: None,
+            periodo,
+            conservazione,
+            completo_stimato_percento: None,
+            sito: sito.to_string(),
+            coordinate: None,
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
+            misurazioni: Misurazioni::nuove(),
+            note: vec![],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
+        }
+    }
+
+    pub fn con_misurazioni(mut self, misurazioni: Misurazioni) -> Self {
+        self.misurazioni = misurazioni;
+        self
+    }
+
+    pub fn con_classe_funzionale(mut self, classe: ClasseFunzionale) -> Self {
+        self.classe_funzionale = Some(classe);
+        self
+    }
+
+    pub fn con_tipo(mut self, tipo: &str) -> Self {
+        self.tipo = Some(tipo.to_string());
+        self
+    }
+
+    /// Oggetto completo: secondo la stima di completezza se presente,
+    /// altrimenti secondo lo stato di conservazione
+    pub fn e_completo(&self) -> bool {
+        match self.completo_stimato_percento {
+            Some(p) => p >= 95.0,
+            None => self.conservazione != Conservazione::Frammentario,
+        }
+    }
+
+    /// Materiale principale: il componente con la quota stimata maggiore,
+    /// altrimenti il primo dell'elenco
+    pub fn materiale(&self) -> Option<&Materiale> {
+        self.componenti
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| {
+                let quota = |c: &Componente| c.percentuale.unwrap_or(0.0);
+                quota(a).total_cmp(&quota(b)).then(j.cmp(i))
+            })
+            .map(|(_, c)| &c.materiale)
+    }
+
+    /// Reperto da scavo: legato a una campagna, con posizione di scavo
+    /// o con la catena di provenienza che parte da uno scavo
+    pub fn da_scavo(&self) -> bool {
+        self.campagna_scavo.is_some()
+            || self.posizione_scavo.is_some()
+            || self.provenienza.first().is_some_and(|e| e.tipo == TipoEventoProvenienza::Scavo)
+    }
+
+    /// Vero se almeno un componente e del materiale indicato
+    pub fn contiene_materiale(&self, materiale: &Materiale) -> bool {
+        self.componenti.iter().any(|c| &c.materiale == materiale)
+    }
+
+    /// Detentore attuale secondo la catena di provenienza
+    pub fn detentore_attuale(&self) -> Option<&str> {
+        self.provenienza.last().map(|e| e.detentore.as_str())
+    }
+
+    /// Rischio di deterioramento da 1 (basso) a 5 (alto): combina lo stato
+    /// attuale con la fragilita del materiale (il ferro si ossida rapidamente)
+    pub fn punteggio_rischio(&self) -> u8 {
+        let base = 6 - self.conservazione.punteggio();
+        // Basta un componente fragile per aumentare il rischio
+        let materiale = self
+            .componenti
+            .iter()
+            .any(|c| matches!(c.materiale, Materiale::Ferro | Materiale::Osso)) as u8;
+        (base + materiale).min(5)
+    }
+
+    /// Data dell'ultimo controllo di conservazione
+    pub fn ultimo_controllo(&self) -> Option<NaiveDate> {
+        self.controlli.iter().map(|c| c.data).max()
+    }
+
+    /// Collocazione alla data/ora indicata secondo lo storico movimenti
+    pub fn collocazione_al(&self, quando: NaiveDateTime) -> Option<&str> {
+        self.movimenti
+            .iter()
+            .take_while(|m| m.quando <= quando)
+            .last()
+            .map(|m| m.a.as_str())
+    }
+
+    /// Stima piu recente, se presente
+    pub fn stima_corrente(&self) -> Option<&Stima> {
+        self.stime.iter().max_by_key(|s| s.data)
+    }
+
+    /// Costruzione campo per campo, vedi `RepertoBuilder`
+    pub fn builder() -> RepertoBuilder {
+        RepertoBuilder::default()
+    }
+
+    /// Controlli comuni a nuovi reperti e reperti ricaricati da archivio:
+    /// nome non vuoto, componenti validi, completezza tra 0 e 100.
+    ///
+    /// ```
+    /// use bronzeaxe_core::catalogo::{Conservazione, ErroreReperto, Materiale, Periodo, Reperto};
+    ///
+    /// let mut ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+    /// assert_eq!(ascia.valida(), Ok(()));
+    /// ascia.completo_stimato_percento = Some(120.0);
+    /// assert!(matches!(ascia.valida(), Err(ErroreReperto::DatiNonValidi(_))));
+    /// ```
+    pub fn valida(&self) -> Result<(), ErroreReperto> {
+        if self.nome.trim().is_empty() {
+            return Err(ErroreReperto::NomeVuoto);
+        }
+        valida_componenti(&self.componenti)?;
+        if let Some(p) = self.completo_stimato_percento {
+            if !(p > 0.0 && p <= 100.0) {
+                return Err(ErroreReperto::DatiNonValidi(format!(
+                    "completezza stimata non valida: {}%",
+                    p
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Almeno un componente, quote comprese tra 0 e 100 e somma non oltre 100
+fn valida_componenti(componenti: &[Componente]) -> Result<(), ErroreReperto> {
+    if componenti.is_empty() {
+        return Err(ErroreReperto::DatiNonValidi(
+            "il reperto deve avere almeno un componente".to_string(),
+        ));
+    }
+    let mut totale = 0.0;
+    for c in componenti {
+        if let Some(p) = c.percentuale {
+            if !(0.0..=100.0).contains(&p) {
+                return Err(ErroreReperto::DatiNonValidi(format!(
+                    "percentuale non valida per '{}': {}",
+                    c.parte, p
+                )));
+            }
+            totale += p;
+        }
+    }
+    if totale > 100.0 {
+        return Err(ErroreReperto::DatiNonValidi(format!(
+            "le percentuali dei componenti sommano a {:.1}",
+            totale
+        )));
+    }
+    Ok(())
+}
+
+/// Perche un reperto del catalogo non e valido. Con std diventa
+/// `ErroreInventario`, con le stesse varianti e gli stessi messaggi.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ErroreReperto {
+    #[error("Il nome del reperto non puo essere vuoto")]
+    NomeVuoto,
+    #[error("Dati non validi: {0}")]
+    DatiNonValidi(String),
+}
+
+/// Costruisce un `Reperto` un campo alla volta, senza scrivere l'id o i
+/// campi vuoti. Nome, materiale, sito e conservazione sono obbligatori;
+/// il periodo, se non indicato, e `Sconosciuto`. `build()` applica gli
+/// stessi controlli di `Reperto::valida`.
+///
+/// ```
+/// use bronzeaxe_core::catalogo::{Conservazione, Materiale, Misurazioni, Periodo, Reperto};
+///
+/// let ascia = Reperto::builder()
+///     .nome("Ascia a margini rialzati")
+///     .materiale(Materiale::Bronzo)
+///     .periodo(Periodo::BronzoMedio)
+///     .conservazione(Conservazione::Buono)
+///     .sito("Frattesina")
+///     .coordinate(45.03, 11.58)
+///     .misurazioni(Misurazioni::nuove().con_peso(327.6))
+///     .build()
+///     .unwrap();
+/// assert_eq!(ascia.to_string(), "#0 Ascia a margini rialzati (Bronzo, Bronzo Medio (1700-1350 a.C.), Buono)");
+///
+/// let errore = Reperto::builder().nome("Spillone").materiale(Materiale::Bronzo).build().unwrap_err();
+/// assert_eq!(errore.to_string(), "Dati non validi: campi obbligatori mancanti: sito, conservazione");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RepertoBuilder {
+    nome: Option<String>,
+    descrizione: String,
+    componenti: Vec<Componente>,
+    classe_funzionale: Option<ClasseFunzionale>,
+    tipo: Option<String>,
+    periodo: Option<Periodo>,
+    conservazione: Option<Conservazione>,
+    completo_stimato_percento: Option<f64>,
+    sito: Option<String>,
+    coordinate: Option<Coordinate>,
+    rinvenuto_il: Option<NaiveDateTime>,
+    misurazioni: Option<Misurazioni>,
+    collezione: Option<String>,
+    campagna_scavo: Option<String>,
+}
+
+impl RepertoBuilder {
+    pub fn nome(mut self, nome: &str) -> Self {
+        self.nome = Some(nome.to_string());
+        self
+    }
+
+    pub fn descrizione(mut self, descrizione: &str) -> Self {
+        self.descrizione = descrizione.to_string();
+        self
+    }
+
+    /// Reperto in un solo materiale: sostituisce i componenti gia indicati
+    pub fn materiale(mut self, materiale: Materiale) -> Self {
+        self.componenti = vec![Componente::intero(materiale)];
+        self
+    }
+
+    /// Aggiunge una parte in un altro materiale (lama, manico, rivetti...)
+    pub fn componente(mut self, componente: Componente) -> Self {
+        self.componenti.push(componente);
+        self
+    }
+
+    pub fn classe_funzionale(mut self, classe: ClasseFunzionale) -> Self {
+        self.classe_funzionale = Some(classe);
+        self
+    }
+
+    pub fn tipo(mut self, tipo: &str) -> Self {
+        self.tipo = Some(tipo.to_string());
+        self
+    }
+
+    pub fn periodo(mut self, periodo: Periodo) -> Self {
+        self.periodo = Some(periodo);
+        self
+    }
+
+    pub fn conservazione(mut self, conservazione: Conservazione) -> Self {
+        self.conservazione = Some(conservazione);
+        self
+    }
+
+    pub fn completo_stimato_percento(mut self, percento: f64) -> Self {
+        self.completo_stimato_percento = Some(percento);
+        self
+    }
+
+    pub fn sito(mut self, sito: &str) -> Self {
+        self.sito = Some(sito.to_string());
+        self
+    }
+
+    /// Latitudine e longitudine in gradi decimali (WGS84)
+    pub fn coordinate(mut self, latitudine: f64, longitudine: f64) -> Self {
+        self.coordinate = Some(Coordinate { latitudine, longitudine });
+        self
+    }
+
+    pub fn rinvenuto_il(mut self, quando: NaiveDateTime) -> Self {
+        self.rinvenuto_il = Some(quando);
+        self
+    }
+
+    pub fn misurazioni(mut self, misurazioni: Misurazioni) -> Self {
+        self.misurazioni = Some(misurazioni);
+        self
+    }
+
+    pub fn collezione(mut self, collezione: &str) -> Self {
+        self.collezione = Some(collezione.to_string());
+        self
+    }
+
+    pub fn campagna_scavo(mut self, codice: &str) -> Self {
+        self.campagna_scavo = Some(codice.to_string());
+        self
+    }
+
+    /// Il reperto, con id 0 e stato "in ingresso" come `Reperto::nuovo`.
+    /// Senza nome: `NomeVuoto`; altri campi obbligatori mancanti o valori
+    /// fuori intervallo: `DatiNonValidi`, con tutti i campi mancanti insieme.
+    pub fn build(self) -> Result<Reperto, ErroreReperto> {
+        let nome = self.nome.filter(|n| !n.trim().is_empty()).ok_or(ErroreReperto::NomeVuoto)?;
+        let sito = self.sito.filter(|s| !s.trim().is_empty());
+        let principale = self.componenti.first().map(|c| c.materiale.clone());
+        let (Some(materiale), Some(sito), Some(conservazione)) = (principale, &sito, self.conservazione.clone()) else {
+            let mancanti: Vec<&str> = [
+                ("materiale", self.componenti.is_empty()),
+                ("sito", sito.is_none()),
+                ("conservazione", self.conservazione.is_none()),
+            ]
+            .into_iter()
+            .filter_map(|(campo, manca)| manca.then_some(campo))
+            .collect();
+            return Err(ErroreReperto::DatiNonValidi(format!(
+                "campi obbligatori mancanti: {}",
+                mancanti.join(", ")
+            )));
+        };
+        if let Some(c) = &self.coordinate {
+            if !(-90.0..=90.0).contains(&c.latitudine) || !(-180.0..=180.0).contains(&c.longitudine) {
+                return Err(ErroreReperto::DatiNonValidi(format!("coordinate fuori intervallo: {}", c)));
+            }
+        }
+
+        let periodo = self.periodo.unwrap_or(Periodo::Sconosciuto);
+        let mut reperto = Reperto::nuovo(&nome, materiale, periodo, conservazione, sito);
+        reperto.componenti = self.componenti;
+        reperto.descrizione = self.descrizione;
+        reperto.classe_funzionale = self.classe_funzionale;
+        reperto.tipo = self.tipo;
+        reperto.completo_stimato_percento = self.completo_stimato_percento;
+        reperto.coordinate = self.coordinate;
+        reperto.rinvenuto_il = self.rinvenuto_il;
+        reperto.misurazioni = self.misurazioni.unwrap_or_else(Misurazioni::nuove);
+        reperto.collezione = self.collezione;
+        reperto.campagna_scavo = self.campagna_scavo;
+        reperto.valida()?;
+        Ok(reperto)
+    }
+}
+
+impl fmt::Display for Reperto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} ({}, {}, {})",
+            self.id,
+            self.nome,
+            self.materiale().map_or("?".to_string(), |m| m.to_string()),
+            self.periodo,
+            self.conservazione
+        )
+    }
+}
@@ -0,0 +1,58 @@
+// ============================================================================
+// BRONZEAXE-CORE: IL MODELLO DEI DATI SENZA STD
+// ============================================================================
+// Reperto, Periodo, Misurazioni e le regole di validazione, in un crate che
+// non usa la libreria standard: solo `core` (tipi, tratti, fmt) e `alloc`
+// (String, Vec, Box). Cosi lo stesso modello gira dove std non c'e:
+// il firmware di un data logger sul campo, un modulo WebAssembly piccolo.
+//
+//   #![no_std]            niente std: niente file, thread, println!
+//   extern crate alloc;   ma l'allocatore si, quindi String e Vec
+//
+// Il modulo `catalogo` contiene il modello completo del catalogo di
+// src/catalogo/ (Reperto, Componente, Periodo, Conservazione e il resto),
+// con serde e chrono senza std.
+//
+// Chi ha std (la libreria del tutorial) riesporta questi tipi e aggiunge
+// l'inventario, le schede stampabili e il resto. Per provare che il crate
+// compila davvero senza std serve un target che non la ha (lo stesso
+// controllo gira in CI, .github/workflows/rust.yml):
+//
+//   rustup target add thumbv7em-none-eabi
+//   cargo build -p bronzeaxe-core --target thumbv7em-none-eabi
+// ============================================================================
+
+//! Modello dei dati dei reperti per ambienti senza `std` (solo `core` e `alloc`).
+//!
+//! ```
+//! use bronzeaxe_core::{ErroreValidazione, Misurazioni, Periodo, Reperto};
+//!
+//! let ascia = Reperto::nuovo("Ascia a margini rialzati", Periodo::Medio, 327.6);
+//! assert_eq!(ascia.valida(), Ok(()));
+//! assert_eq!(Reperto::nuovo("", Periodo::Medio, 1.0).valida(), Err(ErroreValidazione::NomeVuoto));
+//!
+//! let misure = Misurazioni::nuove().con_dimensioni(14.2, 5.1, 1.3);
+//! assert!(misure.valida().is_ok());
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod catalogo;
+mod misurazioni;
+mod reperto;
+
+pub use misurazioni::{MisuraNonValida, Misurazioni};
+pub use reperto::{Periodo, Reperto};
+
+use thiserror::Error;
+
+/// Perche un reperto non e valido. Con std diventa `ErroreInventario`.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ErroreValidazione {
+    #[error("Il nome del reperto non puo essere vuoto")]
+    NomeVuoto,
+    #[error("Peso non valido: {0} g")]
+    PesoNonValido(f64),
+}
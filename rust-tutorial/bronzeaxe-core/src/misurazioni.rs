@@ -0,0 +1,125 @@
+// ============================================================================
+// MISURAZIONI
+// ============================================================================
+// Le misure del capitolo 9, tutte facoltative: sul campo spesso se ne
+// prende solo qualcuna. Display scrive direttamente nel Formatter, senza
+// costruire stringhe intermedie: su un microcontrollore ogni allocazione
+// conta.
+// ============================================================================
+
+use core::fmt;
+
+use thiserror::Error;
+
+/// Dimensioni e peso di un reperto; `None` dove la misura manca.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Misurazioni {
+    pub lunghezza_cm: Option<f64>,
+    pub larghezza_cm: Option<f64>,
+    pub altezza_cm: Option<f64>,
+    pub peso_grammi: Option<f64>,
+}
+
+/// Una misura presente ma negativa, nulla o non finita.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+#[error("Misura non valida: {misura} = {valore}")]
+pub struct MisuraNonValida {
+    /// Il nome del campo, per esempio "lunghezza_cm"
+    pub misura: &'static str,
+    pub valore: f64,
+}
+
+impl Misurazioni {
+    pub fn nuove() -> Self {
+        Misurazioni::default()
+    }
+
+    pub fn con_dimensioni(mut self, lunghezza: f64, larghezza: f64, altezza: f64) -> Self {
+        self.lunghezza_cm = Some(lunghezza);
+        self.larghezza_cm = Some(larghezza);
+        self.altezza_cm = Some(altezza);
+        self
+    }
+
+    pub fn con_peso(mut self, peso: f64) -> Self {
+        self.peso_grammi = Some(peso);
+        self
+    }
+
+    /// Lunghezza x larghezza x altezza, solo se ci sono tutte e tre.
+    ///
+    /// ```
+    /// use bronzeaxe_core::Misurazioni;
+    ///
+    /// assert_eq!(Misurazioni::nuove().con_dimensioni(10.0, 2.0, 1.5).volume_approssimativo(), Some(30.0));
+    /// assert_eq!(Misurazioni::nuove().con_peso(12.0).volume_approssimativo(), None);
+    /// ```
+    pub fn volume_approssimativo(&self) -> Option<f64> {
+        match (self.lunghezza_cm, self.larghezza_cm, self.altezza_cm) {
+            (Some(l), Some(w), Some(h)) => Some(l * w * h),
+            _ => None,
+        }
+    }
+
+    fn presenti(&self) -> impl Iterator<Item = (&'static str, f64)> {
+        [
+            ("lunghezza_cm", self.lunghezza_cm),
+            ("larghezza_cm", self.larghezza_cm),
+            ("altezza_cm", self.altezza_cm),
+            ("peso_grammi", self.peso_grammi),
+        ]
+        .into_iter()
+        .filter_map(|(misura, valore)| Some((misura, valore?)))
+    }
+
+    /// Le misure mancanti vanno bene; quelle presenti devono essere positive.
+    ///
+    /// ```
+    /// use bronzeaxe_core::{MisuraNonValida, Misurazioni};
+    ///
+    /// let misure = Misurazioni::nuove().con_dimensioni(14.2, -5.1, 1.3);
+    /// assert_eq!(misure.valida(), Err(MisuraNonValida { misura: "larghezza_cm", valore: -5.1 }));
+    /// ```
+    pub fn valida(&self) -> Result<(), MisuraNonValida> {
+        match self.presenti().find(|&(_, valore)| !valore.is_finite() || valore <= 0.0) {
+            Some((misura, valore)) => Err(MisuraNonValida { misura, valore }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Misurazioni {
+    /// `L:14.2cm, W:5.1cm, 327g`, oppure `N/D` se non c'e nessuna misura
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut prima = true;
+        for (misura, valore) in self.presenti() {
+            if !prima {
+                f.write_str(", ")?;
+            }
+            prima = false;
+            match misura {
+                "lunghezza_cm" => write!(f, "L:{:.1}cm", valore)?,
+                "larghezza_cm" => write!(f, "W:{:.1}cm", valore)?,
+                "altezza_cm" => write!(f, "H:{:.1}cm", valore)?,
+                _ => write!(f, "{:.0}g", valore)?,
+            }
+        }
+        if prima {
+            f.write_str("N/D")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn display_salta_le_misure_mancanti() {
+        assert_eq!(Misurazioni::nuove().to_string(), "N/D");
+        let misure = Misurazioni { lunghezza_cm: Some(14.25), altezza_cm: Some(1.3), ..Misurazioni::nuove() }.con_peso(327.6);
+        assert_eq!(misure.to_string(), "L:14.2cm, H:1.3cm, 328g");
+    }
+}
@@ -0,0 +1,93 @@
+// ============================================================================
+// REPERTO E PERIODO
+// ============================================================================
+
+use alloc::string::{String, ToString};
+
+use crate::ErroreValidazione;
+
+/// Periodo dell'eta del bronzo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Periodo {
+    Antico,
+    Medio,
+    Recente,
+    Finale,
+}
+
+impl Periodo {
+    /// Periodo dal nome minuscolo ("antico", "medio", "recente", "finale").
+    ///
+    /// ```
+    /// use bronzeaxe_core::Periodo;
+    ///
+    /// assert_eq!(Periodo::da_nome("medio"), Some(Periodo::Medio));
+    /// assert_eq!(Periodo::da_nome("tardo"), None);
+    /// ```
+    pub fn da_nome(nome: &str) -> Option<Periodo> {
+        match nome {
+            "antico" => Some(Periodo::Antico),
+            "medio" => Some(Periodo::Medio),
+            "recente" => Some(Periodo::Recente),
+            "finale" => Some(Periodo::Finale),
+            _ => None,
+        }
+    }
+}
+
+/// Un reperto dell'inventario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reperto {
+    /// Assegnato dall'inventario: 0 finche il reperto non e stato aggiunto
+    pub id: u32,
+    pub nome: String,
+    pub periodo: Periodo,
+    pub peso_grammi: f64,
+}
+
+impl Reperto {
+    /// Crea un reperto non ancora inventariato (`id` = 0).
+    ///
+    /// ```
+    /// use bronzeaxe_core::{Periodo, Reperto};
+    ///
+    /// let r = Reperto::nuovo("Fibula ad arco", Periodo::Recente, 26.9);
+    /// assert_eq!(r.id, 0);
+    /// ```
+    pub fn nuovo(nome: &str, periodo: Periodo, peso_grammi: f64) -> Self {
+        Reperto { id: 0, nome: nome.to_string(), periodo, peso_grammi }
+    }
+
+    /// Il nome non puo essere vuoto e il peso deve essere un numero
+    /// positivo (niente NaN ne infinito).
+    ///
+    /// ```
+    /// use bronzeaxe_core::{ErroreValidazione, Periodo, Reperto};
+    ///
+    /// let fibula = Reperto::nuovo("Fibula", Periodo::Recente, 0.0);
+    /// assert_eq!(fibula.valida(), Err(ErroreValidazione::PesoNonValido(0.0)));
+    /// ```
+    pub fn valida(&self) -> Result<(), ErroreValidazione> {
+        if self.nome.trim().is_empty() {
+            Err(ErroreValidazione::NomeVuoto)
+        } else if !self.peso_grammi.is_finite() || self.peso_grammi <= 0.0 {
+            Err(ErroreValidazione::PesoNonValido(self.peso_grammi))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pesi_non_validi_rifiutati() {
+        for peso in [0.0, -5.0, f64::NAN, f64::INFINITY] {
+            let esito = Reperto::nuovo("Ascia", Periodo::Medio, peso).valida();
+            assert!(matches!(esito, Err(ErroreValidazione::PesoNonValido(_))), "peso {} accettato", peso);
+        }
+        assert_eq!(Reperto::nuovo("  ", Periodo::Medio, -1.0).valida(), Err(ErroreValidazione::NomeVuoto));
+    }
+}
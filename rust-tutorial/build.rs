@@ -0,0 +1,34 @@
+// ============================================================================
+// SCRIPT DI BUILD
+// ============================================================================
+// Con `--features cabi` rigenera include/bronzeaxe.h dalle funzioni
+// `extern "C"` di src/cabi.rs, con cbindgen: l'intestazione per C, C++ e
+// Delphi resta sempre uguale al codice. Senza la feature non fa nulla, e
+// cbindgen non viene nemmeno compilato.
+// ============================================================================
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/cabi.rs");
+
+    #[cfg(feature = "cabi")]
+    intestazione_c();
+}
+
+#[cfg(feature = "cabi")]
+fn intestazione_c() {
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("BRONZEAXE_H".to_string()),
+        header: Some("/* Generato da build.rs con cbindgen: non modificare a mano. */".to_string()),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        documentation_style: cbindgen::DocumentationStyle::C99,
+        ..Default::default()
+    };
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/cabi.rs")
+        .generate()
+        .expect("intestazione C non generata")
+        .write_to_file("include/bronzeaxe.h");
+}
@@ -0,0 +1,130 @@
+// ============================================================================
+// BENCHMARK: CICLO FOR, ITERATORI E RAYON
+// ============================================================================
+// Nel capitolo 5 abbiamo detto che gli iteratori sono "zero-cost": una
+// catena `filter().map().sum()` costa quanto il ciclo for scritto a mano.
+// Qui lo verifichiamo: la stessa somma su 10 milioni di pesi, calcolata
+// in tre modi, con i tempi a confronto.
+//
+//   1. un ciclo for con un `if`
+//   2. una catena di iteratori
+//   3. la stessa catena con rayon: `par_iter()` al posto di `iter()`
+//      divide il lavoro tra i core della CPU
+//
+// I tempi hanno senso solo con le ottimizzazioni attive:
+//
+//   cargo run --release --features parallel --example benchmark_iteratori
+//   cargo run --release --features parallel --example benchmark_iteratori -- 1000000   (meno dati)
+// ============================================================================
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use rust_tutorial::schermo;
+
+/// Quanti reperti se non indicato sulla riga di comando
+const QUANTI: usize = 10_000_000;
+/// Ogni misura si ripete e si tiene la piu veloce: le altre risentono di
+/// cache fredde e di altri programmi in esecuzione
+const RIPETIZIONI: usize = 5;
+/// Solo i reperti sopra questa soglia entrano nella somma
+const SOGLIA_GRAMMI: f64 = 500.0;
+
+fn main() {
+    schermo::intestazione(&["BENCHMARK: CICLO FOR, ITERATORI E RAYON"]);
+
+    if cfg!(debug_assertions) {
+        println!("ATTENZIONE: compilazione di debug, senza ottimizzazioni.");
+        println!("Gli iteratori qui sembrano lenti: il compilatore non li ha");
+        println!("ancora trasformati in un ciclo. Per misure vere:");
+        println!("  cargo run --release --features parallel --example benchmark_iteratori\n");
+    }
+
+    let quanti = std::env::args().nth(1).and_then(|n| n.replace('_', "").parse().ok()).unwrap_or(QUANTI);
+    let pesi = genera_pesi(quanti);
+    println!(
+        "{} pesi, somma di quelli sopra {} g; la migliore di {} misure.",
+        quanti, SOGLIA_GRAMMI, RIPETIZIONI
+    );
+    println!("rayon usa {} thread.\n", rayon::current_num_threads());
+
+    let (somma_ciclo, tempo_ciclo) = misura(|| con_ciclo(black_box(&pesi)));
+    let (somma_iter, tempo_iter) = misura(|| con_iteratori(black_box(&pesi)));
+    let (somma_rayon, tempo_rayon) = misura(|| con_rayon(black_box(&pesi)));
+
+    println!("{:<12} {:>12} {:>10}", "Metodo", "Tempo", "Rispetto");
+    for (nome, tempo) in [("ciclo for", tempo_ciclo), ("iteratori", tempo_iter), ("rayon", tempo_rayon)] {
+        let rapporto = tempo.as_secs_f64() / tempo_ciclo.as_secs_f64();
+        println!("{:<12} {:>9.2} ms {:>9.2}x", nome, tempo.as_secs_f64() * 1000.0, rapporto);
+    }
+
+    // Ciclo e iteratori sommano nello stesso ordine: stesso risultato, bit
+    // per bit. rayon somma a pezzi e poi unisce i parziali, e con i f64
+    // (a + b) + c non e sempre uguale a a + (b + c): puo differire di poco.
+    println!();
+    println!("Somma (ciclo):     {:.3} g", somma_ciclo);
+    println!("Somma (iteratori): {:.3} g  identica: {}", somma_iter, somma_iter == somma_ciclo);
+    let scarto = (somma_rayon - somma_ciclo).abs() / somma_ciclo.abs().max(1.0);
+    println!("Somma (rayon):     {:.3} g  scarto relativo: {:.1e}", somma_rayon, scarto);
+    assert_eq!(somma_iter, somma_ciclo);
+    assert!(scarto < 1e-9, "rayon troppo lontano: {}", scarto);
+
+    println!();
+    schermo::riquadro(
+        "COSA CI DICONO I NUMERI",
+        &[
+            "- In release ciclo e iteratori hanno tempi quasi uguali:",
+            "  la catena di iteratori diventa lo stesso codice macchina",
+            "- rayon accelera quando il lavoro per elemento e poco e i",
+            "  dati sono tanti, ma avviare i thread ha un costo: con",
+            "  pochi dati (prova -- 1000) puo essere il piu lento",
+            "- Con i f64 l'ordine delle somme conta: il parallelo puo",
+            "  dare un risultato leggermente diverso",
+        ],
+    );
+}
+
+/// Pesi tra 10 g e 5 kg, sempre gli stessi: un generatore lineare
+/// congruenziale basta e non richiede dipendenze
+fn genera_pesi(quanti: usize) -> Vec<f64> {
+    let mut stato: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..quanti)
+        .map(|_| {
+            stato = stato.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let frazione = (stato >> 11) as f64 / (1u64 << 53) as f64;
+            10.0 + frazione * 4990.0
+        })
+        .collect()
+}
+
+fn con_ciclo(pesi: &[f64]) -> f64 {
+    let mut somma = 0.0;
+    for &peso in pesi {
+        if peso > SOGLIA_GRAMMI {
+            somma += peso;
+        }
+    }
+    somma
+}
+
+fn con_iteratori(pesi: &[f64]) -> f64 {
+    pesi.iter().filter(|&&peso| peso > SOGLIA_GRAMMI).sum()
+}
+
+fn con_rayon(pesi: &[f64]) -> f64 {
+    pesi.par_iter().filter(|&&peso| peso > SOGLIA_GRAMMI).sum()
+}
+
+/// Esegue `calcolo` RIPETIZIONI volte: il risultato e il tempo migliore
+fn misura(calcolo: impl Fn() -> f64) -> (f64, Duration) {
+    let mut risultato = 0.0;
+    let mut migliore = Duration::MAX;
+    for _ in 0..RIPETIZIONI {
+        let inizio = Instant::now();
+        // black_box impedisce al compilatore di scartare un risultato inutilizzato
+        risultato = black_box(calcolo());
+        migliore = migliore.min(inizio.elapsed());
+    }
+    (risultato, migliore)
+}
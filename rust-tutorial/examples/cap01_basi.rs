@@ -3,47 +3,55 @@
 // ============================================================================
 // Questo file copre tutti i fondamenti del linguaggio Rust.
 // Esegui con: cargo run --example cap01_basi
+// In inglese:  TUTORIAL_LINGUA=en cargo run --example cap01_basi
+//
+// `tprintln!` e `tprint!` sono `println!` e `print!` con la traduzione
+// inglese (catalogo in lingua/cap01.rs): si scrivono e funzionano allo
+// stesso modo.
 // ============================================================================
 
+use rust_tutorial::schermo;
+use rust_tutorial::{tprint, tprintln, tr};
+
+include!("../lingua/cap01.rs");
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 1: LE BASI DI RUST               ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&[tr!("CAPITOLO 1: LE BASI DI RUST")]);
 
     // ========================================================================
     // 1.1 - VARIABILI E IMMUTABILITA
     // ========================================================================
-    println!("--- 1.1 Variabili e Immutabilita ---\n");
+    tprintln!("--- 1.1 Variabili e Immutabilita ---\n");
 
     // In Rust, le variabili sono IMMUTABILI per default.
     // Questo e un design deliberato: ti costringe a dichiarare esplicitamente
     // quando vuoi che un valore possa cambiare.
     let x = 5;
-    println!("x = {} (immutabile)", x);
+    tprintln!("x = {} (immutabile)", x);
     // x = 6; // ERRORE! Decommenta per vedere il messaggio del compilatore
 
     // Per rendere una variabile mutabile, usa `mut`
     let mut y = 10;
-    println!("y = {} (mutabile, prima)", y);
+    tprintln!("y = {} (mutabile, prima)", y);
     y = 20;
-    println!("y = {} (mutabile, dopo)", y);
+    tprintln!("y = {} (mutabile, dopo)", y);
 
     // SHADOWING: puoi ridichiarare una variabile con lo stesso nome.
     // Non e la stessa cosa di `mut` - crea una NUOVA variabile.
     let z = 5;
     let z = z + 1;      // z e ora 6 (nuova variabile)
     let z = z * 2;      // z e ora 12 (un'altra nuova variabile)
-    println!("z dopo shadowing = {}", z);
+    tprintln!("z dopo shadowing = {}", z);
 
     // Lo shadowing permette anche di cambiare tipo!
     let spazi = "   ";           // &str (stringa)
     let spazi = spazi.len();     // usize (numero)
-    println!("Numero di spazi: {}\n", spazi);
+    tprintln!("Numero di spazi: {}\n", spazi);
 
     // ========================================================================
     // 1.2 - TIPI DI DATO
     // ========================================================================
-    println!("--- 1.2 Tipi di Dato ---\n");
+    tprintln!("--- 1.2 Tipi di Dato ---\n");
 
     // INTERI
     let intero_8: i8 = -128;           // da -128 a 127
@@ -53,40 +61,40 @@ fn main() {
     let intero_senza_segno: u32 = 42;  // solo positivi: da 0 a 4_294_967_295
     let intero_architettura: usize = 100; // dimensione dipende dalla CPU (32 o 64 bit)
 
-    println!("i8:    {}", intero_8);
-    println!("i16:   {}", intero_16);
-    println!("i32:   {}", intero_32);
-    println!("i64:   {}", intero_64);
-    println!("u32:   {}", intero_senza_segno);
-    println!("usize: {}", intero_architettura);
+    tprintln!("i8:    {}", intero_8);
+    tprintln!("i16:   {}", intero_16);
+    tprintln!("i32:   {}", intero_32);
+    tprintln!("i64:   {}", intero_64);
+    tprintln!("u32:   {}", intero_senza_segno);
+    tprintln!("usize: {}", intero_architettura);
 
     // FLOAT (numeri decimali)
     let pi: f64 = 3.14159265358979;    // 64 bit - precisione doppia (default)
     let pi_corto: f32 = 3.14;          // 32 bit - precisione singola
-    println!("pi (f64): {}", pi);
-    println!("pi (f32): {}", pi_corto);
+    tprintln!("pi (f64): {}", pi);
+    tprintln!("pi (f32): {}", pi_corto);
 
     // BOOLEANI
     let vero: bool = true;
     let falso = false;  // il tipo e inferito
-    println!("vero: {}, falso: {}", vero, falso);
+    tprintln!("vero: {}, falso: {}", vero, falso);
 
     // CARATTERI - Rust usa Unicode! Un char e 4 byte.
     let lettera = 'a';
     let emoji = '🦀';      // Si, anche le emoji!
     let ideogramma = '漢';
-    println!("lettera: {}, emoji: {}, ideogramma: {}", lettera, emoji, ideogramma);
+    tprintln!("lettera: {}, emoji: {}, ideogramma: {}", lettera, emoji, ideogramma);
 
     // STRINGHE - Due tipi principali:
-    let stringa_letterale: &str = "Sono una string slice";  // &str - immutabile, nello stack
-    let stringa_owned: String = String::from("Sono una String"); // String - mutabile, nell'heap
-    println!("{}", stringa_letterale);
-    println!("{}\n", stringa_owned);
+    let stringa_letterale: &str = tr!("Sono una string slice");  // &str - immutabile, nello stack
+    let stringa_owned: String = String::from(tr!("Sono una String")); // String - mutabile, nell'heap
+    tprintln!("{}", stringa_letterale);
+    tprintln!("{}\n", stringa_owned);
 
     // ========================================================================
     // 1.3 - COSTANTI
     // ========================================================================
-    println!("--- 1.3 Costanti ---\n");
+    tprintln!("--- 1.3 Costanti ---\n");
 
     // Le costanti sono SEMPRE immutabili (non puoi usare mut).
     // DEVONO avere il tipo annotato esplicitamente.
@@ -95,53 +103,53 @@ fn main() {
     const PI_GRECO: f64 = 3.14159265358979;
     const MAX_REPERTI: usize = 10_000;
 
-    println!("Velocita della luce: {} m/s", VELOCITA_LUCE);
-    println!("Pi greco: {}", PI_GRECO);
-    println!("Max reperti: {}\n", MAX_REPERTI);
+    tprintln!("Velocita della luce: {} m/s", VELOCITA_LUCE);
+    tprintln!("Pi greco: {}", PI_GRECO);
+    tprintln!("Max reperti: {}\n", MAX_REPERTI);
 
     // ========================================================================
     // 1.4 - TUPLE E ARRAY
     // ========================================================================
-    println!("--- 1.4 Tuple e Array ---\n");
+    tprintln!("--- 1.4 Tuple e Array ---\n");
 
     // TUPLE: raggruppano valori di tipi DIVERSI. Dimensione fissa.
-    let reperto: (i32, f64, &str) = (1, 3.5, "Ascia di bronzo");
+    let reperto: (i32, f64, &str) = (1, 3.5, tr!("Ascia di bronzo"));
 
     // Accesso per posizione (destructuring)
     let (id, peso, nome) = reperto;
-    println!("Reperto: id={}, peso={}kg, nome={}", id, peso, nome);
+    tprintln!("Reperto: id={}, peso={}kg, nome={}", id, peso, nome);
 
     // Accesso per indice con il punto
-    println!("Primo elemento: {}", reperto.0);
-    println!("Secondo elemento: {}", reperto.1);
-    println!("Terzo elemento: {}", reperto.2);
+    tprintln!("Primo elemento: {}", reperto.0);
+    tprintln!("Secondo elemento: {}", reperto.1);
+    tprintln!("Terzo elemento: {}", reperto.2);
 
     // ARRAY: tutti gli elementi dello STESSO tipo. Dimensione fissa.
-    let mesi: [&str; 4] = ["Gennaio", "Febbraio", "Marzo", "Aprile"];
-    println!("\nPrimo mese: {}", mesi[0]);
-    println!("Terzo mese: {}", mesi[2]);
+    let mesi: [&str; 4] = [tr!("Gennaio"), tr!("Febbraio"), tr!("Marzo"), tr!("Aprile")];
+    tprintln!("\nPrimo mese: {}", mesi[0]);
+    tprintln!("Terzo mese: {}", mesi[2]);
 
     // Array inizializzato con lo stesso valore
     let zeri = [0; 5]; // [0, 0, 0, 0, 0]
-    println!("Array di zeri: {:?}\n", zeri);
+    tprintln!("Array di zeri: {:?}\n", zeri);
 
     // ========================================================================
     // 1.5 - FUNZIONI
     // ========================================================================
-    println!("--- 1.5 Funzioni ---\n");
+    tprintln!("--- 1.5 Funzioni ---\n");
 
     // Chiamata a funzioni definite sotto
-    saluta("Archeologo");
+    saluta(tr!("Archeologo"));
 
     let somma = somma(15, 27);
-    println!("15 + 27 = {}", somma);
+    tprintln!("15 + 27 = {}", somma);
 
     let area = area_rettangolo(5.0, 3.0);
-    println!("Area rettangolo 5x3 = {:.1}", area);
+    tprintln!("Area rettangolo 5x3 = {:.1}", area);
 
     // Funzione che ritorna una tupla
     let (minimo, massimo) = trova_min_max(42, 17);
-    println!("Min: {}, Max: {}", minimo, massimo);
+    tprintln!("Min: {}, Max: {}", minimo, massimo);
 
     // In Rust, l'ultima espressione SENZA punto e virgola e il valore di ritorno.
     // Questo e un concetto importante: Rust e un linguaggio "expression-based".
@@ -150,55 +158,55 @@ fn main() {
         let b = 20;
         a + b  // NESSUN punto e virgola = questa e l'espressione restituita dal blocco
     };
-    println!("Risultato del blocco: {}\n", risultato);
+    tprintln!("Risultato del blocco: {}\n", risultato);
 
     // ========================================================================
     // 1.6 - CONTROLLO DI FLUSSO
     // ========================================================================
-    println!("--- 1.6 Controllo di Flusso ---\n");
+    tprintln!("--- 1.6 Controllo di Flusso ---\n");
 
     // IF/ELSE - Le condizioni NON hanno parentesi (a differenza di C/Java)
     let temperatura = 25;
     if temperatura > 30 {
-        println!("Fa caldo!");
+        tprintln!("Fa caldo!");
     } else if temperatura > 20 {
-        println!("Temperatura piacevole: {}°C", temperatura);
+        tprintln!("Temperatura piacevole: {}°C", temperatura);
     } else {
-        println!("Fa fresco!");
+        tprintln!("Fa fresco!");
     }
 
     // IF come espressione (come il ternario in altri linguaggi)
-    let stato = if temperatura > 20 { "caldo" } else { "freddo" };
-    println!("Stato: {}", stato);
+    let stato = if temperatura > 20 { tr!("caldo") } else { tr!("freddo") };
+    tprintln!("Stato: {}", stato);
 
     // MATCH - Molto piu potente di switch/case
     let giorno = 3;
     let nome_giorno = match giorno {
-        1 => "Lunedi",
-        2 => "Martedi",
-        3 => "Mercoledi",
-        4 => "Giovedi",
-        5 => "Venerdi",
-        6 | 7 => "Weekend!",       // Pattern multipli con |
-        _ => "Giorno non valido",   // _ = tutti gli altri casi (obbligatorio!)
+        1 => tr!("Lunedi"),
+        2 => tr!("Martedi"),
+        3 => tr!("Mercoledi"),
+        4 => tr!("Giovedi"),
+        5 => tr!("Venerdi"),
+        6 | 7 => tr!("Weekend!"),       // Pattern multipli con |
+        _ => tr!("Giorno non valido"),   // _ = tutti gli altri casi (obbligatorio!)
     };
-    println!("Giorno {}: {}", giorno, nome_giorno);
+    tprintln!("Giorno {}: {}", giorno, nome_giorno);
 
     // Match con range
     let voto = 85;
     let giudizio = match voto {
-        90..=100 => "Eccellente",
-        80..=89 => "Ottimo",
-        70..=79 => "Buono",
-        60..=69 => "Sufficiente",
-        _ => "Insufficiente",
+        90..=100 => tr!("Eccellente"),
+        80..=89 => tr!("Ottimo"),
+        70..=79 => tr!("Buono"),
+        60..=69 => tr!("Sufficiente"),
+        _ => tr!("Insufficiente"),
     };
-    println!("Voto {}: {}\n", voto, giudizio);
+    tprintln!("Voto {}: {}\n", voto, giudizio);
 
     // ========================================================================
     // 1.7 - CICLI
     // ========================================================================
-    println!("--- 1.7 Cicli ---\n");
+    tprintln!("--- 1.7 Cicli ---\n");
 
     // LOOP - ciclo infinito (si interrompe con break)
     let mut contatore = 0;
@@ -208,79 +216,79 @@ fn main() {
             break contatore * 2;  // loop puo restituire un valore con break!
         }
     };
-    println!("Risultato del loop: {}", risultato);
+    tprintln!("Risultato del loop: {}", risultato);
 
     // WHILE
     let mut n = 3;
-    print!("Conto alla rovescia: ");
+    tprint!("Conto alla rovescia: ");
     while n > 0 {
-        print!("{}... ", n);
+        tprint!("{}... ", n);
         n -= 1;
     }
-    println!("Via!");
+    tprintln!("Via!");
 
     // FOR - il modo piu comune e sicuro per iterare
-    print!("Numeri da 1 a 5: ");
+    tprint!("Numeri da 1 a 5: ");
     for i in 1..=5 {   // ..= include l'ultimo valore (range inclusivo)
-        print!("{} ", i);
+        tprint!("{} ", i);
     }
-    println!();
+    tprintln!();
 
     // FOR su un array
-    let reperti = ["Ascia", "Spada", "Fibula", "Anello"];
-    println!("\nReperti trovati:");
+    let reperti = [tr!("Ascia"), tr!("Spada"), tr!("Fibula"), tr!("Anello")];
+    tprintln!("\nReperti trovati:");
     for (indice, reperto) in reperti.iter().enumerate() {
-        println!("  {}. {}", indice + 1, reperto);
+        tprintln!("  {}. {}", indice + 1, reperto);
     }
 
     // FOR con range esclusivo (non include l'ultimo)
-    print!("\nRange 0..5: ");
+    tprint!("\nRange 0..5: ");
     for i in 0..5 {     // 0, 1, 2, 3, 4 (il 5 NON e incluso)
-        print!("{} ", i);
+        tprint!("{} ", i);
     }
-    println!();
+    tprintln!();
 
     // LOOP ETICHETTATI - utili con cicli annidati
-    println!("\nTabella 3x3 con loop etichettati:");
+    tprintln!("\nTabella 3x3 con loop etichettati:");
     'esterno: for riga in 1..=3 {
         for colonna in 1..=3 {
             if riga == 2 && colonna == 2 {
-                println!("  ({},{}) -> skip!", riga, colonna);
+                tprintln!("  ({},{}) -> skip!", riga, colonna);
                 continue 'esterno;  // salta al prossimo ciclo esterno
             }
-            print!("  ({},{}) ", riga, colonna);
+            tprint!("  ({},{}) ", riga, colonna);
         }
-        println!();
+        tprintln!();
     }
 
     // ========================================================================
     // 1.8 - MACRO println! E FORMATTAZIONE
     // ========================================================================
-    println!("\n--- 1.8 Formattazione ---\n");
+    tprintln!("\n--- 1.8 Formattazione ---\n");
 
-    let nome = "Ascia";
+    let nome = tr!("Ascia");
     let peso = 3.14159;
     let quantita = 42;
 
     // Formattazione di base
-    println!("Nome: {}", nome);
-    println!("Peso con 2 decimali: {:.2}", peso);
-    println!("Quantita con padding: {:>10}", quantita);   // allineato a destra
-    println!("Quantita con padding: {:<10}|", quantita);   // allineato a sinistra
-    println!("Quantita con zeri: {:05}", quantita);        // 00042
-    println!("Binario: {:b}", quantita);                   // 101010
-    println!("Esadecimale: {:x}", quantita);               // 2a
-    println!("Debug di un array: {:?}", [1, 2, 3]);        // [1, 2, 3]
-    println!("Debug formattato: {:#?}", ("Rust", 2015, true)); // Pretty-print
+    tprintln!("Nome: {}", nome);
+    tprintln!("Peso con 2 decimali: {:.2}", peso);
+    tprintln!("Quantita con padding: {:>10}", quantita);   // allineato a destra
+    tprintln!("Quantita con padding: {:<10}|", quantita);   // allineato a sinistra
+    tprintln!("Quantita con zeri: {:05}", quantita);        // 00042
+    tprintln!("Binario: {:b}", quantita);                   // 101010
+    tprintln!("Esadecimale: {:x}", quantita);               // 2a
+    tprintln!("Debug di un array: {:?}", [1, 2, 3]);        // [1, 2, 3]
+    tprintln!("Debug formattato: {:#?}", ("Rust", 2015, true)); // Pretty-print
 
     // Argomenti nominati
-    println!(
+    tprintln!(
         "{linguaggio} e stato creato nel {anno}",
         linguaggio = "Rust",
         anno = 2010
     );
 
-    println!("\n✅ Capitolo 1 completato!");
+    schermo::completato(1);
 }
 
 // ============================================================================
@@ -290,7 +298,7 @@ fn main() {
 /// Funzione che saluta qualcuno.
 /// In Rust, i parametri DEVONO avere il tipo annotato.
 fn saluta(nome: &str) {
-    println!("Ciao, {}! Benvenuto nel mondo di Rust.", nome);
+    tprintln!("Ciao, {}! Benvenuto nel mondo di Rust.", nome);
 }
 
 /// Funzione che somma due numeri e restituisce il risultato.
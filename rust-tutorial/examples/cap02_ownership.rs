@@ -8,10 +8,10 @@
 // Esegui con: cargo run --example cap02_ownership
 // ============================================================================
 
+use rust_tutorial::schermo;
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 2: OWNERSHIP E BORROWING          ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 2: OWNERSHIP E BORROWING"]);
 
     // ========================================================================
     // 2.1 - LE TRE REGOLE DELL'OWNERSHIP
@@ -248,26 +248,25 @@ fn main() {
     // ========================================================================
     println!("--- 2.10 Riepilogo ---\n");
 
-    println!("┌──────────────────────────────────────────────┐");
-    println!("│  RIEPILOGO OWNERSHIP E BORROWING             │");
-    println!("├──────────────────────────────────────────────┤");
-    println!("│                                              │");
-    println!("│  let s1 = String::from(\"ciao\");              │");
-    println!("│  let s2 = s1;    // MOVE: s1 non valido     │");
-    println!("│  let s3 = s2.clone(); // CLONE: copia deep  │");
-    println!("│                                              │");
-    println!("│  let r = &s2;    // BORROW: prestito immut. │");
-    println!("│  let m = &mut s; // BORROW: prestito mut.   │");
-    println!("│                                              │");
-    println!("│  REGOLE:                                     │");
-    println!("│  - 1 owner alla volta                        │");
-    println!("│  - N riferimenti immutabili (&T) OPPURE      │");
-    println!("│    1 riferimento mutabile (&mut T)           │");
-    println!("│  - I riferimenti devono sempre essere validi │");
-    println!("│                                              │");
-    println!("└──────────────────────────────────────────────┘");
-
-    println!("\n✅ Capitolo 2 completato!");
+    schermo::riquadro(
+        "RIEPILOGO OWNERSHIP E BORROWING",
+        &[
+            "let s1 = String::from(\"ciao\");",
+            "let s2 = s1;    // MOVE: s1 non valido",
+            "let s3 = s2.clone(); // CLONE: copia deep",
+            "",
+            "let r = &s2;    // BORROW: prestito immut.",
+            "let m = &mut s; // BORROW: prestito mut.",
+            "",
+            "REGOLE:",
+            "- 1 owner alla volta",
+            "- N riferimenti immutabili (&T) OPPURE",
+            "  1 riferimento mutabile (&mut T)",
+            "- I riferimenti devono sempre essere validi",
+        ],
+    );
+
+    schermo::completato(2);
 }
 
 // ============================================================================
@@ -8,10 +8,11 @@
 // Esegui con: cargo run --example cap03_strutture
 // ============================================================================
 
+use rust_tutorial::schermo;
+use rust_tutorial::tui_text::{Bordo, Riquadro};
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 3: STRUCT, ENUM, PATTERN MATCHING ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 3: STRUCT, ENUM, PATTERN MATCHING"]);
 
     // ========================================================================
     // 3.1 - STRUCT CLASSICHE
@@ -336,7 +337,7 @@ fn main() {
     println!("  Pezzi in buono stato: {}", catalogo.conta_per_stato(&StatoConservazione::Buono));
     println!("  Pezzi in ottimo stato: {}", catalogo.conta_per_stato(&StatoConservazione::Ottimo));
 
-    println!("\n✅ Capitolo 3 completato!");
+    schermo::completato(3);
 }
 
 // ============================================================================
@@ -517,10 +518,10 @@ impl Catalogo {
         self.pezzi.push(pezzo);
     }
 
+    /// Il riquadro si allarga da solo fino alla riga piu lunga
     fn stampa(&self) {
-        println!("╔═══════════════════════════════════════════╗");
-        println!("║          CATALOGO REPERTI                 ║");
-        println!("╠═══════════════════════════════════════════╣");
+        let mut riquadro = Riquadro::nuovo(Bordo::Doppio).larghezza_minima(43);
+        riquadro.riga("CATALOGO REPERTI");
         for pezzo in &self.pezzi {
             let tipo_str = match &pezzo.tipo {
                 TipoReperto::Arma(n, l) => format!("Arma: {} ({:.0}cm)", n, l),
@@ -530,13 +531,15 @@ impl Catalogo {
                 }
                 TipoReperto::Frammento => "Frammento".to_string(),
             };
-            println!("║ #{} - {}", pezzo.id, pezzo.nome);
-            println!("║    Tipo: {}", tipo_str);
-            println!("║    Periodo: {} ({})", pezzo.periodo.nome(), pezzo.periodo.range_anni());
-            println!("║    Stato: {}", pezzo.stato.descrizione());
-            println!("╠═══════════════════════════════════════════╣");
+            riquadro
+                .separatore()
+                .riga(format!("#{} - {}", pezzo.id, pezzo.nome))
+                .riga(format!("   Tipo: {}", tipo_str))
+                .riga(format!("   Periodo: {} ({})", pezzo.periodo.nome(), pezzo.periodo.range_anni()))
+                .riga(format!("   Stato: {}", pezzo.stato.descrizione()));
         }
-        println!("╚═══════════════════════════════════════════╝\n");
+        schermo::stampa(&riquadro);
+        println!();
     }
 
     fn trova_per_id(&self, id: u32) -> Option<&Pezzo> {
@@ -14,10 +14,10 @@
 use std::fmt;
 use std::num::ParseIntError;
 
+use rust_tutorial::schermo;
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 4: GESTIONE DEGLI ERRORI          ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 4: GESTIONE DEGLI ERRORI"]);
 
     // ========================================================================
     // 4.1 - PANIC! (ERRORI IRRECUPERABILI)
@@ -143,6 +143,7 @@ fn main() {
     println!("--- 4.5 Errori Personalizzati ---\n");
 
     // Puoi creare i tuoi tipi di errore con enum
+    // (nel capitolo 16 lo stesso lavoro lo fa il crate thiserror)
 
     let test_cases = vec![
         ("Ascia", "Bronzo", "350.5", "2019"),    // OK
@@ -284,7 +285,7 @@ fn main() {
         .collect();
     println!("Solo validi: {:?}", solo_validi);  // [1, 3, 5]
 
-    println!("\n✅ Capitolo 4 completato!");
+    schermo::completato(4);
 }
 
 // ============================================================================
@@ -13,10 +13,10 @@
 
 use std::collections::HashMap;
 
+use rust_tutorial::schermo;
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 5: COLLEZIONI                     ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 5: COLLEZIONI"]);
 
     // ========================================================================
     // 5.1 - VEC<T> (VETTORI)
@@ -224,6 +224,8 @@ fn main() {
     // Gli iteratori sono uno dei punti di forza di Rust.
     // Sono LAZY: non fanno nulla finche non consumi i risultati.
     // Sono ZERO-COST: il compilatore li ottimizza quanto un loop manuale.
+    // Per verificarlo con i tempi alla mano (ciclo for, iteratori e rayon):
+    //   cargo run --release --features parallel --example benchmark_iteratori
 
     let numeri = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
 
@@ -364,7 +366,7 @@ fn main() {
         println!("  {} - {:.0}g", ascia.nome, ascia.peso);
     }
 
-    println!("\n✅ Capitolo 5 completato!");
+    schermo::completato(5);
 }
 
 // ============================================================================
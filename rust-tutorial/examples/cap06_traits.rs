@@ -10,10 +10,11 @@
 
 use std::fmt;
 
+use rust_tutorial::schermo;
+use rust_tutorial::tui_text::{Bordo, Riquadro};
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 6: TRAITS E GENERICS              ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 6: TRAITS E GENERICS"]);
 
     // ========================================================================
     // 6.1 - DEFINIRE E IMPLEMENTARE TRAITS
@@ -256,7 +257,7 @@ fn main() {
 
     report.stampa();
 
-    println!("\n✅ Capitolo 6 completato!");
+    schermo::completato(6);
 }
 
 // ============================================================================
@@ -571,19 +572,19 @@ impl ClassificazioneReport {
     }
 
     fn stampa(&self) {
-        println!("╔═══════════════════════════════════════════════════╗");
-        println!("║         REPORT DI CLASSIFICAZIONE                ║");
-        println!("╠═══════════════════════════════════════════════════╣");
+        let mut riquadro = Riquadro::nuovo(Bordo::Doppio).larghezza_minima(51);
+        riquadro.riga("REPORT DI CLASSIFICAZIONE");
         for (nome, periodo, importanza, peso) in &self.entries {
-            println!("║ {} ", nome);
-            println!("║   Periodo: {}", periodo);
-            println!("║   Importanza: {}/10 | Peso: {:.0}g", importanza, peso);
-            println!("╠═══════════════════════════════════════════════════╣");
+            riquadro
+                .separatore()
+                .riga(nome.as_str())
+                .riga(format!("  Periodo: {}", periodo))
+                .riga(format!("  Importanza: {}/10 | Peso: {:.0}g", importanza, peso));
         }
         let media_importanza: f64 = self.entries.iter()
             .map(|(_, _, imp, _)| *imp as f64)
             .sum::<f64>() / self.entries.len() as f64;
-        println!("║ Media importanza: {:.1}/10", media_importanza);
-        println!("╚═══════════════════════════════════════════════════╝");
+        riquadro.separatore().riga(format!("Media importanza: {:.1}/10", media_importanza));
+        schermo::stampa(&riquadro);
     }
 }
@@ -155,7 +155,8 @@ mod analisi {
             }
         }
 
-        /// Filtra i risultati sopra la soglia
+        /// Filtra i risultati sopra la soglia. `'a` lega i riferimenti
+        /// restituiti alla slice in ingresso (vedi capitolo 10)
         pub fn filtra_significativi<'a>(&self, risultati: &'a [RisultatoAnalisi]) -> Vec<&'a RisultatoAnalisi> {
             risultati.iter()
                 .filter(|r| r.punteggio >= self.soglia)
@@ -300,11 +301,10 @@ mod utils {
 use database::{Database, Record};
 use analisi::{Analizzatore, statistiche};
 use report::Formato;
+use rust_tutorial::schermo;
 
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 7: MODULI E ORGANIZZAZIONE        ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 7: MODULI E ORGANIZZAZIONE"]);
 
     // ========================================================================
     // 7.1 - USARE I MODULI
@@ -435,11 +435,14 @@ fn main() {
     println!("  mod report;      // carica da src/report.rs");
     println!("  mod utils;       // carica da src/utils.rs");
     println!();
+    println!("Per creare questa struttura, gia collegata, in una nuova cartella:");
+    println!("  cargo run -- scaffold mio-catalogo");
+    println!();
     println!("VISIBILITA:");
     println!("  (niente) -> privato al modulo corrente");
     println!("  pub      -> visibile a tutti");
     println!("  pub(crate) -> visibile solo nel crate corrente");
     println!("  pub(super) -> visibile al modulo padre");
 
-    println!("\n✅ Capitolo 7 completato!");
+    schermo::completato(7);
 }
@@ -18,10 +18,10 @@ use std::thread;
 use std::time::Duration;
 use std::collections::HashMap;
 
+use rust_tutorial::schermo;
+
 fn main() {
-    println!("╔══════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 8: CONCORRENZA                    ║");
-    println!("╚══════════════════════════════════════════════╝\n");
+    schermo::intestazione(&["CAPITOLO 8: CONCORRENZA"]);
 
     // ========================================================================
     // 8.1 - CREARE THREAD
@@ -319,28 +319,27 @@ fn main() {
     // ========================================================================
     println!("\n--- 8.8 Riepilogo ---\n");
 
-    println!("┌──────────────────────────────────────────────────┐");
-    println!("│  STRUMENTI DI CONCORRENZA IN RUST               │");
-    println!("├──────────────────────────────────────────────────┤");
-    println!("│                                                  │");
-    println!("│  thread::spawn  -> crea un nuovo thread          │");
-    println!("│  handle.join()  -> aspetta che il thread finisca │");
-    println!("│  move ||        -> sposta dati nel thread        │");
-    println!("│                                                  │");
-    println!("│  mpsc::channel  -> comunicazione tra thread      │");
-    println!("│  tx.send()      -> invia un messaggio            │");
-    println!("│  rx.recv()      -> ricevi un messaggio           │");
-    println!("│                                                  │");
-    println!("│  Mutex<T>       -> accesso esclusivo ai dati     │");
-    println!("│  Arc<T>         -> ownership condivisa (thread)  │");
-    println!("│  Arc<Mutex<T>>  -> dati condivisi e mutabili     │");
-    println!("│                                                  │");
-    println!("│  Send trait     -> tipo trasferibile tra thread  │");
-    println!("│  Sync trait     -> tipo riferibile da piu thread │");
-    println!("│                                                  │");
-    println!("│  GARANZIA: se compila, niente data race!         │");
-    println!("│                                                  │");
-    println!("└──────────────────────────────────────────────────┘");
-
-    println!("\n✅ Capitolo 8 completato!");
+    schermo::riquadro(
+        "STRUMENTI DI CONCORRENZA IN RUST",
+        &[
+            "thread::spawn  -> crea un nuovo thread",
+            "handle.join()  -> aspetta che il thread finisca",
+            "move ||        -> sposta dati nel thread",
+            "",
+            "mpsc::channel  -> comunicazione tra thread",
+            "tx.send()      -> invia un messaggio",
+            "rx.recv()      -> ricevi un messaggio",
+            "",
+            "Mutex<T>       -> accesso esclusivo ai dati",
+            "Arc<T>         -> ownership condivisa (thread)",
+            "Arc<Mutex<T>>  -> dati condivisi e mutabili",
+            "",
+            "Send trait     -> tipo trasferibile tra thread",
+            "Sync trait     -> tipo riferibile da piu thread",
+            "",
+            "GARANZIA: se compila, niente data race!",
+        ],
+    );
+
+    schermo::completato(8);
 }
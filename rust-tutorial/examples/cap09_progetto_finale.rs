@@ -10,823 +10,3375 @@
 // - Collezioni e iteratori (Cap 5)
 // - Moduli (Cap 7)
 // - Serializzazione JSON con serde
+// - Allegati con verifica di integrita (SHA-256) e miniature
+// - Modelli 3D da fotogrammetria (OBJ/PLY/GLB) e documenti (PDF/DOCX)
+// - Prestiti con generazione del contratto da modello
+// - Diritti e licenze (con embargo) su reperti e allegati
+// - Catena di provenienza (scavo -> deposito -> museo -> prestito)
+// - Stime di valore e report assicurativo
+// - Campagne periodiche di controllo dello stato di conservazione
+// - Movimentazioni e report di custodia
+// - Anagrafica delle persone e attribuzione di rinvenimenti, foto e note
+// - Flusso di catalogazione (in ingresso -> catalogato -> verificato -> pubblicato)
+// - Profili di visibilita per esportazioni interne e pubbliche
+// - Offuscamento delle coordinate ed esportazione GeoJSON
+// - Mappa dei rinvenimenti nel terminale
+// - Importazione di tracce GPX e georeferenziazione per orario
+// - Griglia di scavo (quadrati) e conversione in coordinate assolute
+// - Quote, profondita e profilo di sezione in SVG
+// - Campioni per analisi di laboratorio e relativo stato
+// - Interventi di restauro con prodotti e lotti impiegati
+// - Composizione in piu materiali (es. lama in bronzo, immanicatura in osso)
+// - Decorazioni descritte con vocabolario controllato
+// - Osservazioni traceologiche (tracce d'uso e residui)
+// - Classe funzionale distinta dalla tipologia e composizione dei ripostigli
+// - Analisi ponderale (quantogramma del coseno) per lingotti e asce
+// - Indice di frammentazione per contesti e ripostigli
+// - Assistente tipologico basato su chiavi dicotomiche in file JSON
+// - Ricerca di confronti (comparanda) per tipo, periodo e dimensioni
+// - Corpus di riferimento (cataloghi tipo PBF) importato da CSV o JSON
+// - Statistiche aggregate mantenute in modo incrementale
+// - Priorita di restauro per percentili (conservazione, materiale, ultimo intervento)
+// - Tabella incrociata periodo x materiale, anche in CSV
+// - Grafico cronologico a barre impilate per classe funzionale (terminale e SVG)
+// - Generatore di inventari sintetici per demo e prove di carico
+// - Esportazioni in ordine deterministico (per ID o per chiave scelta)
+// - Impronta SHA-256 dello stato del catalogo nell'intestazione di ogni esportazione
+// - Archivio su file singolo o un file per reperto (data/reperti/<id>.json), adatto a Git
+// - Cifratura opzionale del file dati (ChaCha20-Poly1305, chiave da passphrase con Argon2)
+// - Impronta SHA-256 in coda al file dati, verificata al caricamento, con copia di backup
+// - Recupero parziale di file danneggiati, con quarantena dei record illeggibili
+// - Blocco del file dati tra istanze concorrenti (attesa o apertura in sola lettura)
+// - Modalita --watch: ricarica automatica quando il file dati cambia dall'esterno
+// - Archivio SQLite con migrazioni versionate (migrate / status / rollback)
+// - Archivio PostgreSQL (JSONB, indici trigram per la ricerca testuale), scelto dall'indirizzo
+// - Pubblicazione di snapshot e registro delle modifiche per copie in sola lettura
+// - Endpoint /healthz e /readyz per orchestratori di container, comando ping
+// - Configurazione solo da variabili d'ambiente, --data-dir e arresto pulito su SIGTERM
+// - Modifiche concorrenti con numero di versione per reperto e fusione guidata dei conflitti
+// - Registro delle attivita per operatore e report annuale di catalogazione per persona
+// - Riepilogo giornaliero/settimanale via email (SMTP opzionale) di novita e anomalie
+// - Rimozione di schede catalogate con approvazione di un secondo utente responsabile
+// - Radiazione dall'inventario (scarico, smarrimento, furto) con scheda conservata nelle esportazioni
+// - Concessione, autorizzazione ministeriale e decreto di deposito per campagna di scavo
+// - Tesauri gerarchici (materiali, tipologie, decorazioni) con ricerca per termine e SKOS
+// - Collegamento dei termini al Getty AAT, con ricerca online opzionale e cache locale
+// - Pacchetto del dataset pubblico per Zenodo (JSON, CSV, README, licenza) e DOI via API
+// - Citazioni APA, Chicago e BibTeX del dataset e dei reperti, con URI stabili
+// - Esportazione dei metadati nel modello AO-Cat di ARIADNEplus
+// - Grafo CIDOC-CRM inviato in modo incrementale a un endpoint SPARQL Update
+// - Bibliografia dei reperti completata da Crossref e iD ORCID delle persone, con cache locale
+// - Paragrafo per la relazione di scavo dalle statistiche, rifinito da un modello linguistico
+//   se configurato (BRONZEAXE_LLM_URL, BRONZEAXE_LLM_MODELLO, BRONZEAXE_LLM_CHIAVE)
+// - Riga di comando descritta con clap: aiuto generato, valori controllati (vedi capitolo 14)
 //
-// Esegui con: cargo run --example cap09_progetto_finale
+// Esegui con: cargo run --features full --example cap09_progetto_finale
+// Elenco dei sottocomandi e delle opzioni:
+//   cargo run --features full --example cap09_progetto_finale -- --help
+// Assistente interattivo:
+//   cargo run --features full --example cap09_progetto_finale -- --assistente-tipologia [chiave.json]
+// Lista di priorita di restauro dei reperti dell'archivio:
+//   cargo run --features full --example cap09_progetto_finale -- triage [n] [--archivio indirizzo]
+// Ricarica automatica di un file dati modificato da altri (es. sincronizzazione):
+//   cargo run --features full --example cap09_progetto_finale -- --watch inventario.json
+// Copia in sola lettura che segue gli snapshot pubblicati in una cartella e ne
+// serve le rotte GET (/reperti, /reperti/{id}, /healthz, /readyz) su BRONZEAXE_ASCOLTA:
+//   cargo run --features full --example cap09_progetto_finale -- --mirror cartella_pubblicazione
+// Endpoint di salute (archivio da file, cartella/, sqlite:file o postgres://):
+//   cargo run --features full --example cap09_progetto_finale -- serve [--data-dir dati] [--ascolta 127.0.0.1:8080] [--archivio indirizzo]
+//   (oppure BRONZEAXE_DATA_DIR, BRONZEAXE_ASCOLTA, BRONZEAXE_ARCHIVIO; SIGTERM o Ctrl-C per fermarlo)
+//   cargo run --features full --example cap09_progetto_finale -- ping http://127.0.0.1:8080[/readyz]
+// Migrazioni dello schema del database SQLite:
+//   cargo run --features full --example cap09_progetto_finale -- db migrate|status|rollback inventario.db
+// Pacchetto del dataset pubblico per Zenodo; con BRONZEAXE_ZENODO_TOKEN crea la bozza,
+// con --pubblica registra anche il DOI (BRONZEAXE_ZENODO_URL per la sandbox):
+//   cargo run --features full --example cap09_progetto_finale -- deposito cartella [--archivio indirizzo] [--bozza] [--pubblica]
+// Citazione del dataset o di un reperto (stili apa, chicago, bibtex); BRONZEAXE_DOI
+// indica il DOI del dataset depositato:
+//   cargo run --features full --example cap09_progetto_finale -- cita [id] [--stile apa] [--archivio indirizzo]
+// Metadati AO-Cat (Turtle) per l'aggregazione in ARIADNEplus:
+//   cargo run --features full --example cap09_progetto_finale -- aocat [--archivio indirizzo] > ariadne.ttl
+// Invio del grafo CIDOC-CRM pubblicato a un triple store (BRONZEAXE_SPARQL):
+//   cargo run --features full --example cap09_progetto_finale -- sparql cartella_pubblicazione [--stato file]
+// Verifica delle impronte degli allegati e aggiornamento dei percorsi dopo uno spostamento:
+//   cargo run --features full --example cap09_progetto_finale -- media verify [--archivio indirizzo]
+//   cargo run --features full --example cap09_progetto_finale -- media relocate --from vecchia --to nuova [--archivio indirizzo]
+//   cargo run --features full --example cap09_progetto_finale -- media miniature [--archivio indirizzo]
+// Riepilogo delle novita (da cron), inviato per email se BRONZEAXE_SMTP_SERVER e impostata:
+//   cargo run --features full --example cap09_progetto_finale -- digest [giornaliero|settimanale] [--archivio indirizzo]
+//   (BRONZEAXE_SMTP_SERVER=host[:porta], BRONZEAXE_SMTP_UTENTE, BRONZEAXE_SMTP_PASSWORD,
+//    BRONZEAXE_SMTP_MITTENTE, BRONZEAXE_NOTIFICHE_A=a@x.it,b@y.it, BRONZEAXE_RIEPILOGO)
+// Inventario sintetico in JSON:
+//   cargo run --features full --example cap09_progetto_finale -- seed --count 10000 [--seme 42] [--ordine id|nome|periodo|sito] [--id sequenziale|uuid|sito] [--output file.json]
+// Campi calcolati da formule (righe `densita = peso_grammi / volume`), con filtri:
+//   cargo run --features full --example cap09_progetto_finale -- campi --definizioni campi.conf [--dove "densita > 8"] [--dove "peso > 0.3kg"] [--csv] [--archivio indirizzo]
+//   (oppure BRONZEAXE_CAMPI_CALCOLATI al posto di --definizioni)
+// Bozze dal testo OCR delle schede cartacee (pagine separate da \f), da verificare:
+//   cargo run --features full --example cap09_progetto_finale -- ocr schede.txt [--regole regole.ocr] [--sito "Frattesina"] [--salva] [--archivio indirizzo]
+// Bozze dalle righe dettate sul campo e trascritte (una per reperto, "annulla" toglie l'ultima):
+//   cargo run --features full --example cap09_progetto_finale -- dettatura [trascrizione.txt] [--sito "Frattesina"] [--salva] [--archivio indirizzo]
+// Fogli PDF di etichette con QR per casse e cassetti, su carta L7160, L7163, L7165 o L7166:
+//   cargo run --features full --example cap09_progetto_finale -- etichette [--modello L7163] [--collocazione "Deposito A"] [--salta 3] [--output etichette.pdf] [--archivio indirizzo]
+// Distinta di trasporto per cassa (pesi, reperti fragili, foto dello stato) di reperti scelti:
+//   cargo run --features full --example cap09_progetto_finale -- trasporto [--id 3,7,12] [--dove "peso > 100g"] [--collocazione "Deposito A"] [--output distinta.txt] [--archivio indirizzo]
+// Dossier di un sito (mappa, periodi, schede, bibliografia) in Markdown o PDF:
+//   cargo run --features full --example cap09_progetto_finale -- dossier "Savignano sul Panaro" [--pdf] [--output dossier.md] [--archivio indirizzo]
 // ============================================================================
 
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fmt;
+use std::path::PathBuf;
+use chrono::NaiveDate;
+use clap::Parser;
+
+// Tutto il gestore sta nella libreria (src/catalogo/): modelli, archivi,
+// servizi e riga di comando. Qui restano la dimostrazione e i comandi che
+// collegano i pezzi.
+use rust_tutorial::catalogo::{
+    aat, archivio, ariadne, assicurazione, calcolati, campioni, cidoc, citazioni, cli, condizione, configurazione,
+    confronti, corpus, custodia, deposito, dettatura, errori, etichette, fusione, generatore, geo, gpx, grafici,
+    inventario, media, migrazione, modelli, notifiche, ocr, persone, ponderale, postgres, prestiti, replica, restauro,
+    ricerca, riferimenti, salute, servizio, sparql, statistiche, tesauro, tipologia, trasporto, visibilita,
+};
+use rust_tutorial::catalogo::narrativa::Narrativa;
 
+// MAIN - DIMOSTRAZIONE COMPLETA
 // ============================================================================
-// MODULO: MODELLI
-// ============================================================================
-mod modelli {
-    use super::*;
 
-    /// Materiale del reperto
-    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-    pub enum Materiale {
-        Bronzo,
-        Ferro,
-        Oro,
-        Argento,
-        Ceramica,
-        Pietra,
-        Osso,
-        Altro(String),
+use modelli::*;
+use errori::ErroreInventario;
+use inventario::Inventario;
+use rust_tutorial::schermo;
+
+/// Un nodo di scrittura pubblica, due copie per la mostra seguono gli snapshot
+fn dimostra_replica(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    let cartella = std::env::temp_dir().join("bronzeaxe_pubblicazione");
+    if cartella.exists() {
+        std::fs::remove_dir_all(&cartella)?;
+    }
+    let pubblicatore = replica::Pubblicatore { cartella: cartella.clone() };
+    let mut redazione = inventario::Inventario::nuovo();
+    for r in inv.tutti() {
+        redazione.importa(r.clone())?;
+    }
+
+    if let Some(v) = pubblicatore.pubblica(&redazione)? {
+        println!("  Pubblicato {} ({} reperti)", &v.snapshot[..12], v.reperti);
+    }
+    let sala_a = replica::Specchio::apri(cartella.clone())?;
+    let sala_b = replica::Specchio::apri(cartella.clone())?;
+    println!("  Sala A e sala B servono {} reperti", sala_a.leggi(|i| i.totale()));
+
+    redazione.aggiungi_nota(2, "Nuova foto per il pannello della mostra")?;
+    redazione.rimuovi(3)?;
+    if let Some(v) = pubblicatore.pubblica(&redazione)? {
+        println!("  Pubblicato {}: modificati {:?}, rimossi {:?}", &v.snapshot[..12], v.modificati, v.rimossi);
+    }
+    println!("  Ripubblicazione senza modifiche: {:?}", pubblicatore.pubblica(&redazione)?.map(|v| v.snapshot));
+    for v in sala_a.aggiorna()? {
+        println!("  Sala A aggiornata a {} tramite il feed", &v.snapshot[..12]);
     }
+    println!(
+        "  Sala A: {} reperti, sala B ancora su {} con {} (aggiornamento al prossimo controllo)",
+        sala_a.leggi(|i| i.totale()),
+        &sala_b.snapshot()[..12],
+        sala_b.leggi(|i| i.totale())
+    );
+    Ok(())
+}
 
-    impl fmt::Display for Materiale {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Materiale::Bronzo => write!(f, "Bronzo"),
-                Materiale::Ferro => write!(f, "Ferro"),
-                Materiale::Oro => write!(f, "Oro"),
-                Materiale::Argento => write!(f, "Argento"),
-                Materiale::Ceramica => write!(f, "Ceramica"),
-                Materiale::Pietra => write!(f, "Pietra"),
-                Materiale::Osso => write!(f, "Osso"),
-                Materiale::Altro(s) => write!(f, "Altro: {}", s),
-            }
+/// Server di salute su una porta libera, interrogato con ping: prima con un
+/// database non migrato (non pronto), poi con lo schema aggiornato
+fn dimostra_salute() -> Result<(), errori::ErroreInventario> {
+    use archivio::Archivio;
+    let file = std::env::temp_dir().join("bronzeaxe_salute.db");
+    if file.exists() {
+        std::fs::remove_file(&file)?;
+    }
+    let db = std::sync::Arc::new(migrazione::ArchivioSqlite::nuovo(file.clone()));
+    let ascolto = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let base = format!("http://{}", ascolto.local_addr()?);
+
+    let arresto = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let interroga = |percorso: &str| match salute::ping(&format!("{}{}", base, percorso)) {
+        Ok(r) => println!("  {:<12} {} {}", percorso, r.codice, r.corpo),
+        Err(e) => println!("  {:<12} errore: {}", percorso, e),
+    };
+    std::thread::scope(|s| {
+        let app = salute::rotte(db.clone());
+        let server = s.spawn(|| salute::servi(&ascolto, app, std::sync::Arc::clone(&arresto)));
+        interroga("/healthz");
+        interroga("/readyz");
+        match rusqlite::Connection::open(&file).map_err(errori::ErroreInventario::from).and_then(|mut c| migrazione::migra(&mut c)) {
+            Ok(v) => println!("  (migrazioni applicate: {:?})", v),
+            Err(e) => println!("  Migrazione: {}", e),
+        }
+        interroga("/readyz");
+        interroga("/sconosciuto");
+        // Quello che fa il gestore di SIGTERM nel comando serve
+        arresto.store(true, std::sync::atomic::Ordering::Relaxed);
+        match server.join().map(|esito| esito.and_then(|()| db.chiudi())) {
+            Ok(Ok(())) => println!("  Server fermato, archivio chiuso"),
+            Ok(Err(e)) => println!("  Server fermato con errore: {}", e),
+            Err(_) => println!("  Server terminato in modo anomalo"),
         }
+    });
+
+    let conf = configurazione::Configurazione::da_variabili(|nome| match nome {
+        "BRONZEAXE_DATA_DIR" => Some("/data".to_string()),
+        "BRONZEAXE_ASCOLTA" => Some("0.0.0.0:8080".to_string()),
+        _ => None,
+    });
+    println!("  Configurazione tipica di un container: {:?}", conf);
+    println!("  Con --data-dir /mnt/volume: archivio {}", conf.con_data_dir(PathBuf::from("/mnt/volume")).archivio);
+    Ok(())
+}
+
+/// Due catalogatori aprono la stessa scheda: il secondo a salvare riceve un
+/// conflitto e risolve con la fusione guidata (risposte simulate)
+fn dimostra_modifiche_concorrenti(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    let id = 4;
+    let mut di_anna = inv.inizia_modifica(id)?;
+    let mut di_marco = inv.inizia_modifica(id)?;
+    println!("  Anna e Marco aprono #{} alla versione {}", id, di_anna.originale.versione);
+
+    di_anna.bozza.descrizione = "Fibula ad arco serpeggiante, ago integro".to_string();
+    di_anna.bozza.note.push("Ricontrollata la molla".into());
+    println!("  Anna salva: versione {}", inv.conferma_modifica(di_anna)?);
+
+    di_marco.bozza.descrizione = "Fibula ad arco serpeggiante con staffa corta".to_string();
+    di_marco.bozza.tipo = Some("Fibula tipo Peschiera".to_string());
+    match inv.conferma_modifica(di_marco.clone()) {
+        Err(e @ errori::ErroreInventario::Conflitto { .. }) => println!("  Marco salva: {}", e),
+        altro => println!("  Marco salva: {:?}", altro.map(|_| ())),
     }
 
-    /// Periodo storico
-    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-    pub enum Periodo {
-        BronzoAntico,     // 2300-1700 a.C.
-        BronzoMedio,      // 1700-1350 a.C.
-        BronzoRecente,    // 1350-1200 a.C.
-        BronzoFinale,     // 1200-950 a.C.
-        PrimaEtaFerro,   // 950-750 a.C.
-        Sconosciuto,
+    let fusione = fusione::fondi(&di_marco, inv.cerca_per_id(id)?)?;
+    let mut uscita = Vec::new();
+    let unita = fusione::chiedi(fusione, std::io::Cursor::new("m\n"), &mut uscita)?;
+    for riga in String::from_utf8_lossy(&uscita).lines().filter(|r| !r.is_empty()) {
+        println!("    | {}", riga);
     }
+    println!("  Dopo la fusione: versione {}", inv.conferma_modifica(unita)?);
+    let finale = inv.cerca_per_id(id)?;
+    println!("  Descrizione: {}", finale.descrizione);
+    println!("  Tipo (solo Marco): {:?}; note di Anna conservate: {}", finale.tipo, finale.note.iter().any(|n| n.testo.contains("molla")));
+    Ok(())
+}
 
-    impl fmt::Display for Periodo {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Periodo::BronzoAntico => write!(f, "Bronzo Antico (2300-1700 a.C.)"),
-                Periodo::BronzoMedio => write!(f, "Bronzo Medio (1700-1350 a.C.)"),
-                Periodo::BronzoRecente => write!(f, "Bronzo Recente (1350-1200 a.C.)"),
-                Periodo::BronzoFinale => write!(f, "Bronzo Finale (1200-950 a.C.)"),
-                Periodo::PrimaEtaFerro => write!(f, "Prima Eta del Ferro (950-750 a.C.)"),
-                Periodo::Sconosciuto => write!(f, "Periodo sconosciuto"),
-            }
+fn dimostra_report_attivita(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use chrono::Datelike;
+    use modelli::{Operazione, RuoloUtente, VoceRegistro};
+    use persone::Cadenza;
+
+    let anna = inv.persone().find(|p| p.nome == "Anna Greco").map(|p| p.id);
+    let marco = inv.persone().find(|p| p.nome == "Marco Esposito").map(|p| p.id);
+    let (Some(anna), Some(marco)) = (anna, marco) else {
+        println!("  Nessuna persona registrata");
+        return Ok(());
+    };
+
+    // Attivita dell'anno scorso, riletta dal registro archiviato
+    let oggi = chrono::Local::now().date_naive();
+    let anno_scorso = oggi.with_year(oggi.year() - 1).unwrap_or(oggi);
+    for (giorni, persona, reperto, operazione) in [
+        (0, anna, 1, Operazione::Creazione),
+        (0, anna, 2, Operazione::Creazione),
+        (40, marco, 1, Operazione::Modifica),
+        (41, marco, 1, Operazione::Modifica),
+        (120, anna, 2, Operazione::CambioStato),
+    ] {
+        let giorno = anno_scorso - chrono::Duration::days(giorni);
+        inv.registra(VoceRegistro {
+            quando: giorno.and_hms_opt(10, 0, 0).unwrap_or_default(),
+            persona,
+            reperto,
+            operazione,
+        })?;
+    }
+
+    // Attivita di oggi, annotata mentre si lavora
+    inv.accedi(anna)?;
+    let nuovo = inv.aggiungi(reperti_di_esempio().remove(0))?;
+    inv.modifica_come(nuovo, RuoloUtente::Catalogatore, |r| r.descrizione.push_str(" (scheda di prova)"))?;
+    inv.accedi(marco)?;
+    inv.modifica_come(nuovo, RuoloUtente::Catalogatore, |r| r.tipo = None)?;
+    inv.rimuovi(nuovo)?;
+    inv.esci();
+    println!("  Voci nel registro: {}", inv.registro().len());
+
+    let inizio = NaiveDate::from_ymd_opt(anno_scorso.year() - 1, 1, 1).unwrap_or(anno_scorso);
+    persone::stampa_attivita_per_periodo(&persone::attivita_per_periodo(inv, inizio, oggi, Cadenza::Anno));
+    println!();
+    let trimestri = persone::attivita_per_periodo(inv, inizio, anno_scorso, Cadenza::Trimestre);
+    print!("{}", persone::csv_attivita(&trimestri).lines().map(|r| format!("  {}\n", r)).collect::<String>());
+    for r in persone::attivita_per_periodo(inv, oggi, oggi, Cadenza::Mese) {
+        println!("  Mese {}: {} ha creato {} e modificato {} schede", r.periodo, r.nome, r.creati, r.modificati);
+    }
+    Ok(())
+}
+
+fn dimostra_riepilogo(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use archivio::Archivio;
+    use notifiche::Frequenza;
+
+    let oggi = chrono::Local::now().date_naive();
+    let anna = inv.persone().next().map(|p| p.id);
+    if let Some(anna) = anna {
+        inv.accedi(anna)?;
+        inv.aggiungi(Reperto::nuovo("Spillone a capocchia conica", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Savignano sul Panaro"))?;
+        inv.esci();
+    }
+    let mut campagna = condizione::Campagna::nuova("Controllo straordinario", oggi);
+    campagna.registra(inv, 1, Conservazione::Frammentario)?;
+
+    let cartella = std::env::temp_dir().join(format!("bronzeaxe_riepilogo_{}", std::process::id()));
+    std::fs::create_dir_all(&cartella)?;
+    let dati = archivio::FileJson::nuovo(cartella.join("inventario.json"), None);
+    dati.salva(inv)?;
+
+    // Al primo salvataggio non c'e ancora un backup: finisce tra i "falliti"
+    let riepilogo = notifiche::Riepilogo::componi(inv, inv.prestiti(), oggi, Frequenza::Settimanale).con_backup(&dati);
+    let conf = configurazione::Configurazione::da_ambiente();
+    let inviato = match &conf.smtp {
+        Some(smtp) => riepilogo.invia(smtp)?,
+        None => false,
+    };
+    println!("  Oggetto: {}", riepilogo.oggetto());
+    for riga in riepilogo.testo().lines() {
+        println!("  | {}", riga);
+    }
+    match &conf.smtp {
+        Some(s) if inviato => println!("  Inviato a {} tramite {}:{}", s.destinatari.join(", "), s.server, s.porta),
+        Some(_) => println!("  Niente da segnalare: nessun invio"),
+        None => println!("  SMTP non configurato (BRONZEAXE_SMTP_SERVER): riepilogo solo a video"),
+    }
+
+    std::fs::remove_dir_all(&cartella)?;
+    Ok(())
+}
+
+fn dimostra_rimozioni_approvate(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use modelli::RuoloUtente;
+
+    let persone: Vec<u32> = inv.persone().map(|p| p.id).collect();
+    let catalogati: Vec<u32> = inv.tutti().iter().filter(|r| r.stato != StatoCatalogazione::InIngresso).map(|r| r.id).collect();
+    let ([anna, marco, sara, ..], [primo, secondo, ..]) = (persone.as_slice(), catalogati.as_slice()) else {
+        println!("  Servono tre persone e due schede catalogate");
+        return Ok(());
+    };
+    inv.assegna_ruolo_utente(*anna, RuoloUtente::Responsabile)?;
+    inv.assegna_ruolo_utente(*marco, RuoloUtente::Revisore)?;
+    inv.assegna_ruolo_utente(*sara, RuoloUtente::Responsabile)?;
+
+    inv.accedi(*anna)?;
+    for id in [*primo, *secondo] {
+        if let Err(e) = inv.rimuovi(id) {
+            println!("  Anna rimuove #{}: {}", id, e);
         }
     }
+    let richieste: Vec<u32> = inv.rimozioni_in_attesa().map(|r| r.numero).collect();
+    if let Err(e) = inv.conferma_rimozione(richieste[0]) {
+        println!("  Anna approva da sola: {}", e);
+    }
 
-    /// Stato di conservazione
-    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-    pub enum Conservazione {
-        Integro,
-        Buono,
-        Discreto,
-        Frammentario,
-        Pessimo,
+    inv.accedi(*marco)?;
+    if let Err(e) = inv.conferma_rimozione(richieste[0]) {
+        println!("  Marco (revisore): {}", e);
     }
 
-    impl Conservazione {
-        pub fn punteggio(&self) -> u8 {
-            match self {
-                Conservazione::Integro => 5,
-                Conservazione::Buono => 4,
-                Conservazione::Discreto => 3,
-                Conservazione::Frammentario => 2,
-                Conservazione::Pessimo => 1,
-            }
+    inv.accedi(*sara)?;
+    let rimosso = inv.conferma_rimozione(richieste[0])?;
+    println!("  Sara (responsabile) approva: rimosso #{} {}", rimosso.id, rimosso.nome);
+    inv.rifiuta_rimozione(richieste[1])?;
+    println!("  Sara respinge la richiesta n. {}: #{} resta in inventario ({})", richieste[1], secondo, inv.cerca_per_id(*secondo).is_ok());
+    inv.esci();
+    println!("  Richieste ancora aperte: {}", inv.rimozioni_in_attesa().count());
+    Ok(())
+}
+
+fn dimostra_radiazione(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use archivio::Archivio;
+    use modelli::{MotivoRadiazione, Radiazione, RuoloUtente};
+
+    let Some(id) = inv.tutti().iter().rev().find(|r| r.collocazione.is_some()).map(|r| r.id) else {
+        return Ok(());
+    };
+    let prima = inv.totale();
+    let smarrimento = Radiazione {
+        motivo: MotivoRadiazione::Smarrimento,
+        data: NaiveDate::from_ymd_opt(2025, 11, 4).unwrap_or_default(),
+        documento: "Denuncia ai Carabinieri TPC n. 118/2025".to_string(),
+        ultima_collocazione: None,
+    };
+    if let Err(e) = inv.radia(id, smarrimento.clone(), RuoloUtente::Revisore) {
+        println!("  {}", e);
+    }
+    inv.radia(id, smarrimento, RuoloUtente::Responsabile)?;
+    println!("  Reperti attivi: {} -> {}; radiati: {}", prima, inv.totale(), inv.radiati().len());
+    for r in inv.radiati() {
+        if let Some(radiazione) = &r.radiazione {
+            println!("    #{} {}: {}", r.id, r.nome, radiazione);
         }
     }
+    println!("  Ancora cercabile tra gli attivi? {}", inv.cerca_per_id(id).is_ok());
+
+    let json: serde_json::Value = serde_json::from_str(&inv.to_json()?)?;
+    println!(
+        "  Esportazione: {} reperti, {} radiati (intestazione: {})",
+        json["reperti"].as_array().map_or(0, Vec::len),
+        json["radiati"].as_array().map_or(0, Vec::len),
+        json["intestazione"]["radiati"]
+    );
+
+    // La scheda radiata sopravvive al salvataggio
+    let file = std::env::temp_dir().join(format!("bronzeaxe_radiati_{}.json", std::process::id()));
+    let dati = archivio::FileJson::nuovo(file.clone(), None);
+    dati.salva(inv)?;
+    let riletto = dati.carica()?;
+    println!("  Dopo il ricaricamento: {} attivi, {} radiati", riletto.totale(), riletto.radiati().len());
+    std::fs::remove_file(&file)?;
+    Ok(())
+}
 
-    impl fmt::Display for Conservazione {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                Conservazione::Integro => write!(f, "Integro"),
-                Conservazione::Buono => write!(f, "Buono"),
-                Conservazione::Discreto => write!(f, "Discreto"),
-                Conservazione::Frammentario => write!(f, "Frammentario"),
-                Conservazione::Pessimo => write!(f, "Pessimo"),
-            }
+fn dimostra_estremi_scavo(inv: &mut inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use modelli::{CampagnaScavo, RuoloUtente};
+    use StatoCatalogazione::*;
+
+    let mut campagna = CampagnaScavo {
+        codice: "SAV-2024".to_string(),
+        sito: "Savignano sul Panaro".to_string(),
+        anno: 2024,
+        concessione: Some("DG-ABAP Serv. II, concessione 7/2024".to_string()),
+        autorizzazione_ministeriale: None,
+        decreto_deposito: None,
+    };
+    inv.registra_campagna_scavo(campagna.clone())?;
+    let Some(id) = inv.in_stato(InIngresso).first().map(|r| r.id) else {
+        return Ok(());
+    };
+    inv.assegna_campagna_scavo(id, &campagna.codice)?;
+    inv.cambia_stato(id, Catalogato, RuoloUtente::Catalogatore)?;
+    inv.cambia_stato(id, Verificato, RuoloUtente::Revisore)?;
+    if let Err(e) = inv.cambia_stato(id, Pubblicato, RuoloUtente::Responsabile) {
+        println!("  Rifiutato: {}", e);
+    }
+
+    campagna.autorizzazione_ministeriale = Some("Prot. MiC 8831 del 12/03/2024".to_string());
+    campagna.decreto_deposito = Some("Decreto SABAP-BO 54/2025".to_string());
+    inv.registra_campagna_scavo(campagna)?;
+    inv.cambia_stato(id, Pubblicato, RuoloUtente::Responsabile)?;
+    println!("  Reperto #{} pubblicato con la campagna completa", id);
+
+    let json: serde_json::Value = serde_json::from_str(&inv.to_json()?)?;
+    for c in json["campagne_scavo"].as_array().into_iter().flatten() {
+        println!(
+            "  Esportazione, campagna {}: concessione {}, autorizzazione {}, deposito {}",
+            c["codice"], c["concessione"], c["autorizzazione_ministeriale"], c["decreto_deposito"]
+        );
+    }
+    if let Some(c) = inv.campagna_scavo("SAV-2019") {
+        println!("  {} ({}, {}): riferimenti mancanti {:?}", c.codice, c.sito, c.anno, c.mancanti());
+    }
+    Ok(())
+}
+
+fn dimostra_tesauri(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use tesauro::Campo;
+
+    let tipologie = tesauro::tipologie()?;
+    let sotto_asce = tipologie.discendenti("asce");
+    println!("  Sotto 'asce': {} concetti", sotto_asce.len());
+    for (campo, tesauro, termine) in [
+        (Campo::Tipologia, &tipologie, "asce"),
+        (Campo::Tipologia, &tipologie, "armi"),
+        (Campo::Materiale, &tesauro::materiali()?, "metalli"),
+        (Campo::Decorazione, &tesauro::decorazioni()?, "motivi geometrici"),
+    ] {
+        let reperti = tesauro.reperti_sotto(inv, campo, termine)?;
+        let ids: Vec<u32> = reperti.iter().map(|r| r.id).collect();
+        println!("  {:?} sotto '{}': {:?}", campo, termine, ids);
+    }
+    if let Err(e) = tipologie.reperti_sotto(inv, Campo::Tipologia, "elmi") {
+        println!("  {}", e);
+    }
+
+    // Andata e ritorno in SKOS
+    let ttl = tesauro::decorazioni()?.to_skos();
+    for riga in ttl.lines().skip_while(|r| !r.starts_with(":cerchielli")).take_while(|r| !r.is_empty()) {
+        println!("    {}", riga);
+    }
+    let riletto = tesauro::Tesauro::da_skos(&ttl)?;
+    println!("  Riletto '{}' ({}): {} concetti", riletto.nome, riletto.uri_base, riletto.concetti().count());
+    for termine in ["cerchielli", "incisione"] {
+        if let Some(c) = riletto.risolvi(termine) {
+            println!("    {}: piu generici {:?}, correlati {:?}", c.etichetta, c.piu_generici, c.correlati);
+        }
+    }
+    Ok(())
+}
+
+fn dimostra_aat() -> Result<(), errori::ErroreInventario> {
+    use aat::Corrispondenza;
+
+    let conf = configurazione::Configurazione::da_ambiente();
+    let cache = std::env::temp_dir().join(format!("bronzeaxe_aat_{}.json", std::process::id()));
+    let cliente = aat::ClienteAat::nuovo(cache.clone(), conf.aat.clone())?;
+    // Corrispondenze gia verificate, come le lascerebbe una sessione precedente
+    for (termine, id, etichetta) in [
+        ("bronzo", "300010957", "bronze (metal)"),
+        ("ferro", "300011002", "iron (metal)"),
+        ("oro", "300011021", "gold (metal)"),
+        ("argento", "300011029", "silver (metal)"),
+    ] {
+        cliente.memorizza(termine, Some(Corrispondenza { id: id.to_string(), etichetta: etichetta.to_string() }))?;
+    }
+
+    let mut materiali = tesauro::materiali()?;
+    let collegati = cliente.collega(&mut materiali)?;
+    println!(
+        "  Concetti collegati ad AAT: {} su {} ({})",
+        collegati,
+        materiali.concetti().count(),
+        if conf.aat.is_some() { "cache + ricerca online" } else { "solo cache, BRONZEAXE_AAT non impostata" }
+    );
+    for c in materiali.concetti().filter(|c| c.aat.is_some()) {
+        println!("    {:<10} -> aat:{}", c.etichetta, c.aat.as_deref().unwrap_or_default());
+    }
+    if let Some(c) = cliente.cerca("Bronzo")? {
+        println!("  'Bronzo' in cache: {} ({})", c.id, c.etichetta);
+    }
+    let ttl = materiali.to_skos();
+    if let Some(riga) = ttl.lines().find(|r| r.contains("exactMatch")) {
+        println!("  SKOS: {}", riga.trim());
+    }
+    let riletto = tesauro::Tesauro::da_skos(&ttl)?;
+    println!("  Dopo la rilettura SKOS: {} concetti con AAT", riletto.concetti().filter(|c| c.aat.is_some()).count());
+    if let Err(e) = materiali.imposta_aat("osso", "aat:300011798") {
+        println!("  {}", e);
+    }
+
+    // La cache sopravvive al riavvio del client
+    let di_nuovo = aat::ClienteAat::nuovo(cache.clone(), None)?;
+    println!("  Cache riletta da {}: 'ferro' -> {:?}", di_nuovo.cache.display(), di_nuovo.cerca("ferro")?.map(|c| c.id));
+    std::fs::remove_file(&cache)?;
+    Ok(())
+}
+
+/// Metadati del dataset pubblico della collezione
+fn metadati_dataset(oggi: NaiveDate) -> deposito::MetadatiDataset {
+    deposito::MetadatiDataset {
+        titolo: "BronzeAxe: catalogo dei bronzi protostorici (dataset pubblico)".to_string(),
+        descrizione: "Schede pubblicate dei reperti in bronzo dell'Eta del Bronzo e della prima Eta del Ferro, \
+                      con materiali, periodi, tipologie e misure."
+            .to_string(),
+        autori: vec![
+            deposito::Autore {
+                nome: "Greco, Anna".to_string(),
+                affiliazione: Some("Soprintendenza ABAP Avellino e Salerno".to_string()),
+                orcid: None,
+            },
+            deposito::Autore { nome: "Esposito, Marco".to_string(), affiliazione: None, orcid: None },
+        ],
+        parole_chiave: vec!["archeologia".to_string(), "Eta del Bronzo".to_string(), "bronzi".to_string()],
+        licenza: Licenza::CcBy,
+        versione: "1.0".to_string(),
+        data_pubblicazione: oggi,
+        doi: std::env::var("BRONZEAXE_DOI").ok(),
+    }
+}
+
+fn dimostra_deposito(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    let oggi = chrono::Local::now().date_naive();
+    let cartella = std::env::temp_dir().join(format!("bronzeaxe_zenodo_{}", std::process::id()));
+    let pacchetto = deposito::prepara(inv, &metadati_dataset(oggi), cartella.clone(), oggi)?;
+    println!("  Pacchetto in {} (snapshot {}):", pacchetto.cartella.display(), &pacchetto.snapshot[..12]);
+    for file in &pacchetto.file {
+        let nome = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        println!("    {:<14} {:>6} bytes", nome, std::fs::metadata(file)?.len());
+    }
+    let m = &pacchetto.metadati["metadata"];
+    println!("  zenodo.json: {} / licenza {} / {} autori", m["upload_type"], m["license"], m["creators"].as_array().map_or(0, Vec::len));
+
+    let mut chiuso = metadati_dataset(oggi);
+    chiuso.licenza = Licenza::TuttiIDirittiRiservati;
+    if let Err(e) = deposito::metadati_zenodo(&chiuso) {
+        println!("  {}", e);
+    }
+
+    match configurazione::Configurazione::da_ambiente().zenodo {
+        // Nella demo solo la bozza: la pubblicazione registra un DOI permanente
+        Some(zenodo) => {
+            let bozza = zenodo.crea_bozza(&pacchetto)?;
+            println!("  Bozza Zenodo {} creata, DOI riservato {:?}", bozza.id, bozza.doi_riservato);
         }
+        None => println!("  BRONZEAXE_ZENODO_TOKEN non impostata: nessun caricamento"),
+    }
+    std::fs::remove_dir_all(&cartella)?;
+    Ok(())
+}
+
+/// Sottocomando `deposito`: prepara il pacchetto e, se richiesto, lo
+/// carica come bozza su Zenodo ed eventualmente lo pubblica
+fn comando_deposito(
+    cartella: PathBuf,
+    opzione: &cli::OpzioneArchivio,
+    bozza: bool,
+    pubblica: bool,
+) -> Result<(), errori::ErroreInventario> {
+    let conf = opzione.configurazione();
+    let archivio = conf.apri_archivio()?;
+    let inv = archivio.carica()?;
+    archivio.chiudi()?;
+
+    let oggi = chrono::Local::now().date_naive();
+    let pacchetto = deposito::prepara(&inv, &metadati_dataset(oggi), cartella, oggi)?;
+    println!("Pacchetto pronto in {} ({} file)", pacchetto.cartella.display(), pacchetto.file.len());
+
+    if !bozza && !pubblica {
+        return Ok(());
+    }
+    let zenodo = conf.zenodo.ok_or_else(|| {
+        errori::ErroreInventario::DatiNonValidi("serve BRONZEAXE_ZENODO_TOKEN per caricare su Zenodo".to_string())
+    })?;
+    let bozza = zenodo.crea_bozza(&pacchetto)?;
+    println!("Bozza {} creata: {}", bozza.id, bozza.pagina.as_deref().unwrap_or("-"));
+    if pubblica {
+        println!("Pubblicato con DOI {}", zenodo.pubblica(&bozza)?);
+    } else if let Some(doi) = &bozza.doi_riservato {
+        println!("DOI riservato (attivo dopo la pubblicazione): {}", doi);
+    }
+    Ok(())
+}
+
+/// Sottocomando `cita`: citazione del dataset o del reperto indicato
+fn comando_cita(
+    id: Option<u32>,
+    stile: citazioni::Stile,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let conf = opzione.configurazione();
+    let archivio = conf.apri_archivio()?;
+    let inv = archivio.carica()?;
+    archivio.chiudi()?;
+
+    let dataset = metadati_dataset(chrono::Local::now().date_naive());
+    println!("{}", citazioni::Citazioni::nuovo(&inv, &dataset).cita(id, stile)?);
+    Ok(())
+}
+
+/// Tesauri con le corrispondenze AAT gia verificate per i soggetti derivati
+fn tesauri_aat() -> Result<Vec<tesauro::Tesauro>, errori::ErroreInventario> {
+    let mut materiali = tesauro::materiali()?;
+    for (id, aat) in [("bronzo", "300010957"), ("ferro", "300011002"), ("oro", "300011021"), ("argento", "300011029")] {
+        materiali.imposta_aat(id, aat)?;
     }
+    Ok(vec![materiali, tesauro::tipologie()?])
+}
 
-    /// Coordinate geografiche
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct Coordinate {
-        pub latitudine: f64,
-        pub longitudine: f64,
+fn dimostra_ao_cat(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    let oggi = chrono::Local::now().date_naive();
+    let tesauri = tesauri_aat()?;
+    let riferimenti: Vec<&tesauro::Tesauro> = tesauri.iter().collect();
+    let ttl = ariadne::ao_cat(inv, &metadati_dataset(oggi), &riferimenti, oggi)?;
+    let risorse = ttl.matches("a aocat:AO_Individual_Data_Resource").count();
+    println!("  {} righe Turtle, 1 collezione e {} risorse", ttl.lines().count(), risorse);
+    if let Some(inizio) = ttl.find("a aocat:AO_Individual_Data_Resource") {
+        let scheda = &ttl[ttl[..inizio].rfind('<').unwrap_or(0)..];
+        for riga in scheda.lines().take_while(|r| !r.is_empty()).filter(|r| !r.contains("has_creator")) {
+            println!("  {}", riga);
+        }
     }
+    Ok(())
+}
 
-    impl fmt::Display for Coordinate {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "({:.4}, {:.4})", self.latitudine, self.longitudine)
+/// Sottocomando `aocat`: metadati AO-Cat dell'archivio sullo standard output
+fn comando_ao_cat(opzione: &cli::OpzioneArchivio) -> Result<(), errori::ErroreInventario> {
+    let conf = opzione.configurazione();
+    let archivio = conf.apri_archivio()?;
+    let inv = archivio.carica()?;
+    archivio.chiudi()?;
+
+    let oggi = chrono::Local::now().date_naive();
+    let tesauri = tesauri_aat()?;
+    let riferimenti: Vec<&tesauro::Tesauro> = tesauri.iter().collect();
+    print!("{}", ariadne::ao_cat(&inv, &metadati_dataset(oggi), &riferimenti, oggi)?);
+    Ok(())
+}
+
+fn dimostra_riferimenti(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use modelli::{Opera, RiferimentoBibliografico};
+
+    let conf = configurazione::Configurazione::da_ambiente();
+    let cache = std::env::temp_dir().join(format!("bronzeaxe_riferimenti_{}.json", std::process::id()));
+    let cliente = riferimenti::ClienteRiferimenti::nuovo(cache.clone(), conf.crossref, conf.orcid)?;
+    // Voci gia in cache, come le lascerebbe una sessione precedente
+    // (10.5555 e il prefisso di prova di Crossref, Josiah Carberry la persona di prova di ORCID)
+    cliente.memorizza_opera(
+        "10.5555/bronzeaxe.2019.07",
+        Some(Opera {
+            doi: "10.5555/bronzeaxe.2019.07".to_string(),
+            titolo: "Asce a margini rialzati dall'Irpinia".to_string(),
+            autori: vec!["Greco, Anna".to_string(), "Carberry, Josiah".to_string()],
+            anno: Some(2019),
+            contenitore: Some("Rivista di Scienze Preistoriche".to_string()),
+            volume: Some("69".to_string()),
+            pagine: Some("115-140".to_string()),
+            editore: None,
+        }),
+    )?;
+    cliente.memorizza_orcid("Josiah Carberry", Some("0000-0002-1825-0097".to_string()))?;
+    cliente.memorizza_orcid("Anna Greco", None)?;
+
+    let mut redazione = inventario::Inventario::nuovo();
+    let Some(primo) = inv.tutti().first().map(|r| (*r).clone()) else {
+        return Ok(());
+    };
+    let id = primo.id;
+    redazione.importa(primo)?;
+    redazione.aggiungi_bibliografia(
+        id,
+        RiferimentoBibliografico {
+            citazione: "Greco, Carberry 2019".to_string(),
+            doi: Some("https://doi.org/10.5555/BronzeAxe.2019.07".to_string()),
+            punto: Some("fig. 3, n. 2".to_string()),
+            opera: None,
+        },
+    )?;
+    redazione.aggiungi_bibliografia(
+        id,
+        RiferimentoBibliografico {
+            citazione: "Carancini 1984, n. 312".to_string(),
+            doi: None,
+            punto: Some("tav. 12".to_string()),
+            opera: None,
+        },
+    )?;
+    let completate = redazione.completa_bibliografia(|doi| cliente.risolvi_doi(doi))?;
+    println!("  Voci bibliografiche completate: {}", completate);
+    for voce in &redazione.cerca_per_id(id)?.bibliografia {
+        match &voce.opera {
+            Some(o) => println!(
+                "    {} -> {} ({}), {} {}, pp. {} [doi:{}]",
+                voce.citazione,
+                o.titolo,
+                o.autori.join("; "),
+                o.contenitore.as_deref().unwrap_or("-"),
+                o.volume.as_deref().unwrap_or(""),
+                o.pagine.as_deref().unwrap_or("-"),
+                o.doi
+            ),
+            None => println!("    {} (senza DOI, resta com'e)", voce.citazione),
         }
     }
 
-    /// Misurazioni del reperto
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct Misurazioni {
-        pub lunghezza_cm: Option<f64>,
-        pub larghezza_cm: Option<f64>,
-        pub altezza_cm: Option<f64>,
-        pub peso_grammi: Option<f64>,
+    redazione.registra_persona("Josiah Carberry", vec![modelli::RuoloPersona::Archeologo])?;
+    redazione.registra_persona("Anna Greco", vec![modelli::RuoloPersona::Archeologo])?;
+    let completate = redazione.completa_orcid(|p| cliente.cerca_orcid(p))?;
+    println!("  iD ORCID aggiunti: {}", completate);
+    for p in redazione.persone() {
+        println!("    {}: {}", p.nome, p.orcid.as_deref().unwrap_or("nessun iD univoco"));
     }
+    if let Err(e) = redazione.imposta_orcid(1, "0000-0002-1825-0098") {
+        println!("  {}", e);
+    }
+    std::fs::remove_file(&cache)?;
+    Ok(())
+}
 
-    impl Misurazioni {
-        pub fn nuove() -> Self {
-            Misurazioni {
-                lunghezza_cm: None,
-                larghezza_cm: None,
-                altezza_cm: None,
-                peso_grammi: None,
-            }
+/// Destinazione che conserva le richieste invece di inviarle
+struct RichiesteRegistrate(std::cell::RefCell<Vec<String>>);
+
+impl sparql::Destinazione for RichiesteRegistrate {
+    fn aggiorna(&self, richiesta: &str) -> Result<(), errori::ErroreInventario> {
+        self.0.borrow_mut().push(richiesta.to_string());
+        Ok(())
+    }
+}
+
+fn dimostra_sparql(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    let oggi = chrono::Local::now().date_naive();
+    let tesauri = tesauri_aat()?;
+    let riferimenti: Vec<&tesauro::Tesauro> = tesauri.iter().collect();
+    let statico = cidoc::turtle(inv, &riferimenti, oggi)?;
+    println!("  Esportazione statica: {} righe Turtle", statico.lines().count());
+
+    let cartella = std::env::temp_dir().join(format!("bronzeaxe_sparql_{}", std::process::id()));
+    let stato = cartella.join("sparql.stato");
+    let pubblicatore = replica::Pubblicatore { cartella: cartella.clone() };
+    let mut redazione = inventario::Inventario::nuovo();
+    for r in inv.tutti() {
+        redazione.importa(r.clone())?;
+    }
+    let endpoint = RichiesteRegistrate(std::cell::RefCell::new(Vec::new()));
+    let mostra = |esito: Option<sparql::Sincronizzazione>| match esito {
+        Some(s) => println!(
+            "  {} {}: {} grafi aggiornati, {} rimossi, {} richieste",
+            if s.completa { "Invio completo" } else { "Invio incrementale" },
+            &s.snapshot[..12],
+            s.aggiornati,
+            s.rimossi,
+            s.richieste
+        ),
+        None => println!("  Endpoint gia allineato"),
+    };
+
+    pubblicatore.pubblica(&redazione)?;
+    mostra(sparql::sincronizza(&cartella, &stato, &endpoint, &riferimenti, oggi)?);
+    mostra(sparql::sincronizza(&cartella, &stato, &endpoint, &riferimenti, oggi)?);
+    let pubblico = redazione.tutti().iter().find(|r| cidoc::pubblico(&redazione, r.id, oggi).is_some()).map(|r| r.id);
+    if let Some(id) = pubblico {
+        redazione.aggiungi_nota(id, "Misure ricontrollate")?;
+    }
+    redazione.rimuovi(2)?;
+    pubblicatore.pubblica(&redazione)?;
+    mostra(sparql::sincronizza(&cartella, &stato, &endpoint, &riferimenti, oggi)?);
+    if let Some(ultima) = endpoint.0.borrow().last() {
+        for riga in ultima.lines().filter(|r| !r.starts_with("PREFIX")).take(4) {
+            println!("    {}", riga);
+        }
+    }
+    std::fs::remove_dir_all(&cartella)?;
+    Ok(())
+}
+
+/// Sottocomando `sparql`: allinea l'endpoint configurato allo snapshot
+/// corrente di una cartella di pubblicazione
+fn comando_sparql(cartella: PathBuf, stato: Option<PathBuf>) -> Result<(), errori::ErroreInventario> {
+    let stato = stato.unwrap_or_else(|| cartella.join("sparql.stato"));
+    let cliente = configurazione::Configurazione::da_ambiente().sparql.ok_or_else(|| {
+        errori::ErroreInventario::DatiNonValidi("serve BRONZEAXE_SPARQL con l'indirizzo dell'endpoint".to_string())
+    })?;
+    let tesauri = tesauri_aat()?;
+    let riferimenti: Vec<&tesauro::Tesauro> = tesauri.iter().collect();
+    match sparql::sincronizza(&cartella, &stato, &cliente, &riferimenti, chrono::Local::now().date_naive())? {
+        Some(s) => println!(
+            "Endpoint allineato a {} ({}): {} reperti aggiornati, {} rimossi",
+            s.snapshot,
+            if s.completa { "invio completo" } else { "incrementale" },
+            s.aggiornati,
+            s.rimossi
+        ),
+        None => println!("Endpoint gia allineato"),
+    }
+    Ok(())
+}
+
+/// Sottocomando `campi`: valuta le formule sui reperti dell'archivio
+fn comando_campi(
+    definizioni: Option<PathBuf>,
+    dove: &[String],
+    csv: bool,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let conf = opzione.configurazione();
+    let Some(file) = definizioni.or(conf.campi_calcolati.clone()) else {
+        return Err(errori::ErroreInventario::DatiNonValidi(
+            "indicare il file delle formule con --definizioni o BRONZEAXE_CAMPI_CALCOLATI".to_string(),
+        ));
+    };
+    let campi = calcolati::CampiCalcolati::da_testo(&std::fs::read_to_string(&file)?)?;
+    let condizioni = dove.iter().map(|c| ricerca::Condizione::da_testo(c)).collect::<Result<Vec<_>, _>>()?;
+
+    let a = conf.apri_archivio()?;
+    let inv = a.carica()?;
+    a.chiudi()?;
+    let reperti = ricerca::filtra(inv.tutti(), &campi, &condizioni)?;
+    if csv {
+        print!("{}", campi.csv(&reperti));
+    } else {
+        println!("{} reperti su {}", reperti.len(), inv.totale());
+        for nome in campi.nomi() {
+            println!("  {}", campi.riepilogo(&reperti, nome)?);
         }
+    }
+    Ok(())
+}
 
-        pub fn con_dimensioni(mut self, l: f64, w: f64, h: f64) -> Self {
-            self.lunghezza_cm = Some(l);
-            self.larghezza_cm = Some(w);
-            self.altezza_cm = Some(h);
-            self
+/// Sottocomando `ocr`: le bozze si mostrano con i loro dubbi; con
+/// `--salva` entrano nell'archivio, dove restano in ingresso finche
+/// qualcuno non le confronta con le schede originali
+fn comando_ocr(
+    testo: &std::path::Path,
+    regole: Option<PathBuf>,
+    sito: &str,
+    salva: bool,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let regole = match regole {
+        Some(file) => ocr::RegoleOcr::da_testo(&std::fs::read_to_string(file)?)?,
+        None => ocr::RegoleOcr::predefinite(),
+    };
+    let bozze = regole.leggi_tutte(&std::fs::read_to_string(testo)?, sito);
+    for (i, bozza) in bozze.iter().enumerate() {
+        let r = &bozza.reperto;
+        println!("Scheda {}: {} (inv. {}, {})", i + 1, r.nome, r.identificativo.as_deref().unwrap_or("?"), r.sito);
+        for dubbio in &bozza.dubbi {
+            println!("  da verificare: {}", dubbio);
         }
+    }
+    if !salva {
+        eprintln!("{} bozze lette; con --salva entrano nell'archivio", bozze.len());
+        return Ok(());
+    }
 
-        pub fn con_peso(mut self, p: f64) -> Self {
-            self.peso_grammi = Some(p);
-            self
+    let conf = opzione.configurazione();
+    let a = conf.apri_archivio()?;
+    let mut inv = a.carica()?;
+    let mut aggiunte = 0;
+    for (i, bozza) in bozze.into_iter().enumerate() {
+        // Una scheda gia importata ha lo stesso inventario: si salta
+        match inv.aggiungi(bozza.reperto) {
+            Ok(_) => aggiunte += 1,
+            Err(e) => eprintln!("Scheda {}: {}", i + 1, e),
         }
+    }
+    a.salva(&inv)?;
+    a.chiudi()?;
+    eprintln!("{} bozze aggiunte a {}, in ingresso", aggiunte, conf.archivio);
+    Ok(())
+}
 
-        pub fn volume_approssimativo(&self) -> Option<f64> {
-            match (self.lunghezza_cm, self.larghezza_cm, self.altezza_cm) {
-                (Some(l), Some(w), Some(h)) => Some(l * w * h),
-                _ => None,
+/// Sottocomando `dettatura`: le righe arrivano dal programma di
+/// trascrizione, una per reperto; chi detta puo dire "annulla" per
+/// togliere la bozza precedente. I siti gia in archivio servono a
+/// completare i nomi detti a meta
+fn comando_dettatura(
+    trascrizione: Option<&std::path::Path>,
+    sito: &str,
+    salva: bool,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let testo = match trascrizione {
+        Some(file) => std::fs::read_to_string(file)?,
+        None => std::io::read_to_string(std::io::stdin())?,
+    };
+    let conf = opzione.configurazione();
+    let a = conf.apri_archivio()?;
+    // Senza --salva l'archivio serve solo per i siti: se manca, va bene lo stesso
+    let mut inv = if salva { a.carica()? } else { a.carica().unwrap_or_else(|_| inventario::Inventario::nuovo()) };
+    let siti: std::collections::BTreeSet<String> = inv.tutti().iter().map(|r| r.sito.clone()).collect();
+    let lettore = dettatura::Dettatura::nuova(sito).con_siti(siti);
+
+    let mut dettati: Vec<dettatura::Dettato> = Vec::new();
+    for riga in testo.lines().map(str::trim).filter(|r| !r.is_empty()) {
+        if riga.trim_end_matches('.').eq_ignore_ascii_case("annulla") {
+            if let Some(tolto) = dettati.pop() {
+                println!("Annullato: {}", tolto.reperto.nome);
             }
+            continue;
+        }
+        dettati.push(lettore.leggi(riga));
+    }
+    for (i, dettato) in dettati.iter().enumerate() {
+        let r = &dettato.reperto;
+        println!("Reperto {}: {} ({}, {})", i + 1, r.nome, r.materiale().map_or("?".to_string(), |m| m.to_string()), r.sito);
+        for dubbio in &dettato.dubbi {
+            println!("  da verificare: {}", dubbio);
+        }
+    }
+    if !salva {
+        eprintln!("{} bozze dettate; con --salva entrano nell'archivio", dettati.len());
+        return Ok(());
+    }
+
+    let mut aggiunte = 0;
+    for (i, dettato) in dettati.into_iter().enumerate() {
+        match inv.aggiungi(dettato.reperto) {
+            Ok(_) => aggiunte += 1,
+            Err(e) => eprintln!("Reperto {}: {}", i + 1, e),
+        }
+    }
+    a.salva(&inv)?;
+    a.chiudi()?;
+    eprintln!("{} bozze aggiunte a {}, in ingresso", aggiunte, conf.archivio);
+    Ok(())
+}
+
+/// Sottocomando `etichette`: un'etichetta per collocazione, con il
+/// riassunto del contenuto e il QR, sui fogli del modello scelto
+fn comando_etichette(
+    modello: &str,
+    collocazione: Option<&str>,
+    salta: usize,
+    output: &std::path::Path,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let Some(modello) = etichette::Modello::per_nome(modello) else {
+        let nomi: Vec<&str> = etichette::Modello::predefiniti().iter().map(|m| m.nome).collect();
+        return Err(errori::ErroreInventario::DatiNonValidi(format!(
+            "modello di etichette sconosciuto: {} (disponibili: {})",
+            modello,
+            nomi.join(", ")
+        )));
+    };
+    let conf = opzione.configurazione();
+    let a = conf.apri_archivio()?;
+    let inv = a.carica()?;
+    let reperti = inv.tutti();
+    let scelti = reperti.iter().copied().filter(|r| {
+        collocazione.is_none_or(|inizio| r.collocazione.as_deref().is_some_and(|c| c.starts_with(inizio)))
+    });
+    let etichette = etichette::etichette(scelti);
+    if etichette.is_empty() {
+        eprintln!("Nessun reperto con una collocazione: niente da stampare");
+        return Ok(());
+    }
+    std::fs::write(output, etichette::pdf(&etichette, &modello, salta))?;
+    let fogli = (salta + etichette.len()).div_ceil(modello.per_foglio());
+    eprintln!("{} etichette su {} fogli {} in {}", etichette.len(), fogli, modello.nome, output.display());
+    Ok(())
+}
+
+/// Sottocomando `trasporto`: i reperti si scelgono per id, con le stesse
+/// condizioni di `campi` o per cassa; almeno uno dei tre criteri serve,
+/// per non stampare per sbaglio tutto il deposito
+fn comando_trasporto(
+    id: &[u32],
+    dove: &[String],
+    collocazione: Option<&str>,
+    output: Option<&std::path::Path>,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    if id.is_empty() && dove.is_empty() && collocazione.is_none() {
+        return Err(errori::ErroreInventario::DatiNonValidi(
+            "indicare i reperti da spedire con --id, --dove o --collocazione".to_string(),
+        ));
+    }
+    let conf = opzione.configurazione();
+    let campi = match &conf.campi_calcolati {
+        Some(file) => calcolati::CampiCalcolati::da_testo(&std::fs::read_to_string(file)?)?,
+        None => calcolati::CampiCalcolati::default(),
+    };
+    let condizioni = dove.iter().map(|c| ricerca::Condizione::da_testo(c)).collect::<Result<Vec<_>, _>>()?;
+
+    let a = conf.apri_archivio()?;
+    let inv = a.carica()?;
+    a.chiudi()?;
+    for i in id {
+        inv.cerca_per_id(*i)?;
+    }
+    let scelti = inv.tutti().into_iter().filter(|r| {
+        (id.is_empty() || id.contains(&r.id))
+            && collocazione.is_none_or(|inizio| r.collocazione.as_deref().is_some_and(|c| c.starts_with(inizio)))
+    });
+    let distinta = trasporto::DistintaTrasporto::nuova(ricerca::filtra(scelti, &campi, &condizioni)?);
+    match output {
+        Some(file) => {
+            std::fs::write(file, distinta.to_string())?;
+            eprintln!("Distinta di {} reperti in {} casse scritta in {}", distinta.reperti(), distinta.casse.len(), file.display());
         }
+        None => print!("{}", distinta),
     }
+    Ok(())
+}
+
+/// Sottocomando `dossier`: il Markdown va sullo standard output, per
+/// passarlo a pandoc o incollarlo in un verbale; il PDF in un file
+fn comando_dossier(
+    sito: &str,
+    pdf: bool,
+    output: Option<PathBuf>,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let conf = opzione.configurazione();
+    let a = conf.apri_archivio()?;
+    let inv = a.carica()?;
+    a.chiudi()?;
+    let dossier = inv.esporta_dossier(sito)?;
+    let output = match output {
+        None if pdf => Some(PathBuf::from(format!("dossier_{}.pdf", sito.replace(' ', "_")))),
+        output => output,
+    };
+    let contenuto = if pdf { dossier.pdf() } else { dossier.markdown().into_bytes() };
+    match output {
+        Some(file) => {
+            std::fs::write(&file, contenuto)?;
+            eprintln!("Dossier di {} ({} reperti) scritto in {}", sito, dossier.reperti.len(), file.display());
+        }
+        None => print!("{}", String::from_utf8_lossy(&contenuto)),
+    }
+    Ok(())
+}
 
-    impl fmt::Display for Misurazioni {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            let mut parti = Vec::new();
-            if let Some(l) = self.lunghezza_cm {
-                parti.push(format!("L:{:.1}cm", l));
+/// Sottocomando `media`: `verify` elenca gli allegati da controllare ed
+/// esce con errore se ce ne sono, cosi si puo pianificare con cron;
+/// `relocate` riscrive i percorsi dopo uno spostamento e `miniature`
+/// rifa le miniature delle foto, e tutti e due salvano
+fn comando_media(azione: cli::AzioneMedia) -> Result<(), errori::ErroreInventario> {
+    match azione {
+        cli::AzioneMedia::Verify { archivio: opzione } => {
+            let a = opzione.configurazione().apri_archivio()?;
+            let inv = a.carica()?;
+            a.chiudi()?;
+            let totale: usize = inv.tutti().iter().map(|r| r.allegati.len()).sum();
+            let problemi = media::verifica(&inv);
+            for esito in &problemi {
+                println!("#{} {}: {}", esito.id_reperto, esito.percorso.display(), esito.problema);
             }
-            if let Some(w) = self.larghezza_cm {
-                parti.push(format!("W:{:.1}cm", w));
+            if !problemi.is_empty() {
+                return Err(errori::ErroreInventario::DatiNonValidi(format!(
+                    "{} allegati su {} da controllare",
+                    problemi.len(),
+                    totale
+                )));
             }
-            if let Some(h) = self.altezza_cm {
-                parti.push(format!("H:{:.1}cm", h));
+            eprintln!("{} allegati integri", totale);
+        }
+        cli::AzioneMedia::Relocate { da, a: verso, archivio: opzione } => {
+            let conf = opzione.configurazione();
+            let a = conf.apri_archivio()?;
+            let mut inv = a.carica()?;
+            let aggiornati = media::riloca(&mut inv, &da, &verso);
+            if aggiornati > 0 {
+                a.salva(&inv)?;
             }
-            if let Some(p) = self.peso_grammi {
-                parti.push(format!("{:.0}g", p));
+            a.chiudi()?;
+            eprintln!("{} allegati rilocati da {} a {}", aggiornati, da.display(), verso.display());
+            let problemi = media::verifica(&inv).len();
+            if problemi > 0 {
+                eprintln!("{} allegati ancora da controllare: vedi `media verify`", problemi);
             }
-            if parti.is_empty() {
-                write!(f, "N/D")
-            } else {
-                write!(f, "{}", parti.join(", "))
+        }
+        cli::AzioneMedia::Miniature { archivio: opzione } => {
+            let a = opzione.configurazione().apri_archivio()?;
+            let mut inv = a.carica()?;
+            let (elaborate, errori) = media::rigenera_miniature(&mut inv);
+            if elaborate > 0 {
+                a.salva(&inv)?;
+            }
+            a.chiudi()?;
+            for (percorso, errore) in &errori {
+                println!("{}: {}", percorso.display(), descrivi(errore));
+            }
+            eprintln!("Miniature rigenerate per {} foto", elaborate);
+            if !errori.is_empty() {
+                return Err(errori::ErroreInventario::DatiNonValidi(format!("{} foto senza miniature", errori.len())));
             }
         }
     }
+    Ok(())
+}
 
-    /// Reperto archeologico - la struct principale
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct Reperto {
-        pub id: u32,
-        pub nome: String,
-        pub descrizione: String,
-        pub materiale: Materiale,
-        pub periodo: Periodo,
-        pub conservazione: Conservazione,
-        pub sito: String,
-        pub coordinate: Option<Coordinate>,
-        pub misurazioni: Misurazioni,
-        pub note: Vec<String>,
+/// Sottocomando `digest`: da pianificare con cron, una volta al giorno o
+/// alla settimana secondo la frequenza scelta
+fn comando_riepilogo(
+    frequenza: Option<notifiche::Frequenza>,
+    opzione: &cli::OpzioneArchivio,
+) -> Result<(), errori::ErroreInventario> {
+    let mut conf = opzione.configurazione();
+    if let Some(f) = frequenza {
+        conf.riepilogo = f;
+    }
+    let a = conf.apri_archivio()?;
+    let inv = a.carica()?;
+    a.chiudi()?;
+    let mut riepilogo =
+        notifiche::Riepilogo::componi(&inv, inv.prestiti(), chrono::Local::now().date_naive(), conf.riepilogo);
+    // Il backup si controlla solo per gli archivi su file singolo
+    if !conf.archivio.contains(':') && !conf.archivio.ends_with('/') {
+        let dati = archivio::FileJson::nuovo(PathBuf::from(&conf.archivio), conf.passphrase.clone());
+        riepilogo = riepilogo.con_backup(&dati);
     }
+    println!("{}\n\n{}", riepilogo.oggetto(), riepilogo.testo());
+    if let Some(smtp) = &conf.smtp {
+        if riepilogo.invia(smtp)? {
+            eprintln!("Riepilogo inviato a {}", smtp.destinatari.join(", "));
+        }
+    }
+    Ok(())
+}
 
-    impl fmt::Display for Reperto {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(
-                f,
-                "#{} {} ({}, {}, {})",
-                self.id, self.nome, self.materiale, self.periodo, self.conservazione
-            )
+/// Sottocomandi `db migrate`, `db status` e `db rollback`
+fn comando_db(azione: cli::AzioneDb, file: &std::path::Path) -> Result<(), errori::ErroreInventario> {
+    let mut conn = rusqlite::Connection::open(file)?;
+    match azione {
+        cli::AzioneDb::Migrate => {
+            let applicate = migrazione::migra(&mut conn)?;
+            if applicate.is_empty() {
+                println!("Schema gia aggiornato");
+            }
+            for v in applicate {
+                println!("Applicata migrazione {}", v);
+            }
+        }
+        cli::AzioneDb::Status => {
+            for s in migrazione::stato(&conn)? {
+                match s.applicata_il {
+                    Some(data) => println!("  {:>3} {:<20} applicata il {}", s.versione, s.nome, data),
+                    None => println!("  {:>3} {:<20} da applicare", s.versione, s.nome),
+                }
+            }
         }
+        cli::AzioneDb::Rollback => match migrazione::annulla(&mut conn)? {
+            Some(v) => println!("Annullata migrazione {}", v),
+            None => println!("Nessuna migrazione da annullare"),
+        },
     }
+    Ok(())
 }
 
-// ============================================================================
-// MODULO: ERRORI
-// ============================================================================
-mod errori {
-    use super::*;
-
-    #[derive(Debug)]
-    pub enum ErroreInventario {
-        RepertoNonTrovato(u32),
-        NomeVuoto,
-        IdDuplicato(u32),
-        DatiNonValidi(String),
-        SerializzazioneErrore(String),
-    }
-
-    impl fmt::Display for ErroreInventario {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                ErroreInventario::RepertoNonTrovato(id) => {
-                    write!(f, "Reperto con ID {} non trovato", id)
+/// Ciclo di vita dello schema su un database temporaneo
+fn dimostra_database(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use archivio::Archivio;
+
+    let percorso = std::env::temp_dir().join("bronzeaxe_inventario.db");
+    if percorso.exists() {
+        std::fs::remove_file(&percorso)?;
+    }
+    let stampa_stato = |conn: &rusqlite::Connection| -> Result<(), errori::ErroreInventario> {
+        for s in migrazione::stato(conn)? {
+            println!("    {} {:<18} {}", s.versione, s.nome, if s.applicata_il.is_some() { "applicata" } else { "da applicare" });
+        }
+        Ok(())
+    };
+
+    let mut conn = rusqlite::Connection::open(&percorso)?;
+    let db = migrazione::ArchivioSqlite::nuovo(percorso.clone());
+    if let Err(e) = db.salva(inv) {
+        println!("  Prima di migrare: {}", e);
+    }
+    println!("  Migrazioni applicate: {:?}", migrazione::migra(&mut conn)?);
+    stampa_stato(&conn)?;
+
+    db.salva(inv)?;
+    let ricaricato = db.carica()?;
+    println!(
+        "  Salvati e riletti {} reperti, impronta {}",
+        ricaricato.totale(),
+        if ricaricato.impronta_snapshot()? == inv.impronta_snapshot()? { "identica" } else { "DIVERSA" }
+    );
+
+    println!("  Rollback: {:?}", migrazione::annulla(&mut conn)?);
+    stampa_stato(&conn)?;
+    if let Err(e) = db.carica() {
+        println!("  Dopo il rollback: {}", e);
+    }
+    println!("  Migrazioni applicate: {:?}", migrazione::migra(&mut conn)?);
+
+    // Un database toccato da una versione futura o con migrazioni alterate viene rifiutato
+    conn.execute("UPDATE schema_migrazioni SET checksum = 'alterato' WHERE versione = 1", [])?;
+    if let Err(e) = migrazione::migra(&mut conn) {
+        println!("  Checksum alterato: {}", e);
+    }
+    conn.execute(
+        "UPDATE schema_migrazioni SET checksum = ?1 WHERE versione = 1",
+        [migrazione::MIGRAZIONI[0].checksum()],
+    )?;
+    conn.execute(
+        "INSERT INTO schema_migrazioni VALUES (99, 'futura', '-', '2030-01-01 00:00:00')",
+        [],
+    )?;
+    if let Err(e) = db.carica() {
+        println!("  Versione sconosciuta: {}", e);
+    }
+    Ok(())
+}
+
+/// Salva e ricarica l'inventario con entrambi gli archivi e confronta le impronte
+fn dimostra_archivio(inv: &inventario::Inventario) -> Result<(), errori::ErroreInventario> {
+    use archivio::Archivio;
+
+    let originale = inv.impronta_snapshot()?;
+    let base = std::env::temp_dir().join("bronzeaxe_archivio");
+    let archivi: Vec<(&str, Box<dyn Archivio>)> = vec![
+        ("file unico", Box::new(archivio::FileJson::nuovo(std::env::temp_dir().join("bronzeaxe_inventario.json"), None))),
+        (
+            "file cifrato",
+            Box::new(archivio::FileJson::nuovo(
+                std::env::temp_dir().join("bronzeaxe_inventario.cifrato"),
+                Some("scavo-2024 notebook di campo".to_string()),
+            )),
+        ),
+        ("un file per reperto", Box::new(archivio::CartellaReperti::nuovo(&base))),
+    ];
+    for (nome, archivio) in &archivi {
+        archivio.salva(inv)?;
+        let ricaricato = archivio.carica()?;
+        println!(
+            "  {:<20} {} reperti, impronta {}",
+            nome,
+            ricaricato.tutti().len(),
+            if ricaricato.impronta_snapshot()? == originale { "identica" } else { "DIVERSA" }
+        );
+    }
+
+    // Sul disco non compaiono ne coordinate ne stime in chiaro
+    let cifrato = std::fs::read(std::env::temp_dir().join("bronzeaxe_inventario.cifrato"))?;
+    println!(
+        "  File cifrato: {} byte, intestazione riconosciuta: {}, contiene 'latitudine': {}",
+        cifrato.len(),
+        archivio::e_cifrato(&cifrato),
+        cifrato.windows(10).any(|w| w == b"latitudine")
+    );
+    let sbagliata = archivio::FileJson::nuovo(
+        std::env::temp_dir().join("bronzeaxe_inventario.cifrato"),
+        Some("passphrase sbagliata".to_string()),
+    );
+    if let Err(e) = sbagliata.carica() {
+        println!("  Con passphrase errata: {}", e);
+    }
+    // Un byte alterato nel file dati viene rilevato prima del parsing JSON
+    let dati = archivio::FileJson::nuovo(std::env::temp_dir().join("bronzeaxe_inventario.json"), None);
+    dati.salva(inv)?;
+    let mut danneggiato = std::fs::read(&dati.percorso)?;
+    danneggiato[100] ^= 0x20;
+    std::fs::write(&dati.percorso, &danneggiato)?;
+    match dati.carica() {
+        Err(e @ errori::ErroreInventario::FileCorrotto { backup: Some(_), .. }) => {
+            println!("  {}", e);
+            let ripristinato = dati.ripristina_backup()?;
+            println!("  Ripristinato dal backup: {} reperti", ripristinato.tutti().len());
+        }
+        Err(e) => println!("  Errore: {}", e),
+        Ok(_) => println!("  Alterazione non rilevata!"),
+    }
+
+    // File troncato con un record non valido: si recupera il resto
+    let mut rovinato = String::from_utf8_lossy(&serde_json::to_vec_pretty(&inv.tutti())?).into_owned();
+    rovinato = rovinato.replacen("\"periodo\": \"BronzoRecente\"", "\"periodo\": \"BronzoRecentissimo\"", 1);
+    rovinato.truncate(rovinato.len() * 9 / 10);
+    let parziale = archivio::FileJson::nuovo(std::env::temp_dir().join("bronzeaxe_troncato.json"), None);
+    std::fs::write(&parziale.percorso, rovinato)?;
+    let rapporto = parziale.recupera()?;
+    println!(
+        "  Recupero: {} reperti letti, {} scartati, troncato: {}, impronta: {}",
+        rapporto.inventario.tutti().len(),
+        rapporto.scartati.len(),
+        rapporto.troncato,
+        match rapporto.impronta_valida {
+            Some(true) => "valida",
+            Some(false) => "non corrispondente",
+            None => "assente",
+        }
+    );
+    for s in &rapporto.scartati {
+        println!("    record {} (byte {}, ID {:?}): {}", s.posizione, s.offset, s.id, s.motivo);
+    }
+    if let Some(q) = &rapporto.quarantena {
+        println!("    quarantena: {}", q.display());
+    }
+
+    let mut alterato = archivio::cifra(b"[]", "prova")?;
+    if let Some(ultimo) = alterato.last_mut() {
+        *ultimo ^= 1;
+    }
+    if let Err(e) = archivio::decifra(&alterato, "prova") {
+        println!("  Con un byte alterato: {}", e);
+    }
+
+    // Due istanze sullo stesso file: la seconda non puo sovrascrivere la prima
+    let percorso = std::env::temp_dir().join("bronzeaxe_inventario.json");
+    let file_dati = move || archivio::FileJson::nuovo(percorso.clone(), None);
+    let prima = archivio::Sessione::apri(file_dati(), archivio::SeOccupato::Attendi)?;
+    let seconda = archivio::Sessione::apri(file_dati(), archivio::SeOccupato::SolaLettura)?;
+    println!("  Prima istanza in sola lettura: {}", prima.sola_lettura());
+    println!("  Seconda istanza in sola lettura: {}", seconda.sola_lettura());
+    println!("  Lettura dalla seconda: {} reperti", seconda.carica()?.tutti().len());
+    if let Err(e) = seconda.salva(inv) {
+        println!("  Salvataggio dalla seconda: {}", e);
+    }
+    let in_attesa = std::thread::spawn(move || {
+        let inizio = std::time::Instant::now();
+        archivio::Sessione::apri(file_dati(), archivio::SeOccupato::Attendi)
+            .map(|s| (s.sola_lettura(), inizio.elapsed()))
+    });
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    prima.salva(inv)?;
+    drop(prima);
+    if let Ok(Ok((sola_lettura, attesa))) = in_attesa.join() {
+        println!(
+            "  Terza istanza in attesa: blocco ottenuto dopo ~{} ms, sola lettura: {}",
+            attesa.as_millis() / 100 * 100,
+            sola_lettura
+        );
+    }
+
+    // Un processo esterno riscrive il file: l'osservatore ricarica e avvisa
+    let osservato = std::env::temp_dir().join("bronzeaxe_osservato.json");
+    let scrittore = archivio::FileJson::nuovo(osservato.clone(), None);
+    scrittore.salva(inv)?;
+    let osservatore = archivio::osserva(
+        archivio::FileJson::nuovo(osservato.clone(), None),
+        std::time::Duration::from_millis(50),
+    )?;
+    let mut ridotto = scrittore.carica()?;
+    ridotto.rimuovi(1)?;
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    scrittore.salva(&ridotto)?;
+    let attesa = std::time::Duration::from_secs(2);
+    if let Ok(evento) = osservatore.eventi().recv_timeout(attesa) {
+        println!("  Osservatore: {:?}", evento);
+    }
+    std::fs::write(&osservato, b"[{\"id\": 1, ")?;
+    if let Ok(archivio::EventoArchivio::RicaricaFallita(e)) = osservatore.eventi().recv_timeout(attesa) {
+        println!("  Osservatore: ricarica fallita ({}), in uso ancora {} reperti", descrivi(&e), osservatore.inventario().read().map_or(0, |i| i.totale()));
+    }
+
+    let cartella = archivio::CartellaReperti::nuovo(&base);
+    let indice = cartella.indice()?;
+    println!("  Indice: {} voci in {}", indice.len(), base.join("reperti").display());
+    for voce in indice.iter().take(3) {
+        println!("    {} -> #{} {}", voce.file, voce.id, voce.nome);
+    }
+
+    // Un ID ripetuto nell'indice viene rifiutato al caricamento
+    let mut doppio = inventario::Inventario::nuovo();
+    let primo = inv.cerca_per_id(indice[0].id)?.clone();
+    doppio.importa(primo.clone())?;
+    if let Err(e) = doppio.importa(primo) {
+        println!("  Reperto ripetuto: {}", e);
+    }
+    Ok(())
+}
+
+/// Esegue un sottocomando; gli errori li stampa `main` in un unico punto
+fn esegui(comando: cli::Comando) -> Result<(), errori::ErroreInventario> {
+    match comando {
+        cli::Comando::Serve { data_dir, ascolta, archivio: opzione, watch } => {
+            let mut conf = configurazione::Configurazione::da_ambiente();
+            if let Some(dir) = data_dir {
+                conf = conf.con_data_dir(dir);
+            }
+            if let Some(a) = ascolta {
+                conf.ascolta = a;
+            }
+            if let Some(a) = opzione.archivio {
+                conf.archivio = a;
+            }
+            let arresto = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            std::fs::create_dir_all(&conf.data_dir)?;
+            for segnale in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+                signal_hook::flag::register(segnale, std::sync::Arc::clone(&arresto))?;
+            }
+            let a: std::sync::Arc<dyn archivio::Archivio> = conf.apri_archivio()?.into();
+            let (servizio, rotte) = if watch {
+                if conf.archivio.ends_with('/') || conf.archivio.contains("://") || conf.archivio.starts_with("sqlite:") {
+                    return Err(errori::ErroreInventario::DatiNonValidi("--watch segue solo un archivio su file JSON".to_string()));
+                }
+                // L'osservatore aggiorna lo stesso inventario che l'API legge
+                let file = archivio::FileJson::nuovo(PathBuf::from(&conf.archivio), conf.passphrase.clone());
+                let osservatore = archivio::osserva(file, std::time::Duration::from_millis(500))?;
+                let servizio = servizio::Servizio::nuovo(osservatore.inventario());
+                std::thread::spawn(move || osservatore.eventi().iter().for_each(stampa_evento));
+                let rotte = servizio.lettura();
+                (servizio, rotte)
+            } else {
+                let servizio = servizio::Servizio::apri(a.as_ref())?;
+                let rotte = servizio.scrittura();
+                (servizio, rotte)
+            };
+            let ascolto = std::net::TcpListener::bind(&conf.ascolta)?;
+            println!("In ascolto su http://{} (archivio {})", conf.ascolta, conf.archivio);
+            salute::servi(&ascolto, salute::rotte(a.clone()).merge(rotte), arresto)?;
+            // SIGTERM: le modifiche ricevute via API si salvano prima di uscire
+            match servizio.chiudi(a.as_ref())? {
+                true => println!("Arresto richiesto: modifiche salvate, archivio chiuso"),
+                false => println!("Arresto richiesto: archivio chiuso"),
+            }
+        }
+        cli::Comando::Ping { url } => {
+            let r = salute::ping(&url)?;
+            println!("{} {} ({} ms)", r.codice, r.corpo, r.tempo.as_millis());
+            if r.codice != 200 {
+                std::process::exit(1);
+            }
+        }
+        cli::Comando::Db { azione, file } => comando_db(azione, &file)?,
+        cli::Comando::Deposito { cartella, archivio, bozza, pubblica } => {
+            comando_deposito(cartella, &archivio, bozza, pubblica)?
+        }
+        cli::Comando::Cita { id, stile, archivio } => comando_cita(id, stile, &archivio)?,
+        cli::Comando::Aocat { archivio } => comando_ao_cat(&archivio)?,
+        cli::Comando::Sparql { cartella, stato } => comando_sparql(cartella, stato)?,
+        cli::Comando::Digest { frequenza, archivio } => comando_riepilogo(frequenza, &archivio)?,
+        cli::Comando::Seed { count, seme, ordine, strategia, output } => {
+            let mut inv = Inventario::nuovo_con_strategia(strategia);
+            for reperto in generatore::Generatore::nuovo(seme).genera(count, &generatore::siti_predefiniti()) {
+                if let Err(e) = inv.aggiungi(reperto) {
+                    eprintln!("Errore: {}", descrivi(&e));
                 }
-                ErroreInventario::NomeVuoto => write!(f, "Il nome del reperto non puo essere vuoto"),
-                ErroreInventario::IdDuplicato(id) => {
-                    write!(f, "Esiste gia un reperto con ID {}", id)
+            }
+            let json = inv.to_json_ordinato(ordine)?;
+            match output {
+                Some(file) => {
+                    std::fs::write(&file, json)?;
+                    eprintln!("Generati {} reperti in {}", inv.totale(), file.display());
+                }
+                None => println!("{}", json),
+            }
+        }
+        cli::Comando::Campi { definizioni, dove, csv, archivio } => comando_campi(definizioni, &dove, csv, &archivio)?,
+        cli::Comando::Ocr { testo, regole, sito, salva, archivio } => comando_ocr(&testo, regole, &sito, salva, &archivio)?,
+        cli::Comando::Dettatura { trascrizione, sito, salva, archivio } => {
+            comando_dettatura(trascrizione.as_deref(), &sito, salva, &archivio)?
+        }
+        cli::Comando::Etichette { modello, collocazione, salta, output, archivio } => {
+            comando_etichette(&modello, collocazione.as_deref(), salta, &output, &archivio)?
+        }
+        cli::Comando::Trasporto { id, dove, collocazione, output, archivio } => {
+            comando_trasporto(&id, &dove, collocazione.as_deref(), output.as_deref(), &archivio)?
+        }
+        cli::Comando::Dossier { sito, pdf, output, archivio } => comando_dossier(&sito, pdf, output, &archivio)?,
+        cli::Comando::Media { azione } => comando_media(azione)?,
+        cli::Comando::Triage { n, archivio } => {
+            let a = archivio.configurazione().apri_archivio()?;
+            let inv = a.carica()?;
+            a.chiudi()?;
+            let oggi = chrono::Local::now().date_naive();
+            println!("Priorita di restauro al {}:", oggi.format("%d/%m/%Y"));
+            statistiche::stampa_priorita(&statistiche::priorita_restauro(&inv.tutti(), oggi, n));
+        }
+    }
+    Ok(())
+}
+
+/// --mirror: le rotte GET sull'inventario dello specchio, che un thread
+/// aggiorna ogni secondo dalla cartella di pubblicazione
+fn servi_specchio(cartella: PathBuf) -> Result<(), errori::ErroreInventario> {
+    let conf = configurazione::Configurazione::da_ambiente();
+    let specchio = std::sync::Arc::new(replica::Specchio::apri(cartella.clone())?);
+    let arresto = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    for segnale in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+        signal_hook::flag::register(segnale, std::sync::Arc::clone(&arresto))?;
+    }
+    let servizio = servizio::Servizio::nuovo(specchio.inventario());
+    let app = salute::rotte(specchio.clone()).merge(servizio.lettura());
+    let ascolto = std::net::TcpListener::bind(&conf.ascolta)?;
+    println!(
+        "Copia in sola lettura di {} ({} reperti, snapshot {}) su http://{}, Ctrl-C per uscire",
+        cartella.display(),
+        specchio.leggi(|inv| inv.totale()),
+        specchio.snapshot(),
+        conf.ascolta
+    );
+
+    let (aggiorna, fermo) = (std::sync::Arc::clone(&specchio), std::sync::Arc::clone(&arresto));
+    std::thread::spawn(move || {
+        while !fermo.load(std::sync::atomic::Ordering::Relaxed) {
+            std::thread::sleep(std::time::Duration::from_secs(1));
+            match aggiorna.aggiorna() {
+                Ok(voci) => {
+                    for v in voci {
+                        println!(
+                            "[{}] snapshot {}: {} modificati, {} rimossi",
+                            chrono::Local::now().format("%H:%M:%S"),
+                            v.snapshot,
+                            v.modificati.len(),
+                            v.rimossi.len()
+                        );
+                    }
                 }
-                ErroreInventario::DatiNonValidi(msg) => write!(f, "Dati non validi: {}", msg),
-                ErroreInventario::SerializzazioneErrore(msg) => {
-                    write!(f, "Errore serializzazione: {}", msg)
+                Err(e) => eprintln!("Aggiornamento fallito, resta in uso lo snapshot precedente: {}", descrivi(&e)),
+            }
+        }
+    });
+    salute::servi(&ascolto, app, arresto)
+}
+
+/// Una riga di log per ogni ricarica dell'osservatore (--watch)
+fn stampa_evento(evento: archivio::EventoArchivio) {
+    let ora = chrono::Local::now().format("%H:%M:%S");
+    match evento {
+        archivio::EventoArchivio::Ricaricato { reperti, impronta } => {
+            println!("[{}] ricaricato: {} reperti, snapshot {}", ora, reperti, impronta)
+        }
+        archivio::EventoArchivio::RicaricaFallita(e) => {
+            eprintln!("[{}] ricarica fallita, dati precedenti in uso: {}", ora, descrivi(&e))
+        }
+    }
+}
+
+/// L'errore seguito dalle sue cause: i messaggi non le ripetono
+fn descrivi(errore: &dyn std::error::Error) -> String {
+    let mut testo = errore.to_string();
+    let mut causa = errore.source();
+    while let Some(c) = causa {
+        testo.push_str(&format!(": {}", c));
+        causa = c.source();
+    }
+    testo
+}
+
+fn main() {
+    // Gli errori di sintassi (opzione sconosciuta, valore non valido, ...)
+    // li segnala clap con l'aiuto del comando, e termina con codice 2
+    let argomenti = cli::Cli::parse();
+    if let Some(chiave) = argomenti.assistente_tipologia {
+        let chiave = match chiave {
+            Some(file) => tipologia::ChiaveTipologica::da_file(&file),
+            None => tipologia::ChiaveTipologica::da_json(tipologia::CHIAVE_ASCE_MARGINI_RIALZATI),
+        };
+        let esito = chiave.and_then(|chiave| {
+            tipologia::assistente(&chiave, std::io::stdin().lock(), &mut std::io::stdout())
+        });
+        match esito {
+            Ok(Some(proposta)) => println!("\nTipo proposto: {}\nRiferimento: {}", proposta.tipo, proposta.riferimento),
+            Ok(None) => println!("\nClassificazione interrotta"),
+            Err(e) => eprintln!("Errore: {}", descrivi(&e)),
+        }
+        return;
+    }
+    if let Some(file) = argomenti.watch {
+        let conf = configurazione::Configurazione::da_ambiente();
+        let archivio = archivio::FileJson::nuovo(file.clone(), conf.passphrase);
+        let osservatore = match archivio::osserva(archivio, std::time::Duration::from_millis(500)) {
+            Ok(o) => o,
+            Err(e) => {
+                eprintln!("Errore: {}", descrivi(&e));
+                return;
+            }
+        };
+        if let Ok(inv) = osservatore.inventario().read() {
+            println!("In ascolto su {} ({} reperti), Ctrl-C per uscire", file.display(), inv.totale());
+        }
+        osservatore.eventi().iter().for_each(stampa_evento);
+        return;
+    }
+    if let Some(cartella) = argomenti.mirror {
+        if let Err(e) = servi_specchio(cartella) {
+            eprintln!("Errore: {}", descrivi(&e));
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(comando) = argomenti.comando {
+        if let Err(e) = esegui(comando) {
+            eprintln!("Errore: {}", descrivi(&e));
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    schermo::intestazione(&["CAPITOLO 9: PROGETTO FINALE", "Gestore di Inventario Archeologico"]);
+
+    // ========================================================================
+    // FASE 1: Creazione dell'inventario
+    // ========================================================================
+    println!("--- Fase 1: Popolamento Inventario ---\n");
+
+    let mut inv = Inventario::nuovo();
+
+    for reperto in reperti_di_esempio() {
+        match inv.aggiungi(reperto) {
+            Ok(id) => println!("  Aggiunto reperto ID #{}", id),
+            Err(e) => println!("  ERRORE: {}", e),
+        }
+    }
+
+    println!("\n  Totale reperti inseriti: {}", inv.totale());
+
+    // ========================================================================
+    // FASE 2: Ricerche
+    // ========================================================================
+    println!("\n--- Fase 2: Ricerche ---\n");
+
+    // Per ID
+    print_search_result("Ricerca ID #3", || {
+        inv.cerca_per_id(3).map(|r| format!("  {}", r))
+    });
+
+    // ID non esistente
+    print_search_result("Ricerca ID #99", || {
+        inv.cerca_per_id(99).map(|r| format!("  {}", r))
+    });
+
+    // Per nome
+    println!("Ricerca nome 'ascia':");
+    for r in inv.cerca_per_nome("ascia") {
+        println!("  {}", r);
+    }
+
+    // Per materiale
+    println!("\nReperti in Ceramica:");
+    for r in inv.cerca_per_materiale(&Materiale::Ceramica) {
+        println!("  {}", r);
+    }
+
+    // Per decorazione
+    println!("\nReperti decorati a incisione:");
+    for r in inv.cerca_per_decorazione(Some(TecnicaDecorativa::Incisione), None) {
+        let decorazioni: Vec<String> = r.decorazioni.iter().map(|d| d.to_string()).collect();
+        println!("  {} [{}]", r, decorazioni.join("; "));
+    }
+
+    // Per componente: trova anche i reperti compositi
+    println!("\nReperti con parti in Osso:");
+    for r in inv.cerca_per_componente(&Materiale::Osso) {
+        let parti: Vec<String> = r.componenti.iter().map(|c| c.to_string()).collect();
+        println!("  {} [{}]", r, parti.join(", "));
+    }
+
+    // Per periodo
+    println!("\nReperti Bronzo Finale:");
+    for r in inv.cerca_per_periodo(&Periodo::BronzoFinale) {
+        println!("  {}", r);
+    }
+
+    // Per sito
+    println!("\nReperti da Pontecagnano:");
+    for r in inv.cerca_per_sito("pontecagnano") {
+        println!("  {}", r);
+    }
+
+    // ========================================================================
+    // FASE 3: Operazioni sull'inventario
+    // ========================================================================
+    println!("\n--- Fase 3: Operazioni ---\n");
+
+    // Aggiungi note
+    match inv.aggiungi_nota(1, "Analisi XRF completata: Cu 88%, Sn 12%") {
+        Ok(()) => println!("  Nota aggiunta al reperto #1"),
+        Err(e) => println!("  Errore: {}", e),
+    }
+
+    // Mostra reperto con note
+    if let Ok(reperto) = inv.cerca_per_id(1) {
+        println!("  Reperto #1 - Note:");
+        for nota in &reperto.note {
+            println!("    - {}", nota);
+        }
+    }
+
+    // Rimuovi un reperto
+    match inv.rimuovi(10) {
+        Ok(rimosso) => println!("\n  Rimosso: {}", rimosso.nome),
+        Err(e) => println!("\n  Errore rimozione: {}", e),
+    }
+    println!("  Totale dopo rimozione: {}", inv.totale());
+
+    // ========================================================================
+    // FASE 4: Statistiche
+    // ========================================================================
+    println!("\n--- Fase 4: Statistiche ---\n");
+
+    let tutti = inv.tutti();
+    let report = statistiche::genera_report(&tutti);
+    statistiche::stampa_report(&report);
+
+    // Lo stesso report in prosa, per la relazione preliminare
+    let mut narrativa = Narrativa::da_report(&report);
+    if let Some(llm) = configurazione::Configurazione::da_ambiente().llm {
+        narrativa = narrativa.clone().rifinisci(&llm).unwrap_or_else(|e| {
+            println!("\n  Rifinitura non riuscita ({}): resta la bozza", e);
+            narrativa
+        });
+    }
+    let origine = if narrativa.rifinita { "rifinito dal modello" } else { "dai modelli di frase" };
+    println!("\n  PARAGRAFO PER LA RELAZIONE ({}):\n", origine);
+    for riga in rust_tutorial::tui_text::a_capo(&narrativa.testo, 74) {
+        println!("  {}", riga);
+    }
+
+    println!("\n  PERIODO x MATERIALE (peso totale in grammi):\n");
+    for riga in report.incrocio.testo(statistiche::Misura::PesoTotale).lines() {
+        println!("  {}", riga);
+    }
+    let percorso = std::env::temp_dir().join("bronzeaxe_periodo_materiale.csv");
+    let snapshot = inv.impronta_snapshot().ok();
+    match std::fs::write(&percorso, report.incrocio.csv(statistiche::Misura::Conteggio, snapshot.as_deref())) {
+        Ok(()) => println!("\n  Tabella CSV salvata in {}", percorso.display()),
+        Err(e) => println!("\n  Errore CSV: {}", e),
+    }
+
+    let ripostiglio = inv.cerca_per_sito("Savignano Irpino");
+    let composizione = statistiche::composizione_funzionale(&ripostiglio);
+    println!("\nComposizione funzionale del ripostiglio di Savignano Irpino:");
+    for (classe, n) in &composizione.conteggi {
+        println!("  {:<10} {:>2} ({:.0}%)", classe, n, composizione.quota(*classe) * 100.0);
+    }
+    if composizione.non_classificati > 0 {
+        println!("  Non classificati: {}", composizione.non_classificati);
+    }
+    if let Some(classe) = composizione.prevalente() {
+        println!("  Classe prevalente: {}", classe);
+    }
+
+    println!("\nFrammentazione per sito:");
+    let mut siti: Vec<String> = inv.tutti().iter().map(|r| r.sito.clone()).collect();
+    siti.sort();
+    siti.dedup();
+    for sito in &siti {
+        let indice = statistiche::indice_frammentazione(&inv.cerca_per_sito(sito));
+        let rapporto = indice.rapporto().map_or("-".to_string(), |r| format!("{:.2}", r));
+        let quota = indice.quota_peso_conservata().map_or("-".to_string(), |q| format!("{:.0}%", q * 100.0));
+        println!(
+            "  {:<18} completi {} / frammentari {} (rapporto {}), peso {:.0}g su {:.0}g stimati ({})",
+            sito, indice.completi, indice.frammentari, rapporto,
+            indice.peso_conservato_g, indice.peso_originale_stimato_g, quota
+        );
+    }
+
+    // ========================================================================
+    // FASE 5: Analisi avanzate con iteratori
+    // ========================================================================
+    println!("\n--- Fase 5: Analisi Avanzate ---\n");
+
+    // Reperto piu pesante
+    let piu_pesante = inv.tutti().into_iter()
+        .filter(|r| r.misurazioni.peso_grammi.is_some())
+        .max_by(|a, b| {
+            a.misurazioni.peso_grammi.unwrap()
+                .partial_cmp(&b.misurazioni.peso_grammi.unwrap())
+                .unwrap()
+        });
+
+    if let Some(r) = piu_pesante {
+        println!("  Reperto piu pesante: {} ({:.0}g)",
+            r.nome, r.misurazioni.peso_grammi.unwrap());
+    }
+
+    // Reperto piu leggero
+    let piu_leggero = inv.tutti().into_iter()
+        .filter(|r| r.misurazioni.peso_grammi.is_some())
+        .min_by(|a, b| {
+            a.misurazioni.peso_grammi.unwrap()
+                .partial_cmp(&b.misurazioni.peso_grammi.unwrap())
+                .unwrap()
+        });
+
+    if let Some(r) = piu_leggero {
+        println!("  Reperto piu leggero: {} ({:.0}g)",
+            r.nome, r.misurazioni.peso_grammi.unwrap());
+    }
+
+    // Distribuzione pesi per periodo
+    println!("\n  Peso medio per periodo:");
+    let mut pesi_per_periodo: HashMap<String, (f64, usize)> = HashMap::new();
+    for r in inv.tutti() {
+        if let Some(peso) = r.misurazioni.peso_grammi {
+            let entry = pesi_per_periodo
+                .entry(format!("{}", r.periodo))
+                .or_insert((0.0, 0));
+            entry.0 += peso;
+            entry.1 += 1;
+        }
+    }
+    let mut periodi_ordinati: Vec<_> = pesi_per_periodo.iter().collect();
+    periodi_ordinati.sort_by(|a, b| {
+        let media_a = a.1.0 / a.1.1 as f64;
+        let media_b = b.1.0 / b.1.1 as f64;
+        media_b.partial_cmp(&media_a).unwrap().then(a.0.cmp(b.0))
+    });
+    for (periodo, (totale, count)) in &periodi_ordinati {
+        let media = totale / *count as f64;
+        println!("    {}: {:.0}g (media su {} reperti)", periodo, media, count);
+    }
+
+    // Reperti con coordinate
+    let con_coordinate: Vec<_> = inv.tutti().into_iter()
+        .filter(|r| r.coordinate.is_some())
+        .collect();
+    println!("\n  Reperti con coordinate GPS: {}/{}", con_coordinate.len(), inv.totale());
+
+    // Volume approssimativo
+    println!("\n  Volumi approssimativi:");
+    for r in inv.tutti() {
+        if let Some(vol) = r.misurazioni.volume_approssimativo() {
+            println!("    {}: {:.1} cm3", r.nome, vol);
+        }
+    }
+
+    // ========================================================================
+    // FASE 6: Esportazione JSON
+    // ========================================================================
+    println!("\n--- Fase 6: Esportazione JSON ---\n");
+
+    match inv.to_json() {
+        Ok(json) => {
+            // Mostra solo le prime righe per non inondare l'output
+            let righe: Vec<&str> = json.lines().collect();
+            let max_righe = 20;
+            for riga in righe.iter().take(max_righe) {
+                println!("  {}", riga);
+            }
+            if righe.len() > max_righe {
+                println!("  ... ({} righe totali)", righe.len());
+            }
+            println!("\n  JSON generato con successo ({} bytes)", json.len());
+        }
+        Err(e) => println!("  Errore esportazione: {}", e),
+    }
+
+    // ========================================================================
+    // FASE 7: Allegati e verifica di integrita
+    // ========================================================================
+    println!("\n--- Fase 7: Allegati ---\n");
+
+    if let Err(e) = dimostra_allegati(&mut inv) {
+        println!("  Errore allegati: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 8: Provenienza e prestiti
+    // ========================================================================
+    println!("\n--- Fase 8: Provenienza e Prestiti ---\n");
+
+    let data = |a, m, g| NaiveDate::from_ymd_opt(a, m, g).unwrap();
+    let catena = [
+        (TipoEventoProvenienza::Scavo, data(2019, 7, 12), "Scavo Savignano Irpino, US 104", Some("Giornale di scavo p. 37")),
+        (TipoEventoProvenienza::Deposito, data(2019, 9, 3), "Deposito Soprintendenza ABAP Avellino", Some("Verbale di consegna 112/2019")),
+        (TipoEventoProvenienza::IngressoMuseo, data(2022, 4, 20), "Museo Archeologico Nazionale di Avellino", Some("Decreto di assegnazione 45/2022")),
+    ];
+    for (tipo, data, detentore, documento) in catena {
+        let evento = EventoProvenienza {
+            tipo,
+            data,
+            detentore: detentore.to_string(),
+            documento: documento.map(String::from),
+        };
+        if let Err(e) = inv.aggiungi_evento_provenienza(1, evento) {
+            println!("  Errore provenienza: {}", e);
+        }
+    }
+    // Un evento fuori ordine viene rifiutato
+    let fuori_ordine = EventoProvenienza {
+        tipo: TipoEventoProvenienza::Deposito,
+        data: data(2018, 1, 1),
+        detentore: "Magazzino comunale".to_string(),
+        documento: None,
+    };
+    if let Err(e) = inv.aggiungi_evento_provenienza(1, fuori_ordine) {
+        println!("  Rifiutato: {}", e);
+    }
+
+    let prestito = prestiti::Prestito {
+        reperti: vec![1, 2, 5],
+        prestatore: prestiti::Istituzione {
+            nome: "Museo Archeologico Nazionale di Avellino".to_string(),
+            citta: "Avellino".to_string(),
+            referente: "dott.ssa M. Rossi".to_string(),
+        },
+        richiedente: prestiti::Istituzione {
+            nome: "Museo delle Civilta".to_string(),
+            citta: "Roma".to_string(),
+            referente: "dott. L. Bianchi".to_string(),
+        },
+        finalita: "mostra \"Il bronzo dell'Irpinia\"".to_string(),
+        data_inizio: chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        data_fine: chrono::NaiveDate::from_ymd_opt(2025, 9, 30).unwrap(),
+    };
+    match prestiti::compila_contratto(prestiti::MODELLO_CONTRATTO, &prestito, &inv) {
+        Ok(contratto) => {
+            for riga in contratto.lines() {
+                println!("  {}", riga);
+            }
+        }
+        Err(e) => println!("  Errore contratto: {}", e),
+    }
+    if let Err(e) = inv.registra_prestito(prestito.clone()) {
+        println!("  Prestito non registrato: {}", e);
+    }
+
+    // Il prestito entra nella catena di provenienza
+    let _ = inv.aggiungi_evento_provenienza(1, EventoProvenienza {
+        tipo: TipoEventoProvenienza::Prestito,
+        data: prestito.data_inizio,
+        detentore: prestito.richiedente.nome.clone(),
+        documento: Some("Contratto di prestito 2025/07".to_string()),
+    });
+    if let Ok(r) = inv.cerca_per_id(1) {
+        println!("\n  Catena di provenienza #{}:", r.id);
+        for evento in &r.provenienza {
+            println!("    {}", evento);
+        }
+        println!("  Detentore attuale: {}", r.detentore_attuale().unwrap_or("sconosciuto"));
+    }
+
+    // ========================================================================
+    // FASE 9: Stime e assicurazione
+    // ========================================================================
+    println!("\n--- Fase 9: Stime e Assicurazione ---\n");
+
+    let collocazioni = [
+        (1, "Ripostiglio di Savignano", "Vetrina 3"),
+        (2, "Ripostiglio di Savignano", "Vetrina 3"),
+        (3, "Ripostiglio di Savignano", "Deposito A - Cassa 12"),
+        (5, "Necropoli di Pontecagnano", "Vetrina 7"),
+        (9, "Necropoli di Pontecagnano", "Deposito A - Cassa 12"),
+    ];
+    for (id, collezione, collocazione) in collocazioni {
+        if let Ok(r) = inv.cerca_per_id_mut(id) {
+            r.collezione = Some(collezione.to_string());
+            r.collocazione = Some(collocazione.to_string());
+        }
+    }
+
+    let stime = [
+        (1, 12_000.0, data(2020, 5, 10), "perito G. Verdi"),
+        (1, 15_000.0, data(2024, 11, 2), "perito G. Verdi"),
+        (2, 14_000.0, data(2024, 11, 2), "perito G. Verdi"),
+        (3, 40_000.0, data(2023, 2, 15), "perito A. Neri"),
+        (5, 6_500.0, data(2024, 1, 20), "perito A. Neri"),
+        (9, 3_000.0, data(2024, 1, 20), "perito A. Neri"),
+    ];
+    for (id, valore, data, perito) in stime {
+        let stima = Stima { valore, valuta: "EUR".to_string(), data, perito: perito.to_string() };
+        if let Err(e) = inv.aggiungi_stima(id, stima) {
+            println!("  Errore stima: {}", e);
+        }
+    }
+    if let Ok(r) = inv.cerca_per_id(1) {
+        println!("  Storico stime #1:");
+        for stima in &r.stime {
+            println!("    {}", stima);
+        }
+        if let Some(corrente) = r.stima_corrente() {
+            println!("  Stima corrente #1: {}", corrente);
+        }
+    }
+    println!();
+    assicurazione::stampa_report(&assicurazione::genera_report(&inv.tutti()));
+
+    // ========================================================================
+    // FASE 10: Campagna di controllo conservativo
+    // ========================================================================
+    println!("\n--- Fase 10: Controllo Conservativo ---\n");
+
+    // Prima campagna: si controlla tutto
+    let mut campagna_2023 = condizione::Campagna::nuova("Controllo 2023", data(2023, 3, 1));
+    let ids: Vec<u32> = campagna_2023.da_controllare(&inv).iter().map(|r| r.id).collect();
+    for id in &ids {
+        let attuale = inv.cerca_per_id(*id).map(|r| r.conservazione.clone());
+        if let Ok(stato) = attuale {
+            let _ = campagna_2023.registra(&mut inv, *id, stato);
+        }
+    }
+    println!("  {}: controllati {} reperti", campagna_2023.nome, ids.len());
+
+    // Seconda campagna: solo i reperti scaduti in base al rischio
+    let mut campagna_2024 = condizione::Campagna::nuova("Controllo 2024", data(2024, 3, 15));
+    println!("  {} - reperti da ricontrollare:", campagna_2024.nome);
+    let scaduti: Vec<(u32, String, u8)> = campagna_2024
+        .da_controllare(&inv)
+        .iter()
+        .map(|r| (r.id, r.nome.clone(), r.punteggio_rischio()))
+        .collect();
+    for (id, nome, rischio) in &scaduti {
+        println!("    #{} {} (rischio {}/5)", id, nome, rischio);
+    }
+    let nuovi_stati = [(3, Conservazione::Frammentario), (6, Conservazione::Pessimo)];
+    for (id, stato) in nuovi_stati {
+        if let Err(e) = campagna_2024.registra(&mut inv, id, stato) {
+            println!("  Errore controllo: {}", e);
+        }
+    }
+    println!("  Deterioramenti rispetto al controllo precedente:");
+    for v in campagna_2024.deterioramenti() {
+        println!("    #{}: {} -> {}", v.id_reperto, v.precedente, v.attuale);
+    }
+
+    // ========================================================================
+    // FASE 11: Movimentazioni e custodia
+    // ========================================================================
+    println!("\n--- Fase 11: Movimentazioni ---\n");
+
+    let ora = |a, m, g, h| data(a, m, g).and_hms_opt(h, 0, 0).unwrap();
+    let movimenti = [
+        (3, "Laboratorio di restauro", ora(2024, 4, 2, 9), "M. Esposito", Some("Consolidamento della lama")),
+        (3, "Vetrina 3", ora(2024, 6, 18, 16), "M. Esposito", Some("Rientro dal restauro")),
+        (2, "Deposito A - Cassa 4", ora(2024, 5, 6, 11), "L. Russo", None),
+    ];
+    for (id, a, quando, responsabile, motivo) in movimenti {
+        if let Err(e) = inv.sposta(id, a, quando, responsabile, motivo) {
+            println!("  Errore movimento: {}", e);
+        }
+    }
+    if let Ok(r) = inv.cerca_per_id(3) {
+        for riga in custodia::report(r).lines() {
+            println!("  {}", riga);
+        }
+    }
+    let istante = ora(2024, 5, 1, 12);
+    println!("\n  Posizioni al {}:", istante.format("%d/%m/%Y %H:%M"));
+    for (id, posizione) in custodia::posizioni_al(&inv, istante) {
+        if let Some(posizione) = posizione {
+            println!("    #{} -> {}", id, posizione);
+        }
+    }
+
+    // ========================================================================
+    // FASE 12: Persone e attribuzioni
+    // ========================================================================
+    println!("\n--- Fase 12: Persone ---\n");
+
+    if let Err(e) = dimostra_persone(&mut inv) {
+        println!("  Errore persone: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 13: Flusso di catalogazione
+    // ========================================================================
+    println!("\n--- Fase 13: Flusso di Catalogazione ---\n");
+
+    use StatoCatalogazione::*;
+    // Il #1 viene dallo scavo 2019: per pubblicarlo servono gli estremi della campagna
+    let _ = inv.registra_campagna_scavo(CampagnaScavo {
+        codice: "SAV-2019".to_string(),
+        sito: "Savignano Irpino".to_string(),
+        anno: 2019,
+        concessione: Some("DG-ABAP Serv. II, concessione 23/2019".to_string()),
+        autorizzazione_ministeriale: Some("Prot. MiC 14502 del 03/05/2019".to_string()),
+        decreto_deposito: Some("Decreto SABAP-AV 112/2019".to_string()),
+    });
+    let _ = inv.assegna_campagna_scavo(1, "SAV-2019");
+    for id in [1, 2, 3, 5] {
+        let _ = inv.cambia_stato(id, Catalogato, RuoloUtente::Catalogatore);
+    }
+    for id in [1, 2] {
+        let _ = inv.cambia_stato(id, Verificato, RuoloUtente::Revisore);
+    }
+    let _ = inv.cambia_stato(1, Pubblicato, RuoloUtente::Responsabile);
+
+    let tentativi = [
+        (4, Pubblicato, RuoloUtente::Responsabile),
+        (2, Pubblicato, RuoloUtente::Catalogatore),
+    ];
+    for (id, stato, ruolo) in tentativi {
+        if let Err(e) = inv.cambia_stato(id, stato, ruolo) {
+            println!("  Rifiutato: {}", e);
+        }
+    }
+    if let Err(e) = inv.modifica_come(1, RuoloUtente::Catalogatore, |r| r.nome.push_str(" (rev.)")) {
+        println!("  Rifiutato: {}", e);
+    }
+
+    println!("\n  In attesa di verifica:");
+    for r in inv.in_attesa_di_verifica() {
+        println!("    {}", r);
+    }
+    for stato in [InIngresso, Catalogato, Verificato, Pubblicato] {
+        println!("  Schede '{}': {}", stato, inv.in_stato(stato).len());
+    }
+
+    // ========================================================================
+    // FASE 14: Profili di visibilita
+    // ========================================================================
+    println!("\n--- Fase 14: Profili di Visibilita ---\n");
+
+    let oggi = data(2025, 6, 1);
+    for profilo in [visibilita::ProfiloVisibilita::interno(), visibilita::ProfiloVisibilita::pubblico()] {
+        match inv.to_json_con_profilo(&profilo, oggi) {
+            Ok(json) => println!(
+                "  Profilo {:<9} {:>6} bytes, coordinate: {}, stime: {}",
+                profilo.nome,
+                json.len(),
+                json.contains("latitudine"),
+                json.contains("perito")
+            ),
+            Err(e) => println!("  Errore esportazione: {}", e),
+        }
+    }
+
+    // Coordinate offuscate per il dataset pubblico
+    use visibilita::{Offuscamento, ProfiloVisibilita};
+    let offuscamenti = [
+        Offuscamento::Troncamento { decimali: 2 },
+        Offuscamento::Spostamento { raggio_m: 2_000.0, seme: 2025 },
+    ];
+    if let Ok(r) = inv.cerca_per_id(1) {
+        if let Some(c) = &r.coordinate {
+            println!("\n  Coordinate interne #1: {}", c);
+            for offuscamento in &offuscamenti {
+                println!("    {:?} -> {}", offuscamento, offuscamento.applica(r.id, c));
+            }
+        }
+    }
+    let profilo_geo = ProfiloVisibilita::pubblico_con_coordinate(offuscamenti[1]);
+    match inv.to_geojson(&profilo_geo, oggi) {
+        Ok(geojson) => println!(
+            "  GeoJSON pubblico: {} feature ({} bytes)",
+            geojson.matches("\"Feature\"").count(),
+            geojson.len()
+        ),
+        Err(e) => println!("  Errore GeoJSON: {}", e),
+    }
+
+    // ========================================================================
+    // FASE 15: Tracce GPX e mappa dei rinvenimenti
+    // ========================================================================
+    println!("\n--- Fase 15: GPX e Mappa ---\n");
+
+    // Orari di rinvenimento annotati sul campo per i reperti senza coordinate
+    for (id, h, m) in [(4, 9, 42), (6, 11, 5), (8, 16, 30)] {
+        if let Ok(r) = inv.cerca_per_id_mut(id) {
+            r.rinvenuto_il = data(2024, 6, 12).and_hms_opt(h, m, 0);
+        }
+    }
+    let traccia = r#"<?xml version="1.0"?>
+<gpx version="1.1" creator="Garmin eTrex 32x">
+  <wpt lat="41.2251" lon="15.1793"><time>2024-06-12T09:40:10Z</time><name>WP017</name></wpt>
+  <wpt lat="41.2239" lon="15.1801"><time>2024-06-12T11:07:45Z</time><name>WP018</name></wpt>
+  <trk><trkseg>
+    <trkpt lat="41.2244" lon="15.1779"><time>2024-06-12T10:15:00Z</time></trkpt>
+    <trkpt lat="41.2260" lon="15.1770"><time>2024-06-12T13:00:00Z</time></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+    match gpx::leggi(traccia) {
+        Ok(punti) => {
+            println!("  Letti {} punti GPS", punti.len());
+            for a in gpx::associa(&mut inv, &punti, "etrex_2024-06-12.gpx", chrono::Duration::minutes(10)) {
+                println!(
+                    "    #{} <- {} ({:.4}, {:.4}), scarto {} s",
+                    a.id_reperto,
+                    a.punto.nome.as_deref().unwrap_or("punto traccia"),
+                    a.punto.latitudine,
+                    a.punto.longitudine,
+                    a.scarto.num_seconds()
+                );
+            }
+        }
+        Err(e) => println!("  Errore GPX: {}", e),
+    }
+    println!();
+
+    let tutti = inv.tutti();
+    if let Some(riquadro) = geo::Riquadro::da_reperti(&tutti, 0.1) {
+        let opzioni = geo::OpzioniMappa { larghezza: 50, altezza: 12, ..Default::default() };
+        for riga in geo::mappa_ascii(&tutti, &riquadro, &opzioni).lines() {
+            println!("  {}", riga);
+        }
+    }
+
+    // Griglia di scavo: quadrati di 4 m ruotati di 15 gradi
+    let griglia = geo::GrigliaScavo {
+        origine: Coordinate { latitudine: 41.2240, longitudine: 15.1780 },
+        orientamento_gradi: 15.0,
+        lato_m: 4.0,
+    };
+    if let Err(e) = inv.definisci_griglia("Savignano Irpino", griglia) {
+        println!("  Errore griglia: {}", e);
+    }
+    inv.definisci_caposaldo("Savignano Irpino", geo::Caposaldo { quota_piano_m: 412.30, tolleranza_m: 0.05 });
+    let posizioni = [
+        (7, "B4", Some(Quadrante::NE), Some(35.0), None),
+        (2, "AA1", None, Some(20.0), Some(412.10)),
+        (1, "C2", Some(Quadrante::SO), None, Some(411.95)),
+        (3, "D3", None, Some(60.0), None),
+        (4, "B2", None, Some(40.0), Some(412.60)),
+    ];
+    for (id, quadrato, quadrante, profondita_cm, quota_m) in posizioni {
+        let posizione = PosizioneScavo { quadrato: quadrato.to_string(), quadrante, profondita_cm, quota_m };
+        if let Err(e) = inv.imposta_posizione_scavo(id, posizione) {
+            println!("  Posizione #{} rifiutata: {}", id, e);
+        }
+    }
+    println!("\n  Posizioni di scavo:");
+    for id in [7, 2, 5] {
+        match (inv.cerca_per_id(id), inv.coordinate_da_griglia(id)) {
+            (Ok(r), Ok(c)) => println!(
+                "    #{} {} -> {}",
+                id,
+                r.posizione_scavo.as_ref().map(|p| p.to_string()).unwrap_or_default(),
+                c
+            ),
+            (_, Err(e)) | (Err(e), _) => println!("    #{}: {}", id, e),
+        }
+    }
+
+    // Sezione lungo un transetto attraverso l'area di scavo
+    let transetto = geo::Transetto {
+        da: Coordinate { latitudine: 41.22395, longitudine: 15.17795 },
+        a: Coordinate { latitudine: 41.22425, longitudine: 15.17835 },
+        fascia_m: 10.0,
+    };
+    match inv.punti_sezione("Savignano Irpino", &transetto) {
+        Ok(punti) => {
+            println!("\n  Sezione ({:.1} m):", transetto.lunghezza_m());
+            for p in &punti {
+                println!("    #{} a {:.1} m, quota {:.2} m", p.id_reperto, p.distanza_m, p.quota_m);
+            }
+            let svg = geo::profilo_sezione_svg(&punti, &transetto, 412.30);
+            let percorso = std::env::temp_dir().join("bronzeaxe_sezione.svg");
+            match std::fs::write(&percorso, &svg) {
+                Ok(()) => println!("  Profilo SVG salvato in {}", percorso.display()),
+                Err(e) => println!("  Errore SVG: {}", e),
+            }
+        }
+        Err(e) => println!("  Errore sezione: {}", e),
+    }
+
+    // ========================================================================
+    // FASE 16: Campioni e analisi
+    // ========================================================================
+    println!("\n--- Fase 16: Campioni ---\n");
+
+    if let Err(e) = dimostra_campioni(&mut inv) {
+        println!("  Errore campioni: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 17: Restauro e materiali di consumo
+    // ========================================================================
+    println!("\n--- Fase 17: Restauro ---\n");
+
+    let prodotto = |nome: &str, lotto: &str, quantita: Option<&str>| ProdottoUsato {
+        prodotto: nome.to_string(),
+        lotto: lotto.to_string(),
+        quantita: quantita.map(String::from),
+    };
+    let interventi = vec![
+        (3, data(2024, 4, 3), "Stabilizzazione della corrosione", vec![
+            prodotto("Benzotriazolo 3%", "BTA-2311", Some("50 ml")),
+            prodotto("Paraloid B-72", "PB72-0419", Some("20 ml")),
+        ]),
+        (1, data(2024, 5, 10), "Consolidamento del tallone", vec![prodotto("Paraloid B-72", "PB72-0419", None)]),
+        (5, data(2024, 5, 12), "Pulitura meccanica", vec![prodotto("Paraloid B-72", "PB72-0502", None)]),
+        (5, data(2024, 5, 20), "Protezione finale", vec![prodotto("Cera microcristallina", "", None)]),
+    ];
+    for (id, quando, descrizione, prodotti) in interventi {
+        let intervento = Intervento {
+            data: quando,
+            descrizione: descrizione.to_string(),
+            restauratore: "M. Esposito".to_string(),
+            prodotti,
+        };
+        if let Err(e) = inv.registra_intervento(id, intervento) {
+            println!("  Rifiutato intervento su #{}: {}", id, e);
+        }
+    }
+
+    println!("  Consumi per lotto:");
+    for ((nome, lotto), n) in restauro::consumi(&inv) {
+        println!("    {} lotto {}: {} interventi", nome, lotto, n);
+    }
+    println!("  Richiamo del lotto PB72-0419, reperti da ricontrollare:");
+    for (r, i) in restauro::interventi_con_prodotto(&inv, "paraloid", Some("PB72-0419")) {
+        let dettaglio: Vec<String> = i.prodotti.iter().map(|p| p.to_string()).collect();
+        println!("    #{} {} - {} del {} [{}]", r.id, r.nome, i.descrizione, i.data.format("%d/%m/%Y"), dettaglio.join(", "));
+    }
+
+    // ========================================================================
+    // FASE 18: Tracce d'uso e residui
+    // ========================================================================
+    println!("\n--- Fase 18: Traceologia ---\n");
+
+    let osservazioni = [
+        (1, MetodoOsservazione::Stereomicroscopio, "tallone", InterpretazioneTraccia::Immanicatura, Some("Lucidatura a fascia trasversale")),
+        (1, MetodoOsservazione::Stereomicroscopio, "tagliente", InterpretazioneTraccia::Riaffilatura, None),
+        (2, MetodoOsservazione::Sem, "tallone", InterpretazioneTraccia::Immanicatura, None),
+        (4, MetodoOsservazione::Macroscopica, "lama", InterpretazioneTraccia::Martellatura, None),
+        (4, MetodoOsservazione::AnalisiResidui, "lingua da presa", InterpretazioneTraccia::ResiduoOrganico, Some("Fibre di legno")),
+        (6, MetodoOsservazione::Macroscopica, "", InterpretazioneTraccia::NonDiagnostica, None),
+    ];
+    for (id, metodo, zona, interpretazione, note) in osservazioni {
+        let osservazione = Osservazione {
+            metodo,
+            localizzazione: zona.to_string(),
+            interpretazione,
+            note: note.map(String::from),
+        };
+        if let Err(e) = inv.aggiungi_osservazione(id, osservazione) {
+            println!("  Rifiutata osservazione su #{}: {}", id, e);
+        }
+    }
+
+    println!("  Reperti con tracce di immanicatura:");
+    for r in inv.cerca_per_traccia(InterpretazioneTraccia::Immanicatura) {
+        println!("    {}", r);
+        for o in &r.osservazioni {
+            println!("      - {}", o);
+        }
+    }
+
+    // ========================================================================
+    // FASE 19: Analisi ponderale
+    // ========================================================================
+    println!("\n--- Fase 19: Analisi Ponderale ---\n");
+
+    for peso in [12.3, 24.6, 36.4, 61.2, 48.9, 18.1, 30.7] {
+        let lingotto = Reperto::nuovo(
+            "Frammento di lingotto a piccone",
+            Materiale::Bronzo,
+            Periodo::BronzoFinale,
+            Conservazione::Frammentario,
+            "Savignano Irpino",
+        )
+        .con_misurazioni(Misurazioni::nuove().con_peso(peso))
+        .con_classe_funzionale(ClasseFunzionale::Lingotto);
+        if let Err(e) = inv.aggiungi(lingotto) {
+            println!("  Errore: {}", e);
+        }
+    }
+    let lingotti: Vec<&Reperto> = inv
+        .tutti()
+        .into_iter()
+        .filter(|r| r.classe_funzionale == Some(ClasseFunzionale::Lingotto))
+        .collect();
+    match ponderale::analizza(&lingotti, 4.0, 10.0, 0.01) {
+        Ok(analisi) => {
+            println!(
+                "  Unita migliore: {:.2} g (phi = {:.2}, scarto medio {:.0}% dell'unita)",
+                analisi.unita_g,
+                analisi.phi,
+                analisi.scarto_relativo_medio * 100.0
+            );
+            for r in &analisi.residui {
+                println!("    #{:<3} {:>6.1} g = {:>2} x unita {:+.2} g", r.id_reperto, r.peso_g, r.multiplo, r.scarto_g);
+            }
+        }
+        Err(e) => println!("  Analisi non possibile: {}", e),
+    }
+    if let Err(e) = ponderale::analizza(&inv.cerca_per_sito("Toppo Daguzzo"), 4.0, 10.0, 0.01) {
+        println!("  Toppo Daguzzo: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 20: Assistente tipologico
+    // ========================================================================
+    println!("\n--- Fase 20: Assistente Tipologico ---\n");
+
+    // Qui le risposte sono simulate; con --assistente-tipologia si risponde da tastiera
+    let risposte = std::io::Cursor::new("1\n4\n1\n1\n");
+    let esito = tipologia::ChiaveTipologica::da_json(tipologia::CHIAVE_ASCE_MARGINI_RIALZATI)
+        .and_then(|chiave| tipologia::assistente(&chiave, risposte, &mut std::io::stdout()));
+    match esito {
+        Ok(Some(proposta)) => {
+            println!("\n  Tipo proposto: {} [{}]", proposta.tipo, proposta.riferimento);
+            for (domanda, risposta) in &proposta.percorso {
+                println!("    - {} {}", domanda, risposta);
+            }
+        }
+        Ok(None) => println!("  Classificazione interrotta"),
+        Err(e) => println!("  Errore: {}", e),
+    }
+    let incompleta = r#"{"nome": "x", "fonte": "y", "radice": {"domanda": "?", "risposte": [{"etichetta": "si", "nodo": {"tipo": "A", "riferimento": "-"}}]}}"#;
+    if let Err(e) = tipologia::ChiaveTipologica::da_json(incompleta) {
+        println!("  Chiave rifiutata: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 21: Confronti
+    // ========================================================================
+    println!("\n--- Fase 21: Confronti ---\n");
+
+    let nuova_ascia = Reperto::nuovo(
+        "Ascia da Monte Cerreto",
+        Materiale::Bronzo,
+        Periodo::BronzoFinale,
+        Conservazione::Buono,
+        "Monte Cerreto",
+    )
+    .con_tipo("Ascia a margini rialzati tipo Savignano")
+    .con_classe_funzionale(ClasseFunzionale::Utensile)
+    .con_misurazioni(Misurazioni::nuove().con_dimensioni(19.2, 4.4, 2.0).con_peso(365.0));
+
+    let tutti = inv.tutti();
+    let risultati = confronti::cerca(&nuova_ascia, tutti.iter().copied(), &confronti::Tolleranze::default());
+    println!("  Confronti per '{}': {}", nuova_ascia.nome, risultati.len());
+    for c in &risultati {
+        println!("    {} - punteggio {}", c.reperto, c.punteggio);
+        for motivo in &c.motivi {
+            println!("      - {}", motivo);
+        }
+    }
+
+    let fuori_misura = Reperto::nuovo("Ascia miniaturistica", Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Integro, "Monte Cerreto")
+        .con_tipo("Ascia a margini rialzati tipo Savignano")
+        .con_misurazioni(Misurazioni::nuove().con_dimensioni(6.0, 1.8, 0.6));
+    let risultati = confronti::cerca(&fuori_misura, tutti.iter().copied(), &confronti::Tolleranze::default());
+    println!("  Confronti per '{}': {}", fuori_misura.nome, risultati.len());
+
+    // ========================================================================
+    // FASE 22: Corpus di riferimento
+    // ========================================================================
+    println!("\n--- Fase 22: Corpus di Riferimento ---\n");
+
+    let mut corpus = corpus::CorpusRiferimento::nuovo();
+    match corpus.importa_csv(corpus::CORPUS_ASCE_PBF) {
+        Ok(n) => println!("  Importati {} tipi da CSV", n),
+        Err(e) => println!("  Errore importazione: {}", e),
+    }
+    let json = r#"[{"tipo": "Pugnale tipo Peschiera", "classe": "arma", "periodo": "BronzoRecente",
+        "lunghezza_cm": {"min": 18, "max": 30}, "larghezza_cm": null, "peso_g": null,
+        "distribuzione": ["Veneto", "Lombardia"], "riferimento": "Bianco Peroni 1994", "esemplari": null}]"#;
+    match corpus.importa_json(json) {
+        Ok(n) => println!("  Importati {} tipi da JSON", n),
+        Err(e) => println!("  Errore importazione: {}", e),
+    }
+    if let Err(e) = corpus.importa_json(json) {
+        println!("  Seconda importazione rifiutata: {}", e);
+    }
+    if let Err(e) = corpus.importa_csv("tipo;classe\nAscia;utensile") {
+        println!("  CSV rifiutato: {}", e);
+    }
+
+    let ignota = Reperto::nuovo("Ascia sporadica", Materiale::Bronzo, Periodo::Sconosciuto, Conservazione::Discreto, "Sporadico")
+        .con_classe_funzionale(ClasseFunzionale::Utensile)
+        .con_misurazioni(Misurazioni::nuove().con_dimensioni(19.0, 4.6, 2.2).con_peso(400.0));
+    for candidato in [&nuova_ascia, &ignota] {
+        println!("  Tipi compatibili con '{}':", candidato.nome);
+        for c in corpus.corrispondenze(candidato) {
+            let regioni = c.tipo.distribuzione.join(", ");
+            println!("    {} [{}] punteggio {} - diffuso in: {}", c.tipo.tipo, c.tipo.riferimento, c.punteggio, regioni);
+            println!("      {}", c.motivi.join("; "));
+        }
+    }
+    if let Some(t) = corpus.tipo("Ascia a margini rialzati tipo Savignano") {
+        println!("  Esemplari noti del tipo Savignano: {}", t.esemplari.map_or("?".to_string(), |n| n.to_string()));
+    }
+
+    // ========================================================================
+    // FASE 23: Statistiche incrementali
+    // ========================================================================
+    println!("\n--- Fase 23: Statistiche Incrementali ---\n");
+
+    let stampa_cache = |titolo: &str, cache: &statistiche::CacheStatistiche| {
+        let mut periodi: Vec<_> = cache.per_periodo.iter().collect();
+        periodi.sort();
+        println!("  {}: {} reperti, peso medio {:.1} g, conservazione media {:.2}",
+            titolo, cache.totale, cache.peso_medio().unwrap_or(0.0), cache.conservazione_media());
+        for (periodo, n) in periodi {
+            println!("    {:<36} {:>2} reperti, {:>6.0} g", periodo, n, cache.peso_per_periodo.get(periodo).unwrap_or(&0.0));
+        }
+    };
+    stampa_cache("Prima", inv.statistiche());
+
+    if let Ok(r) = inv.cerca_per_id_mut(2) {
+        r.periodo = Periodo::BronzoRecente;
+        r.misurazioni.peso_grammi = Some(470.0);
+    }
+    if let Ok(r) = inv.cerca_per_id_mut(2) {
+        r.conservazione = Conservazione::Discreto;
+    }
+    let _ = inv.rimuovi(17);
+    stampa_cache("Dopo modifica di #2 e rimozione di #17", inv.statistiche());
+
+    inv.modifica_tutti(|r| {
+        if r.sito == "Pontecagnano" {
+            r.conservazione = Conservazione::Buono;
+        }
+    });
+    let incrementale = inv.statistiche().clone();
+    let ricalcolata = statistiche::CacheStatistiche::da_reperti(inv.tutti());
+    println!("  Cache coerente con il ricalcolo completo: {}", incrementale.coincide_con(&ricalcolata));
+
+    // ========================================================================
+    // FASE 24: Priorita di restauro
+    // ========================================================================
+    println!("\n--- Fase 24: Priorita di Restauro ---\n");
+
+    statistiche::stampa_priorita(&statistiche::priorita_restauro(&inv.tutti(), data(2025, 6, 1), 5));
+
+    // ========================================================================
+    // FASE 25: Cronologia dei rinvenimenti
+    // ========================================================================
+    println!("\n--- Fase 25: Cronologia ---\n");
+
+    let tutti = inv.tutti();
+    for riga in grafici::cronologia_terminale(&tutti, 30).lines() {
+        println!("  {}", riga);
+    }
+    let percorso = std::env::temp_dir().join("bronzeaxe_cronologia.svg");
+    match std::fs::write(&percorso, grafici::cronologia_svg(&tutti)) {
+        Ok(()) => println!("  Grafico SVG salvato in {}", percorso.display()),
+        Err(e) => println!("  Errore SVG: {}", e),
+    }
+
+    // ========================================================================
+    // FASE 26: Inventario sintetico
+    // ========================================================================
+    println!("\n--- Fase 26: Inventario Sintetico ---\n");
+
+    let inizio = std::time::Instant::now();
+    let mut sintetico = Inventario::nuovo();
+    for reperto in generatore::Generatore::nuovo(7).genera(10_000, &generatore::siti_predefiniti()) {
+        if let Err(e) = sintetico.aggiungi(reperto) {
+            println!("  Errore: {}", e);
+        }
+    }
+    println!("  Generati {} reperti in {:?}", sintetico.totale(), inizio.elapsed());
+
+    let inizio = std::time::Instant::now();
+    let report = statistiche::genera_report(&sintetico.tutti());
+    println!("  Report completo in {:?}", inizio.elapsed());
+    let inizio = std::time::Instant::now();
+    let peso_medio = sintetico.statistiche().peso_medio().unwrap_or(0.0);
+    println!("  Aggregati dalla cache in {:?} (peso medio {:.1} g)", inizio.elapsed(), peso_medio);
+    for riga in report.incrocio.testo(statistiche::Misura::Conteggio).lines() {
+        println!("  {}", riga);
+    }
+    for riga in grafici::cronologia_terminale(&sintetico.tutti(), 40).lines() {
+        println!("  {}", riga);
+    }
+
+    // ========================================================================
+    // FASE 27: Esportazioni riproducibili
+    // ========================================================================
+    println!("\n--- Fase 27: Esportazioni Riproducibili ---\n");
+
+    let rigenerato = {
+        let mut inv2 = Inventario::nuovo();
+        for reperto in generatore::Generatore::nuovo(7).genera(10_000, &generatore::siti_predefiniti()) {
+            let _ = inv2.aggiungi(reperto);
+        }
+        inv2
+    };
+    match (sintetico.to_json(), rigenerato.to_json()) {
+        (Ok(a), Ok(b)) => println!("  Due esportazioni dello stesso inventario sono identiche: {}", a == b),
+        (Err(e), _) | (_, Err(e)) => println!("  Errore: {}", e),
+    }
+    for ordinamento in ["periodo", "sito"].into_iter().filter_map(inventario::Ordinamento::da_nome) {
+        let primi: Vec<String> = inv
+            .tutti_ordinati(ordinamento)
+            .iter()
+            .take(3)
+            .map(|r| format!("#{} {}", r.id, r.nome))
+            .collect();
+        println!("  Primi per {:?}: {}", ordinamento, primi.join(", "));
+    }
+
+    // ========================================================================
+    // FASE 28: Impronta del catalogo
+    // ========================================================================
+    println!("\n--- Fase 28: Impronta del Catalogo ---\n");
+
+    match (inv.impronta_snapshot(), inv.to_json_con_profilo(&visibilita::ProfiloVisibilita::pubblico(), oggi)) {
+        (Ok(impronta), Ok(pubblicato)) => {
+            println!("  Snapshot attuale: {}", impronta);
+            println!("  Export pubblico verificato: {:?}", inv.verifica_snapshot(&pubblicato));
+            let _ = inv.aggiungi_nota(1, "Revisione della scheda");
+            println!("  Dopo una modifica al catalogo: {:?}", inv.verifica_snapshot(&pubblicato));
+            println!("  Nuovo snapshot: {}", inv.impronta_snapshot().unwrap_or_default());
+        }
+        (Err(e), _) | (_, Err(e)) => println!("  Errore: {}", e),
+    }
+
+    // ========================================================================
+    // FASE 29: Archivio su disco
+    // ========================================================================
+    println!("\n--- Fase 29: Archivio su Disco ---\n");
+
+    if let Err(e) = dimostra_archivio(&inv) {
+        println!("  Errore: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 30: Database SQLite e migrazioni
+    // ========================================================================
+    println!("\n--- Fase 30: Database SQLite e Migrazioni ---\n");
+
+    if let Err(e) = dimostra_database(&inv) {
+        println!("  Errore: {}", e);
+    }
+
+    // ========================================================================
+    // FASE 31: Archivio scelto da configurazione
+    // ========================================================================
+    println!("\n--- Fase 31: Archivio da Configurazione ---\n");
+
+    let temp = std::env::temp_dir();
+    let mut indirizzi = vec![
+        temp.join("bronzeaxe_configurato.json").display().to_string(),
+        format!("{}/", temp.join("bronzeaxe_configurato").display()),
+    ];
+    match std::env::var("BRONZEAXE_POSTGRES_URL") {
+        Ok(url) => indirizzi.push(url),
+        Err(_) => println!("  (impostare BRONZEAXE_POSTGRES_URL per provare anche PostgreSQL)"),
+    }
+    for indirizzo in &indirizzi {
+        let esito = archivio::da_indirizzo(indirizzo, None).and_then(|a| {
+            a.salva(&inv)?;
+            a.carica()
+        });
+        match esito {
+            Ok(letto) => println!("  {:<45} {} reperti", indirizzo, letto.totale()),
+            Err(e) => println!("  {:<45} errore: {}", indirizzo, e),
+        }
+    }
+    if let Ok(url) = std::env::var("BRONZEAXE_POSTGRES_URL") {
+        match postgres::ArchivioPostgres::nuovo(&url, 2).and_then(|pg| pg.cerca_testo("ascia", 5)) {
+            Ok(trovati) => {
+                for r in trovati {
+                    println!("    ricerca 'ascia': #{} {}", r.id, r.nome);
                 }
             }
+            Err(e) => println!("  Ricerca testuale: {}", e),
         }
     }
 
-    impl From<serde_json::Error> for ErroreInventario {
-        fn from(e: serde_json::Error) -> Self {
-            ErroreInventario::SerializzazioneErrore(e.to_string())
-        }
+    // ========================================================================
+    // FASE 32: Copie in sola lettura
+    // ========================================================================
+    println!("\n--- Fase 32: Copie in Sola Lettura ---\n");
+
+    if let Err(e) = dimostra_replica(&inv) {
+        println!("  Errore: {}", e);
     }
-}
 
-// ============================================================================
-// MODULO: INVENTARIO
-// ============================================================================
-mod inventario {
-    use super::errori::ErroreInventario;
-    use super::modelli::*;
-    use std::collections::HashMap;
+    // ========================================================================
+    // FASE 33: Endpoint di salute
+    // ========================================================================
+    println!("\n--- Fase 33: Endpoint di Salute ---\n");
 
-    /// Inventario principale
-    pub struct Inventario {
-        reperti: HashMap<u32, Reperto>,
-        prossimo_id: u32,
+    if let Err(e) = dimostra_salute() {
+        println!("  Errore: {}", e);
     }
 
-    impl Inventario {
-        pub fn nuovo() -> Self {
-            Inventario {
-                reperti: HashMap::new(),
-                prossimo_id: 1,
-            }
-        }
-
-        /// Aggiungi un reperto con ID automatico
-        pub fn aggiungi(&mut self, mut reperto: Reperto) -> Result<u32, ErroreInventario> {
-            if reperto.nome.trim().is_empty() {
-                return Err(ErroreInventario::NomeVuoto);
-            }
+    // ========================================================================
+    // FASE 34: Modifiche concorrenti
+    // ========================================================================
+    println!("\n--- Fase 34: Modifiche Concorrenti ---\n");
 
-            let id = self.prossimo_id;
-            reperto.id = id;
-            self.reperti.insert(id, reperto);
-            self.prossimo_id += 1;
-            Ok(id)
-        }
+    if let Err(e) = dimostra_modifiche_concorrenti(&mut inv) {
+        println!("  Errore: {}", e);
+    }
 
-        /// Cerca un reperto per ID
-        pub fn cerca_per_id(&self, id: u32) -> Result<&Reperto, ErroreInventario> {
-            self.reperti
-                .get(&id)
-                .ok_or(ErroreInventario::RepertoNonTrovato(id))
-        }
+    println!("\n--- Fase 35: Report di Attivita per Persona ---\n");
 
-        /// Cerca reperti per nome (ricerca parziale, case-insensitive)
-        pub fn cerca_per_nome(&self, query: &str) -> Vec<&Reperto> {
-            let query_lower = query.to_lowercase();
-            self.reperti
-                .values()
-                .filter(|r| r.nome.to_lowercase().contains(&query_lower))
-                .collect()
-        }
+    if let Err(e) = dimostra_report_attivita(&mut inv) {
+        println!("  Errore: {}", e);
+    }
 
-        /// Cerca reperti per materiale
-        pub fn cerca_per_materiale(&self, materiale: &Materiale) -> Vec<&Reperto> {
-            self.reperti
-                .values()
-                .filter(|r| &r.materiale == materiale)
-                .collect()
-        }
+    println!("\n--- Fase 36: Riepilogo via Email ---\n");
 
-        /// Cerca reperti per periodo
-        pub fn cerca_per_periodo(&self, periodo: &Periodo) -> Vec<&Reperto> {
-            self.reperti
-                .values()
-                .filter(|r| &r.periodo == periodo)
-                .collect()
-        }
+    if let Err(e) = dimostra_riepilogo(&mut inv) {
+        println!("  Errore: {}", e);
+    }
 
-        /// Cerca reperti per sito
-        pub fn cerca_per_sito(&self, sito: &str) -> Vec<&Reperto> {
-            let sito_lower = sito.to_lowercase();
-            self.reperti
-                .values()
-                .filter(|r| r.sito.to_lowercase().contains(&sito_lower))
-                .collect()
-        }
+    println!("\n--- Fase 37: Rimozioni con Approvazione ---\n");
 
-        /// Rimuovi un reperto
-        pub fn rimuovi(&mut self, id: u32) -> Result<Reperto, ErroreInventario> {
-            self.reperti
-                .remove(&id)
-                .ok_or(ErroreInventario::RepertoNonTrovato(id))
-        }
+    if let Err(e) = dimostra_rimozioni_approvate(&mut inv) {
+        println!("  Errore: {}", e);
+    }
 
-        /// Aggiungi una nota a un reperto
-        pub fn aggiungi_nota(&mut self, id: u32, nota: &str) -> Result<(), ErroreInventario> {
-            let reperto = self.reperti
-                .get_mut(&id)
-                .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
-            reperto.note.push(nota.to_string());
-            Ok(())
-        }
+    println!("\n--- Fase 38: Reperti Radiati ---\n");
 
-        /// Tutti i reperti
-        pub fn tutti(&self) -> Vec<&Reperto> {
-            let mut reperti: Vec<_> = self.reperti.values().collect();
-            reperti.sort_by_key(|r| r.id);
-            reperti
-        }
+    if let Err(e) = dimostra_radiazione(&mut inv) {
+        println!("  Errore: {}", e);
+    }
 
-        /// Numero totale di reperti
-        pub fn totale(&self) -> usize {
-            self.reperti.len()
-        }
+    println!("\n--- Fase 39: Estremi Autorizzativi dello Scavo ---\n");
 
-        /// Serializza l'inventario in JSON
-        pub fn to_json(&self) -> Result<String, serde_json::Error> {
-            let reperti: Vec<&Reperto> = self.tutti();
-            serde_json::to_string_pretty(&reperti)
-        }
+    if let Err(e) = dimostra_estremi_scavo(&mut inv) {
+        println!("  Errore: {}", e);
     }
-}
-
-// ============================================================================
-// MODULO: STATISTICHE
-// ============================================================================
-mod statistiche {
-    use super::modelli::*;
-    use std::collections::HashMap;
-
-    pub struct ReportStatistiche {
-        pub totale_reperti: usize,
-        pub per_materiale: HashMap<String, usize>,
-        pub per_periodo: HashMap<String, usize>,
-        pub per_sito: HashMap<String, usize>,
-        pub per_conservazione: HashMap<String, usize>,
-        pub peso_medio: Option<f64>,
-        pub peso_totale: f64,
-        pub punteggio_conservazione_medio: f64,
-    }
-
-    pub fn genera_report(reperti: &[&Reperto]) -> ReportStatistiche {
-        let mut per_materiale: HashMap<String, usize> = HashMap::new();
-        let mut per_periodo: HashMap<String, usize> = HashMap::new();
-        let mut per_sito: HashMap<String, usize> = HashMap::new();
-        let mut per_conservazione: HashMap<String, usize> = HashMap::new();
-
-        let mut peso_totale = 0.0;
-        let mut count_peso = 0;
-        let mut somma_conservazione = 0u32;
-
-        for reperto in reperti {
-            *per_materiale
-                .entry(format!("{}", reperto.materiale))
-                .or_insert(0) += 1;
-            *per_periodo
-                .entry(format!("{}", reperto.periodo))
-                .or_insert(0) += 1;
-            *per_sito
-                .entry(reperto.sito.clone())
-                .or_insert(0) += 1;
-            *per_conservazione
-                .entry(format!("{}", reperto.conservazione))
-                .or_insert(0) += 1;
-
-            if let Some(peso) = reperto.misurazioni.peso_grammi {
-                peso_totale += peso;
-                count_peso += 1;
-            }
-
-            somma_conservazione += reperto.conservazione.punteggio() as u32;
-        }
-
-        let peso_medio = if count_peso > 0 {
-            Some(peso_totale / count_peso as f64)
-        } else {
-            None
-        };
 
-        let punteggio_conservazione_medio = if !reperti.is_empty() {
-            somma_conservazione as f64 / reperti.len() as f64
-        } else {
-            0.0
-        };
+    println!("\n--- Fase 40: Tesauri ---\n");
 
-        ReportStatistiche {
-            totale_reperti: reperti.len(),
-            per_materiale,
-            per_periodo,
-            per_sito,
-            per_conservazione,
-            peso_medio,
-            peso_totale,
-            punteggio_conservazione_medio,
-        }
+    if let Err(e) = dimostra_tesauri(&inv) {
+        println!("  Errore: {}", e);
     }
 
-    pub fn stampa_report(report: &ReportStatistiche) {
-        println!("╔═══════════════════════════════════════════════════════╗");
-        println!("║            STATISTICHE INVENTARIO                    ║");
-        println!("╠═══════════════════════════════════════════════════════╣");
-        println!("║  Totale reperti: {:>4}                                ║", report.totale_reperti);
-        println!("║  Peso totale: {:>8.0}g                              ║", report.peso_totale);
-        if let Some(medio) = report.peso_medio {
-            println!("║  Peso medio:  {:>8.1}g                              ║", medio);
-        }
-        println!("║  Conservazione media: {:.1}/5                          ║",
-            report.punteggio_conservazione_medio);
-        println!("╠═══════════════════════════════════════════════════════╣");
+    println!("\n--- Fase 41: Collegamento al Getty AAT ---\n");
 
-        println!("║  PER MATERIALE:                                      ║");
-        let mut materiali: Vec<_> = report.per_materiale.iter().collect();
-        materiali.sort_by(|a, b| b.1.cmp(a.1));
-        for (mat, count) in &materiali {
-            let barre = "#".repeat(*count * 2);
-            println!("║    {:<15} {:>3} {:<20}       ║", mat, count, barre);
-        }
+    if let Err(e) = dimostra_aat() {
+        println!("  Errore: {}", e);
+    }
 
-        println!("╠═══════════════════════════════════════════════════════╣");
-        println!("║  PER PERIODO:                                        ║");
-        let mut periodi: Vec<_> = report.per_periodo.iter().collect();
-        periodi.sort_by(|a, b| b.1.cmp(a.1));
-        for (per, count) in &periodi {
-            println!("║    {:<40} {:>3}  ║", per, count);
-        }
+    println!("\n--- Fase 42: Deposito del Dataset su Zenodo ---\n");
 
-        println!("╠═══════════════════════════════════════════════════════╣");
-        println!("║  PER SITO:                                           ║");
-        let mut siti: Vec<_> = report.per_sito.iter().collect();
-        siti.sort_by(|a, b| b.1.cmp(a.1));
-        for (sito, count) in &siti {
-            println!("║    {:<40} {:>3}  ║", sito, count);
-        }
+    if let Err(e) = dimostra_deposito(&inv) {
+        println!("  Errore: {}", e);
+    }
 
-        println!("╠═══════════════════════════════════════════════════════╣");
-        println!("║  PER CONSERVAZIONE:                                  ║");
-        let mut conservazione: Vec<_> = report.per_conservazione.iter().collect();
-        conservazione.sort_by(|a, b| b.1.cmp(a.1));
-        for (stato, count) in &conservazione {
-            println!("║    {:<15} {:>3}                                  ║", stato, count);
+    println!("\n--- Fase 43: Citazioni ---\n");
+
+    let oggi = chrono::Local::now().date_naive();
+    let mut dataset = metadati_dataset(oggi);
+    dataset.doi = Some("10.5281/zenodo.1234567".to_string());
+    let citazioni = citazioni::Citazioni::nuovo(&inv, &dataset);
+    let primo = inv.tutti_compresi_radiati().first().map_or(1, |r| r.id);
+    for (stile, nome) in [(citazioni::Stile::Apa, "APA"), (citazioni::Stile::Chicago, "Chicago")] {
+        for id in [None, Some(primo)] {
+            match citazioni.cita(id, stile) {
+                Ok(testo) => println!("  {:<8} {}", nome, testo),
+                Err(e) => println!("  Errore: {}", e),
+            }
         }
+    }
+    match citazioni.cita(Some(primo), citazioni::Stile::Bibtex) {
+        Ok(testo) => println!("\n{}", testo),
+        Err(e) => println!("  Errore: {}", e),
+    }
+    if let Err(e) = citazioni.cita(Some(999), citazioni::Stile::Apa) {
+        println!("\n  {}", e);
+    }
 
-        println!("╚═══════════════════════════════════════════════════════╝");
+    println!("\n--- Fase 44: Metadati AO-Cat per ARIADNE ---\n");
+
+    if let Err(e) = dimostra_ao_cat(&inv) {
+        println!("  Errore: {}", e);
     }
-}
 
-// ============================================================================
-// MAIN - DIMOSTRAZIONE COMPLETA
-// ============================================================================
+    println!("\n--- Fase 45: Grafo CIDOC-CRM su Triple Store ---\n");
 
-use modelli::*;
-use errori::ErroreInventario;
-use inventario::Inventario;
+    if let Err(e) = dimostra_sparql(&inv) {
+        println!("  Errore: {}", e);
+    }
 
-fn main() {
-    println!("╔══════════════════════════════════════════════════════════╗");
-    println!("║   CAPITOLO 9: PROGETTO FINALE                           ║");
-    println!("║   Gestore di Inventario Archeologico                     ║");
-    println!("╚══════════════════════════════════════════════════════════╝\n");
+    println!("\n--- Fase 46: Bibliografia da Crossref e iD ORCID ---\n");
+
+    if let Err(e) = dimostra_riferimenti(&inv) {
+        println!("  Errore: {}", e);
+    }
 
     // ========================================================================
-    // FASE 1: Creazione dell'inventario
+    // RIEPILOGO
     // ========================================================================
-    println!("--- Fase 1: Popolamento Inventario ---\n");
+    println!("\n--- Riepilogo del Progetto ---\n");
 
-    let mut inv = Inventario::nuovo();
+    println!("Questo progetto ha utilizzato:");
+    println!("  Cap 1 - Variabili, funzioni, cicli, formattazione");
+    println!("  Cap 2 - Ownership (&, &mut, clone) in tutte le funzioni");
+    println!("  Cap 3 - Struct (Reperto, Misurazioni), Enum (Materiale, Periodo)");
+    println!("  Cap 4 - Result<T,E>, ErroreInventario, operatore ?");
+    println!("  Cap 5 - Vec, HashMap, String, iteratori (filter, map, fold)");
+    println!("  Cap 6 - Display trait, Serialize/Deserialize, From trait");
+    println!("  Cap 7 - Moduli (modelli, errori, inventario, statistiche)");
+    println!("  Cap 8 - (La concorrenza si applica in server/analisi parallela)");
+
+    schermo::completato(9);
+    println!("   Congratulazioni, hai completato il tutorial!");
+    println!("   Ora sei pronto per costruire applicazioni reali in Rust.");
+}
+
+// ============================================================================
+// FUNZIONI HELPER
+// ============================================================================
 
-    // Reperti del ripostiglio di Savignano
-    let reperti_da_inserire = vec![
+/// Reperti del ripostiglio di Savignano e di altri siti, usati nella demo
+fn reperti_di_esempio() -> Vec<Reperto> {
+    vec![
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Ascia a margini rialzati tipo Savignano".to_string(),
             descrizione: "Ascia in bronzo con margini rialzati e tallone distinto".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoFinale,
+            classe_funzionale: Some(ClasseFunzionale::Utensile),
+            tipo: Some("Ascia a margini rialzati tipo Savignano".to_string()),
+            decorazioni: vec![],
             conservazione: Conservazione::Buono,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: Some(Coordinate { latitudine: 41.2247, longitudine: 15.1788 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(18.5, 4.2, 2.1).con_peso(350.0),
-            note: vec!["Patina verde uniforme".to_string()],
+            note: vec!["Patina verde uniforme".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Ascia a tallone tipo appenninico".to_string(),
             descrizione: "Ascia con tallone sviluppato e lama espansa".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoFinale,
+            classe_funzionale: Some(ClasseFunzionale::Utensile),
+            tipo: Some("Ascia a tallone tipo appenninico".to_string()),
+            decorazioni: vec![],
             conservazione: Conservazione::Integro,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: Some(Coordinate { latitudine: 41.2247, longitudine: 15.1788 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(21.0, 5.5, 2.8).con_peso(480.0),
             note: vec![],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Spada tipo Allerona".to_string(),
             descrizione: "Spada con lingua da presa e lama a foglia".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![
+                Componente::parte("lama e lingua da presa", Materiale::Bronzo, Some(90.0)),
+                Componente::parte("guancette dell'impugnatura", Materiale::Osso, Some(10.0)),
+            ],
             periodo: Periodo::BronzoFinale,
+            classe_funzionale: Some(ClasseFunzionale::Arma),
+            tipo: Some("Spada tipo Allerona".to_string()),
+            decorazioni: vec![],
             conservazione: Conservazione::Discreto,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: Some(Coordinate { latitudine: 41.2247, longitudine: 15.1788 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(65.0, 5.0, 1.5).con_peso(850.0),
-            note: vec!["Lama con segni di utilizzo".to_string(), "Punta spezzata".to_string()],
+            note: vec!["Lama con segni di utilizzo".into(), "Punta spezzata".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Pugnale a lingua da presa".to_string(),
             descrizione: "Pugnale con manico a lingua e rivetti".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoRecente,
+            classe_funzionale: Some(ClasseFunzionale::Arma),
+            tipo: None,
+            decorazioni: vec![],
             conservazione: Conservazione::Buono,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: None,
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(28.0, 4.0, 1.0).con_peso(280.0),
             note: vec![],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Fibula ad arco serpeggiante".to_string(),
             descrizione: "Fibula in bronzo con arco a serpentina".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::PrimaEtaFerro,
+            classe_funzionale: Some(ClasseFunzionale::Ornamento),
+            tipo: None,
+            decorazioni: vec![
+                Decorazione { tecnica: TecnicaDecorativa::Incisione, motivo: MotivoDecorativo::Linee, posizione: PosizioneDecorazione::Arco },
+            ],
             conservazione: Conservazione::Integro,
+            completo_stimato_percento: None,
             sito: "Pontecagnano".to_string(),
             coordinate: Some(Coordinate { latitudine: 40.6435, longitudine: 14.8715 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(8.5, 3.0, 2.0).con_peso(45.0),
-            note: vec!["Ardiglione integro".to_string()],
+            note: vec!["Ardiglione integro".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Punta di lancia a fiamma".to_string(),
             descrizione: "Punta di lancia con lama a fiamma e cannone".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoRecente,
+            classe_funzionale: Some(ClasseFunzionale::Arma),
+            tipo: None,
+            decorazioni: vec![],
             conservazione: Conservazione::Frammentario,
+            completo_stimato_percento: Some(70.0),
             sito: "Toppo Daguzzo".to_string(),
             coordinate: None,
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(22.0, 4.5, 3.0).con_peso(150.0),
-            note: vec!["Cannone fratturato".to_string()],
+            note: vec!["Cannone fratturato".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Anello a cerchio".to_string(),
             descrizione: "Anello in bronzo con sezione circolare".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoFinale,
+            classe_funzionale: Some(ClasseFunzionale::Ornamento),
+            tipo: None,
+            decorazioni: vec![],
             conservazione: Conservazione::Integro,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: Some(Coordinate { latitudine: 41.2247, longitudine: 15.1788 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(3.0, 3.0, 0.5).con_peso(25.0),
             note: vec![],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Frammento di vaso a impasto".to_string(),
             descrizione: "Frammento di parete con decorazione a cordoni".to_string(),
-            materiale: Materiale::Ceramica,
+            componenti: vec![Componente::intero(Materiale::Ceramica)],
             periodo: Periodo::BronzoMedio,
+            classe_funzionale: None,
+            tipo: None,
+            decorazioni: vec![
+                Decorazione { tecnica: TecnicaDecorativa::Applicazione, motivo: MotivoDecorativo::Cordoni, posizione: PosizioneDecorazione::Parete },
+            ],
             conservazione: Conservazione::Frammentario,
+            completo_stimato_percento: Some(8.0),
             sito: "Toppo Daguzzo".to_string(),
             coordinate: None,
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(8.0, 6.0, 0.8).con_peso(95.0),
-            note: vec!["Decorazione a cordoni plastici".to_string()],
+            note: vec!["Decorazione a cordoni plastici".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Rasoio lunato".to_string(),
             descrizione: "Rasoio in bronzo a forma di mezzaluna".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::PrimaEtaFerro,
+            classe_funzionale: Some(ClasseFunzionale::Utensile),
+            tipo: None,
+            decorazioni: vec![
+                Decorazione { tecnica: TecnicaDecorativa::Incisione, motivo: MotivoDecorativo::Barca, posizione: PosizioneDecorazione::Lama },
+                Decorazione { tecnica: TecnicaDecorativa::Punzonatura, motivo: MotivoDecorativo::Cerchielli, posizione: PosizioneDecorazione::Lama },
+            ],
             conservazione: Conservazione::Discreto,
+            completo_stimato_percento: None,
             sito: "Pontecagnano".to_string(),
             coordinate: Some(Coordinate { latitudine: 40.6435, longitudine: 14.8715 }),
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(12.0, 8.0, 0.3).con_peso(65.0),
             note: vec![],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
         Reperto {
             id: 0,
+            identificativo: None,
             nome: "Falce in bronzo".to_string(),
             descrizione: "Falce con innesto a codolo".to_string(),
-            materiale: Materiale::Bronzo,
+            componenti: vec![Componente::intero(Materiale::Bronzo)],
             periodo: Periodo::BronzoRecente,
+            classe_funzionale: Some(ClasseFunzionale::Utensile),
+            tipo: None,
+            decorazioni: vec![],
             conservazione: Conservazione::Pessimo,
+            completo_stimato_percento: None,
             sito: "Savignano Irpino".to_string(),
             coordinate: None,
+            origine_coordinate: None,
+            rinvenuto_il: None,
+            posizione_scavo: None,
             misurazioni: Misurazioni::nuove().con_dimensioni(25.0, 3.5, 0.5).con_peso(180.0),
-            note: vec!["Fortemente ossidata".to_string(), "Codolo frammentato".to_string()],
+            note: vec!["Fortemente ossidata".into(), "Codolo frammentato".into()],
+            stato: StatoCatalogazione::InIngresso,
+            allegati: vec![],
+            diritti: None,
+            provenienza: vec![],
+            collezione: None,
+            rinvenuto_da: None,
+            collocazione: None,
+            stime: vec![],
+            controlli: vec![],
+            movimenti: vec![],
+            interventi: vec![],
+            osservazioni: vec![],
+            versione: 0,
+            radiazione: None,
+            campagna_scavo: None,
+            bibliografia: Vec::new(),
         },
-    ];
+    ]
+}
 
-    for reperto in reperti_da_inserire {
-        match inv.aggiungi(reperto) {
-            Ok(id) => println!("  Aggiunto reperto ID #{}", id),
-            Err(e) => println!("  ERRORE: {}", e),
-        }
+fn dimostra_allegati(inv: &mut Inventario) -> Result<(), ErroreInventario> {
+    use std::fs;
+
+    // Simuliamo un archivio fotografico in una cartella temporanea
+    let archivio = std::env::temp_dir().join("bronzeaxe_demo_foto");
+    let nuovo_disco = std::env::temp_dir().join("bronzeaxe_demo_foto_nuovo");
+    let _ = fs::remove_dir_all(&archivio);
+    let _ = fs::remove_dir_all(&nuovo_disco);
+    fs::create_dir_all(&archivio)?;
+
+    // Immagini sintetiche al posto delle foto vere
+    let foto_finta = |tono: u8| {
+        image::RgbImage::from_fn(1200, 800, move |x, y| {
+            image::Rgb([tono, (x % 256) as u8, (y % 256) as u8])
+        })
+    };
+
+    for (id, nome_file) in [(1, "ascia_001.png"), (2, "ascia_002.png"), (3, "spada_003.png")] {
+        let percorso = archivio.join(nome_file);
+        foto_finta(id as u8 * 60).save(&percorso)?;
+        inv.aggiungi_allegato(id, media::allega(percorso)?)?;
     }
-
-    println!("\n  Totale reperti inseriti: {}", inv.totale());
-
-    // ========================================================================
-    // FASE 2: Ricerche
-    // ========================================================================
-    println!("\n--- Fase 2: Ricerche ---\n");
-
-    // Per ID
-    print_search_result("Ricerca ID #3", || {
-        inv.cerca_per_id(3).map(|r| format!("  {}", r))
-    });
-
-    // ID non esistente
-    print_search_result("Ricerca ID #99", || {
-        inv.cerca_per_id(99).map(|r| format!("  {}", r))
-    });
-
-    // Per nome
-    println!("Ricerca nome 'ascia':");
-    for r in inv.cerca_per_nome("ascia") {
-        println!("  {}", r);
+    println!("  Allegate 3 foto in {}", archivio.display());
+    if let Some(miniature) = inv.cerca_per_id(1)?.allegati[0].miniature.as_ref() {
+        println!("    Miniature #1: {} / {}", miniature.piccola, miniature.media);
     }
 
-    // Per materiale
-    println!("\nReperti in Ceramica:");
-    for r in inv.cerca_per_materiale(&Materiale::Ceramica) {
-        println!("  {}", r);
+    // Modello 3D da fotogrammetria (intestazione GLB minima)
+    let glb = archivio.join("ascia_001.glb");
+    fs::write(&glb, b"glTF\x02\x00\x00\x00")?;
+    let modello = media::allega_modello_3d(&glb, 182_340, Some("scala 5 cm".to_string()), "Metashape 2.1")?;
+    if let Some(html) = media::html_modello_3d(&modello) {
+        println!("    Embed catalogo: {}", html);
     }
+    inv.aggiungi_allegato(1, modello)?;
 
-    // Per periodo
-    println!("\nReperti Bronzo Finale:");
-    for r in inv.cerca_per_periodo(&Periodo::BronzoFinale) {
-        println!("  {}", r);
+    let stl = archivio.join("spada_003.stl");
+    fs::write(&stl, "solid spada")?;
+    if let Err(e) = media::allega_modello_3d(&stl, 5_000, None, "Meshroom") {
+        println!("    Rifiutato: {}", e);
     }
 
-    // Per sito
-    println!("\nReperti da Pontecagnano:");
-    for r in inv.cerca_per_sito("pontecagnano") {
-        println!("  {}", r);
+    // Relazione di scavo in PDF
+    let relazione = archivio.join("relazione_savignano_2023.pdf");
+    fs::write(&relazione, "%PDF-1.7\n% relazione preliminare")?;
+    inv.aggiungi_allegato(1, media::allega_documento(&relazione, CategoriaDocumento::RelazioneScavo)?)?;
+    println!("    Documento allegato: {}", relazione.display());
+
+    // Diritti: la scheda #1 e pubblica, la foto della #2 e sotto embargo
+    // fino alla pubblicazione della monografia
+    inv.cerca_per_id_mut(1)?.diritti = Some(Diritti {
+        licenza: Licenza::CcBy,
+        titolare: "Soprintendenza ABAP Avellino".to_string(),
+        embargo_fino_al: None,
+    });
+    inv.cerca_per_id_mut(2)?.allegati[0].diritti = Some(Diritti {
+        licenza: Licenza::TuttiIDirittiRiservati,
+        titolare: "Universita di Napoli".to_string(),
+        embargo_fino_al: NaiveDate::from_ymd_opt(2027, 1, 1),
+    });
+    for id in [1, 2] {
+        let r = inv.cerca_per_id(id)?;
+        let diritti_foto = r.allegati[0].diritti.as_ref().map(|d| d.to_string());
+        println!(
+            "    #{} scheda: {} / foto: {}",
+            id,
+            r.diritti.as_ref().map(|d| d.to_string()).unwrap_or("-".to_string()),
+            diritti_foto.unwrap_or("-".to_string())
+        );
     }
-
-    // ========================================================================
-    // FASE 3: Operazioni sull'inventario
-    // ========================================================================
-    println!("\n--- Fase 3: Operazioni ---\n");
-
-    // Aggiungi note
-    match inv.aggiungi_nota(1, "Analisi XRF completata: Cu 88%, Sn 12%") {
-        Ok(()) => println!("  Nota aggiunta al reperto #1"),
-        Err(e) => println!("  Errore: {}", e),
+    let oggi = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+    let completo = inv.to_json()?;
+    let pubblico = inv.to_json_pubblicabile(oggi)?;
+    println!(
+        "    Export pubblico al {}: {} allegati su {}",
+        oggi,
+        pubblico.matches("impronta_sha256").count(),
+        completo.matches("impronta_sha256").count()
+    );
+
+    // Una foto viene ritoccata, un'altra cancellata per errore
+    foto_finta(10).save(archivio.join("ascia_002.png"))?;
+    fs::remove_file(archivio.join("spada_003.png"))?;
+
+    println!("  Verifica allegati:");
+    for esito in media::verifica(inv) {
+        println!("    #{} {} -> {:?}", esito.id_reperto, esito.percorso.display(), esito.problema);
     }
 
-    // Mostra reperto con note
+    // L'archivio viene spostato su un nuovo disco: aggiorniamo i percorsi
+    fs::rename(&archivio, &nuovo_disco)?;
+    let aggiornati = media::riloca(inv, &archivio, &nuovo_disco);
+    println!("  Rilocati {} allegati in {}", aggiornati, nuovo_disco.display());
     if let Ok(reperto) = inv.cerca_per_id(1) {
-        println!("  Reperto #1 - Note:");
-        for nota in &reperto.note {
-            println!("    - {}", nota);
+        for allegato in &reperto.allegati {
+            println!("    #1 {}", allegato);
         }
     }
+    println!("  Problemi dopo lo spostamento: {}", media::verifica(inv).len());
 
-    // Rimuovi un reperto
-    match inv.rimuovi(10) {
-        Ok(rimosso) => println!("\n  Rimosso: {}", rimosso.nome),
-        Err(e) => println!("\n  Errore rimozione: {}", e),
-    }
-    println!("  Totale dopo rimozione: {}", inv.totale());
-
-    // ========================================================================
-    // FASE 4: Statistiche
-    // ========================================================================
-    println!("\n--- Fase 4: Statistiche ---\n");
-
-    let tutti = inv.tutti();
-    let report = statistiche::genera_report(&tutti);
-    statistiche::stampa_report(&report);
-
-    // ========================================================================
-    // FASE 5: Analisi avanzate con iteratori
-    // ========================================================================
-    println!("\n--- Fase 5: Analisi Avanzate ---\n");
-
-    // Reperto piu pesante
-    let piu_pesante = inv.tutti().into_iter()
-        .filter(|r| r.misurazioni.peso_grammi.is_some())
-        .max_by(|a, b| {
-            a.misurazioni.peso_grammi.unwrap()
-                .partial_cmp(&b.misurazioni.peso_grammi.unwrap())
-                .unwrap()
-        });
-
-    if let Some(r) = piu_pesante {
-        println!("  Reperto piu pesante: {} ({:.0}g)",
-            r.nome, r.misurazioni.peso_grammi.unwrap());
+    let (elaborate, errori) = media::rigenera_miniature(inv);
+    println!("  Miniature rigenerate: {} (errori: {})", elaborate, errori.len());
+    for (percorso, errore) in &errori {
+        println!("    {} -> {}", percorso.display(), errore);
     }
 
-    // Reperto piu leggero
-    let piu_leggero = inv.tutti().into_iter()
-        .filter(|r| r.misurazioni.peso_grammi.is_some())
-        .min_by(|a, b| {
-            a.misurazioni.peso_grammi.unwrap()
-                .partial_cmp(&b.misurazioni.peso_grammi.unwrap())
-                .unwrap()
-        });
+    fs::remove_dir_all(&nuovo_disco)?;
+    Ok(())
+}
 
-    if let Some(r) = piu_leggero {
-        println!("  Reperto piu leggero: {} ({:.0}g)",
-            r.nome, r.misurazioni.peso_grammi.unwrap());
+fn dimostra_persone(inv: &mut Inventario) -> Result<(), ErroreInventario> {
+    let direttrice = inv.registra_persona("Anna Greco", vec![RuoloPersona::Archeologo])?;
+    let restauratore = inv.registra_persona("Marco Esposito", vec![RuoloPersona::Restauratore])?;
+    let disegnatrice = inv.registra_persona(
+        "Sara Colombo",
+        vec![RuoloPersona::Disegnatore, RuoloPersona::Fotografo],
+    )?;
+    for p in inv.persone() {
+        println!("  #{} {}", p.id, p);
     }
 
-    // Distribuzione pesi per periodo
-    println!("\n  Peso medio per periodo:");
-    let mut pesi_per_periodo: HashMap<String, (f64, usize)> = HashMap::new();
-    for r in inv.tutti() {
-        if let Some(peso) = r.misurazioni.peso_grammi {
-            let entry = pesi_per_periodo
-                .entry(format!("{}", r.periodo))
-                .or_insert((0.0, 0));
-            entry.0 += peso;
-            entry.1 += 1;
-        }
+    for id in [1, 2, 3, 7] {
+        inv.attribuisci_rinvenimento(id, direttrice)?;
     }
-    let mut periodi_ordinati: Vec<_> = pesi_per_periodo.iter().collect();
-    periodi_ordinati.sort_by(|a, b| {
-        let media_a = a.1.0 / a.1.1 as f64;
-        let media_b = b.1.0 / b.1.1 as f64;
-        media_b.partial_cmp(&media_a).unwrap()
-    });
-    for (periodo, (totale, count)) in &periodi_ordinati {
-        let media = totale / *count as f64;
-        println!("    {}: {:.0}g (media su {} reperti)", periodo, media, count);
+    inv.aggiungi_nota_di(3, restauratore, "Consolidata con resina acrilica")?;
+    inv.aggiungi_nota_di(1, disegnatrice, "Disegno in scala 1:1 completato")?;
+    for allegato in &mut inv.cerca_per_id_mut(1)?.allegati {
+        allegato.autore = Some(disegnatrice);
     }
 
-    // Reperti con coordinate
-    let con_coordinate: Vec<_> = inv.tutti().into_iter()
-        .filter(|r| r.coordinate.is_some())
-        .collect();
-    println!("\n  Reperti con coordinate GPS: {}/{}", con_coordinate.len(), inv.totale());
-
-    // Volume approssimativo
-    println!("\n  Volumi approssimativi:");
-    for r in inv.tutti() {
-        if let Some(vol) = r.misurazioni.volume_approssimativo() {
-            println!("    {}: {:.1} cm3", r.nome, vol);
-        }
+    // Un'attribuzione a una persona inesistente viene rifiutata
+    if let Err(e) = inv.attribuisci_rinvenimento(4, 99) {
+        println!("  Rifiutato: {}", e);
     }
 
-    // ========================================================================
-    // FASE 6: Esportazione JSON
-    // ========================================================================
-    println!("\n--- Fase 6: Esportazione JSON ---\n");
+    println!();
+    persone::stampa_report(inv);
+    Ok(())
+}
 
-    match inv.to_json() {
-        Ok(json) => {
-            // Mostra solo le prime righe per non inondare l'output
-            let righe: Vec<&str> = json.lines().collect();
-            let max_righe = 20;
-            for riga in righe.iter().take(max_righe) {
-                println!("  {}", riga);
-            }
-            if righe.len() > max_righe {
-                println!("  ... ({} righe totali)", righe.len());
-            }
-            println!("\n  JSON generato con successo ({} bytes)", json.len());
-        }
-        Err(e) => println!("  Errore esportazione: {}", e),
-    }
+fn dimostra_campioni(inv: &mut Inventario) -> Result<(), ErroreInventario> {
+    use campioni::{StatoCampione, TipoCampione};
+    let data = |a, m, g| NaiveDate::from_ymd_opt(a, m, g).unwrap();
 
-    // ========================================================================
-    // RIEPILOGO
-    // ========================================================================
-    println!("\n--- Riepilogo del Progetto ---\n");
+    let xrf = inv.preleva_campione(TipoCampione::Metallo, Some(1), None, data(2024, 2, 5), "Lab. archeometria")?;
+    let terreno = inv.preleva_campione(TipoCampione::Terreno, None, Some("US 104"), data(2024, 2, 5), "Lab. pedologia")?;
+    let residui = inv.preleva_campione(TipoCampione::Organico, Some(8), Some("US 210"), data(2024, 3, 1), "Lab. chimica organica")?;
 
-    println!("Questo progetto ha utilizzato:");
-    println!("  Cap 1 - Variabili, funzioni, cicli, formattazione");
-    println!("  Cap 2 - Ownership (&, &mut, clone) in tutte le funzioni");
-    println!("  Cap 3 - Struct (Reperto, Misurazioni), Enum (Materiale, Periodo)");
-    println!("  Cap 4 - Result<T,E>, ErroreInventario, operatore ?");
-    println!("  Cap 5 - Vec, HashMap, String, iteratori (filter, map, fold)");
-    println!("  Cap 6 - Display trait, Serialize/Deserialize, From trait");
-    println!("  Cap 7 - Moduli (modelli, errori, inventario, statistiche)");
-    println!("  Cap 8 - (La concorrenza si applica in server/analisi parallela)");
+    inv.invia_campione(xrf, "Universita di Bologna - XRF", data(2024, 2, 12))?;
+    inv.registra_esito(xrf, "Cu 88.1%, Sn 11.4%, Pb 0.3%", data(2024, 4, 3))?;
+    inv.invia_campione(terreno, "CNR-ISPC", data(2024, 2, 20))?;
 
-    println!("\n✅ Capitolo 9 completato! Congratulazioni, hai completato il tutorial!");
-    println!("   Ora sei pronto per costruire applicazioni reali in Rust.");
-}
+    if let Err(e) = inv.registra_esito(residui, "tracce di grassi animali", data(2024, 5, 1)) {
+        println!("  Rifiutato: {}", e);
+    }
 
-// ============================================================================
-// FUNZIONI HELPER
-// ============================================================================
+    for c in inv.campioni() {
+        println!("  {}", c);
+    }
+    let in_attesa = inv
+        .campioni()
+        .filter(|c| matches!(c.stato, StatoCampione::Inviato { .. }))
+        .count();
+    println!("  In attesa di risultati: {}", in_attesa);
+    println!("  Campioni dal reperto #1: {}", inv.campioni_di(1).len());
+    Ok(())
+}
 
 fn print_search_result<F>(label: &str, f: F)
 where
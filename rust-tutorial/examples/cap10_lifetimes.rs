@@ -0,0 +1,364 @@
+// ============================================================================
+// CAPITOLO 10: LIFETIMES
+// ============================================================================
+// Nel capitolo 2 abbiamo visto che un riferimento non puo vivere piu a lungo
+// del valore a cui punta. I lifetimes sono il modo in cui Rust ragiona su
+// questa regola: un nome (`'a`) per "il tempo durante il quale un riferimento
+// e valido". Nel capitolo 7 `filtra_significativi` usava gia `'a` senza
+// spiegarlo: qui vediamo perche serve e quando si puo omettere.
+//
+// Esegui con: cargo run --example cap10_lifetimes
+// ============================================================================
+
+use std::collections::HashMap;
+
+use rust_tutorial::schermo;
+
+/// Un reperto del catalogo. Possiede i suoi dati (String): nessun lifetime
+#[derive(Debug)]
+struct Reperto {
+    id: u32,
+    nome: String,
+    sito: String,
+    periodo: String,
+    peso_grammi: f64,
+}
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 10: LIFETIMES"]);
+
+    let catalogo = crea_catalogo();
+
+    // ========================================================================
+    // 10.1 - IL PROBLEMA: A CHI APPARTIENE IL RIFERIMENTO RESTITUITO?
+    // ========================================================================
+    println!("--- 10.1 Il Problema ---\n");
+
+    // Questa funzione riceve DUE riferimenti e ne restituisce uno.
+    // Il compilatore deve sapere: il risultato vive quanto `a` o quanto `b`?
+    //
+    //   fn piu_pesante(a: &Reperto, b: &Reperto) -> &Reperto   // ERRORE!
+    //   error[E0106]: missing lifetime specifier
+    //
+    // Con `'a` diciamo: "il risultato e valido finche sono validi ENTRAMBI".
+    let pesante = piu_pesante(&catalogo[0], &catalogo[2]);
+    println!("Tra {} e {} pesa di piu: {}", catalogo[0].nome, catalogo[2].nome, pesante.nome);
+
+    // `'a` NON cambia quanto vivono i valori: descrive un vincolo che il
+    // compilatore verifica. Ecco un caso che il compilatore rifiuta:
+    //
+    //   let risultato;
+    //   {
+    //       let temporaneo = Reperto { ... };
+    //       risultato = piu_pesante(&catalogo[0], &temporaneo);
+    //   } // <-- `temporaneo` muore qui
+    //   println!("{}", risultato.nome); // ERRORE: `temporaneo` non vive abbastanza
+    //
+    // Anche se a runtime il risultato potrebbe essere catalogo[0], la firma
+    // promette solo "vive quanto il piu breve dei due", e tanto basta.
+
+    println!();
+
+    // ========================================================================
+    // 10.2 - RESTITUIRE PARTI DEL CATALOGO (SLICE)
+    // ========================================================================
+    println!("--- 10.2 Restituire Slice del Catalogo ---\n");
+
+    // Restituire una slice evita di copiare i reperti: il chiamante ottiene
+    // una "finestra" sul catalogo, valida finche il catalogo esiste.
+    let primi = primi_n(&catalogo, 3);
+    println!("Primi {} reperti (nessuna copia):", primi.len());
+    for r in primi {
+        println!("  #{} {}", r.id, r.nome);
+    }
+
+    // Filtrare produce un Vec di riferimenti: il Vec e nuovo, i reperti no
+    let da_savignano = dello_stesso_sito(&catalogo, "Savignano");
+    println!("\nReperti da Savignano: {}", da_savignano.len());
+    for r in &da_savignano {
+        println!("  #{} {} ({} g)", r.id, r.nome, r.peso_grammi);
+    }
+
+    // Finche `da_savignano` esiste, il catalogo e in prestito: non possiamo
+    // muoverlo ne modificarlo.
+    //   drop(catalogo); // ERRORE: cannot move out of `catalogo` because it is borrowed
+    //   println!("{}", da_savignano.len());
+
+    println!();
+
+    // ========================================================================
+    // 10.3 - QUANDO SERVE UN SOLO LIFETIME E QUANDO DUE
+    // ========================================================================
+    println!("--- 10.3 Uno o Due Lifetimes ---\n");
+
+    // `cerca_per_nome` restituisce un riferimento al CATALOGO, non al testo
+    // cercato. Legando il risultato solo a `'c`, il testo della ricerca puo
+    // morire subito dopo la chiamata.
+    let trovato = {
+        let ricerca = String::from("fibula");
+        cerca_per_nome(&catalogo, &ricerca)
+    }; // `ricerca` muore qui, ma `trovato` punta al catalogo: va bene
+    match trovato {
+        Some(r) => println!("Trovato: #{} {}", r.id, r.nome),
+        None => println!("Nessun reperto trovato"),
+    }
+
+    // Se avessimo scritto `fn cerca_per_nome<'a>(catalogo: &'a [Reperto],
+    // testo: &'a str) -> Option<&'a Reperto>`, il blocco qui sopra non
+    // compilerebbe: il risultato sarebbe legato anche a `ricerca`.
+    println!("La stringa di ricerca e gia stata liberata, il risultato e ancora valido");
+
+    println!();
+
+    // ========================================================================
+    // 10.4 - STRUCT CHE CONTENGONO RIFERIMENTI
+    // ========================================================================
+    println!("--- 10.4 Struct con Riferimenti ---\n");
+
+    // Una struct che contiene un riferimento deve dichiarare il lifetime:
+    // la struct non puo vivere piu a lungo dei dati a cui punta.
+    let vetrina = Vetrina::nuova("Vetrina 3 - Armi e strumenti in bronzo", &catalogo[1..4]);
+    vetrina.stampa();
+    if let Some(r) = vetrina.piu_pesante() {
+        println!("  Pezzo forte: {} ({} g)", r.nome, r.peso_grammi);
+    }
+
+    // Un'etichetta che punta a un pezzo di testo della scheda: nessuna allocazione
+    let scheda = String::from("SAV-2019-012: Ascia a margini rialzati, tipo Savignano");
+    let etichetta = Etichetta::da_scheda(&scheda);
+    println!("\n  Codice: {}  Descrizione: {}", etichetta.codice, etichetta.descrizione);
+
+    println!();
+
+    // ========================================================================
+    // 10.5 - REGOLE DI ELISIONE
+    // ========================================================================
+    println!("--- 10.5 Regole di Elisione ---\n");
+
+    // Nella maggior parte delle funzioni non scriviamo lifetimes: il
+    // compilatore applica tre regole ("elisione") e li deduce da solo.
+    //
+    // REGOLA 1: ogni parametro riferimento riceve un proprio lifetime
+    //   fn f(a: &str, b: &str)          ==>  fn f<'a, 'b>(a: &'a str, b: &'b str)
+    //
+    // REGOLA 2: se c'e UN SOLO riferimento in ingresso, l'uscita prende il suo
+    //   fn prima_parola(s: &str) -> &str ==>  fn prima_parola<'a>(s: &'a str) -> &'a str
+    //
+    // REGOLA 3: nei metodi con &self, l'uscita prende il lifetime di self
+    //   fn nome(&self, x: &str) -> &str  ==>  l'uscita vive quanto self
+    //
+    // Se dopo le tre regole il lifetime dell'uscita e ancora ambiguo
+    // (come in `piu_pesante`), bisogna scriverlo a mano.
+
+    println!("Regola 2: sigla di \"{}\" = \"{}\"", catalogo[0].nome, sigla(&catalogo[0].nome));
+    println!("Regola 3: la vetrina si chiama \"{}\"", vetrina.titolo());
+
+    println!();
+
+    // ========================================================================
+    // 10.6 - LIFETIME NEI METODI E NEGLI ITERATORI
+    // ========================================================================
+    println!("--- 10.6 Lifetime nei Metodi e negli Iteratori ---\n");
+
+    // Un indice che presta i dati del catalogo: le chiavi sono &str che
+    // puntano dentro i reperti, quindi niente String duplicate.
+    let indice = IndicePerSito::costruisci(&catalogo);
+    for (sito, reperti) in indice.siti() {
+        let nomi: Vec<&str> = reperti.iter().map(|r| r.nome.as_str()).collect();
+        println!("  {:<22} {}", sito, nomi.join(", "));
+    }
+
+    // `impl Iterator<Item = &Reperto> + '_`: l'iteratore prende in prestito
+    // l'indice, e `'_` lo dice esplicitamente senza inventare un nome.
+    let pesanti: Vec<u32> = indice.piu_pesanti_di(200.0).map(|r| r.id).collect();
+    println!("  Reperti oltre 200 g: {:?}", pesanti);
+
+    println!();
+
+    // ========================================================================
+    // 10.7 - 'static
+    // ========================================================================
+    println!("--- 10.7 Il Lifetime 'static ---\n");
+
+    // `'static` indica dati che vivono per tutto il programma: i letterali
+    // stringa sono scritti nel binario e sono sempre `&'static str`.
+    let periodo: &'static str = periodo_canonico("bm");
+    println!("Sigla 'bm' -> {}", periodo);
+
+    // ATTENZIONE: `'static` non e la soluzione agli errori di lifetime.
+    // Se il compilatore dice che un riferimento non vive abbastanza, di
+    // solito la soluzione e restituire un valore posseduto (String, Vec)
+    // oppure ridurre la durata del prestito, non aggiungere `'static`.
+    let descrizione: String = descrivi(&catalogo[0]);
+    println!("Valore posseduto invece di un riferimento: {}", descrizione);
+
+    println!();
+
+    // ========================================================================
+    // 10.8 - RIEPILOGO
+    // ========================================================================
+    println!("--- 10.8 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "LIFETIMES IN BREVE",
+        &[
+            "- 'a non allunga la vita a nessun valore:",
+            "  descrive un vincolo che il compilatore",
+            "  verifica",
+            "- Lega l'uscita SOLO agli ingressi da cui",
+            "  proviene davvero",
+            "- Struct<'a>: la struct non sopravvive ai",
+            "  dati a cui punta",
+            "- Le 3 regole di elisione coprono quasi",
+            "  tutti i casi",
+            "- Nel dubbio, restituisci un valore",
+            "  posseduto",
+        ],
+    );
+
+    schermo::completato(10);
+}
+
+// ============================================================================
+// STRUCT CON LIFETIME
+// ============================================================================
+
+/// Una vetrina del museo mostra alcuni reperti del catalogo senza copiarli.
+/// `'a` dice che la vetrina non puo esistere piu a lungo del catalogo.
+struct Vetrina<'a> {
+    titolo: &'a str,
+    reperti: &'a [Reperto],
+}
+
+impl<'a> Vetrina<'a> {
+    fn nuova(titolo: &'a str, reperti: &'a [Reperto]) -> Self {
+        Vetrina { titolo, reperti }
+    }
+
+    /// Regola 3: l'uscita vive quanto `self` (e quindi al massimo quanto `'a`)
+    fn titolo(&self) -> &str {
+        self.titolo
+    }
+
+    /// Qui scriviamo `'a` esplicitamente: il reperto restituito appartiene
+    /// al catalogo, non alla vetrina, e puo sopravvivere alla vetrina stessa
+    fn piu_pesante(&self) -> Option<&'a Reperto> {
+        self.reperti.iter().max_by(|a, b| a.peso_grammi.total_cmp(&b.peso_grammi))
+    }
+
+    fn stampa(&self) {
+        println!("  {}", self.titolo);
+        for r in self.reperti {
+            println!("    #{} {} - {}", r.id, r.nome, r.periodo);
+        }
+    }
+}
+
+/// Etichetta ricavata da una scheda nel formato "CODICE: descrizione".
+/// Entrambi i campi puntano dentro la stessa String della scheda.
+struct Etichetta<'s> {
+    codice: &'s str,
+    descrizione: &'s str,
+}
+
+impl<'s> Etichetta<'s> {
+    fn da_scheda(scheda: &'s str) -> Self {
+        match scheda.split_once(':') {
+            Some((codice, descrizione)) => Etichetta { codice: codice.trim(), descrizione: descrizione.trim() },
+            None => Etichetta { codice: "", descrizione: scheda.trim() },
+        }
+    }
+}
+
+/// Indice dei reperti per sito: sia le chiavi sia i valori sono prestiti
+struct IndicePerSito<'a> {
+    per_sito: HashMap<&'a str, Vec<&'a Reperto>>,
+}
+
+impl<'a> IndicePerSito<'a> {
+    fn costruisci(catalogo: &'a [Reperto]) -> Self {
+        let mut per_sito: HashMap<&'a str, Vec<&'a Reperto>> = HashMap::new();
+        for r in catalogo {
+            per_sito.entry(r.sito.as_str()).or_default().push(r);
+        }
+        IndicePerSito { per_sito }
+    }
+
+    /// Siti in ordine alfabetico con i loro reperti
+    fn siti(&self) -> Vec<(&'a str, &Vec<&'a Reperto>)> {
+        let mut siti: Vec<_> = self.per_sito.iter().map(|(s, r)| (*s, r)).collect();
+        siti.sort_by_key(|(s, _)| *s);
+        siti
+    }
+
+    /// `'_` e il lifetime "anonimo": l'iteratore prende in prestito `self`
+    fn piu_pesanti_di(&self, soglia: f64) -> impl Iterator<Item = &'a Reperto> + '_ {
+        self.per_sito.values().flatten().copied().filter(move |r| r.peso_grammi > soglia)
+    }
+}
+
+// ============================================================================
+// FUNZIONI DI SUPPORTO
+// ============================================================================
+
+fn crea_catalogo() -> Vec<Reperto> {
+    let dati = [
+        (1, "Fibula ad arco semplice", "Savignano Irpino", "Bronzo Recente", 26.9),
+        (2, "Pugnale triangolare", "Toppo Daguzzo", "Bronzo Antico", 198.3),
+        (3, "Ascia a margini rialzati", "Savignano sul Panaro", "Bronzo Medio", 327.6),
+        (4, "Spada a lingua da presa", "Pontecagnano", "Bronzo Finale", 779.6),
+        (5, "Punta di lancia a cannone", "Savignano Irpino", "Prima Eta del Ferro", 142.5),
+    ];
+    dati.iter()
+        .map(|&(id, nome, sito, periodo, peso)| Reperto {
+            id,
+            nome: nome.to_string(),
+            sito: sito.to_string(),
+            periodo: periodo.to_string(),
+            peso_grammi: peso,
+        })
+        .collect()
+}
+
+/// Il risultato vive quanto il piu breve tra `a` e `b`
+fn piu_pesante<'a>(a: &'a Reperto, b: &'a Reperto) -> &'a Reperto {
+    if a.peso_grammi >= b.peso_grammi { a } else { b }
+}
+
+/// Elisione (regola 2): scrivere `<'a>` qui sarebbe superfluo
+fn primi_n(catalogo: &[Reperto], n: usize) -> &[Reperto] {
+    &catalogo[..n.min(catalogo.len())]
+}
+
+/// Due ingressi riferimento: il lifetime dell'uscita va indicato
+fn dello_stesso_sito<'a>(catalogo: &'a [Reperto], sito: &str) -> Vec<&'a Reperto> {
+    catalogo.iter().filter(|r| r.sito.starts_with(sito)).collect()
+}
+
+/// Il risultato e legato solo al catalogo (`'c`), non al testo cercato
+fn cerca_per_nome<'c>(catalogo: &'c [Reperto], testo: &str) -> Option<&'c Reperto> {
+    let testo = testo.to_lowercase();
+    catalogo.iter().find(|r| r.nome.to_lowercase().contains(&testo))
+}
+
+/// Prima parola del nome: una slice della stringa ricevuta
+fn sigla(nome: &str) -> &str {
+    nome.split_whitespace().next().unwrap_or(nome)
+}
+
+/// Testi fissi: il compilatore sa che vivono per sempre
+fn periodo_canonico(sigla: &str) -> &'static str {
+    match sigla {
+        "ba" => "Bronzo Antico",
+        "bm" => "Bronzo Medio",
+        "br" => "Bronzo Recente",
+        "bf" => "Bronzo Finale",
+        "pf" => "Prima Eta del Ferro",
+        _ => "Periodo sconosciuto",
+    }
+}
+
+/// Costruisce un testo nuovo: restituire una String evita ogni vincolo di lifetime
+fn descrivi(r: &Reperto) -> String {
+    format!("#{} {} ({}, {})", r.id, r.nome, r.sito, r.periodo)
+}
@@ -0,0 +1,347 @@
+// ============================================================================
+// CAPITOLO 11: SMART POINTER E MUTABILITA INTERNA
+// ============================================================================
+// Finora ogni valore aveva un solo owner e i prestiti seguivano le regole
+// del capitolo 2. Alcune strutture pero non si adattano a questo schema:
+// un museo e un albero (collezione -> vetrina -> reperto) in cui i figli
+// vogliono conoscere il genitore, e lo stesso reperto puo comparire in piu
+// percorsi di visita. Gli smart pointer della libreria standard risolvono questi casi:
+//
+//   Box<T>      un valore nell'heap con un solo owner
+//   Rc<T>       piu owner dello stesso valore (conteggio dei riferimenti)
+//   RefCell<T>  modifiche attraverso un riferimento condiviso, verificate a runtime
+//   Weak<T>     un riferimento che non tiene in vita il valore
+//
+// Esegui con: cargo run --example cap11_smart_pointers
+// ============================================================================
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use rust_tutorial::schermo;
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 11: SMART POINTER"]);
+
+    // ========================================================================
+    // 11.1 - BOX<T>: VALORI NELL'HEAP E TIPI RICORSIVI
+    // ========================================================================
+    println!("--- 11.1 Box<T> ---\n");
+
+    // Box mette il valore nell'heap e ne e l'unico owner: quando il Box
+    // esce dallo scope, il valore viene liberato (come una String).
+    let peso = Box::new(327.6);
+    println!("Peso nell'heap: {} g", peso);
+
+    // L'uso piu comune: i tipi ricorsivi. Una stratigrafia e una sequenza
+    // di strati, ognuno sopra il successivo. Senza Box il compilatore non
+    // potrebbe calcolare la dimensione di `Strato` (sarebbe infinita).
+    //
+    //   enum Strato { Us(u32, Strato), Sterile }   // ERRORE: recursive type has infinite size
+    let sequenza = Strato::Us(
+        101,
+        "humus",
+        Box::new(Strato::Us(102, "crollo", Box::new(Strato::Us(103, "battuto", Box::new(Strato::Sterile))))),
+    );
+    println!("Sequenza stratigrafica: {}", sequenza.descrivi());
+    println!("Profondita: {} unita stratigrafiche", sequenza.profondita());
+
+    // Box<dyn Trait> permette di mettere tipi diversi nella stessa collezione
+    let schede: Vec<Box<dyn Scheda>> = vec![
+        Box::new(Reperto::nuovo(1, "Ascia a margini rialzati")),
+        Box::new(Calco { originale: "Spada di Pontecagnano".to_string() }),
+    ];
+    for s in &schede {
+        println!("  {}", s.etichetta());
+    }
+
+    println!();
+
+    // ========================================================================
+    // 11.2 - RC<T>: PIU OWNER PER LO STESSO VALORE
+    // ========================================================================
+    println!("--- 11.2 Rc<T> ---\n");
+
+    // Lo stesso reperto compare in due percorsi di visita. Chi ne e l'owner?
+    // Con Rc entrambi: il valore vive finche esiste almeno un Rc.
+    let spada = Rc::new(Reperto::nuovo(4, "Spada a lingua da presa"));
+    println!("Dopo la creazione: {} owner", Rc::strong_count(&spada));
+
+    let percorso_armi = [Rc::clone(&spada)];
+    println!("Nel percorso 'armi': {} owner", Rc::strong_count(&spada));
+    {
+        let percorso_bronzo_finale = [Rc::clone(&spada)];
+        println!(
+            "Anche nel percorso 'Bronzo Finale': {} owner ({} reperti)",
+            Rc::strong_count(&spada),
+            percorso_bronzo_finale.len()
+        );
+    } // il secondo percorso viene distrutto: il conteggio scende
+    println!("Percorso 'Bronzo Finale' chiuso: {} owner", Rc::strong_count(&spada));
+
+    // `Rc::clone` NON copia il reperto: incrementa solo il contatore.
+    // Lo scriviamo cosi (e non `spada.clone()`) per renderlo evidente.
+    println!("Stesso reperto? {}", Rc::ptr_eq(&spada, &percorso_armi[0]));
+
+    // Rc da solo da accesso in SOLA LETTURA: piu owner non possono
+    // modificare lo stesso valore senza coordinarsi.
+    //   percorso_armi[0].nome.push_str("!"); // ERRORE: cannot borrow data in an `Rc` as mutable
+
+    println!();
+
+    // ========================================================================
+    // 11.3 - REFCELL<T>: MUTABILITA INTERNA
+    // ========================================================================
+    println!("--- 11.3 RefCell<T> ---\n");
+
+    // RefCell sposta il controllo del borrow checker a RUNTIME: da un
+    // riferimento condiviso si puo ottenere un prestito mutabile con
+    // `borrow_mut()`. Le regole restano le stesse (N lettori oppure 1
+    // scrittore), ma se vengono violate il programma va in panic.
+    let registro = RefCell::new(Vec::<String>::new());
+    annota(&registro, "Apertura sala");
+    annota(&registro, "Controllo climatico: 52% UR");
+    println!("Registro: {:?}", registro.borrow());
+
+    // `try_borrow_mut` permette di gestire il conflitto senza panic
+    let lettura = registro.borrow();
+    match registro.try_borrow_mut() {
+        Ok(_) => println!("Scrittura concessa"),
+        Err(_) => println!("Scrittura rifiutata: il registro e in lettura ({} voci)", lettura.len()),
+    }
+    drop(lettura);
+
+    // Rc<RefCell<T>>: piu owner E possibilita di modificare.
+    // E la combinazione tipica per i grafi e gli alberi.
+    let condiviso = Rc::new(RefCell::new(Reperto::nuovo(7, "Fibula ad arco")));
+    let in_vetrina = Rc::clone(&condiviso);
+    in_vetrina.borrow_mut().note.push("Restaurata nel 2024".to_string());
+    println!("Note viste dall'altro owner: {:?}", condiviso.borrow().note);
+
+    println!();
+
+    // ========================================================================
+    // 11.4 - UN ALBERO: COLLEZIONE -> VETRINA -> REPERTO
+    // ========================================================================
+    println!("--- 11.4 L'Albero del Museo ---\n");
+
+    // Ogni nodo possiede i suoi figli (Rc) e conosce il genitore (Weak).
+    let museo = Nodo::nuovo("Collezione Protostorica");
+    let vetrina_armi = Nodo::nuovo("Vetrina 1 - Armi");
+    let vetrina_ornamenti = Nodo::nuovo("Vetrina 2 - Ornamenti");
+    Nodo::aggiungi_figlio(&museo, &vetrina_armi);
+    Nodo::aggiungi_figlio(&museo, &vetrina_ornamenti);
+    for nome in ["Spada a lingua da presa", "Pugnale triangolare"] {
+        Nodo::aggiungi_figlio(&vetrina_armi, &Nodo::nuovo(nome));
+    }
+    Nodo::aggiungi_figlio(&vetrina_ornamenti, &Nodo::nuovo("Fibula ad arco semplice"));
+
+    museo.stampa(0);
+
+    // Risalire l'albero: da un reperto alla collezione
+    let pugnale = Rc::clone(&vetrina_armi.figli.borrow()[1]);
+    println!("\nPercorso del pugnale: {}", pugnale.percorso());
+
+    // Spostare un reperto da una vetrina all'altra: RefCell permette di
+    // modificare i figli anche se i nodi sono condivisi tramite Rc
+    Nodo::sposta(&pugnale, &vetrina_ornamenti);
+    println!("Dopo lo spostamento:  {}", pugnale.percorso());
+    println!(
+        "Vetrina armi: {} reperti, vetrina ornamenti: {} reperti",
+        vetrina_armi.figli.borrow().len(),
+        vetrina_ornamenti.figli.borrow().len()
+    );
+
+    println!();
+
+    // ========================================================================
+    // 11.5 - I CICLI E COME WEAK LI ROMPE
+    // ========================================================================
+    println!("--- 11.5 Cicli di Riferimenti ---\n");
+
+    // Se il figlio tenesse il genitore con un Rc, genitore e figlio si
+    // terrebbero in vita a vicenda: il conteggio non arriverebbe mai a zero
+    // e la memoria non verrebbe liberata (memory leak, senza errori!).
+    {
+        let a = Rc::new(NodoForte { nome: "Vetrina A".to_string(), altro: RefCell::new(None) });
+        let b = Rc::new(NodoForte { nome: "Vetrina B".to_string(), altro: RefCell::new(None) });
+        *a.altro.borrow_mut() = Some(Rc::clone(&b));
+        *b.altro.borrow_mut() = Some(Rc::clone(&a)); // ciclo!
+        println!("Con Rc in entrambe le direzioni: A ha {} owner, B ne ha {}", Rc::strong_count(&a), Rc::strong_count(&b));
+        // Per liberarli bisogna rompere il ciclo a mano:
+        *a.altro.borrow_mut() = None;
+    } // senza la riga precedente, nessuno dei due `Drop` verrebbe eseguito
+    println!();
+
+    // Con Weak il genitore non viene tenuto in vita dai figli
+    let reperto_orfano;
+    {
+        let vetrina_temporanea = Nodo::nuovo("Vetrina temporanea");
+        reperto_orfano = Nodo::nuovo("Ascia in prestito");
+        Nodo::aggiungi_figlio(&vetrina_temporanea, &reperto_orfano);
+        println!(
+            "Vetrina: {} owner forti, {} deboli",
+            Rc::strong_count(&vetrina_temporanea),
+            Rc::weak_count(&vetrina_temporanea)
+        );
+        println!("Percorso: {}", reperto_orfano.percorso());
+    } // la vetrina viene distrutta anche se il reperto la "conosce"
+    // `upgrade()` restituisce None: il genitore non esiste piu
+    println!(
+        "Dopo la chiusura: genitore presente? {}",
+        reperto_orfano.genitore.borrow().upgrade().is_some()
+    );
+    println!("Percorso: {}", reperto_orfano.percorso());
+
+    println!();
+
+    // ========================================================================
+    // 11.6 - RIEPILOGO
+    // ========================================================================
+    println!("--- 11.6 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "QUALE SMART POINTER?",
+        &[
+            "Box<T>         un owner, valore nell'heap",
+            "               (tipi ricorsivi, dyn Trait)",
+            "Rc<T>          piu owner, sola lettura",
+            "RefCell<T>     modifica con prestiti",
+            "               verificati a runtime",
+            "Rc<RefCell<T>> piu owner che modificano",
+            "Weak<T>        riferimento che non tiene",
+            "               in vita (genitori, cache)",
+            "",
+            "Tra thread: Arc e Mutex (capitolo 8)",
+        ],
+    );
+
+    schermo::completato(11);
+}
+
+// ============================================================================
+// TIPI DI ESEMPIO
+// ============================================================================
+
+/// Sequenza stratigrafica: tipo ricorsivo, possibile solo grazie a Box
+enum Strato {
+    Us(u32, &'static str, Box<Strato>),
+    Sterile,
+}
+
+impl Strato {
+    fn descrivi(&self) -> String {
+        match self {
+            Strato::Us(numero, descrizione, sotto) => format!("US {} ({}) > {}", numero, descrizione, sotto.descrivi()),
+            Strato::Sterile => "sterile".to_string(),
+        }
+    }
+
+    fn profondita(&self) -> usize {
+        match self {
+            Strato::Us(_, _, sotto) => 1 + sotto.profondita(),
+            Strato::Sterile => 0,
+        }
+    }
+}
+
+trait Scheda {
+    fn etichetta(&self) -> String;
+}
+
+#[derive(Debug)]
+struct Reperto {
+    id: u32,
+    nome: String,
+    note: Vec<String>,
+}
+
+impl Reperto {
+    fn nuovo(id: u32, nome: &str) -> Self {
+        Reperto { id, nome: nome.to_string(), note: Vec::new() }
+    }
+}
+
+impl Scheda for Reperto {
+    fn etichetta(&self) -> String {
+        format!("Reperto #{}: {}", self.id, self.nome)
+    }
+}
+
+struct Calco {
+    originale: String,
+}
+
+impl Scheda for Calco {
+    fn etichetta(&self) -> String {
+        format!("Calco di: {}", self.originale)
+    }
+}
+
+/// Aggiunge una voce prendendo solo un riferimento condiviso al registro
+fn annota(registro: &RefCell<Vec<String>>, voce: &str) {
+    registro.borrow_mut().push(voce.to_string());
+}
+
+// ============================================================================
+// L'ALBERO DEL MUSEO
+// ============================================================================
+
+/// Nodo dell'albero: possiede i figli (Rc) e punta al genitore (Weak).
+/// RefCell permette di cambiare figli e genitore dopo la creazione.
+struct Nodo {
+    nome: String,
+    genitore: RefCell<Weak<Nodo>>,
+    figli: RefCell<Vec<Rc<Nodo>>>,
+}
+
+impl Nodo {
+    fn nuovo(nome: &str) -> Rc<Nodo> {
+        Rc::new(Nodo { nome: nome.to_string(), genitore: RefCell::new(Weak::new()), figli: RefCell::new(Vec::new()) })
+    }
+
+    fn aggiungi_figlio(genitore: &Rc<Nodo>, figlio: &Rc<Nodo>) {
+        // Rc::downgrade crea un Weak: non incrementa il conteggio forte
+        *figlio.genitore.borrow_mut() = Rc::downgrade(genitore);
+        genitore.figli.borrow_mut().push(Rc::clone(figlio));
+    }
+
+    /// Toglie il nodo dal genitore attuale e lo aggiunge al nuovo
+    fn sposta(nodo: &Rc<Nodo>, nuovo_genitore: &Rc<Nodo>) {
+        if let Some(vecchio) = nodo.genitore.borrow().upgrade() {
+            vecchio.figli.borrow_mut().retain(|f| !Rc::ptr_eq(f, nodo));
+        }
+        Nodo::aggiungi_figlio(nuovo_genitore, nodo);
+    }
+
+    /// Risale fino alla radice seguendo i Weak
+    fn percorso(&self) -> String {
+        let mut parti = vec![self.nome.clone()];
+        let mut corrente = self.genitore.borrow().upgrade();
+        while let Some(nodo) = corrente {
+            parti.push(nodo.nome.clone());
+            corrente = nodo.genitore.borrow().upgrade();
+        }
+        parti.reverse();
+        parti.join(" > ")
+    }
+
+    fn stampa(&self, livello: usize) {
+        println!("{}{}", "  ".repeat(livello + 1), self.nome);
+        for figlio in self.figli.borrow().iter() {
+            figlio.stampa(livello + 1);
+        }
+    }
+}
+
+/// Nodo che tiene l'altro con un Rc: permette di creare un ciclo
+struct NodoForte {
+    nome: String,
+    altro: RefCell<Option<Rc<NodoForte>>>,
+}
+
+impl Drop for NodoForte {
+    fn drop(&mut self) {
+        println!("  (drop di {})", self.nome);
+    }
+}
@@ -0,0 +1,419 @@
+// ============================================================================
+// CAPITOLO 12: CLOSURE E ITERATORI AVANZATI
+// ============================================================================
+// Abbiamo gia usato closure e iteratori (`map`, `filter`, `collect`) in quasi
+// tutti i capitoli. Qui guardiamo sotto il cofano: come una closure cattura
+// le variabili (Fn, FnMut, FnOnce), come si restituisce una closure, e come
+// si scrive un iteratore da zero, fino a costruire un adattatore che si
+// aggancia a qualsiasi iteratore sui reperti dell'inventario.
+//
+// Esegui con: cargo run --example cap12_closures_iteratori
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::iter::Peekable;
+
+use rust_tutorial::schermo;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Periodo {
+    Antico,
+    Medio,
+    Recente,
+    Finale,
+}
+
+#[derive(Debug, Clone)]
+struct Reperto {
+    id: u32,
+    nome: String,
+    sito: String,
+    periodo: Periodo,
+    peso_grammi: f64,
+}
+
+/// Inventario minimo: i reperti sono privati, si leggono solo con gli iteratori
+struct Inventario {
+    reperti: BTreeMap<u32, Reperto>,
+}
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 12: CLOSURE E ITERATORI"]);
+
+    let inventario = crea_inventario();
+
+    // ========================================================================
+    // 12.1 - COME UNA CLOSURE CATTURA LE VARIABILI
+    // ========================================================================
+    println!("--- 12.1 Modi di Cattura: Fn, FnMut, FnOnce ---\n");
+
+    // Una closure "cattura" le variabili dell'ambiente nel modo meno
+    // invasivo che le basta. Il modo decide quale trait implementa:
+    //
+    //   Fn      legge le variabili catturate (prestito &)      -> chiamabile N volte
+    //   FnMut   le modifica (prestito &mut)                    -> chiamabile N volte, serve `mut`
+    //   FnOnce  le consuma (sposta l'ownership fuori)          -> chiamabile UNA volta
+
+    // Fn: legge `soglia`
+    let soglia = 150.0;
+    let e_pesante = |r: &Reperto| r.peso_grammi > soglia;
+    let pesanti = inventario.iter().filter(|r| e_pesante(r)).count();
+    println!("Fn:     {} reperti oltre {} g (soglia ancora usabile: {})", pesanti, soglia, soglia);
+
+    // FnMut: modifica `contatore`. La closure stessa deve essere `mut`.
+    let mut contatore = 0;
+    let mut conta_visita = |nome: &str| {
+        contatore += 1;
+        println!("        visita {}: {}", contatore, nome);
+    };
+    conta_visita("Spada a lingua da presa");
+    conta_visita("Fibula ad arco");
+    // Finche la closure esiste, `contatore` e in prestito mutabile:
+    //   println!("{}", contatore); // ERRORE qui, se dopo usassimo ancora conta_visita
+    println!("FnMut:  visite contate = {}", contatore);
+
+    // FnOnce: consuma `etichette` restituendola. Dopo la prima chiamata
+    // il valore catturato non c'e piu, quindi non si puo richiamare.
+    let etichette = vec!["A1".to_string(), "A2".to_string()];
+    let consegna = move || etichette;
+    let consegnate = consegna();
+    // consegna(); // ERRORE: closure cannot be invoked more than once
+    println!("FnOnce: consegnate {:?}", consegnate);
+
+    // `move` forza la cattura per valore anche se basterebbe un prestito.
+    // Serve quando la closure deve sopravvivere allo scope (thread, closure
+    // restituite). Non cambia il trait: questa closure e ancora Fn.
+    let sito = String::from("Savignano");
+    let dello_stesso_sito = move |r: &Reperto| r.sito.starts_with(&sito);
+    // println!("{}", sito); // ERRORE: `sito` e stato mosso nella closure
+    println!("move:   {} reperti da Savignano", inventario.iter().filter(|r| dello_stesso_sito(r)).count());
+
+    println!();
+
+    // ========================================================================
+    // 12.2 - FUNZIONI CHE RICEVONO CLOSURE
+    // ========================================================================
+    println!("--- 12.2 Funzioni che Ricevono Closure ---\n");
+
+    // Il bound sceglie cosa puo fare il chiamante: chiedere `Fn` e il piu
+    // restrittivo per chi scrive la closure, `FnOnce` il piu permissivo.
+    let riassunto = applica_a_ogni(&inventario, |r| format!("#{} {}", r.id, r.nome));
+    println!("Con Fn:     {}", riassunto.join(" | "));
+
+    let mut totale = 0.0;
+    inventario.per_ogni(|r| totale += r.peso_grammi);
+    println!("Con FnMut:  peso complessivo {:.1} g", totale);
+
+    let rapporto = String::from("Inventario verificato il 16/10");
+    esegui_una_volta(move || println!("Con FnOnce: {}", rapporto));
+
+    println!();
+
+    // ========================================================================
+    // 12.3 - RESTITUIRE CLOSURE
+    // ========================================================================
+    println!("--- 12.3 Restituire Closure ---\n");
+
+    // Ogni closure ha un tipo anonimo: per restituirla si usa `impl Fn`
+    // (un solo tipo concreto) oppure `Box<dyn Fn>` (tipi diversi a runtime).
+    let dal_bronzo_medio = filtro_periodo(Periodo::Medio);
+    let nomi: Vec<&str> = inventario.iter().filter(|r| dal_bronzo_medio(r)).map(|r| r.nome.as_str()).collect();
+    println!("impl Fn:       Bronzo Medio -> {:?}", nomi);
+
+    // Un filtro scelto a runtime, per esempio da un menu
+    for scelta in ["pesanti", "leggeri", "tutti"] {
+        let filtro = filtro_da_menu(scelta);
+        println!("Box<dyn Fn>:   '{}' -> {} reperti", scelta, inventario.iter().filter(|r| filtro(r)).count());
+    }
+
+    // Comporre due filtri: il risultato e a sua volta una closure
+    let pesanti_del_bronzo_medio = entrambi(filtro_periodo(Periodo::Medio), |r: &Reperto| r.peso_grammi > 200.0);
+    println!(
+        "Composizione:  Bronzo Medio e oltre 200 g -> {} reperti",
+        inventario.iter().filter(|r| pesanti_del_bronzo_medio(r)).count()
+    );
+
+    println!();
+
+    // ========================================================================
+    // 12.4 - UN ITERATORE SCRITTO DA ZERO
+    // ========================================================================
+    println!("--- 12.4 Implementare Iterator ---\n");
+
+    // Basta implementare `next()`: tutti gli altri metodi (map, filter,
+    // take, sum, ...) arrivano gratis dal trait Iterator.
+    let numeri: Vec<String> = NumeriInventario::nuovo("SAV", 2024).take(3).collect();
+    println!("Prossimi numeri di inventario: {:?}", numeri);
+
+    // Un iteratore puo essere infinito: e `take` a fermarlo. Gli
+    // iteratori sono pigri, quindi nulla viene calcolato finche non serve.
+    let decimo = NumeriInventario::nuovo("PON", 2024).nth(9);
+    println!("Il decimo numero sarebbe: {:?}", decimo);
+
+    // Iteratore finito sui quadrati di una griglia di scavo
+    let quadrati: Vec<String> = Griglia::nuova(3, 2).collect();
+    println!("Quadrati della griglia 3x2: {}", quadrati.join(" "));
+
+    println!();
+
+    // ========================================================================
+    // 12.5 - ITERARE SU UNA STRUCT: IntoIterator
+    // ========================================================================
+    println!("--- 12.5 IntoIterator per l'Inventario ---\n");
+
+    // Implementando IntoIterator per &Inventario, il ciclo for funziona
+    // direttamente sull'inventario, senza esporre la BTreeMap interna.
+    for r in &inventario {
+        println!("  #{} {:<28} {:?}", r.id, r.nome, r.periodo);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 12.6 - UN ADATTATORE SU MISURA PER I REPERTI
+    // ========================================================================
+    println!("--- 12.6 Adattatori di Iteratori ---\n");
+
+    // Un adattatore avvolge un altro iteratore e ne trasforma gli elementi,
+    // come fanno `map` e `filter`. Con un "extension trait" lo rendiamo
+    // disponibile come metodo su QUALSIASI iteratore di &Reperto.
+    let bronzo_recente: Vec<u32> = inventario.iter().del_periodo(Periodo::Recente).map(|r| r.id).collect();
+    println!("del_periodo(Recente): {:?}", bronzo_recente);
+
+    // `per_sito` raggruppa reperti consecutivi dello stesso sito: prima li
+    // ordiniamo, poi l'adattatore produce (sito, reperti) uno alla volta
+    let mut ordinati: Vec<&Reperto> = inventario.iter().collect();
+    ordinati.sort_by(|a, b| a.sito.cmp(&b.sito));
+    println!("per_sito():");
+    for (sito, reperti) in ordinati.into_iter().per_sito() {
+        let peso: f64 = reperti.iter().map(|r| r.peso_grammi).sum();
+        println!("  {:<22} {} reperti, {:>7.1} g", sito, reperti.len(), peso);
+    }
+
+    // Gli adattatori si combinano con quelli della libreria standard
+    let pesi_per_sito: Vec<(String, usize)> = inventario
+        .iter()
+        .filter(|r| r.peso_grammi > 100.0)
+        .per_sito()
+        .map(|(sito, reperti)| (sito, reperti.len()))
+        .collect();
+    println!("filter + per_sito + map: {:?}", pesi_per_sito);
+
+    println!();
+
+    // ========================================================================
+    // 12.7 - RIEPILOGO
+    // ========================================================================
+    println!("--- 12.7 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "CLOSURE E ITERATORI",
+        &[
+            "Fn      legge, chiamabile N volte",
+            "FnMut   modifica, chiamabile N volte",
+            "FnOnce  consuma, chiamabile una volta",
+            "move    cattura per valore",
+            "",
+            "impl Fn / Box<dyn Fn> per restituirle",
+            "Iterator: basta scrivere next()",
+            "IntoIterator: abilita il ciclo for",
+            "Extension trait: nuovi adattatori su tutti",
+            "gli iteratori",
+        ],
+    );
+
+    schermo::completato(12);
+}
+
+// ============================================================================
+// INVENTARIO E INTOITERATOR
+// ============================================================================
+
+impl Inventario {
+    fn iter(&self) -> std::collections::btree_map::Values<'_, u32, Reperto> {
+        self.reperti.values()
+    }
+
+    /// Chiama la closure su ogni reperto: FnMut permette di accumulare
+    fn per_ogni(&self, mut f: impl FnMut(&Reperto)) {
+        for r in self.reperti.values() {
+            f(r);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Inventario {
+    type Item = &'a Reperto;
+    type IntoIter = std::collections::btree_map::Values<'a, u32, Reperto>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.reperti.values()
+    }
+}
+
+// ============================================================================
+// FUNZIONI CHE RICEVONO E RESTITUISCONO CLOSURE
+// ============================================================================
+
+/// `F: Fn(&Reperto) -> String`: la closure viene chiamata piu volte e non modifica nulla
+fn applica_a_ogni<F>(inventario: &Inventario, f: F) -> Vec<String>
+where
+    F: Fn(&Reperto) -> String,
+{
+    inventario.iter().map(f).collect()
+}
+
+/// FnOnce: la closure puo consumare quello che ha catturato
+fn esegui_una_volta(f: impl FnOnce()) {
+    f();
+}
+
+/// `move` e obbligatorio: `periodo` e un parametro e muore alla fine della funzione
+fn filtro_periodo(periodo: Periodo) -> impl Fn(&Reperto) -> bool {
+    move |r| r.periodo == periodo
+}
+
+/// Rami diversi producono closure di tipi diversi: serve `Box<dyn Fn>`
+fn filtro_da_menu(scelta: &str) -> Box<dyn Fn(&Reperto) -> bool> {
+    match scelta {
+        "pesanti" => Box::new(|r| r.peso_grammi > 200.0),
+        "leggeri" => Box::new(|r| r.peso_grammi <= 50.0),
+        _ => Box::new(|_| true),
+    }
+}
+
+/// Combina due filtri in uno nuovo
+fn entrambi(a: impl Fn(&Reperto) -> bool, b: impl Fn(&Reperto) -> bool) -> impl Fn(&Reperto) -> bool {
+    move |r| a(r) && b(r)
+}
+
+// ============================================================================
+// ITERATORI SCRITTI DA ZERO
+// ============================================================================
+
+/// Genera numeri di inventario progressivi: "SAV-2024-0001", "SAV-2024-0002", ...
+struct NumeriInventario {
+    sigla: String,
+    anno: u32,
+    prossimo: u32,
+}
+
+impl NumeriInventario {
+    fn nuovo(sigla: &str, anno: u32) -> Self {
+        NumeriInventario { sigla: sigla.to_string(), anno, prossimo: 1 }
+    }
+}
+
+impl Iterator for NumeriInventario {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let numero = format!("{}-{}-{:04}", self.sigla, self.anno, self.prossimo);
+        self.prossimo += 1;
+        Some(numero) // mai None: l'iteratore e infinito
+    }
+}
+
+/// Quadrati di una griglia di scavo (A1, B1, C1, A2, ...), riga per riga
+struct Griglia {
+    colonne: u8,
+    righe: u32,
+    posizione: u32,
+}
+
+impl Griglia {
+    fn nuova(colonne: u8, righe: u32) -> Self {
+        Griglia { colonne, righe, posizione: 0 }
+    }
+}
+
+impl Iterator for Griglia {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let colonne = u32::from(self.colonne);
+        if self.posizione >= colonne * self.righe {
+            return None; // fine della griglia
+        }
+        let lettera = char::from(b'A' + (self.posizione % colonne) as u8);
+        let riga = self.posizione / colonne + 1;
+        self.posizione += 1;
+        Some(format!("{}{}", lettera, riga))
+    }
+}
+
+// ============================================================================
+// ADATTATORI SUI REPERTI
+// ============================================================================
+
+/// Adattatore che lascia passare solo i reperti di un periodo
+struct DelPeriodo<I> {
+    interno: I,
+    periodo: Periodo,
+}
+
+impl<'a, I: Iterator<Item = &'a Reperto>> Iterator for DelPeriodo<I> {
+    type Item = &'a Reperto;
+
+    fn next(&mut self) -> Option<&'a Reperto> {
+        // `find` consuma l'iteratore interno fino al prossimo reperto adatto
+        let periodo = self.periodo;
+        self.interno.find(|r| r.periodo == periodo)
+    }
+}
+
+/// Adattatore che raggruppa i reperti consecutivi dello stesso sito.
+/// Usa `Peekable` per guardare il prossimo elemento senza consumarlo.
+struct PerSito<I: Iterator> {
+    interno: Peekable<I>,
+}
+
+impl<'a, I: Iterator<Item = &'a Reperto>> Iterator for PerSito<I> {
+    type Item = (String, Vec<&'a Reperto>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let primo = self.interno.next()?; // `?` funziona anche con Option
+        let mut gruppo = vec![primo];
+        while let Some(r) = self.interno.next_if(|r| r.sito == primo.sito) {
+            gruppo.push(r);
+        }
+        Some((primo.sito.clone(), gruppo))
+    }
+}
+
+/// Extension trait: aggiunge i nuovi adattatori a ogni iteratore di &Reperto
+trait AdattatoriReperti<'a>: Iterator<Item = &'a Reperto> + Sized {
+    fn del_periodo(self, periodo: Periodo) -> DelPeriodo<Self> {
+        DelPeriodo { interno: self, periodo }
+    }
+
+    fn per_sito(self) -> PerSito<Self> {
+        PerSito { interno: self.peekable() }
+    }
+}
+
+/// Implementazione "blanket": vale per tutti gli I che soddisfano il bound
+impl<'a, I: Iterator<Item = &'a Reperto>> AdattatoriReperti<'a> for I {}
+
+// ============================================================================
+// DATI DI ESEMPIO
+// ============================================================================
+
+fn crea_inventario() -> Inventario {
+    let dati = [
+        (1, "Fibula ad arco", "Savignano Irpino", Periodo::Recente, 26.9),
+        (2, "Pugnale triangolare", "Toppo Daguzzo", Periodo::Antico, 198.3),
+        (3, "Ascia a margini rialzati", "Savignano sul Panaro", Periodo::Medio, 327.6),
+        (4, "Spada a lingua da presa", "Pontecagnano", Periodo::Finale, 779.6),
+        (5, "Coltello ad anima d'osso", "Toppo Daguzzo", Periodo::Medio, 32.4),
+        (6, "Ascia ad alette", "Pontecagnano", Periodo::Recente, 364.0),
+        (7, "Spillone a capocchia", "Savignano Irpino", Periodo::Recente, 12.2),
+    ];
+    let reperti = dati
+        .iter()
+        .map(|&(id, nome, sito, periodo, peso_grammi)| {
+            (id, Reperto { id, nome: nome.to_string(), sito: sito.to_string(), periodo, peso_grammi })
+        })
+        .collect();
+    Inventario { reperti }
+}
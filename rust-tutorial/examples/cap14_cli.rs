@@ -0,0 +1,388 @@
+// ============================================================================
+// CAPITOLO 14: RIGA DI COMANDO CON CLAP
+// ============================================================================
+// Il progetto finale (capitolo 9) ha molti sottocomandi: serve, cita,
+// deposito, seed... All'inizio li leggevamo a mano da `std::env::args()`.
+// Funziona, ma ogni opzione richiede codice ripetitivo e i messaggi di
+// errore restano a carico nostro. Qui costruiamo passo passo una CLI con
+// clap, la stessa tecnica usata ora dal capitolo 9.
+//
+// Esegui con: cargo run --features cli --example cap14_cli
+// Oppure usalo come vero programma:
+//   cargo run --features cli --example cap14_cli -- --help
+//   cargo run --features cli --example cap14_cli -- elenca --periodo medio
+//   cargo run --features cli --example cap14_cli -- aggiungi "Ascia ad alette" --periodo recente --peso 364
+// ============================================================================
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+use rust_tutorial::schermo;
+
+fn main() {
+    // Con argomenti si comporta da CLI vera, senza mostra il capitolo
+    if std::env::args().len() > 1 {
+        let cli = Cli::parse();
+        let mut inv = inventario_di_esempio();
+        if let Err(e) = esegui(&mut inv, cli.comando) {
+            eprintln!("Errore: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    schermo::intestazione(&["CAPITOLO 14: RIGA DI COMANDO CON CLAP"]);
+
+    // ========================================================================
+    // 14.1 - IL PUNTO DI PARTENZA: ARGOMENTI A MANO
+    // ========================================================================
+    println!("--- 14.1 Argomenti a Mano ---\n");
+
+    // Cosi leggeva le opzioni il capitolo 9: cercare il nome, prendere
+    // l'elemento successivo, convertirlo, inventare un messaggio d'errore.
+    for riga in [
+        vec!["inventario", "cerca", "2"],
+        vec!["inventario", "cerca", "due"],
+        vec!["inventario", "cerca"],
+    ] {
+        let argomenti: Vec<String> = riga.iter().map(|s| s.to_string()).collect();
+        println!("  {:<28} -> {}", riga.join(" "), cerca_a_mano(&argomenti));
+    }
+    // Nessun --help, nessun controllo sulle opzioni sconosciute,
+    // e ogni nuovo sottocomando ripete lo stesso schema.
+
+    println!();
+
+    // ========================================================================
+    // 14.2 - L'API DERIVE: UNA STRUCT DESCRIVE GLI ARGOMENTI
+    // ========================================================================
+    println!("--- 14.2 Derive API ---\n");
+
+    // `#[derive(Parser)]` genera il parser a partire dai campi:
+    //   - un campo semplice e un argomento posizionale
+    //   - `#[arg(long)]` lo trasforma in `--nome-campo`
+    //   - `Option<T>` lo rende facoltativo, `bool` e un flag
+    //   - i commenti `///` diventano il testo dell'aiuto
+    //
+    // `try_parse_from` riceve gli argomenti da una lista invece che dalla
+    // riga di comando: comodo per gli esempi e per i test.
+    let primo = PrimoPasso::try_parse_from(["esporta", "inventario.json", "--verboso"]);
+    println!("  esporta inventario.json --verboso");
+    println!("    -> {:?}", primo);
+
+    let aiuto = PrimoPasso::command().render_help().to_string();
+    println!("\n  Aiuto generato da clap:");
+    for riga in aiuto.lines() {
+        println!("  | {}", riga);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 14.3 - SOTTOCOMANDI
+    // ========================================================================
+    println!("--- 14.3 Sottocomandi ---\n");
+
+    // Un enum con `#[derive(Subcommand)]`: ogni variante e un sottocomando
+    // con i suoi argomenti. Il `match` su `Comando` prende il posto della
+    // catena di `if argomenti.get(1) == Some("...")`.
+    //
+    // Le opzioni comuni (qui `--formato`) stanno in una struct con
+    // `#[derive(Args)]` e si includono con `#[command(flatten)]`.
+    let mut inv = inventario_di_esempio();
+    for riga in [
+        "inventario elenca",
+        "inventario elenca --periodo medio --formato csv",
+        "inventario cerca 3",
+        "inventario rimuovi 1",
+        "inventario elenca",
+    ] {
+        println!("  $ {}", riga);
+        match Cli::try_parse_from(riga.split_whitespace()) {
+            Ok(cli) => {
+                if let Err(e) = esegui(&mut inv, cli.comando) {
+                    println!("    Errore: {}", e);
+                }
+            }
+            Err(e) => println!("    {}", e.kind()),
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 14.4 - CONVERTIRE E VALIDARE I VALORI
+    // ========================================================================
+    println!("--- 14.4 Conversione dei Valori ---\n");
+
+    // clap converte ogni argomento nel tipo del campo, e il tipo decide
+    // cosa e valido:
+    //   u32, f64, PathBuf, ...                 tramite FromStr
+    //   #[derive(ValueEnum)]                   un valore da un elenco fisso
+    //   value_parser!(u32).range(1..)          un intervallo
+    //   value_parser = peso_positivo           una funzione nostra
+    let prove = [
+        "inventario aggiungi Fibula --periodo recente --peso 26.9",
+        "inventario aggiungi Fibula --periodo tardo --peso 26.9",
+        // `--peso=-3`: scritto `--peso -3`, clap leggerebbe "-3" come un'opzione
+        "inventario aggiungi Fibula --periodo recente --peso=-3",
+        "inventario cerca 0",
+    ];
+    for riga in prove {
+        println!("  $ {}", riga);
+        match Cli::try_parse_from(riga.split_whitespace()) {
+            Ok(cli) => match esegui(&mut inv, cli.comando) {
+                Ok(()) => {}
+                Err(e) => println!("    Errore: {}", e),
+            },
+            // Mostriamo solo la prima riga dell'errore di clap
+            Err(e) => println!("    {}", e.to_string().lines().next().unwrap_or_default()),
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 14.5 - SEGNALARE GLI ERRORI
+    // ========================================================================
+    println!("--- 14.5 Errori e Codici di Uscita ---\n");
+
+    // Due famiglie di errori, due trattamenti diversi:
+    //
+    //   Errori d'uso (opzione sconosciuta, valore non valido): li trova
+    //   clap prima che il programma faccia qualsiasi cosa. `Cli::parse()`
+    //   stampa il messaggio con suggerimenti ed esce con codice 2.
+    //
+    //   Errori dell'applicazione (reperto inesistente, file illeggibile):
+    //   li restituisce `esegui` come Result. `main` li stampa in un unico
+    //   punto ed esce con codice 1.
+    for riga in ["inventario cerc 3", "inventario elenca --formto csv", "inventario --help"] {
+        if let Err(e) = Cli::try_parse_from(riga.split_whitespace()) {
+            println!(
+                "  {:<34} tipo {:?}, codice {}, {}",
+                riga,
+                e.kind(),
+                e.exit_code(),
+                if e.use_stderr() { "su stderr" } else { "su stdout" }
+            );
+        }
+    }
+
+    // Un errore scoperto DOPO il parsing puo comunque avere il formato di clap:
+    let errore = Cli::command().error(ErrorKind::ValueValidation, "il reperto #99 non esiste");
+    println!("\n  Errore costruito a mano: {}", errore.to_string().lines().next().unwrap_or_default());
+
+    // `debug_assert` verifica la definizione della CLI (nomi duplicati,
+    // conflitti impossibili...): da mettere in un test
+    Cli::command().debug_assert();
+    println!("  Definizione della CLI verificata con debug_assert()");
+
+    println!();
+
+    // ========================================================================
+    // 14.6 - IL PROGETTO FINALE
+    // ========================================================================
+    println!("--- 14.6 Il Progetto Finale ---\n");
+
+    // Il capitolo 9 applica gli stessi passi, nel modulo `cli`:
+    //   - `Cli` con le opzioni globali --assistente-tipologia, --watch, --mirror
+    //   - `Comando` con serve, ping, db, deposito, cita, aocat, sparql, ...
+    //   - `OpzioneArchivio` (--archivio) condivisa con #[command(flatten)]
+    //   - `AzioneDb` come ValueEnum (migrate, status, rollback)
+    //   - stili di citazione e frequenze convertiti con value_parser
+    //   - `fn esegui(Comando) -> Result<...>`: un solo punto di gestione errori
+    println!("  cargo run --features full --example cap09_progetto_finale -- --help");
+    println!("  cargo run --features full --example cap09_progetto_finale -- cita --help");
+    println!("  cargo run --features full --example cap09_progetto_finale -- db mgrate x.db   # suggerisce 'migrate'");
+
+    println!();
+
+    // ========================================================================
+    // 14.7 - RIEPILOGO
+    // ========================================================================
+    println!("--- 14.7 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "CLI CON CLAP",
+        &[
+            "#[derive(Parser)]      la struct principale",
+            "#[derive(Subcommand)]  un enum di comandi",
+            "#[derive(Args)]        opzioni riusabili",
+            "#[derive(ValueEnum)]   valori da un elenco",
+            "#[arg(long, short)]    --opzione, -o",
+            "value_parser           conversioni su misura",
+            "",
+            "Errori d'uso: clap, codice 2",
+            "Errori dell'app: Result, codice 1",
+        ],
+    );
+
+    schermo::completato(14);
+}
+
+// ============================================================================
+// PASSO 1: UNA SOLA STRUCT
+// ============================================================================
+
+/// Esporta l'inventario (primo esempio, senza sottocomandi)
+#[derive(Debug, Parser)]
+#[command(name = "esporta")]
+struct PrimoPasso {
+    /// File di destinazione
+    file: String,
+    /// Mostra i dettagli dell'operazione
+    #[arg(long, short)]
+    verboso: bool,
+}
+
+// ============================================================================
+// PASSO 2: SOTTOCOMANDI E VALORI
+// ============================================================================
+
+/// Gestione di un piccolo inventario di reperti
+#[derive(Debug, Parser)]
+#[command(name = "inventario", version)]
+struct Cli {
+    #[command(subcommand)]
+    comando: Comando,
+}
+
+#[derive(Debug, Subcommand)]
+enum Comando {
+    /// Elenca i reperti, eventualmente di un solo periodo
+    Elenca {
+        #[arg(long)]
+        periodo: Option<PeriodoCli>,
+        #[command(flatten)]
+        uscita: OpzioniUscita,
+    },
+    /// Mostra un reperto
+    Cerca {
+        #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+        id: u32,
+        #[command(flatten)]
+        uscita: OpzioniUscita,
+    },
+    /// Aggiunge un reperto
+    Aggiungi {
+        nome: String,
+        #[arg(long)]
+        periodo: PeriodoCli,
+        /// Peso in grammi
+        #[arg(long, value_parser = peso_positivo)]
+        peso: f64,
+    },
+    /// Rimuove un reperto
+    Rimuovi { id: u32 },
+}
+
+/// Opzioni condivise da piu sottocomandi
+#[derive(Debug, Args)]
+struct OpzioniUscita {
+    /// Formato di stampa
+    #[arg(long, value_enum, default_value_t = Formato::Testo)]
+    formato: Formato,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Formato {
+    Testo,
+    Csv,
+}
+
+/// `Periodo` della libreria non conosce clap: un enum "specchio" con
+/// `ValueEnum` e una conversione tengono separati i due mondi
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PeriodoCli {
+    Antico,
+    Medio,
+    Recente,
+    Finale,
+}
+
+impl From<PeriodoCli> for Periodo {
+    fn from(p: PeriodoCli) -> Periodo {
+        match p {
+            PeriodoCli::Antico => Periodo::Antico,
+            PeriodoCli::Medio => Periodo::Medio,
+            PeriodoCli::Recente => Periodo::Recente,
+            PeriodoCli::Finale => Periodo::Finale,
+        }
+    }
+}
+
+/// Un value_parser e una funzione &str -> Result<T, E>: il messaggio
+/// d'errore finisce dentro quello di clap
+fn peso_positivo(testo: &str) -> Result<f64, String> {
+    let peso: f64 = testo.parse().map_err(|_| format!("'{}' non e un numero", testo))?;
+    if peso > 0.0 {
+        Ok(peso)
+    } else {
+        Err("il peso deve essere maggiore di zero".to_string())
+    }
+}
+
+// ============================================================================
+// ESECUZIONE DEI COMANDI
+// ============================================================================
+
+/// Gli errori dell'applicazione risalgono come Result fino a `main`
+fn esegui(inv: &mut Inventario, comando: Comando) -> Result<(), ErroreInventario> {
+    match comando {
+        Comando::Elenca { periodo, uscita } => {
+            let reperti: Vec<&Reperto> = match periodo {
+                Some(p) => inv.cerca_per_periodo(p.into()),
+                None => inv.iter().collect(),
+            };
+            for r in reperti {
+                stampa(r, uscita.formato);
+            }
+        }
+        Comando::Cerca { id, uscita } => stampa(inv.cerca_per_id(id)?, uscita.formato),
+        Comando::Aggiungi { nome, periodo, peso } => {
+            let id = inv.aggiungi(Reperto::nuovo(&nome, periodo.into(), peso))?;
+            println!("    Aggiunto #{} {}", id, nome);
+        }
+        Comando::Rimuovi { id } => {
+            let r = inv.rimuovi(id)?;
+            println!("    Rimosso #{} {}", r.id, r.nome);
+        }
+    }
+    Ok(())
+}
+
+fn stampa(r: &Reperto, formato: Formato) {
+    match formato {
+        Formato::Testo => println!("    #{} {:<26} {:?}, {:.1} g", r.id, r.nome, r.periodo, r.peso_grammi),
+        Formato::Csv => println!("    {},{},{:?},{}", r.id, r.nome, r.periodo, r.peso_grammi),
+    }
+}
+
+// ============================================================================
+// FUNZIONI DI SUPPORTO
+// ============================================================================
+
+/// Il vecchio modo: `cerca <id>` letto a mano
+fn cerca_a_mano(argomenti: &[String]) -> String {
+    let Some(id) = argomenti.get(2) else {
+        return "uso: cerca <id>".to_string();
+    };
+    match id.parse::<u32>() {
+        Ok(id) => format!("cerco il reperto #{}", id),
+        Err(_) => format!("ID '{}' non valido", id),
+    }
+}
+
+fn inventario_di_esempio() -> Inventario {
+    let mut inv = Inventario::nuovo();
+    for (nome, periodo, peso) in [
+        ("Pugnale triangolare", Periodo::Antico, 198.3),
+        ("Ascia a margini rialzati", Periodo::Medio, 327.6),
+        ("Spada a lingua da presa", Periodo::Finale, 779.6),
+    ] {
+        if let Err(e) = inv.aggiungi(Reperto::nuovo(nome, periodo, peso)) {
+            eprintln!("Errore: {}", e);
+        }
+    }
+    inv
+}
@@ -0,0 +1,417 @@
+// ============================================================================
+// CAPITOLO 15: SERVIZI WEB CON AXUM
+// ============================================================================
+// Un server HTTP scritto a mano, che legge la richiesta riga per riga da
+// un TcpStream, basta per due percorsi come /healthz e /readyz, ma non per
+// un'API completa. Qui esponiamo l'inventario via HTTP con axum, un passo
+// alla volta: router, estrattori, stato condiviso, errori in JSON. Il
+// modulo `salute` del capitolo 9 usa lo stesso router.
+//
+// Esegui con: cargo run --features server,net --example cap15_web_axum
+// Oppure avvia il server e interrogalo con curl:
+//   cargo run --features server,net --example cap15_web_axum -- serve [127.0.0.1:3000]
+//   curl http://127.0.0.1:3000/reperti?periodo=medio
+//   curl -X POST -H 'content-type: application/json' \
+//        -d '{"nome":"Fibula","periodo":"recente","peso_grammi":26.9}' http://127.0.0.1:3000/reperti
+// ============================================================================
+
+use std::sync::{Arc, RwLock};
+
+use axum::body::Body;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Path, Query, State};
+use axum::http::{Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+use rust_tutorial::schermo;
+use serde::{Deserialize, Serialize};
+use tower::ServiceExt;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let argomenti: Vec<String> = std::env::args().collect();
+    if argomenti.get(1).map(String::as_str) == Some("serve") {
+        let indirizzo = argomenti.get(2).map_or("127.0.0.1:3000", String::as_str);
+        let ascolto = match tokio::net::TcpListener::bind(indirizzo).await {
+            Ok(a) => a,
+            Err(e) => {
+                eprintln!("Errore: {}", e);
+                std::process::exit(1);
+            }
+        };
+        println!("In ascolto su http://{}, Ctrl-C per uscire", indirizzo);
+        if let Err(e) = axum::serve(ascolto, applicazione(Stato::nuovo(inventario_di_esempio()))).await {
+            eprintln!("Errore: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    schermo::intestazione(&["CAPITOLO 15: SERVIZI WEB CON AXUM"]);
+
+    // ========================================================================
+    // 15.1 - IL PRIMO ROUTER
+    // ========================================================================
+    println!("--- 15.1 Router e Handler ---\n");
+
+    // Un handler e una normale funzione async: riceve cio che chiede
+    // (gli "estrattori") e restituisce qualcosa che implementa IntoResponse.
+    // Il Router associa metodo + percorso all'handler giusto.
+    let primo = Router::new().route("/healthz", get(healthz));
+
+    // `oneshot` invia una richiesta al router senza aprire una porta:
+    // e il modo in cui si testano le applicazioni axum
+    for uri in ["/healthz", "/altro"] {
+        let (codice, corpo) = chiedi(&primo, "GET", uri, None).await;
+        println!("  GET {:<10} -> {} {}", uri, codice.as_u16(), corpo);
+    }
+    // Il percorso sconosciuto risponde 404 da solo: niente `_ =>` nel match
+
+    println!();
+
+    // ========================================================================
+    // 15.2 - ESTRATTORI: PATH, QUERY, JSON
+    // ========================================================================
+    println!("--- 15.2 Estrattori ---\n");
+
+    // Gli argomenti dell'handler dicono ad axum cosa estrarre dalla richiesta:
+    //   Path<u32>            il segmento {id} di /reperti/{id}
+    //   Query<Filtro>        ?periodo=medio, deserializzato con serde
+    //   Json<NuovoReperto>   il corpo JSON della richiesta
+    //   State<Stato>         lo stato condiviso dell'applicazione (15.3)
+    // Se l'estrazione fallisce, l'handler non viene nemmeno chiamato.
+    let app = applicazione(Stato::nuovo(inventario_di_esempio()));
+    for uri in ["/reperti", "/reperti?periodo=medio", "/reperti/3"] {
+        let (codice, corpo) = chiedi(&app, "GET", uri, None).await;
+        println!("  GET {:<22} -> {} {}", uri, codice.as_u16(), corpo);
+    }
+    let (codice, corpo) = chiedi(&app, "GET", "/reperti/tre", None).await;
+    println!("  GET {:<22} -> {} {}", "/reperti/tre", codice.as_u16(), corpo);
+
+    println!();
+
+    // ========================================================================
+    // 15.3 - STATO CONDIVISO
+    // ========================================================================
+    println!("--- 15.3 Stato Condiviso ---\n");
+
+    // Le richieste arrivano in parallelo: l'inventario vive in un
+    // Arc<RwLock<...>> (capitolo 8) dentro una struct `Stato` clonabile.
+    // `with_state` lo consegna al router, `State<Stato>` agli handler.
+    // Clonare lo Stato clona solo l'Arc: l'inventario resta uno.
+    let corpo = r#"{"nome": "Fibula ad arco", "periodo": "recente", "peso_grammi": 26.9}"#;
+    let (codice, risposta) = chiedi(&app, "POST", "/reperti", Some(corpo)).await;
+    println!("  POST /reperti           -> {} {}", codice.as_u16(), risposta);
+    let (codice, risposta) = chiedi(&app, "DELETE", "/reperti/1", None).await;
+    println!("  DELETE /reperti/1       -> {} {}", codice.as_u16(), risposta);
+    let (_, elenco) = chiedi(&app, "GET", "/reperti", None).await;
+    println!("  GET /reperti            -> {}", elenco);
+
+    println!();
+
+    // ========================================================================
+    // 15.4 - ERRORI IN JSON
+    // ========================================================================
+    println!("--- 15.4 Errori in JSON ---\n");
+
+    // Gli handler restituiscono Result<_, ErroreApi>. `ErroreApi`
+    // implementa IntoResponse: sceglie il codice HTTP e scrive il corpo
+    // {"errore": "..."}, lo stesso formato del server del capitolo 9.
+    //
+    // Perche un tipo nuovo e non `impl IntoResponse for ErroreInventario`?
+    // Trait (di axum) e tipo (della libreria) sono entrambi esterni a
+    // questo crate: la "orphan rule" lo vieta. Un wrapper risolve.
+    let prove = [
+        ("GET", "/reperti/99", None),
+        ("GET", "/reperti?periodo=tardo", None),
+        ("POST", "/reperti", Some(r#"{"nome": "", "periodo": "medio", "peso_grammi": 10}"#)),
+        ("POST", "/reperti", Some(r#"{"nome": "Ascia", "periodo": "medio"}"#)),
+        ("POST", "/reperti", Some("non e json")),
+    ];
+    for (metodo, uri, corpo) in prove {
+        let (codice, risposta) = chiedi(&app, metodo, uri, corpo).await;
+        println!("  {:<6} {:<22} -> {} {}", metodo, uri, codice.as_u16(), risposta);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 15.5 - DAL SERVER A MANO AL ROUTER
+    // ========================================================================
+    println!("--- 15.5 Dal Capitolo 9 ad Axum ---\n");
+
+    // Scritto a mano, un server di salute fa tutto da solo:
+    //   - legge "GET /readyz HTTP/1.1" e le intestazioni dal TcpStream
+    //   - `match percorso { "/healthz" => ..., "/readyz" => ..., _ => 404 }`
+    //   - scrive a mano la riga di stato, Content-Length e il corpo
+    //   - gestisce una connessione alla volta: un client lento blocca gli altri
+    // Con axum le stesse due rotte diventano (e cosi sono in `salute`):
+    //   Router::new()
+    //       .route("/healthz", get(healthz))
+    //       .route("/readyz", get(readyz))
+    // e il parsing HTTP, i codici, keep-alive e la concorrenza li gestisce hyper.
+    // Stessi percorsi e stessa forma del JSON: per un client (o Kubernetes) nulla cambia.
+    for uri in ["/healthz", "/readyz"] {
+        let (codice, corpo) = chiedi(&app, "GET", uri, None).await;
+        println!("  GET {:<8} -> {} {}", uri, codice.as_u16(), corpo);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 15.6 - UN SERVER VERO
+    // ========================================================================
+    println!("--- 15.6 Un Server Vero ---\n");
+
+    // `axum::serve` collega il router a una porta TCP. La porta 0 chiede al
+    // sistema una porta libera; il server gira in un task separato e lo
+    // interroghiamo con un vero client HTTP (ureq, gia usato nel capitolo 9).
+    // Per fermarsi in modo pulito a un segnale, come `serve` del capitolo 9
+    // con SIGTERM, si aggiunge `.with_graceful_shutdown(futuro)`.
+    match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+        Ok(ascolto) => {
+            let indirizzo = ascolto.local_addr().map(|a| a.to_string()).unwrap_or_default();
+            let server = tokio::spawn(async move { axum::serve(ascolto, app).await });
+            let url = format!("http://{}/reperti/2", indirizzo);
+            // ureq e bloccante: va eseguito fuori dal thread del runtime
+            let esito = tokio::task::spawn_blocking(move || {
+                ureq::get(&url).call().and_then(|mut r| r.body_mut().read_to_string())
+            })
+            .await;
+            match esito {
+                Ok(Ok(corpo)) => println!("  http://{}/reperti/2 -> {}", indirizzo, corpo),
+                Ok(Err(e)) => println!("  Richiesta fallita: {}", e),
+                Err(e) => println!("  Task interrotto: {}", e),
+            }
+            server.abort();
+        }
+        Err(e) => println!("  Impossibile aprire una porta: {}", e),
+    }
+
+    println!();
+
+    // ========================================================================
+    // 15.7 - RIEPILOGO
+    // ========================================================================
+    println!("--- 15.7 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "AXUM",
+        &[
+            "Router::new().route(\"/x\", get(handler))",
+            "Handler: async fn(estrattori) -> risposta",
+            "",
+            "Path, Query, Json    dati della richiesta",
+            "State<T>             stato condiviso (Arc)",
+            "IntoResponse         errori -> codice + JSON",
+            "",
+            "oneshot()     testare senza aprire porte",
+            "axum::serve   il server vero",
+        ],
+    );
+
+    schermo::completato(15);
+}
+
+// ============================================================================
+// STATO E ROUTER
+// ============================================================================
+
+/// Stato condiviso: clonarlo costa solo l'incremento di un contatore
+#[derive(Clone)]
+struct Stato {
+    inventario: Arc<RwLock<Inventario>>,
+}
+
+impl Stato {
+    fn nuovo(inventario: Inventario) -> Self {
+        Stato { inventario: Arc::new(RwLock::new(inventario)) }
+    }
+}
+
+fn applicazione(stato: Stato) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/reperti", get(elenca).post(aggiungi))
+        .route("/reperti/{id}", get(mostra).delete(rimuovi))
+        .with_state(stato)
+}
+
+// ============================================================================
+// TIPI DELL'API
+// ============================================================================
+// Cio che viaggia in JSON e separato dai tipi della libreria: l'API puo
+// cambiare formato senza toccare l'inventario, e viceversa.
+
+#[derive(Serialize)]
+struct RepertoJson {
+    id: u32,
+    nome: String,
+    periodo: &'static str,
+    peso_grammi: f64,
+}
+
+impl From<&Reperto> for RepertoJson {
+    fn from(r: &Reperto) -> Self {
+        RepertoJson { id: r.id, nome: r.nome.clone(), periodo: nome_periodo(r.periodo), peso_grammi: r.peso_grammi }
+    }
+}
+
+#[derive(Deserialize)]
+struct NuovoReperto {
+    nome: String,
+    periodo: String,
+    peso_grammi: f64,
+}
+
+#[derive(Deserialize)]
+struct Filtro {
+    periodo: Option<String>,
+}
+
+// ============================================================================
+// ERRORI
+// ============================================================================
+
+enum ErroreApi {
+    Inventario(ErroreInventario),
+    /// Richiesta malformata: JSON non valido, periodo sconosciuto...
+    Richiesta(String),
+}
+
+impl From<ErroreInventario> for ErroreApi {
+    fn from(e: ErroreInventario) -> Self {
+        ErroreApi::Inventario(e)
+    }
+}
+
+impl From<JsonRejection> for ErroreApi {
+    fn from(e: JsonRejection) -> Self {
+        ErroreApi::Richiesta(e.body_text())
+    }
+}
+
+impl IntoResponse for ErroreApi {
+    fn into_response(self) -> Response {
+        let (codice, messaggio) = match self {
+            ErroreApi::Inventario(e @ ErroreInventario::RepertoNonTrovato(_)) => (StatusCode::NOT_FOUND, e.to_string()),
+            ErroreApi::Inventario(e) => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+            ErroreApi::Richiesta(m) => (StatusCode::BAD_REQUEST, m),
+        };
+        (codice, Json(serde_json::json!({ "errore": messaggio }))).into_response()
+    }
+}
+
+// ============================================================================
+// HANDLER
+// ============================================================================
+
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "stato": "vivo" }))
+}
+
+/// Come /readyz del capitolo 9, che verifica l'archivio: qui l'unica
+/// risorsa e l'inventario in memoria, inutilizzabile se il lock e avvelenato
+async fn readyz(State(stato): State<Stato>) -> (StatusCode, Json<serde_json::Value>) {
+    if stato.inventario.is_poisoned() {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({ "stato": "non pronto" })))
+    } else {
+        (StatusCode::OK, Json(serde_json::json!({ "stato": "pronto", "inventario": "ok" })))
+    }
+}
+
+async fn elenca(State(stato): State<Stato>, Query(filtro): Query<Filtro>) -> Result<Json<Vec<RepertoJson>>, ErroreApi> {
+    let periodo = match filtro.periodo {
+        Some(p) => Some(periodo_da_nome(&p)?),
+        None => None,
+    };
+    let inv = stato.inventario.read().map_err(|_| ErroreApi::Richiesta("inventario non disponibile".to_string()))?;
+    let reperti = match periodo {
+        Some(p) => inv.cerca_per_periodo(p).into_iter().map(RepertoJson::from).collect(),
+        None => inv.iter().map(RepertoJson::from).collect(),
+    };
+    Ok(Json(reperti))
+}
+
+async fn mostra(State(stato): State<Stato>, Path(id): Path<u32>) -> Result<Json<RepertoJson>, ErroreApi> {
+    let inv = stato.inventario.read().map_err(|_| ErroreApi::Richiesta("inventario non disponibile".to_string()))?;
+    Ok(Json(RepertoJson::from(inv.cerca_per_id(id)?)))
+}
+
+/// Il corpo arriva come Result: cosi anche il JSON malformato diventa un ErroreApi
+async fn aggiungi(
+    State(stato): State<Stato>,
+    corpo: Result<Json<NuovoReperto>, JsonRejection>,
+) -> Result<(StatusCode, Json<RepertoJson>), ErroreApi> {
+    let Json(nuovo) = corpo?;
+    let periodo = periodo_da_nome(&nuovo.periodo)?;
+    let mut inv = stato.inventario.write().map_err(|_| ErroreApi::Richiesta("inventario non disponibile".to_string()))?;
+    let id = inv.aggiungi(Reperto::nuovo(&nuovo.nome, periodo, nuovo.peso_grammi))?;
+    Ok((StatusCode::CREATED, Json(RepertoJson::from(inv.cerca_per_id(id)?))))
+}
+
+async fn rimuovi(State(stato): State<Stato>, Path(id): Path<u32>) -> Result<StatusCode, ErroreApi> {
+    let mut inv = stato.inventario.write().map_err(|_| ErroreApi::Richiesta("inventario non disponibile".to_string()))?;
+    inv.rimuovi(id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// FUNZIONI DI SUPPORTO
+// ============================================================================
+
+fn nome_periodo(p: Periodo) -> &'static str {
+    match p {
+        Periodo::Antico => "antico",
+        Periodo::Medio => "medio",
+        Periodo::Recente => "recente",
+        Periodo::Finale => "finale",
+    }
+}
+
+fn periodo_da_nome(nome: &str) -> Result<Periodo, ErroreApi> {
+    match nome {
+        "antico" => Ok(Periodo::Antico),
+        "medio" => Ok(Periodo::Medio),
+        "recente" => Ok(Periodo::Recente),
+        "finale" => Ok(Periodo::Finale),
+        _ => Err(ErroreApi::Richiesta(format!("periodo '{}' sconosciuto (antico, medio, recente, finale)", nome))),
+    }
+}
+
+/// Invia una richiesta al router e restituisce codice e corpo
+async fn chiedi(app: &Router, metodo: &str, uri: &str, corpo: Option<&str>) -> (StatusCode, String) {
+    let mut richiesta = Request::builder().method(metodo).uri(uri);
+    let corpo = match corpo {
+        Some(c) => {
+            richiesta = richiesta.header("content-type", "application/json");
+            Body::from(c.to_string())
+        }
+        None => Body::empty(),
+    };
+    let risposta = match richiesta.body(corpo) {
+        // Il Router non fallisce mai: gli errori sono gia risposte HTTP
+        Ok(r) => app.clone().oneshot(r).await.unwrap_or_else(|e| match e {}),
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let codice = risposta.status();
+    let byte = axum::body::to_bytes(risposta.into_body(), 64 * 1024).await.unwrap_or_default();
+    (codice, String::from_utf8_lossy(&byte).into_owned())
+}
+
+fn inventario_di_esempio() -> Inventario {
+    let mut inv = Inventario::nuovo();
+    for (nome, periodo, peso) in [
+        ("Pugnale triangolare", Periodo::Antico, 198.3),
+        ("Ascia a margini rialzati", Periodo::Medio, 327.6),
+        ("Spada a lingua da presa", Periodo::Finale, 779.6),
+    ] {
+        if let Err(e) = inv.aggiungi(Reperto::nuovo(nome, periodo, peso)) {
+            eprintln!("Errore: {}", e);
+        }
+    }
+    inv
+}
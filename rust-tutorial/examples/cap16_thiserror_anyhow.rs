@@ -0,0 +1,250 @@
+// ============================================================================
+// CAPITOLO 16: GLI ERRORI NELL'ECOSISTEMA (THISERROR E ANYHOW)
+// ============================================================================
+// Nel capitolo 4 abbiamo costruito gli errori a mano: un enum, un
+// `impl Display` con un `match`, un `impl From` per ogni conversione.
+// Funziona, ma e tanto codice ripetitivo. Nei progetti reali si usano
+// quasi sempre due crate complementari:
+//
+//   thiserror  per le LIBRERIE: genera Display, Error e From per i tuoi enum
+//   anyhow     per i PROGRAMMI: un unico tipo di errore con contesto
+//
+// La libreria del tutorial (src/inventario.rs) e passata a thiserror;
+// questo capitolo fa la parte del programma e usa anyhow.
+//
+// Esegui con: cargo run --example cap16_thiserror_anyhow
+// ============================================================================
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context};
+use rust_tutorial::inventario::{ErroreInventario, Inventario};
+use rust_tutorial::schermo;
+
+// `main` puo restituire anyhow::Result: se c'e un errore, Rust lo stampa
+// con la catena delle cause ed esce con codice 1
+fn main() -> anyhow::Result<()> {
+    schermo::intestazione(&["CAPITOLO 16: THISERROR E ANYHOW"]);
+
+    let cartella = std::env::temp_dir().join("bronzeaxe_cap16");
+    std::fs::create_dir_all(&cartella).context("creazione della cartella di prova")?;
+    prepara_file(&cartella)?;
+
+    // ========================================================================
+    // 16.1 - PRIMA: TUTTO A MANO
+    // ========================================================================
+    println!("--- 16.1 Errori a Mano ---\n");
+
+    // Fino al capitolo 13 la libreria definiva ErroreInventario cosi:
+    //
+    //   #[derive(Debug, Clone, PartialEq)]
+    //   pub enum ErroreInventario { RepertoNonTrovato(u32), NomeVuoto, PesoNonValido(f64) }
+    //
+    //   impl fmt::Display for ErroreInventario {
+    //       fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    //           match self {
+    //               ErroreInventario::RepertoNonTrovato(id) => write!(f, "Reperto #{} non trovato", id),
+    //               ErroreInventario::NomeVuoto => write!(f, "Il nome del reperto non puo essere vuoto"),
+    //               ErroreInventario::PesoNonValido(peso) => write!(f, "Peso non valido: {} g", peso),
+    //           }
+    //       }
+    //   }
+    //
+    //   impl std::error::Error for ErroreInventario {}
+    //
+    // Ogni variante va scritta due volte (enum e match), e se un errore ne
+    // avvolge un altro bisogna ricordarsi di implementare anche `source()`.
+    println!("  Ogni variante: definizione + ramo del match in Display");
+    println!("  Ogni errore avvolto: un `impl Error` con source() scritto a mano");
+
+    println!();
+
+    // ========================================================================
+    // 16.2 - THISERROR NELLA LIBRERIA
+    // ========================================================================
+    println!("--- 16.2 thiserror ---\n");
+
+    // Ora il messaggio sta accanto alla variante:
+    //
+    //   #[derive(Debug, Clone, PartialEq, Error)]
+    //   pub enum ErroreInventario {
+    //       #[error("Reperto #{0} non trovato")]
+    //       RepertoNonTrovato(u32),
+    //       ...
+    //       #[error("Riga {riga}: peso illeggibile")]
+    //       PesoIllegibile { riga: usize, #[source] causa: ParseFloatError },
+    //   }
+    //
+    //   #[error("...")]  genera Display; {0} e {campo} sono i dati della variante
+    //   #[source]        collega l'errore originale: Error::source() lo restituisce
+    //   #[from]          come #[source], e in piu genera `impl From` per usare `?`
+    //
+    // Il risultato e un tipo normale: l'utente della libreria non sa (e non
+    // deve sapere) che dietro c'e thiserror. I messaggi sono identici a prima.
+    let testo = "Ascia a margini rialzati;medio;327.6\nFibula ad arco;recente;-1\n";
+    match Inventario::da_csv(testo) {
+        Ok(inv) => println!("  Letti {} reperti", inv.len()),
+        Err(e) => {
+            println!("  Errore: {}", e);
+            // Il `match` sulle varianti resta possibile: e un enum come prima
+            if let ErroreInventario::RepertoRifiutato { riga, .. } = &e {
+                println!("  (variante RepertoRifiutato, riga {})", riga);
+            }
+            stampa_catena(&e);
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 16.3 - ANYHOW NEL PROGRAMMA
+    // ========================================================================
+    println!("--- 16.3 anyhow ---\n");
+
+    // Un programma combina errori di origini diverse: file system, parsing,
+    // libreria. Con anyhow::Result<T> ogni errore che implementa
+    // std::error::Error passa con `?`, senza enum da mantenere.
+    // `.context(...)` aggiunge cosa si stava facendo: la causa originale
+    // non si perde, diventa il primo anello della catena.
+    for nome in ["buono.csv", "mancante.csv", "peso_sbagliato.csv", "vuoto.csv", "solo_commenti.csv"] {
+        match carica(&cartella.join(nome)) {
+            Ok(inv) => println!("  {:<20} {} reperti", nome, inv.len()),
+            // {:#} stampa tutta la catena su una riga, separata da ": "
+            Err(e) => println!("  {:<20} {:#}", nome, e),
+        }
+    }
+
+    // `{:?}` invece produce il formato esteso, con "Caused by:" per ogni causa.
+    // E quello che si vede se `main` restituisce l'errore.
+    if let Err(e) = carica(&cartella.join("peso_sbagliato.csv")) {
+        println!("\n  Formato {{:?}}:");
+        // Con RUST_BACKTRACE=1 segue anche lo stack: qui lo tralasciamo
+        let esteso = format!("{:?}", e);
+        for riga in esteso.lines().take_while(|r| !r.starts_with("Stack backtrace")) {
+            println!("  | {}", riga);
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 16.4 - RECUPERARE IL TIPO: DOWNCAST
+    // ========================================================================
+    println!("--- 16.4 downcast_ref ---\n");
+
+    // anyhow cancella il tipo, ma non lo perde: con `downcast_ref` il
+    // programma puo chiedere "e un ErroreInventario?" e reagire, per
+    // esempio con un codice di uscita diverso.
+    for nome in ["mancante.csv", "peso_sbagliato.csv"] {
+        if let Err(e) = carica(&cartella.join(nome)) {
+            println!("  {:<20} codice di uscita {}", nome, codice_uscita(&e));
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 16.5 - QUANDO USARE QUALE
+    // ========================================================================
+    println!("--- 16.5 Quando Usare Quale ---\n");
+
+    println!("  {:<34} Scelta", "Situazione");
+    println!("  {}", "-".repeat(60));
+    for (situazione, scelta) in [
+        ("Libreria usata da altri", "thiserror: tipi precisi"),
+        ("Il chiamante deve fare match", "thiserror"),
+        ("main, CLI, script, prototipi", "anyhow: contesto e catena"),
+        ("Serve solo mostrare l'errore", "anyhow"),
+        ("Programma che chiama la libreria", "entrambi"),
+        ("Nessuna dipendenza ammessa", "a mano (capitolo 4)"),
+    ] {
+        println!("  {:<34} {}", situazione, scelta);
+    }
+    // Regola pratica: chi PRODUCE errori per altri li tipizza (thiserror),
+    // chi li CONSUMA e deve solo riferirli li raccoglie (anyhow).
+    // Mai anyhow::Error nell'API pubblica di una libreria: toglierebbe
+    // al chiamante la possibilita di distinguere i casi.
+
+    println!();
+
+    // ========================================================================
+    // 16.6 - RIEPILOGO
+    // ========================================================================
+    println!("--- 16.6 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "THISERROR E ANYHOW",
+        &[
+            "thiserror (librerie)",
+            "  #[derive(Error)]   Display + Error",
+            "  #[error(\"...\")]    il messaggio",
+            "  #[source] #[from]  la causa, e From",
+            "",
+            "anyhow (programmi)",
+            "  anyhow::Result<T>  qualsiasi errore",
+            "  .context(\"...\")    cosa stavi facendo",
+            "  bail! / ensure!    errori al volo",
+            "  downcast_ref       ritrovare il tipo",
+        ],
+    );
+
+    std::fs::remove_dir_all(&cartella).context("pulizia della cartella di prova")?;
+    schermo::completato(16);
+    Ok(())
+}
+
+// ============================================================================
+// FUNZIONI DEL PROGRAMMA (ANYHOW)
+// ============================================================================
+
+/// Legge un inventario da file: tre tipi di errore diversi, un solo `?`
+fn carica(percorso: &Path) -> anyhow::Result<Inventario> {
+    let testo = std::fs::read_to_string(percorso)
+        .with_context(|| format!("impossibile leggere {}", percorso.display()))?;
+    // ensure!: come assert!, ma restituisce un errore invece di andare in panico
+    ensure!(!testo.trim().is_empty(), "{} e vuoto", percorso.display());
+    let inv = Inventario::da_csv(&testo).with_context(|| format!("formato non valido in {}", percorso.display()))?;
+    if inv.is_empty() {
+        // bail!: crea l'errore e lo restituisce subito
+        bail!("{} non contiene reperti", percorso.display());
+    }
+    Ok(inv)
+}
+
+/// 2 per i dati sbagliati (colpa del file), 1 per tutto il resto
+fn codice_uscita(errore: &anyhow::Error) -> i32 {
+    match errore.downcast_ref::<ErroreInventario>() {
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+// ============================================================================
+// FUNZIONI DI SUPPORTO
+// ============================================================================
+
+/// Segue `source()` dall'errore fino alla causa prima
+fn stampa_catena(errore: &dyn Error) {
+    let mut causa = errore.source();
+    let mut livello = 1;
+    while let Some(c) = causa {
+        println!("  {}causa: {}", "  ".repeat(livello), c);
+        causa = c.source();
+        livello += 1;
+    }
+}
+
+fn prepara_file(cartella: &Path) -> anyhow::Result<()> {
+    let file: [(&str, &str); 4] = [
+        ("buono.csv", "# nome;periodo;peso\nPugnale triangolare;antico;198.3\nSpada a lingua da presa;finale;779.6\n"),
+        ("peso_sbagliato.csv", "Ascia ad alette;recente;364\nFalce;recente;circa 90\n"),
+        ("vuoto.csv", "\n"),
+        ("solo_commenti.csv", "# nome;periodo;peso\n"),
+    ];
+    for (nome, contenuto) in file {
+        let percorso: PathBuf = cartella.join(nome);
+        std::fs::write(&percorso, contenuto).with_context(|| format!("scrittura di {}", percorso.display()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,564 @@
+// ============================================================================
+// CAPITOLO 17: SERDE IN PROFONDITA
+// ============================================================================
+// Il progetto finale usa serde ovunque, quasi sempre con il solo
+// `#[derive(Serialize, Deserialize)]`. Il formato che ne esce pero e
+// deciso dai nomi Rust: appena il JSON deve seguire regole esterne
+// (un'API, un file vecchio, un'altra applicazione) servono gli attributi.
+//
+// In questo capitolo:
+//   - rinominare, ignorare e dare valori predefiniti ai campi
+//   - `flatten`: campi annidati in Rust, piatti nel JSON (e viceversa)
+//   - le quattro rappresentazioni degli enum, `tag` e `content`
+//   - strutture versionate e migrazione automatica con `from`/`into`
+//   - un Deserializer scritto a mano per il vecchio CSV del registro
+//
+// Esegui con: cargo run --example cap17_serde
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rust_tutorial::schermo;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 17: SERDE IN PROFONDITA"]);
+
+    // ========================================================================
+    // 17.1 - RINOMINARE E IGNORARE I CAMPI
+    // ========================================================================
+    println!("--- 17.1 Nomi dei Campi ---\n");
+
+    // Un'interfaccia web in JavaScript si aspetta i nomi in camelCase;
+    // in Rust restano in snake_case. `rename_all` converte tutti i campi,
+    // `rename` ne cambia uno solo.
+    let scheda = SchedaWeb {
+        id: 1,
+        nome: "Ascia a margini rialzati".to_string(),
+        peso_grammi: 327.6,
+        numero_inventario: Some("SAV-2024-0001".to_string()),
+        note: Vec::new(),
+        selezionata: true,
+    };
+    println!("  {}", serde_json::to_string(&scheda).unwrap());
+    // `note` vuoto e `selezionata` non compaiono: vedi gli attributi sui campi
+
+    // In lettura i campi con `default` possono mancare
+    let json = r#"{"id":2,"denominazione":"Fibula ad arco","pesoGrammi":26.9}"#;
+    let letta: SchedaWeb = serde_json::from_str(json).unwrap();
+    println!("  Letta: {:?}", letta);
+    // `skip` non legge e non scrive: il campo riparte dal suo Default
+    println!("  selezionata dopo la lettura: {}", letta.selezionata);
+
+    // `deny_unknown_fields` trasforma un refuso in un errore invece di
+    // ignorarlo in silenzio
+    let refuso = r#"{"id":3,"denominazione":"Falce","pesoGrami":90.0}"#;
+    if let Err(e) = serde_json::from_str::<SchedaWeb>(refuso) {
+        println!("  Refuso: {}", e);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 17.2 - FLATTEN
+    // ========================================================================
+    println!("--- 17.2 flatten ---\n");
+
+    // In Rust le misure sono una struct a parte (riusabile, con i suoi
+    // metodi); nel JSON stanno allo stesso livello degli altri campi.
+    // Un secondo `flatten` su una mappa raccoglie i campi sconosciuti:
+    // chi legge e riscrive il file non perde dati che non capisce.
+    let json = r#"{
+        "id": 7,
+        "nome": "Spada a lingua da presa",
+        "lunghezza_cm": 64.5,
+        "larghezza_cm": 5.1,
+        "scavo": "Pontecagnano 1962",
+        "restauro": {"anno": 1998}
+    }"#;
+    let reperto: RepertoMisurato = serde_json::from_str(json).unwrap();
+    println!("  Misure: {:?}", reperto.misure);
+    println!("  Rapporto L/l: {:.1}", reperto.misure.rapporto());
+    println!("  Campi sconosciuti conservati: {:?}", reperto.altri.keys().collect::<Vec<_>>());
+    println!("  Riscritto: {}", serde_json::to_string(&reperto).unwrap());
+    // Attenzione: con `flatten` non si puo usare `deny_unknown_fields`,
+    // e i formati binari (bincode, postcard) spesso non lo supportano.
+
+    println!();
+
+    // ========================================================================
+    // 17.3 - ENUM: TAG E CONTENT
+    // ========================================================================
+    println!("--- 17.3 Rappresentazioni degli Enum ---\n");
+
+    // serde ha quattro modi di scrivere un enum:
+    //
+    //   esterno (predefinito)   {"Lega":{"rame":88.0,"stagno":12.0}}
+    //   interno   tag = "t"     {"t":"Lega","rame":88.0,"stagno":12.0}
+    //   adiacente tag + content {"t":"Lega","c":{"rame":88.0,"stagno":12.0}}
+    //   untagged                {"rame":88.0,"stagno":12.0}
+    //
+    // Il progetto finale usa quello esterno. Per un'API conviene un tag
+    // esplicito: il tipo si legge con un solo campo, sempre lo stesso.
+    for materiale in [
+        Materiale::Bronzo,
+        Materiale::Lega { rame: 88.0, stagno: 12.0 },
+        Materiale::Altro("ambra".to_string()),
+    ] {
+        println!("  {:<36} {}", format!("{:?}", materiale), serde_json::to_string(&materiale).unwrap());
+    }
+    // Materiale e adiacente (tag + content) perche `Altro(String)` contiene
+    // un valore che non e un oggetto: con il solo `tag` non saprebbe dove
+    // mettere la stringa, e la serializzazione fallirebbe a runtime.
+
+    // Datazione invece ha solo varianti con campi: basta il tag interno
+    println!();
+    for datazione in [
+        Datazione::Periodo { periodo: Periodo::BronzoFinale },
+        Datazione::Intervallo { da: -1200, a: -1100 },
+        Datazione::Radiocarbonio { anni_bp: 3050, errore: 35 },
+    ] {
+        println!("  {}", serde_json::to_string(&datazione).unwrap());
+    }
+
+    // Per gli enum senza dati (Periodo) conta solo il nome: `rename_all`
+    // lo sceglie, `alias` accetta anche i nomi usati in passato
+    let vecchio: Periodo = serde_json::from_str("\"BronzoMedio\"").unwrap();
+    let nuovo: Periodo = serde_json::from_str("\"bronzo_medio\"").unwrap();
+    println!("\n  \"BronzoMedio\" e \"bronzo_medio\" -> {:?}, {:?}", vecchio, nuovo);
+    println!("  Riscritto sempre come {}", serde_json::to_string(&vecchio).unwrap());
+
+    println!();
+
+    // ========================================================================
+    // 17.4 - STRUTTURE VERSIONATE
+    // ========================================================================
+    println!("--- 17.4 Versioni del Formato ---\n");
+
+    // I file salvati un anno fa hanno un'altra forma. Invece di un
+    // `if` sparso nel codice, ogni versione ha il suo tipo e un campo
+    // "versione" fa da tag. `#[serde(from = ...)]` su Scheda fa si che
+    // chiunque deserializzi una Scheda passi dalla migrazione.
+    let file = [
+        r#"{"versione":"1","nome":"Ascia ad alette","periodo":"BronzoRecente","peso":364.0}"#,
+        r#"{"versione":"2","nome":"Rasoio lunato","materiale":{"tipo":"bronzo"},
+            "datazione":{"tipo":"radiocarbonio","anni_bp":2980,"errore":40},"peso_grammi":41.2}"#,
+        r#"{"versione":"3","nome":"Dal futuro"}"#,
+    ];
+    for json in file {
+        match serde_json::from_str::<Scheda>(json) {
+            Ok(scheda) => println!("  {:?}", scheda),
+            Err(e) => println!("  Errore: {}", e),
+        }
+    }
+
+    // `into = ...` fa il percorso inverso: si scrive sempre l'ultima versione
+    let migrata: Scheda = serde_json::from_str(file[0]).unwrap();
+    println!("  Salvata di nuovo: {}", serde_json::to_string(&migrata).unwrap());
+
+    println!();
+
+    // ========================================================================
+    // 17.5 - UN DESERIALIZER PER IL VECCHIO CSV
+    // ========================================================================
+    println!("--- 17.5 Un Deserializer Scritto a Mano ---\n");
+
+    // Il registro cartaceo e stato trascritto anni fa in un foglio di
+    // calcolo: campi separati da ';', virgola decimale, celle vuote per i
+    // dati mancanti, periodi con i vecchi nomi (BronzoMedio). Invece di
+    // spezzare le righe a mano come in Corpus::importa_csv (capitolo 9),
+    // scriviamo un Deserializer: da quel momento qualsiasi struct con
+    // `#[derive(Deserialize)]` si legge da quel formato.
+    //
+    //   serde_json::Deserializer  JSON -> modello di dati serde
+    //   RigaCsv (qui sotto)        riga ';' -> modello di dati serde
+    //   #[derive(Deserialize)]     modello di dati serde -> la tua struct
+    let registro = "\
+# nome;periodo;materiale;peso;sito
+Ascia a margini rialzati;BronzoMedio;bronzo;327,6;Savignano
+Pugnale triangolare;BronzoAntico;rame;198,3;
+Vago di collana;BronzoFinale;ambra;;Frattesina
+";
+    let righe: Vec<RigaRegistro> = da_csv_legacy(registro).unwrap();
+    for riga in &righe {
+        println!("  {:?}", riga);
+    }
+
+    // Le righe del registro diventano schede della versione corrente
+    println!();
+    for riga in righe {
+        let scheda = Scheda::from(riga);
+        println!("  {}", serde_json::to_string(&scheda).unwrap());
+    }
+
+    // Gli errori dicono la riga e cosa non va
+    println!();
+    for sbagliato in [
+        "Falce;BronzoRecente;bronzo;circa 90;\n",
+        "Fibula;Neolitico;bronzo;12;\n",
+        "Spillone;BronzoMedio;bronzo\n",
+        "Spillone;BronzoMedio;bronzo;31;Frattesina;1998\n",
+    ] {
+        if let Err(e) = da_csv_legacy::<RigaRegistro>(sbagliato) {
+            println!("  {}", e);
+        }
+    }
+
+    println!();
+
+    // ========================================================================
+    // 17.6 - RIEPILOGO
+    // ========================================================================
+    println!("--- 17.6 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "SERDE IN PROFONDITA",
+        &[
+            "Campi",
+            "  rename / rename_all / alias  i nomi",
+            "  default / skip / skip_serializing_if",
+            "  deny_unknown_fields   niente refusi",
+            "  flatten               annidato <-> piatto",
+            "",
+            "Enum",
+            "  tag = \"tipo\"          tag interno",
+            "  tag + content         tag adiacente",
+            "  untagged              prova ogni variante",
+            "",
+            "Versioni: enum con tag + from / into",
+            "Formati nuovi: impl Deserializer<'de>",
+        ],
+    );
+
+    schermo::completato(17);
+}
+
+// ============================================================================
+// CAMPI: RENAME, DEFAULT, SKIP
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SchedaWeb {
+    id: u32,
+    // Il nome nel JSON e diverso da quello in Rust
+    #[serde(rename = "denominazione")]
+    nome: String,
+    peso_grammi: f64,
+    // Option: null o assente in lettura, omesso in scrittura se None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    numero_inventario: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    note: Vec<String>,
+    // Stato dell'interfaccia: non fa parte dei dati
+    #[serde(skip)]
+    selezionata: bool,
+}
+
+// ============================================================================
+// FLATTEN
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Misure {
+    lunghezza_cm: f64,
+    larghezza_cm: f64,
+}
+
+impl Misure {
+    fn rapporto(&self) -> f64 {
+        self.lunghezza_cm / self.larghezza_cm
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RepertoMisurato {
+    id: u32,
+    nome: String,
+    #[serde(flatten)]
+    misure: Misure,
+    // Tutto cio che non corrisponde a un campo finisce qui
+    #[serde(flatten)]
+    altri: BTreeMap<String, serde_json::Value>,
+}
+
+// ============================================================================
+// ENUM
+// ============================================================================
+
+/// Periodo: nuovi nomi in snake_case, i vecchi accettati in lettura
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Periodo {
+    #[serde(alias = "BronzoAntico")]
+    BronzoAntico,
+    #[serde(alias = "BronzoMedio")]
+    BronzoMedio,
+    #[serde(alias = "BronzoRecente")]
+    BronzoRecente,
+    #[serde(alias = "BronzoFinale")]
+    BronzoFinale,
+    #[serde(alias = "PrimaEtaFerro")]
+    PrimaEtaFerro,
+}
+
+/// Materiale con tag adiacente: {"tipo": ..., "dettagli": ...}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tipo", content = "dettagli", rename_all = "snake_case")]
+enum Materiale {
+    Bronzo,
+    Rame,
+    Oro,
+    Lega { rame: f64, stagno: f64 },
+    Altro(String),
+}
+
+impl Materiale {
+    /// Il materiale come veniva scritto a mano nel registro
+    fn da_testo(testo: &str) -> Self {
+        match testo.trim().to_lowercase().as_str() {
+            "bronzo" => Materiale::Bronzo,
+            "rame" => Materiale::Rame,
+            "oro" => Materiale::Oro,
+            altro => Materiale::Altro(altro.to_string()),
+        }
+    }
+}
+
+/// Datazione con tag interno: {"tipo": ..., altri campi}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+enum Datazione {
+    Periodo { periodo: Periodo },
+    Intervallo { da: i32, a: i32 },
+    Radiocarbonio { anni_bp: u32, errore: u32 },
+}
+
+// ============================================================================
+// STRUTTURE VERSIONATE
+// ============================================================================
+
+/// La scheda come la usa il programma
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SchedaSuFile", into = "SchedaSuFile")]
+struct Scheda {
+    nome: String,
+    materiale: Materiale,
+    datazione: Datazione,
+    peso_grammi: Option<f64>,
+}
+
+/// Tutte le forme che una scheda ha avuto su file
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "versione")]
+enum SchedaSuFile {
+    #[serde(rename = "1")]
+    V1(SchedaV1),
+    #[serde(rename = "2")]
+    V2(SchedaV2),
+}
+
+/// Versione 1: solo bronzi, datazione per periodo, peso obbligatorio
+#[derive(Serialize, Deserialize)]
+struct SchedaV1 {
+    nome: String,
+    periodo: Periodo,
+    peso: f64,
+}
+
+/// Versione 2, quella attuale. E separata da Scheda: quando arrivera la
+/// versione 3, questa resta com'e e Scheda cambia.
+#[derive(Serialize, Deserialize)]
+struct SchedaV2 {
+    nome: String,
+    materiale: Materiale,
+    datazione: Datazione,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    peso_grammi: Option<f64>,
+}
+
+impl From<SchedaSuFile> for Scheda {
+    fn from(file: SchedaSuFile) -> Self {
+        match file {
+            SchedaSuFile::V1(v1) => Scheda {
+                nome: v1.nome,
+                materiale: Materiale::Bronzo,
+                datazione: Datazione::Periodo { periodo: v1.periodo },
+                peso_grammi: Some(v1.peso),
+            },
+            SchedaSuFile::V2(v2) => Scheda {
+                nome: v2.nome,
+                materiale: v2.materiale,
+                datazione: v2.datazione,
+                peso_grammi: v2.peso_grammi,
+            },
+        }
+    }
+}
+
+impl From<Scheda> for SchedaSuFile {
+    fn from(scheda: Scheda) -> Self {
+        SchedaSuFile::V2(SchedaV2 {
+            nome: scheda.nome,
+            materiale: scheda.materiale,
+            datazione: scheda.datazione,
+            peso_grammi: scheda.peso_grammi,
+        })
+    }
+}
+
+// ============================================================================
+// IL VECCHIO CSV DEL REGISTRO
+// ============================================================================
+
+/// Una riga del registro; l'ordine dei campi e quello delle colonne
+#[derive(Debug, Deserialize)]
+struct RigaRegistro {
+    nome: String,
+    periodo: Periodo,
+    materiale: String,
+    peso: Option<f64>,
+    sito: Option<String>,
+}
+
+impl From<RigaRegistro> for Scheda {
+    fn from(riga: RigaRegistro) -> Self {
+        let nome = match riga.sito {
+            Some(sito) => format!("{} ({})", riga.nome, sito),
+            None => riga.nome,
+        };
+        Scheda {
+            nome,
+            materiale: Materiale::da_testo(&riga.materiale),
+            datazione: Datazione::Periodo { periodo: riga.periodo },
+            peso_grammi: riga.peso,
+        }
+    }
+}
+
+/// Errore di lettura del CSV; `custom` e l'unico metodo richiesto da serde
+#[derive(Debug, thiserror::Error)]
+#[error("riga {riga}: {messaggio}")]
+struct ErroreCsv {
+    riga: usize,
+    messaggio: String,
+}
+
+impl de::Error for ErroreCsv {
+    fn custom<T: fmt::Display>(messaggio: T) -> Self {
+        // La riga non e nota qui: la aggiunge da_csv_legacy
+        ErroreCsv { riga: 0, messaggio: messaggio.to_string() }
+    }
+}
+
+/// Legge tutte le righe nel tipo T; salta righe vuote e commenti '#'
+fn da_csv_legacy<T: DeserializeOwned>(testo: &str) -> Result<Vec<T>, ErroreCsv> {
+    let mut risultato = Vec::new();
+    for (n, riga) in testo.lines().enumerate() {
+        if riga.trim().is_empty() || riga.starts_with('#') {
+            continue;
+        }
+        let mut deserializzatore = RigaCsv { campi: riga.split(';') };
+        let valore = T::deserialize(&mut deserializzatore)
+            .and_then(|valore| match deserializzatore.campi.next() {
+                Some(_) => Err(de::Error::custom("troppi campi")),
+                None => Ok(valore),
+            })
+            .map_err(|e: ErroreCsv| ErroreCsv { riga: n + 1, ..e })?;
+        risultato.push(valore);
+    }
+    Ok(risultato)
+}
+
+/// Una riga intera: per serde e una sequenza di campi
+struct RigaCsv<'de> {
+    campi: std::str::Split<'de, char>,
+}
+
+impl<'de> de::Deserializer<'de> for &mut RigaCsv<'de> {
+    type Error = ErroreCsv;
+
+    // Una struct, una tupla o una sequenza: i campi in ordine
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_seq(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for RigaCsv<'de> {
+    type Error = ErroreCsv;
+
+    fn next_element_seed<S: de::DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, ErroreCsv> {
+        match self.campi.next() {
+            Some(campo) => seed.deserialize(Campo(campo.trim())).map(Some),
+            // Nessun altro campo: serde segnala "invalid length" da solo
+            None => Ok(None),
+        }
+    }
+}
+
+/// Un singolo campo: qui stanno le regole del dialetto
+struct Campo<'de>(&'de str);
+
+impl Campo<'_> {
+    fn numero<N: std::str::FromStr>(&self) -> Result<N, ErroreCsv> {
+        // Virgola decimale, come nei fogli di calcolo italiani
+        self.0
+            .replace(',', ".")
+            .parse()
+            .map_err(|_| de::Error::custom(format!("numero non valido: '{}'", self.0)))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Campo<'de> {
+    type Error = ErroreCsv;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_f64(self.numero()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_u32(self.numero()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_i32(self.numero()?)
+    }
+
+    // Cella vuota = None
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ErroreCsv> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    // Solo varianti senza dati: il testo e il nome della variante.
+    // Gli `alias` di Periodo valgono anche qui.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _nome: &'static str,
+        _varianti: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ErroreCsv> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i64 i128 u8 u16 u64 u128 f32 char str string bytes byte_buf
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
@@ -0,0 +1,524 @@
+// ============================================================================
+// CAPITOLO 18: DESIGN PATTERN IN RUST
+// ============================================================================
+// I design pattern classici nascono per linguaggi a oggetti. In Rust alcuni
+// diventano inutili, altri cambiano forma grazie a ownership, trait e
+// sistema dei tipi. Qui ne vediamo quattro applicati all'inventario della
+// libreria (src/inventario.rs):
+//
+//   newtype    un tipo nuovo attorno a uno esistente: Grammi, NumeroInventario
+//   builder    costruire un oggetto un pezzo alla volta
+//   typestate  lo stato nel tipo: una scheda non validata non si salva
+//   strategy   esportatori intercambiabili dietro un trait
+//
+// Esegui con: cargo run --example cap18_pattern
+// ============================================================================
+
+use std::fmt;
+use std::str::FromStr;
+
+use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+use rust_tutorial::schede;
+use rust_tutorial::schermo;
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 18: DESIGN PATTERN IN RUST"]);
+
+    // ========================================================================
+    // 18.1 - NEWTYPE
+    // ========================================================================
+    println!("--- 18.1 Newtype ---\n");
+
+    // Nel capitolo 3 avevamo `struct Peso(f64)` per non confondere pesi e
+    // lunghezze. Il newtype diventa davvero utile quando il costruttore
+    // controlla il valore: un `Grammi` esistente e sempre valido, e chi lo
+    // riceve non deve ricontrollarlo.
+    for valore in [327.6, -3.0, f64::NAN] {
+        match Grammi::nuovo(valore) {
+            Ok(g) => println!("  Grammi::nuovo({}) -> {}", valore, g),
+            Err(e) => println!("  Grammi::nuovo({}) -> {}", valore, e),
+        }
+    }
+
+    // Con FromStr il newtype si legge da testo con .parse(), come i numeri
+    for testo in ["SAV-2024-0007", "sav-2024-7", "SAV-24"] {
+        match testo.parse::<NumeroInventario>() {
+            Ok(n) => println!("  {:<14} -> {} (sito {}, anno {})", testo, n, n.sito(), n.anno()),
+            Err(e) => println!("  {:<14} -> errore: {}", testo, e),
+        }
+    }
+
+    // Il newtype serve anche a aggirare la regola dell'orfano: non si puo
+    // implementare Display (trait esterno) per Vec<Reperto> (tipo esterno),
+    // ma per un nostro tipo che lo avvolge si
+    let elenco = Elenco(vec![
+        Reperto::nuovo("Pugnale triangolare", Periodo::Antico, 198.3),
+        Reperto::nuovo("Fibula ad arco", Periodo::Recente, 26.9),
+    ]);
+    print!("{}", elenco);
+    // Costo a runtime: zero. Grammi occupa esattamente quanto un f64.
+    println!("  size_of::<Grammi>() = {} byte", std::mem::size_of::<Grammi>());
+
+    println!();
+
+    // ========================================================================
+    // 18.2 - BUILDER
+    // ========================================================================
+    println!("--- 18.2 Builder ---\n");
+
+    // Rust non ha argomenti con nome ne valori predefiniti. Invece di un
+    // costruttore con sei parametri, di cui quattro opzionali, si parte dai
+    // dati obbligatori e si aggiunge il resto con metodi concatenati.
+    // Ogni metodo prende `self` e lo restituisce: la catena si legge come
+    // una frase e non servono variabili intermedie.
+    let bozza = Scheda::nuova("Ascia a margini rialzati")
+        .periodo(Periodo::Medio)
+        .peso(327.6)
+        .sito("Savignano sul Panaro")
+        .nota("margini poco rilevati")
+        .nota("patina verde uniforme");
+    println!("  {:?}", bozza);
+
+    // I campi mancanti restano vuoti: la scheda e ancora una bozza
+    let incompleta = Scheda::nuova("Frammento di lama").sito("Frattesina");
+    println!("  {:?}", incompleta);
+
+    println!();
+
+    // ========================================================================
+    // 18.3 - TYPESTATE
+    // ========================================================================
+    println!("--- 18.3 Typestate ---\n");
+
+    // Lo stato della scheda fa parte del tipo: Scheda<Bozza> o
+    // Scheda<Validata>. Solo Scheda<Validata> ha il metodo `salva`, e
+    // l'unico modo di ottenerla e `valida()`. Scrivere
+    //
+    //   bozza.salva(&mut inv)
+    //
+    // non e un errore a runtime: non compila.
+    //
+    //   error[E0599]: no method named `salva` found for struct `Scheda<Bozza>`
+    let mut inv = Inventario::nuovo();
+    for bozza in [
+        bozza,
+        incompleta,
+        Scheda::nuova("  ").periodo(Periodo::Finale).peso(-5.0),
+    ] {
+        match bozza.valida() {
+            Ok(validata) => {
+                // Qui periodo e peso non sono piu Option: nessun unwrap
+                let riassunto = format!("{:?}, {}", validata.stato.periodo, validata.stato.peso);
+                match validata.salva(&mut inv) {
+                    Ok(id) => println!("  Salvata come #{} ({})", id, riassunto),
+                    Err(e) => println!("  Rifiutata dall'inventario: {}", e),
+                }
+            }
+            Err(rifiutata) => {
+                println!("  Non valida: {}", rifiutata.problemi.join(", "));
+                // La bozza torna indietro intatta: si puo correggere e riprovare
+                let corretta = rifiutata.bozza.periodo(Periodo::Finale).peso(41.0);
+                if let Ok(validata) = corretta.valida() {
+                    println!("  Corretta e salvata come #{}", validata.salva(&mut inv).unwrap());
+                }
+            }
+        }
+    }
+    // Spesso lo stato e una struct vuota (`struct Bozza;`) e non occupa
+    // memoria. Qui ogni stato porta i suoi dati: Option nella bozza,
+    // valori certi nella scheda validata. La versione a stati vuoti, con
+    // un terzo stato Catalogato, e nella libreria: src/inventario/scheda.rs
+    println!("  Reperti nell'inventario: {}", inv.len());
+
+    println!();
+
+    // ========================================================================
+    // 18.4 - STRATEGY: ESPORTATORI
+    // ========================================================================
+    println!("--- 18.4 Strategy ---\n");
+
+    // L'algoritmo (come esportare) e separato da chi lo usa (l'inventario).
+    // In Rust la strategia e un trait; si sceglie:
+    //   - a runtime, con Box<dyn Esportatore>: lista di esportatori, scelta per nome
+    //   - a compilazione, con un tipo concreto: nessun costo dinamico
+    // `esporta` e generica e accetta entrambi.
+    let esportatori: Vec<Box<dyn Esportatore>> = vec![
+        Box::new(Csv { separatore: ';' }),
+        Box::new(Json),
+        Box::new(Markdown),
+        // Una strategia "al volo": una closure per riga, senza un nuovo tipo
+        Box::new(PerRiga(|r: &Reperto| format!("#{} {}", r.id, r.nome.to_uppercase()))),
+    ];
+    for esportatore in &esportatori {
+        println!("  [{}] file inventario.{}", esportatore.nome(), esportatore.estensione());
+        for riga in esporta(&inv, esportatore.as_ref()).lines() {
+            println!("    {}", riga);
+        }
+    }
+
+    // Scelta per nome, come farebbe un'opzione --formato
+    match esportatori.iter().find(|e| e.nome() == "markdown") {
+        Some(e) => println!("  --formato markdown -> {} byte", esporta(&inv, e.as_ref()).len()),
+        None => println!("  formato sconosciuto"),
+    }
+
+    // Dispatch statico: con un tipo concreto il compilatore genera una
+    // versione di `esporta` apposta per Csv, senza chiamate indirette
+    let tsv = esporta(&inv, &Csv { separatore: '\t' });
+    println!("  Statico (TSV): {} righe", tsv.lines().count());
+
+    println!();
+
+    // ========================================================================
+    // 18.5 - RIEPILOGO
+    // ========================================================================
+    println!("--- 18.5 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "DESIGN PATTERN IN RUST",
+        &[
+            "newtype    struct Grammi(f64)",
+            "           valori validi per costruzione",
+            "builder    .periodo(..).peso(..) su self",
+            "typestate  Scheda<Bozza> / Scheda<Validata>",
+            "           gli usi sbagliati non compilano",
+            "strategy   trait Esportatore",
+            "           dyn a runtime, generico statico",
+        ],
+    );
+
+    schermo::completato(18);
+}
+
+// ============================================================================
+// NEWTYPE
+// ============================================================================
+
+/// Peso in grammi, sempre finito e positivo
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+struct Grammi(f64);
+
+impl Grammi {
+    fn nuovo(valore: f64) -> Result<Grammi, ErroreInventario> {
+        if valore.is_finite() && valore > 0.0 {
+            Ok(Grammi(valore))
+        } else {
+            Err(ErroreInventario::PesoNonValido(valore))
+        }
+    }
+
+    fn valore(self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Grammi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} g", self.0)
+    }
+}
+
+/// Numero di inventario "SITO-ANNO-NNNN"; i campi sono privati, quindi
+/// l'unico modo di crearne uno e passare da `parse`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NumeroInventario {
+    sito: String,
+    anno: u16,
+    progressivo: u32,
+}
+
+impl NumeroInventario {
+    fn sito(&self) -> &str {
+        &self.sito
+    }
+
+    fn anno(&self) -> u16 {
+        self.anno
+    }
+}
+
+impl FromStr for NumeroInventario {
+    type Err = String;
+
+    fn from_str(testo: &str) -> Result<Self, Self::Err> {
+        let parti: Vec<&str> = testo.split('-').collect();
+        let [sito, anno, progressivo] = parti[..] else {
+            return Err(format!("attese 3 parti, trovate {}", parti.len()));
+        };
+        if sito.is_empty() || !sito.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(format!("sigla del sito '{}' non valida", sito));
+        }
+        Ok(NumeroInventario {
+            sito: sito.to_string(),
+            anno: anno.parse().map_err(|_| format!("anno '{}' non valido", anno))?,
+            progressivo: progressivo.parse().map_err(|_| format!("progressivo '{}' non valido", progressivo))?,
+        })
+    }
+}
+
+impl fmt::Display for NumeroInventario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{:04}", self.sito, self.anno, self.progressivo)
+    }
+}
+
+/// Newtype per implementare Display su un Vec
+struct Elenco(Vec<Reperto>);
+
+impl fmt::Display for Elenco {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, r) in self.0.iter().enumerate() {
+            writeln!(f, "  {}. {} ({:?}, {} g)", i + 1, r.nome, r.periodo, r.peso_grammi)?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// BUILDER E TYPESTATE
+// ============================================================================
+
+/// Stato: scheda ancora da controllare, i dati possono mancare
+#[derive(Debug)]
+struct Bozza {
+    periodo: Option<Periodo>,
+    peso: Option<f64>,
+}
+
+/// Stato: scheda controllata, i dati ci sono e sono validi
+#[derive(Debug)]
+struct Validata {
+    periodo: Periodo,
+    peso: Grammi,
+}
+
+/// Scheda di un reperto; `S` e lo stato (Bozza o Validata)
+#[derive(Debug)]
+struct Scheda<S> {
+    nome: String,
+    sito: Option<String>,
+    note: Vec<String>,
+    stato: S,
+}
+
+/// Una bozza che non ha passato la validazione, con i motivi
+struct Rifiutata {
+    bozza: Scheda<Bozza>,
+    problemi: Vec<String>,
+}
+
+// Metodi disponibili in qualsiasi stato
+impl<S> Scheda<S> {
+    fn descrizione(&self) -> String {
+        match &self.sito {
+            Some(sito) => format!("{} ({})", self.nome, sito),
+            None => self.nome.clone(),
+        }
+    }
+}
+
+// Metodi della sola bozza: il builder e la validazione
+impl Scheda<Bozza> {
+    fn nuova(nome: &str) -> Self {
+        Scheda {
+            nome: nome.to_string(),
+            sito: None,
+            note: Vec::new(),
+            stato: Bozza { periodo: None, peso: None },
+        }
+    }
+
+    fn periodo(mut self, periodo: Periodo) -> Self {
+        self.stato.periodo = Some(periodo);
+        self
+    }
+
+    fn peso(mut self, grammi: f64) -> Self {
+        self.stato.peso = Some(grammi);
+        self
+    }
+
+    fn sito(mut self, sito: &str) -> Self {
+        self.sito = Some(sito.to_string());
+        self
+    }
+
+    fn nota(mut self, nota: &str) -> Self {
+        self.note.push(nota.to_string());
+        self
+    }
+
+    /// Consuma la bozza: o diventa Validata o torna indietro con i problemi
+    fn valida(self) -> Result<Scheda<Validata>, Box<Rifiutata>> {
+        let mut problemi = Vec::new();
+        if self.nome.trim().is_empty() {
+            problemi.push("nome vuoto".to_string());
+        }
+        let peso = match self.stato.peso.map(Grammi::nuovo) {
+            Some(Ok(g)) => Some(g),
+            Some(Err(e)) => {
+                problemi.push(e.to_string());
+                None
+            }
+            None => {
+                problemi.push("peso mancante".to_string());
+                None
+            }
+        };
+        if self.stato.periodo.is_none() {
+            problemi.push("periodo mancante".to_string());
+        }
+
+        match (self.stato.periodo, peso) {
+            (Some(periodo), Some(peso)) if problemi.is_empty() => Ok(Scheda {
+                nome: self.nome,
+                sito: self.sito,
+                note: self.note,
+                stato: Validata { periodo, peso },
+            }),
+            _ => Err(Box::new(Rifiutata { bozza: self, problemi })),
+        }
+    }
+}
+
+// Metodi della sola scheda validata
+impl Scheda<Validata> {
+    /// Consuma la scheda: una volta salvata non si puo salvare di nuovo
+    fn salva(self, inventario: &mut Inventario) -> Result<u32, ErroreInventario> {
+        let nome = self.descrizione();
+        inventario.aggiungi(Reperto::nuovo(&nome, self.stato.periodo, self.stato.peso.valore()))
+    }
+}
+
+// ============================================================================
+// STRATEGY
+// ============================================================================
+
+/// Un formato di esportazione dell'inventario
+trait Esportatore {
+    fn nome(&self) -> &str;
+
+    fn estensione(&self) -> &str {
+        "txt"
+    }
+
+    fn intestazione(&self) -> Option<String> {
+        None
+    }
+
+    fn riga(&self, reperto: &Reperto) -> String;
+
+    /// Cosa mettere tra una riga e l'altra
+    fn separatore(&self) -> &str {
+        "\n"
+    }
+
+    fn chiusura(&self) -> Option<String> {
+        None
+    }
+}
+
+struct Csv {
+    separatore: char,
+}
+
+impl Esportatore for Csv {
+    fn nome(&self) -> &str {
+        "csv"
+    }
+
+    fn estensione(&self) -> &str {
+        if self.separatore == '\t' { "tsv" } else { "csv" }
+    }
+
+    fn intestazione(&self) -> Option<String> {
+        Some(["id", "nome", "periodo", "peso"].join(&self.separatore.to_string()))
+    }
+
+    fn riga(&self, r: &Reperto) -> String {
+        let s = self.separatore;
+        format!("{}{s}{}{s}{:?}{s}{}", r.id, r.nome, r.periodo, r.peso_grammi)
+    }
+}
+
+struct Json;
+
+impl Esportatore for Json {
+    fn nome(&self) -> &str {
+        "json"
+    }
+
+    fn estensione(&self) -> &str {
+        "json"
+    }
+
+    fn intestazione(&self) -> Option<String> {
+        Some("[".to_string())
+    }
+
+    fn riga(&self, r: &Reperto) -> String {
+        let valore = serde_json::json!({
+            "id": r.id,
+            "nome": r.nome,
+            "periodo": format!("{:?}", r.periodo),
+            "peso_grammi": r.peso_grammi,
+        });
+        format!("  {}", valore)
+    }
+
+    // Niente virgola dopo l'ultimo elemento
+    fn separatore(&self) -> &str {
+        ",\n"
+    }
+
+    fn chiusura(&self) -> Option<String> {
+        Some("]".to_string())
+    }
+}
+
+/// Colonne, etichette e unita vengono da `SchedaStampabile` di Reperto
+/// (src/inventario.rs): un campo nuovo compare qui senza toccare l'esportatore
+struct Markdown;
+
+impl Esportatore for Markdown {
+    fn nome(&self) -> &str {
+        "markdown"
+    }
+
+    fn estensione(&self) -> &str {
+        "md"
+    }
+
+    fn intestazione(&self) -> Option<String> {
+        Some(schede::intestazione_markdown::<Reperto>())
+    }
+
+    fn riga(&self, r: &Reperto) -> String {
+        schede::riga_markdown(r)
+    }
+}
+
+/// Adatta una closure al trait: la strategia piu leggera possibile
+struct PerRiga<F>(F);
+
+impl<F: Fn(&Reperto) -> String> Esportatore for PerRiga<F> {
+    fn nome(&self) -> &str {
+        "personalizzato"
+    }
+
+    fn riga(&self, reperto: &Reperto) -> String {
+        (self.0)(reperto)
+    }
+}
+
+/// Il "contesto" del pattern: non sa quale formato sta usando.
+/// `?Sized` accetta sia un tipo concreto (dispatch statico) sia `dyn Esportatore`.
+fn esporta<E: Esportatore + ?Sized>(inventario: &Inventario, esportatore: &E) -> String {
+    let corpo: Vec<String> = inventario.iter().map(|r| esportatore.riga(r)).collect();
+    let mut parti: Vec<String> = esportatore.intestazione().into_iter().collect();
+    if !corpo.is_empty() {
+        parti.push(corpo.join(esportatore.separatore()));
+    }
+    parti.extend(esportatore.chiusura());
+    parti.join("\n")
+}
@@ -0,0 +1,269 @@
+// ============================================================================
+// CAPITOLO 13: TEST AUTOMATICI
+// ============================================================================
+// Fin qui abbiamo verificato il codice "a occhio", eseguendo i capitoli e
+// leggendo l'output. Rust ha un sistema di test integrato in Cargo: basta
+// scrivere funzioni con `#[test]` e lanciare `cargo test`.
+//
+// In questo capitolo:
+//   - test unitari accanto al codice (src/inventario.rs e in fondo a questo file)
+//   - test di integrazione nella cartella tests/ (tests/inventario.rs)
+//   - doctest: gli esempi nella documentazione vengono eseguiti davvero
+//   - come scegliere quali test eseguire con i filtri di `cargo test`
+//
+// Esegui con: cargo run --example cap_test
+// Test con:   cargo test --example cap_test
+// ============================================================================
+
+use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+use rust_tutorial::schermo;
+
+fn main() {
+    schermo::intestazione(&["CAPITOLO 13: TEST AUTOMATICI"]);
+
+    // ========================================================================
+    // 13.1 - IL CODICE DA TESTARE
+    // ========================================================================
+    println!("--- 13.1 Il Codice da Testare ---\n");
+
+    // Per poter essere testato da tests/ e dai doctest, l'inventario vive
+    // nella libreria del progetto (src/lib.rs), non in un esempio.
+    // Gli esempi e i test lo importano come qualsiasi crate esterno:
+    //   use rust_tutorial::inventario::{Inventario, Reperto, ...};
+    let mut inv = Inventario::nuovo();
+    for (nome, periodo, peso) in [
+        ("Pugnale triangolare", Periodo::Antico, 198.3),
+        ("Ascia a margini rialzati", Periodo::Medio, 327.6),
+        ("Spada a lingua da presa", Periodo::Finale, 779.6),
+    ] {
+        let id = inv.aggiungi(Reperto::nuovo(nome, periodo, peso)).unwrap();
+        println!("  #{} {:<26} {}", id, nome, numero_inventario("sav", 2024, id));
+    }
+    println!("  Peso totale: {:.1} g", inv.peso_totale());
+
+    // Questi sono i casi che un test deve fissare una volta per tutte
+    match inv.aggiungi(Reperto::nuovo("", Periodo::Medio, 10.0)) {
+        Err(e) => println!("  Nome vuoto    -> {}", e),
+        Ok(id) => println!("  Nome vuoto accettato?! id {}", id),
+    }
+    if let Err(ErroreInventario::RepertoNonTrovato(id)) = inv.cerca_per_id(99) {
+        println!("  Id inesistente -> RepertoNonTrovato({})", id);
+    }
+    // Anche le funzioni di supporto in fondo al file hanno i loro test
+    for testo in ["SAV-2024-0003", "SAV-2024"] {
+        println!("  Lettura di {:<14} -> {:?}", testo, leggi_numero_inventario(testo));
+    }
+
+    println!();
+
+    // ========================================================================
+    // 13.2 - TEST UNITARI
+    // ========================================================================
+    println!("--- 13.2 Test Unitari ---\n");
+
+    // Un test e una funzione senza argomenti marcata `#[test]`.
+    // Passa se termina normalmente, fallisce se va in panico.
+    //
+    //   #[cfg(test)]            // compilato solo da `cargo test`
+    //   mod tests {
+    //       use super::*;       // vede tutto il modulo, anche il privato
+    //
+    //       #[test]
+    //       fn nome_vuoto_rifiutato() {
+    //           let mut inv = Inventario::nuovo();
+    //           let esito = inv.aggiungi(Reperto::nuovo("   ", Periodo::Medio, 10.0));
+    //           assert_eq!(esito, Err(ErroreInventario::NomeVuoto));
+    //       }
+    //   }
+    //
+    // Le macro di verifica:
+    //   assert!(cond)           la condizione deve essere vera
+    //   assert_eq!(a, b)        a == b, in caso contrario stampa entrambi i valori
+    //   assert_ne!(a, b)        a != b
+    //   assert!(cond, "msg {}", x)   messaggio personalizzato
+    println!("  src/inventario.rs     -> mod tests, accanto al codice");
+    println!("  fine di questo file   -> mod tests dell'esempio");
+    println!("  Stesso file = accesso ai campi privati (es. prossimo_id)");
+
+    // Attributi utili:
+    //   #[should_panic(expected = "testo")]   il test passa SOLO se va in panico
+    //   #[ignore]                              saltato, salvo `cargo test -- --ignored`
+    //   fn test() -> Result<(), E>             si puo usare `?` dentro il test
+    println!("  Attributi: #[should_panic], #[ignore], test che restituiscono Result");
+
+    println!();
+
+    // ========================================================================
+    // 13.3 - TEST DI INTEGRAZIONE
+    // ========================================================================
+    println!("--- 13.3 Test di Integrazione ---\n");
+
+    // Ogni file .rs in tests/ e compilato come un crate separato che usa
+    // la libreria dall'esterno: vede solo cio che e `pub`. Servono a
+    // verificare flussi completi, come li vivrebbe un utente della libreria.
+    //
+    // Le funzioni degli esempi invece sono private e un esempio non si puo
+    // importare. tests/cap04.rs (e cap03, cap10, cap12) usa `include!`:
+    // il sorgente del capitolo viene copiato nel crate di test e le sue
+    // funzioni, come `leggi_eta`, si chiamano direttamente.
+    println!("  rust-tutorial/");
+    println!("  ├── src/");
+    println!("  │   ├── lib.rs            <- la libreria");
+    println!("  │   └── inventario.rs     <- codice + test unitari");
+    println!("  ├── tests/");
+    println!("  │   ├── inventario.rs     <- test di integrazione");
+    println!("  │   └── cap04.rs          <- test delle funzioni del capitolo 4");
+    println!("  └── examples/");
+    println!("      └── cap_test.rs       <- questo capitolo");
+
+    println!();
+
+    // ========================================================================
+    // 13.4 - DOCTEST
+    // ========================================================================
+    println!("--- 13.4 Doctest ---\n");
+
+    // I blocchi di codice nei commenti `///` della libreria sono test:
+    // `cargo test` li compila ed esegue. Cosi la documentazione non
+    // puo restare indietro rispetto al codice.
+    //
+    //   /// Cerca un reperto per id.
+    //   ///
+    //   /// ```
+    //   /// use rust_tutorial::inventario::{ErroreInventario, Inventario};
+    //   ///
+    //   /// let inv = Inventario::nuovo();
+    //   /// assert_eq!(inv.cerca_per_id(7), Err(ErroreInventario::RepertoNonTrovato(7)));
+    //   /// ```
+    //   pub fn cerca_per_id(&self, id: u32) -> Result<&Reperto, ErroreInventario>
+    //
+    // I doctest esistono solo per le librerie: per questo l'inventario
+    // testato sta in src/lib.rs e non dentro un esempio.
+    println!("  Ogni ``` nei commenti /// di src/ e un test eseguito da cargo test");
+    println!("  Solo doctest:  cargo test --doc");
+
+    println!();
+
+    // ========================================================================
+    // 13.5 - SCEGLIERE QUALI TEST ESEGUIRE
+    // ========================================================================
+    println!("--- 13.5 Filtrare i Test ---\n");
+
+    // Gli argomenti PRIMA di `--` vanno a Cargo, quelli DOPO al programma
+    // di test. Il filtro e una sottostringa del nome completo del test
+    // (percorso del modulo incluso).
+    let comandi = [
+        ("cargo test", "tutto: unitari, integrazione, doctest"),
+        ("cargo test nome_vuoto", "solo i test il cui nome contiene 'nome_vuoto'"),
+        ("cargo test inventario::tests", "solo i test unitari del modulo"),
+        ("cargo test --lib", "solo i test unitari della libreria"),
+        ("cargo test --test inventario", "solo tests/inventario.rs"),
+        ("cargo test --test cap04", "le funzioni del capitolo 4"),
+        ("cargo test --doc", "solo i doctest"),
+        ("cargo test --example cap_test", "i test di questo capitolo"),
+        ("cargo test -- --exact nome", "nome esatto, non sottostringa"),
+        ("cargo test -- --ignored", "solo i test #[ignore]"),
+        ("cargo test -- --nocapture", "mostra i println! dei test"),
+        ("cargo test -- --test-threads=1", "uno alla volta, non in parallelo"),
+    ];
+    for (comando, descrizione) in comandi {
+        println!("  {:<34} {}", comando, descrizione);
+    }
+
+    println!();
+
+    // ========================================================================
+    // 13.6 - RIEPILOGO
+    // ========================================================================
+    println!("--- 13.6 Riepilogo ---\n");
+
+    schermo::riquadro(
+        "TEST IN RUST",
+        &[
+            "#[test]          una funzione = un test",
+            "#[cfg(test)]     codice solo per i test",
+            "assert_eq!       confronto con diagnostica",
+            "",
+            "Unitari:     nello stesso file, vedono",
+            "             anche il privato",
+            "Integrazione: tests/, solo API pubblica",
+            "Doctest:     esempi /// eseguiti davvero",
+            "",
+            "cargo test <filtro> -- <opzioni>",
+        ],
+    );
+
+    schermo::completato(13);
+}
+
+// ============================================================================
+// FUNZIONI DI SUPPORTO
+// ============================================================================
+
+/// Numero di inventario nel formato "SIGLA-ANNO-NNNN"
+fn numero_inventario(sigla: &str, anno: u32, progressivo: u32) -> String {
+    format!("{}-{}-{:04}", sigla.to_uppercase(), anno, progressivo)
+}
+
+/// Legge un numero di inventario; None se il formato non e valido
+fn leggi_numero_inventario(testo: &str) -> Option<(String, u32, u32)> {
+    let mut parti = testo.split('-');
+    let sigla = parti.next()?;
+    let anno = parti.next()?.parse().ok()?;
+    let progressivo = parti.next()?.parse().ok()?;
+    if parti.next().is_some() || sigla.is_empty() || !sigla.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    Some((sigla.to_string(), anno, progressivo))
+}
+
+// ============================================================================
+// TEST DEL CAPITOLO
+// ============================================================================
+// Anche un esempio puo avere test. Cargo li esegue con
+// `cargo test --example cap_test`; in Cargo.toml `test = true` li include
+// anche nel semplice `cargo test`.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formato_numero_inventario() {
+        assert_eq!(numero_inventario("sav", 2024, 7), "SAV-2024-0007");
+    }
+
+    #[test]
+    fn andata_e_ritorno() {
+        // Proprieta "round trip": scrivere e rileggere restituisce i dati di partenza
+        for (sigla, anno, n) in [("SAV", 2024, 1), ("PON", 1998, 1234)] {
+            let testo = numero_inventario(sigla, anno, n);
+            assert_eq!(leggi_numero_inventario(&testo), Some((sigla.to_string(), anno, n)));
+        }
+    }
+
+    #[test]
+    fn numeri_malformati() {
+        for testo in ["", "SAV", "SAV-2024", "SAV-anno-0001", "sav-2024-0001", "SAV-2024-0001-X"] {
+            assert_eq!(leggi_numero_inventario(testo), None, "accettato: {:?}", testo);
+        }
+    }
+
+    #[test]
+    fn test_che_restituisce_result() -> Result<(), ErroreInventario> {
+        let mut inv = Inventario::nuovo();
+        let id = inv.aggiungi(Reperto::nuovo("Ascia", Periodo::Medio, 327.6))?;
+        assert_eq!(inv.cerca_per_id(id)?.peso_grammi, 327.6);
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "lento: esempio di test da eseguire solo con --ignored"]
+    fn molti_reperti() {
+        let mut inv = Inventario::nuovo();
+        for i in 0..100_000 {
+            inv.aggiungi(Reperto::nuovo(&format!("Frammento {}", i), Periodo::Recente, 1.0)).unwrap();
+        }
+        assert_eq!(inv.len(), 100_000);
+    }
+}
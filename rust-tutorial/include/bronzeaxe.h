@@ -0,0 +1,79 @@
+/* Generato da build.rs con cbindgen: non modificare a mano. */
+
+#ifndef BRONZEAXE_H
+#define BRONZEAXE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Cresce solo quando cambia qualcosa di incompatibile nell'interfaccia.
+#define BRONZEAXE_VERSIONE_API 2
+
+// Un inventario aperto con `bronzeaxe_apri`. Da C e opaco: si usa solo
+// attraverso il puntatore.
+typedef struct BronzeaxeInventario BronzeaxeInventario;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Versione dell'interfaccia: un programma C la confronta con
+// `BRONZEAXE_VERSIONE_API` della sua intestazione.
+uint32_t bronzeaxe_versione_api(void);
+
+// Apre l'inventario di un archivio: `file.json`, `cartella/`,
+// `sqlite:file.db` o `postgres://...`, come `--archivio`. `passphrase`
+// serve per un file JSON cifrato, altrimenti e NULL. Restituisce NULL se
+// l'archivio non si legge.
+//
+// # Safety
+// `indirizzo` e NULL oppure una stringa UTF-8 terminata da zero;
+// `passphrase` e NULL oppure una stringa UTF-8 terminata da zero.
+struct BronzeaxeInventario *bronzeaxe_apri(const char *indirizzo, const char *passphrase);
+
+// Cerca i reperti il cui nome contiene `testo` (senza distinguere
+// maiuscole) o il cui periodo si chiama `testo` (es. "bronzomedio").
+// Scrive al massimo `capacita` id in `id`, in ordine crescente, e
+// restituisce quanti reperti corrispondono: se sono piu di `capacita` si
+// richiama con un buffer piu grande. -1 in caso di errore.
+//
+// # Safety
+// `inventario` viene da `bronzeaxe_apri`; `testo` e una stringa terminata
+// da zero; `id` punta ad almeno `capacita` elementi (NULL se `capacita` e 0).
+int64_t bronzeaxe_cerca(const struct BronzeaxeInventario *inventario,
+                        const char *testo,
+                        uint32_t *id,
+                        size_t capacita);
+
+// La scheda del reperto `id` in JSON (vedi `scheda_json`), da liberare con
+// `bronzeaxe_libera_stringa`. NULL se il reperto non esiste.
+//
+// # Safety
+// `inventario` viene da `bronzeaxe_apri` e non e ancora stato chiuso.
+char *bronzeaxe_scheda_json(const struct BronzeaxeInventario *inventario, uint32_t id);
+
+// Libera una stringa restituita dalla libreria. NULL e ammesso.
+//
+// # Safety
+// `testo` viene da `bronzeaxe_scheda_json` e non e gia stato liberato.
+void bronzeaxe_libera_stringa(char *testo);
+
+// Chiude l'inventario e ne libera la memoria. NULL e ammesso.
+//
+// # Safety
+// `inventario` viene da `bronzeaxe_apri` e non e gia stato chiuso.
+void bronzeaxe_chiudi(struct BronzeaxeInventario *inventario);
+
+// Il messaggio dell'ultimo errore in questo thread, o NULL se non ce ne
+// sono stati. Resta valido fino al prossimo errore nello stesso thread;
+// non va liberato.
+const char *bronzeaxe_ultimo_errore(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BRONZEAXE_H */
@@ -0,0 +1,44 @@
+/*
+ * Programma C di esempio per l'API di src/cabi.rs.
+ *
+ *   cargo rustc --lib --release --crate-type cdylib
+ *   cc include/esempio.c -Iinclude -Ltarget/release -lrust_tutorial -o esempio
+ *   LD_LIBRARY_PATH=target/release ./esempio inventario.json ascia
+ *
+ * L'inventario si indica come con --archivio (file.json, cartella/,
+ * sqlite:file.db, postgres://...); la passphrase di un file cifrato si
+ * legge da BRONZEAXE_PASSPHRASE.
+ */
+#include <stdlib.h>
+#include <stdio.h>
+
+#include "bronzeaxe.h"
+
+int main(int argc, char **argv) {
+    if (argc != 3) {
+        fprintf(stderr, "uso: %s <archivio> <testo>\n", argv[0]);
+        return 2;
+    }
+    if (bronzeaxe_versione_api() != BRONZEAXE_VERSIONE_API) {
+        fprintf(stderr, "libreria incompatibile con questa intestazione\n");
+        return 1;
+    }
+
+    BronzeaxeInventario *inventario = bronzeaxe_apri(argv[1], getenv("BRONZEAXE_PASSPHRASE"));
+    if (inventario == NULL) {
+        fprintf(stderr, "errore: %s\n", bronzeaxe_ultimo_errore());
+        return 1;
+    }
+
+    uint32_t id[16];
+    int64_t trovati = bronzeaxe_cerca(inventario, argv[2], id, 16);
+    printf("%lld reperti per \"%s\"\n", (long long)trovati, argv[2]);
+    for (int64_t i = 0; i < trovati && i < 16; i++) {
+        char *scheda = bronzeaxe_scheda_json(inventario, id[i]);
+        printf("%s\n", scheda);
+        bronzeaxe_libera_stringa(scheda); /* non free(): la memoria e di Rust */
+    }
+
+    bronzeaxe_chiudi(inventario);
+    return 0;
+}
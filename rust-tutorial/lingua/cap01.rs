@@ -0,0 +1,88 @@
+// ============================================================================
+// CATALOGO INGLESE: CAPITOLO 1
+// ============================================================================
+// Incluso da examples/cap01_basi.rs (vedi src/lingua.rs). I testi che non
+// compaiono qui (numeri, simboli) restano come sono.
+// ============================================================================
+
+macro_rules! inglese {
+    ("CAPITOLO 1: LE BASI DI RUST") => { "CHAPTER 1: RUST BASICS" };
+    ("--- 1.1 Variabili e Immutabilita ---\n") => { "--- 1.1 Variables and Immutability ---\n" };
+    ("x = {} (immutabile)") => { "x = {} (immutable)" };
+    ("y = {} (mutabile, prima)") => { "y = {} (mutable, before)" };
+    ("y = {} (mutabile, dopo)") => { "y = {} (mutable, after)" };
+    ("z dopo shadowing = {}") => { "z after shadowing = {}" };
+    ("Numero di spazi: {}\n") => { "Number of spaces: {}\n" };
+    ("--- 1.2 Tipi di Dato ---\n") => { "--- 1.2 Data Types ---\n" };
+    ("vero: {}, falso: {}") => { "true: {}, false: {}" };
+    ("lettera: {}, emoji: {}, ideogramma: {}") => { "letter: {}, emoji: {}, ideogram: {}" };
+    ("--- 1.3 Costanti ---\n") => { "--- 1.3 Constants ---\n" };
+    ("Velocita della luce: {} m/s") => { "Speed of light: {} m/s" };
+    ("Pi greco: {}") => { "Pi: {}" };
+    ("Max reperti: {}\n") => { "Max finds: {}\n" };
+    ("--- 1.4 Tuple e Array ---\n") => { "--- 1.4 Tuples and Arrays ---\n" };
+    ("Reperto: id={}, peso={}kg, nome={}") => { "Find: id={}, weight={}kg, name={}" };
+    ("Primo elemento: {}") => { "First element: {}" };
+    ("Secondo elemento: {}") => { "Second element: {}" };
+    ("Terzo elemento: {}") => { "Third element: {}" };
+    ("\nPrimo mese: {}") => { "\nFirst month: {}" };
+    ("Terzo mese: {}") => { "Third month: {}" };
+    ("Array di zeri: {:?}\n") => { "Array of zeros: {:?}\n" };
+    ("--- 1.5 Funzioni ---\n") => { "--- 1.5 Functions ---\n" };
+    ("Area rettangolo 5x3 = {:.1}") => { "Area of a 5x3 rectangle = {:.1}" };
+    ("Risultato del blocco: {}\n") => { "Block result: {}\n" };
+    ("--- 1.6 Controllo di Flusso ---\n") => { "--- 1.6 Control Flow ---\n" };
+    ("Fa caldo!") => { "It's hot!" };
+    ("Temperatura piacevole: {}°C") => { "Pleasant temperature: {}°C" };
+    ("Fa fresco!") => { "It's cool!" };
+    ("Stato: {}") => { "State: {}" };
+    ("Giorno {}: {}") => { "Day {}: {}" };
+    ("Voto {}: {}\n") => { "Grade {}: {}\n" };
+    ("--- 1.7 Cicli ---\n") => { "--- 1.7 Loops ---\n" };
+    ("Risultato del loop: {}") => { "Loop result: {}" };
+    ("Conto alla rovescia: ") => { "Countdown: " };
+    ("Via!") => { "Go!" };
+    ("Numeri da 1 a 5: ") => { "Numbers from 1 to 5: " };
+    ("\nReperti trovati:") => { "\nFinds recovered:" };
+    ("\nTabella 3x3 con loop etichettati:") => { "\n3x3 table with labelled loops:" };
+    ("\n--- 1.8 Formattazione ---\n") => { "\n--- 1.8 Formatting ---\n" };
+    ("Nome: {}") => { "Name: {}" };
+    ("Peso con 2 decimali: {:.2}") => { "Weight with 2 decimals: {:.2}" };
+    ("Quantita con padding: {:>10}") => { "Quantity with padding: {:>10}" };
+    ("Quantita con padding: {:<10}|") => { "Quantity with padding: {:<10}|" };
+    ("Quantita con zeri: {:05}") => { "Quantity with zeros: {:05}" };
+    ("Binario: {:b}") => { "Binary: {:b}" };
+    ("Esadecimale: {:x}") => { "Hexadecimal: {:x}" };
+    ("Debug di un array: {:?}") => { "Debug of an array: {:?}" };
+    ("Debug formattato: {:#?}") => { "Pretty debug: {:#?}" };
+    ("{linguaggio} e stato creato nel {anno}") => { "{linguaggio} was created in {anno}" };
+    ("Ciao, {}! Benvenuto nel mondo di Rust.") => { "Hello, {}! Welcome to the world of Rust." };
+
+    // Dati stampati con tr!
+    ("Ascia di bronzo") => { "Bronze axe" };
+    ("Ascia") => { "Axe" };
+    ("Spada") => { "Sword" };
+    ("Fibula") => { "Brooch" };
+    ("Anello") => { "Ring" };
+    ("Sono una string slice") => { "I am a string slice" };
+    ("Sono una String") => { "I am a String" };
+    ("Gennaio") => { "January" };
+    ("Febbraio") => { "February" };
+    ("Marzo") => { "March" };
+    ("Aprile") => { "April" };
+    ("Archeologo") => { "Archaeologist" };
+    ("caldo") => { "warm" };
+    ("freddo") => { "cold" };
+    ("Lunedi") => { "Monday" };
+    ("Martedi") => { "Tuesday" };
+    ("Mercoledi") => { "Wednesday" };
+    ("Giovedi") => { "Thursday" };
+    ("Venerdi") => { "Friday" };
+    ("Giorno non valido") => { "Invalid day" };
+    ("Eccellente") => { "Excellent" };
+    ("Ottimo") => { "Very good" };
+    ("Buono") => { "Good" };
+    ("Sufficiente") => { "Sufficient" };
+    ("Insufficiente") => { "Insufficient" };
+    ($testo:tt) => { $testo };
+}
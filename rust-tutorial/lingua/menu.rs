@@ -0,0 +1,99 @@
+// ============================================================================
+// CATALOGO INGLESE: MENU DEI CAPITOLI
+// ============================================================================
+// Incluso da src/main.rs (vedi src/lingua.rs).
+// ============================================================================
+
+macro_rules! inglese {
+    // Riquadri
+    ("TUTORIAL RUST: DA ZERO A HERO") => { "RUST TUTORIAL: FROM ZERO TO HERO" };
+    ("Una guida completa al linguaggio Rust") => { "A complete guide to the Rust language" };
+    ("con esempi reali e funzionanti") => { "with real, working examples" };
+    ("PERCHE RUST?") => { "WHY RUST?" };
+    ("- Sicurezza della memoria senza garbage collector") => { "- Memory safety without a garbage collector" };
+    ("- Prestazioni pari a C/C++") => { "- Performance on par with C/C++" };
+    ("- Concorrenza senza data race") => { "- Concurrency without data races" };
+    ("- Compilatore che ti guida e ti protegge") => { "- A compiler that guides and protects you" };
+    ("- Ecosistema moderno (Cargo, crates.io)") => { "- Modern ecosystem (Cargo, crates.io)" };
+    ("CHI USA RUST?") => { "WHO USES RUST?" };
+    ("Linux Kernel (secondo linguaggio ufficiale)") => { "Linux Kernel (second official language)" };
+    // Messaggi
+    ("  Progressi illeggibili ({}): {}. Si riparte da zero.") => { "  Unreadable progress file ({}): {}. Starting from scratch." };
+    ("Capitolo '{}' sconosciuto (1-{})") => { "Unknown chapter '{}' (1-{})" };
+    ("\n  Scelta: ") => { "\n  Choice: " };
+    ("\n  Premi Invio per tornare al menu...") => { "\n  Press Enter to return to the menu..." };
+    ("\n  Azzerare tutti i progressi? (s/N) ") => { "\n  Reset all progress? (y/N) " };
+    ("  Progressi lasciati come erano") => { "  Progress left unchanged" };
+    ("\n  '{}' non e una scelta valida") => { "\n  '{}' is not a valid choice" };
+    ("  Alla prossima! Leggi TUTORIAL_RUST.md per la guida completa.") => { "  See you next time! Read TUTORIAL_RUST.md for the complete guide." };
+    ("\n  CAPITOLI DISPONIBILI:          {} = completato\n") => { "\n  AVAILABLE CHAPTERS:            {} = completed\n" };
+    ("\n  Progressi: {}") => { "\n  Progress:  {}" };
+    ("\n   e. Esercizi   d. Demo   p. Perche Rust?   r. Azzera progressi   q. Esci") => { "\n   e. Exercises   d. Demo   p. Why Rust?   r. Reset progress   q. Quit" };
+    ("\n  ESERCIZI:\n") => { "\n  EXERCISES:\n" };
+    ("\n  SCHEDE DI RIEPILOGO:\n") => { "\n  CHEAT SHEETS:\n" };
+    ("\n  Nel terminale:  cargo run -- cheatsheet <argomento>") => { "\n  In the terminal:  cargo run -- cheatsheet <topic>" };
+    ("  In Markdown:    cargo run -- cheatsheet <argomento|tutti> --markdown > PROMEMORIA.md") => { "  As Markdown:      cargo run -- cheatsheet <topic|all> --markdown > CHEATSHEET.md" };
+    ("Argomento '{}' sconosciuto. Argomenti: {}, tutti") => { "Unknown topic '{}'. Topics: {}, all" };
+    ("\n  GLOSSARIO:\n") => { "\n  GLOSSARY:\n" };
+    ("\n  Cerca:  cargo run -- glossario cerca <termine>") => { "\n  Search:  cargo run -- glossary search <term>" };
+    ("Nessun termine per '{}'. Elenco completo: cargo run -- glossario") => { "No term matches '{}'. Full list: cargo run -- glossary" };
+    ("    Capitolo {}: {}   (cargo run -- {})") => { "    Chapter {}: {}   (cargo run -- {})" };
+    ("    Vedi anche: {}") => { "    See also: {}" };
+    ("\n  SNIPPET:\n") => { "\n  SNIPPETS:\n" };
+    ("\n  Esegui:  cargo run -- run <snippet>") => { "\n  Run:  cargo run -- run <snippet>" };
+    ("Impossibile leggere {}: {}") => { "Cannot read {}: {}" };
+    ("\n  Uscita salvata in {}") => { "\n  Output saved to {}" };
+    ("Impossibile scrivere {}: {}") => { "Cannot write {}: {}" };
+    ("\n  Manca {}: crealo con  cargo run -- run {} --aggiorna") => { "\n  {} is missing: create it with  cargo run -- run {} --aggiorna" };
+    ("\n  {} Uscita uguale a {}.out") => { "\n  {} Output matches {}.out" };
+    ("\n  L'uscita e diversa da {}.out (- attesa, + ottenuta):\n") => { "\n  Output differs from {}.out (- expected, + actual):\n" };
+    ("Uso: cargo run -- scaffold <nome-progetto>") => { "Usage: cargo run -- scaffold <project-name>" };
+    ("\n  Creato il progetto '{}' ({} file):\n") => { "\n  Created project '{}' ({} files):\n" };
+    ("\n  Per iniziare:  cd {} && cargo run") => { "\n  To start:  cd {} && cargo run" };
+    ("  Guida:         capitolo 7 (moduli) e capitolo 9 (progetto finale)") => { "  Guide:     chapter 7 (modules) and chapter 9 (final project)" };
+    ("  cap. {:>2}  {:<14} {}") => { "  ch.  {:>2}  {:<14} {}" };
+    ("\n  Testo e primo indizio:  cargo run -- hint <esercizio> 1") => { "\n  Text and first hint:    cargo run -- hint <exercise> 1" };
+    ("  Indizio piu esplicito:  cargo run -- hint <esercizio> 2") => { "  More explicit hint:     cargo run -- hint <exercise> 2" };
+    ("  Soluzione completa:     cargo run --features solutions -- hint <esercizio> 3") => { "  Full solution:          cargo run --features solutions -- hint <exercise> 3" };
+    ("Uso: cargo run -- hint <esercizio> [1|2|3]") => { "Usage: cargo run -- hint <exercise> [1|2|3]" };
+    ("Esercizio '{}' sconosciuto. Esercizi: {}") => { "Unknown exercise '{}'. Exercises: {}" };
+    ("Livello '{}' non valido: 1 e 2 sono indizi, 3 la soluzione") => { "Invalid level '{}': 1 and 2 are hints, 3 is the solution" };
+    ("\n  Esercizio {} (capitolo {}): {}\n") => { "\n  Exercise {} (chapter {}): {}\n" };
+    ("  Indizio {}: {}") => { "  Hint {}: {}" };
+    ("\n  Ancora bloccato?  cargo run -- hint {} {}") => { "\n  Still stuck?  cargo run -- hint {} {}" };
+    ("\n  Soluzione (src/soluzioni/{}.rs):\n") => { "\n  Solution (src/soluzioni/{}.rs):\n" };
+    ("\n  Per questo esercizio non c'e ancora una soluzione") => { "\n  There is no solution for this exercise yet" };
+    ("\n  Le soluzioni sono escluse da questa compilazione. Per vederle:") => { "\n  Solutions are not compiled into this build. To see them:" };
+    ("  Impossibile salvare i progressi in {}: {}") => { "  Cannot save progress to {}: {}" };
+    ("  Progressi azzerati") => { "  Progress reset" };
+    ("\n  Il capitolo e terminato con {}") => { "\n  The chapter ended with {}" };
+    ("  Impossibile avviare cargo: {}") => { "  Cannot start cargo: {}" };
+    ("\n  Versione Rust: {}") => { "\n  Rust version: {}" };
+    ("  Edizione: 2021") => { "  Edition: 2021" };
+    ("\n--- Demo rapida dei concetti chiave ---\n") => { "\n--- Quick demo of the key concepts ---\n" };
+    ("  Pattern matching: voto {} = {}") => { "  Pattern matching: grade {} = {}" };
+    ("  Iteratori: somma quadrati 1..5 = {}") => { "  Iterators: sum of squares 1..5 = {}" };
+    ("\n  Esegui i capitoli per approfondire ogni concetto!") => { "\n  Run the chapters to explore each concept!" };
+    ("Ciao, {}!") => { "Hello, {}!" };
+    // Titoli dei capitoli e dati della demo
+    ("Le Basi") => { "The Basics" };
+    ("Struct/Enum") => { "Structs/Enums" };
+    ("Gestione Errori") => { "Error Handling" };
+    ("Collezioni") => { "Collections" };
+    ("Moduli") => { "Modules" };
+    ("Concorrenza") => { "Concurrency" };
+    ("Progetto Finale") => { "Final Project" };
+    ("Smart Pointer") => { "Smart Pointers" };
+    ("Closure/Iteratori") => { "Closures/Iterators" };
+    ("Test Automatici") => { "Automated Tests" };
+    ("CLI con clap") => { "CLI with clap" };
+    ("Web con axum") => { "Web with axum" };
+    ("serde avanzato") => { "Advanced serde" };
+    ("Design pattern") => { "Design patterns" };
+    ("errore!") => { "error!" };
+    ("Eccellente") => { "Excellent" };
+    ("Ottimo") => { "Very good" };
+    ("Buono") => { "Good" };
+    ("Da migliorare") => { "Needs work" };
+    ($testo:tt) => { $testo };
+}
@@ -0,0 +1,4 @@
+//! Analisi del catalogo. Ogni tipo di analisi ha il suo sotto-modulo:
+//! aggiungine altri qui accanto (per esempio `mod cronologia;`).
+
+pub mod statistiche;
@@ -0,0 +1,42 @@
+//! Conteggi e medie sui reperti.
+
+use std::collections::BTreeMap;
+
+use crate::database::Reperto;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Riepilogo {
+    pub totale: usize,
+    /// None se il catalogo e vuoto
+    pub peso_medio: Option<f64>,
+    /// Quanti reperti per materiale, in ordine alfabetico
+    pub per_materiale: BTreeMap<String, usize>,
+}
+
+pub fn riepilogo(reperti: &[Reperto]) -> Riepilogo {
+    let mut per_materiale = BTreeMap::new();
+    for reperto in reperti {
+        *per_materiale.entry(reperto.materiale.clone()).or_insert(0) += 1;
+    }
+    let peso_medio = if reperti.is_empty() {
+        None
+    } else {
+        Some(reperti.iter().map(|r| r.peso_grammi).sum::<f64>() / reperti.len() as f64)
+    };
+    Riepilogo { totale: reperti.len(), peso_medio, per_materiale }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn riepilogo_di_due_reperti() {
+        let reperti = [Reperto::nuovo("ascia", "bronzo", 300.0), Reperto::nuovo("fibula", "bronzo", 20.0)];
+        let r = riepilogo(&reperti);
+        assert_eq!(r.totale, 2);
+        assert_eq!(r.peso_medio, Some(160.0));
+        assert_eq!(r.per_materiale["Bronzo"], 2);
+        assert_eq!(riepilogo(&[]).peso_medio, None);
+    }
+}
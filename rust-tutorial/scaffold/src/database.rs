@@ -0,0 +1,62 @@
+//! I reperti del catalogo e il loro archivio (per ora in memoria).
+
+use crate::utils;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reperto {
+    pub id: u32,
+    pub nome: String,
+    // TODO: un enum Materiale, come nel capitolo 9
+    pub materiale: String,
+    pub peso_grammi: f64,
+}
+
+impl Reperto {
+    /// Un reperto senza id: lo assegna `Database::aggiungi`
+    pub fn nuovo(nome: &str, materiale: &str, peso_grammi: f64) -> Self {
+        Reperto { id: 0, nome: utils::normalizza(nome), materiale: utils::normalizza(materiale), peso_grammi }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Database {
+    reperti: Vec<Reperto>,
+    prossimo_id: u32,
+}
+
+impl Database {
+    pub fn nuovo() -> Self {
+        Database::default()
+    }
+
+    /// Aggiunge il reperto e restituisce l'id assegnato
+    pub fn aggiungi(&mut self, mut reperto: Reperto) -> u32 {
+        // TODO: rifiutare nomi vuoti e pesi negativi con un Result
+        self.prossimo_id += 1;
+        reperto.id = self.prossimo_id;
+        self.reperti.push(reperto);
+        self.prossimo_id
+    }
+
+    pub fn cerca(&self, id: u32) -> Option<&Reperto> {
+        self.reperti.iter().find(|r| r.id == id)
+    }
+
+    pub fn tutti(&self) -> &[Reperto] {
+        &self.reperti
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gli_id_partono_da_uno() {
+        let mut db = Database::nuovo();
+        let id = db.aggiungi(Reperto::nuovo("spillone", "bronzo", 12.0));
+        assert_eq!(id, 1);
+        assert_eq!(db.cerca(id).unwrap().nome, "Spillone");
+        assert!(db.cerca(2).is_none());
+    }
+}
@@ -0,0 +1,44 @@
+// ============================================================================
+// IL TUO CATALOGO
+// ============================================================================
+// Scheletro generato da `bronzeaxe-tutor scaffold`, con la struttura del
+// capitolo 7 (7.6): un file per modulo, gia collegati tra loro.
+//
+//   src/main.rs                   questo file: crea il catalogo e stampa il report
+//   src/database.rs               mod database: i reperti e dove sono conservati
+//   src/analisi/mod.rs            mod analisi: raccoglie i sotto-moduli
+//   src/analisi/statistiche.rs    mod analisi::statistiche: conteggi e medie
+//   src/report.rs                 mod report: il testo da mostrare
+//   src/utils.rs                  mod utils: piccole funzioni di servizio
+//
+// Prova subito:
+//   cargo run
+//   cargo test
+//
+// Poi fallo crescere guardando il capitolo 9 (cargo run --example
+// cap09_progetto_finale nel tutorial): periodi e materiali come enum,
+// errori con thiserror, salvataggio in JSON, ricerca e filtri.
+// ============================================================================
+
+mod analisi;
+mod database;
+mod report;
+mod utils;
+
+use database::{Database, Reperto};
+
+fn main() {
+    let mut db = Database::nuovo();
+    let ascia = db.aggiungi(Reperto::nuovo("ascia a margini rialzati", "bronzo", 327.6));
+    db.aggiungi(Reperto::nuovo("fibula ad arco", "bronzo", 26.9));
+    db.aggiungi(Reperto::nuovo("macina", "pietra", 4150.0));
+
+    let riepilogo = analisi::statistiche::riepilogo(db.tutti());
+    print!("{}", report::testo(&riepilogo));
+
+    if let Some(reperto) = db.cerca(ascia) {
+        println!("\nReperto #{}: {} ({} g)", reperto.id, reperto.nome, reperto.peso_grammi);
+    }
+
+    // TODO: leggere i reperti da un file invece di scriverli qui
+}
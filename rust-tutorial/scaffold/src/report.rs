@@ -0,0 +1,18 @@
+//! Il testo del report, separato dalla stampa: si puo scrivere su file o
+//! controllare in un test.
+
+use crate::analisi::statistiche::Riepilogo;
+use crate::utils;
+
+pub fn testo(riepilogo: &Riepilogo) -> String {
+    let mut testo = String::from("CATALOGO\n");
+    testo += &format!("  Reperti:     {}\n", riepilogo.totale);
+    match riepilogo.peso_medio {
+        Some(peso) => testo += &format!("  Peso medio:  {}\n", utils::grammi(peso)),
+        None => testo += "  Peso medio:  -\n",
+    }
+    for (materiale, quanti) in &riepilogo.per_materiale {
+        testo += &format!("  {:<12} {}\n", materiale, quanti);
+    }
+    testo
+}
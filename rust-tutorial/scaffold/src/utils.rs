@@ -0,0 +1,20 @@
+//! Piccole funzioni usate da piu moduli.
+
+/// Toglie gli spazi in eccesso e mette la maiuscola iniziale
+pub fn normalizza(testo: &str) -> String {
+    let testo = testo.trim();
+    let mut lettere = testo.chars();
+    match lettere.next() {
+        Some(prima) => prima.to_uppercase().chain(lettere).collect(),
+        None => String::new(),
+    }
+}
+
+/// Un peso leggibile: grammi sotto il chilo, chili sopra
+pub fn grammi(peso: f64) -> String {
+    if peso >= 1000.0 {
+        format!("{:.2} kg", peso / 1000.0)
+    } else {
+        format!("{:.1} g", peso)
+    }
+}
@@ -0,0 +1,171 @@
+// ============================================================================
+// #[derive(Scheda)]
+// ============================================================================
+// Macro procedurale che scrive l'implementazione di
+// `rust_tutorial::schede::SchedaStampabile`: etichette, unita di misura e
+// ordine dei campi si dichiarano con attributi sulla struct, invece di
+// ripeterli a mano in ogni esportazione.
+//
+//   #[derive(Scheda)]
+//   #[scheda(titolo = "Reperto")]
+//   struct Reperto {
+//       #[scheda(etichetta = "Peso", unita = "g", precisione = 1, ordine = 1)]
+//       peso_grammi: f64,
+//       #[scheda(debug)]
+//       periodo: Periodo,
+//       #[scheda(salta)]
+//       interno: u64,
+//   }
+//
+// Una macro derive e una funzione da token a token: riceve la definizione
+// della struct (letta con `syn`) e restituisce codice nuovo (scritto con
+// `quote!`). Sta in un crate a parte perche `proc-macro = true` non
+// permette di esportare altro. L'uso e documentato in src/schede.rs.
+// ============================================================================
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitInt, LitStr, Type};
+
+#[proc_macro_derive(Scheda, attributes(scheda))]
+pub fn deriva_scheda(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    // Gli errori diventano compile_error! con la posizione giusta nel sorgente
+    genera(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Cosa dicono gli attributi `#[scheda(...)]` di un campo
+struct Campo {
+    nome: syn::Ident,
+    etichetta: String,
+    unita: Option<String>,
+    precisione: Option<usize>,
+    debug: bool,
+    opzionale: bool,
+    ordine: Option<i64>,
+}
+
+fn genera(input: &DeriveInput) -> syn::Result<Tokens> {
+    let Data::Struct(dati) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "#[derive(Scheda)] funziona solo sulle struct"));
+    };
+    let Fields::Named(campi) = &dati.fields else {
+        return Err(syn::Error::new_spanned(&input.ident, "#[derive(Scheda)] richiede campi con nome"));
+    };
+
+    let mut titolo = input.ident.to_string();
+    for attributo in input.attrs.iter().filter(|a| a.path().is_ident("scheda")) {
+        attributo.parse_nested_meta(|meta| {
+            if meta.path.is_ident("titolo") {
+                titolo = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("sulla struct e ammesso solo `titolo = \"...\"`"))
+            }
+        })?;
+    }
+
+    let mut voci = Vec::new();
+    for campo in &campi.named {
+        if let Some(voce) = leggi_campo(campo)? {
+            voci.push(voce);
+        }
+    }
+    // Prima i campi con `ordine`, dal piu piccolo; poi gli altri, come
+    // sono dichiarati (sort_by_key e stabile)
+    voci.sort_by_key(|v| (v.ordine.is_none(), v.ordine));
+
+    let etichette = voci.iter().map(|v| &v.etichetta);
+    let valori = voci.iter().map(valore);
+    let tipo = &input.ident;
+    let (impl_generics, tipo_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::rust_tutorial::schede::SchedaStampabile for #tipo #tipo_generics #where_clause {
+            const TITOLO: &'static str = #titolo;
+            const ETICHETTE: &'static [&'static str] = &[#(#etichette),*];
+
+            fn valori(&self) -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#valori),*]
+            }
+        }
+    })
+}
+
+/// None se il campo e marcato `salta`
+fn leggi_campo(campo: &syn::Field) -> syn::Result<Option<Campo>> {
+    let nome = campo.ident.clone().expect("campi con nome");
+    let mut voce = Campo {
+        etichetta: etichetta_predefinita(&nome.to_string()),
+        nome,
+        unita: None,
+        precisione: None,
+        debug: false,
+        opzionale: e_option(&campo.ty),
+        ordine: None,
+    };
+    let mut salta = false;
+    for attributo in campo.attrs.iter().filter(|a| a.path().is_ident("scheda")) {
+        attributo.parse_nested_meta(|meta| {
+            if meta.path.is_ident("etichetta") {
+                voce.etichetta = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("unita") {
+                voce.unita = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("precisione") {
+                voce.precisione = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("ordine") {
+                voce.ordine = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("debug") {
+                voce.debug = true;
+            } else if meta.path.is_ident("salta") {
+                salta = true;
+            } else {
+                return Err(meta.error("attributo sconosciuto: usa etichetta, unita, precisione, ordine, debug o salta"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(if salta { None } else { Some(voce) })
+}
+
+/// `peso_grammi` -> "Peso grammi"
+fn etichetta_predefinita(nome: &str) -> String {
+    let testo = nome.trim_start_matches("r#").replace('_', " ");
+    let mut lettere = testo.chars();
+    match lettere.next() {
+        Some(prima) => prima.to_uppercase().chain(lettere).collect(),
+        None => testo,
+    }
+}
+
+/// Vero per `Option<T>` (anche scritto `std::option::Option<T>`)
+fn e_option(tipo: &Type) -> bool {
+    match tipo {
+        Type::Path(percorso) => percorso.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// L'espressione che produce il testo di un campo
+fn valore(voce: &Campo) -> Tokens {
+    let formato = match (voce.debug, voce.precisione) {
+        (true, _) => "{:?}".to_string(),
+        (false, Some(cifre)) => format!("{{:.{}}}", cifre),
+        (false, None) => "{}".to_string(),
+    };
+    let formato = match &voce.unita {
+        Some(unita) => format!("{} {}", formato, unita.replace('{', "{{").replace('}', "}}")),
+        None => formato,
+    };
+    let nome = &voce.nome;
+    if voce.opzionale {
+        quote! {
+            match &self.#nome {
+                ::std::option::Option::Some(valore) => ::std::format!(#formato, valore),
+                ::std::option::Option::None => ::std::string::String::from("-"),
+            }
+        }
+    } else {
+        quote! { ::std::format!(#formato, self.#nome) }
+    }
+}
@@ -0,0 +1,12 @@
+// Capitolo 12: gli adattatori non fanno nulla finche non si consuma
+fn main() {
+    let pesi = [327.6, 26.9, 4150.0];
+    let pesanti = pesi.iter().filter(|p| {
+        println!("  controllo {}", p);
+        **p > 100.0
+    });
+    println!("filter creato, nessun controllo ancora");
+
+    let primo = pesanti.take(1).next();
+    println!("primo pesante: {:?}", primo);
+}
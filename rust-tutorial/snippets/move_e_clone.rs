@@ -0,0 +1,18 @@
+// Capitolo 2: move, clone e tipi Copy
+fn main() {
+    let sito = String::from("Savignano");
+    let copia = sito.clone();
+    let spostato = sito; // da qui `sito` non si puo piu usare
+    println!("{} / {}", copia, spostato);
+
+    let peso = 327.6; // f64 e Copy: l'assegnamento copia
+    let altro = peso;
+    println!("{} e {}", peso, altro);
+
+    stampa(spostato);
+    // println!("{}", spostato);  // errore: borrow of moved value
+}
+
+fn stampa(testo: String) {
+    println!("stampa ha preso l'ownership di '{}'", testo);
+}
@@ -0,0 +1,18 @@
+// Capitolo 4: map, and_then e unwrap_or su Option
+fn leggi_peso(testo: &str) -> Option<f64> {
+    testo.trim().replace(',', ".").parse().ok()
+}
+
+fn main() {
+    for testo in ["327,6", " 26.9 ", "ignoto"] {
+        let in_kg = leggi_peso(testo).map(|g| g / 1000.0);
+        let pesante = leggi_peso(testo).and_then(|g| if g > 100.0 { Some(g) } else { None });
+        println!(
+            "{:<10} kg={:?} pesante={:?} peso={}",
+            format!("'{}'", testo),
+            in_kg,
+            pesante,
+            leggi_peso(testo).unwrap_or(0.0)
+        );
+    }
+}
@@ -0,0 +1,15 @@
+// Capitolo 2: un prestito mutabile alla volta
+fn aggiungi(reperti: &mut Vec<String>, nome: &str) {
+    reperti.push(nome.to_string());
+}
+
+fn main() {
+    let mut reperti = vec![String::from("ascia")];
+    aggiungi(&mut reperti, "fibula");
+
+    let primo = &reperti[0]; // prestito in lettura...
+    println!("primo: {}", primo);
+    // ...che finisce qui, all'ultimo uso: ora si puo di nuovo modificare
+    aggiungi(&mut reperti, "spillone");
+    println!("{:?}", reperti);
+}
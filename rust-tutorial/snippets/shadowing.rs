@@ -0,0 +1,13 @@
+// Capitolo 1: shadowing, un nuovo `let` con lo stesso nome
+fn main() {
+    let peso = "327,6";
+    let peso: f64 = peso.replace(',', ".").parse().unwrap();
+    let peso = peso * 2.0;
+    println!("peso = {}", peso);
+
+    {
+        let peso = "solo dentro il blocco";
+        println!("nel blocco: {}", peso);
+    }
+    println!("fuori: {}", peso);
+}
@@ -0,0 +1,32 @@
+// Capitolo 6: tipi diversi nello stesso Vec con Box<dyn Trait>
+trait Reperto {
+    fn descrivi(&self) -> String;
+}
+
+struct Ascia {
+    peso: f64,
+}
+
+struct Fibula {
+    tipo: &'static str,
+}
+
+impl Reperto for Ascia {
+    fn descrivi(&self) -> String {
+        format!("ascia di {} g", self.peso)
+    }
+}
+
+impl Reperto for Fibula {
+    fn descrivi(&self) -> String {
+        format!("fibula {}", self.tipo)
+    }
+}
+
+fn main() {
+    let reperti: Vec<Box<dyn Reperto>> = vec![Box::new(Ascia { peso: 327.6 }), Box::new(Fibula { tipo: "ad arco" })];
+    for reperto in &reperti {
+        // Il metodo giusto si sceglie a runtime
+        println!("{}", reperto.descrivi());
+    }
+}
@@ -0,0 +1,341 @@
+// ============================================================================
+// MODULO: API C
+// ============================================================================
+// Le funzioni di questo modulo si chiamano da C, C++, Delphi o qualsiasi
+// linguaggio che sappia chiamare una libreria condivisa: i gestionali delle
+// collezioni museali non sono scritti in Rust, ma possono usare l'inventario.
+//
+//   bronzeaxe_apri("inventario.json", passphrase) -> BronzeaxeInventario* (o NULL)
+//   bronzeaxe_cerca(inv, "ascia", id, n)          -> quanti reperti, id nel buffer
+//   bronzeaxe_scheda_json(inv, id)                -> char* da liberare
+//   bronzeaxe_libera_stringa(testo)
+//   bronzeaxe_chiudi(inv)
+//   bronzeaxe_ultimo_errore()                     -> perche l'ultima chiamata e fallita
+//
+// L'inventario e quello del catalogo, letto dallo stesso indirizzo della
+// riga di comando (`archivio::da_indirizzo`): file JSON, anche cifrato,
+// cartella/, sqlite:file.db o postgres://...
+// Le regole dell'interfaccia, che non cambiano tra una versione e l'altra
+// (BRONZEAXE_VERSIONE_API sale solo se cambiano):
+// - solo tipi C: puntatori, interi a dimensione fissa, stringhe UTF-8
+//   terminate da zero
+// - l'inventario e opaco: da C si vede solo un puntatore
+// - chi riceve memoria da Rust la restituisce a Rust (libera/chiudi), mai
+//   con free()
+// - gli errori sono NULL o -1, con il messaggio in bronzeaxe_ultimo_errore
+// - un panic non attraversa mai il confine: sarebbe comportamento
+//   indefinito in C, e diventa un errore come gli altri
+//
+// Libreria e intestazione:
+//   cargo rustc --lib --release --crate-type cdylib    (libreria condivisa)
+//   cargo build --features cabi                        (rigenera include/bronzeaxe.h)
+//
+// Un programma C di esempio e in include/esempio.c.
+// ============================================================================
+
+//! Interfaccia `extern "C"` per aprire un inventario, cercare e leggere le schede in JSON.
+
+// Il modulo intero esiste solo con la feature: apre l'inventario del catalogo
+#![cfg(feature = "json")]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::catalogo::archivio;
+use crate::catalogo::errori::ErroreInventario;
+use crate::catalogo::inventario::Inventario;
+use crate::catalogo::modelli::Reperto;
+
+/// Cresce solo quando cambia qualcosa di incompatibile nell'interfaccia.
+pub const BRONZEAXE_VERSIONE_API: u32 = 2;
+
+/// Un inventario aperto con `bronzeaxe_apri`. Da C e opaco: si usa solo
+/// attraverso il puntatore.
+pub struct BronzeaxeInventario {
+    inventario: Inventario,
+}
+
+thread_local! {
+    // Un messaggio per thread: due thread che chiamano la libreria insieme
+    // non si sovrascrivono l'errore a vicenda
+    static ULTIMO_ERRORE: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn segnala(errore: impl ToString) {
+    // Un \0 dentro il messaggio troncherebbe la stringa C: lo togliamo
+    let messaggio = CString::new(errore.to_string().replace('\0', "")).unwrap_or_default();
+    ULTIMO_ERRORE.with(|e| *e.borrow_mut() = Some(messaggio));
+}
+
+/// Esegue il corpo di una funzione esportata fermando un eventuale panic:
+/// in quel caso segnala il messaggio e restituisce `fallito`
+fn protetto<T>(fallito: T, corpo: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(corpo)).unwrap_or_else(|panico| {
+        let motivo = panico
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panico.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "motivo sconosciuto".to_string());
+        segnala(format!("errore interno della libreria: {}", motivo));
+        fallito
+    })
+}
+
+/// Il testo di una stringa C, o None (con l'errore segnalato) se e NULL
+/// o non e UTF-8.
+///
+/// # Safety
+/// `testo` e NULL oppure punta a una stringa terminata da zero.
+unsafe fn leggi_stringa<'a>(testo: *const c_char, cosa: &str) -> Option<&'a str> {
+    if testo.is_null() {
+        segnala(format!("{} e NULL", cosa));
+        return None;
+    }
+    match CStr::from_ptr(testo).to_str() {
+        Ok(testo) => Some(testo),
+        Err(_) => {
+            segnala(format!("{} non e UTF-8 valido", cosa));
+            None
+        }
+    }
+}
+
+/// La scheda completa di un reperto come oggetto JSON, la stessa
+/// dell'archivio.
+///
+/// ```
+/// use rust_tutorial::cabi::scheda_json;
+/// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+///
+/// let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+/// let json = scheda_json(&ascia).unwrap();
+/// assert!(json.starts_with(r#"{"id":0,"nome":"Ascia","#));
+/// assert!(json.contains(r#""periodo":"BronzoMedio""#));
+/// ```
+pub fn scheda_json(reperto: &Reperto) -> Result<String, ErroreInventario> {
+    Ok(serde_json::to_string(reperto)?)
+}
+
+/// Carica l'inventario dall'indirizzo e rilascia subito l'archivio
+/// (lock del file, connessioni al database)
+fn apri(indirizzo: &str, passphrase: Option<String>) -> Result<Inventario, ErroreInventario> {
+    let archivio = archivio::da_indirizzo(indirizzo, passphrase)?;
+    let inventario = archivio.carica()?;
+    archivio.chiudi()?;
+    Ok(inventario)
+}
+
+/// Versione dell'interfaccia: un programma C la confronta con
+/// `BRONZEAXE_VERSIONE_API` della sua intestazione.
+#[no_mangle]
+pub extern "C" fn bronzeaxe_versione_api() -> u32 {
+    protetto(0, || BRONZEAXE_VERSIONE_API)
+}
+
+/// Apre l'inventario di un archivio: `file.json`, `cartella/`,
+/// `sqlite:file.db` o `postgres://...`, come `--archivio`. `passphrase`
+/// serve per un file JSON cifrato, altrimenti e NULL. Restituisce NULL se
+/// l'archivio non si legge.
+///
+/// # Safety
+/// `indirizzo` e NULL oppure una stringa UTF-8 terminata da zero;
+/// `passphrase` e NULL oppure una stringa UTF-8 terminata da zero.
+#[no_mangle]
+pub unsafe extern "C" fn bronzeaxe_apri(indirizzo: *const c_char, passphrase: *const c_char) -> *mut BronzeaxeInventario {
+    protetto(ptr::null_mut(), || {
+        let Some(indirizzo) = leggi_stringa(indirizzo, "l'indirizzo") else {
+            return ptr::null_mut();
+        };
+        let passphrase = match passphrase.is_null() {
+            true => None,
+            false => match leggi_stringa(passphrase, "la passphrase") {
+                Some(passphrase) => Some(passphrase.to_string()),
+                None => return ptr::null_mut(),
+            },
+        };
+        match apri(indirizzo, passphrase) {
+            Ok(inventario) => Box::into_raw(Box::new(BronzeaxeInventario { inventario })),
+            Err(e) => {
+                segnala(format!("{}: {}", indirizzo, e));
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Cerca i reperti il cui nome contiene `testo` (senza distinguere
+/// maiuscole) o il cui periodo si chiama `testo` (es. "bronzomedio").
+/// Scrive al massimo `capacita` id in `id`, in ordine crescente, e
+/// restituisce quanti reperti corrispondono: se sono piu di `capacita` si
+/// richiama con un buffer piu grande. -1 in caso di errore.
+///
+/// # Safety
+/// `inventario` viene da `bronzeaxe_apri`; `testo` e una stringa terminata
+/// da zero; `id` punta ad almeno `capacita` elementi (NULL se `capacita` e 0).
+#[no_mangle]
+pub unsafe extern "C" fn bronzeaxe_cerca(
+    inventario: *const BronzeaxeInventario,
+    testo: *const c_char,
+    id: *mut u32,
+    capacita: usize,
+) -> i64 {
+    protetto(-1, || {
+        let Some(inventario) = inventario.as_ref() else {
+            segnala("l'inventario e NULL");
+            return -1;
+        };
+        let Some(testo) = leggi_stringa(testo, "il testo cercato") else {
+            return -1;
+        };
+        if id.is_null() && capacita > 0 {
+            segnala("il buffer degli id e NULL");
+            return -1;
+        }
+
+        let testo = testo.to_lowercase();
+        let trovati: Vec<u32> = inventario
+            .inventario
+            .tutti()
+            .into_iter()
+            .filter(|r| r.nome.to_lowercase().contains(&testo) || format!("{:?}", r.periodo).to_lowercase() == testo)
+            .map(|r| r.id)
+            .collect();
+        for (i, &trovato) in trovati.iter().take(capacita).enumerate() {
+            *id.add(i) = trovato;
+        }
+        trovati.len() as i64
+    })
+}
+
+/// La scheda del reperto `id` in JSON (vedi `scheda_json`), da liberare con
+/// `bronzeaxe_libera_stringa`. NULL se il reperto non esiste.
+///
+/// # Safety
+/// `inventario` viene da `bronzeaxe_apri` e non e ancora stato chiuso.
+#[no_mangle]
+pub unsafe extern "C" fn bronzeaxe_scheda_json(inventario: *const BronzeaxeInventario, id: u32) -> *mut c_char {
+    protetto(ptr::null_mut(), || {
+        let Some(inventario) = inventario.as_ref() else {
+            segnala("l'inventario e NULL");
+            return ptr::null_mut();
+        };
+        match inventario.inventario.cerca_per_id(id).and_then(scheda_json) {
+            // Il JSON di serde_json non contiene \0: i caratteri di controllo sono sempre escape
+            Ok(json) => CString::new(json).map_or(ptr::null_mut(), CString::into_raw),
+            Err(e) => {
+                segnala(e);
+                ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Libera una stringa restituita dalla libreria. NULL e ammesso.
+///
+/// # Safety
+/// `testo` viene da `bronzeaxe_scheda_json` e non e gia stato liberato.
+#[no_mangle]
+pub unsafe extern "C" fn bronzeaxe_libera_stringa(testo: *mut c_char) {
+    protetto((), || {
+        if !testo.is_null() {
+            drop(CString::from_raw(testo));
+        }
+    })
+}
+
+/// Chiude l'inventario e ne libera la memoria. NULL e ammesso.
+///
+/// # Safety
+/// `inventario` viene da `bronzeaxe_apri` e non e gia stato chiuso.
+#[no_mangle]
+pub unsafe extern "C" fn bronzeaxe_chiudi(inventario: *mut BronzeaxeInventario) {
+    protetto((), || {
+        if !inventario.is_null() {
+            drop(Box::from_raw(inventario));
+        }
+    })
+}
+
+/// Il messaggio dell'ultimo errore in questo thread, o NULL se non ce ne
+/// sono stati. Resta valido fino al prossimo errore nello stesso thread;
+/// non va liberato.
+#[no_mangle]
+pub extern "C" fn bronzeaxe_ultimo_errore() -> *const c_char {
+    protetto(ptr::null(), || ULTIMO_ERRORE.with(|e| e.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::archivio::{Archivio, FileJson};
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo};
+
+    /// Un archivio JSON nella cartella temporanea, aperto come da C
+    fn apri(nome: &str, passphrase: Option<&str>, apri_con: Option<&str>) -> *mut BronzeaxeInventario {
+        let percorso = std::env::temp_dir().join(format!("cabi_{}_{}.json", std::process::id(), nome));
+        let mut inv = Inventario::nuovo();
+        for (nome, periodo) in
+            [("Ascia a margini rialzati", Periodo::BronzoMedio), ("Spada", Periodo::BronzoFinale), ("Ascia piatta", Periodo::BronzoAntico)]
+        {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, periodo, Conservazione::Buono, "Frattesina")).unwrap();
+        }
+        FileJson::nuovo(percorso.clone(), passphrase.map(String::from)).salva(&inv).unwrap();
+        let percorso_c = CString::new(percorso.to_str().unwrap()).unwrap();
+        let passphrase_c = apri_con.map(|p| CString::new(p).unwrap());
+        let inventario = unsafe { bronzeaxe_apri(percorso_c.as_ptr(), passphrase_c.as_ref().map_or(ptr::null(), |p| p.as_ptr())) };
+        std::fs::remove_file(&percorso).ok();
+        inventario
+    }
+
+    fn ultimo_errore() -> String {
+        unsafe { CStr::from_ptr(bronzeaxe_ultimo_errore()) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn apri_cerca_e_leggi_come_da_c() {
+        let inventario = apri("chiaro", None, None);
+        assert!(!inventario.is_null(), "{}", ultimo_errore());
+        unsafe {
+            let ascia = CString::new("ASCIA").unwrap();
+            let mut id = [0u32; 1];
+            // Due risultati ma spazio per uno: il conteggio dice di riprovare
+            assert_eq!(bronzeaxe_cerca(inventario, ascia.as_ptr(), id.as_mut_ptr(), id.len()), 2);
+            assert_eq!(id, [1]);
+            let finale = CString::new("bronzofinale").unwrap();
+            assert_eq!(bronzeaxe_cerca(inventario, finale.as_ptr(), ptr::null_mut(), 0), 1);
+
+            let json = bronzeaxe_scheda_json(inventario, 2);
+            let scheda: serde_json::Value = serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!((scheda["id"].as_u64(), scheda["nome"].as_str()), (Some(2), Some("Spada")));
+            bronzeaxe_libera_stringa(json);
+
+            assert!(bronzeaxe_scheda_json(inventario, 9).is_null());
+            assert_eq!(ultimo_errore(), "Reperto con ID 9 non trovato");
+            bronzeaxe_chiudi(inventario);
+        }
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn archivio_cifrato_con_passphrase() {
+        let inventario = apri("cifrato", Some("bronzo"), Some("bronzo"));
+        assert!(!inventario.is_null(), "{}", ultimo_errore());
+        unsafe { bronzeaxe_chiudi(inventario) };
+        assert!(apri("senza", Some("bronzo"), None).is_null());
+        assert!(ultimo_errore().contains("cifrato"), "{}", ultimo_errore());
+    }
+
+    #[test]
+    fn errori_con_messaggio() {
+        unsafe {
+            assert!(bronzeaxe_apri(ptr::null(), ptr::null()).is_null());
+            assert_eq!(ultimo_errore(), "l'indirizzo e NULL");
+            assert_eq!(bronzeaxe_cerca(ptr::null(), ptr::null(), ptr::null_mut(), 0), -1);
+        }
+        // Un panic diventa un errore come gli altri, senza arrivare a C
+        assert_eq!(protetto(-1, || panic!("indice fuori dai limiti")), -1);
+        assert_eq!(ultimo_errore(), "errore interno della libreria: indice fuori dai limiti");
+    }
+}
@@ -0,0 +1,76 @@
+// ============================================================================
+// MODULO: CATALOGO
+// ============================================================================
+// L'inventario completo del progetto finale (capitolo 9): schede con
+// materiali, siti, stati di catalogazione, campioni, griglie di scavo,
+// profili di visibilita, statistiche (anche come paragrafo per le
+// relazioni), campi calcolati da formule, bozze dalle vecchie schede
+// cartacee passate all'OCR o dettate sul campo, etichette e distinte di
+// trasporto per le casse del deposito, dossier dei siti; poi gli archivi
+// (file, cartella, SQLite, PostgreSQL), la replica, i servizi, le
+// esportazioni per i dati aperti e la riga di comando. L'esempio
+// cap09_progetto_finale li collega in una dimostrazione; qualsiasi altro
+// programma puo usarli allo stesso modo:
+//
+//   use rust_tutorial::catalogo::prelude::*;  // Inventario, Reperto, ErroreInventario...
+//
+// Il modulo `inventario` della libreria e un'altra cosa: la versione
+// ridotta usata nei capitoli sui test e sui pattern.
+// ============================================================================
+
+//! Inventario completo del progetto finale: modelli, archivi, servizi e riga di comando.
+
+// Il catalogo intero esiste solo con la feature: schede e archivi sono JSON
+#![cfg(feature = "json")]
+
+pub mod aat;
+pub mod archivio;
+pub mod ariadne;
+pub mod assicurazione;
+pub mod calcolati;
+pub mod campioni;
+pub mod cidoc;
+pub mod citazioni;
+pub mod cli;
+pub mod condizione;
+pub mod configurazione;
+pub mod confronti;
+pub mod corpus;
+pub mod custodia;
+pub mod deposito;
+pub mod dettatura;
+pub mod dossier;
+pub mod errori;
+pub mod etichette;
+pub mod fusione;
+pub mod generatore;
+pub mod geo;
+pub mod gpx;
+pub mod grafici;
+pub mod inventario;
+pub mod magazzino;
+pub mod media;
+pub mod migrazione;
+pub mod modelli;
+pub mod narrativa;
+pub mod notifiche;
+pub mod ocr;
+mod pdf;
+pub mod persone;
+pub mod ponderale;
+pub mod postgres;
+pub mod prelude;
+pub mod prestiti;
+pub mod replica;
+pub mod restauro;
+pub mod ricerca;
+pub mod riferimenti;
+pub mod salute;
+pub mod servizio;
+pub mod sparql;
+pub mod statistiche;
+pub mod tesauro;
+pub mod tipologia;
+pub mod trasporto;
+pub mod unita;
+pub mod visibilita;
@@ -0,0 +1,140 @@
+// ============================================================================
+// MODULO: GETTY AAT
+// ============================================================================
+// I termini dei tesauri si collegano agli identificativi del Getty Art &
+// Architecture Thesaurus. La ricerca online e facoltativa: le risposte
+// restano in una cache su file, che basta per lavorare offline.
+// ============================================================================
+
+//! Collegamento dei termini al Getty AAT, con cache locale.
+
+use super::errori::ErroreInventario;
+use super::tesauro::Tesauro;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub const URI_AAT: &str = "http://vocab.getty.edu/aat/";
+pub const ENDPOINT_GETTY: &str = "https://vocab.getty.edu/sparql.json";
+
+/// Concetto AAT corrispondente a un termine locale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corrispondenza {
+    /// Identificativo numerico, es. "300010957"
+    pub id: String,
+    /// Etichetta preferita in AAT
+    pub etichetta: String,
+}
+
+/// Ricerca dei termini in AAT con cache locale su file. Senza endpoint
+/// lavora solo sulla cache: l'accesso in rete e opzionale
+pub struct ClienteAat {
+    pub cache: PathBuf,
+    pub endpoint: Option<String>,
+    /// Anche le ricerche senza risultato restano in cache (`None`)
+    voci: Mutex<BTreeMap<String, Option<Corrispondenza>>>,
+}
+
+impl ClienteAat {
+    pub fn nuovo(cache: PathBuf, endpoint: Option<String>) -> Result<Self, ErroreInventario> {
+        let voci = match fs::read(&cache) {
+            Ok(dati) => serde_json::from_slice(&dati)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(ClienteAat { cache, endpoint, voci: Mutex::new(voci) })
+    }
+
+    fn chiave(termine: &str) -> String {
+        termine.trim().to_lowercase()
+    }
+
+    /// Registra a mano una corrispondenza (es. verificata da un curatore)
+    pub fn memorizza(&self, termine: &str, corrispondenza: Option<Corrispondenza>) -> Result<(), ErroreInventario> {
+        let mut voci = self.voci.lock().map_err(|_| ErroreInventario::DatiNonValidi("cache AAT inutilizzabile".to_string()))?;
+        voci.insert(Self::chiave(termine), corrispondenza);
+        if let Some(cartella) = self.cache.parent() {
+            fs::create_dir_all(cartella)?;
+        }
+        fs::write(&self.cache, serde_json::to_vec_pretty(&*voci)?)?;
+        Ok(())
+    }
+
+    /// Corrispondenza AAT per un termine italiano: prima la cache, poi
+    /// (se configurato) l'endpoint SPARQL del Getty
+    pub fn cerca(&self, termine: &str) -> Result<Option<Corrispondenza>, ErroreInventario> {
+        let in_cache = self
+            .voci
+            .lock()
+            .map_err(|_| ErroreInventario::DatiNonValidi("cache AAT inutilizzabile".to_string()))?
+            .get(&Self::chiave(termine))
+            .cloned();
+        if let Some(voce) = in_cache {
+            return Ok(voce);
+        }
+        let Some(endpoint) = &self.endpoint else {
+            return Ok(None);
+        };
+        let trovata = interroga(endpoint, termine)?;
+        self.memorizza(termine, trovata.clone())?;
+        Ok(trovata)
+    }
+
+    /// Collega ai concetti AAT i concetti del tesauro che ancora non lo
+    /// sono, cercando etichetta e sinonimi. Restituisce quanti ne ha collegati
+    pub fn collega(&self, tesauro: &mut Tesauro) -> Result<usize, ErroreInventario> {
+        let da_cercare: Vec<(String, Vec<String>)> = tesauro
+            .concetti()
+            .filter(|c| c.aat.is_none())
+            .map(|c| (c.id.clone(), std::iter::once(c.etichetta.clone()).chain(c.sinonimi.clone()).collect()))
+            .collect();
+        let mut collegati = 0;
+        for (id, termini) in da_cercare {
+            for termine in termini {
+                if let Some(c) = self.cerca(&termine)? {
+                    tesauro.imposta_aat(&id, &c.id)?;
+                    collegati += 1;
+                    break;
+                }
+            }
+        }
+        Ok(collegati)
+    }
+}
+
+/// Cerca il termine tra le etichette italiane (preferite o alternative) di AAT
+#[cfg(feature = "net")]
+fn interroga(endpoint: &str, termine: &str) -> Result<Option<Corrispondenza>, ErroreInventario> {
+    let letterale = termine.trim().replace('\\', "\\\\").replace('"', "\\\"");
+    let query = format!(
+        "PREFIX skos: <http://www.w3.org/2004/02/skos/core#>\n\
+         PREFIX gvp: <http://vocab.getty.edu/ontology#>\n\
+         PREFIX xl: <http://www.w3.org/2008/05/skos-xl#>\n\
+         SELECT ?c ?nome WHERE {{\n\
+           ?c skos:inScheme <{}> ; skos:prefLabel|skos:altLabel \"{}\"@it ;\n\
+              gvp:prefLabelGVP/xl:literalForm ?nome .\n\
+         }} LIMIT 1",
+        URI_AAT, letterale
+    );
+    let risposta: serde_json::Value = ureq::get(endpoint)
+        .query("query", &query)
+        .header("Accept", "application/sparql-results+json")
+        .call()?
+        .body_mut()
+        .read_json()?;
+    let Some(riga) = risposta["results"]["bindings"].as_array().and_then(|r| r.first()) else {
+        return Ok(None);
+    };
+    let uri = riga["c"]["value"].as_str().unwrap_or_default();
+    Ok(uri.strip_prefix(URI_AAT).map(|id| Corrispondenza {
+        id: id.to_string(),
+        etichetta: riga["nome"]["value"].as_str().unwrap_or_default().to_string(),
+    }))
+}
+
+#[cfg(not(feature = "net"))]
+fn interroga(_endpoint: &str, _termine: &str) -> Result<Option<Corrispondenza>, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "net", operazione: "interrogare AAT" })
+}
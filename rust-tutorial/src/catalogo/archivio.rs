@@ -0,0 +1,1182 @@
+// ============================================================================
+// MODULO: ARCHIVIO
+// ============================================================================
+// Dove e come l'inventario viene salvato. Lo stesso trait `Archivio` ha
+// piu realizzazioni, scelte dall'indirizzo:
+//
+//   inventario.json        un file JSON (cifrato con una passphrase)
+//   cartella/              un file per reperto, adatto a Git
+//   sqlite:inventario.db   SQLite, con migrazioni (vedi `migrazione`)
+//   postgres://...         PostgreSQL (vedi `postgres`)
+//
+// Ogni archivio salva, accanto ai reperti, lo `StatoInventario` (la
+// strategia degli identificativi e il resto): il file JSON e un oggetto
+// `{"reperti": [...], "stato": {...}}`, la cartella ha `stato.json`, i
+// database una tabella a parte. I file delle versioni precedenti, un
+// semplice elenco di reperti, si leggono ancora.
+//
+// Il file JSON ha in coda la sua impronta SHA-256 e una copia di backup;
+// se e danneggiato si recuperano i record leggibili e gli altri vanno in
+// quarantena.
+// ============================================================================
+
+//! Salvataggio dell'inventario su file, cartella, SQLite o PostgreSQL.
+
+use super::errori::ErroreInventario;
+use super::inventario::{Inventario, StatoInventario};
+use super::modelli::Reperto;
+#[cfg(feature = "crypto")]
+use argon2::Argon2;
+#[cfg(feature = "crypto")]
+use chacha20poly1305::aead::rand_core::RngCore;
+#[cfg(feature = "crypto")]
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+#[cfg(feature = "crypto")]
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Dove e come l'inventario viene salvato su disco. `Send + Sync`: lo
+/// condividono i thread del server
+pub trait Archivio: Send + Sync {
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario>;
+    fn carica(&self) -> Result<Inventario, ErroreInventario>;
+
+    /// Controllo di prontezza: archivio raggiungibile e schema aggiornato.
+    /// Lo chiama /readyz a ogni sonda: le implementazioni lo tengono leggero
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        self.carica().map(|_| ())
+    }
+
+    /// Chiusura ordinata prima dell'arresto del processo
+    fn chiudi(&self) -> Result<(), ErroreInventario> {
+        Ok(())
+    }
+}
+
+/// Versione di ogni reperto all'ultima lettura o scrittura di un
+/// archivio. Al salvataggio un reperto che nell'archivio ha un'altra
+/// versione e stato cambiato da altri nel frattempo: `Conflitto`, come
+/// per `Inventario::conferma_modifica`. Finche l'archivio non ha letto ne
+/// scritto nulla non c'e niente da confrontare e si scrive senza controlli
+#[derive(Debug, Default)]
+pub struct VersioniLette(Mutex<Option<BTreeMap<u32, u64>>>);
+
+impl VersioniLette {
+    fn lette(&self) -> Option<BTreeMap<u32, u64>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Da chiamare dopo ogni lettura o scrittura riuscita
+    pub fn ricorda<'a>(&self, reperti: impl IntoIterator<Item = &'a Reperto>) {
+        let versioni = reperti.into_iter().map(|r| (r.id, r.versione)).collect();
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(versioni);
+    }
+
+    /// Come scrivere il reperto `id` in un archivio che si aggiorna riga
+    /// per riga. `mia` e la versione in memoria (`None` se il reperto e
+    /// stato rimosso), `salvata` quella ora nell'archivio
+    pub fn scrittura(&self, id: u32, mia: Option<u64>, salvata: Option<u64>) -> Result<Scrittura, ErroreInventario> {
+        let lette = self.lette();
+        let letta = match &lette {
+            Some(lette) => lette.get(&id).copied(),
+            // Niente di letto: si sovrascrive quello che c'e
+            None => salvata,
+        };
+        match (mia, letta, salvata) {
+            (_, Some(letta), _) if lette.is_some() && mia == Some(letta) => Ok(Scrittura::Invariato),
+            // Aggiunto da altri dopo la lettura: non e nostro da cancellare
+            (None, None, _) => Ok(Scrittura::Invariato),
+            (Some(_), None, None) => Ok(Scrittura::Nuovo),
+            (_, Some(letta), Some(_)) => Ok(Scrittura::Sovrascrivi { attesa: letta }),
+            // Cancellato da altri dopo la lettura
+            (_, Some(letta), None) => Err(ErroreInventario::Conflitto { id, letta, attuale: 0 }),
+            // Lo stesso id aggiunto qui e da altri
+            (Some(_), None, Some(attuale)) => Err(ErroreInventario::Conflitto { id, letta: 0, attuale }),
+        }
+    }
+
+    /// Confronta le versioni ora nell'archivio con quelle lette: chi
+    /// riscrive tutto l'archivio non deve cancellare le modifiche altrui
+    pub fn controlla(&self, salvate: &BTreeMap<u32, u64>) -> Result<(), ErroreInventario> {
+        let Some(lette) = self.lette() else {
+            return Ok(());
+        };
+        for (&id, &attuale) in salvate {
+            match lette.get(&id) {
+                Some(&letta) if letta == attuale => {}
+                letta => return Err(ErroreInventario::Conflitto { id, letta: letta.copied().unwrap_or(0), attuale }),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cosa fare di un reperto al salvataggio, secondo `VersioniLette::scrittura`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scrittura {
+    /// Non e cambiato dalla lettura: la riga resta com'e
+    Invariato,
+    /// Non e nell'archivio: si inserisce
+    Nuovo,
+    /// Si aggiorna (o si cancella) solo se l'archivio ha ancora la versione `attesa`
+    Sovrascrivi { attesa: u64 },
+}
+
+/// Contenuto del file JSON: i reperti (`&Reperto` in scrittura) e lo stato
+#[derive(Serialize, Deserialize)]
+struct FileDati<R> {
+    reperti: Vec<R>,
+    #[serde(default)]
+    stato: StatoInventario,
+}
+
+/// Di una scheda salvata basta la versione, per `VersioniLette::controlla`
+#[derive(Deserialize)]
+struct Versione {
+    id: u32,
+    #[serde(default)]
+    versione: u64,
+}
+
+/// Legge anche i file delle versioni precedenti, un elenco di soli reperti
+fn leggi_file_dati<R: DeserializeOwned>(json: &[u8]) -> Result<FileDati<R>, serde_json::Error> {
+    if json.trim_ascii_start().starts_with(b"[") {
+        Ok(FileDati { reperti: serde_json::from_slice(json)?, stato: StatoInventario::default() })
+    } else {
+        serde_json::from_slice(json)
+    }
+}
+
+/// Tutto l'inventario in un unico file JSON, cifrato se c'e una passphrase
+pub struct FileJson {
+    pub percorso: PathBuf,
+    pub passphrase: Option<String>,
+    versioni: VersioniLette,
+}
+
+/// Intestazione dei file cifrati, seguita da sale, nonce e testo cifrato
+const MAGIA_CIFRATO: &[u8] = b"BRONZEAXE-CIFRATO-1\n";
+#[cfg(feature = "crypto")]
+const LUNGHEZZA_SALE: usize = 16;
+#[cfg(feature = "crypto")]
+const LUNGHEZZA_NONCE: usize = 12;
+
+#[cfg(feature = "crypto")]
+fn deriva_chiave(passphrase: &str, sale: &[u8]) -> Result<ChaCha20Poly1305, ErroreInventario> {
+    let mut chiave = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), sale, &mut chiave)
+        .map_err(|e| ErroreInventario::DatiNonValidi(format!("derivazione della chiave: {}", e)))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&chiave)))
+}
+
+/// Sale e nonce nuovi a ogni salvataggio: lo stesso inventario non produce mai lo stesso file
+#[cfg(feature = "crypto")]
+pub fn cifra(dati: &[u8], passphrase: &str) -> Result<Vec<u8>, ErroreInventario> {
+    let mut sale = [0u8; LUNGHEZZA_SALE];
+    OsRng.fill_bytes(&mut sale);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cifrato = deriva_chiave(passphrase, &sale)?
+        .encrypt(&nonce, dati)
+        .map_err(|_| ErroreInventario::DecifraturaFallita)?;
+    Ok([MAGIA_CIFRATO, &sale, &nonce, &cifrato].concat())
+}
+
+/// Il tag Poly1305 rileva sia la passphrase sbagliata sia ogni alterazione del file
+#[cfg(feature = "crypto")]
+pub fn decifra(file: &[u8], passphrase: &str) -> Result<Vec<u8>, ErroreInventario> {
+    let corpo = file
+        .strip_prefix(MAGIA_CIFRATO)
+        .filter(|c| c.len() >= LUNGHEZZA_SALE + LUNGHEZZA_NONCE)
+        .ok_or(ErroreInventario::DecifraturaFallita)?;
+    let (sale, resto) = corpo.split_at(LUNGHEZZA_SALE);
+    let (nonce, cifrato) = resto.split_at(LUNGHEZZA_NONCE);
+    deriva_chiave(passphrase, sale)?
+        .decrypt(Nonce::from_slice(nonce), cifrato)
+        .map_err(|_| ErroreInventario::DecifraturaFallita)
+}
+
+// Senza la feature `crypto` una passphrase e un errore, non un file in chiaro
+#[cfg(not(feature = "crypto"))]
+pub fn cifra(_dati: &[u8], _passphrase: &str) -> Result<Vec<u8>, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "crypto", operazione: "cifrare il file dati" })
+}
+
+#[cfg(not(feature = "crypto"))]
+pub fn decifra(_file: &[u8], _passphrase: &str) -> Result<Vec<u8>, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "crypto", operazione: "decifrare il file dati" })
+}
+
+pub fn e_cifrato(file: &[u8]) -> bool {
+    file.starts_with(MAGIA_CIFRATO)
+}
+
+/// Coda del file dati: `\n--sha256:<64 cifre esadecimali>\n`
+const PREFISSO_CODA: &[u8] = b"\n--sha256:";
+const LUNGHEZZA_CODA: usize = PREFISSO_CODA.len() + 64 + 1;
+
+fn con_coda(mut contenuto: Vec<u8>) -> Vec<u8> {
+    let impronta = format!("{:x}", Sha256::digest(&contenuto));
+    contenuto.extend_from_slice(PREFISSO_CODA);
+    contenuto.extend_from_slice(impronta.as_bytes());
+    contenuto.push(b'\n');
+    contenuto
+}
+
+fn manca_passphrase() -> ErroreInventario {
+    ErroreInventario::PermessoNegato("il file dati e cifrato, serve la passphrase".to_string())
+}
+
+/// Separa il contenuto dalla coda e dice se l'impronta corrisponde;
+/// `None` se il file non ha coda (versioni precedenti)
+fn verifica_coda(file: &[u8]) -> Option<(&[u8], bool)> {
+    let inizio = file.len().checked_sub(LUNGHEZZA_CODA)?;
+    let (contenuto, coda) = file.split_at(inizio);
+    let registrata = coda.strip_prefix(PREFISSO_CODA)?.strip_suffix(b"\n")?;
+    let calcolata = format!("{:x}", Sha256::digest(contenuto));
+    Some((contenuto, registrata == calcolata.as_bytes()))
+}
+
+/// Record che il recupero non ha potuto reinserire, salvato in quarantena
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordScartato {
+    /// Posizione nell'elenco (0 = primo record)
+    pub posizione: usize,
+    /// Byte di inizio nel JSON in chiaro
+    pub offset: usize,
+    pub id: Option<u32>,
+    pub motivo: String,
+    pub testo: String,
+}
+
+pub struct RapportoRecupero {
+    pub inventario: Inventario,
+    pub scartati: Vec<RecordScartato>,
+    /// Il file finisce prima della chiusura dell'elenco
+    pub troncato: bool,
+    /// `None` se il file non aveva impronta in coda
+    pub impronta_valida: Option<bool>,
+    pub quarantena: Option<PathBuf>,
+}
+
+/// Divide un elenco JSON nei suoi elementi senza interpretarli, contando
+/// parentesi e stringhe: un record rovinato non impedisce di leggere gli altri.
+/// Restituisce (offset, testo) di ogni elemento e se l'elenco e troncato
+fn dividi_elementi(json: &[u8]) -> (Vec<(usize, &[u8])>, bool) {
+    let mut elementi = Vec::new();
+    let Some(apertura) = json.iter().position(|&c| c == b'[') else {
+        return (elementi, true);
+    };
+    let mut i = apertura + 1;
+    loop {
+        while i < json.len() && (json[i].is_ascii_whitespace() || json[i] == b',') {
+            i += 1;
+        }
+        if i >= json.len() {
+            return (elementi, true);
+        }
+        if json[i] == b']' {
+            return (elementi, false);
+        }
+        let inizio = i;
+        let (mut profondita, mut in_stringa, mut escape) = (0usize, false, false);
+        let mut fine = None;
+        while i < json.len() {
+            let c = json[i];
+            i += 1;
+            if in_stringa {
+                match c {
+                    _ if escape => escape = false,
+                    b'\\' => escape = true,
+                    b'"' => in_stringa = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                b'"' => in_stringa = true,
+                b'{' | b'[' => profondita += 1,
+                b'}' | b']' if profondita == 0 => {
+                    // Chiusura dell'elenco dopo un elemento non oggetto
+                    i -= 1;
+                    fine = Some(i);
+                    break;
+                }
+                b'}' | b']' => {
+                    profondita -= 1;
+                    if profondita == 0 {
+                        fine = Some(i);
+                        break;
+                    }
+                }
+                b',' if profondita == 0 => {
+                    fine = Some(i - 1);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        match fine {
+            Some(fine) => elementi.push((inizio, &json[inizio..fine])),
+            None => {
+                elementi.push((inizio, &json[inizio..]));
+                return (elementi, true);
+            }
+        }
+    }
+}
+
+impl FileJson {
+    pub fn nuovo(percorso: impl Into<PathBuf>, passphrase: Option<String>) -> Self {
+        FileJson { percorso: percorso.into(), passphrase, versioni: VersioniLette::default() }
+    }
+
+    /// Versioni dei reperti ora nel file; `None` se manca o non si legge
+    fn versioni_salvate(&self) -> Option<BTreeMap<u32, u64>> {
+        let (contenuto, _) = self.leggi(true).ok()?;
+        let file: FileDati<Versione> = leggi_file_dati(&contenuto).ok()?;
+        Some(file.reperti.into_iter().map(|r| (r.id, r.versione)).collect())
+    }
+
+    /// Copia dell'ultimo file integro, aggiornata a ogni salvataggio
+    pub fn percorso_backup(&self) -> PathBuf {
+        let mut nome = self.percorso.clone().into_os_string();
+        nome.push(".bak");
+        PathBuf::from(nome)
+    }
+
+    /// Sostituisce il file dati con il backup e lo ricarica
+    pub fn ripristina_backup(&self) -> Result<Inventario, ErroreInventario> {
+        fs::copy(self.percorso_backup(), &self.percorso)?;
+        self.carica()
+    }
+
+    /// Legge il file, verifica l'impronta se richiesto e decifra
+    fn leggi(&self, verifica_impronta: bool) -> Result<(Vec<u8>, Option<bool>), ErroreInventario> {
+        let file = fs::read(&self.percorso)?;
+        let (mut contenuto, impronta_valida) = match verifica_coda(&file) {
+            Some((_, false)) if verifica_impronta => return Err(self.corrotto()),
+            Some((contenuto, valida)) => (contenuto.to_vec(), Some(valida)),
+            None => (file, None),
+        };
+        if e_cifrato(&contenuto) {
+            let passphrase = self.passphrase.as_deref().ok_or_else(manca_passphrase)?;
+            contenuto = decifra(&contenuto, passphrase)?;
+        }
+        Ok((contenuto, impronta_valida))
+    }
+
+    /// Carica tutti i record leggibili di un file danneggiato o troncato.
+    /// Quelli scartati finiscono in `<file>.quarantena.json` con il motivo;
+    /// il file originale non viene modificato. Lo stato si conserva solo
+    /// se il file si legge per intero
+    pub fn recupera(&self) -> Result<RapportoRecupero, ErroreInventario> {
+        let (json, impronta_valida) = self.leggi(false)?;
+        // I reperti sono il primo elenco del file, in entrambi i formati
+        let (elementi, troncato) = dividi_elementi(&json);
+        let stato = leggi_file_dati::<IgnoredAny>(&json).map(|f| f.stato).unwrap_or_default();
+
+        let mut inventario = Inventario::da_archivio(Vec::new(), stato)?;
+        let mut scartati = Vec::new();
+        for (posizione, (offset, testo)) in elementi.into_iter().enumerate() {
+            let esito = serde_json::from_slice::<Reperto>(testo)
+                .map_err(ErroreInventario::from)
+                .and_then(|r| inventario.importa(r));
+            if let Err(e) = esito {
+                let id = serde_json::from_slice::<serde_json::Value>(testo)
+                    .ok()
+                    .and_then(|v| v.get("id")?.as_u64())
+                    .and_then(|id| u32::try_from(id).ok());
+                scartati.push(RecordScartato {
+                    posizione,
+                    offset,
+                    id,
+                    motivo: e.to_string(),
+                    testo: String::from_utf8_lossy(testo).into_owned(),
+                });
+            }
+        }
+
+        let quarantena = if scartati.is_empty() {
+            None
+        } else {
+            let mut nome = self.percorso.clone().into_os_string();
+            nome.push(".quarantena.json");
+            let percorso = PathBuf::from(nome);
+            fs::write(&percorso, serde_json::to_vec_pretty(&scartati)?)?;
+            Some(percorso)
+        };
+        self.versioni.ricorda(inventario.tutti_compresi_radiati());
+        Ok(RapportoRecupero { inventario, scartati, troncato, impronta_valida, quarantena })
+    }
+
+    fn corrotto(&self) -> ErroreInventario {
+        let backup = self.percorso_backup();
+        ErroreInventario::FileCorrotto {
+            percorso: self.percorso.display().to_string(),
+            backup: backup.exists().then(|| backup.display().to_string()),
+        }
+    }
+}
+
+impl Archivio for FileJson {
+    /// `Conflitto` se un reperto nel file e cambiato dall'ultima lettura:
+    /// il file si riscrive intero e la modifica altrui andrebbe persa
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario> {
+        if let Some(salvate) = self.versioni_salvate() {
+            self.versioni.controlla(&salvate)?;
+        }
+        let dati = FileDati { reperti: inventario.tutti_compresi_radiati(), stato: inventario.stato() };
+        let json = serde_json::to_vec_pretty(&dati)?;
+        let contenuto = match &self.passphrase {
+            Some(p) => cifra(&json, p)?,
+            None => json,
+        };
+        // Il file corrente diventa il backup solo se e integro
+        if let Ok(attuale) = fs::read(&self.percorso) {
+            if !matches!(verifica_coda(&attuale), Some((_, false))) {
+                fs::write(self.percorso_backup(), attuale)?;
+            }
+        }
+        // Scrittura su file temporaneo e rename: un arresto a meta scrittura
+        // lascia intatto il file precedente
+        let mut temporaneo = self.percorso.clone().into_os_string();
+        temporaneo.push(".tmp");
+        let temporaneo = PathBuf::from(temporaneo);
+        let mut file = fs::File::create(&temporaneo)?;
+        std::io::Write::write_all(&mut file, &con_coda(contenuto))?;
+        file.sync_all()?;
+        fs::rename(&temporaneo, &self.percorso)?;
+        self.versioni.ricorda(inventario.tutti_compresi_radiati());
+        Ok(())
+    }
+
+    /// Un file in chiaro si legge anche con passphrase impostata, cosi il
+    /// primo salvataggio successivo lo converte in cifrato
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        let (contenuto, _) = self.leggi(true)?;
+        let file: FileDati<Reperto> = leggi_file_dati(&contenuto)?;
+        self.versioni.ricorda(&file.reperti);
+        Inventario::da_archivio(file.reperti, file.stato)
+    }
+
+    /// Basta l'impronta in coda, senza decifrare ne interpretare il JSON;
+    /// solo i file senza coda (versioni precedenti) si leggono per intero
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        let file = fs::read(&self.percorso)?;
+        match verifica_coda(&file) {
+            Some((_, false)) => Err(self.corrotto()),
+            Some((contenuto, true)) if e_cifrato(contenuto) && self.passphrase.is_none() => Err(manca_passphrase()),
+            Some(_) => Ok(()),
+            None => {
+                let (contenuto, _) = self.leggi(true)?;
+                leggi_file_dati::<IgnoredAny>(&contenuto)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Cosa fare se il file dati e gia aperto in scrittura da un'altra istanza
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeOccupato {
+    Attendi,
+    SolaLettura,
+}
+
+/// File dati aperto da questa istanza: finche esiste, tiene il blocco
+/// esclusivo (advisory) su `<file>.lock`, cosi due istanze non si
+/// sovrascrivono a vicenda le modifiche
+pub struct Sessione {
+    archivio: FileJson,
+    blocco: Option<fs::File>,
+}
+
+impl Sessione {
+    pub fn apri(archivio: FileJson, se_occupato: SeOccupato) -> Result<Sessione, ErroreInventario> {
+        let mut nome = archivio.percorso.clone().into_os_string();
+        nome.push(".lock");
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(PathBuf::from(nome))?;
+        let blocco = match file.try_lock() {
+            Ok(()) => Some(file),
+            Err(fs::TryLockError::WouldBlock) => match se_occupato {
+                SeOccupato::Attendi => {
+                    file.lock()?;
+                    Some(file)
+                }
+                SeOccupato::SolaLettura => {
+                    eprintln!(
+                        "  Attenzione: {} e in uso da un'altra istanza, aperto in sola lettura",
+                        archivio.percorso.display()
+                    );
+                    None
+                }
+            },
+            Err(fs::TryLockError::Error(e)) => return Err(e.into()),
+        };
+        Ok(Sessione { archivio, blocco })
+    }
+
+    pub fn sola_lettura(&self) -> bool {
+        self.blocco.is_none()
+    }
+}
+
+impl Archivio for Sessione {
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario> {
+        if self.sola_lettura() {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "{} e aperto in sola lettura",
+                self.archivio.percorso.display()
+            )));
+        }
+        self.archivio.salva(inventario)
+    }
+
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        self.archivio.carica()
+    }
+
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        self.archivio.verifica()
+    }
+}
+
+/// Sceglie l'archivio dall'indirizzo in configurazione:
+/// `postgres://...`, `sqlite:<file>`, `<cartella>/` oppure `<file>.json`.
+/// La passphrase vale solo per il file JSON
+pub fn da_indirizzo(indirizzo: &str, passphrase: Option<String>) -> Result<Box<dyn Archivio>, ErroreInventario> {
+    if indirizzo.starts_with("postgres://") || indirizzo.starts_with("postgresql://") {
+        #[cfg(feature = "db")]
+        return Ok(Box::new(super::postgres::ArchivioPostgres::nuovo(indirizzo, 5)?));
+        #[cfg(not(feature = "db"))]
+        return Err(ErroreInventario::FeatureMancante { feature: "db", operazione: "un archivio PostgreSQL" });
+    }
+    if let Some(_file) = indirizzo.strip_prefix("sqlite:") {
+        #[cfg(feature = "db")]
+        return Ok(Box::new(super::migrazione::ArchivioSqlite::nuovo(PathBuf::from(_file))));
+        #[cfg(not(feature = "db"))]
+        return Err(ErroreInventario::FeatureMancante { feature: "db", operazione: "un archivio SQLite" });
+    }
+    if indirizzo.ends_with('/') {
+        return Ok(Box::new(CartellaReperti::nuovo(indirizzo)));
+    }
+    Ok(Box::new(FileJson::nuovo(PathBuf::from(indirizzo), passphrase)))
+}
+
+/// Evento emesso dall'osservatore a ogni modifica esterna del file dati
+#[derive(Debug)]
+pub enum EventoArchivio {
+    Ricaricato { reperti: usize, impronta: String },
+    /// Il file e cambiato ma non si legge: resta in uso la versione precedente
+    RicaricaFallita(ErroreInventario),
+}
+
+/// Inventario tenuto allineato al file dati da un thread di controllo.
+/// Il thread termina quando l'osservatore viene rilasciato
+pub struct Osservatore {
+    inventario: Arc<RwLock<Inventario>>,
+    eventi: mpsc::Receiver<EventoArchivio>,
+}
+
+impl Osservatore {
+    pub fn inventario(&self) -> Arc<RwLock<Inventario>> {
+        Arc::clone(&self.inventario)
+    }
+
+    pub fn eventi(&self) -> &mpsc::Receiver<EventoArchivio> {
+        &self.eventi
+    }
+}
+
+fn firma(percorso: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(percorso).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Carica il file e ne controlla data di modifica e dimensione ogni
+/// `intervallo`. Si ricarica solo quando il file e rimasto fermo per un
+/// intervallo intero, per non leggere una scrittura ancora in corso
+pub fn osserva(archivio: FileJson, intervallo: Duration) -> Result<Osservatore, ErroreInventario> {
+    let inventario = Arc::new(RwLock::new(archivio.carica()?));
+    let (invia, eventi) = mpsc::channel();
+    let condiviso = Arc::clone(&inventario);
+    std::thread::spawn(move || {
+        let mut ultima = firma(&archivio.percorso);
+        loop {
+            std::thread::sleep(intervallo);
+            let attuale = firma(&archivio.percorso);
+            if attuale == ultima {
+                continue;
+            }
+            std::thread::sleep(intervallo);
+            if firma(&archivio.percorso) != attuale {
+                continue;
+            }
+            ultima = attuale;
+            let evento = match archivio.carica() {
+                Ok(nuovo) => {
+                    let evento = EventoArchivio::Ricaricato {
+                        reperti: nuovo.totale(),
+                        impronta: nuovo.impronta_snapshot().unwrap_or_default(),
+                    };
+                    match condiviso.write() {
+                        Ok(mut inv) => *inv = nuovo,
+                        Err(_) => return,
+                    }
+                    evento
+                }
+                Err(e) => EventoArchivio::RicaricaFallita(e),
+            };
+            if invia.send(evento).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(Osservatore { inventario, eventi })
+}
+
+/// Voce dell'indice: permette di elencare il catalogo senza aprire ogni scheda
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoceIndice {
+    pub id: u32,
+    pub nome: String,
+    pub file: String,
+}
+
+/// Un file per reperto in `<radice>/reperti/<id>.json` piu `indice.json`
+/// e `stato.json`: ogni modifica tocca solo la scheda interessata, con
+/// diff leggibili in Git
+pub struct CartellaReperti {
+    pub radice: PathBuf,
+    versioni: VersioniLette,
+}
+
+impl CartellaReperti {
+    pub fn nuovo(radice: impl Into<PathBuf>) -> Self {
+        CartellaReperti { radice: radice.into(), versioni: VersioniLette::default() }
+    }
+
+    /// Versioni delle schede ora nell'indice; `None` se l'indice o una
+    /// scheda mancano o non si leggono
+    fn versioni_salvate(&self) -> Option<BTreeMap<u32, u64>> {
+        let cartella = self.cartella();
+        self.indice()
+            .ok()?
+            .iter()
+            .map(|voce| {
+                let scheda: Versione = serde_json::from_slice(&fs::read(cartella.join(&voce.file)).ok()?).ok()?;
+                Some((scheda.id, scheda.versione))
+            })
+            .collect()
+    }
+
+    fn cartella(&self) -> PathBuf {
+        self.radice.join("reperti")
+    }
+
+    /// Legge l'indice senza caricare le schede
+    pub fn indice(&self) -> Result<Vec<VoceIndice>, ErroreInventario> {
+        Ok(serde_json::from_slice(&fs::read(self.cartella().join("indice.json"))?)?)
+    }
+}
+
+/// Scrive solo se il contenuto e cambiato, cosi i file intatti non risultano modificati
+fn scrivi_se_cambiato(percorso: &Path, contenuto: &[u8]) -> Result<(), ErroreInventario> {
+    if !fs::read(percorso).is_ok_and(|attuale| attuale == contenuto) {
+        fs::write(percorso, contenuto)?;
+    }
+    Ok(())
+}
+
+fn leggi_scheda(cartella: &Path, voce: &VoceIndice) -> Result<Reperto, ErroreInventario> {
+    let reperto: Reperto = serde_json::from_slice(&fs::read(cartella.join(&voce.file))?)?;
+    if reperto.id != voce.id {
+        return Err(ErroreInventario::DatiNonValidi(format!(
+            "il file {} contiene il reperto {} invece di {}",
+            voce.file, reperto.id, voce.id
+        )));
+    }
+    Ok(reperto)
+}
+
+impl Archivio for CartellaReperti {
+    /// `Conflitto` se una scheda e cambiata dall'ultima lettura, come per
+    /// `FileJson`: salvare riscriverebbe le schede cambiate da altri
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario> {
+        if let Some(salvate) = self.versioni_salvate() {
+            self.versioni.controlla(&salvate)?;
+        }
+        let cartella = self.cartella();
+        fs::create_dir_all(&cartella)?;
+
+        let mut indice = Vec::new();
+        for reperto in inventario.tutti_compresi_radiati() {
+            let file = format!("{}.json", reperto.id);
+            let mut json = serde_json::to_vec_pretty(reperto)?;
+            json.push(b'\n');
+            scrivi_se_cambiato(&cartella.join(&file), &json)?;
+            indice.push(VoceIndice { id: reperto.id, nome: reperto.nome.clone(), file });
+        }
+
+        // Le schede dei reperti rimossi non devono sopravvivere nell'archivio;
+        // quelle dei radiati sono nell'indice e restano
+        let scritti: HashSet<u32> = indice.iter().map(|v| v.id).collect();
+        for voce in fs::read_dir(&cartella)? {
+            let nome = voce?.file_name().to_string_lossy().into_owned();
+            let orfano = nome
+                .strip_suffix(".json")
+                .and_then(|id| id.parse::<u32>().ok())
+                .is_some_and(|id| !scritti.contains(&id));
+            if orfano {
+                fs::remove_file(cartella.join(&nome))?;
+            }
+        }
+
+        let mut json = serde_json::to_vec_pretty(&indice)?;
+        json.push(b'\n');
+        scrivi_se_cambiato(&cartella.join("indice.json"), &json)?;
+        let mut json = serde_json::to_vec_pretty(&inventario.stato())?;
+        json.push(b'\n');
+        scrivi_se_cambiato(&cartella.join("stato.json"), &json)?;
+        self.versioni.ricorda(inventario.tutti_compresi_radiati());
+        Ok(())
+    }
+
+    /// Le schede sono lette e deserializzate in parallelo, poi inserite in ordine di ID
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        let cartella = self.cartella();
+        let indice = self.indice()?;
+        let thread = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let blocco = indice.len().div_ceil(thread).max(1);
+
+        let letti: Vec<Result<Reperto, ErroreInventario>> = std::thread::scope(|s| {
+            let lavori: Vec<_> = indice
+                .chunks(blocco)
+                .map(|voci| {
+                    let cartella = &cartella;
+                    s.spawn(move || {
+                        voci.iter().map(|v| leggi_scheda(cartella, v)).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            lavori
+                .into_iter()
+                .flat_map(|l| l.join().expect("thread di caricamento terminato con errore"))
+                .collect()
+        });
+
+        // Le cartelle delle versioni precedenti non hanno stato.json
+        let stato = match fs::read(cartella.join("stato.json")) {
+            Ok(json) => serde_json::from_slice(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StatoInventario::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let reperti = letti.into_iter().collect::<Result<Vec<_>, _>>()?;
+        self.versioni.ricorda(&reperti);
+        Inventario::da_archivio(reperti, stato)
+    }
+
+    /// L'indice leggibile basta: le schede si leggono al caricamento
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        self.indice().map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::inventario::StrategiaId;
+    use crate::catalogo::modelli::{
+        CampagnaScavo, Conservazione, Materiale, MotivoRadiazione, Periodo, Radiazione, RuoloUtente, StatoCatalogazione,
+    };
+    use chrono::NaiveDate;
+
+    /// Cartella temporanea vuota, diversa per ogni test
+    fn cartella_vuota(nome: &str) -> PathBuf {
+        let cartella = std::env::temp_dir().join(format!("bronzeaxe_archivio_{}_{}", nome, std::process::id()));
+        let _ = fs::remove_dir_all(&cartella);
+        fs::create_dir_all(&cartella).unwrap();
+        cartella
+    }
+
+    fn inventario_di_prova() -> Inventario {
+        let mut inv = Inventario::nuovo();
+        for nome in ["Ascia", "Spillone", "Fibula"] {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+                .unwrap();
+        }
+        inv
+    }
+
+    #[test]
+    fn cartella_conserva_le_schede_radiate() {
+        let radice = cartella_vuota("radiati");
+        let archivio = CartellaReperti::nuovo(&radice);
+        let mut inv = inventario_di_prova();
+        let radiazione = Radiazione {
+            motivo: MotivoRadiazione::Furto,
+            data: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            documento: "Denuncia 12/2024".to_string(),
+            ultima_collocazione: None,
+        };
+        inv.radia(2, radiazione, RuoloUtente::Responsabile).unwrap();
+
+        // Due salvataggi: il secondo non deve scambiare la scheda radiata per un orfano
+        archivio.salva(&inv).unwrap();
+        archivio.salva(&inv).unwrap();
+        assert!(radice.join("reperti/2.json").exists());
+
+        let riletto = archivio.carica().unwrap();
+        assert_eq!(riletto.radiati().iter().map(|r| r.id).collect::<Vec<_>>(), [2]);
+        assert!(riletto.cerca_per_id(2).is_err());
+        assert_eq!(riletto.tutti_compresi_radiati().len(), 3);
+        fs::remove_dir_all(radice).unwrap();
+    }
+
+    #[test]
+    fn file_modificato_da_un_altra_sessione() {
+        let cartella = cartella_vuota("conflitto");
+        let percorso = cartella.join("inventario.json");
+        let (prima, seconda) = (FileJson::nuovo(percorso.clone(), None), FileJson::nuovo(percorso.clone(), None));
+        prima.salva(&inventario_di_prova()).unwrap();
+
+        let (mut mio, mut tuo) = (prima.carica().unwrap(), seconda.carica().unwrap());
+        mio.cerca_per_id_mut(1).unwrap().nome = "Ascia piatta".to_string();
+        prima.salva(&mio).unwrap();
+        // Il file si riscrive intero: salvare ora cancellerebbe la modifica della prima
+        tuo.cerca_per_id_mut(2).unwrap().nome = "Spillone a rotella".to_string();
+        assert!(matches!(seconda.salva(&tuo), Err(ErroreInventario::Conflitto { id: 1, letta: 0, attuale: 1 })));
+
+        // Dopo una nuova lettura si salva
+        let mut tuo = seconda.carica().unwrap();
+        tuo.cerca_per_id_mut(2).unwrap().nome = "Spillone a rotella".to_string();
+        seconda.salva(&tuo).unwrap();
+        let riletto = prima.carica().unwrap();
+        assert_eq!(riletto.cerca_per_id(1).unwrap().nome, "Ascia piatta");
+        assert_eq!(riletto.cerca_per_id(2).unwrap().nome, "Spillone a rotella");
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn cartella_modificata_da_un_altra_sessione() {
+        let radice = cartella_vuota("conflitto_cartella");
+        let (prima, seconda) = (CartellaReperti::nuovo(&radice), CartellaReperti::nuovo(&radice));
+        prima.salva(&inventario_di_prova()).unwrap();
+
+        let (mut mio, mut tuo) = (prima.carica().unwrap(), seconda.carica().unwrap());
+        mio.cerca_per_id_mut(1).unwrap().nome = "Ascia piatta".to_string();
+        prima.salva(&mio).unwrap();
+        // La seconda riscriverebbe la scheda 1 con il nome vecchio
+        tuo.cerca_per_id_mut(2).unwrap().nome = "Spillone a rotella".to_string();
+        assert!(matches!(seconda.salva(&tuo), Err(ErroreInventario::Conflitto { id: 1, letta: 0, attuale: 1 })));
+
+        let mut tuo = seconda.carica().unwrap();
+        tuo.cerca_per_id_mut(2).unwrap().nome = "Spillone a rotella".to_string();
+        seconda.salva(&tuo).unwrap();
+        let riletto = prima.carica().unwrap();
+        assert_eq!(riletto.cerca_per_id(1).unwrap().nome, "Ascia piatta");
+        assert_eq!(riletto.cerca_per_id(2).unwrap().nome, "Spillone a rotella");
+        fs::remove_dir_all(radice).unwrap();
+    }
+
+    #[test]
+    fn cartella_andata_e_ritorno_e_schede_rovinate() {
+        let radice = cartella_vuota("cartella");
+        let archivio = CartellaReperti::nuovo(&radice);
+        let mut inv = inventario_di_prova();
+        archivio.salva(&inv).unwrap();
+        let nomi = |inv: &Inventario| inv.tutti_compresi_radiati().iter().map(|r| r.nome.clone()).collect::<Vec<_>>();
+        assert_eq!(nomi(&archivio.carica().unwrap()), ["Ascia", "Spillone", "Fibula"]);
+        assert_eq!(archivio.indice().unwrap().len(), 3);
+
+        // Un reperto rimosso perde anche il suo file
+        inv.rimuovi(3).unwrap();
+        archivio.salva(&inv).unwrap();
+        assert!(!radice.join("reperti/3.json").exists());
+
+        // Una scheda copiata sopra un'altra viene riconosciuta dall'id
+        fs::copy(radice.join("reperti/1.json"), radice.join("reperti/2.json")).unwrap();
+        assert!(matches!(archivio.carica(), Err(ErroreInventario::DatiNonValidi(_))));
+        fs::write(radice.join("reperti/2.json"), "{ \"id\": 2,").unwrap();
+        assert!(matches!(archivio.carica(), Err(ErroreInventario::SerializzazioneErrore(_))));
+        fs::remove_dir_all(radice).unwrap();
+    }
+
+    #[test]
+    fn strategia_salvata_con_i_reperti() {
+        let cartella = cartella_vuota("strategia");
+        let archivi: [Box<dyn Archivio>; 2] = [
+            Box::new(FileJson::nuovo(cartella.join("inventario.json"), None)),
+            Box::new(CartellaReperti::nuovo(cartella.join("schede"))),
+        ];
+        let frattesina = || Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        for archivio in &archivi {
+            let mut inv = Inventario::nuovo_con_strategia(StrategiaId::prefisso_sito());
+            inv.aggiungi(frattesina()).unwrap();
+            archivio.salva(&inv).unwrap();
+
+            let mut riletto = archivio.carica().unwrap();
+            assert_eq!(riletto.strategia(), &StrategiaId::prefisso_sito());
+            let id = riletto.aggiungi(frattesina()).unwrap();
+            assert_eq!(riletto.cerca_per_id(id).unwrap().identificativo.as_deref(), Some("FRA-0002"));
+        }
+
+        // Un file delle versioni precedenti, solo l'elenco dei reperti
+        let vecchio = FileJson::nuovo(cartella.join("vecchio.json"), None);
+        fs::write(&vecchio.percorso, serde_json::to_vec(&inventario_di_prova().tutti()).unwrap()).unwrap();
+        let riletto = vecchio.carica().unwrap();
+        assert_eq!((riletto.totale(), riletto.strategia()), (3, &StrategiaId::Sequenziale));
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn pubblicazione_dopo_il_caricamento() {
+        let cartella = cartella_vuota("campagne");
+        let archivi: [Box<dyn Archivio>; 2] = [
+            Box::new(FileJson::nuovo(cartella.join("inventario.json"), None)),
+            Box::new(CartellaReperti::nuovo(cartella.join("schede"))),
+        ];
+        for archivio in &archivi {
+            let mut inv = inventario_di_prova();
+            inv.registra_campagna_scavo(CampagnaScavo {
+                codice: "FRT-2024".to_string(),
+                sito: "Frattesina".to_string(),
+                anno: 2024,
+                concessione: Some("Concessione 7/2024".to_string()),
+                autorizzazione_ministeriale: Some("Prot. 8831".to_string()),
+                decreto_deposito: Some("Decreto 54/2025".to_string()),
+            })
+            .unwrap();
+            inv.assegna_campagna_scavo(1, "FRT-2024").unwrap();
+            inv.cambia_stato(1, StatoCatalogazione::Catalogato, RuoloUtente::Catalogatore).unwrap();
+            inv.cambia_stato(1, StatoCatalogazione::Verificato, RuoloUtente::Revisore).unwrap();
+            archivio.salva(&inv).unwrap();
+
+            // Senza la campagna il reperto da scavo non si pubblicherebbe
+            let mut riletto = archivio.carica().unwrap();
+            assert_eq!(riletto.campagna_scavo("FRT-2024").map(|c| c.anno), Some(2024));
+            riletto.cambia_stato(1, StatoCatalogazione::Pubblicato, RuoloUtente::Responsabile).unwrap();
+        }
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn rimozione_confermata_in_un_altra_sessione() {
+        let cartella = cartella_vuota("rimozioni");
+        let archivio = FileJson::nuovo(cartella.join("inventario.json"), None);
+        let mut inv = inventario_di_prova();
+        let anna = inv.registra_persona("Anna Greco", Vec::new()).unwrap();
+        let marco = inv.registra_persona("Marco Esposito", Vec::new()).unwrap();
+        let sara = inv.registra_persona("Sara Colombo", Vec::new()).unwrap();
+        inv.assegna_ruolo_utente(anna, RuoloUtente::Responsabile).unwrap();
+        inv.assegna_ruolo_utente(marco, RuoloUtente::Revisore).unwrap();
+        inv.assegna_ruolo_utente(sara, RuoloUtente::Responsabile).unwrap();
+        inv.cambia_stato(1, StatoCatalogazione::Catalogato, RuoloUtente::Catalogatore).unwrap();
+        inv.accedi(anna).unwrap();
+        let Err(ErroreInventario::RimozioneInAttesa { richiesta, .. }) = inv.rimuovi(1) else {
+            panic!("la rimozione di una scheda catalogata va approvata");
+        };
+        archivio.salva(&inv).unwrap();
+
+        // Seconda sessione: conta chi e collegato, non un ruolo dichiarato
+        let mut seconda = archivio.carica().unwrap();
+        assert_eq!(seconda.rimozioni_in_attesa().count(), 1);
+        seconda.accedi(anna).unwrap();
+        assert!(seconda.conferma_rimozione(richiesta).is_err());
+        seconda.accedi(marco).unwrap();
+        assert!(seconda.conferma_rimozione(richiesta).is_err());
+        seconda.accedi(sara).unwrap();
+        assert_eq!(seconda.conferma_rimozione(richiesta).unwrap().id, 1);
+        seconda.esci();
+        archivio.salva(&seconda).unwrap();
+
+        let terza = archivio.carica().unwrap();
+        assert!(terza.cerca_per_id(1).is_err());
+        assert_eq!(terza.rimozioni_in_attesa().count(), 0);
+        assert_eq!(terza.persone().count(), 3);
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn report_di_attivita_dopo_il_caricamento() {
+        use crate::catalogo::persone::{attivita_per_periodo, Cadenza};
+
+        let cartella = cartella_vuota("attivita");
+        let archivi: [Box<dyn Archivio>; 2] = [
+            Box::new(FileJson::nuovo(cartella.join("inventario.json"), None)),
+            Box::new(CartellaReperti::nuovo(cartella.join("schede"))),
+        ];
+        for archivio in &archivi {
+            let mut inv = inventario_di_prova();
+            let anna = inv.registra_persona("Anna Greco", Vec::new()).unwrap();
+            inv.accedi(anna).unwrap();
+            inv.aggiungi(Reperto::nuovo("Pugnale", Materiale::Bronzo, Periodo::BronzoRecente, Conservazione::Buono, "Frattesina"))
+                .unwrap();
+            inv.cambia_stato(1, StatoCatalogazione::Catalogato, RuoloUtente::Catalogatore).unwrap();
+            archivio.salva(&inv).unwrap();
+
+            // Il report dell'anno si fa in un'altra sessione, dal solo archivio
+            let riletto = archivio.carica().unwrap();
+            let da = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+            let a = NaiveDate::from_ymd_opt(2100, 12, 31).unwrap();
+            let righe = attivita_per_periodo(&riletto, da, a, Cadenza::Anno);
+            assert_eq!(righe.len(), 1);
+            assert_eq!((righe[0].nome.as_str(), righe[0].creati, righe[0].modificati), ("Anna Greco", 1, 1));
+        }
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn riepilogo_dopo_il_caricamento() {
+        use crate::catalogo::notifiche::{Frequenza, Riepilogo};
+        use crate::catalogo::prestiti::{Istituzione, Prestito};
+
+        let cartella = cartella_vuota("riepilogo");
+        let archivio = FileJson::nuovo(cartella.join("inventario.json"), None);
+        let mut inv = inventario_di_prova();
+        let museo = |nome: &str| Istituzione {
+            nome: nome.to_string(),
+            citta: "Rovigo".to_string(),
+            referente: "dott.ssa M. Rossi".to_string(),
+        };
+        inv.registra_prestito(Prestito {
+            reperti: vec![2],
+            prestatore: museo("Museo dei Grandi Fiumi"),
+            richiedente: museo("Museo delle Civilta"),
+            finalita: "mostra".to_string(),
+            data_inizio: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            data_fine: NaiveDate::from_ymd_opt(2024, 9, 30).unwrap(),
+        })
+        .unwrap();
+        archivio.salva(&inv).unwrap();
+
+        // Il comando digest parte da solo, senza operatore e senza la
+        // sessione che ha inserito i reperti e il prestito
+        let riletto = archivio.carica().unwrap();
+        let oggi = chrono::Local::now().date_naive();
+        let riepilogo = Riepilogo::componi(&riletto, riletto.prestiti(), oggi, Frequenza::Giornaliera);
+        assert_eq!(riepilogo.nuovi.len(), 3);
+        assert_eq!(riepilogo.prestiti_scaduti.len(), 1);
+        assert!(riepilogo.prestiti_scaduti[0].starts_with("Museo delle Civilta"));
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn impronta_in_coda_e_backup() {
+        let cartella = cartella_vuota("impronta");
+        let archivio = FileJson::nuovo(cartella.join("inventario.json"), None);
+        let mut inv = inventario_di_prova();
+        archivio.salva(&inv).unwrap();
+        inv.rimuovi(3).unwrap();
+        archivio.salva(&inv).unwrap();
+        assert_eq!(archivio.carica().unwrap().tutti_compresi_radiati().len(), 2);
+
+        // Un byte cambiato nel file: l'impronta non corrisponde piu
+        let mut file = fs::read(&archivio.percorso).unwrap();
+        let posizione = file.windows(5).position(|w| w == b"Ascia").unwrap();
+        file[posizione] = b'O';
+        fs::write(&archivio.percorso, &file).unwrap();
+        match archivio.carica() {
+            Err(ErroreInventario::FileCorrotto { backup, .. }) => assert!(backup.is_some()),
+            altro => panic!("atteso FileCorrotto, ottenuto {:?}", altro.map(|_| ())),
+        }
+        // Il backup e il salvataggio precedente, con tutti e tre i reperti
+        assert_eq!(archivio.ripristina_backup().unwrap().tutti_compresi_radiati().len(), 3);
+        assert!(archivio.carica().is_ok());
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn file_cifrato() {
+        let cartella = cartella_vuota("cifrato");
+        let percorso = cartella.join("inventario.json");
+        let con = |passphrase: Option<&str>| FileJson::nuovo(percorso.clone(), passphrase.map(String::from));
+        con(Some("bronzo antico")).salva(&inventario_di_prova()).unwrap();
+
+        let (contenuto, valida) = verifica_coda(&fs::read(&percorso).unwrap()).map(|(c, v)| (c.to_vec(), v)).unwrap();
+        assert!(valida && e_cifrato(&contenuto));
+        assert!(!contenuto.windows(5).any(|w| w == b"Ascia"));
+
+        assert!(matches!(con(None).carica(), Err(ErroreInventario::PermessoNegato(_))));
+        assert!(matches!(con(Some("ferro")).carica(), Err(ErroreInventario::DecifraturaFallita)));
+        assert_eq!(con(Some("bronzo antico")).carica().unwrap().tutti_compresi_radiati().len(), 3);
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn osservatore_su_file_cifrato() {
+        let cartella = cartella_vuota("osservato");
+        let percorso = cartella.join("inventario.json");
+        let con = |passphrase: Option<&str>| FileJson::nuovo(percorso.clone(), passphrase.map(String::from));
+        let mut inv = inventario_di_prova();
+        con(Some("bronzo antico")).salva(&inv).unwrap();
+        assert!(matches!(con(None).verifica(), Err(ErroreInventario::PermessoNegato(_))));
+
+        let osservatore = osserva(con(Some("bronzo antico")), Duration::from_millis(20)).unwrap();
+        inv.rimuovi(3).unwrap();
+        con(Some("bronzo antico")).salva(&inv).unwrap();
+        let evento = osservatore.eventi().recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(evento, EventoArchivio::Ricaricato { reperti: 2, .. }));
+        assert_eq!(osservatore.inventario().read().unwrap().totale(), 2);
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn recupero_con_quarantena() {
+        let cartella = cartella_vuota("recupero");
+        let archivio = FileJson::nuovo(cartella.join("inventario.json"), None);
+        let json = serde_json::to_string(&inventario_di_prova().tutti_compresi_radiati()).unwrap();
+        // Il secondo record perde il nome, il file si interrompe a meta del terzo
+        let rovinato = json.replacen("\"nome\":\"Spillone\"", "\"nome\":42", 1);
+        let troncato = &rovinato[..rovinato.rfind("Fibula").unwrap()];
+        fs::write(&archivio.percorso, troncato).unwrap();
+
+        assert!(archivio.carica().is_err());
+        let rapporto = archivio.recupera().unwrap();
+        assert!(rapporto.troncato);
+        assert_eq!(rapporto.impronta_valida, None);
+        assert_eq!(rapporto.inventario.tutti_compresi_radiati().iter().map(|r| r.id).collect::<Vec<_>>(), [1]);
+        // Del record troncato non si riesce a leggere nemmeno l'id
+        assert_eq!(rapporto.scartati.iter().map(|s| (s.posizione, s.id)).collect::<Vec<_>>(), [(1, Some(2)), (2, None)]);
+        let quarantena: Vec<RecordScartato> =
+            serde_json::from_slice(&fs::read(rapporto.quarantena.unwrap()).unwrap()).unwrap();
+        assert_eq!(quarantena.len(), 2);
+        // Il file originale resta com'era
+        assert_eq!(fs::read_to_string(&archivio.percorso).unwrap(), troncato);
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn blocco_tra_sessioni() {
+        let cartella = cartella_vuota("blocco");
+        let percorso = cartella.join("inventario.json");
+        let file = || FileJson::nuovo(percorso.clone(), None);
+        let prima = Sessione::apri(file(), SeOccupato::SolaLettura).unwrap();
+        assert!(!prima.sola_lettura());
+        prima.salva(&inventario_di_prova()).unwrap();
+
+        let seconda = Sessione::apri(file(), SeOccupato::SolaLettura).unwrap();
+        assert!(seconda.sola_lettura());
+        assert_eq!(seconda.carica().unwrap().tutti_compresi_radiati().len(), 3);
+        assert!(matches!(seconda.salva(&Inventario::nuovo()), Err(ErroreInventario::PermessoNegato(_))));
+
+        // Chiusa la prima sessione il blocco si libera
+        drop(prima);
+        assert!(!Sessione::apri(file(), SeOccupato::Attendi).unwrap().sola_lettura());
+        fs::remove_dir_all(cartella).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+// ============================================================================
+// MODULO: ARIADNE
+// ============================================================================
+// ARIADNEplus aggrega i dataset archeologici europei descritti con il
+// modello AO-Cat: il dataset diventa una collezione, ogni reperto
+// pubblico una risorsa, in Turtle.
+// ============================================================================
+
+//! Metadati AO-Cat per ARIADNEplus.
+
+use super::citazioni::{uri_reperto, URI_DATASET};
+use super::deposito::MetadatiDataset;
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::Reperto;
+use super::tesauro::Tesauro;
+use super::visibilita::{Offuscamento, ProfiloVisibilita};
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+
+pub const AOCAT: &str = "https://www.ariadne-infrastructure.eu/resource/ao/cat/1.1/";
+
+fn letterale(s: &str, lingua: &str) -> String {
+    format!("\"{}\"@{}", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"), lingua)
+}
+
+fn concetto(etichetta: &str, lingua: &str) -> String {
+    format!("[ a aocat:AO_Concept ; skos:prefLabel {} ]", letterale(etichetta, lingua))
+}
+
+fn intervallo(da: i32, a: i32, periodo: Option<&str>) -> String {
+    let nome = periodo.map_or(String::new(), |p| format!(" ; aocat:has_native_period {}", letterale(p, "it")));
+    format!(
+        "[ a aocat:AO_Temporal_Region{} ; aocat:from \"{}\"^^xsd:gYear ; aocat:until \"{}\"^^xsd:gYear ]",
+        nome, da, a
+    )
+}
+
+/// Soggetti derivati: equivalenti AAT di tipologia e materiale
+fn soggetti_aat(r: &Reperto, tesauri: &[&Tesauro]) -> BTreeSet<String> {
+    let termini = [r.tipo.clone(), r.materiale().map(|m| m.to_string())];
+    termini
+        .iter()
+        .flatten()
+        .flat_map(|t| tesauri.iter().filter_map(move |tesauro| tesauro.risolvi(t)))
+        .filter_map(|c| c.aat.clone())
+        .collect()
+}
+
+/// Dataset in Turtle secondo il modello AO-Cat di ARIADNEplus: una
+/// AO_Collection per il dataset e una AO_Individual_Data_Resource per
+/// ogni reperto pubblico. Le coordinate sono troncate a due decimali;
+/// i `tesauri` con corrispondenze AAT forniscono i soggetti derivati
+pub fn ao_cat(
+    inv: &Inventario,
+    dataset: &MetadatiDataset,
+    tesauri: &[&Tesauro],
+    oggi: NaiveDate,
+) -> Result<String, ErroreInventario> {
+    let profilo = ProfiloVisibilita::pubblico_con_coordinate(Offuscamento::Troncamento { decimali: 2 });
+    let reperti: Vec<Reperto> = inv.tutti().into_iter().filter_map(|r| profilo.applica(r, oggi)).collect();
+    if reperti.is_empty() {
+        return Err(ErroreInventario::DatiNonValidi("nessun reperto pubblicato da esportare".to_string()));
+    }
+    let url_licenza = super::deposito::url_licenza(&dataset.licenza)?;
+
+    let persone: Vec<String> = dataset
+        .autori
+        .iter()
+        .map(|a| format!("[ a aocat:AO_Person ; aocat:has_name {} ]", letterale(&a.nome, "it")))
+        .collect();
+    let mut comuni = vec![
+        "aocat:has_language \"it\"".to_string(),
+        format!("aocat:has_publisher [ a aocat:AO_Agent ; aocat:has_name {} ]", letterale("BronzeAxe", "it")),
+        format!("aocat:has_creator {}", persone.join(", ")),
+        format!("aocat:has_access_policy <{}>", url_licenza),
+        format!("aocat:has_access_rights {}", letterale(&dataset.licenza.to_string(), "it")),
+        format!("aocat:has_ARIADNE_subject {}", concetto("Artefact", "en")),
+    ];
+    if let Some(doi) = &dataset.doi {
+        comuni.push(format!("aocat:has_original_id \"doi:{}\"", doi));
+    }
+
+    let mut ttl = format!(
+        "@prefix aocat: <{}> .\n@prefix skos: <http://www.w3.org/2004/02/skos/core#> .\n@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n",
+        AOCAT
+    );
+
+    let anni: Vec<(i32, i32)> = reperti.iter().filter_map(|r| r.periodo.anni()).collect();
+    let siti: BTreeSet<&str> = reperti.iter().map(|r| r.sito.as_str()).collect();
+    let mut collezione = vec![
+        format!("aocat:has_title {}", letterale(&dataset.titolo, "it")),
+        format!("aocat:has_description {}", letterale(&dataset.descrizione, "it")),
+        format!("aocat:has_landing_page <{}>", URI_DATASET),
+        format!("aocat:was_issued \"{}\"^^xsd:date", dataset.data_pubblicazione.format("%Y-%m-%d")),
+        format!("aocat:has_version \"{}\"", dataset.versione),
+    ];
+    collezione.extend(comuni.iter().cloned());
+    if let (Some(da), Some(a)) = (anni.iter().map(|p| p.0).min(), anni.iter().map(|p| p.1).max()) {
+        collezione.push(format!("aocat:has_temporal_coverage {}", intervallo(da, a, None)));
+    }
+    for sito in &siti {
+        collezione.push(format!(
+            "aocat:has_spatial_coverage [ a aocat:AO_Spatial_Region ; aocat:has_place_name {} ]",
+            letterale(sito, "it")
+        ));
+    }
+    let parti: Vec<String> = reperti.iter().map(|r| format!("<{}>", uri_reperto(r.id))).collect();
+    collezione.push(format!("aocat:has_part {}", parti.join(", ")));
+    ttl += &format!("\n<{}> a aocat:AO_Collection ;\n    {} .\n", URI_DATASET, collezione.join(" ;\n    "));
+
+    for r in &reperti {
+        let uri = uri_reperto(r.id);
+        let mut proprieta = vec![
+            format!("aocat:has_title {}", letterale(&r.nome, "it")),
+            format!("aocat:has_original_id \"{}\"", r.id),
+            format!("aocat:has_landing_page <{}>", uri),
+            format!("aocat:is_part_of <{}>", URI_DATASET),
+        ];
+        proprieta.extend(comuni.iter().cloned());
+        for nativo in [r.tipo.clone(), r.materiale().map(|m| m.to_string())].iter().flatten() {
+            proprieta.push(format!("aocat:has_native_subject {}", concetto(nativo, "it")));
+        }
+        for aat in soggetti_aat(r, tesauri) {
+            proprieta.push(format!("aocat:has_derived_subject <{}{}>", super::aat::URI_AAT, aat));
+        }
+        if let Some((da, a)) = r.periodo.anni() {
+            proprieta.push(format!(
+                "aocat:has_temporal_coverage {}",
+                intervallo(da, a, Some(&r.periodo.to_string()))
+            ));
+        }
+        let punto = r.coordinate.as_ref().map_or(String::new(), |c| {
+            format!(" ; aocat:has_latitude {} ; aocat:has_longitude {}", c.latitudine, c.longitudine)
+        });
+        proprieta.push(format!(
+            "aocat:has_spatial_coverage [ a aocat:AO_Spatial_Region ; aocat:has_place_name {}{} ]",
+            letterale(&r.sito, "it"),
+            punto
+        ));
+        ttl += &format!("\n<{}> a aocat:AO_Individual_Data_Resource ;\n    {} .\n", uri, proprieta.join(" ;\n    "));
+    }
+    Ok(ttl)
+}
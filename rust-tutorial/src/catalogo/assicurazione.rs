@@ -0,0 +1,63 @@
+// ============================================================================
+// MODULO: REPORT ASSICURATIVO
+// ============================================================================
+// Per assicurare una collezione o un deposito servono i valori correnti
+// sommati per collezione e per collocazione. Le valute restano separate:
+// il tutorial non fa conversioni di cambio.
+// ============================================================================
+
+//! Totali delle stime di valore per collezione e collocazione.
+
+use super::modelli::*;
+use std::collections::BTreeMap;
+
+/// Somme dei valori correnti, raggruppate per (gruppo, valuta)
+pub type Totali = BTreeMap<(String, String), f64>;
+
+pub struct ReportAssicurativo {
+    pub per_collezione: Totali,
+    pub per_collocazione: Totali,
+    pub totale_per_valuta: BTreeMap<String, f64>,
+    pub senza_stima: Vec<u32>,
+}
+
+/// Somma le stime correnti per collezione e per collocazione.
+/// Le valute non vengono convertite: ogni totale e per singola valuta.
+pub fn genera_report(reperti: &[&Reperto]) -> ReportAssicurativo {
+    let mut report = ReportAssicurativo {
+        per_collezione: BTreeMap::new(),
+        per_collocazione: BTreeMap::new(),
+        totale_per_valuta: BTreeMap::new(),
+        senza_stima: Vec::new(),
+    };
+    for r in reperti {
+        let Some(stima) = r.stima_corrente() else {
+            report.senza_stima.push(r.id);
+            continue;
+        };
+        let collezione = r.collezione.clone().unwrap_or_else(|| "(nessuna collezione)".to_string());
+        let collocazione = r.collocazione.clone().unwrap_or_else(|| "(collocazione ignota)".to_string());
+        *report.per_collezione.entry((collezione, stima.valuta.clone())).or_insert(0.0) += stima.valore;
+        *report.per_collocazione.entry((collocazione, stima.valuta.clone())).or_insert(0.0) += stima.valore;
+        *report.totale_per_valuta.entry(stima.valuta.clone()).or_insert(0.0) += stima.valore;
+    }
+    report
+}
+
+pub fn stampa_report(report: &ReportAssicurativo) {
+    println!("  REPORT ASSICURATIVO");
+    println!("  Per collezione:");
+    for ((collezione, valuta), totale) in &report.per_collezione {
+        println!("    {:<40} {:>12.2} {}", collezione, totale, valuta);
+    }
+    println!("  Per collocazione:");
+    for ((collocazione, valuta), totale) in &report.per_collocazione {
+        println!("    {:<40} {:>12.2} {}", collocazione, totale, valuta);
+    }
+    for (valuta, totale) in &report.totale_per_valuta {
+        println!("  Totale assicurato: {:.2} {}", totale, valuta);
+    }
+    if !report.senza_stima.is_empty() {
+        println!("  Reperti senza stima: {:?}", report.senza_stima);
+    }
+}
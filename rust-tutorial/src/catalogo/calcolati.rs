@@ -0,0 +1,601 @@
+// ============================================================================
+// MODULO: CAMPI CALCOLATI
+// ============================================================================
+// Campi definiti da una formula, come le colonne calcolate di un foglio
+// di calcolo, scritti in un file di configurazione:
+//
+//   # campi.conf
+//   densita = peso_grammi / volume
+//   spessore_relativo = altezza_cm / lunghezza_cm * 100
+//   massa_kg = peso_grammi / 1000
+//
+// Una formula puo usare i campi numerici del reperto (CAMPI_BASE) e gli
+// altri campi calcolati, in qualsiasi ordine: i riferimenti circolari
+// (a = b + 1, b = a * 2) vengono rifiutati quando si legge il file, cosi
+// la valutazione non puo mai girare all'infinito.
+//
+// I valori si calcolano solo quando servono e ognuno una volta sola per
+// reperto: se `densita` compare in tre formule, si calcola una volta.
+// Una misura mancante o una divisione per zero danno un valore mancante,
+// come una cella vuota, non un errore.
+//
+// I campi calcolati valgono come quelli base nelle ricerche (vedi
+// `ricerca`), nell'esportazione CSV e nel riepilogo statistico.
+// ============================================================================
+
+//! Campi calcolati da formule, con controllo dei riferimenti circolari.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use thiserror::Error;
+
+use super::modelli::Reperto;
+use super::unita::{self, ErroreUnita};
+
+/// I campi numerici del reperto che una formula puo usare.
+pub const CAMPI_BASE: [&str; 7] = [
+    "id",
+    "peso_grammi",
+    "lunghezza_cm",
+    "larghezza_cm",
+    "altezza_cm",
+    "volume",
+    "completo_stimato_percento",
+];
+
+fn campo_base(reperto: &Reperto, nome: &str) -> Option<Option<f64>> {
+    let m = &reperto.misurazioni;
+    Some(match nome {
+        "id" => Some(reperto.id as f64),
+        "peso_grammi" => m.peso_grammi,
+        "lunghezza_cm" => m.lunghezza_cm,
+        "larghezza_cm" => m.larghezza_cm,
+        "altezza_cm" => m.altezza_cm,
+        "volume" => m.volume_approssimativo(),
+        "completo_stimato_percento" => reperto.completo_stimato_percento,
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ErroreCampi {
+    #[error("riga {riga}: {motivo}")]
+    Sintassi { riga: usize, motivo: String },
+    #[error("il campo '{0}' e definito due volte")]
+    Duplicato(String),
+    #[error("'{0}' e un campo del reperto: scegli un altro nome")]
+    NomeRiservato(String),
+    #[error("campo '{campo}' sconosciuto{}", usato_in.as_ref().map_or(String::new(), |u| format!(" (nella formula di '{}')", u)))]
+    CampoSconosciuto { campo: String, usato_in: Option<String> },
+    #[error("riferimento circolare: {}", .0.join(" -> "))]
+    Ciclo(Vec<String>),
+    #[error("condizione non valida: {0}")]
+    Condizione(String),
+    #[error(transparent)]
+    Unita(#[from] ErroreUnita),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operatore {
+    Piu,
+    Meno,
+    Per,
+    Diviso,
+}
+
+/// Una formula letta dal file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Espressione {
+    Numero(f64),
+    Campo(String),
+    Opposto(Box<Espressione>),
+    Operazione(Box<Espressione>, Operatore, Box<Espressione>),
+}
+
+impl Espressione {
+    /// Legge una formula: numeri, nomi di campo, + - * / e parentesi.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::calcolati::Espressione;
+    ///
+    /// let e = Espressione::da_testo("peso_grammi / (lunghezza_cm * 2)").unwrap();
+    /// assert_eq!(e.to_string(), "(peso_grammi / (lunghezza_cm * 2))");
+    /// assert!(Espressione::da_testo("peso_grammi /").is_err());
+    /// ```
+    pub fn da_testo(testo: &str) -> Result<Espressione, String> {
+        let mut lettore = Lettore { simboli: simboli(testo)?, posizione: 0 };
+        let espressione = lettore.somma()?;
+        match lettore.simboli.get(lettore.posizione) {
+            None => Ok(espressione),
+            Some(s) => Err(format!("'{}' inatteso", s)),
+        }
+    }
+
+    /// I campi nominati nella formula
+    fn campi<'a>(&'a self, campi: &mut Vec<&'a str>) {
+        match self {
+            Espressione::Numero(_) => {}
+            Espressione::Campo(nome) => campi.push(nome),
+            Espressione::Opposto(e) => e.campi(campi),
+            Espressione::Operazione(a, _, b) => {
+                a.campi(campi);
+                b.campi(campi);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Espressione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Espressione::Numero(n) => write!(f, "{}", n),
+            Espressione::Campo(nome) => write!(f, "{}", nome),
+            Espressione::Opposto(e) => write!(f, "-{}", e),
+            Espressione::Operazione(a, op, b) => {
+                let simbolo = match op {
+                    Operatore::Piu => '+',
+                    Operatore::Meno => '-',
+                    Operatore::Per => '*',
+                    Operatore::Diviso => '/',
+                };
+                write!(f, "({} {} {})", a, simbolo, b)
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Lettura delle formule: prima i simboli, poi la discesa ricorsiva
+//   somma   = prodotto (('+' | '-') prodotto)*
+//   prodotto = fattore (('*' | '/') fattore)*
+//   fattore = '-' fattore | numero | campo | '(' somma ')'
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Simbolo {
+    Numero(f64),
+    Nome(String),
+    Segno(char),
+}
+
+impl fmt::Display for Simbolo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Simbolo::Numero(n) => write!(f, "{}", n),
+            Simbolo::Nome(nome) => write!(f, "{}", nome),
+            Simbolo::Segno(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn simboli(testo: &str) -> Result<Vec<Simbolo>, String> {
+    let mut simboli = Vec::new();
+    let mut caratteri = testo.char_indices().peekable();
+    while let Some(&(inizio, c)) = caratteri.peek() {
+        if c.is_whitespace() {
+            caratteri.next();
+        } else if "+-*/()".contains(c) {
+            simboli.push(Simbolo::Segno(c));
+            caratteri.next();
+        } else if c.is_ascii_digit() || c == '.' || c.is_alphabetic() || c == '_' {
+            let numero = c.is_ascii_digit() || c == '.';
+            let mut fine = inizio;
+            while let Some(&(i, c)) = caratteri.peek() {
+                if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                    break;
+                }
+                fine = i + c.len_utf8();
+                caratteri.next();
+            }
+            let parola = &testo[inizio..fine];
+            simboli.push(if numero {
+                Simbolo::Numero(parola.parse().map_err(|_| format!("numero non valido: '{}'", parola))?)
+            } else if parola.contains('.') {
+                return Err(format!("nome non valido: '{}'", parola));
+            } else {
+                Simbolo::Nome(parola.to_string())
+            });
+        } else {
+            return Err(format!("carattere non ammesso: '{}'", c));
+        }
+    }
+    Ok(simboli)
+}
+
+struct Lettore {
+    simboli: Vec<Simbolo>,
+    posizione: usize,
+}
+
+impl Lettore {
+    fn segno(&mut self, ammessi: &str) -> Option<char> {
+        match self.simboli.get(self.posizione) {
+            Some(Simbolo::Segno(c)) if ammessi.contains(*c) => {
+                self.posizione += 1;
+                Some(*c)
+            }
+            _ => None,
+        }
+    }
+
+    fn somma(&mut self) -> Result<Espressione, String> {
+        let mut a = self.prodotto()?;
+        while let Some(c) = self.segno("+-") {
+            let op = if c == '+' { Operatore::Piu } else { Operatore::Meno };
+            a = Espressione::Operazione(Box::new(a), op, Box::new(self.prodotto()?));
+        }
+        Ok(a)
+    }
+
+    fn prodotto(&mut self) -> Result<Espressione, String> {
+        let mut a = self.fattore()?;
+        while let Some(c) = self.segno("*/") {
+            let op = if c == '*' { Operatore::Per } else { Operatore::Diviso };
+            a = Espressione::Operazione(Box::new(a), op, Box::new(self.fattore()?));
+        }
+        Ok(a)
+    }
+
+    fn fattore(&mut self) -> Result<Espressione, String> {
+        if self.segno("-").is_some() {
+            return Ok(Espressione::Opposto(Box::new(self.fattore()?)));
+        }
+        if self.segno("(").is_some() {
+            let dentro = self.somma()?;
+            return match self.segno(")") {
+                Some(_) => Ok(dentro),
+                None => Err("manca una ')'".to_string()),
+            };
+        }
+        let simbolo = self.simboli.get(self.posizione).cloned();
+        self.posizione += 1;
+        match simbolo {
+            Some(Simbolo::Numero(n)) => Ok(Espressione::Numero(n)),
+            Some(Simbolo::Nome(nome)) => Ok(Espressione::Campo(nome)),
+            Some(s) => Err(format!("'{}' inatteso", s)),
+            None => Err("la formula finisce troppo presto".to_string()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Definizioni e valutazione
+// ----------------------------------------------------------------------------
+
+/// Le definizioni lette da un file `nome = formula`, senza cicli.
+#[derive(Debug, Clone, Default)]
+pub struct CampiCalcolati {
+    /// Nell'ordine del file: e l'ordine delle colonne esportate
+    definizioni: Vec<(String, Espressione)>,
+}
+
+impl CampiCalcolati {
+    /// Legge le definizioni, una per riga; righe vuote e `#` sono ignorate.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::calcolati::{CampiCalcolati, ErroreCampi};
+    ///
+    /// let campi = CampiCalcolati::da_testo("densita = peso_grammi / volume").unwrap();
+    /// assert_eq!(campi.nomi().collect::<Vec<_>>(), ["densita"]);
+    ///
+    /// let errore = CampiCalcolati::da_testo("a = b + 1\nb = a * 2").unwrap_err();
+    /// assert_eq!(errore, ErroreCampi::Ciclo(vec!["a".into(), "b".into(), "a".into()]));
+    /// ```
+    pub fn da_testo(testo: &str) -> Result<CampiCalcolati, ErroreCampi> {
+        let mut definizioni: Vec<(String, Espressione)> = Vec::new();
+        for (i, linea) in testo.lines().enumerate() {
+            let riga = i + 1;
+            let linea = linea.trim();
+            if linea.is_empty() || linea.starts_with('#') {
+                continue;
+            }
+            let sintassi = |motivo: String| ErroreCampi::Sintassi { riga, motivo };
+            let Some((nome, formula)) = linea.split_once('=') else {
+                return Err(sintassi("attesa una riga `nome = formula`".to_string()));
+            };
+            let nome = nome.trim();
+            if !nome.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                || !nome.chars().all(|c| c.is_alphanumeric() || c == '_')
+            {
+                return Err(sintassi(format!("nome di campo non valido: '{}'", nome)));
+            }
+            // Anche i nomi brevi delle ricerche (`peso`): sarebbero ambigui
+            if CAMPI_BASE.contains(&nome) || unita::nome_breve(nome) {
+                return Err(ErroreCampi::NomeRiservato(nome.to_string()));
+            }
+            if definizioni.iter().any(|(n, _)| n == nome) {
+                return Err(ErroreCampi::Duplicato(nome.to_string()));
+            }
+            definizioni.push((nome.to_string(), Espressione::da_testo(formula).map_err(sintassi)?));
+        }
+
+        let campi = CampiCalcolati { definizioni };
+        campi.controlla_riferimenti()?;
+        Ok(campi)
+    }
+
+    /// Ogni campo nominato esiste, e nessuno dipende da se stesso
+    fn controlla_riferimenti(&self) -> Result<(), ErroreCampi> {
+        let dipendenze: BTreeMap<&str, Vec<&str>> = self
+            .definizioni
+            .iter()
+            .map(|(nome, formula)| {
+                let mut usati = Vec::new();
+                formula.campi(&mut usati);
+                (nome.as_str(), usati)
+            })
+            .collect();
+        for (nome, usati) in &dipendenze {
+            if let Some(sconosciuto) = usati.iter().find(|u| !CAMPI_BASE.contains(u) && !dipendenze.contains_key(*u)) {
+                return Err(ErroreCampi::CampoSconosciuto {
+                    campo: sconosciuto.to_string(),
+                    usato_in: Some(nome.to_string()),
+                });
+            }
+        }
+
+        // Visita in profondita: un campo ancora "in corso" incontrato di
+        // nuovo chiude un ciclo, e il percorso e proprio il ciclo
+        let mut finiti = Vec::new();
+        for (nome, _) in &self.definizioni {
+            let mut percorso = Vec::new();
+            visita(nome, &dipendenze, &mut percorso, &mut finiti)?;
+        }
+        Ok(())
+    }
+
+    /// I nomi dei campi calcolati, nell'ordine del file
+    pub fn nomi(&self) -> impl Iterator<Item = &str> {
+        self.definizioni.iter().map(|(nome, _)| nome.as_str())
+    }
+
+    /// La formula di un campo calcolato
+    pub fn formula(&self, nome: &str) -> Option<&Espressione> {
+        self.definizioni.iter().find(|(n, _)| n == nome).map(|(_, f)| f)
+    }
+
+    /// `true` per i campi base e per quelli definiti qui
+    pub fn conosce(&self, campo: &str) -> bool {
+        CAMPI_BASE.contains(&campo) || self.formula(campo).is_some()
+    }
+
+    /// I valori di un reperto, calcolati quando vengono chiesti.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::calcolati::CampiCalcolati;
+    /// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Misurazioni, Periodo, Reperto};
+    ///
+    /// let campi = CampiCalcolati::da_testo("densita = peso_grammi / volume").unwrap();
+    /// let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina")
+    ///     .con_misurazioni(Misurazioni::nuove().con_dimensioni(10.0, 4.0, 1.0).con_peso(320.0));
+    /// assert_eq!(campi.per(&ascia).valore("densita"), Ok(Some(8.0)));
+    /// // Senza peso la densita manca, come una cella vuota
+    /// let senza_peso = Reperto { misurazioni: Misurazioni::nuove().con_dimensioni(10.0, 4.0, 1.0), ..ascia };
+    /// assert_eq!(campi.per(&senza_peso).valore("densita"), Ok(None));
+    /// ```
+    pub fn per<'a>(&'a self, reperto: &'a Reperto) -> Valori<'a> {
+        Valori { campi: self, reperto, calcolati: RefCell::new(HashMap::new()) }
+    }
+
+    /// Una riga per reperto con id, nome e i campi calcolati, separati da
+    /// `;` come gli altri CSV del progetto. Un valore mancante e una cella vuota.
+    pub fn csv(&self, reperti: &[&Reperto]) -> String {
+        let mut csv = format!("id;nome;{}\n", self.nomi().collect::<Vec<_>>().join(";"));
+        for reperto in reperti {
+            let valori = self.per(reperto);
+            let mut riga = vec![reperto.id.to_string(), campo_csv(&reperto.nome)];
+            for nome in self.nomi() {
+                riga.push(valori.valore(nome).ok().flatten().map_or(String::new(), |v| format!("{:.3}", v)));
+            }
+            csv += &riga.join(";");
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Minimo, massimo e media di un campo (base o calcolato) sui reperti.
+    pub fn riepilogo(&self, reperti: &[&Reperto], campo: &str) -> Result<Riepilogo, ErroreCampi> {
+        self.richiedi(campo)?;
+        let mut riepilogo = Riepilogo { campo: campo.to_string(), ..Riepilogo::default() };
+        let mut somma = 0.0;
+        for reperto in reperti {
+            match self.per(reperto).valore(campo)? {
+                Some(v) => {
+                    riepilogo.minimo = Some(riepilogo.minimo.map_or(v, |m: f64| m.min(v)));
+                    riepilogo.massimo = Some(riepilogo.massimo.map_or(v, |m: f64| m.max(v)));
+                    somma += v;
+                    riepilogo.conteggio += 1;
+                }
+                None => riepilogo.mancanti += 1,
+            }
+        }
+        if riepilogo.conteggio > 0 {
+            riepilogo.media = Some(somma / riepilogo.conteggio as f64);
+        }
+        Ok(riepilogo)
+    }
+
+    pub(super) fn richiedi(&self, campo: &str) -> Result<(), ErroreCampi> {
+        if self.conosce(campo) {
+            Ok(())
+        } else {
+            Err(ErroreCampi::CampoSconosciuto { campo: campo.to_string(), usato_in: None })
+        }
+    }
+}
+
+fn visita<'a>(
+    nome: &'a str,
+    dipendenze: &BTreeMap<&'a str, Vec<&'a str>>,
+    percorso: &mut Vec<&'a str>,
+    finiti: &mut Vec<&'a str>,
+) -> Result<(), ErroreCampi> {
+    if finiti.contains(&nome) {
+        return Ok(());
+    }
+    if let Some(inizio) = percorso.iter().position(|p| *p == nome) {
+        let mut ciclo: Vec<String> = percorso[inizio..].iter().map(|p| p.to_string()).collect();
+        ciclo.push(nome.to_string());
+        return Err(ErroreCampi::Ciclo(ciclo));
+    }
+    // I campi base non hanno dipendenze
+    let Some(usati) = dipendenze.get(nome) else {
+        return Ok(());
+    };
+    percorso.push(nome);
+    for usato in usati {
+        visita(usato, dipendenze, percorso, finiti)?;
+    }
+    percorso.pop();
+    finiti.push(nome);
+    Ok(())
+}
+
+fn campo_csv(valore: &str) -> String {
+    if valore.contains([';', '"', '\n']) {
+        format!("\"{}\"", valore.replace('"', "\"\""))
+    } else {
+        valore.to_string()
+    }
+}
+
+/// I valori di un reperto: ogni campo calcolato si valuta alla prima
+/// richiesta e poi si ricorda.
+pub struct Valori<'a> {
+    campi: &'a CampiCalcolati,
+    reperto: &'a Reperto,
+    calcolati: RefCell<HashMap<&'a str, Option<f64>>>,
+}
+
+impl<'a> Valori<'a> {
+    /// Il valore di un campo base o calcolato; `None` se manca una misura
+    /// o la formula divide per zero.
+    pub fn valore(&self, campo: &str) -> Result<Option<f64>, ErroreCampi> {
+        if let Some(valore) = campo_base(self.reperto, campo) {
+            return Ok(valore);
+        }
+        let Some((nome, formula)) = self.campi.definizioni.iter().find(|(n, _)| n == campo) else {
+            return Err(ErroreCampi::CampoSconosciuto { campo: campo.to_string(), usato_in: None });
+        };
+        if let Some(valore) = self.calcolati.borrow().get(campo) {
+            return Ok(*valore);
+        }
+        let valore = self.valuta(formula)?;
+        self.calcolati.borrow_mut().insert(nome.as_str(), valore);
+        Ok(valore)
+    }
+
+    fn valuta(&self, formula: &Espressione) -> Result<Option<f64>, ErroreCampi> {
+        Ok(match formula {
+            Espressione::Numero(n) => Some(*n),
+            Espressione::Campo(nome) => self.valore(nome)?,
+            Espressione::Opposto(e) => self.valuta(e)?.map(|v| -v),
+            Espressione::Operazione(a, op, b) => {
+                let (Some(a), Some(b)) = (self.valuta(a)?, self.valuta(b)?) else {
+                    return Ok(None);
+                };
+                let risultato = match op {
+                    Operatore::Piu => a + b,
+                    Operatore::Meno => a - b,
+                    Operatore::Per => a * b,
+                    Operatore::Diviso => a / b,
+                };
+                // Una divisione per zero e una cella vuota, non infinito
+                risultato.is_finite().then_some(risultato)
+            }
+        })
+    }
+}
+
+/// Riepilogo di un campo su un insieme di reperti.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Riepilogo {
+    pub campo: String,
+    /// Reperti con il valore
+    pub conteggio: usize,
+    /// Reperti senza (misura mancante, divisione per zero)
+    pub mancanti: usize,
+    pub minimo: Option<f64>,
+    pub massimo: Option<f64>,
+    pub media: Option<f64>,
+}
+
+impl fmt::Display for Riepilogo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numero = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:.3}", v));
+        write!(
+            f,
+            "{}: {} valori ({} mancanti), min {}, max {}, media {}",
+            self.campo,
+            self.conteggio,
+            self.mancanti,
+            numero(self.minimo),
+            numero(self.massimo),
+            numero(self.media)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Misurazioni, Periodo};
+
+    fn reperto(peso: Option<f64>, dimensioni: (f64, f64, f64)) -> Reperto {
+        let (l, w, h) = dimensioni;
+        let mut misure = Misurazioni::nuove().con_dimensioni(l, w, h);
+        misure.peso_grammi = peso;
+        Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina")
+            .con_misurazioni(misure)
+    }
+
+    #[test]
+    fn precedenza_e_campi_in_qualsiasi_ordine() {
+        let campi = CampiCalcolati::da_testo(
+            "# doppia usa densita, definita nella riga dopo\n\
+             doppia = densita * 2\n\
+             densita = peso_grammi / volume\n\
+             resto = 10 - 4 - 3\n\
+             misto = -2 + 3 * (1 + 1)",
+        )
+        .unwrap();
+        let r = reperto(Some(80.0), (5.0, 2.0, 1.0));
+        let valori = campi.per(&r);
+        assert_eq!(valori.valore("doppia"), Ok(Some(16.0)));
+        assert_eq!(valori.valore("resto"), Ok(Some(3.0)));
+        assert_eq!(valori.valore("misto"), Ok(Some(4.0)));
+        assert_eq!(valori.valore("peso_grammi"), Ok(Some(80.0)));
+        assert!(valori.valore("densita_media").is_err());
+    }
+
+    #[test]
+    fn definizioni_rifiutate() {
+        let errore = |testo| CampiCalcolati::da_testo(testo).unwrap_err();
+        assert_eq!(errore("a = a + 1"), ErroreCampi::Ciclo(vec!["a".into(), "a".into()]));
+        assert_eq!(
+            errore("x = 1\na = b\nb = c\nc = a + x"),
+            ErroreCampi::Ciclo(vec!["a".into(), "b".into(), "c".into(), "a".into()])
+        );
+        assert_eq!(errore("volume = 1"), ErroreCampi::NomeRiservato("volume".into()));
+        assert_eq!(errore("a = 1\na = 2"), ErroreCampi::Duplicato("a".into()));
+        assert!(matches!(errore("a = peso * 2"), ErroreCampi::CampoSconosciuto { .. }));
+        assert!(matches!(errore("\na = (1 + 2"), ErroreCampi::Sintassi { riga: 2, .. }));
+        assert!(matches!(errore("a = 1 $ 2"), ErroreCampi::Sintassi { riga: 1, .. }));
+    }
+
+    #[test]
+    fn csv_e_riepilogo_con_valori_mancanti() {
+        let campi = CampiCalcolati::da_testo("densita = peso_grammi / volume").unwrap();
+        let pesante = reperto(Some(80.0), (5.0, 2.0, 1.0));
+        let senza_peso = reperto(None, (5.0, 2.0, 1.0));
+        let piatto = reperto(Some(10.0), (5.0, 2.0, 0.0));
+        let reperti = [&pesante, &senza_peso, &piatto];
+
+        assert_eq!(campi.csv(&reperti[..1]), "id;nome;densita\n0;Ascia;8.000\n");
+        let riepilogo = campi.riepilogo(&reperti, "densita").unwrap();
+        assert_eq!((riepilogo.conteggio, riepilogo.mancanti), (1, 2));
+        assert_eq!(riepilogo.media, Some(8.0));
+    }
+}
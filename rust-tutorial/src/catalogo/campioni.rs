@@ -0,0 +1,74 @@
+// ============================================================================
+// MODULO: CAMPIONI
+// ============================================================================
+// Campioni prelevati da un reperto o da un contesto di scavo per le
+// analisi di laboratorio, con il loro stato (prelevato, inviato, esito).
+// ============================================================================
+
+//! Campioni per le analisi di laboratorio.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Natura del campione prelevato
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TipoCampione {
+    Metallo,
+    Terreno,
+    Organico,
+}
+
+/// Avanzamento del campione verso il laboratorio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum StatoCampione {
+    Prelevato,
+    Inviato { laboratorio: String, data: NaiveDate },
+    RisultatiRicevuti { laboratorio: String, data: NaiveDate, esito: String },
+}
+
+impl fmt::Display for StatoCampione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatoCampione::Prelevato => write!(f, "prelevato"),
+            StatoCampione::Inviato { laboratorio, data } => {
+                write!(f, "inviato a {} il {}", laboratorio, data.format("%d/%m/%Y"))
+            }
+            StatoCampione::RisultatiRicevuti { laboratorio, data, esito } => write!(
+                f,
+                "risultati da {} del {}: {}",
+                laboratorio,
+                data.format("%d/%m/%Y"),
+                esito
+            ),
+        }
+    }
+}
+
+/// Campione prelevato da un reperto o da un contesto di scavo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Campione {
+    pub id: u32,
+    pub tipo: TipoCampione,
+    pub id_reperto: Option<u32>,
+    /// Contesto stratigrafico (es. "US 104")
+    pub contesto: Option<String>,
+    pub prelevato_il: NaiveDate,
+    /// Laboratorio a cui e destinato il campione
+    pub destinazione: String,
+    pub stato: StatoCampione,
+}
+
+impl fmt::Display for Campione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "C{:03} {:?}", self.id, self.tipo)?;
+        if let Some(id) = self.id_reperto {
+            write!(f, " da reperto #{}", id)?;
+        }
+        if let Some(contesto) = &self.contesto {
+            write!(f, " ({})", contesto)?;
+        }
+        write!(f, " - {}", self.stato)
+    }
+}
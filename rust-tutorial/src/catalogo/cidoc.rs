@@ -0,0 +1,187 @@
+// ============================================================================
+// MODULO: CIDOC-CRM
+// ============================================================================
+// Il reperto pubblico come grafo CIDOC-CRM: oggetto, materiali, misure,
+// luogo di rinvenimento e catena di provenienza, in triple valide sia in
+// Turtle sia in una richiesta SPARQL. Ogni evento di provenienza e un
+// passaggio: di proprieta per l'acquisizione (E8_Acquisition), di custodia
+// per tutti gli altri (E10_Transfer_of_Custody), collegati in ordine con
+// P134_continued.
+// ============================================================================
+
+//! Grafo CIDOC-CRM dei reperti pubblici.
+
+use super::citazioni::uri_reperto;
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::{EventoProvenienza, Reperto, TipoEventoProvenienza};
+use super::tesauro::Tesauro;
+use super::visibilita::ProfiloVisibilita;
+use chrono::NaiveDate;
+
+pub const PREFISSI: [(&str, &str); 4] = [
+    ("crm", "http://www.cidoc-crm.org/cidoc-crm/"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("aat", "http://vocab.getty.edu/aat/"),
+];
+
+/// Tipi AAT delle misure e delle unita
+const MISURE: [(&str, &str, &str); 4] = [
+    ("lunghezza", "aat:300055645", "aat:300379098"),
+    ("larghezza", "aat:300055647", "aat:300379098"),
+    ("altezza", "aat:300055644", "aat:300379098"),
+    ("peso", "aat:300056240", "aat:300379225"),
+];
+
+fn letterale(s: &str) -> String {
+    format!("\"{}\"@it", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Grafo CIDOC-CRM di un reperto, come elenco di triple valido sia in
+/// Turtle sia dentro un `INSERT DATA` SPARQL. I nodi intermedi hanno URI
+/// `<reperto>#parte`, cosi il grafo si puo sostituire senza nodi anonimi
+pub fn triple(r: &Reperto, tesauri: &[&Tesauro]) -> String {
+    let uri = uri_reperto(r.id);
+    let aat = |termine: &str| tesauri.iter().find_map(|t| t.risolvi(termine)).and_then(|c| c.aat.clone());
+    let mut ttl = format!(
+        "<{u}> a crm:E22_Human-Made_Object ;\n    rdfs:label {} ;\n    crm:P1_is_identified_by <{u}#id> ;\n    crm:P53_has_former_or_current_location <{u}#sito> .\n\
+         <{u}#id> a crm:E42_Identifier ;\n    crm:P190_has_symbolic_content \"{}\" .\n\
+         <{u}#sito> a crm:E53_Place ;\n    rdfs:label {} .\n",
+        letterale(&r.nome),
+        r.id,
+        letterale(&r.sito),
+        u = uri
+    );
+    if let Some(tipo) = &r.tipo {
+        match aat(tipo) {
+            Some(id) => ttl += &format!("<{}> crm:P2_has_type aat:{} .\n", uri, id),
+            None => ttl += &format!("<{u}> crm:P2_has_type <{u}#tipo> .\n<{u}#tipo> a crm:E55_Type ;\n    rdfs:label {} .\n", letterale(tipo), u = uri),
+        }
+    }
+    if let Some(materiale) = r.materiale() {
+        let materiale = materiale.to_string();
+        match aat(&materiale) {
+            Some(id) => ttl += &format!("<{}> crm:P45_consists_of aat:{} .\n", uri, id),
+            None => ttl += &format!("<{u}> crm:P45_consists_of <{u}#materiale> .\n<{u}#materiale> a crm:E57_Material ;\n    rdfs:label {} .\n", letterale(&materiale), u = uri),
+        }
+    }
+    if let Some((da, a)) = r.periodo.anni() {
+        ttl += &format!(
+            "<{u}> crm:P108i_was_produced_by <{u}#produzione> .\n<{u}#produzione> a crm:E12_Production ;\n    crm:P4_has_time-span <{u}#periodo> .\n\
+             <{u}#periodo> a crm:E52_Time-Span ;\n    rdfs:label {} ;\n    crm:P82a_begin_of_the_begin \"{}\"^^xsd:gYear ;\n    crm:P82b_end_of_the_end \"{}\"^^xsd:gYear .\n",
+            letterale(&r.periodo.to_string()),
+            da,
+            a,
+            u = uri
+        );
+    }
+    let m = &r.misurazioni;
+    for ((nome, tipo, unita), valore) in MISURE.iter().zip([m.lunghezza_cm, m.larghezza_cm, m.altezza_cm, m.peso_grammi]) {
+        if let Some(valore) = valore {
+            ttl += &format!(
+                "<{u}> crm:P43_has_dimension <{u}#{n}> .\n<{u}#{n}> a crm:E54_Dimension ;\n    crm:P2_has_type {} ;\n    crm:P90_has_value \"{}\"^^xsd:double ;\n    crm:P91_has_unit {} .\n",
+                tipo,
+                valore,
+                unita,
+                u = uri,
+                n = nome
+            );
+        }
+    }
+    for (n, evento) in r.provenienza.iter().enumerate() {
+        ttl += &provenienza(&uri, n + 1, evento);
+    }
+    ttl
+}
+
+/// Il passaggio `n` della catena di provenienza, con la data, il nuovo
+/// detentore e il documento che lo attesta
+fn provenienza(uri: &str, n: usize, evento: &EventoProvenienza) -> String {
+    let (classe, oggetto, verso) = match evento.tipo {
+        TipoEventoProvenienza::Acquisizione => ("E8_Acquisition", "P24_transferred_title_of", "P22_transferred_title_to"),
+        _ => ("E10_Transfer_of_Custody", "P30_transferred_custody_of", "P29_custody_received_by"),
+    };
+    let e = format!("{}#provenienza-{}", uri, n);
+    let mut ttl = format!(
+        "<{e}> a crm:{} ;
+    rdfs:label {} ;
+    crm:{} <{u}> ;
+    crm:{} <{e}-detentore> ;
+    crm:P4_has_time-span <{e}-data> .
+         <{e}-detentore> a crm:E39_Actor ;
+    rdfs:label {} .
+         <{e}-data> a crm:E52_Time-Span ;
+    crm:P82a_begin_of_the_begin \"{d}\"^^xsd:date ;
+    crm:P82b_end_of_the_end \"{d}\"^^xsd:date .
+",
+        classe,
+        letterale(&evento.tipo.to_string()),
+        oggetto,
+        verso,
+        letterale(&evento.detentore),
+        u = uri,
+        e = e,
+        d = evento.data.format("%Y-%m-%d")
+    );
+    if let Some(documento) = &evento.documento {
+        ttl += &format!("<{e}> crm:P70i_is_documented_in <{e}-documento> .
+<{e}-documento> a crm:E31_Document ;
+    rdfs:label {} .
+", letterale(documento), e = e);
+    }
+    if n > 1 {
+        ttl += &format!("<{e}> crm:P134_continued <{}#provenienza-{}> .
+", uri, n - 1, e = e);
+    }
+    ttl
+}
+
+/// Reperto nella forma da pubblicare: `None` se non e pubblico
+pub fn pubblico(inv: &Inventario, id: u32, oggi: NaiveDate) -> Option<Reperto> {
+    inv.cerca_per_id(id).ok().and_then(|r| ProfiloVisibilita::pubblico().applica(r, oggi))
+}
+
+/// Esportazione statica in Turtle dei reperti pubblici
+pub fn turtle(inv: &Inventario, tesauri: &[&Tesauro], oggi: NaiveDate) -> Result<String, ErroreInventario> {
+    let mut ttl: String = PREFISSI.iter().map(|(p, uri)| format!("@prefix {}: <{}> .\n", p, uri)).collect();
+    for r in inv.tutti() {
+        if let Some(r) = pubblico(inv, r.id, oggi) {
+            ttl.push('\n');
+            ttl += &triple(&r, tesauri);
+        }
+    }
+    Ok(ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo};
+
+    #[test]
+    fn catena_di_provenienza() {
+        let mut r = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        r.id = 4;
+        let evento = |tipo, giorno, detentore: &str, documento: Option<&str>| EventoProvenienza {
+            tipo,
+            data: NaiveDate::from_ymd_opt(2024, 3, giorno).unwrap(),
+            detentore: detentore.to_string(),
+            documento: documento.map(String::from),
+        };
+        r.provenienza = vec![
+            evento(TipoEventoProvenienza::Scavo, 1, "Soprintendenza ABAP", Some("verbale 12/2024")),
+            evento(TipoEventoProvenienza::Acquisizione, 20, "Museo di Adria", None),
+        ];
+        let ttl = triple(&r, &[]);
+        let u = uri_reperto(4);
+        assert!(ttl.contains(&format!("<{u}#provenienza-1> a crm:E10_Transfer_of_Custody ;")));
+        assert!(ttl.contains(&format!("crm:P30_transferred_custody_of <{u}> ;")));
+        assert!(ttl.contains(&format!("<{u}#provenienza-1> crm:P70i_is_documented_in <{u}#provenienza-1-documento> .")));
+        assert!(ttl.contains(&format!("<{u}#provenienza-2> a crm:E8_Acquisition ;")));
+        assert!(ttl.contains(&format!("crm:P22_transferred_title_to <{u}#provenienza-2-detentore> ;")));
+        assert!(ttl.contains("crm:P82a_begin_of_the_begin \"2024-03-20\"^^xsd:date ;"));
+        assert!(ttl.contains(&format!("<{u}#provenienza-2> crm:P134_continued <{u}#provenienza-1> .")));
+        assert!(!ttl.contains("provenienza-2-documento"));
+    }
+}
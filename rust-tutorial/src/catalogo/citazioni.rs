@@ -0,0 +1,178 @@
+// ============================================================================
+// MODULO: CITAZIONI
+// ============================================================================
+// Il dataset e ogni reperto hanno un URI stabile e si citano negli stili
+// APA, Chicago e BibTeX.
+// ============================================================================
+
+//! Citazioni del dataset e dei reperti con URI stabili.
+
+use super::deposito::MetadatiDataset;
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use chrono::Datelike;
+
+/// Radice degli URI stabili dei reperti: l'ID non viene mai riusato,
+/// nemmeno dopo una radiazione, quindi l'URI resta valido
+pub const URI_REPERTI: &str = "https://w3id.org/bronzeaxe/reperti/";
+pub const URI_DATASET: &str = "https://w3id.org/bronzeaxe/dataset";
+
+pub fn uri_reperto(id: u32) -> String {
+    format!("{}{}", URI_REPERTI, id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stile {
+    Apa,
+    Chicago,
+    Bibtex,
+}
+
+impl Stile {
+    pub fn da_nome(nome: &str) -> Option<Self> {
+        match nome {
+            "apa" => Some(Stile::Apa),
+            "chicago" => Some(Stile::Chicago),
+            "bibtex" => Some(Stile::Bibtex),
+            _ => None,
+        }
+    }
+}
+
+/// Autore nella forma "Cognome, Nome" scomposto nelle due parti
+fn cognome_nome(autore: &str) -> (&str, Option<&str>) {
+    match autore.split_once(',') {
+        Some((cognome, nome)) => (cognome.trim(), Some(nome.trim())),
+        None => (autore.trim(), None),
+    }
+}
+
+/// APA: "Greco, A., Esposito, M., & Russo, L."
+fn autori_apa(autori: &[&str]) -> String {
+    let nomi: Vec<String> = autori
+        .iter()
+        .map(|a| match cognome_nome(a) {
+            (cognome, Some(nome)) => {
+                let iniziali: Vec<String> =
+                    nome.split_whitespace().filter_map(|n| n.chars().next()).map(|c| format!("{}.", c)).collect();
+                format!("{}, {}", cognome, iniziali.join(" "))
+            }
+            (cognome, None) => cognome.to_string(),
+        })
+        .collect();
+    match nomi.as_slice() {
+        [] => String::new(),
+        [solo] => solo.clone(),
+        [primi @ .., ultimo] => format!("{}, & {}", primi.join(", "), ultimo),
+    }
+}
+
+/// Chicago: primo autore invertito, gli altri in ordine naturale
+fn autori_chicago(autori: &[&str]) -> String {
+    let nomi: Vec<String> = autori
+        .iter()
+        .enumerate()
+        .map(|(i, a)| match cognome_nome(a) {
+            (cognome, Some(nome)) if i > 0 => format!("{} {}", nome, cognome),
+            _ => a.trim().to_string(),
+        })
+        .collect();
+    match nomi.as_slice() {
+        [] => String::new(),
+        [solo] => solo.clone(),
+        [primi @ .., ultimo] => format!("{}, and {}", primi.join(", "), ultimo),
+    }
+}
+
+fn bibtex(testo: &str) -> String {
+    testo.replace('&', "\\&").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Generatore di citazioni per il dataset e per i singoli reperti
+pub struct Citazioni<'a> {
+    inv: &'a Inventario,
+    dataset: &'a MetadatiDataset,
+}
+
+impl<'a> Citazioni<'a> {
+    pub fn nuovo(inv: &'a Inventario, dataset: &'a MetadatiDataset) -> Self {
+        Citazioni { inv, dataset }
+    }
+
+    /// URL del dataset: il DOI se gia registrato, altrimenti l'URI stabile
+    fn url_dataset(&self) -> String {
+        self.dataset.doi.as_ref().map_or(URI_DATASET.to_string(), |doi| format!("https://doi.org/{}", doi))
+    }
+
+    /// Con un DOI il dataset e pubblicato dal deposito (Zenodo)
+    fn editore(&self) -> &'static str {
+        if self.dataset.doi.is_some() { "Zenodo" } else { "BronzeAxe" }
+    }
+
+    /// Citazione del dataset (`id` assente) o di un reperto, anche radiato
+    pub fn cita(&self, id: Option<u32>, stile: Stile) -> Result<String, ErroreInventario> {
+        let d = self.dataset;
+        let autori: Vec<&str> = d.autori.iter().map(|a| a.nome.as_str()).collect();
+        let anno = d.data_pubblicazione.year();
+        let url = self.url_dataset();
+        let editore = self.editore();
+
+        let Some(id) = id else {
+            return Ok(match stile {
+                Stile::Apa => format!(
+                    "{} ({}). {} (Version {}) [Data set]. {}. {}",
+                    autori_apa(&autori), anno, d.titolo, d.versione, editore, url
+                ),
+                Stile::Chicago => format!(
+                    "{}. {}. Version {}. {}, {}. {}.",
+                    autori_chicago(&autori), d.titolo, d.versione, editore, anno, url
+                ),
+                Stile::Bibtex => {
+                    let mut voce = format!(
+                        "@misc{{bronzeaxe_{},\n  author = {{{}}},\n  title = {{{}}},\n  year = {{{}}},\n  version = {{{}}},\n  publisher = {{{}}},\n",
+                        anno,
+                        bibtex(&autori.join(" and ")),
+                        bibtex(&d.titolo),
+                        anno,
+                        d.versione,
+                        editore
+                    );
+                    if let Some(doi) = &d.doi {
+                        voce += &format!("  doi = {{{}}},\n", doi);
+                    }
+                    voce + &format!("  url = {{{}}},\n  note = {{Dataset}}\n}}", url)
+                }
+            });
+        };
+
+        let reperto = self
+            .inv
+            .tutti_compresi_radiati()
+            .into_iter()
+            .find(|r| r.id == id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
+        let uri = uri_reperto(id);
+        Ok(match stile {
+            Stile::Apa => format!(
+                "{} ({}). {} [Reperto n. {}]. In {} (Version {}). {}. {}",
+                autori_apa(&autori), anno, reperto.nome, id, d.titolo, d.versione, editore, uri
+            ),
+            Stile::Chicago => format!(
+                "{}. \"{}.\" Reperto n. {}. In {}, version {}. {}, {}. {}.",
+                autori_chicago(&autori), reperto.nome, id, d.titolo, d.versione, editore, anno, uri
+            ),
+            Stile::Bibtex => format!(
+                "@misc{{bronzeaxe_reperto_{},\n  author = {{{}}},\n  title = {{{}}},\n  year = {{{}}},\n  publisher = {{{}}},\n  url = {{{}}},\n  note = {{Reperto n. {}, in: {}, version {}}}\n}}",
+                id,
+                bibtex(&autori.join(" and ")),
+                bibtex(&reperto.nome),
+                anno,
+                editore,
+                uri,
+                id,
+                bibtex(&d.titolo),
+                d.versione
+            ),
+        })
+    }
+}
@@ -0,0 +1,305 @@
+// ============================================================================
+// MODULO: RIGA DI COMANDO
+// ============================================================================
+// La riga di comando descritta con clap: ogni sottocomando e una variante
+// di `Comando`, ogni opzione un campo. clap genera l'aiuto (`--help`),
+// controlla i valori e stampa errori con suggerimenti prima che il
+// programma tocchi l'archivio.
+// ============================================================================
+
+//! Riga di comando del gestore di inventario, descritta con clap.
+
+// Il modulo intero esiste solo con la feature: e l'unico a usare clap
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use super::citazioni::Stile;
+use super::configurazione::Configurazione;
+use super::inventario::{Ordinamento, StrategiaId};
+use super::notifiche::Frequenza;
+
+/// Gestore di inventario archeologico. Senza sottocomando esegue la
+/// dimostrazione completa del capitolo 9.
+#[derive(Debug, Parser)]
+#[command(name = "cap09_progetto_finale", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// Classificazione guidata con una chiave tipologica (predefinita: asce a margini rialzati)
+    #[arg(long, value_name = "CHIAVE", num_args = 0..=1)]
+    pub assistente_tipologia: Option<Option<PathBuf>>,
+    /// Ricarica l'inventario a ogni modifica del file
+    #[arg(long, value_name = "FILE", conflicts_with = "assistente_tipologia")]
+    pub watch: Option<PathBuf>,
+    /// Copia in sola lettura di una cartella di pubblicazione, servita con
+    /// le sole rotte GET (BRONZEAXE_ASCOLTA)
+    #[arg(long, value_name = "CARTELLA", conflicts_with_all = ["assistente_tipologia", "watch"])]
+    pub mirror: Option<PathBuf>,
+    #[command(subcommand)]
+    pub comando: Option<Comando>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Comando {
+    /// API REST dell'inventario, con /healthz e /readyz
+    Serve {
+        /// Cartella dei dati (predefinita: BRONZEAXE_DATA_DIR, poi `dati`)
+        #[arg(long, value_name = "CARTELLA")]
+        data_dir: Option<PathBuf>,
+        /// Indirizzo di ascolto (predefinito: BRONZEAXE_ASCOLTA, poi 127.0.0.1:8080)
+        #[arg(long, value_name = "INDIRIZZO:PORTA")]
+        ascolta: Option<String>,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+        /// Ricarica l'archivio (un file JSON) quando lo cambia un altro
+        /// programma; l'API resta in sola lettura
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Interroga un servizio avviato con `serve`
+    Ping {
+        /// Indirizzo del servizio, eventualmente con /readyz
+        #[arg(default_value = "http://127.0.0.1:8080")]
+        url: String,
+    },
+    /// Migrazioni dello schema SQLite
+    Db {
+        azione: AzioneDb,
+        /// Database SQLite
+        file: PathBuf,
+    },
+    /// Pacchetto del dataset per Zenodo, eventualmente caricato come bozza
+    Deposito {
+        /// Cartella in cui preparare il pacchetto
+        cartella: PathBuf,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+        /// Carica il pacchetto come bozza (serve BRONZEAXE_ZENODO_TOKEN)
+        #[arg(long)]
+        bozza: bool,
+        /// Carica e pubblica, registrando il DOI
+        #[arg(long)]
+        pubblica: bool,
+    },
+    /// Citazione del dataset o di un reperto
+    Cita {
+        /// ID del reperto; senza, si cita l'intero dataset
+        id: Option<u32>,
+        #[arg(long, default_value = "apa", value_parser = stile)]
+        stile: Stile,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Metadati ARIADNEplus AO-Cat in Turtle
+    Aocat {
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Allinea l'endpoint SPARQL a una cartella di pubblicazione
+    Sparql {
+        /// Cartella di pubblicazione (vedi --mirror)
+        cartella: PathBuf,
+        /// File con l'ultimo snapshot inviato (predefinito: <cartella>/sparql.stato)
+        #[arg(long, value_name = "FILE")]
+        stato: Option<PathBuf>,
+    },
+    /// Riepilogo delle novita, da pianificare con cron
+    Digest {
+        /// giornaliero o settimanale (predefinito: BRONZEAXE_RIEPILOGO)
+        #[arg(value_parser = frequenza)]
+        frequenza: Option<Frequenza>,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Genera un inventario sintetico in JSON
+    Seed {
+        /// Numero di reperti da generare
+        #[arg(long, default_value_t = 1000)]
+        count: usize,
+        /// Seme del generatore: stesso seme, stesso inventario
+        #[arg(long, default_value_t = 42)]
+        seme: u64,
+        #[arg(long, default_value = "id", value_parser = ordinamento)]
+        ordine: Ordinamento,
+        /// Identificativi globali: sequenziale (solo id), uuid o sito (SAV-0001)
+        #[arg(long = "id", default_value = "sequenziale", value_parser = strategia_id)]
+        strategia: StrategiaId,
+        /// File di destinazione (predefinito: standard output)
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Reperti con la priorita di restauro piu alta
+    Triage {
+        /// Quanti reperti elencare
+        #[arg(default_value_t = 10)]
+        n: usize,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Campi calcolati da formule: riepilogo o CSV, con filtri
+    Campi {
+        /// File con le righe `nome = formula` (predefinito: BRONZEAXE_CAMPI_CALCOLATI)
+        #[arg(long, value_name = "FILE")]
+        definizioni: Option<PathBuf>,
+        /// Condizione su un campo base o calcolato, es. "densita > 8" o "peso > 0.3kg"; ripetibile
+        #[arg(long, value_name = "CONDIZIONE")]
+        dove: Vec<String>,
+        /// Un CSV con i campi calcolati di ogni reperto invece del riepilogo
+        #[arg(long)]
+        csv: bool,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Bozze di reperti dal testo OCR delle schede cartacee
+    Ocr {
+        /// Testo dell'OCR, una scheda per pagina
+        testo: PathBuf,
+        /// File con le righe `campo = espressione`, provate prima di quelle predefinite
+        #[arg(long, value_name = "FILE")]
+        regole: Option<PathBuf>,
+        /// Sito per le schede che non indicano la localita
+        #[arg(long, default_value = "non indicato")]
+        sito: String,
+        /// Aggiunge le bozze all'archivio (in ingresso); senza, le mostra soltanto
+        #[arg(long)]
+        salva: bool,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Bozze di reperti dalle righe dettate sul campo
+    Dettatura {
+        /// Trascrizione, una riga per reperto (predefinito: lo standard input)
+        trascrizione: Option<PathBuf>,
+        /// Sito per le righe che non lo nominano
+        #[arg(long, default_value = "non indicato")]
+        sito: String,
+        /// Aggiunge le bozze all'archivio (in ingresso); senza, le mostra soltanto
+        #[arg(long)]
+        salva: bool,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Fogli PDF di etichette per casse e cassetti, una per collocazione
+    Etichette {
+        /// Foglio di etichette adesive: L7160, L7163, L7165 o L7166
+        #[arg(long, default_value = "L7163")]
+        modello: String,
+        /// Solo le collocazioni che iniziano cosi, es. "Deposito A"
+        #[arg(long)]
+        collocazione: Option<String>,
+        /// Posizioni gia usate sul primo foglio
+        #[arg(long, default_value_t = 0)]
+        salta: usize,
+        #[arg(long, default_value = "etichette.pdf")]
+        output: PathBuf,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Distinta di trasporto dei reperti scelti, cassa per cassa
+    Trasporto {
+        /// Id dei reperti da spedire, es. 3,7,12
+        #[arg(long, value_delimiter = ',')]
+        id: Vec<u32>,
+        /// Condizione su un campo base o calcolato, es. "peso > 100g"; ripetibile
+        #[arg(long, value_name = "CONDIZIONE")]
+        dove: Vec<String>,
+        /// Solo le casse che iniziano cosi, es. "Deposito A"
+        #[arg(long)]
+        collocazione: Option<String>,
+        /// File di destinazione (predefinito: standard output)
+        #[arg(long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Dossier di un sito: mappa, periodi, schede e bibliografia
+    Dossier {
+        sito: String,
+        /// PDF invece di Markdown
+        #[arg(long)]
+        pdf: bool,
+        /// File di destinazione (predefinito: lo standard output, o dossier_<sito>.pdf con --pdf)
+        #[arg(long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Controlli e manutenzione degli allegati
+    Media {
+        #[command(subcommand)]
+        azione: AzioneMedia,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AzioneMedia {
+    /// Ricalcola le impronte: allegati mancanti, modificati o illeggibili
+    Verify {
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Aggiorna i percorsi degli allegati spostati in un'altra cartella
+    Relocate {
+        /// Cartella in cui stavano gli allegati
+        #[arg(long = "from", value_name = "CARTELLA")]
+        da: PathBuf,
+        /// Cartella in cui stanno adesso
+        #[arg(long = "to", value_name = "CARTELLA")]
+        a: PathBuf,
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+    /// Rigenera le miniature di tutte le foto (quelle che usa il dossier)
+    Miniature {
+        #[command(flatten)]
+        archivio: OpzioneArchivio,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AzioneDb {
+    /// Applica le migrazioni mancanti
+    Migrate,
+    /// Elenca le migrazioni e il loro stato
+    Status,
+    /// Annulla l'ultima migrazione applicata
+    Rollback,
+}
+
+/// `--archivio`, condiviso dai sottocomandi che leggono l'inventario
+#[derive(Debug, Args)]
+pub struct OpzioneArchivio {
+    /// File, cartella/, sqlite:file.db o postgres://... (predefinito: BRONZEAXE_ARCHIVIO)
+    #[arg(long, value_name = "INDIRIZZO")]
+    pub archivio: Option<String>,
+}
+
+impl OpzioneArchivio {
+    /// Configurazione dall'ambiente, con l'archivio indicato sulla riga di comando
+    pub fn configurazione(&self) -> Configurazione {
+        let mut conf = Configurazione::da_ambiente();
+        if let Some(a) = &self.archivio {
+            conf.archivio = a.clone();
+        }
+        conf
+    }
+}
+
+// I nomi ammessi restano quelli di `da_nome`: qui li colleghiamo a clap,
+// che aggiunge il messaggio all'errore "invalid value"
+fn stile(nome: &str) -> Result<Stile, String> {
+    Stile::da_nome(nome).ok_or_else(|| "stili ammessi: apa, chicago, bibtex".to_string())
+}
+
+fn frequenza(nome: &str) -> Result<Frequenza, String> {
+    Frequenza::da_nome(nome).ok_or_else(|| "frequenze ammesse: giornaliero, settimanale".to_string())
+}
+
+fn ordinamento(nome: &str) -> Result<Ordinamento, String> {
+    Ordinamento::da_nome(nome).ok_or_else(|| "ordinamenti ammessi: id, nome, periodo, sito".to_string())
+}
+
+fn strategia_id(nome: &str) -> Result<StrategiaId, String> {
+    StrategiaId::da_nome(nome).ok_or_else(|| "strategie ammesse: sequenziale, uuid, sito".to_string())
+}
@@ -0,0 +1,98 @@
+// ============================================================================
+// MODULO: CAMPAGNE DI CONTROLLO DELLO STATO
+// ============================================================================
+// I reperti vanno ricontrollati tanto piu spesso quanto piu sono a
+// rischio. Una campagna registra lo stato di ogni reperto controllato e
+// segnala i peggioramenti rispetto al controllo precedente.
+// ============================================================================
+
+//! Campagne periodiche di controllo dello stato di conservazione.
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::*;
+use chrono::NaiveDate;
+
+/// Intervallo massimo tra due controlli, in giorni, in base al rischio
+pub fn intervallo_controllo(rischio: u8) -> i64 {
+    match rischio {
+        5 => 180,
+        4 => 365,
+        3 => 2 * 365,
+        _ => 5 * 365,
+    }
+}
+
+/// Variazione dello stato rispetto al controllo precedente
+#[derive(Debug)]
+pub struct Variazione {
+    pub id_reperto: u32,
+    pub precedente: Conservazione,
+    pub attuale: Conservazione,
+}
+
+impl Variazione {
+    pub fn e_peggioramento(&self) -> bool {
+        self.attuale.punteggio() < self.precedente.punteggio()
+    }
+}
+
+/// Campagna di controllo dello stato di conservazione
+pub struct Campagna {
+    pub nome: String,
+    pub data: NaiveDate,
+    pub variazioni: Vec<Variazione>,
+}
+
+impl Campagna {
+    pub fn nuova(nome: &str, data: NaiveDate) -> Self {
+        Campagna { nome: nome.to_string(), data, variazioni: Vec::new() }
+    }
+
+    /// Reperti da ricontrollare: mai controllati o con l'ultimo controllo
+    /// piu vecchio dell'intervallo previsto per il loro rischio.
+    /// Ordinati per rischio decrescente.
+    pub fn da_controllare<'a>(&self, inv: &'a Inventario) -> Vec<&'a Reperto> {
+        let mut scaduti: Vec<&Reperto> = inv
+            .tutti()
+            .into_iter()
+            .filter(|r| match r.ultimo_controllo() {
+                None => true,
+                Some(ultimo) => {
+                    (self.data - ultimo).num_days() >= intervallo_controllo(r.punteggio_rischio())
+                }
+            })
+            .collect();
+        scaduti.sort_by_key(|r| (std::cmp::Reverse(r.punteggio_rischio()), r.id));
+        scaduti
+    }
+
+    /// Registra il nuovo stato di un reperto e aggiorna la scheda
+    pub fn registra(
+        &mut self,
+        inv: &mut Inventario,
+        id: u32,
+        conservazione: Conservazione,
+    ) -> Result<(), ErroreInventario> {
+        let reperto = inv.cerca_per_id_mut(id)?;
+        let precedente = reperto
+            .controlli
+            .iter()
+            .max_by_key(|c| c.data)
+            .map(|c| c.conservazione.clone())
+            .unwrap_or_else(|| reperto.conservazione.clone());
+        reperto.controlli.push(ControlloConservazione {
+            data: self.data,
+            conservazione: conservazione.clone(),
+            campagna: self.nome.clone(),
+        });
+        reperto.conservazione = conservazione.clone();
+        self.variazioni.push(Variazione { id_reperto: id, precedente, attuale: conservazione });
+        Ok(())
+    }
+
+    /// Reperti peggiorati rispetto al controllo precedente
+    pub fn deterioramenti(&self) -> Vec<&Variazione> {
+        self.variazioni.iter().filter(|v| v.e_peggioramento()).collect()
+    }
+}
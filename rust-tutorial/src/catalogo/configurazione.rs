@@ -0,0 +1,117 @@
+// ============================================================================
+// MODULO: CONFIGURAZIONE DEL SERVIZIO
+// ============================================================================
+// In un container la configurazione arriva dalle variabili d'ambiente
+// (BRONZEAXE_...); la riga di comando puo sovrascriverle. Tutto quello
+// che e facoltativo (posta, AAT, Zenodo, SPARQL, modello linguistico)
+// resta spento se la variabile manca. La passphrase del file dati cifrato
+// (BRONZEAXE_PASSPHRASE) vale per tutti i comandi, anche per --watch.
+// ============================================================================
+
+//! Configurazione da variabili d'ambiente.
+
+#[cfg(feature = "net")]
+use super::deposito::ClienteZenodo;
+use super::archivio::{self, Archivio};
+use super::errori::ErroreInventario;
+use super::notifiche::Frequenza;
+#[cfg(feature = "net")]
+use super::notifiche::Smtp;
+#[cfg(feature = "net")]
+use super::sparql::ClienteSparql;
+#[cfg(feature = "ai")]
+use super::narrativa::ClienteLlm;
+use std::path::PathBuf;
+
+/// Impostazioni del servizio. Ogni valore viene dalle variabili
+/// d'ambiente (adatte ai container) e puo essere sovrascritto da riga di comando
+#[derive(Debug)]
+pub struct Configurazione {
+    /// Cartella con tutti i dati: da montare come volume
+    pub data_dir: PathBuf,
+    pub archivio: String,
+    /// Passphrase del file dati cifrato
+    pub passphrase: Option<String>,
+    pub ascolta: String,
+    /// Server di posta per i riepiloghi: senza, le notifiche sono spente
+    #[cfg(feature = "net")]
+    pub smtp: Option<Smtp>,
+    pub riepilogo: Frequenza,
+    /// Endpoint SPARQL per le ricerche AAT: senza, si usa solo la cache
+    pub aat: Option<String>,
+    /// Credenziali per depositare il dataset su Zenodo
+    #[cfg(feature = "net")]
+    pub zenodo: Option<ClienteZenodo>,
+    /// Endpoint SPARQL Update a cui inviare il grafo CIDOC-CRM
+    #[cfg(feature = "net")]
+    pub sparql: Option<ClienteSparql>,
+    /// API di Crossref e di ORCID: senza, si usa solo la cache
+    pub crossref: Option<String>,
+    pub orcid: Option<String>,
+    /// File con le formule dei campi calcolati (`densita = peso_grammi / volume`)
+    pub campi_calcolati: Option<PathBuf>,
+    /// Modello linguistico che rifinisce il paragrafo del report: senza, resta il testo dai modelli di frase
+    #[cfg(feature = "ai")]
+    pub llm: Option<ClienteLlm>,
+}
+
+impl Configurazione {
+    /// `BRONZEAXE_DATA_DIR` (predefinita `dati`), `BRONZEAXE_ARCHIVIO`
+    /// (predefinito `<data_dir>/inventario.json`) e `BRONZEAXE_ASCOLTA`
+    pub fn da_ambiente() -> Self {
+        Self::da_variabili(|nome| std::env::var(nome).ok())
+    }
+
+    /// Come `da_ambiente`, leggendo le variabili dalla funzione indicata
+    pub fn da_variabili(variabile: impl Fn(&str) -> Option<String>) -> Self {
+        let data_dir = PathBuf::from(variabile("BRONZEAXE_DATA_DIR").unwrap_or_else(|| "dati".to_string()));
+        let archivio = variabile("BRONZEAXE_ARCHIVIO")
+            .unwrap_or_else(|| data_dir.join("inventario.json").display().to_string());
+        Configurazione {
+            data_dir,
+            archivio,
+            passphrase: variabile("BRONZEAXE_PASSPHRASE"),
+            ascolta: variabile("BRONZEAXE_ASCOLTA").unwrap_or_else(|| "127.0.0.1:8080".to_string()),
+            #[cfg(feature = "net")]
+            smtp: Smtp::da_variabili(&variabile),
+            riepilogo: variabile("BRONZEAXE_RIEPILOGO")
+                .and_then(|f| Frequenza::da_nome(&f))
+                .unwrap_or(Frequenza::Giornaliera),
+            // "getty" per l'endpoint pubblico, oppure l'indirizzo di un altro
+            aat: variabile("BRONZEAXE_AAT").map(|a| match a.as_str() {
+                "getty" => super::aat::ENDPOINT_GETTY.to_string(),
+                _ => a,
+            }),
+            #[cfg(feature = "net")]
+            zenodo: ClienteZenodo::da_variabili(&variabile),
+            #[cfg(feature = "net")]
+            sparql: ClienteSparql::da_variabili(&variabile),
+            // "crossref" e "orcid" per le API pubbliche, oppure un altro indirizzo
+            crossref: variabile("BRONZEAXE_CROSSREF").map(|c| match c.as_str() {
+                "crossref" => super::riferimenti::CROSSREF.to_string(),
+                _ => c,
+            }),
+            orcid: variabile("BRONZEAXE_ORCID").map(|o| match o.as_str() {
+                "orcid" => super::riferimenti::ORCID.to_string(),
+                _ => o,
+            }),
+            campi_calcolati: variabile("BRONZEAXE_CAMPI_CALCOLATI").map(PathBuf::from),
+            #[cfg(feature = "ai")]
+            llm: ClienteLlm::da_variabili(&variabile),
+        }
+    }
+
+    /// L'archivio configurato, con la sua passphrase
+    pub fn apri_archivio(&self) -> Result<Box<dyn Archivio>, ErroreInventario> {
+        archivio::da_indirizzo(&self.archivio, self.passphrase.clone())
+    }
+
+    /// `--data-dir` sposta anche l'archivio predefinito, se non indicato altrove
+    pub fn con_data_dir(mut self, data_dir: PathBuf) -> Self {
+        if self.archivio == self.data_dir.join("inventario.json").display().to_string() {
+            self.archivio = data_dir.join("inventario.json").display().to_string();
+        }
+        self.data_dir = data_dir;
+        self
+    }
+}
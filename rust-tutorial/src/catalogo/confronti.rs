@@ -0,0 +1,103 @@
+// ============================================================================
+// MODULO: CONFRONTI
+// ============================================================================
+// I comparanda di un reperto: stesso tipo (o stessa classe funzionale
+// se non e tipizzato), misure entro le tolleranze, punteggio piu alto
+// per periodo e sito vicini.
+// ============================================================================
+
+//! Ricerca di confronti per tipo, periodo e dimensioni.
+
+use super::modelli::*;
+
+/// Scarto massimo ammesso sulle misure, in percentuale
+pub struct Tolleranze {
+    pub dimensioni_percento: f64,
+    pub peso_percento: f64,
+}
+
+impl Default for Tolleranze {
+    fn default() -> Self {
+        Tolleranze { dimensioni_percento: 10.0, peso_percento: 20.0 }
+    }
+}
+
+/// Reperto confrontabile, con punteggio e motivazioni della somiglianza
+pub struct Confronto<'a> {
+    pub reperto: &'a Reperto,
+    pub punteggio: u32,
+    pub motivi: Vec<String>,
+}
+
+fn scarto_percento(a: f64, b: f64) -> f64 {
+    (a - b).abs() / a.max(b) * 100.0
+}
+
+/// Cerca confronti per `nuovo` in un corpus (l'inventario o un corpus di
+/// riferimento). Un candidato deve condividere il tipo, se `nuovo` e
+/// tipizzato, altrimenti la classe funzionale; le misure fuori
+/// tolleranza lo escludono. Risultati in ordine di punteggio decrescente
+pub fn cerca<'a>(
+    nuovo: &Reperto,
+    corpus: impl IntoIterator<Item = &'a Reperto>,
+    tolleranze: &Tolleranze,
+) -> Vec<Confronto<'a>> {
+    let mut confronti: Vec<Confronto> = corpus
+        .into_iter()
+        .filter(|r| nuovo.id == 0 || r.id != nuovo.id)
+        .filter_map(|r| valuta(nuovo, r, tolleranze))
+        .collect();
+    confronti.sort_by(|a, b| b.punteggio.cmp(&a.punteggio).then(a.reperto.id.cmp(&b.reperto.id)));
+    confronti
+}
+
+fn valuta<'a>(nuovo: &Reperto, r: &'a Reperto, tolleranze: &Tolleranze) -> Option<Confronto<'a>> {
+    let mut punteggio = 0;
+    let mut motivi = Vec::new();
+
+    match (&nuovo.tipo, &r.tipo) {
+        (Some(a), Some(b)) if a == b => {
+            punteggio += 3;
+            motivi.push(format!("stesso tipo ({})", a));
+        }
+        (Some(_), _) => return None,
+        (None, _) => {}
+    }
+    match (nuovo.classe_funzionale, r.classe_funzionale) {
+        (Some(a), Some(b)) if a == b => {
+            punteggio += 1;
+            motivi.push(format!("stessa classe funzionale ({})", a));
+        }
+        _ if nuovo.tipo.is_none() => return None,
+        _ => {}
+    }
+    if nuovo.periodo == r.periodo {
+        punteggio += 2;
+        motivi.push(format!("stesso periodo ({})", r.periodo));
+    }
+    if let (Some(a), Some(b)) = (nuovo.materiale(), r.materiale()) {
+        if a == b {
+            punteggio += 1;
+            motivi.push(format!("stesso materiale ({})", a));
+        }
+    }
+
+    let (m, n) = (&nuovo.misurazioni, &r.misurazioni);
+    let misure = [
+        ("lunghezza", m.lunghezza_cm, n.lunghezza_cm, "cm", tolleranze.dimensioni_percento),
+        ("larghezza", m.larghezza_cm, n.larghezza_cm, "cm", tolleranze.dimensioni_percento),
+        ("spessore", m.altezza_cm, n.altezza_cm, "cm", tolleranze.dimensioni_percento),
+        ("peso", m.peso_grammi, n.peso_grammi, "g", tolleranze.peso_percento),
+    ];
+    for (nome, a, b, unita, tolleranza) in misure {
+        let (Some(a), Some(b)) = (a, b) else { continue };
+        let scarto = scarto_percento(a, b);
+        if scarto > tolleranza {
+            return None;
+        }
+        punteggio += 1;
+        motivi.push(format!("{} {} {} (nuovo {} {}, scarto {:.0}%)", nome, b, unita, a, unita, scarto));
+    }
+
+    Some(Confronto { reperto: r, punteggio, motivi })
+}
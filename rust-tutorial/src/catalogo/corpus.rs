@@ -0,0 +1,210 @@
+// ============================================================================
+// MODULO: CORPUS DI RIFERIMENTO
+// ============================================================================
+// I cataloghi dei tipi (come i volumi PBF) danno per ogni tipo gli
+// intervalli delle misure e la distribuzione geografica. Il corpus si
+// importa da CSV o JSON e serve a proporre un tipo per un reperto nuovo.
+// ============================================================================
+
+//! Corpus di riferimento importato da CSV o JSON.
+
+use super::errori::ErroreInventario;
+use super::modelli::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Corpus di esempio incluso nell'eseguibile
+pub const CORPUS_ASCE_PBF: &str = include_str!("../../dati/corpus/asce_pbf.csv");
+
+/// Intervallo chiuso di valori ammessi per una misura
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Intervallo {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Intervallo {
+    pub fn contiene(&self, valore: f64) -> bool {
+        (self.min..=self.max).contains(&valore)
+    }
+}
+
+impl fmt::Display for Intervallo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.min, self.max)
+    }
+}
+
+/// Tipo di un catalogo di riferimento, con intervalli dimensionali e
+/// distribuzione geografica
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TipoRiferimento {
+    pub tipo: String,
+    pub classe: Option<ClasseFunzionale>,
+    pub periodo: Option<Periodo>,
+    pub lunghezza_cm: Option<Intervallo>,
+    pub larghezza_cm: Option<Intervallo>,
+    pub peso_g: Option<Intervallo>,
+    #[serde(default)]
+    pub distribuzione: Vec<String>,
+    pub riferimento: String,
+    pub esemplari: Option<u32>,
+}
+
+/// Tipo del corpus compatibile con un reperto
+pub struct Corrispondenza<'a> {
+    pub tipo: &'a TipoRiferimento,
+    pub punteggio: u32,
+    pub motivi: Vec<String>,
+}
+
+/// Catalogo di riferimento, separato dall'inventario di lavoro
+#[derive(Debug, Default)]
+pub struct CorpusRiferimento {
+    tipi: BTreeMap<String, TipoRiferimento>,
+}
+
+impl CorpusRiferimento {
+    pub fn nuovo() -> Self {
+        Self::default()
+    }
+
+    /// Importa un array JSON di tipi; restituisce quanti tipi sono stati aggiunti
+    pub fn importa_json(&mut self, json: &str) -> Result<usize, ErroreInventario> {
+        let tipi: Vec<TipoRiferimento> = serde_json::from_str(json)?;
+        self.aggiungi_tutti(tipi)
+    }
+
+    /// Importa un CSV separato da ';' con intestazione
+    /// tipo;classe;periodo;lunghezza_cm;larghezza_cm;peso_g;distribuzione;riferimento;esemplari
+    /// Gli intervalli si scrivono "min-max", la distribuzione separata da '|';
+    /// le righe che iniziano con '#' sono commenti
+    pub fn importa_csv(&mut self, csv: &str) -> Result<usize, ErroreInventario> {
+        let mut righe = csv
+            .lines()
+            .enumerate()
+            .filter(|(_, r)| !r.trim().is_empty() && !r.starts_with('#'));
+        righe.next().ok_or_else(|| ErroreInventario::DatiNonValidi("CSV vuoto".to_string()))?;
+
+        let mut tipi = Vec::new();
+        for (n, riga) in righe {
+            let errore = |msg: String| ErroreInventario::DatiNonValidi(format!("riga {}: {}", n + 1, msg));
+            let campi: Vec<&str> = riga.split(';').map(str::trim).collect();
+            if campi.len() != 9 {
+                return Err(errore(format!("attesi 9 campi, trovati {}", campi.len())));
+            }
+            let enumerato = |testo: &str| -> Result<Option<serde_json::Value>, ErroreInventario> {
+                Ok((!testo.is_empty()).then(|| serde_json::Value::String(testo.to_string())))
+            };
+            let classe = match enumerato(campi[1])? {
+                Some(v) => Some(serde_json::from_value(v).map_err(|_| errore(format!("classe '{}' sconosciuta", campi[1])))?),
+                None => None,
+            };
+            let periodo = match enumerato(campi[2])? {
+                Some(v) => Some(serde_json::from_value(v).map_err(|_| errore(format!("periodo '{}' sconosciuto", campi[2])))?),
+                None => None,
+            };
+            let intervallo = |testo: &str| -> Result<Option<Intervallo>, ErroreInventario> {
+                if testo.is_empty() {
+                    return Ok(None);
+                }
+                let (min, max) = testo
+                    .split_once('-')
+                    .and_then(|(a, b)| Some((a.parse::<f64>().ok()?, b.parse::<f64>().ok()?)))
+                    .filter(|(a, b)| a <= b)
+                    .ok_or_else(|| errore(format!("intervallo '{}' non valido", testo)))?;
+                Ok(Some(Intervallo { min, max }))
+            };
+            let esemplari = match campi[8] {
+                "" => None,
+                testo => Some(testo.parse().map_err(|_| errore(format!("numero di esemplari '{}' non valido", testo)))?),
+            };
+            tipi.push(TipoRiferimento {
+                tipo: campi[0].to_string(),
+                classe,
+                periodo,
+                lunghezza_cm: intervallo(campi[3])?,
+                larghezza_cm: intervallo(campi[4])?,
+                peso_g: intervallo(campi[5])?,
+                distribuzione: campi[6].split('|').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect(),
+                riferimento: campi[7].to_string(),
+                esemplari,
+            });
+        }
+        self.aggiungi_tutti(tipi)
+    }
+
+    fn aggiungi_tutti(&mut self, tipi: Vec<TipoRiferimento>) -> Result<usize, ErroreInventario> {
+        for t in &tipi {
+            if t.tipo.is_empty() {
+                return Err(ErroreInventario::DatiNonValidi("tipo senza nome".to_string()));
+            }
+            if self.tipi.contains_key(&t.tipo) {
+                return Err(ErroreInventario::DatiNonValidi(format!("tipo '{}' gia presente nel corpus", t.tipo)));
+            }
+        }
+        let n = tipi.len();
+        self.tipi.extend(tipi.into_iter().map(|t| (t.tipo.clone(), t)));
+        Ok(n)
+    }
+
+    pub fn tipi(&self) -> impl Iterator<Item = &TipoRiferimento> {
+        self.tipi.values()
+    }
+
+    pub fn tipo(&self, nome: &str) -> Option<&TipoRiferimento> {
+        self.tipi.get(nome)
+    }
+
+    /// Tipi del corpus compatibili con classe, periodo e misure del
+    /// reperto, in ordine di punteggio decrescente
+    pub fn corrispondenze(&self, reperto: &Reperto) -> Vec<Corrispondenza<'_>> {
+        let mut risultati: Vec<Corrispondenza> = self.tipi().filter_map(|t| valuta(reperto, t)).collect();
+        risultati.sort_by(|a, b| b.punteggio.cmp(&a.punteggio).then(a.tipo.tipo.cmp(&b.tipo.tipo)));
+        risultati
+    }
+}
+
+fn valuta<'a>(reperto: &Reperto, t: &'a TipoRiferimento) -> Option<Corrispondenza<'a>> {
+    let mut punteggio = 0;
+    let mut motivi = Vec::new();
+    if reperto.tipo.as_deref() == Some(t.tipo.as_str()) {
+        punteggio += 3;
+        motivi.push("tipo gia attribuito".to_string());
+    }
+    if let (Some(a), Some(b)) = (reperto.classe_funzionale, t.classe) {
+        if a != b {
+            return None;
+        }
+        punteggio += 1;
+        motivi.push(format!("classe {}", a));
+    }
+    if let Some(periodo) = &t.periodo {
+        if reperto.periodo != Periodo::Sconosciuto {
+            if &reperto.periodo != periodo {
+                return None;
+            }
+            punteggio += 2;
+            motivi.push(format!("periodo {}", periodo));
+        }
+    }
+    let m = &reperto.misurazioni;
+    let misure = [
+        ("lunghezza", m.lunghezza_cm, t.lunghezza_cm, "cm"),
+        ("larghezza", m.larghezza_cm, t.larghezza_cm, "cm"),
+        ("peso", m.peso_grammi, t.peso_g, "g"),
+    ];
+    let mut confrontate = 0;
+    for (nome, valore, intervallo, unita) in misure {
+        let (Some(valore), Some(intervallo)) = (valore, intervallo) else { continue };
+        if !intervallo.contiene(valore) {
+            return None;
+        }
+        confrontate += 1;
+        punteggio += 1;
+        motivi.push(format!("{} {} {} entro {} {}", nome, valore, unita, intervallo, unita));
+    }
+    // Senza misure confrontabili la corrispondenza non e significativa
+    (confrontate > 0).then_some(Corrispondenza { tipo: t, punteggio, motivi })
+}
@@ -0,0 +1,37 @@
+// ============================================================================
+// MODULO: CATENA DI CUSTODIA
+// ============================================================================
+// Ogni movimentazione di un reperto resta registrata: il report di
+// custodia le elenca in ordine e si puo chiedere dove fosse ogni reperto
+// in un momento qualsiasi.
+// ============================================================================
+
+//! Report di custodia e posizioni dei reperti a una data.
+
+use super::inventario::Inventario;
+use super::modelli::*;
+use chrono::NaiveDateTime;
+
+/// Report di catena di custodia per un singolo reperto
+pub fn report(reperto: &Reperto) -> String {
+    let mut righe = vec![format!("CATENA DI CUSTODIA - #{} {}", reperto.id, reperto.nome)];
+    if reperto.movimenti.is_empty() {
+        righe.push("  Nessun movimento registrato".to_string());
+    }
+    for (i, m) in reperto.movimenti.iter().enumerate() {
+        righe.push(format!("  {:>2}. {}", i + 1, m));
+    }
+    righe.push(format!(
+        "  Collocazione attuale: {}",
+        reperto.collocazione.as_deref().unwrap_or("sconosciuta")
+    ));
+    righe.join("\n")
+}
+
+/// Dove si trovava ogni reperto alla data/ora indicata
+pub fn posizioni_al(inv: &Inventario, quando: NaiveDateTime) -> Vec<(u32, Option<&str>)> {
+    inv.tutti()
+        .into_iter()
+        .map(|r| (r.id, r.collocazione_al(quando)))
+        .collect()
+}
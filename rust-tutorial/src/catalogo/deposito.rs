@@ -0,0 +1,286 @@
+// ============================================================================
+// MODULO: DEPOSITO DEL DATASET
+// ============================================================================
+// Il dataset pubblico (JSON, CSV, README e licenza) si prepara in una
+// cartella e si deposita su Zenodo attraverso la sua API, che assegna
+// il DOI.
+// ============================================================================
+
+//! Pacchetto del dataset pubblico e deposito su Zenodo.
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::{Licenza, Reperto};
+use super::visibilita::ProfiloVisibilita;
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+/// Autore del dataset, nella forma richiesta da Zenodo ("Cognome, Nome")
+#[derive(Debug, Clone)]
+pub struct Autore {
+    pub nome: String,
+    pub affiliazione: Option<String>,
+    pub orcid: Option<String>,
+}
+
+/// Metadati descrittivi del dataset da depositare
+#[derive(Debug, Clone)]
+pub struct MetadatiDataset {
+    pub titolo: String,
+    pub descrizione: String,
+    pub autori: Vec<Autore>,
+    pub parole_chiave: Vec<String>,
+    pub licenza: Licenza,
+    pub versione: String,
+    pub data_pubblicazione: NaiveDate,
+    /// DOI registrato alla pubblicazione del deposito
+    pub doi: Option<String>,
+}
+
+/// Identificativo della licenza nel vocabolario di Zenodo e URL del testo
+fn licenza_zenodo(licenza: &Licenza) -> Result<(&'static str, &'static str), ErroreInventario> {
+    Ok(match licenza {
+        Licenza::Cc0 => ("cc-zero", "https://creativecommons.org/publicdomain/zero/1.0/"),
+        Licenza::CcBy => ("cc-by-4.0", "https://creativecommons.org/licenses/by/4.0/"),
+        Licenza::CcBySa => ("cc-by-sa-4.0", "https://creativecommons.org/licenses/by-sa/4.0/"),
+        Licenza::CcByNc => ("cc-by-nc-4.0", "https://creativecommons.org/licenses/by-nc/4.0/"),
+        Licenza::CcByNcNd => ("cc-by-nc-nd-4.0", "https://creativecommons.org/licenses/by-nc-nd/4.0/"),
+        Licenza::TuttiIDirittiRiservati => {
+            return Err(ErroreInventario::DatiNonValidi(
+                "un dataset in accesso aperto richiede una licenza Creative Commons".to_string(),
+            ))
+        }
+    })
+}
+
+/// Indirizzo del testo della licenza
+pub fn url_licenza(licenza: &Licenza) -> Result<&'static str, ErroreInventario> {
+    licenza_zenodo(licenza).map(|(_, url)| url)
+}
+
+/// Metadati della deposizione nel formato dell'API Zenodo
+pub fn metadati_zenodo(metadati: &MetadatiDataset) -> Result<Value, ErroreInventario> {
+    if metadati.autori.is_empty() || metadati.titolo.trim().is_empty() {
+        return Err(ErroreInventario::DatiNonValidi(
+            "il dataset deve avere titolo e almeno un autore".to_string(),
+        ));
+    }
+    let (licenza, _) = licenza_zenodo(&metadati.licenza)?;
+    let autori: Vec<Value> = metadati
+        .autori
+        .iter()
+        .map(|a| {
+            let mut autore = json!({ "name": a.nome });
+            if let Some(affiliazione) = &a.affiliazione {
+                autore["affiliation"] = json!(affiliazione);
+            }
+            if let Some(orcid) = &a.orcid {
+                autore["orcid"] = json!(orcid);
+            }
+            autore
+        })
+        .collect();
+    Ok(json!({
+        "metadata": {
+            "upload_type": "dataset",
+            "title": metadati.titolo,
+            "description": metadati.descrizione,
+            "creators": autori,
+            "keywords": metadati.parole_chiave,
+            "license": licenza,
+            "access_right": "open",
+            "version": metadati.versione,
+            "publication_date": metadati.data_pubblicazione.format("%Y-%m-%d").to_string(),
+            "language": "ita",
+        }
+    }))
+}
+
+/// Pacchetto pronto per la deposizione: cartella e file da caricare
+#[derive(Debug)]
+pub struct Pacchetto {
+    pub cartella: PathBuf,
+    pub file: Vec<PathBuf>,
+    pub metadati: Value,
+    pub snapshot: String,
+}
+
+fn campo_csv(valore: &str) -> String {
+    if valore.contains([';', '"', '\n']) {
+        format!("\"{}\"", valore.replace('"', "\"\""))
+    } else {
+        valore.to_string()
+    }
+}
+
+/// Una riga per reperto, separatore `;` come gli altri CSV del progetto
+fn csv(reperti: &[Reperto]) -> String {
+    let numero = |v: Option<f64>| v.map_or(String::new(), |v| v.to_string());
+    let mut csv = "id;nome;materiale;periodo;sito;tipo;classe;conservazione;lunghezza_cm;larghezza_cm;altezza_cm;peso_g;radiato\n".to_string();
+    for r in reperti {
+        let campi = [
+            r.id.to_string(),
+            r.nome.clone(),
+            r.materiale().map_or(String::new(), |m| m.to_string()),
+            r.periodo.to_string(),
+            r.sito.clone(),
+            r.tipo.clone().unwrap_or_default(),
+            r.classe_funzionale.as_ref().map_or(String::new(), |c| c.to_string()),
+            r.conservazione.to_string(),
+            numero(r.misurazioni.lunghezza_cm),
+            numero(r.misurazioni.larghezza_cm),
+            numero(r.misurazioni.altezza_cm),
+            numero(r.misurazioni.peso_grammi),
+            r.radiazione.as_ref().map_or(String::new(), |d| d.motivo.to_string()),
+        ];
+        csv += &campi.iter().map(|c| campo_csv(c)).collect::<Vec<_>>().join(";");
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Scrive nella cartella il dataset pubblico (profilo `pubblico`):
+/// reperti.json, reperti.csv, README.md, LICENSE.txt e zenodo.json
+pub fn prepara(
+    inv: &Inventario,
+    metadati: &MetadatiDataset,
+    cartella: PathBuf,
+    oggi: NaiveDate,
+) -> Result<Pacchetto, ErroreInventario> {
+    let zenodo = metadati_zenodo(metadati)?;
+    let (licenza, url_licenza) = licenza_zenodo(&metadati.licenza)?;
+    let profilo = ProfiloVisibilita::pubblico();
+    let reperti: Vec<Reperto> = inv
+        .tutti_compresi_radiati()
+        .into_iter()
+        .filter_map(|r| profilo.applica(r, oggi))
+        .collect();
+    let snapshot = inv.impronta_snapshot()?;
+
+    let autori: Vec<&str> = metadati.autori.iter().map(|a| a.nome.as_str()).collect();
+    let readme = format!(
+        "# {titolo}\n\n{descrizione}\n\n\
+         - Autori: {autori}\n\
+         - Versione: {versione} ({data})\n\
+         - Licenza: {licenza} ({url})\n\
+         - Reperti: {n}\n\
+         - Snapshot del catalogo: {snapshot}\n\n\
+         ## File\n\n\
+         - `reperti.json`: schede complete nel formato BronzeAxe (profilo pubblico)\n\
+         - `reperti.csv`: campi principali, una riga per reperto, separatore `;`\n\
+         - `LICENSE.txt`: licenza d'uso dei dati\n\
+         - `zenodo.json`: metadati della deposizione\n\n\
+         Le coordinate, le stime e le collocazioni non sono incluse. I reperti\n\
+         radiati dall'inventario sono presenti e marcati nella colonna `radiato`.\n",
+        titolo = metadati.titolo,
+        descrizione = metadati.descrizione,
+        autori = autori.join("; "),
+        versione = metadati.versione,
+        data = metadati.data_pubblicazione.format("%Y-%m-%d"),
+        licenza = metadati.licenza,
+        url = url_licenza,
+        n = reperti.len(),
+        snapshot = snapshot,
+    );
+    let titolari = if autori.is_empty() { "gli autori".to_string() } else { autori.join("; ") };
+    let testo_licenza = format!(
+        "{} - {}\n\nCopyright {} {}\n\nI dati di questo dataset sono distribuiti con licenza {} ({}).\nIl testo completo della licenza e disponibile all'indirizzo indicato.\n",
+        metadati.titolo,
+        metadati.versione,
+        metadati.data_pubblicazione.format("%Y"),
+        titolari,
+        metadati.licenza,
+        licenza
+    ) + url_licenza + "\n";
+
+    fs::create_dir_all(&cartella)?;
+    let mut file = Vec::new();
+    for (nome, contenuto) in [
+        ("reperti.json", inv.to_json_con_profilo(&profilo, oggi)?),
+        ("reperti.csv", csv(&reperti)),
+        ("README.md", readme),
+        ("LICENSE.txt", testo_licenza),
+        ("zenodo.json", serde_json::to_string_pretty(&zenodo)?),
+    ] {
+        let percorso = cartella.join(nome);
+        fs::write(&percorso, contenuto)?;
+        file.push(percorso);
+    }
+    Ok(Pacchetto { cartella, file, metadati: zenodo, snapshot })
+}
+
+/// Bozza creata su Zenodo: il DOI e riservato ma non ancora registrato
+#[cfg(feature = "net")]
+#[derive(Debug)]
+pub struct Bozza {
+    pub id: u64,
+    pub doi_riservato: Option<String>,
+    pub pagina: Option<String>,
+}
+
+/// Client dell'API di deposizione di Zenodo (o della sua sandbox)
+#[cfg(feature = "net")]
+#[derive(Debug, Clone)]
+pub struct ClienteZenodo {
+    /// Es. https://sandbox.zenodo.org/api per le prove
+    pub url_base: String,
+    pub token: String,
+}
+
+#[cfg(feature = "net")]
+impl ClienteZenodo {
+    /// Da `BRONZEAXE_ZENODO_TOKEN` e `BRONZEAXE_ZENODO_URL` (predefinito
+    /// l'istanza principale); `None` senza token
+    pub fn da_variabili(variabile: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        Some(ClienteZenodo {
+            token: variabile("BRONZEAXE_ZENODO_TOKEN")?,
+            url_base: variabile("BRONZEAXE_ZENODO_URL").unwrap_or_else(|| "https://zenodo.org/api".to_string()),
+        })
+    }
+
+    fn autorizzazione(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    /// Crea la bozza con i metadati del pacchetto e carica i file
+    pub fn crea_bozza(&self, pacchetto: &Pacchetto) -> Result<Bozza, ErroreInventario> {
+        let risposta: Value = ureq::post(format!("{}/deposit/depositions", self.url_base))
+            .header("Authorization", self.autorizzazione())
+            .send_json(&pacchetto.metadati)?
+            .body_mut()
+            .read_json()?;
+        let id = risposta["id"]
+            .as_u64()
+            .ok_or_else(|| ErroreInventario::ServizioRemoto("risposta di Zenodo senza id".to_string()))?;
+        let bucket = risposta["links"]["bucket"]
+            .as_str()
+            .ok_or_else(|| ErroreInventario::ServizioRemoto("risposta di Zenodo senza bucket".to_string()))?;
+        for percorso in &pacchetto.file {
+            let nome = percorso.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            ureq::put(format!("{}/{}", bucket, nome))
+                .header("Authorization", self.autorizzazione())
+                .header("Content-Type", "application/octet-stream")
+                .send(&fs::read(percorso)?[..])?;
+        }
+        Ok(Bozza {
+            id,
+            doi_riservato: risposta["metadata"]["prereserve_doi"]["doi"].as_str().map(String::from),
+            pagina: risposta["links"]["html"].as_str().map(String::from),
+        })
+    }
+
+    /// Pubblica la bozza: Zenodo registra il DOI, che viene restituito
+    pub fn pubblica(&self, bozza: &Bozza) -> Result<String, ErroreInventario> {
+        let risposta: Value = ureq::post(format!("{}/deposit/depositions/{}/actions/publish", self.url_base, bozza.id))
+            .header("Authorization", self.autorizzazione())
+            .send_empty()?
+            .body_mut()
+            .read_json()?;
+        risposta["doi"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| ErroreInventario::ServizioRemoto("Zenodo non ha restituito il DOI".to_string()))
+    }
+}
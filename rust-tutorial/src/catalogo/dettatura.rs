@@ -0,0 +1,387 @@
+// ============================================================================
+// MODULO: DETTATURA SUL CAMPO
+// ============================================================================
+// Sullo scavo le mani sono occupate: il reperto si detta a un programma di
+// trascrizione vocale, che restituisce una riga di testo per reperto.
+// Ogni riga diventa la bozza di un reperto:
+//
+//   "ascia, bronzo, Savignano, peso trecentocinquanta grammi"
+//
+//   ascia                           ->  nome (sempre il primo pezzo)
+//   bronzo                          ->  Materiale::Bronzo
+//   Savignano                       ->  sito, confrontato con i siti noti
+//   peso trecentocinquanta grammi   ->  350 g
+//
+// I pezzi sono separati da virgole (seguite da uno spazio: "12,5" resta
+// un numero) o da punti e virgola. I numeri si dettano in lettere
+// ("dodici virgola cinque", "un chilo e mezzo") o arrivano in cifre, se
+// il programma di trascrizione li converte.
+//
+// Come per le schede passate all'OCR (vedi `ocr`), una bozza porta una
+// nota con i dubbi da controllare: un pezzo non capito, un sito ambiguo,
+// una misura senza unita.
+// ============================================================================
+
+//! Bozze di reperti da righe dettate e trascritte, per la registrazione sul campo.
+
+use super::modelli::{Conservazione, Materiale, Misurazioni, Nota, Periodo, Reperto};
+use super::unita::{self, Quantita};
+
+/// L'inizio della nota che accompagna ogni reperto dettato
+pub const NOTA_DETTATURA: &str = "Bozza da dettatura";
+
+/// Cifre e decine in lettere, le parole piu lunghe prima: "tredici" va
+/// provato prima di "tre". Le forme tronche ("vent" in "ventuno",
+/// "cent" in "centotto") valgono quanto quelle intere.
+const NUMERI: [(&str, u32); 41] = [
+    ("diciassette", 17),
+    ("diciannove", 19),
+    ("quattordici", 14),
+    ("cinquanta", 50),
+    ("cinquant", 50),
+    ("settanta", 70),
+    ("sessanta", 60),
+    ("quaranta", 40),
+    ("novanta", 90),
+    ("ottanta", 80),
+    ("settant", 70),
+    ("sessant", 60),
+    ("quarant", 40),
+    ("quindici", 15),
+    ("diciotto", 18),
+    ("quattro", 4),
+    ("tredici", 13),
+    ("novant", 90),
+    ("ottant", 80),
+    ("trenta", 30),
+    ("dodici", 12),
+    ("undici", 11),
+    ("sedici", 16),
+    ("cinque", 5),
+    ("trent", 30),
+    ("venti", 20),
+    ("dieci", 10),
+    ("cento", 100),
+    ("mille", 1000),
+    ("sette", 7),
+    ("vent", 20),
+    ("cent", 100),
+    ("mila", 1000),
+    ("zero", 0),
+    ("nove", 9),
+    ("otto", 8),
+    ("uno", 1),
+    ("una", 1),
+    ("due", 2),
+    ("tre", 3),
+    ("sei", 6),
+];
+
+/// Un intero in lettere, scritto attaccato come si scrive in italiano
+/// ("trecentocinquanta", "duemilacento"), o in cifre. Un numero che non
+/// sta in un `u32` ("cento cento cento cento cento") non e capito.
+fn intero(parola: &str) -> Option<u32> {
+    if let Ok(n) = parola.parse() {
+        return Some(n);
+    }
+    if parola == "un" {
+        return Some(1);
+    }
+    let (mut totale, mut corrente, mut resto) = (0u32, 0u32, parola);
+    while !resto.is_empty() {
+        let (lettere, valore) = NUMERI.iter().find(|(lettere, _)| resto.starts_with(lettere))?;
+        match valore {
+            100 => corrente = corrente.max(1).checked_mul(100)?,
+            1000 => {
+                totale = totale.checked_add(corrente.max(1).checked_mul(1000)?)?;
+                corrente = 0;
+            }
+            v => corrente = corrente.checked_add(*v)?,
+        }
+        resto = &resto[lettere.len()..];
+    }
+    totale.checked_add(corrente)
+}
+
+/// Un numero dettato: in lettere, anche separate ("trecento cinquanta"),
+/// con i decimali ("dodici virgola cinque", "un chilo e mezzo") o in cifre.
+///
+/// ```
+/// use rust_tutorial::catalogo::dettatura::numero;
+///
+/// assert_eq!(numero("trecentocinquanta"), Some(350.0));
+/// assert_eq!(numero("duemila trecento"), Some(2300.0));
+/// assert_eq!(numero("ventotto"), Some(28.0));
+/// assert_eq!(numero("ventitré"), Some(23.0));
+/// assert_eq!(numero("dodici virgola zero cinque"), Some(12.05));
+/// assert_eq!(numero("uno e mezzo"), Some(1.5));
+/// assert_eq!(numero("12,5"), Some(12.5));
+/// assert_eq!(numero("ascia"), None);
+/// ```
+pub fn numero(testo: &str) -> Option<f64> {
+    // Il programma di trascrizione scrive "ventitré" con l'accento
+    let testo = testo.trim().to_lowercase().replace(['é', 'è'], "e");
+    if let Ok(valore) = testo.replace(',', ".").parse() {
+        return Some(valore);
+    }
+    let (intera, decimali) = match testo.split_once(" virgola ") {
+        Some((intera, decimali)) => (intera, Some(decimali)),
+        None => (testo.as_str(), None),
+    };
+    let mut parole: Vec<&str> = intera.split_whitespace().collect();
+    let mezzo = parole.ends_with(&["e", "mezzo"]);
+    if mezzo {
+        parole.truncate(parole.len() - 2);
+    }
+    parole.retain(|p| *p != "e");
+    if parole.is_empty() {
+        return None;
+    }
+    let mut valore = intero(&parole.concat())? as f64;
+    if let Some(decimali) = decimali {
+        // "virgola zero cinque": ogni parola e una o piu cifre dopo la virgola
+        let cifre = decimali.split_whitespace().map(|p| intero(p).map(|n| n.to_string())).collect::<Option<String>>()?;
+        valore += format!("0.{}", cifre).parse::<f64>().ok()?;
+    }
+    if mezzo {
+        valore += 0.5;
+    }
+    Some(valore)
+}
+
+/// Le unita dette per intero, con il simbolo della tabella di `unita`
+fn unita(parola: &str) -> Option<&'static str> {
+    let simbolo = match parola {
+        "grammi" | "grammo" | "gr" => "g",
+        "chili" | "chilo" | "chilogrammi" | "chilogrammo" => "kg",
+        "milligrammi" | "milligrammo" => "mg",
+        "centimetri" | "centimetro" => "cm",
+        "millimetri" | "millimetro" => "mm",
+        "metri" | "metro" => "m",
+        p => p,
+    };
+    Quantita::da_testo(&format!("1 {}", simbolo)).ok()?.unita
+}
+
+/// Il campo di una misura dalla parola con cui si detta
+fn misura(parola: &str) -> Option<&'static str> {
+    Some(match parola {
+        "peso" | "pesa" => "peso_grammi",
+        "lunghezza" | "lungo" | "lunga" => "lunghezza_cm",
+        "larghezza" | "largo" | "larga" => "larghezza_cm",
+        "altezza" | "alto" | "alta" | "spessore" | "spesso" | "spessa" => "altezza_cm",
+        _ => return None,
+    })
+}
+
+/// Un reperto dettato, con i dubbi della lettura (gli stessi scritti
+/// nella sua prima nota).
+#[derive(Debug, Clone)]
+pub struct Dettato {
+    pub reperto: Reperto,
+    pub dubbi: Vec<String>,
+}
+
+/// Come leggere le righe dettate: i siti gia noti e quello da usare se
+/// la riga non ne nomina uno.
+#[derive(Debug, Clone)]
+pub struct Dettatura {
+    siti: Vec<String>,
+    sito_predefinito: String,
+}
+
+impl Dettatura {
+    pub fn nuova(sito_predefinito: &str) -> Self {
+        Dettatura { siti: Vec::new(), sito_predefinito: sito_predefinito.to_string() }
+    }
+
+    /// I siti con cui confrontare i pezzi dettati: "Savignano" diventa
+    /// "Savignano sul Panaro" se e l'unico sito che inizia cosi.
+    pub fn con_siti<S: Into<String>>(mut self, siti: impl IntoIterator<Item = S>) -> Self {
+        self.siti = siti.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// I siti noti che iniziano con il pezzo dettato
+    fn siti_per(&self, pezzo: &str) -> Vec<&str> {
+        let pezzo = pezzo.to_lowercase();
+        self.siti.iter().filter(|s| s.to_lowercase().starts_with(&pezzo)).map(String::as_str).collect()
+    }
+
+    /// La bozza di una riga dettata.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::dettatura::Dettatura;
+    /// use rust_tutorial::catalogo::modelli::Materiale;
+    ///
+    /// let dettatura = Dettatura::nuova("non indicato").con_siti(["Savignano sul Panaro", "Frattesina"]);
+    /// let dettato = dettatura.leggi("ascia, bronzo, Savignano, peso trecentocinquanta grammi, integra");
+    /// assert_eq!(dettato.reperto.nome, "ascia");
+    /// assert_eq!(dettato.reperto.materiale(), Some(&Materiale::Bronzo));
+    /// assert_eq!(dettato.reperto.sito, "Savignano sul Panaro");
+    /// assert_eq!(dettato.reperto.misurazioni.peso_grammi, Some(350.0));
+    /// assert!(dettato.dubbi.is_empty());
+    /// ```
+    pub fn leggi(&self, riga: &str) -> Dettato {
+        let riga = riga.trim().trim_end_matches('.');
+        let mut pezzi = riga.split(';').flat_map(|p| p.split(", ")).map(str::trim).filter(|p| !p.is_empty());
+        let nome = pezzi.next().unwrap_or("reperto dettato").to_string();
+
+        let mut dubbi = Vec::new();
+        let mut materiale = None;
+        let mut conservazione = None;
+        let mut sito: Option<String> = None;
+        let mut identificativo = None;
+        let mut misurazioni = Misurazioni::nuove();
+        let mut non_capiti = Vec::new();
+        for pezzo in pezzi {
+            let minuscolo = pezzo.to_lowercase();
+            let parole: Vec<&str> = minuscolo.split_whitespace().collect();
+            let siti = self.siti_per(pezzo);
+
+            if let Some(campo) = misura(parole[0]) {
+                if let Some(valore) = self.misura(campo, &parole[1..], pezzo, &mut dubbi) {
+                    *campo_di(&mut misurazioni, campo) = Some(valore);
+                }
+            } else if matches!(parole[0], "inventario" | "numero") && parole.len() > 1 {
+                let resto = parole[1..].join(" ");
+                identificativo = Some(numero(&resto).map_or(resto, |n| n.to_string()));
+            } else if !siti.is_empty() {
+                if siti.len() > 1 {
+                    dubbi.push(format!("sito: '{}' puo essere {}", pezzo, siti.join(" o ")));
+                }
+                sito = Some(if siti.len() == 1 { siti[0].to_string() } else { pezzo.to_string() });
+            } else if let Some(m) = Materiale::riconosci(pezzo).filter(|_| materiale.is_none()) {
+                materiale = Some(m);
+            } else if let Some(c) = Conservazione::riconosci(pezzo).filter(|_| conservazione.is_none()) {
+                conservazione = Some(c);
+            } else if sito.is_none() && numero(pezzo).is_none() {
+                if !self.siti.is_empty() {
+                    dubbi.push(format!("sito: '{}' non e tra i siti noti", pezzo));
+                }
+                sito = Some(pezzo.to_string());
+            } else {
+                dubbi.push(format!("non capito: '{}'", pezzo));
+                non_capiti.push(pezzo);
+            }
+        }
+
+        let materiale = materiale.unwrap_or_else(|| {
+            dubbi.push("materiale: non detto".to_string());
+            Materiale::Altro("non indicato".to_string())
+        });
+        let conservazione = conservazione.unwrap_or_else(|| {
+            dubbi.push("conservazione: non detta, assunto Discreto".to_string());
+            Conservazione::Discreto
+        });
+        let sito = sito.unwrap_or_else(|| {
+            dubbi.push(format!("sito: non detto, usato '{}'", self.sito_predefinito));
+            self.sito_predefinito.clone()
+        });
+
+        let mut reperto = Reperto::nuovo(&nome, materiale, Periodo::Sconosciuto, conservazione, &sito)
+            .con_misurazioni(misurazioni);
+        reperto.identificativo = identificativo;
+        reperto.descrizione = non_capiti.join("; ");
+        reperto.note.push(Nota::from(
+            if dubbi.is_empty() {
+                format!("{}: da verificare", NOTA_DETTATURA)
+            } else {
+                format!("{}, da verificare: {}", NOTA_DETTATURA, dubbi.join("; "))
+            }
+            .as_str(),
+        ));
+        reperto.note.push(Nota::from(format!("Dettato: {}", riga).as_str()));
+        Dettato { reperto, dubbi }
+    }
+
+    /// Il valore dopo la parola della misura, nell'unita del campo
+    fn misura(&self, campo: &str, parole: &[&str], pezzo: &str, dubbi: &mut Vec<String>) -> Option<f64> {
+        let (campo, dimensione) = unita::campo(campo).expect("le misure sono campi del reperto");
+        let mut simbolo = None;
+        let mut numeriche = Vec::new();
+        for parola in parole {
+            match (unita(parola), *parola) {
+                (Some(s), _) if simbolo.is_none() => simbolo = Some(s),
+                (_, "circa") => dubbi.push(format!("{}: detto 'circa'", campo)),
+                (_, "di") => {}
+                _ => numeriche.push(*parola),
+            }
+        }
+        let Some(valore) = numero(&numeriche.join(" ")) else {
+            dubbi.push(format!("{}: numero non capito in '{}'", campo, pezzo));
+            return None;
+        };
+        if simbolo.is_none() {
+            let intesi = if dimensione == unita::Dimensione::Massa { "grammi" } else { "centimetri" };
+            dubbi.push(format!("{}: senza unita, intesi {}", campo, intesi));
+        }
+        match (Quantita { valore, unita: simbolo }).nell_unita_di(campo, dimensione) {
+            Ok(valore) => Some(valore),
+            Err(e) => {
+                dubbi.push(e.to_string());
+                None
+            }
+        }
+    }
+}
+
+fn campo_di<'a>(misurazioni: &'a mut Misurazioni, campo: &str) -> &'a mut Option<f64> {
+    match campo {
+        "peso_grammi" => &mut misurazioni.peso_grammi,
+        "lunghezza_cm" => &mut misurazioni.lunghezza_cm,
+        "larghezza_cm" => &mut misurazioni.larghezza_cm,
+        _ => &mut misurazioni.altezza_cm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn righe_dettate_con_dubbi() {
+        let dettatura = Dettatura::nuova("Frattesina").con_siti(["Savignano Irpino", "Savignano sul Panaro"]);
+
+        let spillone = dettatura.leggi("spillone; lungo dodici virgola cinque centimetri; frammentario; peso circa venti");
+        assert_eq!(spillone.reperto.misurazioni.lunghezza_cm, Some(12.5));
+        assert_eq!(spillone.reperto.misurazioni.peso_grammi, Some(20.0));
+        assert_eq!(spillone.reperto.conservazione, Conservazione::Frammentario);
+        assert_eq!(
+            spillone.dubbi,
+            [
+                "peso_grammi: detto 'circa'",
+                "peso_grammi: senza unita, intesi grammi",
+                "materiale: non detto",
+                "sito: non detto, usato 'Frattesina'",
+            ]
+        );
+
+        let ascia = dettatura.leggi("ascia, Savignano, bronzo, inventario quattromilacinquecentododici, peso un chilo e mezzo.");
+        assert_eq!(ascia.reperto.sito, "Savignano");
+        assert_eq!(ascia.reperto.identificativo.as_deref(), Some("4512"));
+        assert_eq!(ascia.reperto.misurazioni.peso_grammi, Some(1500.0));
+        assert_eq!(ascia.dubbi[0], "sito: 'Savignano' puo essere Savignano Irpino o Savignano sul Panaro");
+        assert!(ascia.reperto.note[0].testo.starts_with(NOTA_DETTATURA));
+
+        // Numeri troppo grandi o con l'accento finale
+        assert_eq!(numero("cento cento cento cento cento"), None);
+        assert_eq!(numero("mille mille mille mille mille mille"), Some(6000.0));
+        assert_eq!(numero("trentatré virgola tré"), Some(33.3));
+        let enorme = dettatura.leggi("ago, bronzo, buono, peso cento cento cento cento cento grammi");
+        assert_eq!(enorme.reperto.misurazioni.peso_grammi, None);
+        assert_eq!(enorme.dubbi[0], "peso_grammi: numero non capito in 'peso cento cento cento cento cento grammi'");
+
+        let strano = dettatura.leggi("fibula, bronzo, buona, Bologna, quarantadue, lunga tre chili");
+        assert_eq!(strano.reperto.sito, "Bologna");
+        assert_eq!(strano.reperto.descrizione, "quarantadue");
+        assert_eq!(
+            strano.dubbi,
+            [
+                "sito: 'Bologna' non e tra i siti noti",
+                "non capito: 'quarantadue'",
+                "'lunghezza_cm' e una lunghezza, '3kg' e una massa",
+            ]
+        );
+    }
+}
@@ -0,0 +1,403 @@
+// ============================================================================
+// MODULO: DOSSIER DI SITO
+// ============================================================================
+// Prima di ogni riunione la direzione chiede "tutto su Savignano": un
+// documento unico con i reperti di un sito, da leggere senza il programma.
+// Il dossier ha sempre le stesse parti:
+//
+//   Riepilogo                  reperti, stati, materiali, campagne
+//   Punti di rinvenimento      la mappa di `geo::mappa_ascii` (feature geo)
+//   Distribuzione per periodo  un istogramma a barre di testo
+//   Catalogo                   la scheda di ogni reperto, in ordine di id,
+//                              con la provenienza e le miniature medie
+//                              delle sue foto
+//   Bibliografia               i riferimenti di tutti i reperti, senza doppioni
+//
+// Si scrive in Markdown, che si legge cosi com'e e si converte in quello
+// che si vuole, oppure in PDF: le stesse righe in Courier, su pagine A4,
+// con il PDF scritto a mano di `pdf`. Mappa e istogramma sono testo a
+// spaziatura fissa, e restano allineati in tutti e due; le immagini delle
+// schede nel PDF diventano una riga con il nome del file.
+// ============================================================================
+
+//! Dossier di un sito in Markdown o PDF: mappa, periodi, schede e bibliografia.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[cfg(feature = "geo")]
+use super::geo::{self, OpzioniMappa, Riquadro};
+use super::modelli::{Opera, Periodo, Reperto, RiferimentoBibliografico};
+use super::pdf::{self, A4_MM, PUNTI_PER_MM};
+
+/// La barra piu lunga dell'istogramma dei periodi, in caratteri
+const BARRA_MASSIMA: usize = 40;
+
+/// I reperti di un sito, pronti da esportare. Si ottiene da
+/// `Inventario::esporta_dossier`.
+#[derive(Debug, Clone)]
+pub struct Dossier<'a> {
+    pub sito: String,
+    /// In ordine di id
+    pub reperti: Vec<&'a Reperto>,
+}
+
+impl<'a> Dossier<'a> {
+    pub fn nuovo(sito: &str, reperti: impl IntoIterator<Item = &'a Reperto>) -> Self {
+        let mut reperti: Vec<&Reperto> = reperti.into_iter().collect();
+        reperti.sort_by_key(|r| r.id);
+        Dossier { sito: sito.to_string(), reperti }
+    }
+
+    /// Il dossier in Markdown.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::dossier::Dossier;
+    /// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+    ///
+    /// let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+    /// let markdown = Dossier::nuovo("Frattesina", [&ascia]).markdown();
+    /// assert!(markdown.starts_with("# Dossier di sito: Frattesina\n"));
+    /// assert!(markdown.contains("\n### #0 Ascia\n"));
+    /// assert!(markdown.contains("\n## Punti di rinvenimento\n"));
+    /// ```
+    pub fn markdown(&self) -> String {
+        let mut md = format!("# Dossier di sito: {}\n\n", self.sito);
+        md.push_str(&self.riepilogo());
+        md.push_str("\n## Punti di rinvenimento\n\n");
+        md.push_str(&self.mappa());
+        md.push_str("\n## Distribuzione per periodo\n\n");
+        md.push_str(&self.periodi());
+        md.push_str("\n## Catalogo\n");
+        for r in &self.reperti {
+            md.push_str(&scheda(r));
+        }
+        md.push_str("\n## Bibliografia\n\n");
+        md.push_str(&self.bibliografia());
+        md
+    }
+
+    fn riepilogo(&self) -> String {
+        let conteggi = |chiavi: Vec<String>| {
+            let mut per_chiave: BTreeMap<String, usize> = BTreeMap::new();
+            for chiave in chiavi {
+                *per_chiave.entry(chiave).or_default() += 1;
+            }
+            let mut voci: Vec<(String, usize)> = per_chiave.into_iter().collect();
+            voci.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+            voci.iter().map(|(k, n)| format!("{} {}", k, n)).collect::<Vec<_>>().join(", ")
+        };
+        let materiali = self.reperti.iter().map(|r| r.materiale().map_or_else(|| "?".to_string(), |m| m.to_string()));
+        let stati = self.reperti.iter().map(|r| r.stato.to_string());
+        let mut campagne: Vec<&str> = self.reperti.iter().filter_map(|r| r.campagna_scavo.as_deref()).collect();
+        campagne.sort();
+        campagne.dedup();
+
+        let mut testo = format!("- Reperti: {}\n", self.reperti.len());
+        testo.push_str(&format!("- Stato della catalogazione: {}\n", conteggi(stati.collect())));
+        testo.push_str(&format!("- Materiali: {}\n", conteggi(materiali.collect())));
+        if !campagne.is_empty() {
+            testo.push_str(&format!("- Campagne di scavo: {}\n", campagne.join(", ")));
+        }
+        testo
+    }
+
+    #[cfg(feature = "geo")]
+    fn mappa(&self) -> String {
+        let Some(riquadro) = Riquadro::da_reperti(&self.reperti, 0.1) else {
+            return "Nessun reperto ha coordinate.\n".to_string();
+        };
+        let mut testo = format!("```text\n{}\n```\n", geo::mappa_ascii(&self.reperti, &riquadro, &OpzioniMappa::default()));
+        let senza = self.reperti.iter().filter(|r| r.coordinate.is_none()).count();
+        if senza > 0 {
+            testo.push_str(&format!("\n{} reperti senza coordinate non sono sulla mappa.\n", senza));
+        }
+        testo
+    }
+
+    #[cfg(not(feature = "geo"))]
+    fn mappa(&self) -> String {
+        "Mappa non disponibile: serve la feature geo.\n".to_string()
+    }
+
+    fn periodi(&self) -> String {
+        let mut per_periodo: BTreeMap<&Periodo, usize> = BTreeMap::new();
+        for r in &self.reperti {
+            *per_periodo.entry(&r.periodo).or_default() += 1;
+        }
+        let massimo = per_periodo.values().copied().max().unwrap_or(1);
+        let colonna = per_periodo.keys().map(|p| p.to_string().len()).max().unwrap_or(0);
+        let mut testo = String::from("```text\n");
+        for (periodo, n) in per_periodo {
+            let barra = "#".repeat((n * BARRA_MASSIMA).div_ceil(massimo));
+            testo.push_str(&format!("{:<w$}  {} {}\n", periodo.to_string(), barra, n, w = colonna));
+        }
+        testo.push_str("```\n");
+        testo
+    }
+
+    fn bibliografia(&self) -> String {
+        // Lo stesso testo citato da piu reperti compare una volta sola
+        let mut voci: Vec<String> = self.reperti.iter().flat_map(|r| &r.bibliografia).map(voce_bibliografica).collect();
+        voci.sort();
+        voci.dedup();
+        if voci.is_empty() {
+            return "Nessun riferimento bibliografico.\n".to_string();
+        }
+        voci.iter().map(|v| format!("- {}\n", v)).collect()
+    }
+
+    /// Il dossier in PDF: le righe del Markdown in Courier, i titoli in
+    /// grassetto, senza i recinti ``` dei blocchi di testo.
+    pub fn pdf(&self) -> Vec<u8> {
+        let (margine, corpo) = (15.0, 8.5);
+        let interlinea = corpo * 1.3 / PUNTI_PER_MM;
+        // Courier e larga esattamente 0.6 volte il corpo
+        let colonne = ((A4_MM.0 - 2.0 * margine) * PUNTI_PER_MM / (corpo * 0.6)) as usize;
+        let righe_per_pagina = ((A4_MM.1 - 2.0 * margine) / interlinea) as usize;
+
+        let mut righe: Vec<(&str, String)> = Vec::new();
+        let markdown = self.markdown();
+        let mut in_blocco = false;
+        for riga in markdown.lines() {
+            if riga.starts_with("```") {
+                in_blocco = !in_blocco;
+                continue;
+            }
+            let font = if !in_blocco && riga.starts_with('#') { "F4" } else { "F3" };
+            let foto = immagine(riga).filter(|_| !in_blocco).map(|nome| format!("[foto: {}]", nome));
+            let riga = match &foto {
+                Some(foto) => foto.as_str(),
+                None if in_blocco => riga,
+                None => riga.trim_start_matches('#').trim_start(),
+            };
+            // Le righe lunghe vanno a capo; quelle dei blocchi si tagliano
+            if in_blocco || riga.chars().count() <= colonne {
+                righe.push((font, riga.to_string()));
+            } else {
+                righe.extend(a_capo(riga, colonne).into_iter().map(|r| (font, r)));
+            }
+        }
+
+        let pagine: Vec<String> = righe
+            .chunks(righe_per_pagina.max(1))
+            .map(|pagina| {
+                let mut contenuto = String::new();
+                for (i, (font, testo)) in pagina.iter().enumerate() {
+                    let y = (A4_MM.1 - margine - (i + 1) as f64 * interlinea) * PUNTI_PER_MM;
+                    contenuto.push_str(&format!(
+                        "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                        font,
+                        corpo,
+                        margine * PUNTI_PER_MM,
+                        y,
+                        pdf::stringa(testo, colonne)
+                    ));
+                }
+                contenuto
+            })
+            .collect();
+        pdf::documento(&pagine)
+    }
+}
+
+/// La scheda di un reperto: solo i campi compilati
+fn scheda(r: &Reperto) -> String {
+    let titolo = match &r.identificativo {
+        Some(codice) => format!("#{} {} - {}", r.id, codice, r.nome),
+        None => format!("#{} {}", r.id, r.nome),
+    };
+    let mut voci: Vec<(&str, String)> = Vec::new();
+    let materiale = match r.componenti.as_slice() {
+        [unico] => unico.materiale.to_string(),
+        componenti => componenti.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("; "),
+    };
+    voci.push(("Materiale", materiale));
+    voci.push(("Periodo", r.periodo.to_string()));
+    voci.push(("Conservazione", r.conservazione.to_string()));
+    voci.push(("Stato", r.stato.to_string()));
+    if let Some(tipo) = &r.tipo {
+        voci.push(("Tipo", tipo.clone()));
+    }
+    if let Some(classe) = &r.classe_funzionale {
+        voci.push(("Classe funzionale", classe.to_string()));
+    }
+    if r.misurazioni.to_string() != "N/D" {
+        voci.push(("Misure", r.misurazioni.to_string()));
+    }
+    let rinvenimento: Vec<String> = [
+        r.posizione_scavo.as_ref().map(|p| p.to_string()),
+        r.rinvenuto_il.map(|d| d.format("%d/%m/%Y").to_string()),
+        r.campagna_scavo.as_ref().map(|c| format!("campagna {}", c)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !rinvenimento.is_empty() {
+        voci.push(("Rinvenimento", rinvenimento.join(", ")));
+    }
+    if let Some(c) = &r.coordinate {
+        voci.push(("Coordinate", c.to_string()));
+    }
+    if let Some(c) = &r.collocazione {
+        voci.push(("Collocazione", c.clone()));
+    }
+    if !r.decorazioni.is_empty() {
+        voci.push(("Decorazioni", r.decorazioni.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("; ")));
+    }
+    if !r.provenienza.is_empty() {
+        voci.push(("Provenienza", r.provenienza.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")));
+    }
+    if !r.bibliografia.is_empty() {
+        let citazioni = r.bibliografia.iter().map(|b| match &b.punto {
+            Some(punto) => format!("{}, {}", b.citazione, punto),
+            None => b.citazione.clone(),
+        });
+        voci.push(("Bibliografia", citazioni.collect::<Vec<_>>().join("; ")));
+    }
+
+    let mut testo = format!("\n### {}\n\n", titolo);
+    for (etichetta, valore) in voci {
+        testo.push_str(&format!("- {}: {}\n", etichetta, valore));
+    }
+    if !r.descrizione.is_empty() {
+        testo.push_str(&format!("\n{}\n", r.descrizione));
+    }
+    // Le foto senza miniature si sistemano con `media miniature`
+    let foto: Vec<String> = r
+        .allegati
+        .iter()
+        .filter_map(|a| {
+            let nome = Path::new(&a.percorso).file_name()?.to_string_lossy();
+            Some(format!("![{}]({})", nome, a.miniature.as_ref()?.media))
+        })
+        .collect();
+    if !foto.is_empty() {
+        testo.push_str(&format!("\n{}\n", foto.join("\n")));
+    }
+    testo
+}
+
+/// Il nome di un'immagine Markdown `![nome](percorso)`, se la riga e tutta quella
+fn immagine(riga: &str) -> Option<&str> {
+    let (nome, resto) = riga.strip_prefix("![")?.split_once("](")?;
+    resto.ends_with(')').then_some(nome)
+}
+
+/// "Carancini, G. (1984). Le asce nell'Italia continentale. PBF IX, 12. doi:..."
+/// se il DOI e stato risolto, altrimenti la citazione com'e stata scritta
+fn voce_bibliografica(riferimento: &RiferimentoBibliografico) -> String {
+    let Some(Opera { doi, titolo, autori, anno, contenitore, volume, pagine, .. }) = &riferimento.opera else {
+        return match &riferimento.doi {
+            Some(doi) => format!("{}. doi:{}", riferimento.citazione, doi),
+            None => riferimento.citazione.clone(),
+        };
+    };
+    let mut voce = autori.join("; ");
+    if let Some(anno) = anno {
+        voce.push_str(&format!(" ({})", anno));
+    }
+    voce.push_str(&format!(". {}.", titolo));
+    let dove: Vec<&str> = [contenitore, volume, pagine].into_iter().flatten().map(String::as_str).collect();
+    if !dove.is_empty() {
+        voce.push_str(&format!(" {}.", dove.join(", ")));
+    }
+    voce.push_str(&format!(" doi:{}", doi));
+    voce
+}
+
+/// Divide una riga sugli spazi in righe di al massimo `colonne` caratteri
+fn a_capo(riga: &str, colonne: usize) -> Vec<String> {
+    let mut righe = vec![String::new()];
+    for parola in riga.split(' ') {
+        let ultima = righe.last_mut().expect("almeno una riga");
+        if !ultima.is_empty() && ultima.chars().count() + 1 + parola.chars().count() > colonne {
+            righe.push(format!("  {}", parola));
+        } else {
+            if !ultima.is_empty() {
+                ultima.push(' ');
+            }
+            ultima.push_str(parola);
+        }
+    }
+    righe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{
+        Allegato, Conservazione, Coordinate, EventoProvenienza, Materiale, Miniature, TipoAllegato, TipoEventoProvenienza,
+    };
+
+    #[test]
+    fn dossier_completo() {
+        let reperto = |id, nome: &str, periodo| {
+            let mut r = Reperto::nuovo(nome, Materiale::Bronzo, periodo, Conservazione::Buono, "Savignano sul Panaro");
+            r.id = id;
+            r
+        };
+        let mut ascia = reperto(2, "Ascia", Periodo::BronzoMedio);
+        ascia.coordinate = Some(Coordinate { latitudine: 44.48, longitudine: 11.03 });
+        ascia.bibliografia.push(RiferimentoBibliografico {
+            citazione: "Carancini 1984, n. 312".to_string(),
+            doi: None,
+            punto: Some("tav. 4".to_string()),
+            opera: None,
+        });
+        let mut spillone = reperto(1, "Spillone", Periodo::BronzoMedio);
+        spillone.bibliografia = ascia.bibliografia.clone();
+        let pugnale = reperto(3, "Pugnale", Periodo::BronzoRecente);
+        for (tipo, giorno, detentore) in
+            [(TipoEventoProvenienza::Scavo, 2, "Soprintendenza"), (TipoEventoProvenienza::IngressoMuseo, 9, "Museo civico")]
+        {
+            ascia.provenienza.push(EventoProvenienza {
+                tipo,
+                data: chrono::NaiveDate::from_ymd_opt(2023, 10, giorno).unwrap(),
+                detentore: detentore.to_string(),
+                documento: None,
+            });
+        }
+        for (nome, miniature) in [("ascia.jpg", true), ("ascia_retro.jpg", false)] {
+            ascia.allegati.push(Allegato {
+                percorso: format!("foto/{}", nome),
+                tipo: TipoAllegato::Immagine,
+                impronta_sha256: String::new(),
+                dimensione_byte: 0,
+                miniature: miniature.then(|| Miniature {
+                    piccola: "foto/ascia.piccola.jpg".into(),
+                    media: "foto/ascia.media.jpg".into(),
+                }),
+                diritti: None,
+                autore: None,
+            });
+        }
+
+        let dossier = Dossier::nuovo("Savignano sul Panaro", [&ascia, &spillone, &pugnale]);
+        let md = dossier.markdown();
+        assert!(md.find("### #1 Spillone").unwrap() < md.find("### #2 Ascia").unwrap());
+        assert!(md.contains("- Bibliografia: Carancini 1984, n. 312, tav. 4\n"));
+        assert!(md.contains(
+            "- Provenienza: 02/10/2023 Scavo -> Soprintendenza; 09/10/2023 Ingresso in museo -> Museo civico\n"
+        ));
+        assert!(md.ends_with("## Bibliografia\n\n- Carancini 1984, n. 312\n"));
+        #[cfg(feature = "geo")]
+        assert!(md.contains("2 reperti senza coordinate non sono sulla mappa."));
+        // Solo la foto con le miniature entra nella scheda
+        assert!(md.contains("\n![ascia.jpg](foto/ascia.media.jpg)\n"));
+        assert!(!md.contains("ascia_retro"));
+        // Il periodo piu frequente ha la barra piu lunga
+        assert!(md.contains(&format!("{} 2\n", "#".repeat(BARRA_MASSIMA))));
+        assert!(md.contains(&format!("{} 1\n", "#".repeat(BARRA_MASSIMA / 2))));
+
+        let pdf = String::from_utf8(dossier.pdf()).unwrap();
+        assert!(pdf.contains("/F4 8.5 Tf") && pdf.contains("(Dossier di sito: Savignano sul Panaro) Tj"));
+        assert!(!pdf.contains("```"));
+        assert!(pdf.contains("([foto: ascia.jpg]) Tj"));
+    }
+
+    #[test]
+    fn righe_lunghe_a_capo() {
+        assert_eq!(a_capo("uno due tre quattro", 8), ["uno due", "  tre", "  quattro"]);
+    }
+}
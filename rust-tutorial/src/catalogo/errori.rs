@@ -0,0 +1,160 @@
+// ============================================================================
+// MODULO: ERRORI
+// ============================================================================
+// Gli errori del catalogo. Quelli che nascono da un altro errore (JSON,
+// I/O, formule dei campi calcolati) lo conservano: `source()` lo
+// restituisce, e `Box<dyn Error>` o anyhow mostrano tutta la catena.
+// Il messaggio non ripete la causa, altrimenti nella catena comparirebbe
+// due volte:
+//
+//   Errore serializzazione
+//   causa: EOF while parsing a list at line 1 column 5
+//
+// Le conversioni dagli errori di database, rete, immagini e regole OCR
+// esistono solo con le feature che portano quei crate (db, net, images,
+// ocr): senza, la libreria non li compila nemmeno. Per questo quegli
+// errori diventano un messaggio; una variante che li contenesse
+// esisterebbe solo con la feature, e un `match` sull'enum cambierebbe
+// secondo le feature attive. Le operazioni compilate senza la loro
+// feature (cifrare senza `crypto`, SQLite senza `db`...) rispondono con
+// `FeatureMancante`.
+// ============================================================================
+
+//! Errori delle operazioni sul catalogo.
+
+use thiserror::Error;
+
+use super::calcolati::ErroreCampi;
+#[cfg(feature = "ocr")]
+use super::ocr::ErroreRegole;
+use super::modelli::{ErroreReperto, StatoCatalogazione};
+
+/// Come `ErroreInventario` di src/inventario.rs, `thiserror` genera
+/// `Display` e `Error::source`: il messaggio di una variante che conserva
+/// la causa non la ripete, la aggiunge chi stampa la catena.
+///
+/// ```
+/// use std::error::Error;
+/// use rust_tutorial::catalogo::errori::ErroreInventario;
+///
+/// let json = serde_json::from_str::<Vec<u32>>("[1, 2").unwrap_err();
+/// let errore: Box<dyn Error> = Box::new(ErroreInventario::from(json));
+/// assert_eq!(errore.to_string(), "Errore serializzazione");
+/// assert!(errore.source().unwrap().to_string().starts_with("EOF"));
+/// ```
+#[derive(Debug, Error)]
+pub enum ErroreInventario {
+    #[error("Reperto con ID {0} non trovato")]
+    RepertoNonTrovato(u32),
+    #[error("Il nome del reperto non puo essere vuoto")]
+    NomeVuoto,
+    #[error("Esiste gia un reperto con ID {0}")]
+    IdDuplicato(u32),
+    #[error("Dati non validi: {0}")]
+    DatiNonValidi(String),
+    #[error("Transizione non ammessa: da '{da}' a '{a}'")]
+    TransizioneNonAmmessa { da: StatoCatalogazione, a: StatoCatalogazione },
+    #[error("Permesso negato: {0}")]
+    PermessoNegato(String),
+    #[error("Errore serializzazione")]
+    SerializzazioneErrore(#[from] serde_json::Error),
+    #[error("Impossibile decifrare: passphrase errata o file alterato")]
+    DecifraturaFallita,
+    #[error("Il file dati {percorso} e corrotto (impronta non corrispondente); {}", ripristino(.backup))]
+    FileCorrotto { percorso: String, backup: Option<String> },
+    #[error("Errore database: {0}")]
+    DatabaseErrore(String),
+    #[error("Schema del database non riconosciuto: {0}")]
+    SchemaNonRiconosciuto(String),
+    #[error("Il reperto {id} e stato modificato da un altro utente (versione letta {letta}, attuale {attuale})")]
+    Conflitto { id: u32, letta: u64, attuale: u64 },
+    /// La rimozione e stata registrata come richiesta da approvare
+    #[error("La rimozione del reperto {id} deve essere approvata da un responsabile (richiesta n. {richiesta})")]
+    RimozioneInAttesa { id: u32, richiesta: u32 },
+    #[error("Invio della notifica fallito: {0}")]
+    NotificaFallita(String),
+    #[error("Errore del servizio remoto: {0}")]
+    ServizioRemoto(String),
+    #[error("Errore di I/O")]
+    IoErrore(#[from] std::io::Error),
+    #[error("Errore immagine: {0}")]
+    ImmagineErrore(String),
+    /// L'operazione richiede una feature non attiva in questa compilazione
+    #[error("Per {operazione} serve la feature `{feature}`")]
+    FeatureMancante { feature: &'static str, operazione: &'static str },
+    /// Una formula o una condizione sui campi calcolati non valida
+    #[error("Campi calcolati non validi")]
+    CampiCalcolati(#[from] ErroreCampi),
+}
+
+fn ripristino(backup: &Option<String>) -> String {
+    match backup {
+        Some(b) => format!("e possibile ripristinare il backup {}", b),
+        None => "nessun backup disponibile".to_string(),
+    }
+}
+
+impl From<ErroreReperto> for ErroreInventario {
+    fn from(errore: ErroreReperto) -> Self {
+        match errore {
+            ErroreReperto::NomeVuoto => ErroreInventario::NomeVuoto,
+            ErroreReperto::DatiNonValidi(motivo) => ErroreInventario::DatiNonValidi(motivo),
+        }
+    }
+}
+
+#[cfg(feature = "ocr")]
+impl From<ErroreRegole> for ErroreInventario {
+    fn from(e: ErroreRegole) -> Self {
+        ErroreInventario::DatiNonValidi(e.to_string())
+    }
+}
+
+#[cfg(feature = "db")]
+impl From<rusqlite::Error> for ErroreInventario {
+    fn from(e: rusqlite::Error) -> Self {
+        ErroreInventario::DatabaseErrore(e.to_string())
+    }
+}
+
+#[cfg(feature = "db")]
+impl From<sqlx::Error> for ErroreInventario {
+    fn from(e: sqlx::Error) -> Self {
+        ErroreInventario::DatabaseErrore(e.to_string())
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<ureq::Error> for ErroreInventario {
+    fn from(e: ureq::Error) -> Self {
+        ErroreInventario::ServizioRemoto(e.to_string())
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<lettre::address::AddressError> for ErroreInventario {
+    fn from(e: lettre::address::AddressError) -> Self {
+        ErroreInventario::NotificaFallita(format!("indirizzo non valido: {}", e))
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<lettre::error::Error> for ErroreInventario {
+    fn from(e: lettre::error::Error) -> Self {
+        ErroreInventario::NotificaFallita(e.to_string())
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<lettre::transport::smtp::Error> for ErroreInventario {
+    fn from(e: lettre::transport::smtp::Error) -> Self {
+        ErroreInventario::NotificaFallita(e.to_string())
+    }
+}
+
+#[cfg(feature = "images")]
+impl From<image::ImageError> for ErroreInventario {
+    fn from(e: image::ImageError) -> Self {
+        ErroreInventario::ImmagineErrore(e.to_string())
+    }
+}
@@ -0,0 +1,253 @@
+// ============================================================================
+// MODULO: ETICHETTE PER CASSE E CASSETTI
+// ============================================================================
+// In deposito si cerca la cassa, non il reperto: ogni collocazione ha
+// un'etichetta con il suo nome, un riassunto del contenuto e un QR che
+// elenca i reperti, da leggere con il telefono senza aprire la cassa.
+//
+//   +------------------------------------------+
+//   | Deposito A / Cassa 12         ########   |
+//   | 14 reperti (id 3-57)          ## QR ##   |
+//   | Bronzo 9, Ceramica 5          ########   |
+//   | Savignano sul Panaro, Frattesina         |
+//   +------------------------------------------+
+//
+// Le etichette si stampano su fogli A4 adesivi gia fustellati: un
+// `Modello` descrive il foglio (colonne, righe, misure e margini in mm)
+// e i piu comuni sono gia pronti (`Modello::predefiniti`). Il PDF si
+// scrive a mano (vedi `pdf`): poche righe di testo e quadrati pieni non
+// richiedono una libreria.
+// ============================================================================
+
+//! Fogli PDF di etichette con QR per le casse e i cassetti del deposito.
+
+// Il modulo intero esiste solo con la feature: e l'unico a usare qrcodegen
+#![cfg(feature = "labels")]
+
+use std::collections::BTreeMap;
+
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use super::modelli::Reperto;
+use super::pdf::{self, A4_MM, PUNTI_PER_MM};
+
+/// Un foglio di etichette adesive: la griglia e le sue misure in mm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modello {
+    pub nome: &'static str,
+    pub colonne: u32,
+    pub righe: u32,
+    pub larghezza: f64,
+    pub altezza: f64,
+    /// Distanza dal bordo superiore del foglio alla prima riga
+    pub margine_alto: f64,
+    /// Distanza dal bordo sinistro del foglio alla prima colonna
+    pub margine_sinistro: f64,
+    /// Da un'etichetta all'inizio della successiva, in orizzontale
+    pub passo_orizzontale: f64,
+    /// Da un'etichetta all'inizio della successiva, in verticale
+    pub passo_verticale: f64,
+}
+
+impl Modello {
+    /// I fogli A4 piu diffusi, con il codice Avery (gli equivalenti di
+    /// altre marche hanno le stesse misure).
+    pub fn predefiniti() -> [Modello; 4] {
+        [
+            Modello::avery("L7160", (3, 7), (63.5, 38.1), (15.15, 7.21), (66.04, 38.1)),
+            Modello::avery("L7163", (2, 7), (99.1, 38.1), (15.15, 4.67), (101.6, 38.1)),
+            Modello::avery("L7165", (2, 4), (99.1, 67.7), (13.07, 4.67), (101.6, 67.7)),
+            Modello::avery("L7166", (2, 3), (99.1, 93.1), (8.7, 4.67), (101.6, 93.1)),
+        ]
+    }
+
+    /// Come nelle schede dei produttori: griglia, etichetta, margini
+    /// (alto, sinistro) e passo (orizzontale, verticale)
+    fn avery(nome: &'static str, griglia: (u32, u32), misure: (f64, f64), margini: (f64, f64), passo: (f64, f64)) -> Modello {
+        Modello {
+            nome,
+            colonne: griglia.0,
+            righe: griglia.1,
+            larghezza: misure.0,
+            altezza: misure.1,
+            margine_alto: margini.0,
+            margine_sinistro: margini.1,
+            passo_orizzontale: passo.0,
+            passo_verticale: passo.1,
+        }
+    }
+
+    /// Modello predefinito per nome, senza distinguere maiuscole.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::etichette::Modello;
+    ///
+    /// let modello = Modello::per_nome("l7163").unwrap();
+    /// assert_eq!(modello.per_foglio(), 14);
+    /// assert!(Modello::per_nome("L9999").is_none());
+    /// ```
+    pub fn per_nome(nome: &str) -> Option<Modello> {
+        Modello::predefiniti().into_iter().find(|m| m.nome.eq_ignore_ascii_case(nome))
+    }
+
+    pub fn per_foglio(&self) -> usize {
+        (self.colonne * self.righe) as usize
+    }
+
+    /// Angolo in alto a sinistra della posizione `n` (da 0, per righe), in mm
+    /// dall'angolo in alto a sinistra del foglio
+    fn posizione(&self, n: usize) -> (f64, f64) {
+        let (riga, colonna) = (n as u32 / self.colonne, n as u32 % self.colonne);
+        (
+            self.margine_sinistro + colonna as f64 * self.passo_orizzontale,
+            self.margine_alto + riga as f64 * self.passo_verticale,
+        )
+    }
+}
+
+/// L'etichetta di una collocazione.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Etichetta {
+    pub collocazione: String,
+    /// Il riassunto del contenuto, una voce per riga
+    pub righe: Vec<String>,
+    /// Il testo del QR: la collocazione e i reperti che contiene
+    pub qr: String,
+}
+
+/// Un'etichetta per collocazione, in ordine di nome; i reperti senza
+/// collocazione non ne hanno.
+///
+/// ```
+/// use rust_tutorial::catalogo::etichette::etichette;
+/// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+///
+/// let mut ascia = Reperto::nuovo("ascia", Materiale::Bronzo, Periodo::Sconosciuto, Conservazione::Buono, "Frattesina");
+/// ascia.id = 7;
+/// ascia.collocazione = Some("Cassa 12".to_string());
+/// let etichette = etichette([&ascia]);
+/// assert_eq!(etichette[0].collocazione, "Cassa 12");
+/// assert_eq!(etichette[0].righe, ["1 reperto (id 7)", "Bronzo 1", "Frattesina"]);
+/// assert_eq!(etichette[0].qr, "Collocazione: Cassa 12\nReperti: 7");
+/// ```
+pub fn etichette<'a>(reperti: impl IntoIterator<Item = &'a Reperto>) -> Vec<Etichetta> {
+    let mut per_collocazione: BTreeMap<&str, Vec<&Reperto>> = BTreeMap::new();
+    for r in reperti {
+        if let Some(collocazione) = &r.collocazione {
+            per_collocazione.entry(collocazione).or_default().push(r);
+        }
+    }
+    per_collocazione.into_iter().map(|(collocazione, reperti)| etichetta(collocazione, &reperti)).collect()
+}
+
+fn etichetta(collocazione: &str, reperti: &[&Reperto]) -> Etichetta {
+    let ids: Vec<u32> = reperti.iter().map(|r| r.id).collect();
+    let (primo, ultimo) = (ids.iter().min().unwrap_or(&0), ids.iter().max().unwrap_or(&0));
+    let mut righe = vec![match reperti.len() {
+        1 => format!("1 reperto (id {})", primo),
+        n if primo == ultimo => format!("{} reperti (id {})", n, primo),
+        n => format!("{} reperti (id {}-{})", n, primo, ultimo),
+    }];
+
+    let mut materiali: BTreeMap<String, usize> = BTreeMap::new();
+    let mut siti: Vec<&str> = Vec::new();
+    for r in reperti {
+        if let Some(m) = r.materiale() {
+            *materiali.entry(m.to_string()).or_default() += 1;
+        }
+        if !siti.contains(&r.sito.as_str()) {
+            siti.push(&r.sito);
+        }
+    }
+    // I materiali piu numerosi per primi, a parita in ordine di nome
+    let mut materiali: Vec<(String, usize)> = materiali.into_iter().collect();
+    materiali.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    righe.push(materiali.iter().map(|(m, n)| format!("{} {}", m, n)).collect::<Vec<_>>().join(", "));
+    righe.push(siti.join(", "));
+
+    // Per gli inventari con identificativi propri il QR porta quelli
+    let elenco: Vec<String> =
+        reperti.iter().map(|r| r.identificativo.clone().unwrap_or_else(|| r.id.to_string())).collect();
+    Etichetta {
+        collocazione: collocazione.to_string(),
+        righe,
+        qr: format!("Collocazione: {}\nReperti: {}", collocazione, elenco.join(", ")),
+    }
+}
+
+/// I fogli di etichette in PDF, una pagina A4 per foglio. Le prime
+/// `saltate` posizioni restano vuote, per riusare un foglio gia
+/// iniziato.
+pub fn pdf(etichette: &[Etichetta], modello: &Modello, saltate: usize) -> Vec<u8> {
+    let posizioni: Vec<usize> = (saltate..saltate + etichette.len()).collect();
+    let fogli = posizioni.last().map_or(1, |ultima| ultima / modello.per_foglio() + 1);
+    let mut pagine = vec![String::new(); fogli];
+    for (etichetta, n) in etichette.iter().zip(posizioni) {
+        let (x, y) = modello.posizione(n % modello.per_foglio());
+        disegna(&mut pagine[n / modello.per_foglio()], etichetta, modello, x, y);
+    }
+    pdf::documento(&pagine)
+}
+
+/// I comandi di disegno di un'etichetta: il testo a sinistra, il QR a
+/// destra, alto quanto l'etichetta
+fn disegna(pagina: &mut String, etichetta: &Etichetta, modello: &Modello, x: f64, y: f64) {
+    let bordo = 3.0;
+    let lato_qr = (modello.altezza - 2.0 * bordo).min(modello.larghezza / 2.0);
+    let colonna_testo = modello.larghezza - lato_qr - 3.0 * bordo;
+    // Coordinate PDF: punti, con l'origine in basso a sinistra
+    let punto = |x_mm: f64, y_mm: f64| (x_mm * PUNTI_PER_MM, (A4_MM.1 - y_mm) * PUNTI_PER_MM);
+
+    let mut alto = y + bordo;
+    let mut righe = vec![("F2", 11.0, etichetta.collocazione.as_str())];
+    righe.extend(etichetta.righe.iter().map(|r| ("F1", 8.0, r.as_str())));
+    for (font, corpo, testo) in righe {
+        // Un punto tipografico e 0.3528 mm; l'interlinea e 1.25 volte il corpo
+        let interlinea = corpo * 1.25 * 25.4 / 72.0;
+        if alto + interlinea > y + modello.altezza - bordo {
+            break;
+        }
+        alto += interlinea;
+        let (px, py) = punto(x + bordo, alto - interlinea * 0.25);
+        // Helvetica e larga in media mezzo corpo
+        let caratteri = (colonna_testo * PUNTI_PER_MM / (corpo * 0.5)) as usize;
+        pagina.push_str(&format!("BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n", font, corpo, px, py, pdf::stringa(testo, caratteri)));
+    }
+
+    // Se i reperti sono troppi per un QR, basta la collocazione
+    let qr = QrCode::encode_text(&etichetta.qr, QrCodeEcc::Medium)
+        .or_else(|_| QrCode::encode_text(&etichetta.collocazione, QrCodeEcc::Medium));
+    if let Ok(qr) = qr {
+        let modulo = lato_qr / qr.size() as f64;
+        let (x_qr, y_qr) = (x + modello.larghezza - bordo - lato_qr, y + bordo);
+        for riga in 0..qr.size() {
+            for colonna in 0..qr.size() {
+                if qr.get_module(colonna, riga) {
+                    let (px, py) = punto(x_qr + colonna as f64 * modulo, y_qr + (riga + 1) as f64 * modulo);
+                    let lato = modulo * PUNTI_PER_MM;
+                    pagina.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re\n", px, py, lato, lato));
+                }
+            }
+        }
+        pagina.push_str("f\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fogli_e_posizioni() {
+        let modello = Modello::per_nome("L7165").unwrap();
+        assert_eq!(modello.posizione(0), (4.67, 13.07));
+        assert_eq!(modello.posizione(3), (4.67 + 101.6, 13.07 + 67.7));
+
+        let etichetta = Etichetta { collocazione: "Cassa (12)".to_string(), righe: vec![], qr: "Cassa 12".to_string() };
+        // 3 saltate + 6 etichette su fogli da 8: due pagine
+        let pdf = String::from_utf8(pdf(&vec![etichetta; 6], &modello, 3)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("/Count 2"));
+        assert_eq!(pdf.matches("(Cassa \\(12\\)) Tj").count(), 6);
+    }
+}
@@ -0,0 +1,175 @@
+// ============================================================================
+// MODULO: FUSIONE DELLE MODIFICHE CONCORRENTI
+// ============================================================================
+// Due catalogatori aprono la stessa scheda: chi salva per secondo trova
+// la versione cambiata. Invece di perdere una delle due modifiche si
+// confrontano tre stati, campo per campo (in JSON):
+//
+//   base (letta all'apertura)  ->  mia (la bozza)  /  loro (salvata da altri)
+//
+// Se ha cambiato una sola parte vince quella; se hanno cambiato entrambe
+// in modo diverso il campo e un conflitto, da risolvere a mano.
+// ============================================================================
+
+//! Fusione a tre vie delle schede modificate da piu persone.
+
+use super::errori::ErroreInventario;
+use super::inventario::Modifica;
+use super::modelli::Reperto;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+
+/// Campo modificato in modo diverso da entrambe le parti
+#[derive(Debug)]
+pub struct CampoInConflitto {
+    pub campo: String,
+    pub mia: Value,
+    pub loro: Value,
+}
+
+/// Risultato della fusione a tre vie tra la versione letta, la propria
+/// bozza e la versione salvata nel frattempo da altri
+pub struct Fusione {
+    loro: Reperto,
+    unito: Map<String, Value>,
+    pub conflitti: Vec<CampoInConflitto>,
+}
+
+fn come_mappa(reperto: &Reperto) -> Result<Map<String, Value>, ErroreInventario> {
+    match serde_json::to_value(reperto)? {
+        Value::Object(m) => Ok(m),
+        _ => Err(ErroreInventario::DatiNonValidi("reperto non serializzabile come oggetto".to_string())),
+    }
+}
+
+/// Un campo assente (Null) si toglie: i campi vuoti non vengono serializzati
+fn imposta(unito: &mut Map<String, Value>, campo: String, valore: Value) {
+    if valore.is_null() {
+        unito.remove(&campo);
+    } else {
+        unito.insert(campo, valore);
+    }
+}
+
+/// Campo per campo: se una sola parte lo ha cambiato vince quella,
+/// se lo hanno cambiato entrambe in modo diverso e un conflitto.
+/// Si guardano i campi di tutte e tre le versioni: un campo svuotato
+/// (e quindi assente) da una sola parte e comunque una modifica
+pub fn fondi(modifica: &Modifica, loro: &Reperto) -> Result<Fusione, ErroreInventario> {
+    let base = come_mappa(&modifica.originale)?;
+    let mia = come_mappa(&modifica.bozza)?;
+    let mut unito = come_mappa(loro)?;
+    let campi: BTreeSet<String> = base.keys().chain(mia.keys()).chain(unito.keys()).cloned().collect();
+    let mut conflitti = Vec::new();
+    for campo in campi {
+        if campo == "id" || campo == "versione" {
+            continue;
+        }
+        let valore_base = base.get(&campo).cloned().unwrap_or(Value::Null);
+        let valore_mio = mia.get(&campo).cloned().unwrap_or(Value::Null);
+        let valore_loro = unito.get(&campo).cloned().unwrap_or(Value::Null);
+        if valore_mio == valore_base || valore_mio == valore_loro {
+            continue;
+        }
+        if valore_loro == valore_base {
+            imposta(&mut unito, campo, valore_mio);
+        } else {
+            conflitti.push(CampoInConflitto { campo, mia: valore_mio, loro: valore_loro });
+        }
+    }
+    Ok(Fusione { loro: loro.clone(), unito, conflitti })
+}
+
+impl Fusione {
+    /// Applica le scelte sui conflitti e prepara una nuova modifica
+    /// basata sulla versione attuale, pronta per `conferma_modifica`
+    pub fn risolvi(mut self, tieni_mia: impl Fn(&CampoInConflitto) -> bool) -> Result<Modifica, ErroreInventario> {
+        for c in self.conflitti.drain(..) {
+            if tieni_mia(&c) {
+                imposta(&mut self.unito, c.campo, c.mia);
+            }
+        }
+        let bozza: Reperto = serde_json::from_value(Value::Object(self.unito))?;
+        Ok(Modifica { originale: self.loro, bozza })
+    }
+}
+
+/// Chiede per ogni campo in conflitto quale valore tenere
+pub fn chiedi<R: BufRead, W: Write>(
+    fusione: Fusione,
+    mut input: R,
+    output: &mut W,
+) -> Result<Modifica, ErroreInventario> {
+    writeln!(output, "Il reperto e stato modificato nel frattempo: {} campi in conflitto", fusione.conflitti.len())?;
+    let mut scelte = Vec::new();
+    for c in &fusione.conflitti {
+        writeln!(output, "\nCampo '{}'", c.campo)?;
+        writeln!(output, "  m) mia:  {}", c.mia)?;
+        writeln!(output, "  l) loro: {}", c.loro)?;
+        let tieni_mia = loop {
+            write!(output, "Quale tenere? [m/l] ")?;
+            output.flush()?;
+            let mut riga = String::new();
+            if input.read_line(&mut riga)? == 0 {
+                return Err(ErroreInventario::DatiNonValidi("fusione interrotta".to_string()));
+            }
+            match riga.trim() {
+                "m" => break true,
+                "l" => break false,
+                _ => writeln!(output, "Rispondere m oppure l")?,
+            }
+        };
+        scelte.push((c.campo.clone(), tieni_mia));
+    }
+    fusione.risolvi(|c| scelte.iter().any(|(campo, mia)| *campo == c.campo && *mia))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo, RiferimentoBibliografico};
+
+    fn ascia() -> Reperto {
+        let mut r = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        r.campagna_scavo = Some("FRT-2023".to_string());
+        r
+    }
+
+    #[test]
+    fn campi_svuotati_da_una_parte() {
+        let originale = ascia();
+        let mut bozza = originale.clone();
+        // `campagna_scavo` a None non compare nel JSON della bozza
+        bozza.campagna_scavo = None;
+        let mut loro = originale.clone();
+        loro.nome = "Ascia a margini rialzati".to_string();
+        loro.bibliografia.push(RiferimentoBibliografico {
+            citazione: "Carancini 1984, n. 312".to_string(),
+            doi: None,
+            punto: None,
+            opera: None,
+        });
+
+        let fusione = fondi(&Modifica { originale, bozza }, &loro).unwrap();
+        assert!(fusione.conflitti.is_empty());
+        let unito = fusione.risolvi(|_| true).unwrap().bozza;
+        assert_eq!(unito.campagna_scavo, None);
+        assert_eq!(unito.nome, "Ascia a margini rialzati");
+        assert_eq!(unito.bibliografia.len(), 1);
+    }
+
+    #[test]
+    fn svuotato_da_loro_e_cambiato_da_me() {
+        let originale = ascia();
+        let mut bozza = originale.clone();
+        bozza.campagna_scavo = Some("FRT-2024".to_string());
+        let mut loro = originale.clone();
+        loro.campagna_scavo = None;
+
+        let fusione = fondi(&Modifica { originale, bozza }, &loro).unwrap();
+        assert_eq!(fusione.conflitti.len(), 1);
+        assert_eq!((fusione.conflitti[0].campo.as_str(), &fusione.conflitti[0].loro), ("campagna_scavo", &Value::Null));
+        assert_eq!(fusione.risolvi(|_| false).unwrap().bozza.campagna_scavo, None);
+    }
+}
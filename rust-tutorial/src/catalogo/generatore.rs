@@ -0,0 +1,145 @@
+// ============================================================================
+// MODULO: GENERATORE DI INVENTARI SINTETICI
+// ============================================================================
+// Per le demo e le prove di carico servono migliaia di reperti
+// plausibili: il generatore li campiona da tipologie e siti configurati,
+// con un generatore pseudocasuale proprio. Lo stesso seme da lo stesso
+// inventario.
+// ============================================================================
+
+//! Inventari sintetici e deterministici per demo e prove di carico.
+
+use super::modelli::*;
+use super::visibilita::{splitmix64, unitario};
+
+/// Sito attorno al quale collocare i reperti generati
+pub struct SitoConfigurato {
+    pub nome: &'static str,
+    pub centro: Coordinate,
+    pub raggio_m: f64,
+}
+
+pub fn siti_predefiniti() -> Vec<SitoConfigurato> {
+    let sito = |nome, latitudine, longitudine, raggio_m| SitoConfigurato {
+        nome,
+        centro: Coordinate { latitudine, longitudine },
+        raggio_m,
+    };
+    vec![
+        sito("Savignano Irpino", 41.2247, 15.1788, 400.0),
+        sito("Pontecagnano", 40.6433, 14.8731, 800.0),
+        sito("Toppo Daguzzo", 41.0239, 15.7392, 300.0),
+        sito("Savignano sul Panaro", 44.4817, 11.0347, 500.0),
+    ]
+}
+
+/// Tipologia da cui campionare: pesi e lunghezze seguono una normale
+struct Modello {
+    nome: &'static str,
+    classe: ClasseFunzionale,
+    materiale: Materiale,
+    periodi: &'static [Periodo],
+    peso_g: (f64, f64),
+    lunghezza_cm: (f64, f64),
+}
+
+fn modelli() -> Vec<Modello> {
+    use Periodo::*;
+    vec![
+        Modello { nome: "Ascia a margini rialzati tipo Savignano", classe: ClasseFunzionale::Utensile, materiale: Materiale::Bronzo, periodi: &[BronzoMedio, BronzoRecente], peso_g: (360.0, 45.0), lunghezza_cm: (18.5, 1.2) },
+        Modello { nome: "Ascia ad alette", classe: ClasseFunzionale::Utensile, materiale: Materiale::Bronzo, periodi: &[BronzoRecente, BronzoFinale], peso_g: (420.0, 60.0), lunghezza_cm: (19.0, 1.5) },
+        Modello { nome: "Spada a lingua da presa", classe: ClasseFunzionale::Arma, materiale: Materiale::Bronzo, periodi: &[BronzoRecente, BronzoFinale], peso_g: (820.0, 90.0), lunghezza_cm: (62.0, 5.0) },
+        Modello { nome: "Pugnale triangolare", classe: ClasseFunzionale::Arma, materiale: Materiale::Bronzo, periodi: &[BronzoAntico, BronzoMedio], peso_g: (160.0, 30.0), lunghezza_cm: (17.0, 2.5) },
+        Modello { nome: "Punta di lancia a cannone", classe: ClasseFunzionale::Arma, materiale: Materiale::Bronzo, periodi: &[BronzoFinale, PrimaEtaFerro], peso_g: (150.0, 25.0), lunghezza_cm: (20.0, 3.0) },
+        Modello { nome: "Fibula ad arco semplice", classe: ClasseFunzionale::Ornamento, materiale: Materiale::Bronzo, periodi: &[BronzoFinale, PrimaEtaFerro], peso_g: (22.0, 6.0), lunghezza_cm: (7.5, 1.5) },
+        Modello { nome: "Vago in ambra", classe: ClasseFunzionale::Ornamento, materiale: Materiale::Altro("Ambra".to_string()), periodi: &[BronzoRecente, BronzoFinale], peso_g: (4.0, 1.5), lunghezza_cm: (1.8, 0.4) },
+        Modello { nome: "Lingotto a piccone", classe: ClasseFunzionale::Lingotto, materiale: Materiale::Bronzo, periodi: &[BronzoFinale], peso_g: (610.0, 120.0), lunghezza_cm: (16.0, 3.0) },
+        Modello { nome: "Coltello ad anima d'osso", classe: ClasseFunzionale::Utensile, materiale: Materiale::Osso, periodi: &[BronzoMedio, BronzoRecente], peso_g: (35.0, 8.0), lunghezza_cm: (12.0, 2.0) },
+    ]
+}
+
+/// Generatore deterministico: lo stesso seme produce lo stesso inventario
+pub struct Generatore {
+    stato: u64,
+}
+
+impl Generatore {
+    pub fn nuovo(seme: u64) -> Self {
+        Generatore { stato: seme }
+    }
+
+    fn prossimo(&mut self) -> u64 {
+        self.stato = splitmix64(self.stato);
+        self.stato
+    }
+
+    fn unitario(&mut self) -> f64 {
+        unitario(self.prossimo())
+    }
+
+    fn indice(&mut self, n: usize) -> usize {
+        (self.unitario() * n as f64) as usize % n
+    }
+
+    /// Normale con il metodo di Box-Muller, troncata a un minimo positivo
+    fn normale(&mut self, (media, deviazione): (f64, f64)) -> f64 {
+        let u1 = self.unitario().max(f64::MIN_POSITIVE);
+        let u2 = self.unitario();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        (media + z * deviazione).max(media * 0.1)
+    }
+
+    fn arrotonda(valore: f64) -> f64 {
+        (valore * 10.0).round() / 10.0
+    }
+
+    /// Genera `n` reperti plausibili distribuiti sui siti indicati
+    pub fn genera(&mut self, n: usize, siti: &[SitoConfigurato]) -> Vec<Reperto> {
+        let modelli = modelli();
+        let conservazioni = [
+            Conservazione::Integro,
+            Conservazione::Buono,
+            Conservazione::Buono,
+            Conservazione::Discreto,
+            Conservazione::Discreto,
+            Conservazione::Frammentario,
+            Conservazione::Pessimo,
+        ];
+        (0..n)
+            .map(|_| {
+                let modello = &modelli[self.indice(modelli.len())];
+                let sito = &siti[self.indice(siti.len())];
+                let periodo = modello.periodi[self.indice(modello.periodi.len())].clone();
+                let conservazione = conservazioni[self.indice(conservazioni.len())].clone();
+                let lunghezza = Self::arrotonda(self.normale(modello.lunghezza_cm));
+                let mut peso = self.normale(modello.peso_g);
+                let mut reperto = Reperto::nuovo(modello.nome, modello.materiale.clone(), periodo, conservazione, sito.nome)
+                    .con_classe_funzionale(modello.classe);
+                if modello.nome.contains(" tipo ") {
+                    reperto.tipo = Some(modello.nome.to_string());
+                }
+                if reperto.conservazione == Conservazione::Frammentario {
+                    let completezza = (20.0 + self.unitario() * 70.0).round();
+                    peso *= completezza / 100.0;
+                    reperto.completo_stimato_percento = Some(completezza);
+                }
+                reperto.misurazioni = Misurazioni {
+                    lunghezza_cm: Some(lunghezza),
+                    larghezza_cm: None,
+                    altezza_cm: None,
+                    peso_grammi: Some(Self::arrotonda(peso)),
+                };
+                // Punto uniforme nel cerchio attorno al centro del sito
+                let distanza = sito.raggio_m * self.unitario().sqrt();
+                let angolo = self.unitario() * std::f64::consts::TAU;
+                let metri_per_grado = 111_320.0;
+                reperto.coordinate = Some(Coordinate {
+                    latitudine: sito.centro.latitudine + distanza * angolo.cos() / metri_per_grado,
+                    longitudine: sito.centro.longitudine
+                        + distanza * angolo.sin() / (metri_per_grado * sito.centro.latitudine.to_radians().cos()),
+                });
+                reperto
+            })
+            .collect()
+    }
+}
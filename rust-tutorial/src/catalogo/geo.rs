@@ -0,0 +1,416 @@
+// ============================================================================
+// MODULO: GEO
+// ============================================================================
+// Griglie di scavo, capisaldi e sezioni: dalla posizione in un quadrato
+// alle coordinate assolute, il profilo di una sezione in SVG e la mappa
+// dei rinvenimenti nel terminale.
+// ============================================================================
+
+//! Griglie di scavo, capisaldi, riquadri geografici e sezioni.
+
+// Il modulo intero esiste solo con la feature, come le tracce GPX
+#![cfg(feature = "geo")]
+
+use super::errori::ErroreInventario;
+use super::modelli::*;
+
+/// Riquadro geografico (bounding box) in gradi decimali
+#[derive(Debug, Clone, Copy)]
+pub struct Riquadro {
+    pub lat_min: f64,
+    pub lon_min: f64,
+    pub lat_max: f64,
+    pub lon_max: f64,
+}
+
+impl Riquadro {
+    /// Il riquadro minimo che contiene tutti i reperti georeferenziati,
+    /// allargato di un margine relativo (es. 0.1 = 10%)
+    pub fn da_reperti(reperti: &[&Reperto], margine: f64) -> Option<Riquadro> {
+        let mut punti = reperti.iter().filter_map(|r| r.coordinate.as_ref());
+        let primo = punti.next()?;
+        let mut q = Riquadro {
+            lat_min: primo.latitudine,
+            lon_min: primo.longitudine,
+            lat_max: primo.latitudine,
+            lon_max: primo.longitudine,
+        };
+        for c in punti {
+            q.lat_min = q.lat_min.min(c.latitudine);
+            q.lat_max = q.lat_max.max(c.latitudine);
+            q.lon_min = q.lon_min.min(c.longitudine);
+            q.lon_max = q.lon_max.max(c.longitudine);
+        }
+        // Evita un riquadro degenere quando tutti i punti coincidono
+        let d_lat = (q.lat_max - q.lat_min).max(0.01) * margine;
+        let d_lon = (q.lon_max - q.lon_min).max(0.01) * margine;
+        Some(Riquadro {
+            lat_min: q.lat_min - d_lat,
+            lon_min: q.lon_min - d_lon,
+            lat_max: q.lat_max + d_lat,
+            lon_max: q.lon_max + d_lon,
+        })
+    }
+
+    pub fn contiene(&self, c: &Coordinate) -> bool {
+        (self.lat_min..=self.lat_max).contains(&c.latitudine)
+            && (self.lon_min..=self.lon_max).contains(&c.longitudine)
+    }
+}
+
+/// Griglia di scavo locale: quadrati di lato fisso a partire da un'origine.
+/// Le colonne sono lettere (A, B, ... Z, AA, ...) verso est della griglia,
+/// le righe numeri da 1 verso nord della griglia; l'origine e l'angolo
+/// sud-ovest del quadrato A1.
+#[derive(Debug, Clone)]
+pub struct GrigliaScavo {
+    pub origine: Coordinate,
+    /// Rotazione del nord di griglia rispetto al nord geografico, in gradi (orari)
+    pub orientamento_gradi: f64,
+    pub lato_m: f64,
+}
+
+/// Metri per grado di latitudine (approssimazione sferica)
+const METRI_PER_GRADO: f64 = 111_320.0;
+
+impl GrigliaScavo {
+    /// Scompone "B4" in (colonna, riga) con indici da 0
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::geo::GrigliaScavo;
+    ///
+    /// assert_eq!(GrigliaScavo::scomponi_quadrato("b4").unwrap(), (1, 3));
+    /// assert_eq!(GrigliaScavo::scomponi_quadrato("AA1").unwrap(), (26, 0));
+    /// // Troppe lettere per una colonna
+    /// assert!(GrigliaScavo::scomponi_quadrato("ZZZZZZZ1").is_err());
+    /// ```
+    pub fn scomponi_quadrato(quadrato: &str) -> Result<(u32, u32), ErroreInventario> {
+        let quadrato = quadrato.trim().to_uppercase();
+        let non_valido = || ErroreInventario::DatiNonValidi(format!("quadrato '{}' non valido", quadrato));
+        let cifre = quadrato.find(|c: char| c.is_ascii_digit()).ok_or_else(non_valido)?;
+        let (lettere, numero) = quadrato.split_at(cifre);
+        if lettere.is_empty() || !lettere.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(non_valido());
+        }
+        let colonna = lettere
+            .bytes()
+            .try_fold(0u32, |acc, b| acc.checked_mul(26)?.checked_add((b - b'A' + 1) as u32))
+            .ok_or_else(non_valido)?
+            - 1;
+        let riga: u32 = numero.parse().map_err(|_| non_valido())?;
+        if riga == 0 {
+            return Err(non_valido());
+        }
+        Ok((colonna, riga - 1))
+    }
+
+    /// Coordinate assolute del centro del quadrato (o del quadrante)
+    pub fn a_coordinate(&self, posizione: &PosizioneScavo) -> Result<Coordinate, ErroreInventario> {
+        let (colonna, riga) = Self::scomponi_quadrato(&posizione.quadrato)?;
+        let mezzo = self.lato_m / 2.0;
+        let quarto = self.lato_m / 4.0;
+        let (dx, dy) = match posizione.quadrante {
+            None => (0.0, 0.0),
+            Some(Quadrante::NO) => (-quarto, quarto),
+            Some(Quadrante::NE) => (quarto, quarto),
+            Some(Quadrante::SO) => (-quarto, -quarto),
+            Some(Quadrante::SE) => (quarto, -quarto),
+        };
+        // Coordinate locali di griglia (metri)
+        let x = colonna as f64 * self.lato_m + mezzo + dx;
+        let y = riga as f64 * self.lato_m + mezzo + dy;
+        // Rotazione nel sistema est/nord geografico
+        let theta = self.orientamento_gradi.to_radians();
+        let est = x * theta.cos() + y * theta.sin();
+        let nord = -x * theta.sin() + y * theta.cos();
+        Ok(Coordinate {
+            latitudine: self.origine.latitudine + nord / METRI_PER_GRADO,
+            longitudine: self.origine.longitudine
+                + est / (METRI_PER_GRADO * self.origine.latitudine.to_radians().cos()),
+        })
+    }
+}
+
+/// Caposaldo altimetrico di un sito: quota del piano di campagna
+#[derive(Debug, Clone, Copy)]
+pub struct Caposaldo {
+    pub quota_piano_m: f64,
+    /// Scarto ammesso tra quota misurata e quota derivata dalla profondita
+    pub tolleranza_m: f64,
+}
+
+impl Caposaldo {
+    /// Controlla la coerenza di quota e profondita rispetto al caposaldo
+    pub fn valida(&self, posizione: &PosizioneScavo) -> Result<(), ErroreInventario> {
+        if let Some(p) = posizione.profondita_cm {
+            if p < 0.0 {
+                return Err(ErroreInventario::DatiNonValidi(format!("profondita negativa: {} cm", p)));
+            }
+        }
+        if let Some(q) = posizione.quota_m {
+            if q > self.quota_piano_m + self.tolleranza_m {
+                return Err(ErroreInventario::DatiNonValidi(format!(
+                    "quota {:.2} m sopra il piano di campagna ({:.2} m)",
+                    q, self.quota_piano_m
+                )));
+            }
+        }
+        if let (Some(p), Some(q)) = (posizione.profondita_cm, posizione.quota_m) {
+            let attesa = self.quota_piano_m - p / 100.0;
+            if (attesa - q).abs() > self.tolleranza_m {
+                return Err(ErroreInventario::DatiNonValidi(format!(
+                    "quota {:.2} m incoerente con la profondita di {:.0} cm (attesa {:.2} m)",
+                    q, p, attesa
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Quota assoluta: misurata o derivata dalla profondita
+    pub fn quota(&self, posizione: &PosizioneScavo) -> Option<f64> {
+        posizione
+            .quota_m
+            .or_else(|| posizione.profondita_cm.map(|p| self.quota_piano_m - p / 100.0))
+    }
+}
+
+/// Linea di sezione tra due punti; include i reperti entro `fascia_m`
+#[derive(Debug, Clone)]
+pub struct Transetto {
+    pub da: Coordinate,
+    pub a: Coordinate,
+    pub fascia_m: f64,
+}
+
+/// Reperto proiettato sulla sezione
+#[derive(Debug, Clone)]
+pub struct PuntoSezione {
+    pub id_reperto: u32,
+    /// Distanza lungo il transetto dal punto iniziale, in metri
+    pub distanza_m: f64,
+    pub quota_m: f64,
+}
+
+impl Transetto {
+    /// Coordinate locali in metri (est, nord) rispetto all'inizio del transetto
+    fn locale(&self, c: &Coordinate) -> (f64, f64) {
+        let coseno = self.da.latitudine.to_radians().cos();
+        (
+            (c.longitudine - self.da.longitudine) * METRI_PER_GRADO * coseno,
+            (c.latitudine - self.da.latitudine) * METRI_PER_GRADO,
+        )
+    }
+
+    pub fn lunghezza_m(&self) -> f64 {
+        let (x, y) = self.locale(&self.a);
+        x.hypot(y)
+    }
+
+    /// Proietta un punto sul transetto: `None` se fuori fascia o oltre gli estremi
+    pub fn proietta(&self, c: &Coordinate) -> Option<f64> {
+        let (ax, ay) = self.locale(&self.a);
+        let (px, py) = self.locale(c);
+        let lunghezza = ax.hypot(ay);
+        if lunghezza == 0.0 {
+            return None;
+        }
+        let lungo = (px * ax + py * ay) / lunghezza;
+        let trasversale = (px * ay - py * ax).abs() / lunghezza;
+        (trasversale <= self.fascia_m && (0.0..=lunghezza).contains(&lungo)).then_some(lungo)
+    }
+}
+
+/// Profilo di sezione in SVG: distanza lungo il transetto (asse x)
+/// e quota assoluta (asse y), con l'ID di ogni reperto
+pub fn profilo_sezione_svg(punti: &[PuntoSezione], transetto: &Transetto, quota_piano_m: f64) -> String {
+    let (larghezza, altezza, margine) = (600.0, 300.0, 40.0);
+    let lunghezza = transetto.lunghezza_m().max(1.0);
+    let quota_min = punti.iter().map(|p| p.quota_m).fold(quota_piano_m - 1.0, f64::min);
+    let scala_x = (larghezza - 2.0 * margine) / lunghezza;
+    let scala_y = (altezza - 2.0 * margine) / (quota_piano_m - quota_min);
+    let x = |d: f64| margine + d * scala_x;
+    let y = |q: f64| margine + (quota_piano_m - q) * scala_y;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"10\">\n",
+        larghezza, altezza
+    );
+    svg.push_str(&format!(
+        "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#8b5a2b\" stroke-width=\"2\"/>\n",
+        x(0.0), y(quota_piano_m), x(lunghezza), y(quota_piano_m)
+    ));
+    svg.push_str(&format!(
+        "  <text x=\"{:.1}\" y=\"{:.1}\">piano di campagna {:.2} m</text>\n",
+        x(0.0), y(quota_piano_m) - 6.0, quota_piano_m
+    ));
+    svg.push_str(&format!(
+        "  <text x=\"{:.1}\" y=\"{:.1}\">{:.1} m</text>\n",
+        x(lunghezza) - 30.0, altezza - 10.0, lunghezza
+    ));
+    for p in punti {
+        svg.push_str(&format!(
+            "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"#b87333\"/>\n  <text x=\"{:.1}\" y=\"{:.1}\">#{} ({:.2} m)</text>\n",
+            x(p.distanza_m), y(p.quota_m), x(p.distanza_m) + 6.0, y(p.quota_m) + 4.0, p.id_reperto, p.quota_m
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Opzioni di disegno della mappa
+#[derive(Debug, Clone, Copy)]
+pub struct OpzioniMappa {
+    pub larghezza: usize,
+    pub altezza: usize,
+    /// Mostra il numero di reperti per cella invece di un semplice segno
+    pub cluster: bool,
+}
+
+impl Default for OpzioniMappa {
+    fn default() -> Self {
+        OpzioniMappa { larghezza: 60, altezza: 20, cluster: true }
+    }
+}
+
+/// Disegna i punti di rinvenimento su una griglia di caratteri.
+/// Nord in alto; con `cluster` le celle mostrano il conteggio (1-9, `+` oltre).
+pub fn mappa_ascii(reperti: &[&Reperto], riquadro: &Riquadro, opzioni: &OpzioniMappa) -> String {
+    let (larghezza, altezza) = (opzioni.larghezza.max(2), opzioni.altezza.max(2));
+    let mut conteggi = vec![vec![0usize; larghezza]; altezza];
+    let mut fuori = 0;
+
+    for c in reperti.iter().filter_map(|r| r.coordinate.as_ref()) {
+        if !riquadro.contiene(c) {
+            fuori += 1;
+            continue;
+        }
+        let x = (c.longitudine - riquadro.lon_min) / (riquadro.lon_max - riquadro.lon_min);
+        let y = (riquadro.lat_max - c.latitudine) / (riquadro.lat_max - riquadro.lat_min);
+        let col = ((x * (larghezza - 1) as f64).round() as usize).min(larghezza - 1);
+        let riga = ((y * (altezza - 1) as f64).round() as usize).min(altezza - 1);
+        conteggi[riga][col] += 1;
+    }
+
+    let mut mappa = String::new();
+    mappa.push_str(&format!("{:.4}N\n", riquadro.lat_max));
+    mappa.push_str(&format!("+{}+\n", "-".repeat(larghezza)));
+    for riga in &conteggi {
+        mappa.push('|');
+        for &n in riga {
+            mappa.push(match (n, opzioni.cluster) {
+                (0, _) => ' ',
+                (_, false) | (1, true) => '*',
+                (2..=9, true) => char::from_digit(n as u32, 10).unwrap_or('+'),
+                _ => '+',
+            });
+        }
+        mappa.push_str("|\n");
+    }
+    mappa.push_str(&format!("+{}+\n", "-".repeat(larghezza)));
+    mappa.push_str(&format!(
+        "{:.4}N  {:.4}E .. {:.4}E",
+        riquadro.lat_min, riquadro.lon_min, riquadro.lon_max
+    ));
+    if fuori > 0 {
+        mappa.push_str(&format!("\n({} reperti fuori dal riquadro)", fuori));
+    }
+    mappa
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vicini(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn posizione(quadrato: &str, quadrante: Option<Quadrante>) -> PosizioneScavo {
+        PosizioneScavo { quadrato: quadrato.to_string(), quadrante, profondita_cm: None, quota_m: None }
+    }
+
+    fn reperto_in(latitudine: f64, longitudine: f64) -> Reperto {
+        let mut r = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        r.coordinate = Some(Coordinate { latitudine, longitudine });
+        r
+    }
+
+    #[test]
+    fn quadrati_non_validi() {
+        for quadrato in ["", "4", "B", "B0", "B-1", "Ä1"] {
+            assert!(GrigliaScavo::scomponi_quadrato(quadrato).is_err(), "{}", quadrato);
+        }
+        assert_eq!(GrigliaScavo::scomponi_quadrato(" z10 ").unwrap(), (25, 9));
+    }
+
+    #[test]
+    fn centro_dei_quadrati_e_dei_quadranti() {
+        let origine = Coordinate { latitudine: 0.0, longitudine: 0.0 };
+        let griglia = GrigliaScavo { origine, orientamento_gradi: 0.0, lato_m: 4.0 };
+        // All'equatore un grado vale METRI_PER_GRADO in tutte e due le direzioni
+        let c = griglia.a_coordinate(&posizione("B1", None)).unwrap();
+        assert!(vicini(c.longitudine * METRI_PER_GRADO, 6.0) && vicini(c.latitudine * METRI_PER_GRADO, 2.0));
+        let c = griglia.a_coordinate(&posizione("A2", Some(Quadrante::NE))).unwrap();
+        assert!(vicini(c.longitudine * METRI_PER_GRADO, 3.0) && vicini(c.latitudine * METRI_PER_GRADO, 7.0));
+
+        // Nord di griglia ruotato di 90 gradi: le colonne vanno verso sud
+        let ruotata = GrigliaScavo { orientamento_gradi: 90.0, ..griglia };
+        let c = ruotata.a_coordinate(&posizione("B1", None)).unwrap();
+        assert!(vicini(c.longitudine * METRI_PER_GRADO, 2.0) && vicini(c.latitudine * METRI_PER_GRADO, -6.0));
+    }
+
+    #[test]
+    fn caposaldo_e_quote() {
+        let caposaldo = Caposaldo { quota_piano_m: 12.0, tolleranza_m: 0.05 };
+        let mut p = posizione("A1", None);
+        p.profondita_cm = Some(150.0);
+        assert_eq!(caposaldo.quota(&p), Some(10.5));
+        p.quota_m = Some(10.52);
+        assert!(caposaldo.valida(&p).is_ok());
+        p.quota_m = Some(11.0);
+        assert!(caposaldo.valida(&p).is_err());
+
+        let mut sopra = posizione("A1", None);
+        sopra.quota_m = Some(12.5);
+        assert!(caposaldo.valida(&sopra).is_err());
+        sopra.quota_m = None;
+        sopra.profondita_cm = Some(-3.0);
+        assert!(caposaldo.valida(&sopra).is_err());
+    }
+
+    #[test]
+    fn proiezione_sul_transetto() {
+        let metri = |m: f64| m / METRI_PER_GRADO;
+        let transetto = Transetto {
+            da: Coordinate { latitudine: 0.0, longitudine: 0.0 },
+            a: Coordinate { latitudine: 0.0, longitudine: metri(10.0) },
+            fascia_m: 1.0,
+        };
+        assert!(vicini(transetto.lunghezza_m(), 10.0));
+        let dentro = transetto.proietta(&Coordinate { latitudine: metri(0.5), longitudine: metri(4.0) }).unwrap();
+        assert!(vicini(dentro, 4.0));
+        // Fuori fascia e oltre l'estremo finale
+        assert!(transetto.proietta(&Coordinate { latitudine: metri(2.0), longitudine: metri(4.0) }).is_none());
+        assert!(transetto.proietta(&Coordinate { latitudine: 0.0, longitudine: metri(11.0) }).is_none());
+    }
+
+    #[test]
+    fn riquadro_e_mappa() {
+        let reperti = [reperto_in(45.0, 11.0), reperto_in(45.1, 11.2), reperto_in(45.1, 11.2)];
+        let riferimenti: Vec<&Reperto> = reperti.iter().collect();
+        let riquadro = Riquadro::da_reperti(&riferimenti, 0.0).unwrap();
+        assert!(vicini(riquadro.lat_min, 45.0) && vicini(riquadro.lon_max, 11.2));
+        assert!(Riquadro::da_reperti(&[], 0.1).is_none());
+
+        // Angolo sud-ovest in basso a sinistra, i due punti coincidenti in alto a destra
+        let opzioni = OpzioniMappa { larghezza: 3, altezza: 2, cluster: true };
+        let mappa = mappa_ascii(&riferimenti, &riquadro, &opzioni);
+        let righe: Vec<&str> = mappa.lines().collect();
+        assert_eq!(&righe[1..5], ["+---+", "|  2|", "|*  |", "+---+"]);
+
+        let lontano = reperto_in(40.0, 9.0);
+        let mappa = mappa_ascii(&[&lontano], &riquadro, &OpzioniMappa { cluster: false, ..opzioni });
+        assert!(mappa.ends_with("(1 reperti fuori dal riquadro)"));
+    }
+}
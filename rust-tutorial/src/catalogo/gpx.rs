@@ -0,0 +1,217 @@
+// ============================================================================
+// MODULO: TRACCE GPX
+// ============================================================================
+// Sul campo i rinvenimenti si annotano con l'ora e il GPS palmare
+// registra la traccia: leggendo il file GPX si assegnano ai reperti le
+// coordinate del punto registrato piu vicino nel tempo. Il GPX si legge a
+// mano, cercando i pochi tag che servono, senza una libreria XML.
+// ============================================================================
+
+//! Lettura dei file GPX e georeferenziazione dei reperti per orario.
+
+// Il modulo intero esiste solo con la feature: le coordinate dalla traccia
+// servono alle mappe e alle sezioni di `geo`
+#![cfg(feature = "geo")]
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::*;
+use chrono::{DateTime, Duration, NaiveDateTime};
+
+/// Punto registrato da un GPS palmare (waypoint o punto traccia)
+#[derive(Debug, Clone)]
+pub struct PuntoGps {
+    pub latitudine: f64,
+    pub longitudine: f64,
+    pub quando: Option<NaiveDateTime>,
+    pub nome: Option<String>,
+}
+
+/// Valore di un attributo XML nell'intestazione di un tag (`lat="41.2"`)
+fn attributo<'a>(intestazione: &'a str, nome: &str) -> Option<&'a str> {
+    let chiave = format!("{}=", nome);
+    let inizio = intestazione.find(&chiave)? + chiave.len();
+    let resto = &intestazione[inizio..];
+    let virgolette = resto.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let resto = &resto[1..];
+    resto.find(virgolette).map(|fine| &resto[..fine])
+}
+
+/// Testo contenuto nel primo elemento `<tag>...</tag>`
+fn elemento<'a>(corpo: &'a str, tag: &str) -> Option<&'a str> {
+    let apertura = format!("<{}>", tag);
+    let chiusura = format!("</{}>", tag);
+    let inizio = corpo.find(&apertura)? + apertura.len();
+    let fine = corpo[inizio..].find(&chiusura)?;
+    Some(corpo[inizio..inizio + fine].trim())
+}
+
+/// Legge waypoint (`<wpt>`) e punti traccia (`<trkpt>`) da un file GPX.
+/// Gli orari GPX sono in UTC e vengono convertiti in `NaiveDateTime` UTC.
+pub fn leggi(xml: &str) -> Result<Vec<PuntoGps>, ErroreInventario> {
+    let mut punti = Vec::new();
+    for tag in ["wpt", "trkpt"] {
+        let apertura = format!("<{} ", tag);
+        let chiusura = format!("</{}>", tag);
+        let mut resto = xml;
+        while let Some(inizio) = resto.find(&apertura) {
+            resto = &resto[inizio..];
+            let fine_intestazione = resto.find('>').ok_or_else(|| {
+                ErroreInventario::DatiNonValidi(format!("tag <{}> non chiuso", tag))
+            })?;
+            let intestazione = &resto[..fine_intestazione];
+            let (corpo, avanzamento) = if intestazione.ends_with('/') {
+                ("", fine_intestazione + 1)
+            } else {
+                let fine = resto.find(&chiusura).ok_or_else(|| {
+                    ErroreInventario::DatiNonValidi(format!("manca {}", chiusura))
+                })?;
+                (&resto[fine_intestazione + 1..fine], fine + chiusura.len())
+            };
+
+            let coordinata = |nome: &str| -> Result<f64, ErroreInventario> {
+                attributo(intestazione, nome)
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| {
+                        ErroreInventario::DatiNonValidi(format!("<{}> senza attributo {} valido", tag, nome))
+                    })
+            };
+            let quando = match elemento(corpo, "time") {
+                Some(t) => Some(
+                    DateTime::parse_from_rfc3339(t)
+                        .map_err(|e| ErroreInventario::DatiNonValidi(format!("orario GPX '{}': {}", t, e)))?
+                        .naive_utc(),
+                ),
+                None => None,
+            };
+            punti.push(PuntoGps {
+                latitudine: coordinata("lat")?,
+                longitudine: coordinata("lon")?,
+                quando,
+                nome: elemento(corpo, "name").map(String::from),
+            });
+            resto = &resto[avanzamento..];
+        }
+    }
+    Ok(punti)
+}
+
+/// Esito dell'associazione di un reperto a un punto GPS
+#[derive(Debug)]
+pub struct Associazione {
+    pub id_reperto: u32,
+    pub punto: PuntoGps,
+    pub scarto: Duration,
+}
+
+/// Assegna le coordinate ai reperti che ne sono privi, usando il punto GPS
+/// piu vicino nel tempo all'orario di rinvenimento, entro la tolleranza.
+/// Le coordinate gia presenti non vengono mai sovrascritte.
+pub fn associa(
+    inv: &mut Inventario,
+    punti: &[PuntoGps],
+    fonte: &str,
+    tolleranza: Duration,
+) -> Vec<Associazione> {
+    let mut associazioni = Vec::new();
+    inv.modifica_tutti(|reperto| {
+        let Some(rinvenuto_il) = reperto.rinvenuto_il else { return };
+        if reperto.coordinate.is_some() {
+            return;
+        }
+        let piu_vicino = punti
+            .iter()
+            .filter_map(|p| p.quando.map(|q| (p, (q - rinvenuto_il).abs())))
+            .min_by_key(|(_, scarto)| *scarto);
+        let Some((punto, scarto)) = piu_vicino else { return };
+        if scarto > tolleranza {
+            return;
+        }
+        reperto.coordinate = Some(Coordinate {
+            latitudine: punto.latitudine,
+            longitudine: punto.longitudine,
+        });
+        reperto.origine_coordinate = Some(OrigineCoordinate {
+            fonte: format!("GPX {}", fonte),
+            punto: punto.nome.clone(),
+            scarto_secondi: scarto.num_seconds(),
+        });
+        associazioni.push(Associazione { id_reperto: reperto.id, punto: punto.clone(), scarto });
+    });
+    associazioni.sort_by_key(|a| a.id_reperto);
+    associazioni
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const TRACCIA: &str = r#"<?xml version="1.0"?>
+<gpx version="1.1" creator="palmare">
+  <wpt lat='45.0501' lon="11.4102"><time>2024-07-03T09:15:00+02:00</time><name>Q12</name></wpt>
+  <trk><trkseg>
+    <trkpt lat="45.0500" lon="11.4100"><time>2024-07-03T07:00:00Z</time></trkpt>
+    <trkpt lat="45.0510" lon="11.4110"><time>2024-07-03T08:00:00Z</time></trkpt>
+    <trkpt lat="45.0520" lon="11.4120"/>
+  </trkseg></trk>
+</gpx>"#;
+
+    fn alle(ora: u32, minuto: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 7, 3).unwrap().and_hms_opt(ora, minuto, 0).unwrap()
+    }
+
+    #[test]
+    fn waypoint_e_punti_traccia() {
+        let punti = leggi(TRACCIA).unwrap();
+        assert_eq!(punti.len(), 4);
+        // I waypoint vengono prima, con l'orario riportato in UTC
+        assert_eq!((punti[0].nome.as_deref(), punti[0].quando), (Some("Q12"), Some(alle(7, 15))));
+        assert_eq!((punti[0].latitudine, punti[0].longitudine), (45.0501, 11.4102));
+        assert_eq!(punti[2].quando, Some(alle(8, 0)));
+        // Un punto senza corpo non ha orario ne nome
+        assert!(punti[3].quando.is_none() && punti[3].nome.is_none());
+    }
+
+    #[test]
+    fn gpx_non_validi() {
+        for xml in [
+            r#"<wpt lat="45.0" lon="11.0"><name>A</name>"#,
+            r#"<wpt lat="45.0"><name>A</name></wpt>"#,
+            r#"<wpt lat="nord" lon="11.0"/>"#,
+            r#"<wpt lat="45.0" lon="11.0"><time>ieri</time></wpt>"#,
+        ] {
+            assert!(leggi(xml).is_err(), "{}", xml);
+        }
+        assert!(leggi("<gpx></gpx>").unwrap().is_empty());
+    }
+
+    #[test]
+    fn associazione_per_orario() {
+        let mut inv = Inventario::nuovo();
+        for (nome, rinvenuto_il, coordinate) in [
+            ("Ascia", Some(alle(7, 20)), None),
+            ("Spillone", Some(alle(7, 50)), Some(Coordinate { latitudine: 44.0, longitudine: 11.0 })),
+            ("Fibula", Some(alle(12, 0)), None),
+            ("Pugnale", None, None),
+        ] {
+            let mut r = Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoRecente, Conservazione::Buono, "Frattesina");
+            r.rinvenuto_il = rinvenuto_il;
+            r.coordinate = coordinate;
+            inv.aggiungi(r).unwrap();
+        }
+
+        let punti = leggi(TRACCIA).unwrap();
+        let associazioni = associa(&mut inv, &punti, "giorno3.gpx", Duration::minutes(30));
+        // Solo l'ascia: lo spillone ha gia le coordinate, la fibula e troppo lontana nel tempo
+        assert_eq!(associazioni.len(), 1);
+        assert_eq!((associazioni[0].id_reperto, associazioni[0].scarto), (1, Duration::minutes(5)));
+
+        let ascia = inv.cerca_per_id(1).unwrap();
+        assert_eq!(ascia.coordinate.as_ref().map(|c| c.latitudine), Some(45.0501));
+        let origine = ascia.origine_coordinate.as_ref().unwrap();
+        assert_eq!((origine.fonte.as_str(), origine.punto.as_deref(), origine.scarto_secondi), ("GPX giorno3.gpx", Some("Q12"), 300));
+        assert_eq!(inv.cerca_per_id(2).unwrap().coordinate.as_ref().map(|c| c.latitudine), Some(44.0));
+        assert!(inv.cerca_per_id(3).unwrap().coordinate.is_none());
+    }
+}
@@ -0,0 +1,131 @@
+// ============================================================================
+// MODULO: GRAFICO CRONOLOGICO
+// ============================================================================
+// Quanti reperti per periodo, divisi per classe funzionale: nel
+// terminale con barre a blocchi, in SVG scritto a mano per le relazioni.
+// ============================================================================
+
+//! Grafico cronologico a barre impilate, nel terminale e in SVG.
+
+use super::modelli::*;
+use std::collections::BTreeMap;
+
+/// Gruppi del grafico: le classi funzionali, poi i non classificati (None)
+const GRUPPI: [Option<ClasseFunzionale>; 6] = [
+    Some(ClasseFunzionale::Arma),
+    Some(ClasseFunzionale::Utensile),
+    Some(ClasseFunzionale::Ornamento),
+    Some(ClasseFunzionale::Lingotto),
+    Some(ClasseFunzionale::Votivo),
+    None,
+];
+
+fn simbolo(gruppo: Option<ClasseFunzionale>) -> char {
+    match gruppo {
+        Some(ClasseFunzionale::Arma) => '█',
+        Some(ClasseFunzionale::Utensile) => '▓',
+        Some(ClasseFunzionale::Ornamento) => '▒',
+        Some(ClasseFunzionale::Lingotto) => '░',
+        Some(ClasseFunzionale::Votivo) => '▚',
+        None => '·',
+    }
+}
+
+fn colore(gruppo: Option<ClasseFunzionale>) -> &'static str {
+    match gruppo {
+        Some(ClasseFunzionale::Arma) => "#8c2d04",
+        Some(ClasseFunzionale::Utensile) => "#cc4c02",
+        Some(ClasseFunzionale::Ornamento) => "#fe9929",
+        Some(ClasseFunzionale::Lingotto) => "#6a51a3",
+        Some(ClasseFunzionale::Votivo) => "#238b45",
+        None => "#bdbdbd",
+    }
+}
+
+fn etichetta(gruppo: Option<ClasseFunzionale>) -> String {
+    gruppo.map_or("Non classificato".to_string(), |c| c.to_string())
+}
+
+/// Conteggi per periodo (in ordine cronologico) e per gruppo
+fn conteggi(reperti: &[&Reperto]) -> BTreeMap<Periodo, [usize; 6]> {
+    let mut conteggi: BTreeMap<Periodo, [usize; 6]> = BTreeMap::new();
+    for r in reperti {
+        let gruppo = GRUPPI.iter().position(|g| *g == r.classe_funzionale).unwrap_or(5);
+        conteggi.entry(r.periodo.clone()).or_default()[gruppo] += 1;
+    }
+    conteggi
+}
+
+/// Barre orizzontali impilate con caratteri a blocchi; la barra piu
+/// lunga occupa `larghezza` caratteri
+pub fn cronologia_terminale(reperti: &[&Reperto], larghezza: usize) -> String {
+    let conteggi = conteggi(reperti);
+    let massimo = conteggi.values().map(|c| c.iter().sum::<usize>()).max().unwrap_or(0).max(1);
+    let colonna = conteggi.keys().map(|p| p.to_string().chars().count()).max().unwrap_or(0);
+    let scala = larghezza.max(1) as f64 / massimo as f64;
+
+    let mut testo = String::new();
+    for (periodo, per_gruppo) in &conteggi {
+        let mut barra = String::new();
+        for (i, n) in per_gruppo.iter().enumerate().filter(|(_, n)| **n > 0) {
+            let lunghezza = ((*n as f64 * scala).round() as usize).max(1);
+            barra.extend(std::iter::repeat_n(simbolo(GRUPPI[i]), lunghezza));
+        }
+        let totale: usize = per_gruppo.iter().sum();
+        testo.push_str(&format!("{:<w$} {} {}\n", periodo.to_string(), barra, totale, w = colonna));
+    }
+    let legenda: Vec<String> = GRUPPI.iter().map(|g| format!("{} {}", simbolo(*g), etichetta(*g))).collect();
+    testo.push_str(&format!("{}\n", legenda.join("  ")));
+    testo
+}
+
+/// Istogramma verticale impilato in SVG, una colonna per periodo
+pub fn cronologia_svg(reperti: &[&Reperto]) -> String {
+    let conteggi = conteggi(reperti);
+    let (larghezza, altezza, margine) = (640.0, 360.0, 50.0);
+    let massimo = conteggi.values().map(|c| c.iter().sum::<usize>()).max().unwrap_or(0).max(1) as f64;
+    let colonne = conteggi.len().max(1) as f64;
+    let passo = (larghezza - 2.0 * margine - 140.0) / colonne;
+    let scala_y = (altezza - 2.0 * margine) / massimo;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"10\">\n",
+        larghezza, altezza
+    );
+    svg.push_str(&format!(
+        "  <line x1=\"{m}\" y1=\"{b}\" x2=\"{x2}\" y2=\"{b}\" stroke=\"#333\"/>\n",
+        m = margine, b = altezza - margine, x2 = margine + passo * colonne
+    ));
+    for (i, (periodo, per_gruppo)) in conteggi.iter().enumerate() {
+        let x = margine + i as f64 * passo + passo * 0.15;
+        let mut base = altezza - margine;
+        for (g, n) in per_gruppo.iter().enumerate().filter(|(_, n)| **n > 0) {
+            let h = *n as f64 * scala_y;
+            base -= h;
+            svg.push_str(&format!(
+                "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"><title>{}: {}</title></rect>\n",
+                x, base, passo * 0.7, h, colore(GRUPPI[g]), etichetta(GRUPPI[g]), n
+            ));
+        }
+        let nome = periodo.to_string();
+        let breve = nome.split(" (").next().unwrap_or(&nome);
+        svg.push_str(&format!(
+            "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            x + passo * 0.35, altezza - margine + 14.0, breve
+        ));
+        svg.push_str(&format!(
+            "  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            x + passo * 0.35, base - 4.0, per_gruppo.iter().sum::<usize>()
+        ));
+    }
+    let legenda_x = larghezza - margine - 110.0;
+    for (i, g) in GRUPPI.iter().enumerate() {
+        let y = margine + i as f64 * 16.0;
+        svg.push_str(&format!(
+            "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"10\" height=\"10\" fill=\"{}\"/>\n  <text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+            legenda_x, y, colore(*g), legenda_x + 14.0, y + 9.0, etichetta(*g)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,1426 @@
+// ============================================================================
+// MODULO: INVENTARIO
+// ============================================================================
+// L'inventario completo: reperti, persone, campioni, griglie e campagne
+// di scavo, registro delle operazioni, prestiti, rimozioni da approvare e
+// schede radiate. Le esportazioni JSON portano l'impronta SHA-256 del catalogo
+// da cui sono state generate.
+//
+// L'id numerico e unico solo dentro un inventario: due scavi hanno
+// entrambi un reperto #1. Per unire inventari diversi ogni reperto puo
+// avere anche un identificativo globale, secondo la `StrategiaId`
+// scelta con `Inventario::nuovo_con_strategia`:
+//
+//   Sequenziale        nessuno, basta l'id (il comportamento di sempre)
+//   Uuid               "3f2b8c1e-...", unico senza coordinarsi con nessuno
+//   PrefissoSito       "SAV-0001", leggibile, unico tra siti con prefissi diversi
+//
+// Quello che non sta nelle schede (la strategia, le campagne di scavo,
+// le persone, le rimozioni da approvare, il registro, i prestiti, le date
+// di inserimento) forma lo `StatoInventario`: ogni archivio lo salva
+// insieme ai reperti e lo restituisce a `Inventario::da_archivio` al
+// caricamento.
+// ============================================================================
+
+//! Inventario completo del progetto finale.
+
+use super::errori::ErroreInventario;
+use super::modelli::*;
+use super::campioni::{Campione, StatoCampione, TipoCampione};
+use super::dossier::Dossier;
+#[cfg(feature = "geo")]
+use super::geo::{Caposaldo, GrigliaScavo, PuntoSezione, Transetto};
+use super::magazzino::{Magazzino, Memoria};
+use super::prestiti::Prestito;
+use super::statistiche::CacheStatistiche;
+use super::visibilita::ProfiloVisibilita;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "geo")]
+use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Copia di lavoro di un reperto: `originale` e lo stato letto all'apertura
+#[derive(Debug, Clone)]
+pub struct Modifica {
+    pub originale: Reperto,
+    pub bozza: Reperto,
+}
+
+/// Intestazione di ogni esportazione: `snapshot` e l'impronta del
+/// catalogo completo da cui e stata generata, anche se filtrata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntestazioneEsportazione {
+    pub formato: String,
+    pub versione: u32,
+    pub snapshot: String,
+    pub profilo: String,
+    pub reperti: usize,
+    /// Schede di reperti radiati, esportate a parte
+    #[serde(default)]
+    pub radiati: usize,
+}
+
+/// Come assegnare l'identificativo globale ai nuovi reperti. Si salva e
+/// si legge con serde, per esempio nel file di configurazione:
+/// `{"tipo": "prefisso_sito", "prefissi": {"Savignano Irpino": "SAV"}, "cifre": 4}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum StrategiaId {
+    /// Solo l'id progressivo
+    #[default]
+    Sequenziale,
+    /// UUID versione 4 (casuale)
+    Uuid,
+    /// Prefisso del sito e numero progressivo per prefisso
+    PrefissoSito {
+        /// Prefisso per sito; per gli altri siti le prime tre lettere del nome
+        #[serde(default)]
+        prefissi: BTreeMap<String, String>,
+        /// Cifre del numero, con gli zeri davanti
+        cifre: usize,
+    },
+}
+
+impl StrategiaId {
+    /// Codici come "SAV-0001", con i prefissi ricavati dai nomi dei siti
+    pub fn prefisso_sito() -> Self {
+        StrategiaId::PrefissoSito { prefissi: BTreeMap::new(), cifre: 4 }
+    }
+
+    /// Prefisso scelto per un sito (solo per `PrefissoSito`)
+    pub fn con_prefisso(mut self, sito: &str, prefisso: &str) -> Self {
+        if let StrategiaId::PrefissoSito { prefissi, .. } = &mut self {
+            prefissi.insert(sito.to_string(), prefisso.to_string());
+        }
+        self
+    }
+
+    /// "Savignano Irpino" -> "SAV"
+    fn prefisso(prefissi: &BTreeMap<String, String>, sito: &str) -> String {
+        if let Some(prefisso) = prefissi.get(sito) {
+            return prefisso.clone();
+        }
+        let lettere: String = sito.chars().filter(char::is_ascii_alphabetic).take(3).collect();
+        if lettere.is_empty() {
+            "REP".to_string()
+        } else {
+            lettere.to_ascii_uppercase()
+        }
+    }
+
+    pub fn da_nome(nome: &str) -> Option<Self> {
+        match nome {
+            "sequenziale" => Some(StrategiaId::Sequenziale),
+            "uuid" => Some(StrategiaId::Uuid),
+            "sito" => Some(StrategiaId::prefisso_sito()),
+            _ => None,
+        }
+    }
+}
+
+/// Quello che l'inventario conserva oltre alle schede. Gli archivi lo
+/// salvano insieme ai reperti; i campi mancanti (archivi delle versioni
+/// precedenti) valgono il predefinito
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatoInventario {
+    #[serde(default)]
+    pub strategia: StrategiaId,
+    /// Servono a pubblicare i reperti da scavo (`verifica_estremi_scavo`)
+    #[serde(default)]
+    pub campagne_scavo: Vec<CampagnaScavo>,
+    /// Chi puo accedere e con quali permessi
+    #[serde(default)]
+    pub persone: Vec<Persona>,
+    /// Le decide un'altra persona, di solito in un'altra sessione
+    #[serde(default)]
+    pub rimozioni_in_attesa: Vec<RichiestaRimozione>,
+    #[serde(default)]
+    pub prossima_richiesta: u32,
+    /// Chi ha fatto cosa: serve ai report di attivita e al riepilogo
+    #[serde(default)]
+    pub registro: Vec<VoceRegistro>,
+    /// Il riepilogo periodico segnala quelli scaduti
+    #[serde(default)]
+    pub prestiti: Vec<Prestito>,
+    /// Data di inserimento di ogni reperto, per i "nuovi" del riepilogo
+    #[serde(default)]
+    pub creazioni: BTreeMap<u32, NaiveDateTime>,
+}
+
+/// Chiave di ordinamento delle esportazioni
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ordinamento {
+    Id,
+    Nome,
+    Periodo,
+    Sito,
+}
+
+impl Ordinamento {
+    pub fn da_nome(nome: &str) -> Option<Self> {
+        match nome {
+            "id" => Some(Ordinamento::Id),
+            "nome" => Some(Ordinamento::Nome),
+            "periodo" => Some(Ordinamento::Periodo),
+            "sito" => Some(Ordinamento::Sito),
+            _ => None,
+        }
+    }
+}
+
+/// Inventario principale. I reperti stanno in un `Magazzino` (vedi
+/// `magazzino`), in memoria se non se ne sceglie un altro.
+pub struct Inventario<M: Magazzino = Memoria> {
+    /// Iterazione sempre in ordine di ID: esportazioni riproducibili
+    reperti: M,
+    prossimo_id: u32,
+    persone: BTreeMap<u32, Persona>,
+    prossimo_id_persona: u32,
+    #[cfg(feature = "geo")]
+    griglie: HashMap<String, GrigliaScavo>,
+    campagne_scavo: BTreeMap<String, CampagnaScavo>,
+    #[cfg(feature = "geo")]
+    caposaldi: HashMap<String, Caposaldo>,
+    campioni: BTreeMap<u32, Campione>,
+    prestiti: Vec<Prestito>,
+    /// Aggregati aggiornati a ogni modifica
+    cache: CacheStatistiche,
+    /// Reperti prestati in scrittura e non ancora ricontati nella cache
+    in_sospeso: HashSet<u32>,
+    /// Dopo modifica_tutti() la cache va ricalcolata da zero
+    cache_invalida: bool,
+    /// Operazioni firmate dall'operatore collegato
+    registro: Vec<VoceRegistro>,
+    /// Quando e stato aggiunto ogni reperto, anche senza operatore
+    creazioni: BTreeMap<u32, NaiveDateTime>,
+    operatore: Option<u32>,
+    rimozioni_in_attesa: BTreeMap<u32, RichiestaRimozione>,
+    prossima_richiesta: u32,
+    /// Reperti radiati: fuori dai conteggi, ma conservati e salvati
+    radiati: M,
+    strategia: StrategiaId,
+    /// Ultimo numero usato per ogni prefisso (StrategiaId::PrefissoSito)
+    ultimi_codici: BTreeMap<String, u32>,
+}
+
+impl Inventario {
+    pub fn nuovo() -> Self {
+        Inventario::vuoto(Memoria::default(), Memoria::default())
+    }
+
+    /// Inventario che assegna ai nuovi reperti anche un identificativo
+    /// globale.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::inventario::{Inventario, StrategiaId};
+    /// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+    ///
+    /// let mut inv = Inventario::nuovo_con_strategia(StrategiaId::prefisso_sito());
+    /// let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Buono, "Savignano Irpino");
+    /// let id = inv.aggiungi(ascia).unwrap();
+    /// assert_eq!(inv.cerca_per_id(id).unwrap().identificativo.as_deref(), Some("SAV-0001"));
+    /// ```
+    pub fn nuovo_con_strategia(strategia: StrategiaId) -> Self {
+        Inventario { strategia, ..Inventario::nuovo() }
+    }
+
+    /// L'inventario letto da un archivio: le schede, radiate comprese, e
+    /// lo stato salvato con loro
+    pub fn da_archivio(
+        reperti: impl IntoIterator<Item = Reperto>,
+        stato: StatoInventario,
+    ) -> Result<Self, ErroreInventario> {
+        let mut inventario = Inventario::nuovo_con_strategia(stato.strategia);
+        for campagna in stato.campagne_scavo {
+            inventario.campagne_scavo.insert(campagna.codice.clone(), campagna);
+        }
+        for persona in stato.persone {
+            inventario.prossimo_id_persona = inventario.prossimo_id_persona.max(persona.id + 1);
+            inventario.persone.insert(persona.id, persona);
+        }
+        for richiesta in stato.rimozioni_in_attesa {
+            inventario.prossima_richiesta = inventario.prossima_richiesta.max(richiesta.numero + 1);
+            inventario.rimozioni_in_attesa.insert(richiesta.numero, richiesta);
+        }
+        inventario.prossima_richiesta = inventario.prossima_richiesta.max(stato.prossima_richiesta);
+        inventario.registro = stato.registro;
+        inventario.prestiti = stato.prestiti;
+        inventario.creazioni = stato.creazioni;
+        for reperto in reperti {
+            inventario.importa(reperto)?;
+        }
+        Ok(inventario)
+    }
+}
+
+impl<M: Magazzino> Inventario<M> {
+    fn vuoto(reperti: M, radiati: M) -> Self {
+        Inventario {
+            reperti,
+            prossimo_id: 1,
+            persone: BTreeMap::new(),
+            prossimo_id_persona: 1,
+            #[cfg(feature = "geo")]
+            griglie: HashMap::new(),
+            campagne_scavo: BTreeMap::new(),
+            #[cfg(feature = "geo")]
+            caposaldi: HashMap::new(),
+            campioni: BTreeMap::new(),
+            prestiti: Vec::new(),
+            cache: CacheStatistiche::default(),
+            in_sospeso: HashSet::new(),
+            cache_invalida: false,
+            registro: Vec::new(),
+            creazioni: BTreeMap::new(),
+            operatore: None,
+            rimozioni_in_attesa: BTreeMap::new(),
+            prossima_richiesta: 1,
+            radiati,
+            strategia: StrategiaId::Sequenziale,
+            ultimi_codici: BTreeMap::new(),
+        }
+    }
+
+    /// Inventario sui reperti gia presenti in due magazzini, gli attivi e
+    /// i radiati: i nuovi id partono dopo il piu alto, e i codici della
+    /// strategia indicata dopo quelli gia assegnati. Le schede non vengono
+    /// validate di nuovo.
+    pub fn con_magazzini(reperti: M, radiati: M, strategia: StrategiaId) -> Self {
+        let mut inventario = Inventario { strategia, ..Inventario::vuoto(reperti, radiati) };
+        let mut codici = Vec::new();
+        for r in inventario.reperti.iter().chain(inventario.radiati.iter()) {
+            inventario.prossimo_id = inventario.prossimo_id.max(r.id + 1);
+            codici.extend(r.identificativo.clone());
+        }
+        for codice in codici {
+            inventario.registra_codice(&codice);
+        }
+        inventario.cache = CacheStatistiche::da_reperti(inventario.reperti.iter());
+        inventario
+    }
+
+    /// I due magazzini (attivi e radiati), per salvarli o chiuderli
+    pub fn in_magazzini(self) -> (M, M) {
+        (self.reperti, self.radiati)
+    }
+
+    pub fn strategia(&self) -> &StrategiaId {
+        &self.strategia
+    }
+
+    /// Lo stato da salvare insieme ai reperti
+    pub fn stato(&self) -> StatoInventario {
+        StatoInventario {
+            strategia: self.strategia.clone(),
+            campagne_scavo: self.campagne_scavo.values().cloned().collect(),
+            persone: self.persone.values().cloned().collect(),
+            rimozioni_in_attesa: self.rimozioni_in_attesa.values().cloned().collect(),
+            prossima_richiesta: self.prossima_richiesta,
+            registro: self.registro.clone(),
+            prestiti: self.prestiti.clone(),
+            creazioni: self.creazioni.clone(),
+        }
+    }
+
+    /// Identificativo globale per un nuovo reperto del sito indicato
+    fn nuovo_identificativo(&mut self, sito: &str) -> Option<String> {
+        match &self.strategia {
+            StrategiaId::Sequenziale => None,
+            StrategiaId::Uuid => Some(uuid::Uuid::new_v4().to_string()),
+            StrategiaId::PrefissoSito { prefissi, cifre } => {
+                let prefisso = StrategiaId::prefisso(prefissi, sito);
+                let cifre = *cifre;
+                let numero = self.ultimi_codici.entry(prefisso.clone()).or_insert(0);
+                *numero += 1;
+                Some(format!("{}-{:0cifre$}", prefisso, numero, cifre = cifre))
+            }
+        }
+    }
+
+    /// Un codice "SAV-0042" gia presente fa ripartire la numerazione di SAV da 43
+    fn registra_codice(&mut self, identificativo: &str) {
+        let Some((prefisso, numero)) = identificativo.rsplit_once('-') else { return };
+        if let Ok(numero) = numero.parse::<u32>() {
+            let ultimo = self.ultimi_codici.entry(prefisso.to_string()).or_insert(0);
+            *ultimo = (*ultimo).max(numero);
+        }
+    }
+
+    /// Reperto (anche radiato) con l'identificativo globale indicato
+    pub fn cerca_per_identificativo(&self, identificativo: &str) -> Option<&Reperto> {
+        self.reperti
+            .iter()
+            .chain(self.radiati.iter())
+            .find(|r| r.identificativo.as_deref() == Some(identificativo))
+    }
+
+    /// Aggiungi un reperto con ID automatico
+    pub fn aggiungi(&mut self, mut reperto: Reperto) -> Result<u32, ErroreInventario> {
+        reperto.valida()?;
+
+        let id = self.prossimo_id;
+        reperto.id = id;
+        // Un identificativo gia assegnato altrove (reperto copiato da un
+        // altro inventario) si conserva, purche non sia gia in uso qui
+        match &reperto.identificativo {
+            Some(esistente) => {
+                self.controlla_identificativo(esistente)?;
+                let esistente = esistente.clone();
+                self.registra_codice(&esistente);
+            }
+            None => reperto.identificativo = self.nuovo_identificativo(&reperto.sito),
+        }
+        reperto.stato = StatoCatalogazione::InIngresso;
+        reperto.radiazione = None;
+        self.cache.aggiungi(&reperto);
+        self.reperti.insert(reperto);
+        self.prossimo_id += 1;
+        self.creazioni.insert(id, chrono::Local::now().naive_local());
+        self.annota(id, Operazione::Creazione);
+        Ok(id)
+    }
+
+    /// Reinserisce un reperto gia catalogato (da archivio) mantenendo ID e stato.
+    /// Le schede radiate tornano tra i radiati
+    pub fn importa(&mut self, reperto: Reperto) -> Result<(), ErroreInventario> {
+        reperto.valida()?;
+        if self.reperti.contains(reperto.id) || self.radiati.contains(reperto.id) {
+            return Err(ErroreInventario::IdDuplicato(reperto.id));
+        }
+        if let Some(identificativo) = &reperto.identificativo {
+            self.controlla_identificativo(identificativo)?;
+            self.registra_codice(identificativo);
+        }
+        self.prossimo_id = self.prossimo_id.max(reperto.id + 1);
+        if reperto.radiazione.is_some() {
+            self.radiati.insert(reperto);
+            return Ok(());
+        }
+        self.cache.aggiungi(&reperto);
+        self.reperti.insert(reperto);
+        Ok(())
+    }
+
+    fn controlla_identificativo(&self, identificativo: &str) -> Result<(), ErroreInventario> {
+        match self.cerca_per_identificativo(identificativo) {
+            Some(altro) => Err(ErroreInventario::DatiNonValidi(format!(
+                "identificativo {} gia assegnato al reperto #{}",
+                identificativo, altro.id
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Cerca un reperto per ID
+    pub fn cerca_per_id(&self, id: u32) -> Result<&Reperto, ErroreInventario> {
+        self.reperti
+            .get(id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))
+    }
+
+    /// Accesso mutabile a un reperto per ID. Conta gia come modifica (la
+    /// versione sale): i controlli che possono rifiutarla vanno fatti prima
+    pub fn cerca_per_id_mut(&mut self, id: u32) -> Result<&mut Reperto, ErroreInventario> {
+        let reperto = self.reperti
+            .get_mut(id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
+        // Il reperto puo cambiare: lo togliamo ora dagli aggregati e lo
+        // ricontiamo alla prossima lettura delle statistiche
+        if !self.cache_invalida && self.in_sospeso.insert(id) {
+            self.cache.togli(reperto);
+        }
+        reperto.versione += 1;
+        Ok(reperto)
+    }
+
+    /// Cerca reperti per nome (ricerca parziale, case-insensitive)
+    pub fn cerca_per_nome(&self, query: &str) -> Vec<&Reperto> {
+        let query_lower = query.to_lowercase();
+        self.reperti
+            .iter()
+            .filter(|r| r.nome.to_lowercase().contains(&query_lower))
+            .collect()
+    }
+
+    /// Cerca reperti per materiale principale
+    pub fn cerca_per_materiale(&self, materiale: &Materiale) -> Vec<&Reperto> {
+        self.reperti
+            .iter()
+            .filter(|r| r.materiale() == Some(materiale))
+            .collect()
+    }
+
+    /// Cerca reperti con almeno un componente del materiale indicato
+    pub fn cerca_per_componente(&self, materiale: &Materiale) -> Vec<&Reperto> {
+        self.reperti
+            .iter()
+            .filter(|r| r.contiene_materiale(materiale))
+            .collect()
+    }
+
+    /// Cerca reperti con una decorazione che soddisfa i criteri indicati
+    pub fn cerca_per_decorazione(
+        &self,
+        tecnica: Option<TecnicaDecorativa>,
+        motivo: Option<MotivoDecorativo>,
+    ) -> Vec<&Reperto> {
+        self.reperti
+            .iter()
+            .filter(|r| {
+                r.decorazioni.iter().any(|d| {
+                    tecnica.is_none_or(|t| d.tecnica == t) && motivo.is_none_or(|m| d.motivo == m)
+                })
+            })
+            .collect()
+    }
+
+    /// Cerca reperti per periodo
+    pub fn cerca_per_periodo(&self, periodo: &Periodo) -> Vec<&Reperto> {
+        self.reperti
+            .iter()
+            .filter(|r| &r.periodo == periodo)
+            .collect()
+    }
+
+    /// Cerca reperti per sito
+    pub fn cerca_per_sito(&self, sito: &str) -> Vec<&Reperto> {
+        let sito_lower = sito.to_lowercase();
+        self.reperti
+            .iter()
+            .filter(|r| r.sito.to_lowercase().contains(&sito_lower))
+            .collect()
+    }
+
+    /// Rimuovi un reperto. Con un operatore collegato (uso multiutente)
+    /// una scheda gia catalogata non sparisce subito: si apre una
+    /// richiesta e si restituisce `RimozioneInAttesa`
+    pub fn rimuovi(&mut self, id: u32) -> Result<Reperto, ErroreInventario> {
+        let stato = self.cerca_per_id(id)?.stato;
+        if let (Some(operatore), false) = (self.operatore, stato == StatoCatalogazione::InIngresso) {
+            let richiesta = match self.rimozioni_in_attesa.values().find(|r| r.reperto == id) {
+                Some(esistente) => esistente.numero,
+                None => {
+                    let numero = self.prossima_richiesta;
+                    self.prossima_richiesta += 1;
+                    self.rimozioni_in_attesa.insert(numero, RichiestaRimozione {
+                        numero,
+                        reperto: id,
+                        richiesta_da: operatore,
+                        quando: chrono::Local::now().naive_local(),
+                    });
+                    numero
+                }
+            };
+            return Err(ErroreInventario::RimozioneInAttesa { id, richiesta });
+        }
+        self.rimuovi_subito(id)
+    }
+
+    /// Richieste di rimozione ancora da decidere
+    pub fn rimozioni_in_attesa(&self) -> impl Iterator<Item = &RichiestaRimozione> {
+        self.rimozioni_in_attesa.values()
+    }
+
+    /// Approva una richiesta di rimozione: l'operatore collegato deve
+    /// essere un responsabile e non chi l'ha aperta
+    pub fn conferma_rimozione(&mut self, richiesta: u32) -> Result<Reperto, ErroreInventario> {
+        let reperto = self.decidi_rimozione(richiesta)?;
+        self.rimuovi_subito(reperto)
+    }
+
+    /// Respinge una richiesta di rimozione: la scheda resta com'e
+    pub fn rifiuta_rimozione(&mut self, richiesta: u32) -> Result<(), ErroreInventario> {
+        self.decidi_rimozione(richiesta).map(|_| ())
+    }
+
+    fn decidi_rimozione(&mut self, richiesta: u32) -> Result<u32, ErroreInventario> {
+        let voce = self.rimozioni_in_attesa.get(&richiesta).ok_or_else(|| {
+            ErroreInventario::DatiNonValidi(format!("richiesta di rimozione n. {} inesistente", richiesta))
+        })?;
+        let Some(operatore) = self.operatore else {
+            return Err(ErroreInventario::PermessoNegato(
+                "serve un operatore collegato per decidere una rimozione".to_string(),
+            ));
+        };
+        let persona = self.persona(operatore)?;
+        if persona.ruolo_utente != Some(RuoloUtente::Responsabile) {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "{} non e responsabile e non puo decidere sulla rimozione del reperto #{}",
+                persona.nome, voce.reperto
+            )));
+        }
+        if operatore == voce.richiesta_da {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "la rimozione del reperto #{} va approvata da un utente diverso da chi l'ha chiesta",
+                voce.reperto
+            )));
+        }
+        Ok(self.rimozioni_in_attesa.remove(&richiesta).map_or(0, |r| r.reperto))
+    }
+
+    fn rimuovi_subito(&mut self, id: u32) -> Result<Reperto, ErroreInventario> {
+        let reperto = self.estrai(id)?;
+        self.annota(id, Operazione::Rimozione);
+        Ok(reperto)
+    }
+
+    /// Toglie un reperto dalla collezione attiva e dagli aggregati
+    fn estrai(&mut self, id: u32) -> Result<Reperto, ErroreInventario> {
+        let reperto = self.reperti
+            .remove(id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
+        if !self.in_sospeso.remove(&id) {
+            self.cache.togli(&reperto);
+        }
+        self.rimozioni_in_attesa.retain(|_, r| r.reperto != id);
+        Ok(reperto)
+    }
+
+    /// Radia un reperto dall'inventario (solo un responsabile, con documento).
+    /// La scheda esce dai conteggi e dalle ricerche ma resta nelle
+    /// esportazioni e negli archivi; senza ultima collocazione indicata
+    /// vale quella registrata sulla scheda
+    pub fn radia(&mut self, id: u32, mut radiazione: Radiazione, ruolo: RuoloUtente) -> Result<(), ErroreInventario> {
+        if ruolo != RuoloUtente::Responsabile {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "{:?} non puo radiare il reperto #{}",
+                ruolo, id
+            )));
+        }
+        if radiazione.documento.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "la radiazione richiede il riferimento al documento che la autorizza".to_string(),
+            ));
+        }
+        let mut reperto = self.estrai(id)?;
+        if radiazione.ultima_collocazione.is_none() {
+            radiazione.ultima_collocazione = reperto.collocazione.clone();
+        }
+        reperto.radiazione = Some(radiazione);
+        reperto.versione += 1;
+        self.radiati.insert(reperto);
+        self.annota(id, Operazione::Radiazione);
+        Ok(())
+    }
+
+    /// Schede dei reperti radiati, in ordine di ID
+    pub fn radiati(&self) -> Vec<&Reperto> {
+        self.radiati.iter().collect()
+    }
+
+    /// Reperti attivi e radiati insieme, in ordine di ID: e cio che
+    /// gli archivi salvano
+    pub fn tutti_compresi_radiati(&self) -> Vec<&Reperto> {
+        let mut reperti: Vec<&Reperto> = self.reperti.iter().chain(self.radiati.iter()).collect();
+        reperti.sort_by_key(|r| r.id);
+        reperti
+    }
+
+    /// Aggregati statistici aggiornati: costo proporzionale ai soli
+    /// reperti modificati dall'ultima lettura
+    pub fn statistiche(&mut self) -> &CacheStatistiche {
+        if self.cache_invalida {
+            self.cache = CacheStatistiche::da_reperti(self.reperti.iter());
+            self.cache_invalida = false;
+        } else {
+            for id in self.in_sospeso.drain() {
+                if let Some(reperto) = self.reperti.get(id) {
+                    self.cache.aggiungi(reperto);
+                }
+            }
+        }
+        self.in_sospeso.clear();
+        &self.cache
+    }
+
+    /// Aggiungi una nota a un reperto
+    pub fn aggiungi_nota(&mut self, id: u32, nota: &str) -> Result<(), ErroreInventario> {
+        let reperto = self.reperti
+            .get_mut(id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
+        reperto.note.push(nota.into());
+        Ok(())
+    }
+
+    /// Porta una scheda in un nuovo stato del flusso di catalogazione,
+    /// verificando che la transizione esista e che il ruolo sia autorizzato
+    pub fn cambia_stato(
+        &mut self,
+        id: u32,
+        nuovo: StatoCatalogazione,
+        ruolo: RuoloUtente,
+    ) -> Result<(), ErroreInventario> {
+        if nuovo == StatoCatalogazione::Pubblicato {
+            self.verifica_estremi_scavo(self.cerca_per_id(id)?)?;
+        }
+        // I controlli in sola lettura: un cambio rifiutato non tocca la versione
+        let attuale = self.cerca_per_id(id)?.stato;
+        let ammessi = attuale.ruoli_per_transizione(nuovo);
+        if ammessi.is_empty() {
+            return Err(ErroreInventario::TransizioneNonAmmessa { da: attuale, a: nuovo });
+        }
+        if !ammessi.contains(&ruolo) {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "{:?} non puo portare il reperto #{} da '{}' a '{}'",
+                ruolo, id, attuale, nuovo
+            )));
+        }
+        self.cerca_per_id_mut(id)?.stato = nuovo;
+        self.annota(id, Operazione::CambioStato);
+        Ok(())
+    }
+
+    /// Modifica una scheda rispettando i permessi del suo stato
+    pub fn modifica_come<F>(&mut self, id: u32, ruolo: RuoloUtente, modifica: F) -> Result<(), ErroreInventario>
+    where
+        F: FnOnce(&mut Reperto),
+    {
+        let stato = self.cerca_per_id(id)?.stato;
+        if !stato.ruoli_per_modifica().contains(&ruolo) {
+            return Err(ErroreInventario::PermessoNegato(format!(
+                "{:?} non puo modificare il reperto #{} in stato '{}'",
+                ruolo, id, stato
+            )));
+        }
+        modifica(self.cerca_per_id_mut(id)?);
+        self.annota(id, Operazione::Modifica);
+        Ok(())
+    }
+
+    /// Reperti in un determinato stato di catalogazione
+    pub fn in_stato(&self, stato: StatoCatalogazione) -> Vec<&Reperto> {
+        self.tutti().into_iter().filter(|r| r.stato == stato).collect()
+    }
+
+    /// Schede catalogate in attesa di verifica
+    pub fn in_attesa_di_verifica(&self) -> Vec<&Reperto> {
+        self.in_stato(StatoCatalogazione::Catalogato)
+    }
+
+    #[cfg(feature = "geo")]
+    /// Definisce (o sostituisce) la griglia di scavo di un sito
+    pub fn definisci_griglia(&mut self, sito: &str, griglia: GrigliaScavo) -> Result<(), ErroreInventario> {
+        if !griglia.lato_m.is_finite() || griglia.lato_m <= 0.0 {
+            return Err(ErroreInventario::DatiNonValidi(
+                "il lato dei quadrati deve essere positivo".to_string(),
+            ));
+        }
+        self.griglie.insert(sito.to_string(), griglia);
+        Ok(())
+    }
+
+    /// Registra (o aggiorna) una campagna di scavo e i suoi estremi autorizzativi
+    pub fn registra_campagna_scavo(&mut self, campagna: CampagnaScavo) -> Result<(), ErroreInventario> {
+        if campagna.codice.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "la campagna di scavo deve avere un codice".to_string(),
+            ));
+        }
+        self.campagne_scavo.insert(campagna.codice.clone(), campagna);
+        Ok(())
+    }
+
+    pub fn campagna_scavo(&self, codice: &str) -> Option<&CampagnaScavo> {
+        self.campagne_scavo.get(codice)
+    }
+
+    /// Registra un prestito: i reperti devono essere in inventario
+    pub fn registra_prestito(&mut self, prestito: Prestito) -> Result<(), ErroreInventario> {
+        if prestito.data_fine < prestito.data_inizio {
+            return Err(ErroreInventario::DatiNonValidi(
+                "il prestito finisce prima di cominciare".to_string(),
+            ));
+        }
+        for id in &prestito.reperti {
+            self.cerca_per_id(*id)?;
+        }
+        self.prestiti.push(prestito);
+        Ok(())
+    }
+
+    /// I prestiti registrati, in ordine di registrazione
+    pub fn prestiti(&self) -> &[Prestito] {
+        &self.prestiti
+    }
+
+    /// Collega un reperto a una campagna gia registrata
+    pub fn assegna_campagna_scavo(&mut self, id: u32, codice: &str) -> Result<(), ErroreInventario> {
+        if !self.campagne_scavo.contains_key(codice) {
+            return Err(ErroreInventario::DatiNonValidi(format!("campagna di scavo '{}' non registrata", codice)));
+        }
+        self.cerca_per_id_mut(id)?.campagna_scavo = Some(codice.to_string());
+        Ok(())
+    }
+
+    /// Un reperto da scavo si pubblica solo con campagna registrata e
+    /// concessione, autorizzazione e decreto di deposito compilati
+    fn verifica_estremi_scavo(&self, reperto: &Reperto) -> Result<(), ErroreInventario> {
+        if !reperto.da_scavo() {
+            return Ok(());
+        }
+        let campagna = reperto
+            .campagna_scavo
+            .as_deref()
+            .and_then(|c| self.campagne_scavo.get(c))
+            .ok_or_else(|| {
+                ErroreInventario::DatiNonValidi(format!(
+                    "il reperto #{} proviene da scavo ma non e collegato a una campagna registrata",
+                    reperto.id
+                ))
+            })?;
+        let mancanti = campagna.mancanti();
+        if !mancanti.is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "per pubblicare il reperto #{} la campagna {} deve indicare: {}",
+                reperto.id,
+                campagna.codice,
+                mancanti.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Campagne di scavo dei reperti indicati, per le esportazioni
+    fn campagne_di<'a>(&'a self, reperti: impl IntoIterator<Item = &'a Reperto>) -> Vec<&'a CampagnaScavo> {
+        let codici: BTreeSet<&str> = reperti.into_iter().filter_map(|r| r.campagna_scavo.as_deref()).collect();
+        codici.into_iter().filter_map(|c| self.campagne_scavo.get(c)).collect()
+    }
+
+    #[cfg(feature = "geo")]
+    /// Converte su richiesta la posizione di scavo di un reperto in
+    /// coordinate assolute, usando la griglia del suo sito
+    pub fn coordinate_da_griglia(&self, id: u32) -> Result<Coordinate, ErroreInventario> {
+        let reperto = self.cerca_per_id(id)?;
+        let posizione = reperto.posizione_scavo.as_ref().ok_or_else(|| {
+            ErroreInventario::DatiNonValidi(format!("il reperto #{} non ha posizione di scavo", id))
+        })?;
+        let griglia = self.griglie.get(&reperto.sito).ok_or_else(|| {
+            ErroreInventario::DatiNonValidi(format!("nessuna griglia definita per '{}'", reperto.sito))
+        })?;
+        griglia.a_coordinate(posizione)
+    }
+
+    #[cfg(feature = "geo")]
+    /// Definisce il caposaldo altimetrico di un sito
+    pub fn definisci_caposaldo(&mut self, sito: &str, caposaldo: Caposaldo) {
+        self.caposaldi.insert(sito.to_string(), caposaldo);
+    }
+
+    #[cfg(feature = "geo")]
+    /// Imposta la posizione di scavo di un reperto, validando quota e
+    /// profondita rispetto al caposaldo del sito (se definito)
+    pub fn imposta_posizione_scavo(&mut self, id: u32, posizione: PosizioneScavo) -> Result<(), ErroreInventario> {
+        GrigliaScavo::scomponi_quadrato(&posizione.quadrato)?;
+        let sito = self.cerca_per_id(id)?.sito.clone();
+        if let Some(caposaldo) = self.caposaldi.get(&sito) {
+            caposaldo.valida(&posizione)?;
+        }
+        self.cerca_per_id_mut(id)?.posizione_scavo = Some(posizione);
+        Ok(())
+    }
+
+    #[cfg(feature = "geo")]
+    /// Reperti di un sito proiettati su un transetto, con la loro quota.
+    /// Le coordinate sono quelle registrate o, in mancanza, quelle di griglia.
+    pub fn punti_sezione(&self, sito: &str, transetto: &Transetto) -> Result<Vec<PuntoSezione>, ErroreInventario> {
+        let caposaldo = self.caposaldi.get(sito).ok_or_else(|| {
+            ErroreInventario::DatiNonValidi(format!("nessun caposaldo definito per '{}'", sito))
+        })?;
+        let mut punti = Vec::new();
+        for r in self.tutti().into_iter().filter(|r| r.sito == sito) {
+            let Some(posizione) = &r.posizione_scavo else { continue };
+            let Some(quota_m) = caposaldo.quota(posizione) else { continue };
+            let coordinate = match self.coordinate_da_griglia(r.id) {
+                Ok(c) => c,
+                Err(_) => match &r.coordinate {
+                    Some(c) => c.clone(),
+                    None => continue,
+                },
+            };
+            if let Some(distanza_m) = transetto.proietta(&coordinate) {
+                punti.push(PuntoSezione { id_reperto: r.id, distanza_m, quota_m });
+            }
+        }
+        punti.sort_by(|a, b| a.distanza_m.total_cmp(&b.distanza_m));
+        Ok(punti)
+    }
+
+    /// Registra il prelievo di un campione da un reperto e/o da un contesto
+    pub fn preleva_campione(
+        &mut self,
+        tipo: TipoCampione,
+        id_reperto: Option<u32>,
+        contesto: Option<&str>,
+        prelevato_il: NaiveDate,
+        destinazione: &str,
+    ) -> Result<u32, ErroreInventario> {
+        if id_reperto.is_none() && contesto.is_none() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "un campione deve essere legato a un reperto o a un contesto".to_string(),
+            ));
+        }
+        if let Some(id) = id_reperto {
+            self.cerca_per_id(id)?;
+        }
+        let id = self.campioni.keys().next_back().map_or(1, |ultimo| ultimo + 1);
+        self.campioni.insert(id, Campione {
+            id,
+            tipo,
+            id_reperto,
+            contesto: contesto.map(String::from),
+            prelevato_il,
+            destinazione: destinazione.to_string(),
+            stato: StatoCampione::Prelevato,
+        });
+        Ok(id)
+    }
+
+    fn campione_mut(&mut self, id: u32) -> Result<&mut Campione, ErroreInventario> {
+        self.campioni
+            .get_mut(&id)
+            .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("campione C{:03} inesistente", id)))
+    }
+
+    /// Segna un campione come inviato al laboratorio
+    pub fn invia_campione(&mut self, id: u32, laboratorio: &str, data: NaiveDate) -> Result<(), ErroreInventario> {
+        let campione = self.campione_mut(id)?;
+        if campione.stato != StatoCampione::Prelevato {
+            return Err(ErroreInventario::DatiNonValidi(format!("campione C{:03} gia inviato", id)));
+        }
+        if data < campione.prelevato_il {
+            return Err(ErroreInventario::DatiNonValidi(
+                "invio precedente al prelievo".to_string(),
+            ));
+        }
+        campione.stato = StatoCampione::Inviato { laboratorio: laboratorio.to_string(), data };
+        Ok(())
+    }
+
+    /// Registra l'esito delle analisi per un campione inviato
+    pub fn registra_esito(&mut self, id: u32, esito: &str, data: NaiveDate) -> Result<(), ErroreInventario> {
+        let campione = self.campione_mut(id)?;
+        let StatoCampione::Inviato { laboratorio, data: inviato_il } = &campione.stato else {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "campione C{:03} non risulta inviato ({})",
+                id, campione.stato
+            )));
+        };
+        if data < *inviato_il {
+            return Err(ErroreInventario::DatiNonValidi(
+                "esito precedente all'invio".to_string(),
+            ));
+        }
+        campione.stato = StatoCampione::RisultatiRicevuti {
+            laboratorio: laboratorio.clone(),
+            data,
+            esito: esito.to_string(),
+        };
+        Ok(())
+    }
+
+    /// Tutti i campioni, in ordine di ID
+    pub fn campioni(&self) -> impl Iterator<Item = &Campione> {
+        self.campioni.values()
+    }
+
+    /// Campioni prelevati da un reperto
+    pub fn campioni_di(&self, id_reperto: u32) -> Vec<&Campione> {
+        self.campioni().filter(|c| c.id_reperto == Some(id_reperto)).collect()
+    }
+
+    /// Registra una nuova persona in anagrafica e ne restituisce l'ID
+    pub fn registra_persona(&mut self, nome: &str, ruoli: Vec<RuoloPersona>) -> Result<u32, ErroreInventario> {
+        if nome.trim().is_empty() {
+            return Err(ErroreInventario::NomeVuoto);
+        }
+        let id = self.prossimo_id_persona;
+        self.persone.insert(id, Persona { id, nome: nome.to_string(), ruoli, orcid: None, ruolo_utente: None });
+        self.prossimo_id_persona += 1;
+        Ok(id)
+    }
+
+    /// Permessi della persona sul flusso di catalogazione (per esempio
+    /// decidere le rimozioni), validi quando accede all'inventario
+    pub fn assegna_ruolo_utente(&mut self, persona: u32, ruolo: RuoloUtente) -> Result<(), ErroreInventario> {
+        self.persone
+            .get_mut(&persona)
+            .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("persona #{} non registrata", persona)))?
+            .ruolo_utente = Some(ruolo);
+        Ok(())
+    }
+
+    /// Cerca una persona per ID
+    pub fn persona(&self, id: u32) -> Result<&Persona, ErroreInventario> {
+        self.persone
+            .get(&id)
+            .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("persona #{} non registrata", id)))
+    }
+
+    /// Tutte le persone registrate, in ordine di ID
+    pub fn persone(&self) -> impl Iterator<Item = &Persona> {
+        self.persone.values()
+    }
+
+    /// Associa un iD ORCID a una persona, dopo averne verificato la cifra di controllo
+    pub fn imposta_orcid(&mut self, persona: u32, orcid: &str) -> Result<(), ErroreInventario> {
+        if !orcid_valido(orcid) {
+            return Err(ErroreInventario::DatiNonValidi(format!("iD ORCID non valido: '{}'", orcid)));
+        }
+        self.persona(persona)?;
+        if let Some(p) = self.persone.get_mut(&persona) {
+            p.orcid = Some(orcid.to_string());
+        }
+        Ok(())
+    }
+
+    /// Cerca l'iD ORCID delle persone che ancora non lo hanno.
+    /// Restituisce quante persone sono state completate
+    pub fn completa_orcid(
+        &mut self,
+        cerca: impl Fn(&Persona) -> Result<Option<String>, ErroreInventario>,
+    ) -> Result<usize, ErroreInventario> {
+        let senza: Vec<Persona> = self.persone().filter(|p| p.orcid.is_none()).cloned().collect();
+        let mut completate = 0;
+        for persona in senza {
+            if let Some(orcid) = cerca(&persona)? {
+                self.imposta_orcid(persona.id, &orcid)?;
+                completate += 1;
+            }
+        }
+        Ok(completate)
+    }
+
+    /// Collega una persona registrata: da qui in poi le operazioni
+    /// sull'inventario finiscono nel registro a suo nome
+    pub fn accedi(&mut self, persona: u32) -> Result<(), ErroreInventario> {
+        self.persona(persona)?;
+        self.operatore = Some(persona);
+        Ok(())
+    }
+
+    /// Scollega l'operatore: le operazioni successive non vengono annotate
+    pub fn esci(&mut self) {
+        self.operatore = None;
+    }
+
+    /// Registro delle attivita, in ordine di inserimento
+    pub fn registro(&self) -> &[VoceRegistro] {
+        &self.registro
+    }
+
+    /// Reperti aggiunti e quando, in ordine di ID (chi li ha aggiunti sta
+    /// nel registro, se c'era un operatore collegato)
+    pub fn creazioni(&self) -> impl Iterator<Item = (u32, NaiveDateTime)> + '_ {
+        self.creazioni.iter().map(|(id, quando)| (*id, *quando))
+    }
+
+    /// Reinserisce una voce di registro (es. da un archivio precedente)
+    pub fn registra(&mut self, voce: VoceRegistro) -> Result<(), ErroreInventario> {
+        self.persona(voce.persona)?;
+        self.registro.push(voce);
+        Ok(())
+    }
+
+    fn annota(&mut self, reperto: u32, operazione: Operazione) {
+        if let Some(persona) = self.operatore {
+            self.registro.push(VoceRegistro {
+                quando: chrono::Local::now().naive_local(),
+                persona,
+                reperto,
+                operazione,
+            });
+        }
+    }
+
+    /// Aggiungi una nota firmata da una persona registrata
+    pub fn aggiungi_nota_di(&mut self, id: u32, autore: u32, testo: &str) -> Result<(), ErroreInventario> {
+        self.persona(autore)?;
+        let reperto = self.cerca_per_id_mut(id)?;
+        reperto.note.push(Nota { testo: testo.to_string(), autore: Some(autore) });
+        Ok(())
+    }
+
+    /// Aggiunge una voce alla bibliografia di un reperto
+    pub fn aggiungi_bibliografia(
+        &mut self,
+        id: u32,
+        mut riferimento: RiferimentoBibliografico,
+    ) -> Result<(), ErroreInventario> {
+        if riferimento.citazione.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi("la citazione e vuota".to_string()));
+        }
+        if let Some(doi) = &riferimento.doi {
+            let doi = normalizza_doi(doi);
+            if !doi.starts_with("10.") || !doi.contains('/') {
+                return Err(ErroreInventario::DatiNonValidi(format!("DOI non valido: '{}'", doi)));
+            }
+            riferimento.doi = Some(doi);
+        }
+        self.cerca_per_id_mut(id)?.bibliografia.push(riferimento);
+        Ok(())
+    }
+
+    /// Completa con i metadati dell'opera le voci bibliografiche che hanno
+    /// un DOI non ancora risolto. Restituisce quante voci sono state completate
+    pub fn completa_bibliografia(
+        &mut self,
+        risolvi: impl Fn(&str) -> Result<Option<Opera>, ErroreInventario>,
+    ) -> Result<usize, ErroreInventario> {
+        let da_risolvere: Vec<(u32, usize, String)> = self
+            .reperti
+            .iter()
+            .flat_map(|r| {
+                r.bibliografia.iter().enumerate().filter_map(move |(i, b)| match (&b.doi, &b.opera) {
+                    (Some(doi), None) => Some((r.id, i, doi.clone())),
+                    _ => None,
+                })
+            })
+            .collect();
+        let mut completate = 0;
+        for (id, indice, doi) in da_risolvere {
+            if let Some(opera) = risolvi(&doi)? {
+                self.cerca_per_id_mut(id)?.bibliografia[indice].opera = Some(opera);
+                completate += 1;
+            }
+        }
+        Ok(completate)
+    }
+
+    /// Attribuisce il rinvenimento di un reperto a una persona registrata
+    pub fn attribuisci_rinvenimento(&mut self, id: u32, persona: u32) -> Result<(), ErroreInventario> {
+        self.persona(persona)?;
+        self.cerca_per_id_mut(id)?.rinvenuto_da = Some(persona);
+        Ok(())
+    }
+
+    /// Collega un allegato a un reperto
+    pub fn aggiungi_allegato(&mut self, id: u32, allegato: Allegato) -> Result<(), ErroreInventario> {
+        let reperto = self.reperti
+            .get_mut(id)
+            .ok_or(ErroreInventario::RepertoNonTrovato(id))?;
+        reperto.allegati.push(allegato);
+        Ok(())
+    }
+
+    /// Aggiunge un evento alla catena di provenienza.
+    /// Gli eventi devono arrivare in ordine cronologico e la catena
+    /// deve cominciare dal rinvenimento o da un'acquisizione.
+    pub fn aggiungi_evento_provenienza(
+        &mut self,
+        id: u32,
+        evento: EventoProvenienza,
+    ) -> Result<(), ErroreInventario> {
+        match self.cerca_per_id(id)?.provenienza.last() {
+            Some(ultimo) if evento.data < ultimo.data => {
+                return Err(ErroreInventario::DatiNonValidi(format!(
+                    "evento del {} precedente all'ultimo registrato ({})",
+                    evento.data, ultimo.data
+                )));
+            }
+            None if !matches!(
+                evento.tipo,
+                TipoEventoProvenienza::Scavo | TipoEventoProvenienza::Acquisizione
+            ) =>
+            {
+                return Err(ErroreInventario::DatiNonValidi(
+                    "la catena di provenienza deve iniziare con scavo o acquisizione".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        self.cerca_per_id_mut(id)?.provenienza.push(evento);
+        Ok(())
+    }
+
+    /// Registra una nuova stima di valore, conservando lo storico
+    pub fn aggiungi_stima(&mut self, id: u32, stima: Stima) -> Result<(), ErroreInventario> {
+        if !stima.valore.is_finite() || stima.valore < 0.0 {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "valore di stima non valido: {}",
+                stima.valore
+            )));
+        }
+        if stima.valuta.len() != 3 || !stima.valuta.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "valuta '{}' non e un codice ISO 4217",
+                stima.valuta
+            )));
+        }
+        self.cerca_per_id_mut(id)?.stime.push(stima);
+        Ok(())
+    }
+
+    /// Registra un'osservazione traceologica
+    pub fn aggiungi_osservazione(&mut self, id: u32, osservazione: Osservazione) -> Result<(), ErroreInventario> {
+        if osservazione.localizzazione.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "indicare la zona osservata".to_string(),
+            ));
+        }
+        self.cerca_per_id_mut(id)?.osservazioni.push(osservazione);
+        Ok(())
+    }
+
+    /// Reperti con almeno un'osservazione con l'interpretazione indicata
+    pub fn cerca_per_traccia(&self, interpretazione: InterpretazioneTraccia) -> Vec<&Reperto> {
+        self.tutti()
+            .into_iter()
+            .filter(|r| r.osservazioni.iter().any(|o| o.interpretazione == interpretazione))
+            .collect()
+    }
+
+    /// Registra un intervento di restauro con i prodotti impiegati
+    pub fn registra_intervento(&mut self, id: u32, intervento: Intervento) -> Result<(), ErroreInventario> {
+        if intervento.descrizione.trim().is_empty() || intervento.restauratore.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "descrizione e restauratore sono obbligatori".to_string(),
+            ));
+        }
+        if let Some(p) = intervento.prodotti.iter().find(|p| p.lotto.trim().is_empty()) {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "lotto mancante per il prodotto '{}'",
+                p.prodotto
+            )));
+        }
+        let reperto = self.cerca_per_id_mut(id)?;
+        let posizione = reperto.interventi.partition_point(|i| i.data <= intervento.data);
+        reperto.interventi.insert(posizione, intervento);
+        Ok(())
+    }
+
+    /// Sposta fisicamente un reperto registrando il movimento
+    /// e aggiornando la collocazione attuale
+    pub fn sposta(
+        &mut self,
+        id: u32,
+        a: &str,
+        quando: NaiveDateTime,
+        responsabile: &str,
+        motivo: Option<&str>,
+    ) -> Result<(), ErroreInventario> {
+        if a.trim().is_empty() || responsabile.trim().is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(
+                "destinazione e responsabile sono obbligatori".to_string(),
+            ));
+        }
+        if let Some(ultimo) = self.cerca_per_id(id)?.movimenti.last() {
+            if quando < ultimo.quando {
+                return Err(ErroreInventario::DatiNonValidi(format!(
+                    "movimento del {} precedente all'ultimo registrato ({})",
+                    quando, ultimo.quando
+                )));
+            }
+        }
+        let reperto = self.cerca_per_id_mut(id)?;
+        reperto.movimenti.push(Movimento {
+            quando,
+            da: reperto.collocazione.clone(),
+            a: a.to_string(),
+            responsabile: responsabile.to_string(),
+            motivo: motivo.map(String::from),
+        });
+        reperto.collocazione = Some(a.to_string());
+        Ok(())
+    }
+
+    /// Applica `modifica` a tutti i reperti, in ordine di ID; la versione
+    /// sale solo su quelli che ne escono davvero cambiati
+    pub fn modifica_tutti(&mut self, mut modifica: impl FnMut(&mut Reperto)) {
+        self.cache_invalida = true;
+        for r in self.reperti.iter_mut() {
+            let prima = serde_json::to_value(&*r).ok();
+            modifica(r);
+            if serde_json::to_value(&*r).ok() != prima {
+                r.versione += 1;
+            }
+        }
+    }
+
+    /// Apre una modifica: copia di lavoro del reperto con la versione letta
+    pub fn inizia_modifica(&self, id: u32) -> Result<Modifica, ErroreInventario> {
+        let originale = self.cerca_per_id(id)?.clone();
+        Ok(Modifica { bozza: originale.clone(), originale })
+    }
+
+    /// Salva la bozza se nel frattempo nessuno ha toccato il reperto,
+    /// altrimenti restituisce `Conflitto` e lascia tutto com'era.
+    /// Restituisce la nuova versione
+    pub fn conferma_modifica(&mut self, modifica: Modifica) -> Result<u64, ErroreInventario> {
+        let Modifica { originale, mut bozza } = modifica;
+        let id = originale.id;
+        let attuale = self.cerca_per_id(id)?;
+        // Confronto anche sul contenuto: copre le modifiche fatte senza passare da qui
+        if attuale.versione != originale.versione
+            || serde_json::to_value(attuale)? != serde_json::to_value(&originale)?
+        {
+            return Err(ErroreInventario::Conflitto {
+                id,
+                letta: originale.versione,
+                attuale: attuale.versione,
+            });
+        }
+        bozza.id = id;
+        bozza.valida()?;
+        bozza.versione = attuale.versione + 1;
+        let versione = bozza.versione;
+        *self.cerca_per_id_mut(id)? = bozza;
+        self.annota(id, Operazione::Modifica);
+        Ok(versione)
+    }
+
+    /// Tutti i reperti, in ordine di ID
+    pub fn tutti(&self) -> Vec<&Reperto> {
+        self.reperti.iter().collect()
+    }
+
+    /// Tutti i reperti secondo l'ordinamento indicato (a parita, per ID)
+    pub fn tutti_ordinati(&self, ordinamento: Ordinamento) -> Vec<&Reperto> {
+        let mut reperti = self.tutti();
+        match ordinamento {
+            Ordinamento::Id => {}
+            Ordinamento::Nome => reperti.sort_by(|a, b| a.nome.cmp(&b.nome)),
+            Ordinamento::Periodo => reperti.sort_by(|a, b| a.periodo.cmp(&b.periodo)),
+            Ordinamento::Sito => reperti.sort_by(|a, b| a.sito.cmp(&b.sito)),
+        }
+        reperti
+    }
+
+    /// Numero totale di reperti
+    pub fn totale(&self) -> usize {
+        self.reperti.len()
+    }
+
+    /// Impronta SHA-256 della serializzazione canonica (JSON compatto, in
+    /// ordine di ID) di quello che esporta `to_json`: reperti, radiati e
+    /// campagne di scavo. Identifica esattamente lo stato del catalogo
+    pub fn impronta_snapshot(&self) -> Result<String, serde_json::Error> {
+        let (reperti, radiati) = (self.tutti(), self.radiati());
+        let campagne = self.campagne_di(reperti.iter().chain(&radiati).copied());
+        let canonico = serde_json::to_vec(&(&reperti, &radiati, &campagne))?;
+        Ok(Sha256::digest(&canonico).iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    fn intestazione(&self, profilo: &str, reperti: usize, radiati: usize) -> Result<IntestazioneEsportazione, serde_json::Error> {
+        Ok(IntestazioneEsportazione {
+            formato: "bronzeaxe".to_string(),
+            versione: 1,
+            snapshot: self.impronta_snapshot()?,
+            profilo: profilo.to_string(),
+            reperti,
+            radiati,
+        })
+    }
+
+    /// Vero se l'esportazione JSON e stata prodotta dallo stato attuale del catalogo
+    pub fn verifica_snapshot(&self, esportazione: &str) -> Result<bool, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct ConIntestazione {
+            intestazione: IntestazioneEsportazione,
+        }
+        let letta: ConIntestazione = serde_json::from_str(esportazione)?;
+        Ok(letta.intestazione.snapshot == self.impronta_snapshot()?)
+    }
+
+    /// Il dossier dei reperti di un sito (vedi `dossier`), da esportare in
+    /// Markdown o PDF. I reperti radiati non ci sono.
+    pub fn esporta_dossier(&self, sito: &str) -> Result<Dossier<'_>, ErroreInventario> {
+        let reperti: Vec<&Reperto> = self.reperti.iter().filter(|r| r.sito == sito).collect();
+        if reperti.is_empty() {
+            return Err(ErroreInventario::DatiNonValidi(format!("nessun reperto del sito '{}'", sito)));
+        }
+        Ok(Dossier::nuovo(sito, reperti))
+    }
+
+    /// Serializza l'inventario in JSON, in ordine di ID
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        self.to_json_ordinato(Ordinamento::Id)
+    }
+
+    /// Serializza l'inventario in JSON nell'ordine indicato
+    pub fn to_json_ordinato(&self, ordinamento: Ordinamento) -> Result<String, serde_json::Error> {
+        let reperti = self.tutti_ordinati(ordinamento);
+        let radiati = self.radiati();
+        let campagne = self.campagne_di(reperti.iter().chain(&radiati).copied());
+        serde_json::to_string_pretty(&serde_json::json!({
+            "intestazione": self.intestazione("completo", reperti.len(), radiati.len())?,
+            "campagne_scavo": campagne,
+            "reperti": reperti,
+            "radiati": radiati,
+        }))
+    }
+
+    /// Serializza solo cio che e pubblicabile alla data indicata:
+    /// i reperti e gli allegati ancora sotto embargo vengono esclusi.
+    pub fn to_json_pubblicabile(&self, oggi: NaiveDate) -> Result<String, serde_json::Error> {
+        let profilo = ProfiloVisibilita {
+            rispetta_embargo: true,
+            ..ProfiloVisibilita::interno()
+        };
+        self.to_json_con_profilo(&profilo, oggi)
+    }
+
+    /// Esporta i reperti georeferenziati come GeoJSON FeatureCollection,
+    /// applicando il profilo di visibilita (e l'eventuale offuscamento)
+    pub fn to_geojson(
+        &self,
+        profilo: &ProfiloVisibilita,
+        oggi: NaiveDate,
+    ) -> Result<String, serde_json::Error> {
+        let features: Vec<serde_json::Value> = self
+            .tutti_compresi_radiati()
+            .into_iter()
+            .filter_map(|r| profilo.applica(r, oggi))
+            .filter_map(|r| {
+                let c = r.coordinate.as_ref()?;
+                Some(serde_json::json!({
+                    "type": "Feature",
+                    "id": r.id,
+                    "geometry": {
+                        "type": "Point",
+                        // GeoJSON vuole [longitudine, latitudine]
+                        "coordinates": [c.longitudine, c.latitudine],
+                    },
+                    "properties": {
+                        "nome": r.nome,
+                        "materiale": r.materiale().map(|m| m.to_string()),
+                        "periodo": r.periodo.to_string(),
+                        "sito": r.sito,
+                        "radiato": r.radiazione.as_ref().map(|d| d.motivo.to_string()),
+                    },
+                }))
+            })
+            .collect();
+        serde_json::to_string_pretty(&serde_json::json!({
+            "type": "FeatureCollection",
+            "bronzeaxe": self.intestazione(&profilo.nome, features.len(), 0)?,
+            "features": features,
+        }))
+    }
+
+    /// Serializza l'inventario filtrando reperti e campi secondo il profilo
+    pub fn to_json_con_profilo(
+        &self,
+        profilo: &ProfiloVisibilita,
+        oggi: NaiveDate,
+    ) -> Result<String, serde_json::Error> {
+        let reperti: Vec<Reperto> = self
+            .tutti()
+            .into_iter()
+            .filter_map(|r| profilo.applica(r, oggi))
+            .collect();
+        let radiati: Vec<Reperto> = self
+            .radiati()
+            .into_iter()
+            .filter_map(|r| profilo.applica(r, oggi))
+            .collect();
+        let campagne = self.campagne_di(reperti.iter().chain(&radiati));
+        serde_json::to_string_pretty(&serde_json::json!({
+            "intestazione": self.intestazione(&profilo.nome, reperti.len(), radiati.len())?,
+            "campagne_scavo": campagne,
+            "reperti": reperti,
+            "radiati": radiati,
+        }))
+    }
+}
@@ -0,0 +1,105 @@
+// ============================================================================
+// MODULO: MAGAZZINO DEI REPERTI
+// ============================================================================
+// L'inventario decide gli id, valida le schede e fa le ricerche; dove
+// stanno i reperti lo decide il magazzino. Le operazioni sono quelle di
+// una mappa ordinata per id:
+//
+//   get(id) / get_mut(id)   -> Option<&Reperto> / Option<&mut Reperto>
+//   insert(r)               sostituisce il reperto con lo stesso id
+//   remove(id)              -> Option<Reperto>
+//   iter() / iter_mut()     in ordine di id
+//   len()
+//
+// `Memoria` (una BTreeMap) e il magazzino predefinito: `Inventario` senza
+// parametri e `Inventario<Memoria>`. Un magazzino su disco o in un
+// database implementa lo stesso trait e si passa a
+// `Inventario::con_magazzini`; ricerche e statistiche non cambiano.
+//
+// Il magazzino non va confuso con l'`Archivio` del capitolo 9, che salva
+// e ricarica l'inventario intero (file JSON, cartella, SQLite...): i
+// metodi qui non falliscono, come quelli di una mappa, e un magazzino
+// che scrive su disco segnala gli errori quando si salva.
+// ============================================================================
+
+//! Il trait `Magazzino` e il magazzino in memoria usato di default.
+
+use std::collections::BTreeMap;
+
+use super::modelli::Reperto;
+
+/// Dove un inventario tiene i suoi reperti, indicizzati per id.
+pub trait Magazzino {
+    fn get(&self, id: u32) -> Option<&Reperto>;
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Reperto>;
+
+    /// Aggiunge il reperto, sostituendo quello con lo stesso id
+    fn insert(&mut self, reperto: Reperto);
+
+    fn remove(&mut self, id: u32) -> Option<Reperto>;
+
+    /// Tutti i reperti, in ordine di id
+    fn iter(&self) -> Box<dyn Iterator<Item = &Reperto> + '_>;
+
+    /// Tutti i reperti in scrittura, in ordine di id
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Reperto> + '_>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains(&self, id: u32) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+/// I reperti in una `BTreeMap`: il magazzino predefinito.
+///
+/// ```
+/// use rust_tutorial::catalogo::magazzino::{Magazzino, Memoria};
+/// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+///
+/// let mut memoria = Memoria::default();
+/// let mut ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+/// ascia.id = 4;
+/// memoria.insert(ascia);
+/// assert_eq!(memoria.get(4).unwrap().nome, "Ascia");
+/// assert_eq!(memoria.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Memoria {
+    reperti: BTreeMap<u32, Reperto>,
+}
+
+impl Magazzino for Memoria {
+    fn get(&self, id: u32) -> Option<&Reperto> {
+        self.reperti.get(&id)
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Reperto> {
+        self.reperti.get_mut(&id)
+    }
+
+    fn insert(&mut self, reperto: Reperto) {
+        self.reperti.insert(reperto.id, reperto);
+    }
+
+    fn remove(&mut self, id: u32) -> Option<Reperto> {
+        self.reperti.remove(&id)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Reperto> + '_> {
+        Box::new(self.reperti.values())
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Reperto> + '_> {
+        Box::new(self.reperti.values_mut())
+    }
+
+    fn len(&self) -> usize {
+        self.reperti.len()
+    }
+}
@@ -0,0 +1,430 @@
+// ============================================================================
+// MODULO: ALLEGATI E MEDIA
+// ============================================================================
+// Foto, modelli 3D e documenti stanno su disco accanto all'archivio; la
+// scheda ne conserva percorso, impronta SHA-256 e dimensione, cosi si
+// accorge se un file e stato cambiato o spostato. Le immagini hanno due
+// miniature (piccola e media) generate all'allegato. Per ora le usa solo
+// il dossier in Markdown: nel PDF la foto resta una riga con il nome del
+// file, e non c'e un'esportazione HTML o IIIF.
+// ============================================================================
+
+//! Allegati dei reperti: impronte, miniature, modelli 3D e documenti.
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::{
+    Allegato, CategoriaDocumento, FormatoDocumento, FormatoModello, Miniature, Modello3D,
+    TipoAllegato,
+};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Calcola l'impronta SHA-256 di un file leggendolo a blocchi
+pub fn impronta_file(percorso: &Path) -> io::Result<String> {
+    let mut file = File::open(percorso)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let letti = file.read(&mut buffer)?;
+        if letti == 0 {
+            break;
+        }
+        hasher.update(&buffer[..letti]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Il percorso come lo conserva la scheda: `Allegato` sta in bronzeaxe-core,
+/// che non ha `PathBuf`, e lo tiene come `String`
+fn in_testo(percorso: &Path) -> String {
+    percorso.to_string_lossy().into_owned()
+}
+
+/// Lato massimo (in pixel) delle miniature piccola e media
+pub const LATO_PICCOLA: u32 = 160;
+pub const LATO_MEDIA: u32 = 640;
+
+/// Crea un allegato registrando impronta e dimensione del file.
+/// Per le immagini vengono generate anche le miniature; senza la feature
+/// `images` l'immagine si allega lo stesso, senza miniature, e le
+/// aggiunge poi `rigenera_miniature`.
+pub fn allega(percorso: impl Into<PathBuf>) -> Result<Allegato, ErroreInventario> {
+    let percorso = percorso.into();
+    let dimensione_byte = std::fs::metadata(&percorso)?.len();
+    let impronta_sha256 = impronta_file(&percorso)?;
+    let (tipo, miniature) = if e_immagine(&percorso) {
+        let miniature = if cfg!(feature = "images") { Some(genera_miniature(&percorso)?) } else { None };
+        (TipoAllegato::Immagine, miniature)
+    } else {
+        (TipoAllegato::Generico, None)
+    };
+    Ok(Allegato { percorso: in_testo(&percorso), tipo, impronta_sha256, dimensione_byte, miniature, diritti: None, autore: None })
+}
+
+/// Riconosce il formato di un modello 3D da estensione e intestazione del file
+pub fn formato_modello(percorso: &Path) -> Result<FormatoModello, ErroreInventario> {
+    let estensione = percorso
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let formato = match estensione.as_str() {
+        "obj" => FormatoModello::Obj,
+        "ply" => FormatoModello::Ply,
+        "glb" => FormatoModello::Glb,
+        altro => {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "formato 3D '{}' non supportato (ammessi: OBJ, PLY, GLB)",
+                altro
+            )))
+        }
+    };
+
+    // PLY e GLB hanno un'intestazione riconoscibile: evitiamo file rinominati
+    let mut intestazione = [0u8; 4];
+    let letti = File::open(percorso)?.read(&mut intestazione)?;
+    let atteso: &[u8] = match formato {
+        FormatoModello::Ply => b"ply",
+        FormatoModello::Glb => b"glTF",
+        FormatoModello::Obj => b"",
+    };
+    if !intestazione[..letti].starts_with(atteso) {
+        return Err(ErroreInventario::DatiNonValidi(format!(
+            "{} non sembra un file {} valido",
+            percorso.display(),
+            formato
+        )));
+    }
+    Ok(formato)
+}
+
+/// Allega un modello 3D validandone il formato
+pub fn allega_modello_3d(
+    percorso: impl Into<PathBuf>,
+    triangoli: u32,
+    riferimento_scala: Option<String>,
+    software_acquisizione: &str,
+) -> Result<Allegato, ErroreInventario> {
+    let percorso = percorso.into();
+    let formato = formato_modello(&percorso)?;
+    if triangoli == 0 {
+        return Err(ErroreInventario::DatiNonValidi(
+            "un modello 3D deve avere almeno un triangolo".to_string(),
+        ));
+    }
+    let mut allegato = allega(percorso)?;
+    allegato.tipo = TipoAllegato::Modello3D(Modello3D {
+        formato,
+        triangoli,
+        riferimento_scala,
+        software_acquisizione: software_acquisizione.to_string(),
+    });
+    Ok(allegato)
+}
+
+/// Allega un documento PDF o DOCX (relazioni di scavo, referti di analisi, ...)
+pub fn allega_documento(
+    percorso: impl Into<PathBuf>,
+    categoria: CategoriaDocumento,
+) -> Result<Allegato, ErroreInventario> {
+    let percorso = percorso.into();
+    let estensione = percorso
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    // Un DOCX e un archivio ZIP: controlliamo la firma oltre all'estensione
+    let (formato, firma): (FormatoDocumento, &[u8]) = match estensione.as_str() {
+        "pdf" => (FormatoDocumento::Pdf, b"%PDF"),
+        "docx" => (FormatoDocumento::Docx, b"PK\x03\x04"),
+        altro => {
+            return Err(ErroreInventario::DatiNonValidi(format!(
+                "documento '{}' non supportato (ammessi: PDF, DOCX)",
+                altro
+            )))
+        }
+    };
+    let mut intestazione = [0u8; 4];
+    let letti = File::open(&percorso)?.read(&mut intestazione)?;
+    if intestazione[..letti] != *firma {
+        return Err(ErroreInventario::DatiNonValidi(format!(
+            "{} non e un {:?} valido",
+            percorso.display(),
+            formato
+        )));
+    }
+    let mut allegato = allega(percorso)?;
+    allegato.tipo = TipoAllegato::Documento { formato, categoria };
+    Ok(allegato)
+}
+
+/// Frammento HTML per il catalogo: `<model-viewer>` per i GLB,
+/// link di download per OBJ/PLY (non visualizzabili nel browser).
+pub fn html_modello_3d(allegato: &Allegato) -> Option<String> {
+    let TipoAllegato::Modello3D(modello) = &allegato.tipo else {
+        return None;
+    };
+    // Il percorso viene da chi ha caricato il file: un nome con
+    // virgolette o '<' non deve poter chiudere l'attributo o il tag
+    let src = testo_html(&allegato.percorso);
+    let html = match modello.formato {
+        FormatoModello::Glb => format!(
+            "<model-viewer src=\"{}\" camera-controls auto-rotate \
+             alt=\"Modello 3D ({} triangoli)\"></model-viewer>",
+            src, modello.triangoli
+        ),
+        formato => format!(
+            "<a href=\"{}\" download>Modello 3D {} {} ({} triangoli)</a>",
+            src,
+            formato,
+            testo_html(&Path::new(&allegato.percorso).file_name().unwrap_or_default().to_string_lossy()),
+            modello.triangoli
+        ),
+    };
+    Some(html)
+}
+
+/// Testo sicuro dentro un tag o un attributo HTML
+fn testo_html(testo: &str) -> String {
+    let mut sicuro = String::with_capacity(testo.len());
+    for c in testo.chars() {
+        match c {
+            '&' => sicuro.push_str("&amp;"),
+            '<' => sicuro.push_str("&lt;"),
+            '>' => sicuro.push_str("&gt;"),
+            '"' => sicuro.push_str("&quot;"),
+            '\'' => sicuro.push_str("&#39;"),
+            c => sicuro.push(c),
+        }
+    }
+    sicuro
+}
+
+/// Riconosce le immagini dall'estensione del file
+pub fn e_immagine(percorso: &Path) -> bool {
+    percorso
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "jpg" | "jpeg" | "png"))
+        .unwrap_or(false)
+}
+
+/// Percorso di una miniatura: `foto.jpg` -> `foto.piccola.jpg`
+#[cfg(feature = "images")]
+fn percorso_miniatura(originale: &Path, suffisso: &str) -> PathBuf {
+    let stem = originale.file_stem().and_then(|s| s.to_str()).unwrap_or("allegato");
+    let ext = originale.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    originale.with_file_name(format!("{}.{}.{}", stem, suffisso, ext))
+}
+
+/// Genera le miniature piccola e media accanto all'originale
+#[cfg(feature = "images")]
+pub fn genera_miniature(originale: &Path) -> Result<Miniature, ErroreInventario> {
+    let immagine = image::open(originale)?;
+    let piccola = percorso_miniatura(originale, "piccola");
+    let media = percorso_miniatura(originale, "media");
+    immagine.thumbnail(LATO_PICCOLA, LATO_PICCOLA).save(&piccola)?;
+    immagine.thumbnail(LATO_MEDIA, LATO_MEDIA).save(&media)?;
+    Ok(Miniature { piccola: in_testo(&piccola), media: in_testo(&media) })
+}
+
+#[cfg(not(feature = "images"))]
+pub fn genera_miniature(_originale: &Path) -> Result<Miniature, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "images", operazione: "generare le miniature" })
+}
+
+/// Rigenera le miniature di tutte le immagini allegate.
+/// Restituisce il numero di immagini elaborate e gli errori incontrati.
+pub fn rigenera_miniature(inv: &mut Inventario) -> (usize, Vec<(PathBuf, ErroreInventario)>) {
+    let mut elaborate = 0;
+    let mut errori = Vec::new();
+    inv.modifica_tutti(|reperto| {
+        for allegato in &mut reperto.allegati {
+            let percorso = Path::new(&allegato.percorso);
+            if !e_immagine(percorso) {
+                continue;
+            }
+            match genera_miniature(percorso) {
+                Ok(miniature) => {
+                    allegato.miniature = Some(miniature);
+                    elaborate += 1;
+                }
+                Err(e) => errori.push((percorso.to_path_buf(), e)),
+            }
+        }
+    });
+    (elaborate, errori)
+}
+
+/// Problema rilevato durante la verifica degli allegati
+#[derive(Debug, PartialEq)]
+pub enum Problema {
+    Mancante,
+    Modificato { impronta_attuale: String },
+    Illeggibile(String),
+}
+
+impl std::fmt::Display for Problema {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Problema::Mancante => write!(f, "mancante"),
+            Problema::Modificato { impronta_attuale } => write!(f, "modificato (impronta attuale {})", impronta_attuale),
+            Problema::Illeggibile(motivo) => write!(f, "illeggibile: {}", motivo),
+        }
+    }
+}
+
+/// Esito della verifica per un singolo allegato
+#[derive(Debug)]
+pub struct EsitoVerifica {
+    pub id_reperto: u32,
+    pub percorso: PathBuf,
+    pub problema: Problema,
+}
+
+/// Ricalcola l'impronta di ogni allegato e riporta quelli mancanti o modificati
+pub fn verifica(inv: &Inventario) -> Vec<EsitoVerifica> {
+    let mut esiti = Vec::new();
+    for reperto in inv.tutti() {
+        for allegato in &reperto.allegati {
+            let problema = match impronta_file(Path::new(&allegato.percorso)) {
+                Ok(impronta) if impronta == allegato.impronta_sha256 => continue,
+                Ok(impronta_attuale) => Problema::Modificato { impronta_attuale },
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Problema::Mancante,
+                Err(e) => Problema::Illeggibile(e.to_string()),
+            };
+            esiti.push(EsitoVerifica {
+                id_reperto: reperto.id,
+                percorso: PathBuf::from(&allegato.percorso),
+                problema,
+            });
+        }
+    }
+    esiti
+}
+
+/// Riscrive il prefisso dei percorsi (es. archivio foto spostato su un nuovo disco).
+/// Restituisce il numero di allegati aggiornati.
+pub fn riloca(inv: &mut Inventario, da: &Path, a: &Path) -> usize {
+    let mut aggiornati = 0;
+    inv.modifica_tutti(|reperto| {
+        for allegato in &mut reperto.allegati {
+            if sposta(&mut allegato.percorso, da, a) {
+                aggiornati += 1;
+            }
+            if let Some(miniature) = &mut allegato.miniature {
+                sposta(&mut miniature.piccola, da, a);
+                sposta(&mut miniature.media, da, a);
+            }
+        }
+    });
+    aggiornati
+}
+
+/// Sostituisce il prefisso `da` con `a`; falso se il percorso non sta sotto `da`
+fn sposta(percorso: &mut String, da: &Path, a: &Path) -> bool {
+    match Path::new(percorso.as_str()).strip_prefix(da) {
+        Ok(resto) => {
+            *percorso = in_testo(&a.join(resto));
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo, Reperto};
+
+    fn file_di_prova(nome: &str, contenuto: &[u8]) -> PathBuf {
+        let cartella = std::env::temp_dir().join(format!("bronzeaxe_media_{}_{}", nome, std::process::id()));
+        std::fs::create_dir_all(&cartella).unwrap();
+        let percorso = cartella.join(nome);
+        std::fs::write(&percorso, contenuto).unwrap();
+        percorso
+    }
+
+    #[cfg(not(feature = "images"))]
+    #[test]
+    fn foto_allegata_anche_senza_images() {
+        let foto = file_di_prova("foto.png", b"non serve che sia un png vero");
+        let allegato = allega(&foto).unwrap();
+        assert!(matches!(allegato.tipo, TipoAllegato::Immagine));
+        assert!(allegato.miniature.is_none());
+        assert_eq!(allegato.dimensione_byte, 29);
+
+        // La feature mancante la segnala solo chi chiede le miniature
+        assert!(matches!(genera_miniature(&foto), Err(ErroreInventario::FeatureMancante { feature: "images", .. })));
+        std::fs::remove_dir_all(foto.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn impronta_a_blocchi() {
+        let piccolo = file_di_prova("abc.txt", b"abc");
+        assert_eq!(
+            impronta_file(&piccolo).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Piu lungo del buffer: l'impronta non dipende da come si legge
+        let contenuto = vec![7u8; 20_000];
+        let grande = file_di_prova("grande.bin", &contenuto);
+        assert_eq!(impronta_file(&grande).unwrap(), format!("{:x}", Sha256::digest(&contenuto)));
+        std::fs::remove_dir_all(piccolo.parent().unwrap()).unwrap();
+        std::fs::remove_dir_all(grande.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn formati_riconosciuti_dall_intestazione() {
+        let ply = file_di_prova("scansione.ply", b"ply\nformat ascii 1.0\n");
+        assert!(matches!(formato_modello(&ply), Ok(FormatoModello::Ply)));
+        let finto = file_di_prova("finto.glb", b"ply\n");
+        assert!(formato_modello(&finto).is_err());
+        assert!(allega_modello_3d(&ply, 0, None, "Artec").is_err());
+
+        let relazione = file_di_prova("relazione.pdf", b"%PDF-1.7");
+        assert!(allega_documento(&relazione, CategoriaDocumento::RelazioneScavo).is_ok());
+        let rinominato = file_di_prova("referto.docx", b"%PDF-1.7");
+        assert!(allega_documento(&rinominato, CategoriaDocumento::RelazioneScavo).is_err());
+        for percorso in [ply, finto, relazione, rinominato] {
+            std::fs::remove_dir_all(percorso.parent().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn verifica_e_rilocazione() {
+        let cartella = std::env::temp_dir().join(format!("bronzeaxe_media_archivio_{}", std::process::id()));
+        let (vecchia, nuova) = (cartella.join("vecchio"), cartella.join("nuovo"));
+        std::fs::create_dir_all(&vecchia).unwrap();
+        for (nome, contenuto) in [("scheda.txt", "scheda"), ("rilievo.txt", "rilievo"), ("diario.txt", "diario")] {
+            std::fs::write(vecchia.join(nome), contenuto).unwrap();
+        }
+        let mut ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        for nome in ["scheda.txt", "rilievo.txt", "diario.txt"] {
+            ascia.allegati.push(allega(vecchia.join(nome)).unwrap());
+        }
+        let mut inv = Inventario::nuovo();
+        inv.aggiungi(ascia).unwrap();
+        assert!(verifica(&inv).is_empty());
+
+        std::fs::write(vecchia.join("rilievo.txt"), "rilievo corretto").unwrap();
+        std::fs::remove_file(vecchia.join("diario.txt")).unwrap();
+        let esiti = verifica(&inv);
+        assert_eq!(esiti.len(), 2);
+        assert!(matches!(&esiti[0].problema, Problema::Modificato { .. }));
+        assert_eq!((esiti[1].percorso.clone(), &esiti[1].problema), (vecchia.join("diario.txt"), &Problema::Mancante));
+
+        // L'archivio cambia disco: si riscrivono solo i percorsi sotto la vecchia radice
+        std::fs::rename(&vecchia, &nuova).unwrap();
+        inv.cerca_per_id_mut(1).unwrap().allegati[2].percorso = "/altrove/diario.txt".to_string();
+        assert_eq!(riloca(&mut inv, &vecchia, &nuova), 2);
+        let allegati = &inv.cerca_per_id(1).unwrap().allegati;
+        assert_eq!(allegati[0].percorso, in_testo(&nuova.join("scheda.txt")));
+        assert_eq!(allegati[2].percorso, "/altrove/diario.txt");
+        assert_eq!(verifica(&inv).len(), 2);
+        std::fs::remove_dir_all(cartella).unwrap();
+    }
+}
@@ -0,0 +1,387 @@
+// ============================================================================
+// MODULO: MIGRAZIONI SQLITE
+// ============================================================================
+// Lo schema del database cresce per passi numerati, ognuno con il suo
+// `su` e `giu`. La tabella `migrazioni` ricorda quali sono state
+// applicate e con quale checksum, cosi una migrazione gia rilasciata e
+// poi modificata viene scoperta.
+// ============================================================================
+
+//! Archivio SQLite con migrazioni versionate.
+
+// Il modulo intero esiste solo con la feature: e l'unico a usare rusqlite
+#![cfg(feature = "db")]
+
+use super::archivio::{Archivio, Scrittura, VersioniLette};
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::Reperto;
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Passo dello schema: `su` lo applica, `giu` lo annulla
+pub struct Migrazione {
+    pub versione: u32,
+    pub nome: &'static str,
+    pub su: &'static str,
+    pub giu: &'static str,
+}
+
+/// Migrazioni incluse nel programma, in ordine. Una migrazione gia
+/// rilasciata non va mai modificata: se ne aggiunge una nuova
+pub const MIGRAZIONI: &[Migrazione] = &[
+    Migrazione {
+        versione: 1,
+        nome: "reperti",
+        su: "CREATE TABLE reperti (
+                 id INTEGER PRIMARY KEY,
+                 nome TEXT NOT NULL,
+                 dati TEXT NOT NULL
+             );",
+        giu: "DROP TABLE reperti;",
+    },
+    Migrazione {
+        versione: 2,
+        nome: "sito_indicizzato",
+        su: "ALTER TABLE reperti ADD COLUMN sito TEXT NOT NULL DEFAULT '';
+             CREATE INDEX reperti_sito ON reperti (sito);",
+        giu: "DROP INDEX reperti_sito;
+              ALTER TABLE reperti DROP COLUMN sito;",
+    },
+    Migrazione {
+        versione: 3,
+        nome: "versione_reperto",
+        su: "ALTER TABLE reperti ADD COLUMN versione INTEGER NOT NULL DEFAULT 0;
+             UPDATE reperti SET versione = COALESCE(json_extract(dati, '$.versione'), 0);",
+        giu: "ALTER TABLE reperti DROP COLUMN versione;",
+    },
+    Migrazione {
+        versione: 4,
+        nome: "stato_inventario",
+        su: "CREATE TABLE stato_inventario (
+                 chiave INTEGER PRIMARY KEY CHECK (chiave = 1),
+                 dati TEXT NOT NULL
+             );",
+        giu: "DROP TABLE stato_inventario;",
+    },
+];
+
+const TABELLA_MIGRAZIONI: &str = "CREATE TABLE IF NOT EXISTS schema_migrazioni (
+    versione INTEGER PRIMARY KEY,
+    nome TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    applicata_il TEXT NOT NULL
+);";
+
+impl Migrazione {
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.su.as_bytes()))
+    }
+}
+
+/// Riga di `db status`
+#[derive(Debug)]
+pub struct StatoMigrazione {
+    pub versione: u32,
+    pub nome: &'static str,
+    /// Data di applicazione, `None` se ancora da applicare
+    pub applicata_il: Option<String>,
+}
+
+/// Confronta le migrazioni registrate nel database con quelle del
+/// programma: versioni sconosciute o checksum diversi bloccano tutto
+fn verifica(conn: &Connection) -> Result<Vec<(u32, String)>, ErroreInventario> {
+    let ha_registro: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'schema_migrazioni'",
+        [],
+        |r| r.get(0),
+    )?;
+    if !ha_registro {
+        let tabelle: u32 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+            [],
+            |r| r.get(0),
+        )?;
+        if tabelle > 0 {
+            return Err(ErroreInventario::SchemaNonRiconosciuto(
+                "il database contiene tabelle ma nessun registro delle migrazioni".to_string(),
+            ));
+        }
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT versione, checksum, applicata_il FROM schema_migrazioni ORDER BY versione",
+    )?;
+    let applicate = stmt
+        .query_map([], |r| Ok((r.get::<_, u32>(0)?, r.get::<_, String>(1)?, r.get::<_, String>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (i, (versione, checksum, _)) in applicate.iter().enumerate() {
+        let Some(m) = MIGRAZIONI.iter().find(|m| m.versione == *versione) else {
+            return Err(ErroreInventario::SchemaNonRiconosciuto(format!(
+                "migrazione {} applicata da una versione piu recente del programma",
+                versione
+            )));
+        };
+        if m.versione != MIGRAZIONI[i].versione {
+            return Err(ErroreInventario::SchemaNonRiconosciuto(format!(
+                "migrazioni applicate fuori ordine (manca la {})",
+                MIGRAZIONI[i].versione
+            )));
+        }
+        if *checksum != m.checksum() {
+            return Err(ErroreInventario::SchemaNonRiconosciuto(format!(
+                "la migrazione {} '{}' e stata modificata dopo l'applicazione",
+                m.versione, m.nome
+            )));
+        }
+    }
+    Ok(applicate.into_iter().map(|(v, _, data)| (v, data)).collect())
+}
+
+pub fn stato(conn: &Connection) -> Result<Vec<StatoMigrazione>, ErroreInventario> {
+    let applicate = verifica(conn)?;
+    Ok(MIGRAZIONI
+        .iter()
+        .map(|m| StatoMigrazione {
+            versione: m.versione,
+            nome: m.nome,
+            applicata_il: applicate.iter().find(|(v, _)| *v == m.versione).map(|(_, d)| d.clone()),
+        })
+        .collect())
+}
+
+/// Applica in ordine le migrazioni mancanti, ognuna nella sua transazione.
+/// Restituisce le versioni applicate
+pub fn migra(conn: &mut Connection) -> Result<Vec<u32>, ErroreInventario> {
+    let gia = verifica(conn)?.len();
+    conn.execute_batch(TABELLA_MIGRAZIONI)?;
+    let mut applicate = Vec::new();
+    for m in &MIGRAZIONI[gia..] {
+        let tx = conn.transaction()?;
+        tx.execute_batch(m.su)?;
+        tx.execute(
+            "INSERT INTO schema_migrazioni (versione, nome, checksum, applicata_il) VALUES (?1, ?2, ?3, ?4)",
+            params![m.versione, m.nome, m.checksum(), chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()],
+        )?;
+        tx.commit()?;
+        applicate.push(m.versione);
+    }
+    Ok(applicate)
+}
+
+/// Annulla l'ultima migrazione applicata, se c'e
+pub fn annulla(conn: &mut Connection) -> Result<Option<u32>, ErroreInventario> {
+    let Some(&(versione, _)) = verifica(conn)?.last() else {
+        return Ok(None);
+    };
+    let m = &MIGRAZIONI[versione as usize - 1];
+    let tx = conn.transaction()?;
+    tx.execute_batch(m.giu)?;
+    tx.execute("DELETE FROM schema_migrazioni WHERE versione = ?1", params![versione])?;
+    tx.commit()?;
+    Ok(Some(versione))
+}
+
+/// Inventario in un database SQLite; lavora solo su uno schema aggiornato
+pub struct ArchivioSqlite {
+    pub percorso: PathBuf,
+    versioni: VersioniLette,
+}
+
+impl ArchivioSqlite {
+    pub fn nuovo(percorso: impl Into<PathBuf>) -> Self {
+        ArchivioSqlite { percorso: percorso.into(), versioni: VersioniLette::default() }
+    }
+
+    fn connetti(&self) -> Result<Connection, ErroreInventario> {
+        let conn = Connection::open(&self.percorso)?;
+        let applicate = verifica(&conn)?.len();
+        if applicate < MIGRAZIONI.len() {
+            return Err(ErroreInventario::SchemaNonRiconosciuto(format!(
+                "schema alla versione {} su {}, eseguire 'db migrate'",
+                applicate,
+                MIGRAZIONI.len()
+            )));
+        }
+        Ok(conn)
+    }
+}
+
+impl Archivio for ArchivioSqlite {
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        self.connetti().map(|_| ())
+    }
+
+    /// Riga per riga: si scrivono solo i reperti cambiati, e solo se nel
+    /// database hanno ancora la versione letta; altrimenti `Conflitto` e
+    /// non si salva nulla. Lo stato dell'inventario e una riga sola,
+    /// riscritta a ogni salvataggio
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario> {
+        let mut conn = self.connetti()?;
+        let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let salvate: BTreeMap<u32, u64> = tx
+            .prepare("SELECT id, versione FROM reperti")?
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        let reperti = inventario.tutti_compresi_radiati();
+        let conflitto = |id: u32, letta: u64| ErroreInventario::Conflitto { id, letta, attuale: salvate[&id] };
+
+        for r in &reperti {
+            let dati = serde_json::to_string(r)?;
+            match self.versioni.scrittura(r.id, Some(r.versione), salvate.get(&r.id).copied())? {
+                Scrittura::Invariato => {}
+                Scrittura::Nuovo => {
+                    tx.execute(
+                        "INSERT INTO reperti (id, nome, sito, dati, versione) VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![r.id, r.nome, r.sito, dati, r.versione],
+                    )?;
+                }
+                Scrittura::Sovrascrivi { attesa } => {
+                    let aggiornate = tx.execute(
+                        "UPDATE reperti SET nome = ?2, sito = ?3, dati = ?4, versione = ?5 WHERE id = ?1 AND versione = ?6",
+                        params![r.id, r.nome, r.sito, dati, r.versione, attesa],
+                    )?;
+                    if aggiornate == 0 {
+                        return Err(conflitto(r.id, attesa));
+                    }
+                }
+            }
+        }
+
+        let presenti: BTreeSet<u32> = reperti.iter().map(|r| r.id).collect();
+        for (&id, &salvata) in salvate.iter().filter(|(id, _)| !presenti.contains(id)) {
+            if let Scrittura::Sovrascrivi { attesa } = self.versioni.scrittura(id, None, Some(salvata))? {
+                if tx.execute("DELETE FROM reperti WHERE id = ?1 AND versione = ?2", params![id, attesa])? == 0 {
+                    return Err(conflitto(id, attesa));
+                }
+            }
+        }
+        tx.execute(
+            "INSERT INTO stato_inventario (chiave, dati) VALUES (1, ?1)
+             ON CONFLICT (chiave) DO UPDATE SET dati = excluded.dati",
+            params![serde_json::to_string(&inventario.stato())?],
+        )?;
+        tx.commit()?;
+        self.versioni.ricorda(reperti);
+        Ok(())
+    }
+
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        let conn = self.connetti()?;
+        let mut stmt = conn.prepare("SELECT dati FROM reperti ORDER BY id")?;
+        let righe = stmt.query_map([], |r| r.get::<_, String>(0))?;
+        let reperti = righe
+            .map(|dati| Ok(serde_json::from_str::<Reperto>(&dati?)?))
+            .collect::<Result<Vec<_>, ErroreInventario>>()?;
+        self.versioni.ricorda(&reperti);
+        let stato = conn
+            .query_row("SELECT dati FROM stato_inventario", [], |r| r.get::<_, String>(0))
+            .optional()?
+            .map(|dati| serde_json::from_str(&dati))
+            .transpose()?
+            .unwrap_or_default();
+        Inventario::da_archivio(reperti, stato)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::inventario::StrategiaId;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo};
+
+    #[test]
+    fn migrazioni_e_rollback() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migra(&mut conn).unwrap(), [1, 2, 3, 4]);
+        assert!(migra(&mut conn).unwrap().is_empty());
+        assert!(stato(&conn).unwrap().iter().all(|s| s.applicata_il.is_some()));
+
+        assert_eq!(annulla(&mut conn).unwrap(), Some(4));
+        assert_eq!(annulla(&mut conn).unwrap(), Some(3));
+        assert_eq!(annulla(&mut conn).unwrap(), Some(2));
+        let applicate: Vec<bool> = stato(&conn).unwrap().iter().map(|s| s.applicata_il.is_some()).collect();
+        assert_eq!(applicate, [true, false, false, false]);
+        // La colonna della migrazione annullata non c'e piu
+        assert!(conn.execute("UPDATE reperti SET sito = ''", []).is_err());
+        assert_eq!(migra(&mut conn).unwrap(), [2, 3, 4]);
+
+        assert_eq!(annulla(&mut conn).unwrap(), Some(4));
+        assert_eq!(annulla(&mut conn).unwrap(), Some(3));
+        assert_eq!(annulla(&mut conn).unwrap(), Some(2));
+        assert_eq!(annulla(&mut conn).unwrap(), Some(1));
+        assert_eq!(annulla(&mut conn).unwrap(), None);
+    }
+
+    #[test]
+    fn migrazione_modificata_o_schema_estraneo() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migra(&mut conn).unwrap();
+        conn.execute("UPDATE schema_migrazioni SET checksum = 'altro' WHERE versione = 1", []).unwrap();
+        assert!(matches!(stato(&conn), Err(ErroreInventario::SchemaNonRiconosciuto(_))));
+
+        let mut estraneo = Connection::open_in_memory().unwrap();
+        estraneo.execute_batch("CREATE TABLE reperti (id INTEGER PRIMARY KEY);").unwrap();
+        assert!(matches!(migra(&mut estraneo), Err(ErroreInventario::SchemaNonRiconosciuto(_))));
+    }
+
+    #[test]
+    fn archivio_sqlite_andata_e_ritorno() {
+        let percorso = std::env::temp_dir().join(format!("bronzeaxe_sqlite_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&percorso);
+        let archivio = ArchivioSqlite::nuovo(percorso.clone());
+        // Finche lo schema non e aggiornato l'archivio non si usa
+        assert!(matches!(archivio.verifica(), Err(ErroreInventario::SchemaNonRiconosciuto(_))));
+        migra(&mut Connection::open(&percorso).unwrap()).unwrap();
+
+        let mut inv = Inventario::nuovo_con_strategia(StrategiaId::prefisso_sito());
+        for nome in ["Ascia", "Spillone"] {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+                .unwrap();
+        }
+        archivio.salva(&inv).unwrap();
+        let mut riletto = archivio.carica().unwrap();
+        assert_eq!(riletto.cerca_per_id(2).unwrap().nome, "Spillone");
+        assert_eq!(riletto.tutti_compresi_radiati().len(), 2);
+        // La strategia torna con i reperti: la numerazione prosegue
+        assert_eq!(riletto.strategia(), &StrategiaId::prefisso_sito());
+        let id = riletto
+            .aggiungi(Reperto::nuovo("Fibula", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+            .unwrap();
+        assert_eq!(riletto.cerca_per_id(id).unwrap().identificativo.as_deref(), Some("FRA-0003"));
+        std::fs::remove_file(percorso).unwrap();
+    }
+
+    #[test]
+    fn due_sessioni_sullo_stesso_database() {
+        let percorso = std::env::temp_dir().join(format!("bronzeaxe_sqlite_conflitto_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&percorso);
+        migra(&mut Connection::open(&percorso).unwrap()).unwrap();
+        let (prima, seconda) = (ArchivioSqlite::nuovo(percorso.clone()), ArchivioSqlite::nuovo(percorso.clone()));
+        let mut inv = Inventario::nuovo();
+        for nome in ["Ascia", "Spillone"] {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+                .unwrap();
+        }
+        prima.salva(&inv).unwrap();
+
+        let (mut mio, mut tuo) = (prima.carica().unwrap(), seconda.carica().unwrap());
+        mio.cerca_per_id_mut(1).unwrap().nome = "Ascia piatta".to_string();
+        prima.salva(&mio).unwrap();
+        // Si riscrivono solo le righe cambiate: l'altra sessione salva il suo reperto
+        tuo.cerca_per_id_mut(2).unwrap().nome = "Spillone a rotella".to_string();
+        seconda.salva(&tuo).unwrap();
+        let riletto = prima.carica().unwrap();
+        assert_eq!(riletto.cerca_per_id(1).unwrap().nome, "Ascia piatta");
+        assert_eq!(riletto.cerca_per_id(2).unwrap().nome, "Spillone a rotella");
+
+        // ...ma non sovrascrive quello cambiato dalla prima
+        tuo.cerca_per_id_mut(1).unwrap().nome = "Ascia ad alette".to_string();
+        assert!(matches!(seconda.salva(&tuo), Err(ErroreInventario::Conflitto { id: 1, .. })));
+        assert_eq!(prima.carica().unwrap().cerca_per_id(1).unwrap().nome, "Ascia piatta");
+        std::fs::remove_file(percorso).unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+// ============================================================================
+// MODULO: MODELLI
+// ============================================================================
+// I tipi dei dati: reperti, materiali, periodi, siti, stati di
+// catalogazione, persone, allegati, decorazioni, restauri e il resto.
+// Stanno in bronzeaxe-core (modulo `catalogo`), che compila senza std:
+// qui sono solo riesportati, cosi i percorsi `catalogo::modelli::...`
+// restano quelli di sempre. Le operazioni sono in `inventario`.
+// ============================================================================
+
+//! Tipi dei dati del catalogo: reperti, materiali, periodi, persone.
+
+pub use bronzeaxe_core::catalogo::*;
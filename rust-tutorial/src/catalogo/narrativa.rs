@@ -0,0 +1,316 @@
+// ============================================================================
+// MODULO: NARRATIVA DEL REPORT
+// ============================================================================
+// Il report statistico (`statistiche::genera_report`) diventa un paragrafo
+// da mettere nella relazione preliminare di scavo:
+//
+//   "Il complesso comprende 20 reperti da 2 siti (Savignano Irpino 12 e
+//    Frattesina 8). Per cronologia prevale il Bronzo Medio ..."
+//
+// Il testo si compone da modelli di frase fissi: stesse statistiche, stesso
+// paragrafo, senza rete. Se e configurato un modello linguistico (API
+// compatibile OpenAI: Ollama, llama.cpp, servizi remoti; feature `ai`),
+// il paragrafo puo essere riscritto in una prosa piu scorrevole.
+//
+// La riscrittura si accetta solo se contiene tutti i numeri della bozza e
+// nessun numero nuovo: in una relazione un conteggio inventato e peggio di
+// una frase rigida. Se il controllo fallisce resta la bozza.
+// ============================================================================
+
+//! Paragrafo descrittivo del report statistico, con rifinitura facoltativa.
+
+use std::collections::BTreeMap;
+
+use super::errori::ErroreInventario;
+use super::modelli::{Materiale, Periodo};
+use super::statistiche::ReportStatistiche;
+
+/// Il paragrafo e da dove viene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Narrativa {
+    pub testo: String,
+    /// Vero se il testo e la riscrittura del modello e non la bozza
+    pub rifinita: bool,
+}
+
+/// Chi riscrive la bozza: un modello linguistico o, nei test, una funzione.
+pub trait Rifinitore {
+    fn rifinisci(&self, bozza: &str) -> Result<String, ErroreInventario>;
+}
+
+impl Narrativa {
+    /// Il paragrafo composto dai modelli di frase.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Misurazioni, Periodo, Reperto};
+    /// use rust_tutorial::catalogo::narrativa::Narrativa;
+    /// use rust_tutorial::catalogo::statistiche::genera_report;
+    ///
+    /// let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina")
+    ///     .con_misurazioni(Misurazioni::nuove().con_peso(327.6));
+    /// let testo = Narrativa::da_report(&genera_report(&[&ascia])).testo;
+    /// assert!(testo.starts_with("Il complesso comprende 1 reperto, dal sito di Frattesina."), "{}", testo);
+    /// ```
+    pub fn da_report(report: &ReportStatistiche) -> Narrativa {
+        Narrativa { testo: bozza(report), rifinita: false }
+    }
+
+    /// Fa riscrivere il paragrafo; la riscrittura sostituisce la bozza solo
+    /// se ne conserva i numeri. Gli errori del servizio tornano al chiamante,
+    /// che puo sempre usare la bozza.
+    pub fn rifinisci(self, rifinitore: &impl Rifinitore) -> Result<Narrativa, ErroreInventario> {
+        let proposta = rifinitore.rifinisci(&self.testo)?;
+        let proposta = proposta.trim();
+        if proposta.is_empty() || numeri(proposta) != numeri(&self.testo) {
+            return Ok(self);
+        }
+        Ok(Narrativa { testo: proposta.to_string(), rifinita: true })
+    }
+}
+
+/// I numeri di un testo, ordinati: "1.350 a.C." e "1350 a.C." sono diversi,
+/// e va bene cosi, il modello deve copiarli
+fn numeri(testo: &str) -> Vec<&str> {
+    let mut numeri: Vec<&str> = testo
+        .split(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .map(|n| n.trim_matches(|c| c == '.' || c == ','))
+        .filter(|n| !n.is_empty())
+        .collect();
+    // L'ordine puo cambiare con la riscrittura, l'insieme no
+    numeri.sort_unstable();
+    numeri
+}
+
+fn bozza(report: &ReportStatistiche) -> String {
+    let totale = report.totale_reperti;
+    if totale == 0 {
+        return "Nel complesso esaminato non ci sono reperti.".to_string();
+    }
+    let mut frasi = Vec::new();
+
+    let siti = piu_frequenti(&report.per_sito);
+    frasi.push(match siti.as_slice() {
+        [(sito, _)] => format!("Il complesso comprende {}, dal sito di {}.", reperti(totale), sito),
+        _ => format!(
+            "Il complesso comprende {} da {} siti ({}).",
+            reperti(totale),
+            siti.len(),
+            elenco(&voci(&siti, 3, |sito, n| format!("{} {}", sito, n)))
+        ),
+    });
+
+    let mut per_periodo: BTreeMap<&Periodo, usize> = BTreeMap::new();
+    let mut per_materiale: BTreeMap<&Materiale, usize> = BTreeMap::new();
+    for ((periodo, materiale), cella) in &report.incrocio.celle {
+        *per_periodo.entry(periodo).or_default() += cella.conteggio;
+        *per_materiale.entry(materiale).or_default() += cella.conteggio;
+    }
+    let periodi = piu_frequenti(&per_periodo);
+    if let Some(((periodo, n), altri)) = periodi.split_first() {
+        let mut frase = format!(
+            "Per cronologia prevale il {} con {} ({}%)",
+            periodo,
+            reperti(*n),
+            percento(*n, totale)
+        );
+        if !altri.is_empty() {
+            // Le date solo per il primo: "Bronzo Recente (1)", non "Bronzo Recente (1350-1200 a.C.) 1"
+            let altri = voci(altri, 2, |periodo, n| format!("{} ({})", periodo.split(" (").next().unwrap_or(periodo), n));
+            frase += &format!(", seguito da {}", elenco(&altri));
+        }
+        frasi.push(frase + ".");
+    }
+    if let Some(((materiale, n), _)) = piu_frequenti(&per_materiale).split_first() {
+        frasi.push(format!(
+            "Il materiale piu attestato e {} ({}, {}%).",
+            materiale.to_string().to_lowercase(),
+            reperti(*n),
+            percento(*n, totale)
+        ));
+    }
+
+    let classificati: Vec<_> = piu_frequenti(&report.per_classe_funzionale)
+        .into_iter()
+        .filter(|(classe, _)| classe != "Non classificato")
+        .collect();
+    if !classificati.is_empty() {
+        let classi = voci(&classificati, 4, |classe, n| format!("{} {}", classe.to_lowercase(), n));
+        let mut frase = format!("Per classe funzionale: {}", elenco(&classi));
+        if let Some(n) = report.per_classe_funzionale.get("Non classificato") {
+            frase += &format!("; {} senza classe", reperti(*n));
+        }
+        frasi.push(frase + ".");
+    }
+
+    if let Some(medio) = report.peso_medio {
+        frasi.push(format!(
+            "Il peso complessivo dei reperti pesati e di {:.0} g, con una media di {:.1} g.",
+            report.peso_totale, medio
+        ));
+    }
+    let stato = piu_frequenti(&report.per_conservazione);
+    if let Some((prevalente, n)) = stato.first() {
+        frasi.push(format!(
+            "Lo stato di conservazione medio e {:.1} su 5; prevale lo stato {} ({}).",
+            report.punteggio_conservazione_medio,
+            prevalente.to_lowercase(),
+            reperti(*n)
+        ));
+    }
+    if let Some((tecnica, n)) = piu_frequenti(&report.per_tecnica_decorativa).first() {
+        frasi.push(format!(
+            "La tecnica decorativa piu frequente e {} ({}).",
+            tecnica.to_lowercase(),
+            reperti(*n)
+        ));
+    }
+    frasi.join(" ")
+}
+
+/// Dal piu frequente; a parita, nell'ordine della mappa
+fn piu_frequenti<K: ToString>(conteggi: &BTreeMap<K, usize>) -> Vec<(String, usize)> {
+    let mut voci: Vec<(String, usize)> = conteggi.iter().map(|(k, n)| (k.to_string(), *n)).collect();
+    voci.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    voci
+}
+
+/// Al massimo `quante` voci scritte da `voce`, poi "altri 3"; se ne resta
+/// una sola si scrive quella
+fn voci(voci: &[(String, usize)], quante: usize, voce: impl Fn(&str, usize) -> String) -> Vec<String> {
+    let quante = if voci.len() == quante + 1 { voci.len() } else { quante };
+    let mut testo: Vec<String> = voci.iter().take(quante).map(|(nome, n)| voce(nome, *n)).collect();
+    let resto: usize = voci.iter().skip(quante).map(|(_, n)| n).sum();
+    if resto > 0 {
+        testo.push(format!("altri {}", resto));
+    }
+    testo
+}
+
+/// "a", "a e b", "a, b e c"
+fn elenco(voci: &[String]) -> String {
+    match voci {
+        [] => String::new(),
+        [sola] => sola.clone(),
+        [prime @ .., ultima] => format!("{} e {}", prime.join(", "), ultima),
+    }
+}
+
+fn reperti(n: usize) -> String {
+    if n == 1 {
+        "1 reperto".to_string()
+    } else {
+        format!("{} reperti", n)
+    }
+}
+
+fn percento(parte: usize, totale: usize) -> usize {
+    (parte as f64 * 100.0 / totale as f64).round() as usize
+}
+
+/// Modello linguistico con API compatibile OpenAI (`/chat/completions`):
+/// Ollama (`http://localhost:11434/v1`), llama.cpp o un servizio remoto.
+#[cfg(feature = "ai")]
+#[derive(Debug, Clone)]
+pub struct ClienteLlm {
+    pub url_base: String,
+    pub modello: String,
+    pub chiave: Option<String>,
+}
+
+#[cfg(feature = "ai")]
+impl ClienteLlm {
+    /// Da `BRONZEAXE_LLM_URL` e `BRONZEAXE_LLM_MODELLO`, con
+    /// `BRONZEAXE_LLM_CHIAVE` per i servizi che la richiedono
+    pub fn da_variabili(variabile: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        Some(ClienteLlm {
+            url_base: variabile("BRONZEAXE_LLM_URL")?,
+            modello: variabile("BRONZEAXE_LLM_MODELLO")?,
+            chiave: variabile("BRONZEAXE_LLM_CHIAVE"),
+        })
+    }
+}
+
+#[cfg(feature = "ai")]
+impl Rifinitore for ClienteLlm {
+    fn rifinisci(&self, bozza: &str) -> Result<String, ErroreInventario> {
+        let richiesta = serde_json::json!({
+            "model": self.modello,
+            "temperature": 0.2,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": "Riscrivi in italiano il paragrafo di una relazione preliminare di scavo \
+                                in prosa scientifica scorrevole. Conserva esattamente tutti i numeri, \
+                                non aggiungerne e non aggiungere interpretazioni. Rispondi solo con il paragrafo."
+                },
+                { "role": "user", "content": bozza }
+            ]
+        });
+        let mut post = ureq::post(format!("{}/chat/completions", self.url_base.trim_end_matches('/')));
+        if let Some(chiave) = &self.chiave {
+            post = post.header("Authorization", format!("Bearer {}", chiave));
+        }
+        let risposta: serde_json::Value = post.send_json(&richiesta)?.body_mut().read_json()?;
+        risposta["choices"][0]["message"]["content"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ErroreInventario::ServizioRemoto("risposta del modello senza testo".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{ClasseFunzionale, Conservazione, Misurazioni, Reperto};
+    use crate::catalogo::statistiche::genera_report;
+
+    fn complesso() -> Vec<Reperto> {
+        let nuovo = |periodo, sito: &str, peso| {
+            Reperto::nuovo("Ascia", Materiale::Bronzo, periodo, Conservazione::Buono, sito)
+                .con_misurazioni(Misurazioni::nuove().con_peso(peso))
+                .con_classe_funzionale(ClasseFunzionale::Utensile)
+        };
+        vec![
+            nuovo(Periodo::BronzoMedio, "Savignano Irpino", 300.0),
+            nuovo(Periodo::BronzoMedio, "Savignano Irpino", 320.0),
+            nuovo(Periodo::BronzoRecente, "Frattesina", 400.0),
+            Reperto::nuovo("Fuseruola", Materiale::Ceramica, Periodo::BronzoMedio, Conservazione::Frammentario, "Frattesina"),
+        ]
+    }
+
+    #[test]
+    fn bozza_dal_report() {
+        let reperti = complesso();
+        let report = genera_report(&reperti.iter().collect::<Vec<_>>());
+        assert_eq!(
+            Narrativa::da_report(&report).testo,
+            "Il complesso comprende 4 reperti da 2 siti (Frattesina 2 e Savignano Irpino 2). \
+             Per cronologia prevale il Bronzo Medio (1700-1350 a.C.) con 3 reperti (75%), \
+             seguito da Bronzo Recente (1). \
+             Il materiale piu attestato e bronzo (3 reperti, 75%). \
+             Per classe funzionale: utensile 3; 1 reperto senza classe. \
+             Il peso complessivo dei reperti pesati e di 1020 g, con una media di 340.0 g. \
+             Lo stato di conservazione medio e 3.5 su 5; prevale lo stato buono (3 reperti)."
+        );
+        assert_eq!(Narrativa::da_report(&genera_report(&[])).testo, "Nel complesso esaminato non ci sono reperti.");
+    }
+
+    struct Fisso(&'static str);
+
+    impl Rifinitore for Fisso {
+        fn rifinisci(&self, _bozza: &str) -> Result<String, ErroreInventario> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    #[test]
+    fn la_rifinitura_deve_conservare_i_numeri() {
+        let bozza = Narrativa { testo: "Sono 12 reperti, peso medio 340.5 g.".to_string(), rifinita: false };
+        let buona = bozza.clone().rifinisci(&Fisso("Il peso medio e di 340.5 g sui 12 reperti.")).unwrap();
+        assert!(buona.rifinita);
+        // Un numero cambiato o aggiunto: resta la bozza
+        for proposta in ["Sono 13 reperti, peso medio 340.5 g.", "Sono 12 reperti (2 siti), peso medio 340.5 g.", ""] {
+            assert_eq!(bozza.clone().rifinisci(&Fisso(proposta)).unwrap(), bozza);
+        }
+    }
+}
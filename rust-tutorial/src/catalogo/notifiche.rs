@@ -0,0 +1,253 @@
+// ============================================================================
+// MODULO: NOTIFICHE
+// ============================================================================
+// Il riepilogo giornaliero o settimanale raccoglie reperti nuovi,
+// prestiti non rientrati, peggioramenti e backup falliti; se e
+// configurato un server SMTP parte per email.
+// ============================================================================
+
+//! Riepilogo periodico delle novita, anche per email.
+
+use super::archivio::{Archivio, FileJson};
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::prestiti::Prestito;
+use chrono::{Duration, NaiveDate};
+#[cfg(feature = "net")]
+use lettre::message::header::ContentType;
+#[cfg(feature = "net")]
+use lettre::transport::smtp::authentication::Credentials;
+#[cfg(feature = "net")]
+use lettre::{Message, SmtpTransport, Transport};
+
+/// Ogni quanto si manda il riepilogo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequenza {
+    Giornaliera,
+    Settimanale,
+}
+
+impl Frequenza {
+    pub fn da_nome(nome: &str) -> Option<Self> {
+        match nome {
+            "giornaliero" | "giornaliera" => Some(Frequenza::Giornaliera),
+            "settimanale" => Some(Frequenza::Settimanale),
+            _ => None,
+        }
+    }
+
+    pub fn giorni(self) -> i64 {
+        match self {
+            Frequenza::Giornaliera => 1,
+            Frequenza::Settimanale => 7,
+        }
+    }
+}
+
+/// Contenuto del riepilogo: una riga di testo per ogni segnalazione
+#[derive(Debug)]
+pub struct Riepilogo {
+    pub dal: NaiveDate,
+    pub al: NaiveDate,
+    pub nuovi: Vec<String>,
+    pub prestiti_scaduti: Vec<String>,
+    pub allarmi: Vec<String>,
+    pub backup_falliti: Vec<String>,
+}
+
+impl Riepilogo {
+    /// Raccoglie le novita dell'ultimo periodo fino a `oggi` compreso:
+    /// reperti aggiunti (da `Inventario::creazioni`), prestiti non rientrati, peggioramenti
+    /// di conservazione e controlli scaduti sui reperti ad alto rischio
+    pub fn componi(inv: &Inventario, prestiti: &[Prestito], oggi: NaiveDate, frequenza: Frequenza) -> Self {
+        let dal = oggi - Duration::days(frequenza.giorni() - 1);
+        let nel_periodo = |data: NaiveDate| data >= dal && data <= oggi;
+
+        let nuovi = inv
+            .creazioni()
+            .filter(|(_, quando)| nel_periodo(quando.date()))
+            .filter_map(|(id, _)| inv.cerca_per_id(id).ok())
+            .map(|r| format!("#{} {} ({})", r.id, r.nome, r.sito))
+            .collect();
+
+        let prestiti_scaduti = prestiti
+            .iter()
+            .filter(|p| p.e_scaduto(inv, oggi))
+            .map(|p| {
+                format!(
+                    "{}: reperti {:?}, scaduto il {}",
+                    p.richiedente.nome,
+                    p.reperti,
+                    p.data_fine.format("%d/%m/%Y")
+                )
+            })
+            .collect();
+
+        let mut allarmi = Vec::new();
+        for r in inv.tutti() {
+            let mut controlli: Vec<_> = r.controlli.iter().collect();
+            controlli.sort_by_key(|c| c.data);
+            if let [.., prima, dopo] = controlli.as_slice() {
+                if nel_periodo(dopo.data) && dopo.conservazione.punteggio() < prima.conservazione.punteggio() {
+                    allarmi.push(format!(
+                        "#{} {}: peggiorato da {} a {} il {}",
+                        r.id,
+                        r.nome,
+                        prima.conservazione,
+                        dopo.conservazione,
+                        dopo.data.format("%d/%m/%Y")
+                    ));
+                }
+            }
+        }
+        let campagna = super::condizione::Campagna::nuova("riepilogo", oggi);
+        for r in campagna.da_controllare(inv).into_iter().filter(|r| r.punteggio_rischio() >= 4) {
+            let ultimo = r.ultimo_controllo().map_or("mai".to_string(), |d| d.format("%d/%m/%Y").to_string());
+            allarmi.push(format!("#{} {}: controllo scaduto (rischio {}, ultimo {})", r.id, r.nome, r.punteggio_rischio(), ultimo));
+        }
+
+        Riepilogo { dal, al: oggi, nuovi, prestiti_scaduti, allarmi, backup_falliti: Vec::new() }
+    }
+
+    /// Aggiunge il controllo della copia di backup del file dati
+    pub fn con_backup(mut self, archivio: &FileJson) -> Self {
+        self.backup_falliti.extend(controlla_backup(archivio));
+        self
+    }
+
+    /// Invia il riepilogo solo se c'e qualcosa da segnalare.
+    /// Restituisce vero se il messaggio e partito
+    pub fn invia(&self, notificatore: &dyn Notificatore) -> Result<bool, ErroreInventario> {
+        if self.e_vuoto() {
+            return Ok(false);
+        }
+        notificatore.invia(&self.oggetto(), &self.testo())?;
+        Ok(true)
+    }
+
+    /// Niente da segnalare: in questo caso non si manda nulla
+    pub fn e_vuoto(&self) -> bool {
+        self.nuovi.is_empty()
+            && self.prestiti_scaduti.is_empty()
+            && self.allarmi.is_empty()
+            && self.backup_falliti.is_empty()
+    }
+
+    pub fn oggetto(&self) -> String {
+        let anomalie = self.prestiti_scaduti.len() + self.allarmi.len() + self.backup_falliti.len();
+        format!(
+            "[BronzeAxe] Riepilogo {} - {} nuovi reperti, {} segnalazioni",
+            self.al.format("%d/%m/%Y"),
+            self.nuovi.len(),
+            anomalie
+        )
+    }
+
+    /// Corpo del messaggio in testo semplice
+    pub fn testo(&self) -> String {
+        let mut testo = format!(
+            "Riepilogo dal {} al {}\n",
+            self.dal.format("%d/%m/%Y"),
+            self.al.format("%d/%m/%Y")
+        );
+        for (titolo, righe) in [
+            ("Nuovi reperti", &self.nuovi),
+            ("Prestiti scaduti", &self.prestiti_scaduti),
+            ("Allarmi di conservazione", &self.allarmi),
+            ("Backup falliti", &self.backup_falliti),
+        ] {
+            testo += &format!("\n{} ({})\n", titolo, righe.len());
+            if righe.is_empty() {
+                testo += "  nessuno\n";
+            }
+            for riga in righe {
+                testo += &format!("  - {}\n", riga);
+            }
+        }
+        testo
+    }
+}
+
+/// Controlla la copia di backup di un file dati: `None` se e leggibile
+/// e integra, altrimenti la descrizione del problema
+pub fn controlla_backup(archivio: &FileJson) -> Option<String> {
+    let backup = FileJson::nuovo(archivio.percorso_backup(), archivio.passphrase.clone());
+    if !backup.percorso.exists() {
+        return Some(format!("{}: backup assente", backup.percorso.display()));
+    }
+    backup.carica().err().map(|e| format!("{}: {}", backup.percorso.display(), e))
+}
+
+/// Canale con cui recapitare un riepilogo
+pub trait Notificatore {
+    fn invia(&self, oggetto: &str, testo: &str) -> Result<(), ErroreInventario>;
+}
+
+/// Invio via SMTP. Porta 465: TLS implicito; con credenziali: STARTTLS;
+/// altrimenti in chiaro (relay locale)
+#[cfg(feature = "net")]
+#[derive(Debug, Clone)]
+pub struct Smtp {
+    pub server: String,
+    pub porta: u16,
+    pub utente: Option<String>,
+    pub password: Option<String>,
+    pub mittente: String,
+    pub destinatari: Vec<String>,
+}
+
+#[cfg(feature = "net")]
+impl Smtp {
+    /// Da `BRONZEAXE_SMTP_SERVER` (host[:porta]) e dalle variabili collegate;
+    /// `None` se il server o i destinatari non sono configurati
+    pub fn da_variabili(variabile: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let server = variabile("BRONZEAXE_SMTP_SERVER")?;
+        let destinatari: Vec<String> = variabile("BRONZEAXE_NOTIFICHE_A")?
+            .split(',')
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if destinatari.is_empty() {
+            return None;
+        }
+        let utente = variabile("BRONZEAXE_SMTP_UTENTE");
+        let (host, porta) = match server.rsplit_once(':') {
+            Some((host, porta)) => (host.to_string(), porta.parse().ok()?),
+            None => (server, if utente.is_some() { 587 } else { 25 }),
+        };
+        Some(Smtp {
+            server: host,
+            porta,
+            password: variabile("BRONZEAXE_SMTP_PASSWORD"),
+            mittente: variabile("BRONZEAXE_SMTP_MITTENTE")
+                .or_else(|| utente.clone())
+                .unwrap_or_else(|| "bronzeaxe@localhost".to_string()),
+            utente,
+            destinatari,
+        })
+    }
+}
+
+#[cfg(feature = "net")]
+impl Notificatore for Smtp {
+    fn invia(&self, oggetto: &str, testo: &str) -> Result<(), ErroreInventario> {
+        let mut messaggio = Message::builder().from(self.mittente.parse()?).subject(oggetto);
+        for destinatario in &self.destinatari {
+            messaggio = messaggio.to(destinatario.parse()?);
+        }
+        let messaggio = messaggio.header(ContentType::TEXT_PLAIN).body(testo.to_string())?;
+
+        let trasporto = match (&self.utente, self.porta) {
+            (_, 465) => SmtpTransport::relay(&self.server)?,
+            (Some(_), _) => SmtpTransport::starttls_relay(&self.server)?,
+            (None, _) => SmtpTransport::builder_dangerous(&self.server),
+        };
+        let trasporto = match (&self.utente, &self.password) {
+            (Some(u), Some(p)) => trasporto.credentials(Credentials::new(u.clone(), p.clone())),
+            _ => trasporto,
+        };
+        trasporto.port(self.porta).build().send(&messaggio)?;
+        Ok(())
+    }
+}
+
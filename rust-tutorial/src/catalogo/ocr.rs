@@ -0,0 +1,513 @@
+// ============================================================================
+// MODULO: SCHEDE CARTACEE DALL'OCR
+// ============================================================================
+// Le schede compilate prima dell'inventario digitale si scansionano e si
+// passano a un OCR (tesseract, per esempio), che restituisce il testo con
+// i suoi errori tipici: "3l2" per 312, "O" per 0, la virgola decimale.
+// Da quel testo si ricavano bozze di reperti:
+//
+//   N. inv. 4512                       ->  identificativo "4512"
+//   Oggetto: ascia a margini rialzati  ->  nome
+//   Materiale: bronzo                  ->  Materiale::Bronzo
+//   Misure: 12,5 x 4 x 1,2 cm          ->  lunghezza, larghezza, altezza
+//   Peso: gr. 3l2                      ->  312 g, con il dubbio segnalato
+//
+// Ogni campo si cerca con espressioni regolari. Quelle predefinite coprono
+// le diciture piu comuni (anche i codici ICCD come INVN e OGTD); un file
+// di regole `campo = espressione` aggiunge quelle di un archivio
+// particolare, che si provano per prime:
+//
+//   # regole.ocr
+//   inventario = ^Cat\. gen\. n\. (\S+)
+//   peso = ^Gr\.?\s*(.+)
+//
+// Il valore e il primo gruppo tra parentesi; maiuscole e minuscole non
+// contano e ^ e $ valgono per ogni riga. Piu schede nello stesso file sono
+// separate dal salto pagina (\f) che l'OCR mette tra una pagina e l'altra.
+//
+// Una bozza non e mai una scheda finita: entra nell'inventario in
+// ingresso, con una nota che elenca i dubbi (correzioni dell'OCR, campi
+// non trovati, valori assunti) da controllare sull'originale.
+// ============================================================================
+
+//! Bozze di reperti dal testo OCR delle schede cartacee, da verificare a mano.
+
+// Il modulo intero esiste solo con la feature: e l'unico a usare regex
+#![cfg(feature = "ocr")]
+
+use std::fmt;
+
+use regex::{Regex, RegexBuilder};
+use thiserror::Error;
+
+use super::modelli::{Conservazione, Materiale, Misurazioni, Nota, Periodo, Reperto, StatoCatalogazione};
+use super::unita::{self, Dimensione, Quantita};
+
+/// L'inizio della nota che accompagna ogni bozza
+pub const NOTA_BOZZA: &str = "Bozza da scheda cartacea (OCR)";
+
+/// I campi che si cercano sulla scheda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampoScheda {
+    Inventario,
+    Oggetto,
+    Materiale,
+    Conservazione,
+    Sito,
+    /// Le tre dimensioni insieme: `12 x 4 x 1 cm`
+    Misure,
+    Lunghezza,
+    Larghezza,
+    Altezza,
+    Peso,
+}
+
+impl CampoScheda {
+    const TUTTI: [CampoScheda; 10] = [
+        CampoScheda::Inventario,
+        CampoScheda::Oggetto,
+        CampoScheda::Materiale,
+        CampoScheda::Conservazione,
+        CampoScheda::Sito,
+        CampoScheda::Misure,
+        CampoScheda::Lunghezza,
+        CampoScheda::Larghezza,
+        CampoScheda::Altezza,
+        CampoScheda::Peso,
+    ];
+
+    /// Il nome usato nel file delle regole
+    pub fn nome(self) -> &'static str {
+        match self {
+            CampoScheda::Inventario => "inventario",
+            CampoScheda::Oggetto => "oggetto",
+            CampoScheda::Materiale => "materiale",
+            CampoScheda::Conservazione => "conservazione",
+            CampoScheda::Sito => "sito",
+            CampoScheda::Misure => "misure",
+            CampoScheda::Lunghezza => "lunghezza",
+            CampoScheda::Larghezza => "larghezza",
+            CampoScheda::Altezza => "altezza",
+            CampoScheda::Peso => "peso",
+        }
+    }
+
+    pub fn da_nome(nome: &str) -> Option<CampoScheda> {
+        CampoScheda::TUTTI.into_iter().find(|c| c.nome() == nome)
+    }
+}
+
+impl fmt::Display for CampoScheda {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.nome())
+    }
+}
+
+/// Le diciture piu comuni, una espressione per campo. Il `\b` dopo la
+/// parola chiave evita che `sp` (spessore) prenda la riga "Spada ...".
+const REGOLE_PREDEFINITE: [(CampoScheda, &str); 10] = [
+    (
+        CampoScheda::Inventario,
+        r"^\s*(?:n(?:um(?:ero)?)?[\x{B0}\x{BA}.]?\s*(?:di\s+)?)?(?:inv(?:entario|n)?)\b\.?\s*[:.]?\s*([a-z0-9][\w./-]*)",
+    ),
+    (CampoScheda::Oggetto, r"^\s*(?:oggetto|definizione|ogtd)\b\s*[:.]?\s*(.+?)\s*$"),
+    (CampoScheda::Materiale, r"^\s*(?:materiale|materia(?:\s+e\s+tecnica)?|mtc)\b\s*[:.]?\s*(.+?)\s*$"),
+    (
+        CampoScheda::Conservazione,
+        r"^\s*(?:stato\s+di\s+conservazione|conservazione|stcc)\b\s*[:.]?\s*(.+?)\s*$",
+    ),
+    (
+        CampoScheda::Sito,
+        r"^\s*(?:localit.|provenienza|sito|luogo\s+di\s+rinvenimento|pvcl)\b\s*[:.]?\s*(.+?)\s*$",
+    ),
+    (CampoScheda::Misure, r"^\s*(?:misure|dimensioni)\b\s*[:.]?\s*(.+?)\s*$"),
+    (CampoScheda::Lunghezza, r"^\s*lung(?:h(?:ezza)?)?\b\.?\s*[:.]?\s*(.+?)\s*$"),
+    (CampoScheda::Larghezza, r"^\s*larg(?:h(?:ezza)?)?\b\.?\s*[:.]?\s*(.+?)\s*$"),
+    (CampoScheda::Altezza, r"^\s*(?:alt(?:ezza)?|spess(?:ore)?|sp)\b\.?\s*[:.]?\s*(.+?)\s*$"),
+    (CampoScheda::Peso, r"^\s*peso\b\.?\s*[:.]?\s*(.+?)\s*$"),
+];
+
+#[derive(Debug, Error)]
+pub enum ErroreRegole {
+    #[error("riga {riga}: {motivo}")]
+    Sintassi { riga: usize, motivo: String },
+    #[error("riga {riga}: campo '{campo}' sconosciuto (ammessi: inventario, oggetto, materiale, conservazione, sito, misure, lunghezza, larghezza, altezza, peso)")]
+    CampoSconosciuto { riga: usize, campo: String },
+}
+
+fn compila(schema: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(schema).case_insensitive(true).multi_line(true).build()
+}
+
+/// Le espressioni con cui si cercano i campi, provate in ordine.
+#[derive(Debug, Clone)]
+pub struct RegoleOcr {
+    regole: Vec<(CampoScheda, Regex)>,
+}
+
+impl Default for RegoleOcr {
+    fn default() -> Self {
+        RegoleOcr::predefinite()
+    }
+}
+
+impl RegoleOcr {
+    pub fn predefinite() -> Self {
+        RegoleOcr {
+            regole: REGOLE_PREDEFINITE
+                .iter()
+                .map(|(campo, schema)| (*campo, compila(schema).expect("regola predefinita")))
+                .collect(),
+        }
+    }
+
+    /// Le righe `campo = espressione` di un file di regole, seguite da
+    /// quelle predefinite.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::ocr::RegoleOcr;
+    ///
+    /// let regole = RegoleOcr::da_testo("# archivio Pigorini\ninventario = ^Cat\\. gen\\. n\\. (\\S+)").unwrap();
+    /// let bozza = regole.leggi("Cat. gen. n. 77/B\nOggetto: fibula", "Frattesina");
+    /// assert_eq!(bozza.reperto.identificativo.as_deref(), Some("77/B"));
+    ///
+    /// assert!(RegoleOcr::da_testo("peso = ^Peso").is_err()); // manca il gruppo
+    /// assert!(RegoleOcr::da_testo("colore = (.+)").is_err());
+    /// ```
+    pub fn da_testo(testo: &str) -> Result<RegoleOcr, ErroreRegole> {
+        let mut regole = Vec::new();
+        for (i, linea) in testo.lines().enumerate() {
+            let riga = i + 1;
+            let linea = linea.trim();
+            if linea.is_empty() || linea.starts_with('#') {
+                continue;
+            }
+            let sintassi = |motivo: String| ErroreRegole::Sintassi { riga, motivo };
+            let Some((campo, schema)) = linea.split_once('=') else {
+                return Err(sintassi("attesa una riga `campo = espressione`".to_string()));
+            };
+            let Some(campo) = CampoScheda::da_nome(campo.trim()) else {
+                return Err(ErroreRegole::CampoSconosciuto { riga, campo: campo.trim().to_string() });
+            };
+            let schema = compila(schema.trim()).map_err(|e| sintassi(e.to_string()))?;
+            if schema.captures_len() < 2 {
+                return Err(sintassi("manca il gruppo tra parentesi con il valore".to_string()));
+            }
+            regole.push((campo, schema));
+        }
+        regole.extend(RegoleOcr::predefinite().regole);
+        Ok(RegoleOcr { regole })
+    }
+
+    /// Il primo valore non vuoto trovato per il campo
+    fn cerca<'t>(&self, campo: CampoScheda, scheda: &'t str) -> Option<&'t str> {
+        self.regole
+            .iter()
+            .filter(|(c, _)| *c == campo)
+            .filter_map(|(_, schema)| schema.captures(scheda)?.get(1))
+            .map(|valore| valore.as_str().trim())
+            .find(|valore| !valore.is_empty())
+    }
+
+    /// La bozza di una scheda. `sito` vale quando la scheda non indica la
+    /// localita di rinvenimento.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::modelli::Materiale;
+    /// use rust_tutorial::catalogo::ocr::RegoleOcr;
+    ///
+    /// let scheda = "N. inv. 4512\nOggetto: ascia a margini rialzati\nMateriale: bronzo\nPeso: gr. 3l2";
+    /// let bozza = RegoleOcr::predefinite().leggi(scheda, "Savignano Irpino");
+    /// assert_eq!(bozza.reperto.nome, "ascia a margini rialzati");
+    /// assert_eq!(bozza.reperto.materiale(), Some(&Materiale::Bronzo));
+    /// assert_eq!(bozza.reperto.misurazioni.peso_grammi, Some(312.0));
+    /// assert_eq!(bozza.dubbi[0].to_string(), "conservazione: non trovata, assunto Discreto");
+    /// assert_eq!(bozza.dubbi[2].to_string(), "peso: letto '3l2', inteso 312");
+    /// ```
+    pub fn leggi(&self, scheda: &str, sito: &str) -> Bozza {
+        let mut lettura = Lettura { regole: self, scheda, dubbi: Vec::new() };
+
+        let identificativo = lettura.cerca(CampoScheda::Inventario, "non trovato").map(str::to_string);
+        let nome = lettura.cerca(CampoScheda::Oggetto, "non trovato").unwrap_or("Scheda senza oggetto").to_string();
+        let materiale = match lettura.cerca(CampoScheda::Materiale, "non trovato") {
+            Some(testo) => Materiale::riconosci(testo).unwrap_or_else(|| {
+                lettura.dubbio(CampoScheda::Materiale, format!("'{}' non riconosciuto", testo));
+                Materiale::Altro(testo.to_string())
+            }),
+            None => Materiale::Altro("non indicato".to_string()),
+        };
+        let conservazione = match lettura.cerca(CampoScheda::Conservazione, "non trovata, assunto Discreto") {
+            Some(testo) => Conservazione::riconosci(testo).unwrap_or_else(|| {
+                lettura.dubbio(CampoScheda::Conservazione, format!("'{}' non riconosciuta, assunto Discreto", testo));
+                Conservazione::Discreto
+            }),
+            None => Conservazione::Discreto,
+        };
+        let sito = lettura
+            .cerca(CampoScheda::Sito, &format!("non trovato, usato '{}'", sito))
+            .unwrap_or(sito)
+            .to_string();
+
+        let [lunghezza, larghezza, altezza] = lettura.misure();
+        let misurazioni = Misurazioni {
+            lunghezza_cm: lettura.misura(CampoScheda::Lunghezza).or(lunghezza),
+            larghezza_cm: lettura.misura(CampoScheda::Larghezza).or(larghezza),
+            altezza_cm: lettura.misura(CampoScheda::Altezza).or(altezza),
+            peso_grammi: lettura.misura(CampoScheda::Peso),
+        };
+
+        let dubbi = lettura.dubbi;
+        let mut reperto = Reperto::nuovo(&nome, materiale, Periodo::Sconosciuto, conservazione, &sito)
+            .con_misurazioni(misurazioni);
+        reperto.identificativo = identificativo;
+        let elenco = dubbi.iter().map(Dubbio::to_string).collect::<Vec<_>>().join("; ");
+        reperto.note.push(Nota::from(
+            if dubbi.is_empty() {
+                format!("{}: da verificare sull'originale", NOTA_BOZZA)
+            } else {
+                format!("{}, da verificare: {}", NOTA_BOZZA, elenco)
+            }
+            .as_str(),
+        ));
+        reperto.note.push(Nota::from(format!("Testo OCR:\n{}", scheda.trim()).as_str()));
+        Bozza { reperto, dubbi }
+    }
+
+    /// Le bozze di tutte le schede di un testo, una per pagina. Le pagine
+    /// vuote si saltano.
+    pub fn leggi_tutte(&self, testo: &str, sito: &str) -> Vec<Bozza> {
+        testo
+            .split('\x0c')
+            .filter(|scheda| !scheda.trim().is_empty())
+            .map(|scheda| self.leggi(scheda, sito))
+            .collect()
+    }
+}
+
+/// Un valore da controllare sulla scheda originale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dubbio {
+    pub campo: CampoScheda,
+    pub motivo: String,
+}
+
+impl fmt::Display for Dubbio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.campo, self.motivo)
+    }
+}
+
+/// Un reperto ricavato da una scheda cartacea, con i dubbi della lettura
+/// (gli stessi scritti nella sua prima nota).
+#[derive(Debug, Clone)]
+pub struct Bozza {
+    pub reperto: Reperto,
+    pub dubbi: Vec<Dubbio>,
+}
+
+/// Vero per le bozze OCR non ancora passate per la catalogazione
+pub fn e_bozza(reperto: &Reperto) -> bool {
+    reperto.stato == StatoCatalogazione::InIngresso && reperto.note.iter().any(|n| n.testo.starts_with(NOTA_BOZZA))
+}
+
+/// Una scheda in lettura, con i dubbi raccolti finora
+struct Lettura<'a> {
+    regole: &'a RegoleOcr,
+    scheda: &'a str,
+    dubbi: Vec<Dubbio>,
+}
+
+impl<'a> Lettura<'a> {
+    fn dubbio(&mut self, campo: CampoScheda, motivo: String) {
+        self.dubbi.push(Dubbio { campo, motivo });
+    }
+
+    /// Il valore del campo; se manca, `assente` diventa un dubbio
+    fn cerca(&mut self, campo: CampoScheda, assente: &str) -> Option<&'a str> {
+        let valore = self.regole.cerca(campo, self.scheda);
+        if valore.is_none() {
+            self.dubbio(campo, assente.to_string());
+        }
+        valore
+    }
+
+    /// Una misura singola (`Peso: gr. 312`); nessun dubbio se la scheda non la riporta
+    fn misura(&mut self, campo: CampoScheda) -> Option<f64> {
+        let testo = self.regole.cerca(campo, self.scheda)?;
+        let (numeri, parole) = numeri(testo);
+        let Some(primo) = numeri.first() else {
+            self.dubbio(campo, format!("nessun numero in '{}'", testo));
+            return None;
+        };
+        let unita = unita_in(&parole);
+        let valore = self.converti(campo, primo, unita)?;
+        if unita.is_none() {
+            self.dubbio(campo, format!("senza unita, inteso {}", nell_unita_del_campo(campo, valore)));
+        }
+        Some(valore)
+    }
+
+    /// Lunghezza, larghezza e altezza da una riga `Misure: 12 x 4 x 1 cm`
+    fn misure(&mut self) -> [Option<f64>; 3] {
+        let mut dimensioni = [None; 3];
+        let Some(testo) = self.regole.cerca(CampoScheda::Misure, self.scheda) else {
+            return dimensioni;
+        };
+        let (numeri, parole) = numeri(testo);
+        if numeri.is_empty() || numeri.len() > 3 {
+            self.dubbio(CampoScheda::Misure, format!("'{}' non e lunghezza x larghezza x altezza", testo));
+        }
+        let unita = unita_in(&parole);
+        if unita.is_none() && !numeri.is_empty() {
+            self.dubbio(CampoScheda::Misure, "senza unita, intesi centimetri".to_string());
+        }
+        let campi = [CampoScheda::Lunghezza, CampoScheda::Larghezza, CampoScheda::Altezza];
+        for ((campo, dimensione), letto) in campi.into_iter().zip(&mut dimensioni).zip(&numeri) {
+            *dimensione = self.converti(campo, letto, unita);
+        }
+        dimensioni
+    }
+
+    /// Il numero letto nell'unita del campo; segnala le cifre corrette e
+    /// le unita dell'altra grandezza
+    fn converti(&mut self, campo: CampoScheda, (letto, valore): &(String, f64), unita: Option<&'static str>) -> Option<f64> {
+        let (nome, dimensione) = unita::campo(campo.nome()).expect("le misure sono campi del reperto");
+        let quantita = Quantita { valore: *valore, unita };
+        match quantita.nell_unita_di(nome, dimensione) {
+            Ok(convertito) => {
+                if letto.contains(['O', 'o', 'l', 'I']) {
+                    self.dubbio(campo, format!("letto '{}', inteso {}", letto, valore));
+                }
+                Some(convertito)
+            }
+            Err(e) => {
+                self.dubbio(campo, e.to_string());
+                None
+            }
+        }
+    }
+}
+
+fn nell_unita_del_campo(campo: CampoScheda, valore: f64) -> String {
+    match unita::campo(campo.nome()) {
+        Some((_, Dimensione::Massa)) => format!("{} g", valore),
+        _ => format!("{} cm", valore),
+    }
+}
+
+/// I numeri del testo, con le lettere che l'OCR scambia per cifre
+/// (O per 0, l e I per 1) e la virgola decimale, e le parole che restano.
+/// Di ogni numero si tiene anche il testo letto.
+fn numeri(testo: &str) -> (Vec<(String, f64)>, Vec<String>) {
+    let cifra = |c: char| c.is_ascii_digit() || matches!(c, 'O' | 'o' | 'l' | 'I' | '.' | ',');
+    let mut numeri = Vec::new();
+    let mut resto = String::new();
+    let mut corrente = String::new();
+    for c in testo.chars().chain([' ']) {
+        if cifra(c) {
+            corrente.push(c);
+            continue;
+        }
+        let gruppo = std::mem::take(&mut corrente);
+        let letto = gruppo.trim_matches(['.', ',']);
+        let normale: String = letto
+            .chars()
+            .map(|c| match c {
+                'O' | 'o' => '0',
+                'l' | 'I' => '1',
+                ',' => '.',
+                c => c,
+            })
+            .collect();
+        // "Bronzo" contiene "o": e un numero solo se c'e almeno una cifra vera
+        match normale.parse() {
+            Ok(valore) if letto.chars().any(|c| c.is_ascii_digit()) => numeri.push((letto.to_string(), valore)),
+            _ => resto.push_str(&gruppo),
+        }
+        resto.push(c);
+    }
+    let parole = resto
+        .split(|c: char| !(c.is_alphanumeric() || c == '%'))
+        .filter(|p| !p.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    (numeri, parole)
+}
+
+/// La prima parola che e un'unita di misura (anche `gr` per grammi)
+fn unita_in(parole: &[String]) -> Option<&'static str> {
+    parole.iter().find_map(|parola| {
+        let parola = match parola.as_str() {
+            "gr" => "g",
+            "mt" => "m",
+            p => p,
+        };
+        Quantita::da_testo(&format!("1 {}", parola)).ok()?.unita
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEDE: &str = "\
+MUSEO CIVICO - SCHEDA DI INVENTARIO
+N. inv. 4512
+Oggetto: ascia a margini rialzati
+Materiale: br.
+Localita: Savignano sul Panaro
+Stato di conservazione: buono, lievi lacune al tallone
+Misure: 12,5 x 4 x 1,2 cm
+Peso: gr. 3l2
+\x0c
+INVN: 1O33
+OGTD: spillone
+MTC: lega di rame
+Lungh. 0,21 m
+Peso: 25 cm
+\x0c
+   \n";
+
+    #[test]
+    fn bozze_dalle_schede() {
+        let bozze = RegoleOcr::predefinite().leggi_tutte(SCHEDE, "Frattesina");
+        assert_eq!(bozze.len(), 2);
+
+        let ascia = &bozze[0].reperto;
+        assert_eq!(ascia.identificativo.as_deref(), Some("4512"));
+        assert_eq!(ascia.sito, "Savignano sul Panaro");
+        assert_eq!(ascia.conservazione, Conservazione::Buono);
+        assert_eq!(ascia.misurazioni.lunghezza_cm, Some(12.5));
+        assert_eq!(ascia.misurazioni.altezza_cm, Some(1.2));
+        assert_eq!(ascia.misurazioni.peso_grammi, Some(312.0));
+        assert_eq!(bozze[0].dubbi, [Dubbio { campo: CampoScheda::Peso, motivo: "letto '3l2', inteso 312".into() }]);
+        assert!(e_bozza(ascia));
+        assert!(ascia.note[0].testo.ends_with("da verificare: peso: letto '3l2', inteso 312"));
+
+        let spillone = &bozze[1];
+        // L'inventario resta com'e: i codici possono contenere lettere
+        assert_eq!(spillone.reperto.identificativo.as_deref(), Some("1O33"));
+        assert_eq!(spillone.reperto.sito, "Frattesina");
+        assert_eq!(spillone.reperto.materiale(), Some(&Materiale::Altro("lega di rame".into())));
+        assert_eq!(spillone.reperto.misurazioni.lunghezza_cm, Some(21.0));
+        assert_eq!(spillone.reperto.misurazioni.peso_grammi, None);
+        let dubbi: Vec<String> = spillone.dubbi.iter().map(Dubbio::to_string).collect();
+        assert_eq!(
+            dubbi,
+            [
+                "materiale: 'lega di rame' non riconosciuto",
+                "conservazione: non trovata, assunto Discreto",
+                "sito: non trovato, usato 'Frattesina'",
+                "peso: 'peso_grammi' e una massa, '25cm' e una lunghezza",
+            ]
+        );
+    }
+
+    #[test]
+    fn numeri_letti_dall_ocr() {
+        let (numeri, parole) = numeri("cm l2,5 x O,8 - Bronzo");
+        assert_eq!(numeri, [("l2,5".to_string(), 12.5), ("O,8".to_string(), 0.8)]);
+        assert_eq!(parole, ["cm", "x", "bronzo"]);
+        assert_eq!(unita_in(&parole), Some("cm"));
+    }
+}
@@ -0,0 +1,109 @@
+// ============================================================================
+// MODULO: PDF SCRITTI A MANO
+// ============================================================================
+// Etichette e dossier hanno bisogno solo di testo e di quadrati pieni: per
+// questo il PDF si scrive a mano, come l'SVG dei grafici del capitolo 9,
+// senza una libreria. Il file ha sempre la stessa struttura:
+//
+//   1 catalogo, 2 albero delle pagine, 3-6 i font standard che ogni
+//   lettore ha gia (F1 Helvetica, F2 Helvetica-Bold, F3 Courier,
+//   F4 Courier-Bold), poi una pagina A4 e il suo contenuto per foglio
+//
+// e in fondo la tabella con la posizione di ogni oggetto. Il contenuto di
+// una pagina e una serie di comandi PDF (`BT ... Tj ET` per il testo,
+// `re` e `f` per i rettangoli), in punti con l'origine in basso a
+// sinistra.
+// ============================================================================
+
+//! Il minimo per scrivere PDF di testo e rettangoli su fogli A4.
+
+/// Punti PDF in un millimetro
+pub(crate) const PUNTI_PER_MM: f64 = 72.0 / 25.4;
+pub(crate) const A4_MM: (f64, f64) = (210.0, 297.0);
+
+const FONT: [&str; 4] = ["Helvetica", "Helvetica-Bold", "Courier", "Courier-Bold"];
+
+/// Una stringa PDF tra parentesi, tagliata a `massimo` caratteri; le
+/// lettere accentate passano in WinAnsi, il resto diventa "?"
+pub(crate) fn stringa(testo: &str, massimo: usize) -> String {
+    let mut caratteri: Vec<char> = testo.chars().collect();
+    if caratteri.len() > massimo {
+        caratteri.truncate(massimo.saturating_sub(3));
+        caratteri.extend("...".chars());
+    }
+    caratteri
+        .into_iter()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            '\u{a0}'..='\u{ff}' => format!("\\{:03o}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Il file PDF, una pagina A4 per contenuto
+pub(crate) fn documento(pagine: &[String]) -> Vec<u8> {
+    let (larghezza, altezza) = (A4_MM.0 * PUNTI_PER_MM, A4_MM.1 * PUNTI_PER_MM);
+    let primo = 3 + FONT.len();
+    let figli: Vec<String> = (0..pagine.len()).map(|i| format!("{} 0 R", primo + 2 * i)).collect();
+    let mut oggetti = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", figli.join(" "), pagine.len()),
+    ];
+    oggetti.extend(
+        FONT.iter().map(|f| format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", f)),
+    );
+    let risorse: Vec<String> = (0..FONT.len()).map(|i| format!("/F{} {} 0 R", i + 1, i + 3)).collect();
+    for (i, contenuto) in pagine.iter().enumerate() {
+        oggetti.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+            larghezza,
+            altezza,
+            risorse.join(" "),
+            primo + 1 + 2 * i
+        ));
+        oggetti.push(format!("<< /Length {} >>\nstream\n{}endstream", contenuto.len(), contenuto));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut posizioni = Vec::new();
+    for (i, oggetto) in oggetti.iter().enumerate() {
+        posizioni.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, oggetto));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", oggetti.len() + 1));
+    for posizione in posizioni {
+        pdf.push_str(&format!("{:010} 00000 n \n", posizione));
+    }
+    pdf.push_str(&format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", oggetti.len() + 1, xref));
+    pdf.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn testo_tagliato_e_codificato() {
+        assert_eq!(stringa("Cassa 12", 20), "Cassa 12");
+        assert_eq!(stringa("Deposito centrale, scaffale 4", 12), "Deposito ...");
+        assert_eq!(stringa("Citta \u{e0} \u{2192}", 20), "Citta \\340 ?");
+    }
+
+    #[test]
+    fn tabella_degli_oggetti() {
+        let pdf = String::from_utf8(documento(&["BT /F3 9 Tf 10 10 Td (uno) Tj ET\n".to_string(), String::new()])).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("/Kids [7 0 R 9 0 R] /Count 2"));
+        assert!(pdf.contains("/F3 5 0 R"));
+        // startxref punta davvero alla tabella, e ogni voce al suo oggetto
+        let xref: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+        for (n, voce) in pdf[xref..].lines().skip(3).take(10).enumerate() {
+            let posizione: usize = voce[..10].parse().unwrap();
+            assert!(pdf[posizione..].starts_with(&format!("{} 0 obj", n + 1)));
+        }
+    }
+}
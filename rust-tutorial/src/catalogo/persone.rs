@@ -0,0 +1,126 @@
+// ============================================================================
+// MODULO: PERSONE E ATTIVITA
+// ============================================================================
+// L'anagrafica collega le persone a rinvenimenti, foto, disegni e note;
+// il registro delle attivita dice chi ha creato o modificato quali
+// schede, per il report annuale di catalogazione.
+// ============================================================================
+
+//! Attivita delle persone sull'inventario e report per periodo.
+
+use super::inventario::Inventario;
+use super::modelli::Operazione;
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Riepilogo dell'attivita di una persona sull'inventario
+#[derive(Debug, Default)]
+pub struct Attivita {
+    pub reperti_rinvenuti: Vec<u32>,
+    pub allegati_prodotti: usize,
+    pub note_scritte: usize,
+}
+
+/// Raccoglie rinvenimenti, foto/disegni e note attribuiti a una persona
+pub fn attivita(inv: &Inventario, persona: u32) -> Attivita {
+    let mut attivita = Attivita::default();
+    for r in inv.tutti() {
+        if r.rinvenuto_da == Some(persona) {
+            attivita.reperti_rinvenuti.push(r.id);
+        }
+        attivita.allegati_prodotti += r.allegati.iter().filter(|a| a.autore == Some(persona)).count();
+        attivita.note_scritte += r.note.iter().filter(|n| n.autore == Some(persona)).count();
+    }
+    attivita
+}
+
+/// Stampa il report di attivita di tutte le persone registrate
+pub fn stampa_report(inv: &Inventario) {
+    println!("  {:<30} {:>10} {:>8} {:>6}", "PERSONA", "RINVENUTI", "ALLEGATI", "NOTE");
+    for p in inv.persone() {
+        let a = attivita(inv, p.id);
+        println!(
+            "  {:<30} {:>10} {:>8} {:>6}",
+            p.nome,
+            a.reperti_rinvenuti.len(),
+            a.allegati_prodotti,
+            a.note_scritte
+        );
+    }
+}
+
+/// Ampiezza dei periodi in cui suddividere il report
+#[derive(Debug, Clone, Copy)]
+pub enum Cadenza {
+    Mese,
+    Trimestre,
+    Anno,
+}
+
+impl Cadenza {
+    fn etichetta(self, data: NaiveDate) -> String {
+        match self {
+            Cadenza::Mese => format!("{}-{:02}", data.year(), data.month()),
+            Cadenza::Trimestre => format!("{}-T{}", data.year(), data.month0() / 3 + 1),
+            Cadenza::Anno => data.year().to_string(),
+        }
+    }
+}
+
+/// Schede create e modificate da una persona in un periodo
+#[derive(Debug)]
+pub struct RigaAttivita {
+    pub periodo: String,
+    pub persona: u32,
+    pub nome: String,
+    pub creati: usize,
+    /// Reperti distinti modificati o spostati di stato (una scheda
+    /// ritoccata dieci volte conta una volta)
+    pub modificati: usize,
+}
+
+/// Report per persona e periodo dal registro delle attivita, limitato
+/// alle operazioni tra `da` e `a` compresi
+pub fn attivita_per_periodo(inv: &Inventario, da: NaiveDate, a: NaiveDate, cadenza: Cadenza) -> Vec<RigaAttivita> {
+    let mut gruppi: BTreeMap<(String, u32), (BTreeSet<u32>, BTreeSet<u32>)> = BTreeMap::new();
+    for voce in inv.registro() {
+        let giorno = voce.quando.date();
+        if giorno < da || giorno > a {
+            continue;
+        }
+        let (creati, modificati) = gruppi.entry((cadenza.etichetta(giorno), voce.persona)).or_default();
+        match voce.operazione {
+            Operazione::Creazione => {
+                creati.insert(voce.reperto);
+            }
+            Operazione::Modifica | Operazione::CambioStato => {
+                modificati.insert(voce.reperto);
+            }
+            Operazione::Rimozione | Operazione::Radiazione => {}
+        }
+    }
+    gruppi
+        .into_iter()
+        .map(|((periodo, persona), (creati, modificati))| RigaAttivita {
+            periodo,
+            persona,
+            nome: inv.persona(persona).map_or_else(|_| format!("#{}", persona), |p| p.nome.clone()),
+            creati: creati.len(),
+            modificati: modificati.len(),
+        })
+        .collect()
+}
+
+pub fn stampa_attivita_per_periodo(righe: &[RigaAttivita]) {
+    println!("  {:<10} {:<30} {:>7} {:>11}", "PERIODO", "PERSONA", "CREATE", "MODIFICATE");
+    for r in righe {
+        println!("  {:<10} {:<30} {:>7} {:>11}", r.periodo, r.nome, r.creati, r.modificati);
+    }
+}
+
+/// Stesso report in CSV (separatore `;`), da allegare alla relazione annuale
+pub fn csv_attivita(righe: &[RigaAttivita]) -> String {
+    righe.iter().fold("periodo;persona;nome;create;modificate\n".to_string(), |csv, r| {
+        csv + &format!("{};{};{};{};{}\n", r.periodo, r.persona, r.nome, r.creati, r.modificati)
+    })
+}
@@ -0,0 +1,95 @@
+// ============================================================================
+// MODULO: ANALISI PONDERALE
+// ============================================================================
+// Lingotti e asce possono essere multipli di un'unita di peso. Il
+// quantogramma del coseno (Kendall) misura, per ogni unita candidata,
+// quanto i pesi si addensano attorno ai suoi multipli: il picco indica
+// l'unita piu probabile.
+// ============================================================================
+
+//! Quantogramma del coseno per cercare unita di peso.
+
+use super::errori::ErroreInventario;
+use super::modelli::Reperto;
+use std::f64::consts::PI;
+
+/// Scarto di un oggetto rispetto al multiplo piu vicino dell'unita
+#[derive(Debug)]
+pub struct Residuo {
+    pub id_reperto: u32,
+    pub peso_g: f64,
+    pub multiplo: u32,
+    /// Peso meno multiplo * unita, in grammi
+    pub scarto_g: f64,
+}
+
+/// Esito dell'analisi: unita migliore e scarti dei singoli oggetti
+#[derive(Debug)]
+pub struct AnalisiPonderale {
+    pub unita_g: f64,
+    /// Valore del quantogramma per l'unita migliore
+    pub phi: f64,
+    pub residui: Vec<Residuo>,
+    /// Media di |scarto| / unita: 0.25 per pesi casuali, 0 per multipli esatti
+    pub scarto_relativo_medio: f64,
+}
+
+/// Quantogramma del coseno di Kendall: valori alti indicano che i pesi
+/// si concentrano attorno a multipli interi dell'unita
+pub fn phi(pesi: &[f64], unita: f64) -> f64 {
+    let n = pesi.len() as f64;
+    (2.0 / n).sqrt() * pesi.iter().map(|w| (2.0 * PI * w / unita).cos()).sum::<f64>()
+}
+
+/// Cerca l'unita ponderale tra `min_g` e `max_g` che meglio spiega i pesi
+/// dei reperti. L'intervallo va scelto con cura: anche i sottomultipli
+/// dell'unita reale producono picchi del quantogramma
+pub fn analizza(
+    reperti: &[&Reperto],
+    min_g: f64,
+    max_g: f64,
+    passo_g: f64,
+) -> Result<AnalisiPonderale, ErroreInventario> {
+    if !(min_g > 0.0 && max_g > min_g && passo_g > 0.0) {
+        return Err(ErroreInventario::DatiNonValidi(format!(
+            "intervallo di ricerca non valido: {}-{} g, passo {}",
+            min_g, max_g, passo_g
+        )));
+    }
+    let pesati: Vec<(u32, f64)> = reperti
+        .iter()
+        .filter_map(|r| r.misurazioni.peso_grammi.map(|p| (r.id, p)))
+        .collect();
+    if pesati.len() < 3 {
+        return Err(ErroreInventario::DatiNonValidi(
+            "servono almeno tre reperti pesati".to_string(),
+        ));
+    }
+    let pesi: Vec<f64> = pesati.iter().map(|(_, p)| *p).collect();
+
+    let passi = ((max_g - min_g) / passo_g).floor() as usize;
+    let (unita_g, phi_max) = (0..=passi)
+        .map(|i| {
+            let unita = min_g + i as f64 * passo_g;
+            (unita, phi(&pesi, unita))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("almeno un passo");
+
+    let residui: Vec<Residuo> = pesati
+        .iter()
+        .map(|&(id_reperto, peso_g)| {
+            let multiplo = (peso_g / unita_g).round().max(1.0);
+            Residuo {
+                id_reperto,
+                peso_g,
+                multiplo: multiplo as u32,
+                scarto_g: peso_g - multiplo * unita_g,
+            }
+        })
+        .collect();
+    let scarto_relativo_medio =
+        residui.iter().map(|r| r.scarto_g.abs() / unita_g).sum::<f64>() / residui.len() as f64;
+
+    Ok(AnalisiPonderale { unita_g, phi: phi_max, residui, scarto_relativo_medio })
+}
@@ -0,0 +1,197 @@
+// ============================================================================
+// MODULO: ARCHIVIO POSTGRESQL
+// ============================================================================
+// Per le installazioni istituzionali l'inventario sta in PostgreSQL: i
+// campi variabili in una colonna JSONB, nome e descrizione con indici
+// trigram per la ricerca testuale. sqlx e asincrono; l'archivio lo
+// nasconde dietro la stessa interfaccia sincrona degli altri.
+// ============================================================================
+
+//! Archivio PostgreSQL con pool di connessioni.
+
+// Il modulo intero esiste solo con la feature: e l'unico a usare sqlx
+#![cfg(feature = "db")]
+
+use super::archivio::{Archivio, Scrittura, VersioniLette};
+use super::errori::ErroreInventario;
+use super::inventario::{Inventario, StatoInventario};
+use super::modelli::Reperto;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::types::Json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+/// Schema idempotente: i campi variabili restano nel JSONB `dati`,
+/// nome e descrizione hanno indici trigram per la ricerca testuale
+const SCHEMA: &[&str] = &[
+    "CREATE EXTENSION IF NOT EXISTS pg_trgm",
+    "CREATE TABLE IF NOT EXISTS reperti (
+         id BIGINT PRIMARY KEY,
+         nome TEXT NOT NULL,
+         sito TEXT NOT NULL,
+         dati JSONB NOT NULL
+     )",
+    "CREATE INDEX IF NOT EXISTS reperti_nome_trgm ON reperti USING gin (nome gin_trgm_ops)",
+    "CREATE INDEX IF NOT EXISTS reperti_descrizione_trgm
+         ON reperti USING gin ((dati->>'descrizione') gin_trgm_ops)",
+    "CREATE INDEX IF NOT EXISTS reperti_dati ON reperti USING gin (dati jsonb_path_ops)",
+    // La versione in colonna serve agli UPDATE condizionati del salvataggio
+    "ALTER TABLE reperti ADD COLUMN IF NOT EXISTS versione BIGINT NOT NULL DEFAULT 0",
+    "UPDATE reperti SET versione = (dati->>'versione')::BIGINT
+         WHERE versione = 0 AND dati ? 'versione'",
+    // Lo stato dell'inventario (strategia degli identificativi e il resto)
+    "CREATE TABLE IF NOT EXISTS stato_inventario (
+         chiave SMALLINT PRIMARY KEY CHECK (chiave = 1),
+         dati JSONB NOT NULL
+     )",
+];
+
+/// Inventario su PostgreSQL per installazioni istituzionali. Il pool di
+/// connessioni e condiviso; l'interfaccia resta sincrona come gli altri archivi
+pub struct ArchivioPostgres {
+    runtime: tokio::runtime::Runtime,
+    pool: PgPool,
+    versioni: VersioniLette,
+}
+
+impl ArchivioPostgres {
+    /// Non si connette subito: la prima connessione si apre al primo utilizzo
+    pub fn nuovo(url: &str, connessioni: u32) -> Result<Self, ErroreInventario> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let pool = {
+            let _contesto = runtime.enter();
+            PgPoolOptions::new()
+                .max_connections(connessioni)
+                .acquire_timeout(Duration::from_secs(5))
+                .connect_lazy(url)?
+        };
+        Ok(ArchivioPostgres { runtime, pool, versioni: VersioniLette::default() })
+    }
+
+    async fn prepara(&self) -> Result<(), ErroreInventario> {
+        for istruzione in SCHEMA {
+            sqlx::query(istruzione).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Ricerca per somiglianza su nome e descrizione, servita dagli indici trigram
+    pub fn cerca_testo(&self, testo: &str, limite: i64) -> Result<Vec<Reperto>, ErroreInventario> {
+        self.runtime.block_on(async {
+            self.prepara().await?;
+            let righe: Vec<(Json<Reperto>,)> = sqlx::query_as(
+                "SELECT dati FROM reperti
+                 WHERE nome % $1 OR nome ILIKE '%' || $1 || '%'
+                    OR dati->>'descrizione' ILIKE '%' || $1 || '%'
+                 ORDER BY similarity(nome, $1) DESC, id
+                 LIMIT $2",
+            )
+            .bind(testo)
+            .bind(limite)
+            .fetch_all(&self.pool)
+            .await?;
+            Ok(righe.into_iter().map(|(Json(r),)| r).collect())
+        })
+    }
+}
+
+impl Archivio for ArchivioPostgres {
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        self.runtime.block_on(async {
+            sqlx::query("SELECT 1").execute(&self.pool).await?;
+            self.prepara().await
+        })
+    }
+
+    /// Attende le query in corso e chiude le connessioni del pool
+    fn chiudi(&self) -> Result<(), ErroreInventario> {
+        self.runtime.block_on(self.pool.close());
+        Ok(())
+    }
+
+    /// Come per SQLite: si scrivono solo le righe cambiate, con un UPDATE
+    /// condizionato alla versione letta; se un'altra sessione l'ha gia
+    /// cambiata, `Conflitto` e la transazione si annulla
+    fn salva(&self, inventario: &Inventario) -> Result<(), ErroreInventario> {
+        self.runtime.block_on(async {
+            self.prepara().await?;
+            let mut tx = self.pool.begin().await?;
+            let righe: Vec<(i64, i64)> = sqlx::query_as("SELECT id, versione FROM reperti").fetch_all(&mut *tx).await?;
+            let salvate: BTreeMap<u32, u64> = righe.into_iter().map(|(id, v)| (id as u32, v as u64)).collect();
+            let reperti = inventario.tutti_compresi_radiati();
+            let conflitto = |id: u32, letta: u64| ErroreInventario::Conflitto { id, letta, attuale: salvate[&id] };
+
+            for r in &reperti {
+                match self.versioni.scrittura(r.id, Some(r.versione), salvate.get(&r.id).copied())? {
+                    Scrittura::Invariato => {}
+                    Scrittura::Nuovo => {
+                        sqlx::query("INSERT INTO reperti (id, nome, sito, dati, versione) VALUES ($1, $2, $3, $4, $5)")
+                            .bind(i64::from(r.id))
+                            .bind(&r.nome)
+                            .bind(&r.sito)
+                            .bind(Json(r))
+                            .bind(r.versione as i64)
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                    Scrittura::Sovrascrivi { attesa } => {
+                        let esito = sqlx::query(
+                            "UPDATE reperti SET nome = $2, sito = $3, dati = $4, versione = $5
+                             WHERE id = $1 AND versione = $6",
+                        )
+                        .bind(i64::from(r.id))
+                        .bind(&r.nome)
+                        .bind(&r.sito)
+                        .bind(Json(r))
+                        .bind(r.versione as i64)
+                        .bind(attesa as i64)
+                        .execute(&mut *tx)
+                        .await?;
+                        if esito.rows_affected() == 0 {
+                            return Err(conflitto(r.id, attesa));
+                        }
+                    }
+                }
+            }
+
+            let presenti: BTreeSet<u32> = reperti.iter().map(|r| r.id).collect();
+            for (&id, &salvata) in salvate.iter().filter(|(id, _)| !presenti.contains(id)) {
+                if let Scrittura::Sovrascrivi { attesa } = self.versioni.scrittura(id, None, Some(salvata))? {
+                    let esito = sqlx::query("DELETE FROM reperti WHERE id = $1 AND versione = $2")
+                        .bind(i64::from(id))
+                        .bind(attesa as i64)
+                        .execute(&mut *tx)
+                        .await?;
+                    if esito.rows_affected() == 0 {
+                        return Err(conflitto(id, attesa));
+                    }
+                }
+            }
+            sqlx::query(
+                "INSERT INTO stato_inventario (chiave, dati) VALUES (1, $1)
+                 ON CONFLICT (chiave) DO UPDATE SET dati = EXCLUDED.dati",
+            )
+            .bind(Json(inventario.stato()))
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            self.versioni.ricorda(reperti);
+            Ok(())
+        })
+    }
+
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        self.runtime.block_on(async {
+            self.prepara().await?;
+            let righe: Vec<(Json<Reperto>,)> = sqlx::query_as("SELECT dati FROM reperti ORDER BY id")
+                .fetch_all(&self.pool)
+                .await?;
+            let reperti: Vec<Reperto> = righe.into_iter().map(|(Json(r),)| r).collect();
+            self.versioni.ricorda(&reperti);
+            let stato: Option<(Json<StatoInventario>,)> = sqlx::query_as("SELECT dati FROM stato_inventario")
+                .fetch_optional(&self.pool)
+                .await?;
+            Inventario::da_archivio(reperti, stato.map(|(Json(s),)| s).unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,44 @@
+// ============================================================================
+// MODULO: PRELUDE DEL CATALOGO
+// ============================================================================
+// I tipi che servono quasi sempre stanno in moduli diversi (il reperto in
+// `modelli`, l'inventario in `inventario`, l'errore in `errori`). Con il
+// prelude basta una riga:
+//
+//   use rust_tutorial::catalogo::prelude::*;
+//
+// Qui ci sono solo i tipi di uso comune, il builder e i trait; quelli piu
+// specifici (campioni, geo, OCR, etichette...) si importano dal loro
+// modulo, cosi un `*` non riempie lo spazio dei nomi di chi lo usa.
+// ============================================================================
+
+//! Una riga per importare i tipi principali del catalogo.
+//!
+//! ```
+//! use rust_tutorial::catalogo::prelude::*;
+//!
+//! fn prova() -> Result<(), ErroreInventario> {
+//!     let mut inv = Inventario::nuovo();
+//!     let ascia = Reperto::builder()
+//!         .nome("Ascia")
+//!         .materiale(Materiale::Bronzo)
+//!         .conservazione(Conservazione::Buono)
+//!         .sito("Frattesina")
+//!         .build()?;
+//!     let id = inv.aggiungi(ascia)?;
+//!     assert_eq!(inv.cerca_per_id(id)?.nome, "Ascia");
+//!     Ok(())
+//! }
+//! prova().unwrap();
+//! ```
+
+pub use super::errori::ErroreInventario;
+pub use super::inventario::{Inventario, Ordinamento, StrategiaId};
+pub use super::modelli::{
+    ClasseFunzionale, Componente, Conservazione, Coordinate, ErroreReperto, Materiale, Misurazioni, Nota, Periodo,
+    Reperto, RepertoBuilder, StatoCatalogazione,
+};
+pub use super::narrativa::{Narrativa, Rifinitore};
+pub use super::ricerca::{Condizione, Confronto};
+pub use super::statistiche::ReportStatistiche;
+pub use super::unita::Quantita;
@@ -0,0 +1,139 @@
+// ============================================================================
+// MODULO: PRESTITI
+// ============================================================================
+// Un prestito porta uno o piu reperti da un'istituzione a un'altra per
+// una mostra o uno studio. Il contratto si genera da un modello di testo
+// con segnaposto; un prestito e scaduto se la data di fine e passata e
+// qualche reperto non e rientrato.
+// ============================================================================
+
+//! Prestiti tra istituzioni, contratti e scadenze.
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::TipoEventoProvenienza;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Ente coinvolto in un prestito (museo, soprintendenza, universita)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Istituzione {
+    pub nome: String,
+    pub citta: String,
+    pub referente: String,
+}
+
+/// Prestito di uno o piu reperti per una mostra o uno studio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prestito {
+    pub reperti: Vec<u32>,
+    pub prestatore: Istituzione,
+    pub richiedente: Istituzione,
+    pub finalita: String,
+    pub data_inizio: NaiveDate,
+    pub data_fine: NaiveDate,
+}
+
+impl Prestito {
+    /// Scaduto se la data di fine e passata e almeno un reperto non ha
+    /// ancora una restituzione nella catena di provenienza
+    pub fn e_scaduto(&self, inv: &Inventario, oggi: NaiveDate) -> bool {
+        self.data_fine < oggi
+            && self.reperti.iter().any(|id| {
+                inv.cerca_per_id(*id).is_ok_and(|r| {
+                    !r.provenienza.iter().any(|e| {
+                        e.tipo == TipoEventoProvenienza::Restituzione && e.data >= self.data_inizio
+                    })
+                })
+            })
+    }
+}
+
+/// Modello predefinito del contratto di prestito.
+/// I segnaposto `{{nome}}` vengono sostituiti da `compila_contratto`.
+pub const MODELLO_CONTRATTO: &str = "\
+CONTRATTO DI PRESTITO TEMPORANEO
+
+Il prestatore {{prestatore}} ({{prestatore_citta}}), nella persona di {{prestatore_referente}},
+concede in prestito a {{richiedente}} ({{richiedente_citta}}), nella persona di
+{{richiedente_referente}}, i seguenti reperti per la finalita: {{finalita}}.
+
+{{reperti}}
+
+Durata: dal {{data_inizio}} al {{data_fine}} ({{giorni}} giorni).
+Valore complessivo dichiarato ai fini assicurativi: da concordare.
+
+Firma prestatore ______________________   Firma richiedente ______________________
+";
+
+/// Compila il modello di contratto con i dati del prestito e dei reperti.
+/// Segnaposto sconosciuti o reperti inesistenti producono un errore.
+pub fn compila_contratto(
+    modello: &str,
+    prestito: &Prestito,
+    inv: &Inventario,
+) -> Result<String, ErroreInventario> {
+    if prestito.data_fine <= prestito.data_inizio {
+        return Err(ErroreInventario::DatiNonValidi(
+            "la data di fine prestito deve seguire quella di inizio".to_string(),
+        ));
+    }
+    if prestito.reperti.is_empty() {
+        return Err(ErroreInventario::DatiNonValidi(
+            "il prestito non contiene reperti".to_string(),
+        ));
+    }
+
+    let mut elenco = String::new();
+    for (i, id) in prestito.reperti.iter().enumerate() {
+        let r = inv.cerca_per_id(*id)?;
+        elenco.push_str(&format!(
+            "  {}. Inv. #{} - {} ({}, {}), {}, stato: {}\n",
+            i + 1,
+            r.id,
+            r.nome,
+            r.componenti.iter().map(|c| c.materiale.to_string()).collect::<Vec<_>>().join(" e "),
+            r.periodo,
+            r.misurazioni,
+            r.conservazione
+        ));
+    }
+
+    let formato_data = "%d/%m/%Y";
+    let giorni = (prestito.data_fine - prestito.data_inizio).num_days();
+    let valori = [
+        ("prestatore", prestito.prestatore.nome.clone()),
+        ("prestatore_citta", prestito.prestatore.citta.clone()),
+        ("prestatore_referente", prestito.prestatore.referente.clone()),
+        ("richiedente", prestito.richiedente.nome.clone()),
+        ("richiedente_citta", prestito.richiedente.citta.clone()),
+        ("richiedente_referente", prestito.richiedente.referente.clone()),
+        ("finalita", prestito.finalita.clone()),
+        ("reperti", elenco.trim_end().to_string()),
+        ("data_inizio", prestito.data_inizio.format(formato_data).to_string()),
+        ("data_fine", prestito.data_fine.format(formato_data).to_string()),
+        ("giorni", giorni.to_string()),
+    ];
+
+    let mut risultato = String::with_capacity(modello.len());
+    let mut resto = modello;
+    while let Some(inizio) = resto.find("{{") {
+        risultato.push_str(&resto[..inizio]);
+        let dopo = &resto[inizio + 2..];
+        let fine = dopo.find("}}").ok_or_else(|| {
+            ErroreInventario::DatiNonValidi("segnaposto '{{' non chiuso nel modello".to_string())
+        })?;
+        let chiave = dopo[..fine].trim();
+        let valore = valori
+            .iter()
+            .find(|(k, _)| *k == chiave)
+            .map(|(_, v)| v)
+            .ok_or_else(|| {
+                ErroreInventario::DatiNonValidi(format!("segnaposto sconosciuto: {}", chiave))
+            })?;
+        risultato.push_str(valore);
+        resto = &dopo[fine + 2..];
+    }
+    risultato.push_str(resto);
+    Ok(risultato)
+}
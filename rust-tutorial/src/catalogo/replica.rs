@@ -0,0 +1,278 @@
+// ============================================================================
+// MODULO: REPLICA IN SOLA LETTURA
+// ============================================================================
+// Un nodo di scrittura pubblica snapshot in una cartella condivisa,
+// ognuno con il nome della sua impronta, e un registro delle modifiche;
+// le copie in sola lettura (per esempio in una mostra) seguono il
+// puntatore `corrente`. Lo specchio tiene l'inventario in un
+// `Arc<RwLock<Inventario>>`, come l'osservatore di `archivio`: le rotte
+// GET di `servizio` leggono lo stesso inventario che `aggiorna` sostituisce.
+// ============================================================================
+
+//! Pubblicazione di snapshot e copie in sola lettura.
+
+use super::archivio::{Archivio, FileJson};
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// Riga di `feed.jsonl`: cosa e cambiato rispetto allo snapshot precedente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoceFeed {
+    pub snapshot: String,
+    pub precedente: Option<String>,
+    pub pubblicato_il: String,
+    pub reperti: usize,
+    /// Reperti nuovi o modificati
+    pub modificati: Vec<u32>,
+    pub rimossi: Vec<u32>,
+}
+
+/// Nodo di scrittura: pubblica in una cartella condivisa
+/// `snapshot/<impronta>.json`, il puntatore `corrente` e `feed.jsonl`
+pub struct Pubblicatore {
+    pub cartella: PathBuf,
+}
+
+fn file_snapshot(cartella: &std::path::Path, impronta: &str) -> FileJson {
+    FileJson::nuovo(cartella.join("snapshot").join(format!("{}.json", impronta)), None)
+}
+
+fn leggi_corrente(cartella: &std::path::Path) -> Result<Option<String>, ErroreInventario> {
+    match fs::read_to_string(cartella.join("corrente")) {
+        Ok(s) => Ok(Some(s.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// L'impronta dei soli reperti attivi, con cui erano nominati gli
+/// snapshot pubblicati prima che contassero anche radiati e campagne
+fn impronta_precedente(inventario: &Inventario) -> Result<String, serde_json::Error> {
+    let canonico = serde_json::to_vec(&inventario.tutti())?;
+    Ok(Sha256::digest(&canonico).iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Carica uno snapshot e controlla che il contenuto corrisponda al nome
+fn carica_snapshot(cartella: &std::path::Path, impronta: &str) -> Result<Inventario, ErroreInventario> {
+    let inventario = file_snapshot(cartella, impronta).carica()?;
+    if inventario.impronta_snapshot()? != impronta && impronta_precedente(&inventario)? != impronta {
+        return Err(ErroreInventario::DatiNonValidi(format!(
+            "lo snapshot {} non corrisponde alla sua impronta",
+            impronta
+        )));
+    }
+    Ok(inventario)
+}
+
+/// Snapshot corrente di una cartella di pubblicazione, se ce n'e uno
+pub fn corrente(cartella: &std::path::Path) -> Result<Option<(String, Inventario)>, ErroreInventario> {
+    match leggi_corrente(cartella)? {
+        Some(impronta) => {
+            let inventario = carica_snapshot(cartella, &impronta)?;
+            Ok(Some((impronta, inventario)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Voci del feed successive allo snapshot indicato; vuoto se il feed
+/// non contiene piu quello snapshot
+pub fn voci_dal(cartella: &std::path::Path, snapshot: &str) -> Result<Vec<VoceFeed>, ErroreInventario> {
+    let feed = fs::read_to_string(cartella.join("feed.jsonl")).unwrap_or_default();
+    let voci: Vec<VoceFeed> = feed.lines().filter_map(|l| serde_json::from_str(l).ok()).collect();
+    let dal = voci.iter().position(|v| v.precedente.as_deref() == Some(snapshot));
+    Ok(dal.map_or_else(Vec::new, |i| voci[i..].to_vec()))
+}
+
+impl Pubblicatore {
+    /// Pubblica lo stato attuale; se non e cambiato nulla non scrive niente.
+    /// Il puntatore viene sostituito con un rename, quindi chi legge vede
+    /// sempre uno snapshot completo
+    pub fn pubblica(&self, inventario: &Inventario) -> Result<Option<VoceFeed>, ErroreInventario> {
+        fs::create_dir_all(self.cartella.join("snapshot"))?;
+        let impronta = inventario.impronta_snapshot()?;
+        let precedente = leggi_corrente(&self.cartella)?;
+        if precedente.as_deref() == Some(impronta.as_str()) {
+            return Ok(None);
+        }
+
+        let (modificati, rimossi) = match &precedente {
+            Some(p) => {
+                let vecchio = carica_snapshot(&self.cartella, p)?;
+                let modificati = inventario
+                    .tutti()
+                    .into_iter()
+                    .filter(|r| {
+                        vecchio.cerca_per_id(r.id).map_or(true, |v| {
+                            serde_json::to_value(v).ok() != serde_json::to_value(r).ok()
+                        })
+                    })
+                    .map(|r| r.id)
+                    .collect();
+                let rimossi = vecchio
+                    .tutti()
+                    .into_iter()
+                    .filter(|v| inventario.cerca_per_id(v.id).is_err())
+                    .map(|v| v.id)
+                    .collect();
+                (modificati, rimossi)
+            }
+            None => (inventario.tutti().iter().map(|r| r.id).collect(), Vec::new()),
+        };
+
+        file_snapshot(&self.cartella, &impronta).salva(inventario)?;
+        let temporaneo = self.cartella.join("corrente.tmp");
+        fs::write(&temporaneo, format!("{}\n", impronta))?;
+        fs::rename(&temporaneo, self.cartella.join("corrente"))?;
+
+        let voce = VoceFeed {
+            snapshot: impronta,
+            precedente,
+            pubblicato_il: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            reperti: inventario.totale(),
+            modificati,
+            rimossi,
+        };
+        let mut feed = fs::OpenOptions::new().create(true).append(true).open(self.cartella.join("feed.jsonl"))?;
+        writeln!(feed, "{}", serde_json::to_string(&voce)?)?;
+        Ok(Some(voce))
+    }
+}
+
+/// Nodo in sola lettura (es. postazioni di una mostra): segue lo snapshot
+/// corrente e non offre alcun modo di modificare l'inventario
+pub struct Specchio {
+    cartella: PathBuf,
+    snapshot: RwLock<String>,
+    inventario: Arc<RwLock<Inventario>>,
+}
+
+impl Specchio {
+    pub fn apri(cartella: PathBuf) -> Result<Specchio, ErroreInventario> {
+        let impronta = leggi_corrente(&cartella)?.ok_or_else(|| {
+            ErroreInventario::DatiNonValidi(format!("nessuno snapshot pubblicato in {}", cartella.display()))
+        })?;
+        let inventario = carica_snapshot(&cartella, &impronta)?;
+        Ok(Specchio { cartella, snapshot: RwLock::new(impronta), inventario: Arc::new(RwLock::new(inventario)) })
+    }
+
+    /// Accesso in lettura allo snapshot in uso
+    pub fn leggi<T>(&self, f: impl FnOnce(&Inventario) -> T) -> T {
+        f(&self.inventario.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// L'inventario in uso, da condividere con il server: segue `aggiorna`
+    pub fn inventario(&self) -> Arc<RwLock<Inventario>> {
+        Arc::clone(&self.inventario)
+    }
+
+    pub fn snapshot(&self) -> String {
+        self.snapshot.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Passa allo snapshot corrente se e cambiato; in caso di errore resta
+    /// in servizio quello precedente. Restituisce le voci del feed applicate
+    pub fn aggiorna(&self) -> Result<Vec<VoceFeed>, ErroreInventario> {
+        let Some(nuova) = leggi_corrente(&self.cartella)? else {
+            return Ok(Vec::new());
+        };
+        let attuale = self.snapshot();
+        if nuova == attuale {
+            return Ok(Vec::new());
+        }
+        let inventario = carica_snapshot(&self.cartella, &nuova)?;
+        let applicate = voci_dal(&self.cartella, &attuale)?;
+        // Prima l'inventario, poi il nome: chi legge il nome trova gia i dati
+        *self.inventario.write().unwrap_or_else(|e| e.into_inner()) = inventario;
+        *self.snapshot.write().unwrap_or_else(|e| e.into_inner()) = nuova;
+        Ok(applicate)
+    }
+}
+
+/// Lo specchio come archivio, per /readyz: si legge ma non si scrive
+impl Archivio for Specchio {
+    fn salva(&self, _inventario: &Inventario) -> Result<(), ErroreInventario> {
+        Err(ErroreInventario::PermessoNegato(format!("{} e una copia in sola lettura", self.cartella.display())))
+    }
+
+    fn carica(&self) -> Result<Inventario, ErroreInventario> {
+        carica_snapshot(&self.cartella, &self.snapshot())
+    }
+
+    /// Lo snapshot in uso c'e ancora ed e integro
+    fn verifica(&self) -> Result<(), ErroreInventario> {
+        file_snapshot(&self.cartella, &self.snapshot()).verifica()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{CampagnaScavo, Conservazione, Materiale, Periodo, Reperto};
+
+    #[test]
+    fn lo_specchio_aggiorna_l_inventario_condiviso() {
+        let cartella = std::env::temp_dir().join(format!("bronzeaxe_replica_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cartella);
+        let pubblicatore = Pubblicatore { cartella: cartella.clone() };
+        let mut inv = Inventario::nuovo();
+        for nome in ["Ascia", "Spillone"] {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+                .unwrap();
+        }
+        pubblicatore.pubblica(&inv).unwrap();
+        let specchio = Specchio::apri(cartella.clone()).unwrap();
+        let condiviso = specchio.inventario();
+        assert!(specchio.verifica().is_ok());
+        assert!(matches!(specchio.salva(&inv), Err(ErroreInventario::PermessoNegato(_))));
+
+        inv.rimuovi(1).unwrap();
+        let voce = pubblicatore.pubblica(&inv).unwrap().unwrap();
+        assert_eq!(specchio.aggiorna().unwrap()[0].rimossi, [1]);
+        // Chi ha preso l'inventario prima dell'aggiornamento vede i dati nuovi
+        assert_eq!(condiviso.read().unwrap().totale(), 1);
+        assert_eq!(specchio.snapshot(), voce.snapshot);
+        fs::remove_dir_all(cartella).unwrap();
+    }
+
+    #[test]
+    fn si_ripubblica_se_cambia_solo_una_campagna() {
+        let cartella = std::env::temp_dir().join(format!("bronzeaxe_replica_campagne_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cartella);
+        let pubblicatore = Pubblicatore { cartella: cartella.clone() };
+        let mut inv = Inventario::nuovo();
+        inv.aggiungi(Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina"))
+            .unwrap();
+        let mut campagna = CampagnaScavo {
+            codice: "FRT-2024".to_string(),
+            sito: "Frattesina".to_string(),
+            anno: 2024,
+            concessione: None,
+            autorizzazione_ministeriale: None,
+            decreto_deposito: None,
+        };
+        inv.registra_campagna_scavo(campagna.clone()).unwrap();
+        inv.assegna_campagna_scavo(1, "FRT-2024").unwrap();
+
+        // Uno snapshot pubblicato con l'impronta dei soli reperti si legge ancora
+        let precedente = impronta_precedente(&inv).unwrap();
+        fs::create_dir_all(cartella.join("snapshot")).unwrap();
+        file_snapshot(&cartella, &precedente).salva(&inv).unwrap();
+        fs::write(cartella.join("corrente"), format!("{}\n", precedente)).unwrap();
+        assert!(corrente(&cartella).unwrap().is_some());
+
+        assert!(pubblicatore.pubblica(&inv).unwrap().is_some());
+        assert!(pubblicatore.pubblica(&inv).unwrap().is_none());
+        campagna.decreto_deposito = Some("Decreto 54/2025".to_string());
+        inv.registra_campagna_scavo(campagna).unwrap();
+        let voce = pubblicatore.pubblica(&inv).unwrap().unwrap();
+        assert!(voce.modificati.is_empty());
+        fs::remove_dir_all(cartella).unwrap();
+    }
+}
@@ -0,0 +1,44 @@
+// ============================================================================
+// MODULO: RESTAURO
+// ============================================================================
+// Se un consolidante o un adesivo si rivela dannoso bisogna ritrovare i
+// reperti trattati: per questo ogni intervento registra prodotti e lotti.
+// ============================================================================
+
+//! Interventi di restauro per prodotto e lotto.
+
+use super::inventario::Inventario;
+use super::modelli::*;
+use std::collections::BTreeMap;
+
+/// Interventi in cui e stato usato un prodotto, eventualmente di un lotto
+/// specifico: serve a risalire ai reperti trattati se il prodotto si
+/// rivela problematico
+pub fn interventi_con_prodotto<'a>(
+    inv: &'a Inventario,
+    prodotto: &str,
+    lotto: Option<&str>,
+) -> Vec<(&'a Reperto, &'a Intervento)> {
+    let prodotto = prodotto.to_lowercase();
+    inv.tutti()
+        .into_iter()
+        .flat_map(|r| r.interventi.iter().map(move |i| (r, i)))
+        .filter(|(_, i)| {
+            i.prodotti.iter().any(|p| {
+                p.prodotto.to_lowercase().contains(&prodotto)
+                    && lotto.is_none_or(|l| p.lotto == l)
+            })
+        })
+        .collect()
+}
+
+/// Numero di interventi per prodotto e lotto
+pub fn consumi(inv: &Inventario) -> BTreeMap<(String, String), usize> {
+    let mut conteggi = BTreeMap::new();
+    for r in inv.tutti() {
+        for p in r.interventi.iter().flat_map(|i| &i.prodotti) {
+            *conteggi.entry((p.prodotto.clone(), p.lotto.clone())).or_insert(0) += 1;
+        }
+    }
+    conteggi
+}
@@ -0,0 +1,199 @@
+// ============================================================================
+// MODULO: RICERCA PER CONDIZIONI
+// ============================================================================
+// Condizioni numeriche scritte come testo, su campi base o calcolati:
+//
+//   peso_grammi > 300
+//   peso > 0.3kg          (nome breve e unita, vedi `unita`)
+//   lunghezza < 25cm
+//   densita >= 8.5
+//   completo_stimato_percento != 100
+//
+// Il valore viene convertito nell'unita del campo quando si legge la
+// condizione: `peso > 0.3kg` diventa `peso_grammi > 300`, e `peso > 25cm`
+// non arriva nemmeno alla ricerca.
+//
+// Un reperto a cui manca il valore (misura non presa, divisione per zero)
+// non soddisfa nessuna condizione, nemmeno `!=`: non si sa quanto vale.
+// ============================================================================
+
+//! Filtri come `densita > 8` sui campi base e calcolati dei reperti.
+
+use std::fmt;
+
+use super::calcolati::{CampiCalcolati, ErroreCampi};
+use super::modelli::Reperto;
+use super::unita::{self, Dimensione, Quantita};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Confronto {
+    Minore,
+    MinoreUguale,
+    Maggiore,
+    MaggioreUguale,
+    Uguale,
+    Diverso,
+}
+
+impl Confronto {
+    /// I simboli, i composti prima: `<=` va provato prima di `<`
+    const SIMBOLI: [(&'static str, Confronto); 6] = [
+        ("<=", Confronto::MinoreUguale),
+        (">=", Confronto::MaggioreUguale),
+        ("!=", Confronto::Diverso),
+        ("<", Confronto::Minore),
+        (">", Confronto::Maggiore),
+        ("=", Confronto::Uguale),
+    ];
+
+    pub fn vale(self, a: f64, b: f64) -> bool {
+        match self {
+            Confronto::Minore => a < b,
+            Confronto::MinoreUguale => a <= b,
+            Confronto::Maggiore => a > b,
+            Confronto::MaggioreUguale => a >= b,
+            Confronto::Uguale => quasi_uguali(a, b),
+            Confronto::Diverso => !quasi_uguali(a, b),
+        }
+    }
+
+    fn simbolo(self) -> &'static str {
+        Confronto::SIMBOLI.iter().find(|(_, c)| *c == self).map_or("?", |(s, _)| s)
+    }
+}
+
+/// Dopo una conversione 0.07m diventano 7.000000000000001 cm:
+/// l'uguaglianza tollera l'errore di arrotondamento
+fn quasi_uguali(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+/// `campo confronto quantita`, per esempio `densita > 8` o `peso > 0.3kg`.
+/// Il campo e sempre il nome completo e il valore e nella sua unita.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condizione {
+    pub campo: String,
+    pub confronto: Confronto,
+    pub valore: f64,
+}
+
+impl Condizione {
+    /// ```
+    /// use rust_tutorial::catalogo::ricerca::{Condizione, Confronto};
+    ///
+    /// let c = Condizione::da_testo("peso >= 0.3kg").unwrap();
+    /// assert_eq!(c.confronto, Confronto::MaggioreUguale);
+    /// assert_eq!(c.to_string(), "peso_grammi >= 300");
+    /// assert!(Condizione::da_testo("peso_grammi circa 300").is_err());
+    ///
+    /// let errore = Condizione::da_testo("peso > 25cm").unwrap_err();
+    /// assert_eq!(errore.to_string(), "'peso_grammi' e una massa, '25cm' e una lunghezza");
+    /// ```
+    pub fn da_testo(testo: &str) -> Result<Condizione, ErroreCampi> {
+        let non_valida = || ErroreCampi::Condizione(testo.to_string());
+        let (posizione, simbolo, confronto) = Confronto::SIMBOLI
+            .iter()
+            .filter_map(|(s, c)| testo.find(s).map(|p| (p, *s, *c)))
+            // Il primo simbolo nel testo; a parita di posizione il piu lungo
+            .min_by_key(|(p, s, _)| (*p, usize::MAX - s.len()))
+            .ok_or_else(non_valida)?;
+        let campo = testo[..posizione].trim();
+        if campo.is_empty() || !campo.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(non_valida());
+        }
+        let quantita = Quantita::da_testo(&testo[posizione + simbolo.len()..])?;
+        // I campi calcolati sono numeri puri: un'unita li rende un errore
+        let (campo, dimensione) = unita::campo(campo).unwrap_or((campo, Dimensione::Numero));
+        Ok(Condizione {
+            campo: campo.to_string(),
+            confronto,
+            valore: quantita.nell_unita_di(campo, dimensione)?,
+        })
+    }
+
+    /// Vero se il reperto ha il valore e la condizione e soddisfatta
+    pub fn vale(&self, campi: &CampiCalcolati, reperto: &Reperto) -> Result<bool, ErroreCampi> {
+        let valore = campi.per(reperto).valore(&self.campo)?;
+        Ok(valore.is_some_and(|v| self.confronto.vale(v, self.valore)))
+    }
+}
+
+impl fmt::Display for Condizione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.campo, self.confronto.simbolo(), self.valore)
+    }
+}
+
+/// I reperti che soddisfano tutte le condizioni. Un campo sconosciuto e
+/// un errore anche se non ci sono reperti da filtrare.
+pub fn filtra<'a>(
+    reperti: impl IntoIterator<Item = &'a Reperto>,
+    campi: &CampiCalcolati,
+    condizioni: &[Condizione],
+) -> Result<Vec<&'a Reperto>, ErroreCampi> {
+    for condizione in condizioni {
+        campi.richiedi(&condizione.campo)?;
+    }
+    let mut trovati = Vec::new();
+    for reperto in reperti {
+        let mut tutte = true;
+        for condizione in condizioni {
+            if !condizione.vale(campi, reperto)? {
+                tutte = false;
+                break;
+            }
+        }
+        if tutte {
+            trovati.push(reperto);
+        }
+    }
+    Ok(trovati)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Misurazioni, Periodo};
+
+    #[test]
+    fn filtra_su_campi_calcolati() {
+        let campi = CampiCalcolati::da_testo("densita = peso_grammi / volume").unwrap();
+        let nuovo = |nome: &str, misure| {
+            Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina")
+                .con_misurazioni(misure)
+        };
+        let reperti = [
+            nuovo("Ascia", Misurazioni::nuove().con_dimensioni(10.0, 4.0, 1.0).con_peso(320.0)),
+            nuovo("Lingotto cavo", Misurazioni::nuove().con_dimensioni(10.0, 4.0, 1.0).con_peso(120.0)),
+            nuovo("Frammento", Misurazioni::nuove().con_peso(40.0)),
+        ];
+
+        let densi = filtra(&reperti, &campi, &[Condizione::da_testo("densita > 5").unwrap()]).unwrap();
+        assert_eq!(densi.iter().map(|r| r.nome.as_str()).collect::<Vec<_>>(), ["Ascia"]);
+        // Il frammento non ha volume: escluso anche da !=
+        let diversi = filtra(&reperti, &campi, &[Condizione::da_testo("densita != 8").unwrap()]).unwrap();
+        assert_eq!(diversi.len(), 1);
+
+        let sconosciuto = Condizione::da_testo("durezza > 3").unwrap();
+        assert!(filtra(&reperti[..0], &campi, &[sconosciuto]).is_err());
+        assert_eq!(Condizione::da_testo("densita<=8").unwrap().to_string(), "densita <= 8");
+    }
+
+    #[test]
+    fn condizioni_con_unita() {
+        let campi = CampiCalcolati::da_testo("densita = peso_grammi / volume").unwrap();
+        let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina")
+            .con_misurazioni(Misurazioni::nuove().con_dimensioni(7.0, 4.0, 1.0).con_peso(320.0));
+        let vale = |testo| Condizione::da_testo(testo).unwrap().vale(&campi, &ascia).unwrap();
+        assert!(vale("peso > 0.3kg"));
+        assert!(!vale("peso_grammi > 320000 mg"));
+        assert!(vale("lunghezza < 25cm"));
+        assert!(vale("lunghezza = 0.07m"));
+        assert!(vale("volume <= 0.028 l"));
+
+        let errore = |testo| Condizione::da_testo(testo).unwrap_err().to_string();
+        assert_eq!(errore("lunghezza < 2kg"), "'lunghezza_cm' e una lunghezza, '2kg' e una massa");
+        assert_eq!(errore("densita > 8g"), "'densita' e un numero puro, '8g' e una massa");
+        assert!(errore("peso > 3 libbre").starts_with("unita 'libbre' sconosciuta"));
+    }
+}
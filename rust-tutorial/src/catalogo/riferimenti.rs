@@ -0,0 +1,179 @@
+// ============================================================================
+// MODULO: RIFERIMENTI BIBLIOGRAFICI
+// ============================================================================
+// I DOI delle voci di bibliografia si completano da Crossref, gli iD
+// delle persone da ORCID; come per l'AAT le risposte restano in cache.
+// ============================================================================
+
+//! Bibliografia da Crossref e iD ORCID, con cache locale.
+
+use super::errori::ErroreInventario;
+use super::modelli::{normalizza_doi, orcid_valido, Opera, Persona};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+pub const CROSSREF: &str = "https://api.crossref.org";
+pub const ORCID: &str = "https://pub.orcid.org/v3.0";
+
+/// Anche le ricerche senza risultato restano in cache (`None`)
+#[derive(Default, Serialize, Deserialize)]
+struct Cache {
+    #[serde(default)]
+    opere: BTreeMap<String, Option<Opera>>,
+    #[serde(default)]
+    orcid: BTreeMap<String, Option<String>>,
+}
+
+/// Ricerca dei DOI su Crossref e degli iD su ORCID, con cache locale su
+/// file. Senza indirizzi lavora solo sulla cache, come `ClienteAat`
+pub struct ClienteRiferimenti {
+    pub cache: PathBuf,
+    pub crossref: Option<String>,
+    pub orcid: Option<String>,
+    voci: Mutex<Cache>,
+}
+
+impl ClienteRiferimenti {
+    pub fn nuovo(cache: PathBuf, crossref: Option<String>, orcid: Option<String>) -> Result<Self, ErroreInventario> {
+        let voci = match fs::read(&cache) {
+            Ok(dati) => serde_json::from_slice(&dati)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Cache::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(ClienteRiferimenti { cache, crossref, orcid, voci: Mutex::new(voci) })
+    }
+
+    fn voci(&self) -> Result<MutexGuard<'_, Cache>, ErroreInventario> {
+        self.voci.lock().map_err(|_| ErroreInventario::DatiNonValidi("cache dei riferimenti inutilizzabile".to_string()))
+    }
+
+    fn salva(&self, voci: &Cache) -> Result<(), ErroreInventario> {
+        if let Some(cartella) = self.cache.parent() {
+            fs::create_dir_all(cartella)?;
+        }
+        fs::write(&self.cache, serde_json::to_vec_pretty(voci)?)?;
+        Ok(())
+    }
+
+    /// Registra a mano i metadati di un DOI (es. verificati da un curatore)
+    pub fn memorizza_opera(&self, doi: &str, opera: Option<Opera>) -> Result<(), ErroreInventario> {
+        let mut voci = self.voci()?;
+        voci.opere.insert(normalizza_doi(doi), opera);
+        self.salva(&voci)
+    }
+
+    /// Registra a mano l'iD ORCID di un nome
+    pub fn memorizza_orcid(&self, nome: &str, orcid: Option<String>) -> Result<(), ErroreInventario> {
+        let mut voci = self.voci()?;
+        voci.orcid.insert(nome.trim().to_lowercase(), orcid);
+        self.salva(&voci)
+    }
+
+    /// Metadati bibliografici di un DOI: prima la cache, poi Crossref
+    pub fn risolvi_doi(&self, doi: &str) -> Result<Option<Opera>, ErroreInventario> {
+        let doi = normalizza_doi(doi);
+        if let Some(voce) = self.voci()?.opere.get(&doi).cloned() {
+            return Ok(voce);
+        }
+        let Some(base) = &self.crossref else {
+            return Ok(None);
+        };
+        let opera = interroga_crossref(base, &doi)?;
+        self.memorizza_opera(&doi, opera.clone())?;
+        Ok(opera)
+    }
+
+    /// iD ORCID di una persona: prima la cache, poi la ricerca pubblica
+    /// di ORCID. Un nome con piu risultati resta senza iD
+    pub fn cerca_orcid(&self, persona: &Persona) -> Result<Option<String>, ErroreInventario> {
+        let chiave = persona.nome.trim().to_lowercase();
+        if let Some(voce) = self.voci()?.orcid.get(&chiave).cloned() {
+            return Ok(voce);
+        }
+        let Some(base) = &self.orcid else {
+            return Ok(None);
+        };
+        let Some((nome, cognome)) = persona.nome.trim().rsplit_once(' ') else {
+            return Ok(None);
+        };
+        let risposta = interroga_orcid(base, &format!("family-name:({}) AND given-names:({})", cognome, nome))?;
+        let trovati = risposta["expanded-result"].as_array().map_or(&[][..], Vec::as_slice);
+        let orcid = match trovati {
+            [unico] => unico["orcid-id"].as_str().filter(|id| orcid_valido(id)).map(String::from),
+            _ => None,
+        };
+        self.memorizza_orcid(&persona.nome, orcid.clone())?;
+        Ok(orcid)
+    }
+}
+
+/// Metadati di un DOI da Crossref; `None` se Crossref non lo conosce
+#[cfg(feature = "net")]
+fn interroga_crossref(base: &str, doi: &str) -> Result<Option<Opera>, ErroreInventario> {
+    let risposta = ureq::get(format!("{}/works/{}", base, doi))
+        .header("User-Agent", "BronzeAxe (https://w3id.org/bronzeaxe)")
+        .call();
+    match risposta {
+        Ok(mut r) => Ok(opera_da_crossref(doi, &r.body_mut().read_json()?)),
+        // DOI sconosciuto a Crossref (es. registrato presso DataCite)
+        Err(ureq::Error::StatusCode(404)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Risultati della ricerca pubblica di ORCID
+#[cfg(feature = "net")]
+fn interroga_orcid(base: &str, query: &str) -> Result<Value, ErroreInventario> {
+    Ok(ureq::get(format!("{}/expanded-search/", base))
+        .query("q", query)
+        .header("Accept", "application/json")
+        .call()?
+        .body_mut()
+        .read_json()?)
+}
+
+#[cfg(not(feature = "net"))]
+fn interroga_crossref(_base: &str, _doi: &str) -> Result<Option<Opera>, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "net", operazione: "interrogare Crossref" })
+}
+
+#[cfg(not(feature = "net"))]
+fn interroga_orcid(_base: &str, _query: &str) -> Result<Value, ErroreInventario> {
+    Err(ErroreInventario::FeatureMancante { feature: "net", operazione: "interrogare ORCID" })
+}
+
+/// Campi utili dalla risposta di Crossref (`/works/<doi>`)
+#[cfg(feature = "net")]
+fn opera_da_crossref(doi: &str, risposta: &Value) -> Option<Opera> {
+    let m = &risposta["message"];
+    let primo = |campo: &str| m[campo].as_array().and_then(|v| v.first()).and_then(Value::as_str).map(String::from);
+    let testo = |campo: &str| m[campo].as_str().map(String::from);
+    let autori = m["author"]
+        .as_array()
+        .map(|autori| {
+            autori
+                .iter()
+                .filter_map(|a| match (a["family"].as_str(), a["given"].as_str()) {
+                    (Some(cognome), Some(nome)) => Some(format!("{}, {}", cognome, nome)),
+                    (Some(cognome), None) => Some(cognome.to_string()),
+                    _ => a["name"].as_str().map(String::from),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let anno = m["issued"]["date-parts"][0][0].as_i64().map(|a| a as i32);
+    Some(Opera {
+        doi: doi.to_string(),
+        titolo: primo("title")?,
+        autori,
+        anno,
+        contenitore: primo("container-title"),
+        volume: testo("volume"),
+        pagine: testo("page"),
+        editore: testo("publisher"),
+    })
+}
@@ -0,0 +1,168 @@
+// ============================================================================
+// MODULO: ENDPOINT DI SALUTE
+// ============================================================================
+// Gli orchestratori di container chiedono /healthz (il processo
+// risponde) e /readyz (l'archivio e utilizzabile) per decidere se
+// riavviare il servizio o mandargli traffico.
+//
+// Le due rotte sono un router axum, come nel capitolo 15.5: parsing
+// HTTP, keep-alive e concorrenza li gestisce hyper, e un client lento
+// non ferma gli altri. /readyz chiama `Archivio::verifica` fuori dal
+// thread delle richieste; per questo la verifica degli archivi deve
+// restare leggera (per FileJson basta l'impronta in coda al file).
+// `servi` serve queste rotte insieme a quelle del servizio (vedi
+// `servizio`); `ping` invece e un client minimo con la sola libreria
+// standard.
+// ============================================================================
+
+//! Endpoint /healthz e /readyz e comando ping.
+
+use super::errori::ErroreInventario;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+// Il server esiste solo con la feature; ping no
+#[cfg(feature = "server")]
+use super::archivio::Archivio;
+#[cfg(feature = "server")]
+use axum::extract::State;
+#[cfg(feature = "server")]
+use axum::http::StatusCode;
+#[cfg(feature = "server")]
+use axum::routing::get;
+#[cfg(feature = "server")]
+use axum::{Json, Router};
+#[cfg(feature = "server")]
+use serde_json::{json, Value};
+#[cfg(feature = "server")]
+use std::net::TcpListener;
+#[cfg(feature = "server")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Le rotte /healthz e /readyz sull'archivio, da unire con `merge` a
+/// quelle del servizio
+#[cfg(feature = "server")]
+pub fn rotte(archivio: Arc<dyn Archivio>) -> Router {
+    Router::new().route("/healthz", get(healthz)).route("/readyz", get(readyz)).with_state(archivio)
+}
+
+/// Il processo risponde: basta questo per non essere riavviato
+#[cfg(feature = "server")]
+async fn healthz() -> Json<Value> {
+    Json(json!({ "stato": "vivo" }))
+}
+
+/// Pronto a ricevere traffico solo se l'archivio e utilizzabile
+#[cfg(feature = "server")]
+async fn readyz(State(archivio): State<Arc<dyn Archivio>>) -> (StatusCode, Json<Value>) {
+    // La verifica legge file o database: non deve occupare il runtime
+    let esito = tokio::task::spawn_blocking(move || archivio.verifica().map_err(|e| e.to_string()))
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    match esito {
+        Ok(()) => (StatusCode::OK, Json(json!({ "stato": "pronto", "archivio": "ok" }))),
+        Err(e) => (StatusCode::SERVICE_UNAVAILABLE, Json(json!({ "stato": "non pronto", "archivio": e }))),
+    }
+}
+
+#[cfg(feature = "server")]
+async fn sconosciuto() -> (StatusCode, Json<Value>) {
+    (StatusCode::NOT_FOUND, Json(json!({ "errore": "percorso sconosciuto" })))
+}
+
+/// Serve le rotte di `app` finche `arresto` resta falso, poi completa le
+/// richieste in corso. Chiudere l'archivio tocca al chiamante
+#[cfg(feature = "server")]
+pub fn servi(ascolto: &TcpListener, app: Router, arresto: Arc<AtomicBool>) -> Result<(), ErroreInventario> {
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let ascolto = ascolto.try_clone()?;
+    ascolto.set_nonblocking(true)?;
+    runtime.block_on(async {
+        let ascolto = tokio::net::TcpListener::from_std(ascolto)?;
+        // Il flag lo alza il gestore dei segnali: lo si controlla a intervalli
+        let fermati = async move {
+            while !arresto.load(Ordering::Relaxed) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        axum::serve(ascolto, app.fallback(sconosciuto)).with_graceful_shutdown(fermati).await
+    })?;
+    Ok(())
+}
+
+/// Esito di `ping`
+#[derive(Debug)]
+pub struct Risposta {
+    pub codice: u16,
+    pub corpo: String,
+    pub tempo: Duration,
+}
+
+/// GET su `http://host:porta[/percorso]` (predefinito /healthz)
+pub fn ping(url: &str) -> Result<Risposta, ErroreInventario> {
+    let resto = url.strip_prefix("http://").ok_or_else(|| {
+        ErroreInventario::DatiNonValidi(format!("indirizzo non supportato: {} (serve http://)", url))
+    })?;
+    let (host, percorso) = match resto.find('/') {
+        Some(i) => (&resto[..i], &resto[i..]),
+        None => (resto, "/healthz"),
+    };
+    let indirizzo = host
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("host non risolto: {}", host)))?;
+
+    let inizio = Instant::now();
+    let mut flusso = TcpStream::connect_timeout(&indirizzo, Duration::from_secs(3))?;
+    flusso.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(flusso, "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", percorso, host)?;
+    let mut risposta = String::new();
+    flusso.read_to_string(&mut risposta)?;
+    let codice = risposta
+        .split_whitespace()
+        .nth(1)
+        .and_then(|c| c.parse().ok())
+        .ok_or_else(|| ErroreInventario::DatiNonValidi("risposta HTTP non valida".to_string()))?;
+    let corpo = risposta.split_once("\r\n\r\n").map_or("", |(_, c)| c).to_string();
+    Ok(Risposta { codice, corpo, tempo: inizio.elapsed() })
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use crate::catalogo::archivio::FileJson;
+    use crate::catalogo::inventario::Inventario;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn chiedi(app: &Router, uri: &str) -> (u16, String) {
+        let risposta = app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        let codice = risposta.status().as_u16();
+        let corpo = axum::body::to_bytes(risposta.into_body(), 64 * 1024).await.unwrap();
+        (codice, String::from_utf8_lossy(&corpo).into_owned())
+    }
+
+    #[tokio::test]
+    async fn pronto_solo_con_l_archivio_integro() {
+        let percorso = std::env::temp_dir().join(format!("bronzeaxe_salute_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&percorso);
+        let archivio = FileJson::nuovo(percorso.clone(), None);
+        let app = rotte(Arc::new(FileJson::nuovo(percorso.clone(), None))).fallback(sconosciuto);
+        assert_eq!(chiedi(&app, "/healthz").await, (200, r#"{"stato":"vivo"}"#.to_string()));
+        assert_eq!(chiedi(&app, "/readyz").await.0, 503);
+        assert_eq!(chiedi(&app, "/altro").await.0, 404);
+
+        archivio.salva(&Inventario::nuovo()).unwrap();
+        assert_eq!(chiedi(&app, "/readyz").await.0, 200);
+        // Un byte cambiato invalida l'impronta in coda
+        let mut file = std::fs::read(&percorso).unwrap();
+        file[0] = b' ';
+        std::fs::write(&percorso, file).unwrap();
+        assert_eq!(chiedi(&app, "/readyz").await.0, 503);
+        std::fs::remove_file(percorso).unwrap();
+    }
+}
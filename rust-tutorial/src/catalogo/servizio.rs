@@ -0,0 +1,227 @@
+// ============================================================================
+// MODULO: API REST DELL'INVENTARIO
+// ============================================================================
+// Il comando `serve` tiene l'inventario in memoria e lo espone via HTTP
+// con le rotte del capitolo 15:
+//
+//   GET    /reperti          tutti i reperti
+//   GET    /reperti/{id}     un reperto, 404 se non c'e
+//   POST   /reperti          nuovo reperto, 201 con il suo id
+//   PUT    /reperti/{id}     sostituisce il reperto; la `versione` nel
+//                            corpo e quella letta, 409 se nel frattempo
+//                            e cambiata
+//   DELETE /reperti/{id}     rimuove il reperto
+//
+// Le scritture restano in memoria e segnano l'inventario come da salvare:
+// all'arresto (SIGTERM) `chiudi` le scrive nell'archivio e lo chiude. I
+// nodi in sola lettura (--watch, --mirror) montano solo le rotte GET;
+// l'inventario lo aggiorna chi lo condivide, l'osservatore o lo specchio.
+// ============================================================================
+
+//! Rotte REST sull'inventario in memoria e salvataggio all'arresto.
+
+// Il modulo intero esiste solo con la feature: e il server dell'inventario
+#![cfg(feature = "server")]
+
+use super::archivio::Archivio;
+use super::errori::ErroreInventario;
+use super::inventario::{Inventario, Modifica};
+use super::modelli::Reperto;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// L'inventario servito via HTTP, condiviso con chi lo aggiorna
+#[derive(Clone)]
+pub struct Servizio {
+    inventario: Arc<RwLock<Inventario>>,
+    /// Modifiche ricevute via API e non ancora salvate
+    in_sospeso: Arc<AtomicBool>,
+}
+
+/// Un `ErroreInventario` come risposta JSON: il wrapper serve per la
+/// orphan rule, come nel capitolo 15
+struct ErroreApi(ErroreInventario);
+
+impl From<ErroreInventario> for ErroreApi {
+    fn from(e: ErroreInventario) -> Self {
+        ErroreApi(e)
+    }
+}
+
+impl IntoResponse for ErroreApi {
+    fn into_response(self) -> Response {
+        let codice = match &self.0 {
+            ErroreInventario::RepertoNonTrovato(_) => StatusCode::NOT_FOUND,
+            ErroreInventario::Conflitto { .. }
+            | ErroreInventario::IdDuplicato(_)
+            | ErroreInventario::RimozioneInAttesa { .. } => StatusCode::CONFLICT,
+            ErroreInventario::NomeVuoto
+            | ErroreInventario::DatiNonValidi(_)
+            | ErroreInventario::TransizioneNonAmmessa { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            ErroreInventario::PermessoNegato(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (codice, Json(serde_json::json!({ "errore": self.0.to_string() }))).into_response()
+    }
+}
+
+impl Servizio {
+    pub fn nuovo(inventario: Arc<RwLock<Inventario>>) -> Self {
+        Servizio { inventario, in_sospeso: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Carica l'archivio; se il file dati non c'e ancora (volume appena
+    /// montato) lo crea vuoto, cosi /readyz risponde subito
+    pub fn apri(archivio: &dyn Archivio) -> Result<Self, ErroreInventario> {
+        let inventario = match archivio.carica() {
+            Err(ErroreInventario::IoErrore(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                let vuoto = Inventario::nuovo();
+                archivio.salva(&vuoto)?;
+                vuoto
+            }
+            esito => esito?,
+        };
+        Ok(Servizio::nuovo(Arc::new(RwLock::new(inventario))))
+    }
+
+    pub fn inventario(&self) -> Arc<RwLock<Inventario>> {
+        Arc::clone(&self.inventario)
+    }
+
+    /// Ci sono modifiche da salvare
+    pub fn in_sospeso(&self) -> bool {
+        self.in_sospeso.load(Ordering::Relaxed)
+    }
+
+    /// Solo le rotte GET: per i nodi in sola lettura
+    pub fn lettura(&self) -> Router {
+        Router::new()
+            .route("/reperti", get(elenca))
+            .route("/reperti/{id}", get(mostra))
+            .with_state(self.clone())
+    }
+
+    /// Le rotte GET piu POST, PUT e DELETE
+    pub fn scrittura(&self) -> Router {
+        Router::new()
+            .route("/reperti", get(elenca).post(aggiungi))
+            .route("/reperti/{id}", get(mostra).put(sostituisci).delete(rimuovi))
+            .with_state(self.clone())
+    }
+
+    /// All'arresto: salva le modifiche in sospeso e chiude l'archivio.
+    /// Restituisce se c'era qualcosa da salvare
+    pub fn chiudi(&self, archivio: &dyn Archivio) -> Result<bool, ErroreInventario> {
+        let da_salvare = self.in_sospeso();
+        if da_salvare {
+            archivio.salva(&*self.leggi()?)?;
+            self.in_sospeso.store(false, Ordering::Relaxed);
+        }
+        archivio.chiudi()?;
+        Ok(da_salvare)
+    }
+
+    fn leggi(&self) -> Result<std::sync::RwLockReadGuard<'_, Inventario>, ErroreInventario> {
+        self.inventario.read().map_err(|_| ErroreInventario::DatiNonValidi("inventario non disponibile".to_string()))
+    }
+
+    /// Esegue una modifica e, se riesce, segna l'inventario come da salvare
+    fn modifica<T>(&self, f: impl FnOnce(&mut Inventario) -> Result<T, ErroreInventario>) -> Result<T, ErroreApi> {
+        let mut inventario = self
+            .inventario
+            .write()
+            .map_err(|_| ErroreInventario::DatiNonValidi("inventario non disponibile".to_string()))?;
+        let esito = f(&mut inventario)?;
+        self.in_sospeso.store(true, Ordering::Relaxed);
+        Ok(esito)
+    }
+}
+
+async fn elenca(State(servizio): State<Servizio>) -> Result<Json<Vec<Reperto>>, ErroreApi> {
+    Ok(Json(servizio.leggi()?.tutti().into_iter().cloned().collect()))
+}
+
+async fn mostra(State(servizio): State<Servizio>, Path(id): Path<u32>) -> Result<Json<Reperto>, ErroreApi> {
+    Ok(Json(servizio.leggi()?.cerca_per_id(id)?.clone()))
+}
+
+async fn aggiungi(
+    State(servizio): State<Servizio>,
+    Json(reperto): Json<Reperto>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ErroreApi> {
+    let id = servizio.modifica(|inv| inv.aggiungi(reperto))?;
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))))
+}
+
+async fn sostituisci(
+    State(servizio): State<Servizio>,
+    Path(id): Path<u32>,
+    Json(bozza): Json<Reperto>,
+) -> Result<Json<serde_json::Value>, ErroreApi> {
+    let versione = servizio.modifica(|inv| {
+        let originale = inv.cerca_per_id(id)?.clone();
+        if bozza.versione != originale.versione {
+            return Err(ErroreInventario::Conflitto { id, letta: bozza.versione, attuale: originale.versione });
+        }
+        inv.conferma_modifica(Modifica { originale, bozza })
+    })?;
+    Ok(Json(serde_json::json!({ "id": id, "versione": versione })))
+}
+
+async fn rimuovi(State(servizio): State<Servizio>, Path(id): Path<u32>) -> Result<StatusCode, ErroreApi> {
+    servizio.modifica(|inv| inv.rimuovi(id))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::archivio::FileJson;
+    use crate::catalogo::modelli::{Conservazione, Materiale, Periodo};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn chiedi(app: &Router, metodo: &str, uri: &str, corpo: Option<String>) -> (u16, serde_json::Value) {
+        let richiesta = Request::builder().method(metodo).uri(uri).header("content-type", "application/json");
+        let risposta = app.clone().oneshot(richiesta.body(corpo.map_or(Body::empty(), Body::from)).unwrap()).await.unwrap();
+        let codice = risposta.status().as_u16();
+        let corpo = axum::body::to_bytes(risposta.into_body(), 1 << 20).await.unwrap();
+        (codice, serde_json::from_slice(&corpo).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn scritture_in_memoria_salvate_alla_chiusura() {
+        let percorso = std::env::temp_dir().join(format!("bronzeaxe_servizio_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&percorso);
+        let archivio = FileJson::nuovo(percorso.clone(), None);
+        let servizio = Servizio::apri(&archivio).unwrap();
+        let app = servizio.scrittura();
+
+        let ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        let (codice, corpo) = chiedi(&app, "POST", "/reperti", Some(serde_json::to_string(&ascia).unwrap())).await;
+        assert_eq!((codice, corpo["id"].as_u64()), (201, Some(1)));
+        let (_, mut letto) = chiedi(&app, "GET", "/reperti/1", None).await;
+        assert_eq!(letto["nome"], "Ascia");
+
+        letto["nome"] = "Ascia piatta".into();
+        assert_eq!(chiedi(&app, "PUT", "/reperti/1", Some(letto.to_string())).await.0, 200);
+        // La stessa versione una seconda volta e ormai vecchia
+        assert_eq!(chiedi(&app, "PUT", "/reperti/1", Some(letto.to_string())).await.0, 409);
+        assert_eq!(chiedi(&app, "GET", "/reperti/7", None).await.0, 404);
+        // I nodi in sola lettura non hanno le rotte di scrittura
+        assert_eq!(chiedi(&servizio.lettura(), "DELETE", "/reperti/1", None).await.0, 405);
+
+        // Su disco c'e ancora l'inventario vuoto creato all'apertura
+        assert_eq!(archivio.carica().unwrap().totale(), 0);
+        assert!(servizio.chiudi(&archivio).unwrap());
+        assert_eq!(archivio.carica().unwrap().cerca_per_id(1).unwrap().nome, "Ascia piatta");
+        assert!(!servizio.chiudi(&archivio).unwrap());
+        std::fs::remove_file(percorso).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+// ============================================================================
+// MODULO: SPARQL UPDATE
+// ============================================================================
+// Il grafo pubblicato si invia a un triple store un po' per volta: si
+// mandano solo i reperti cambiati dall'ultimo invio, ricordato in un
+// file di stato.
+// ============================================================================
+
+//! Invio incrementale del grafo CIDOC-CRM a un endpoint SPARQL.
+
+use super::cidoc;
+use super::citazioni::{uri_reperto, URI_REPERTI};
+use super::errori::ErroreInventario;
+use super::replica;
+use super::tesauro::Tesauro;
+#[cfg(feature = "net")]
+use base64::Engine;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reperti per singola richiesta di aggiornamento
+const BLOCCO: usize = 100;
+
+/// Dove inviare le richieste SPARQL Update
+pub trait Destinazione {
+    fn aggiorna(&self, richiesta: &str) -> Result<(), ErroreInventario>;
+}
+
+/// Endpoint SPARQL 1.1 Update (es. Fuseki `/dataset/update`, GraphDB
+/// `/repositories/<id>/statements`)
+#[cfg(feature = "net")]
+#[derive(Debug, Clone)]
+pub struct ClienteSparql {
+    pub endpoint: String,
+    pub utente: Option<String>,
+    pub password: Option<String>,
+}
+
+#[cfg(feature = "net")]
+impl ClienteSparql {
+    /// Da `BRONZEAXE_SPARQL`, con `BRONZEAXE_SPARQL_UTENTE` e
+    /// `BRONZEAXE_SPARQL_PASSWORD` per l'autenticazione Basic
+    pub fn da_variabili(variabile: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        Some(ClienteSparql {
+            endpoint: variabile("BRONZEAXE_SPARQL")?,
+            utente: variabile("BRONZEAXE_SPARQL_UTENTE"),
+            password: variabile("BRONZEAXE_SPARQL_PASSWORD"),
+        })
+    }
+}
+
+#[cfg(feature = "net")]
+impl Destinazione for ClienteSparql {
+    fn aggiorna(&self, richiesta: &str) -> Result<(), ErroreInventario> {
+        let mut post = ureq::post(&self.endpoint).header("Content-Type", "application/sparql-update");
+        if let Some(utente) = &self.utente {
+            let credenziali = format!("{}:{}", utente, self.password.as_deref().unwrap_or_default());
+            let codificate = base64::engine::general_purpose::STANDARD.encode(credenziali);
+            post = post.header("Authorization", format!("Basic {}", codificate));
+        }
+        post.send(richiesta)?;
+        Ok(())
+    }
+}
+
+/// Grafo con nome che contiene le triple di un reperto
+pub fn grafo(id: u32) -> String {
+    format!("{}/grafo", uri_reperto(id))
+}
+
+/// Esito di una sincronizzazione
+#[derive(Debug)]
+pub struct Sincronizzazione {
+    pub snapshot: String,
+    pub completa: bool,
+    pub aggiornati: usize,
+    pub rimossi: usize,
+    pub richieste: usize,
+}
+
+/// Porta l'endpoint allo snapshot corrente della cartella di
+/// pubblicazione. `stato` ricorda l'ultimo snapshot inviato: da li si
+/// seguono le voci del feed e si rimandano solo i reperti cambiati;
+/// senza stato (o se il feed non lo contiene piu) si rinvia tutto
+pub fn sincronizza(
+    cartella: &Path,
+    stato: &Path,
+    destinazione: &dyn Destinazione,
+    tesauri: &[&Tesauro],
+    oggi: NaiveDate,
+) -> Result<Option<Sincronizzazione>, ErroreInventario> {
+    let Some((snapshot, inventario)) = replica::corrente(cartella)? else {
+        return Ok(None);
+    };
+    let inviato = fs::read_to_string(stato).ok().map(|s| s.trim().to_string());
+    if inviato.as_deref() == Some(snapshot.as_str()) {
+        return Ok(None);
+    }
+    let voci = match &inviato {
+        Some(s) => replica::voci_dal(cartella, s)?,
+        None => Vec::new(),
+    };
+
+    let completa = voci.is_empty();
+    let mut richieste: Vec<String> = Vec::new();
+    let ids: BTreeSet<u32> = if completa {
+        // Stato sconosciuto: si svuotano tutti i grafi dei reperti
+        richieste.push(format!(
+            "DELETE {{ GRAPH ?g {{ ?s ?p ?o }} }} WHERE {{ GRAPH ?g {{ ?s ?p ?o }} FILTER(STRSTARTS(STR(?g), \"{}\")) }}",
+            URI_REPERTI
+        ));
+        inventario.tutti().iter().map(|r| r.id).collect()
+    } else {
+        voci.iter().flat_map(|v| v.modificati.iter().chain(&v.rimossi).copied()).collect()
+    };
+
+    let prefissi: String = cidoc::PREFISSI.iter().map(|(p, uri)| format!("PREFIX {}: <{}>\n", p, uri)).collect();
+    let (mut aggiornati, mut rimossi) = (0, 0);
+    let ids: Vec<u32> = ids.into_iter().collect();
+    for blocco in ids.chunks(BLOCCO) {
+        let mut operazioni = Vec::new();
+        for &id in blocco {
+            if !completa {
+                operazioni.push(format!("DROP SILENT GRAPH <{}>", grafo(id)));
+            }
+            match cidoc::pubblico(&inventario, id, oggi) {
+                Some(r) => {
+                    operazioni.push(format!("INSERT DATA {{ GRAPH <{}> {{\n{}}} }}", grafo(id), cidoc::triple(&r, tesauri)));
+                    aggiornati += 1;
+                }
+                None if !completa => rimossi += 1,
+                None => {}
+            }
+        }
+        if !operazioni.is_empty() {
+            richieste.push(format!("{}{}", prefissi, operazioni.join(" ;\n")));
+        }
+    }
+    for richiesta in &richieste {
+        destinazione.aggiorna(richiesta)?;
+    }
+
+    let temporaneo = PathBuf::from(format!("{}.tmp", stato.display()));
+    fs::write(&temporaneo, format!("{}\n", snapshot))?;
+    fs::rename(&temporaneo, stato)?;
+    Ok(Some(Sincronizzazione { snapshot, completa, aggiornati, rimossi, richieste: richieste.len() }))
+}
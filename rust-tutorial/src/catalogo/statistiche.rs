@@ -0,0 +1,629 @@
+// ============================================================================
+// MODULO: STATISTICHE
+// ============================================================================
+// Aggregati del catalogo mantenuti in modo incrementale (a ogni
+// aggiunta o rimozione), tabelle incrociate e riepiloghi per il terminale.
+// ============================================================================
+
+//! Statistiche incrementali e tabelle incrociate.
+
+use super::modelli::*;
+use chrono::NaiveDate;
+use crate::schermo;
+use crate::tui_text::{self, Bordo, Riquadro};
+use std::collections::{BTreeMap, HashSet};
+
+/// Valore riportato nelle celle della tabella incrociata
+#[derive(Debug, Clone, Copy)]
+pub enum Misura {
+    Conteggio,
+    PesoTotale,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Cella {
+    pub conteggio: usize,
+    pub peso_g: f64,
+}
+
+impl Cella {
+    fn valore(&self, misura: Misura) -> String {
+        match misura {
+            Misura::Conteggio => self.conteggio.to_string(),
+            Misura::PesoTotale => format!("{:.0}", self.peso_g),
+        }
+    }
+
+    fn somma(&mut self, altra: &Cella) {
+        self.conteggio += altra.conteggio;
+        self.peso_g += altra.peso_g;
+    }
+}
+
+/// Tabella incrociata periodo (righe) x materiale principale (colonne)
+#[derive(Debug, Default)]
+pub struct TabellaIncrociata {
+    pub celle: BTreeMap<(Periodo, Materiale), Cella>,
+}
+
+impl TabellaIncrociata {
+    pub fn da_reperti(reperti: &[&Reperto]) -> Self {
+        let mut tabella = TabellaIncrociata::default();
+        for r in reperti {
+            let Some(materiale) = r.materiale() else { continue };
+            let cella = tabella.celle.entry((r.periodo.clone(), materiale.clone())).or_default();
+            cella.conteggio += 1;
+            cella.peso_g += r.misurazioni.peso_grammi.unwrap_or(0.0);
+        }
+        tabella
+    }
+
+    /// Periodi in ordine cronologico
+    pub fn righe(&self) -> Vec<&Periodo> {
+        let mut righe: Vec<&Periodo> = self.celle.keys().map(|(p, _)| p).collect();
+        righe.dedup();
+        righe
+    }
+
+    pub fn colonne(&self) -> Vec<&Materiale> {
+        let mut colonne: Vec<&Materiale> = self.celle.keys().map(|(_, m)| m).collect();
+        colonne.sort();
+        colonne.dedup();
+        colonne
+    }
+
+    /// Griglia completa con intestazione, totali di riga e di colonna
+    fn griglia(&self, misura: Misura) -> Vec<Vec<String>> {
+        let (righe, colonne) = (self.righe(), self.colonne());
+        let mut griglia = vec![];
+        let mut intestazione = vec!["Periodo".to_string()];
+        intestazione.extend(colonne.iter().map(|m| m.to_string()));
+        intestazione.push("Totale".to_string());
+        griglia.push(intestazione);
+
+        let mut totali_colonna = vec![Cella::default(); colonne.len()];
+        let mut totale = Cella::default();
+        for periodo in righe {
+            let mut riga = vec![periodo.to_string()];
+            let mut totale_riga = Cella::default();
+            for (i, materiale) in colonne.iter().enumerate() {
+                let cella = self.celle.get(&(periodo.clone(), (*materiale).clone())).copied().unwrap_or_default();
+                riga.push(cella.valore(misura));
+                totale_riga.somma(&cella);
+                totali_colonna[i].somma(&cella);
+            }
+            riga.push(totale_riga.valore(misura));
+            totale.somma(&totale_riga);
+            griglia.push(riga);
+        }
+        let mut ultima = vec!["Totale".to_string()];
+        ultima.extend(totali_colonna.iter().map(|c| c.valore(misura)));
+        ultima.push(totale.valore(misura));
+        griglia.push(ultima);
+        griglia
+    }
+
+    /// Tabella di testo con colonne allineate
+    pub fn testo(&self, misura: Misura) -> String {
+        let griglia = self.griglia(misura);
+        let colonne = griglia[0].len();
+        let larghezze: Vec<usize> = (0..colonne)
+            .map(|c| griglia.iter().map(|r| r[c].chars().count()).max().unwrap_or(0))
+            .collect();
+        let mut testo = String::new();
+        for (i, riga) in griglia.iter().enumerate() {
+            let mut linea = format!("{:<w$}", riga[0], w = larghezze[0]);
+            for (c, valore) in riga.iter().enumerate().skip(1) {
+                linea.push_str(&format!("  {:>w$}", valore, w = larghezze[c]));
+            }
+            testo.push_str(linea.trim_end());
+            testo.push('\n');
+            if i == 0 || i == griglia.len() - 2 {
+                let totale: usize = larghezze.iter().sum::<usize>() + 2 * (colonne - 1);
+                testo.push_str(&"-".repeat(totale));
+                testo.push('\n');
+            }
+        }
+        testo
+    }
+
+    /// Esportazione CSV separata da ';' con righe e colonne di totale;
+    /// se indicata, l'impronta del catalogo va in una riga di commento iniziale
+    pub fn csv(&self, misura: Misura, snapshot: Option<&str>) -> String {
+        let intestazione = snapshot.map_or(String::new(), |s| format!("# snapshot: {}\n", s));
+        self.griglia(misura)
+            .iter()
+            .fold(intestazione, |csv, riga| csv + &riga.join(";") + "\n")
+    }
+}
+
+pub struct ReportStatistiche {
+    pub totale_reperti: usize,
+    pub incrocio: TabellaIncrociata,
+    pub per_sito: BTreeMap<String, usize>,
+    pub per_conservazione: BTreeMap<String, usize>,
+    pub per_classe_funzionale: BTreeMap<String, usize>,
+    /// Reperti per tecnica e per motivo decorativo (un reperto conta una
+    /// volta per ciascun valore distinto)
+    pub per_tecnica_decorativa: BTreeMap<String, usize>,
+    pub per_motivo_decorativo: BTreeMap<String, usize>,
+    pub peso_medio: Option<f64>,
+    pub peso_totale: f64,
+    pub punteggio_conservazione_medio: f64,
+}
+
+/// Aggregati che l'inventario aggiorna in modo incrementale, senza
+/// ripercorrere tutti i reperti a ogni richiesta
+#[derive(Debug, Default, Clone)]
+pub struct CacheStatistiche {
+    pub totale: usize,
+    pub per_periodo: BTreeMap<String, usize>,
+    pub per_materiale: BTreeMap<String, usize>,
+    pub per_sito: BTreeMap<String, usize>,
+    pub per_conservazione: BTreeMap<String, usize>,
+    pub peso_per_periodo: BTreeMap<String, f64>,
+    pub peso_totale: f64,
+    pub pesati: usize,
+    pub somma_conservazione: u32,
+}
+
+fn incrementa(mappa: &mut BTreeMap<String, usize>, chiave: String) {
+    *mappa.entry(chiave).or_insert(0) += 1;
+}
+
+fn decrementa(mappa: &mut BTreeMap<String, usize>, chiave: &str) {
+    if let Some(n) = mappa.get_mut(chiave) {
+        *n -= 1;
+        if *n == 0 {
+            mappa.remove(chiave);
+        }
+    }
+}
+
+impl CacheStatistiche {
+    pub fn da_reperti<'a>(reperti: impl IntoIterator<Item = &'a Reperto>) -> Self {
+        let mut cache = CacheStatistiche::default();
+        for r in reperti {
+            cache.aggiungi(r);
+        }
+        cache
+    }
+
+    fn materiale(r: &Reperto) -> String {
+        r.materiale().map_or("?".to_string(), |m| m.to_string())
+    }
+
+    pub fn aggiungi(&mut self, r: &Reperto) {
+        self.totale += 1;
+        incrementa(&mut self.per_periodo, r.periodo.to_string());
+        incrementa(&mut self.per_materiale, Self::materiale(r));
+        incrementa(&mut self.per_sito, r.sito.clone());
+        incrementa(&mut self.per_conservazione, r.conservazione.to_string());
+        if let Some(peso) = r.misurazioni.peso_grammi {
+            *self.peso_per_periodo.entry(r.periodo.to_string()).or_insert(0.0) += peso;
+            self.peso_totale += peso;
+            self.pesati += 1;
+        }
+        self.somma_conservazione += r.conservazione.punteggio() as u32;
+    }
+
+    pub fn togli(&mut self, r: &Reperto) {
+        self.totale -= 1;
+        decrementa(&mut self.per_periodo, &r.periodo.to_string());
+        decrementa(&mut self.per_materiale, &Self::materiale(r));
+        decrementa(&mut self.per_sito, &r.sito);
+        decrementa(&mut self.per_conservazione, &r.conservazione.to_string());
+        if let Some(peso) = r.misurazioni.peso_grammi {
+            let periodo = r.periodo.to_string();
+            if let Some(somma) = self.peso_per_periodo.get_mut(&periodo) {
+                *somma -= peso;
+                if !self.per_periodo.contains_key(&periodo) {
+                    self.peso_per_periodo.remove(&periodo);
+                }
+            }
+            self.peso_totale -= peso;
+            self.pesati -= 1;
+        }
+        self.somma_conservazione -= r.conservazione.punteggio() as u32;
+    }
+
+    pub fn peso_medio(&self) -> Option<f64> {
+        (self.pesati > 0).then(|| self.peso_totale / self.pesati as f64)
+    }
+
+    pub fn conservazione_media(&self) -> f64 {
+        if self.totale == 0 {
+            0.0
+        } else {
+            self.somma_conservazione as f64 / self.totale as f64
+        }
+    }
+
+    /// Confronto con un altro insieme di aggregati, con tolleranza sulle
+    /// somme dei pesi (gli arrotondamenti si accumulano)
+    pub fn coincide_con(&self, altra: &CacheStatistiche) -> bool {
+        let vicini = |a: f64, b: f64| (a - b).abs() < 1e-6;
+        self.totale == altra.totale
+            && self.per_periodo == altra.per_periodo
+            && self.per_materiale == altra.per_materiale
+            && self.per_sito == altra.per_sito
+            && self.per_conservazione == altra.per_conservazione
+            && self.pesati == altra.pesati
+            && self.somma_conservazione == altra.somma_conservazione
+            && vicini(self.peso_totale, altra.peso_totale)
+            && self.peso_per_periodo.keys().chain(altra.peso_per_periodo.keys()).all(|k| {
+                vicini(
+                    self.peso_per_periodo.get(k).copied().unwrap_or(0.0),
+                    altra.peso_per_periodo.get(k).copied().unwrap_or(0.0),
+                )
+            })
+    }
+}
+
+/// Vulnerabilita del materiale da 1 (stabile) a 5 (molto instabile)
+pub fn vulnerabilita_materiale(materiale: &Materiale) -> u8 {
+    match materiale {
+        Materiale::Ferro => 5,
+        Materiale::Osso => 4,
+        Materiale::Bronzo | Materiale::Argento | Materiale::Altro(_) => 3,
+        Materiale::Ceramica => 2,
+        Materiale::Oro | Materiale::Pietra => 1,
+    }
+}
+
+/// Voce della lista di priorita di restauro. I percentili (0-100)
+/// indicano la posizione del reperto rispetto agli altri per ciascun
+/// criterio: piu alto, piu urgente
+#[derive(Debug)]
+pub struct PrioritaRestauro {
+    pub id: u32,
+    pub nome: String,
+    pub punteggio: f64,
+    pub percentile_conservazione: f64,
+    pub percentile_materiale: f64,
+    pub percentile_tempo: f64,
+    /// None se il reperto non e mai stato trattato
+    pub giorni_da_ultimo_intervento: Option<i64>,
+}
+
+/// Percentile a rango medio: i valori uguali ricevono lo stesso percentile
+fn percentili(valori: &[f64]) -> Vec<f64> {
+    let n = valori.len() as f64;
+    valori
+        .iter()
+        .map(|v| {
+            let minori = valori.iter().filter(|w| *w < v).count() as f64;
+            let uguali = valori.iter().filter(|w| *w == v).count() as f64;
+            (minori + 0.5 * uguali) / n * 100.0
+        })
+        .collect()
+}
+
+/// I primi `n` reperti da restaurare. Il punteggio pesa per meta lo stato
+/// di conservazione e per un quarto ciascuno la vulnerabilita del
+/// materiale piu fragile e il tempo trascorso dall'ultimo intervento
+/// (i reperti mai trattati contano come i piu trascurati)
+pub fn priorita_restauro(reperti: &[&Reperto], oggi: NaiveDate, n: usize) -> Vec<PrioritaRestauro> {
+    let degrado: Vec<f64> = reperti.iter().map(|r| (6 - r.conservazione.punteggio()) as f64).collect();
+    let materiale: Vec<f64> = reperti
+        .iter()
+        .map(|r| r.componenti.iter().map(|c| vulnerabilita_materiale(&c.materiale)).max().unwrap_or(3) as f64)
+        .collect();
+    let giorni: Vec<Option<i64>> = reperti
+        .iter()
+        .map(|r| r.interventi.iter().map(|i| i.data).max().map(|d| (oggi - d).num_days()))
+        .collect();
+    let tempo: Vec<f64> = giorni.iter().map(|g| g.map_or(f64::INFINITY, |g| g as f64)).collect();
+
+    let (p_degrado, p_materiale, p_tempo) = (percentili(&degrado), percentili(&materiale), percentili(&tempo));
+    let mut lista: Vec<PrioritaRestauro> = reperti
+        .iter()
+        .enumerate()
+        .map(|(i, r)| PrioritaRestauro {
+            id: r.id,
+            nome: r.nome.clone(),
+            punteggio: 0.5 * p_degrado[i] + 0.25 * p_materiale[i] + 0.25 * p_tempo[i],
+            percentile_conservazione: p_degrado[i],
+            percentile_materiale: p_materiale[i],
+            percentile_tempo: p_tempo[i],
+            giorni_da_ultimo_intervento: giorni[i],
+        })
+        .collect();
+    lista.sort_by(|a, b| b.punteggio.total_cmp(&a.punteggio).then(a.id.cmp(&b.id)));
+    lista.truncate(n);
+    lista
+}
+
+pub fn stampa_priorita(lista: &[PrioritaRestauro]) {
+    println!("  {:>3} {:<34} {:>6} {:>6} {:>6} {:>6}  ultimo intervento", "ID", "Reperto", "Tot", "Cons.", "Mat.", "Tempo");
+    for p in lista {
+        let ultimo = p.giorni_da_ultimo_intervento.map_or("mai".to_string(), |g| format!("{} giorni fa", g));
+        println!(
+            "  {:>3} {:<34} {:>6.1} {:>6.0} {:>6.0} {:>6.0}  {}",
+            p.id, p.nome, p.punteggio, p.percentile_conservazione, p.percentile_materiale, p.percentile_tempo, ultimo
+        );
+    }
+}
+
+pub fn genera_report(reperti: &[&Reperto]) -> ReportStatistiche {
+    let mut per_sito: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_conservazione: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_classe_funzionale: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_tecnica_decorativa: BTreeMap<String, usize> = BTreeMap::new();
+    let mut per_motivo_decorativo: BTreeMap<String, usize> = BTreeMap::new();
+
+    let mut peso_totale = 0.0;
+    let mut count_peso = 0;
+    let mut somma_conservazione = 0u32;
+
+    for reperto in reperti {
+        *per_sito
+            .entry(reperto.sito.clone())
+            .or_insert(0) += 1;
+        *per_conservazione
+            .entry(format!("{}", reperto.conservazione))
+            .or_insert(0) += 1;
+
+        *per_classe_funzionale
+            .entry(reperto.classe_funzionale.map_or("Non classificato".to_string(), |c| c.to_string()))
+            .or_insert(0) += 1;
+
+        let tecniche: HashSet<_> = reperto.decorazioni.iter().map(|d| d.tecnica).collect();
+        for tecnica in tecniche {
+            *per_tecnica_decorativa.entry(tecnica.to_string()).or_insert(0) += 1;
+        }
+        let motivi: HashSet<_> = reperto.decorazioni.iter().map(|d| d.motivo).collect();
+        for motivo in motivi {
+            *per_motivo_decorativo.entry(motivo.to_string()).or_insert(0) += 1;
+        }
+
+        if let Some(peso) = reperto.misurazioni.peso_grammi {
+            peso_totale += peso;
+            count_peso += 1;
+        }
+
+        somma_conservazione += reperto.conservazione.punteggio() as u32;
+    }
+
+    let peso_medio = if count_peso > 0 {
+        Some(peso_totale / count_peso as f64)
+    } else {
+        None
+    };
+
+    let punteggio_conservazione_medio = if !reperti.is_empty() {
+        somma_conservazione as f64 / reperti.len() as f64
+    } else {
+        0.0
+    };
+
+    ReportStatistiche {
+        totale_reperti: reperti.len(),
+        incrocio: TabellaIncrociata::da_reperti(reperti),
+        per_sito,
+        per_conservazione,
+        per_classe_funzionale,
+        per_tecnica_decorativa,
+        per_motivo_decorativo,
+        peso_medio,
+        peso_totale,
+        punteggio_conservazione_medio,
+    }
+}
+
+/// Composizione funzionale di un insieme chiuso (ripostiglio, corredo)
+pub struct ComposizioneFunzionale {
+    pub conteggi: BTreeMap<ClasseFunzionale, usize>,
+    pub non_classificati: usize,
+}
+
+impl ComposizioneFunzionale {
+    /// Quota della classe sui reperti classificati, tra 0 e 1
+    pub fn quota(&self, classe: ClasseFunzionale) -> f64 {
+        let totale: usize = self.conteggi.values().sum();
+        if totale == 0 {
+            return 0.0;
+        }
+        self.conteggi.get(&classe).copied().unwrap_or(0) as f64 / totale as f64
+    }
+
+    /// Classe piu rappresentata
+    pub fn prevalente(&self) -> Option<ClasseFunzionale> {
+        self.conteggi.iter().max_by_key(|(_, n)| **n).map(|(c, _)| *c)
+    }
+}
+
+pub fn composizione_funzionale(reperti: &[&Reperto]) -> ComposizioneFunzionale {
+    let mut conteggi = BTreeMap::new();
+    let mut non_classificati = 0;
+    for r in reperti {
+        match r.classe_funzionale {
+            Some(classe) => *conteggi.entry(classe).or_insert(0) += 1,
+            None => non_classificati += 1,
+        }
+    }
+    ComposizioneFunzionale { conteggi, non_classificati }
+}
+
+/// Frammentazione di un contesto o ripostiglio
+pub struct IndiceFrammentazione {
+    pub completi: usize,
+    pub frammentari: usize,
+    /// Peso dei reperti per cui si puo stimare il peso originale
+    pub peso_conservato_g: f64,
+    pub peso_originale_stimato_g: f64,
+    /// Frammenti pesati ma senza stima di completezza
+    pub esclusi_dalla_stima: usize,
+}
+
+impl IndiceFrammentazione {
+    /// Rapporto completi/frammentari (None se non ci sono frammenti)
+    pub fn rapporto(&self) -> Option<f64> {
+        (self.frammentari > 0).then(|| self.completi as f64 / self.frammentari as f64)
+    }
+
+    /// Quota del peso originale giunta fino a noi, tra 0 e 1
+    pub fn quota_peso_conservata(&self) -> Option<f64> {
+        (self.peso_originale_stimato_g > 0.0).then(|| self.peso_conservato_g / self.peso_originale_stimato_g)
+    }
+}
+
+pub fn indice_frammentazione(reperti: &[&Reperto]) -> IndiceFrammentazione {
+    let mut indice = IndiceFrammentazione {
+        completi: 0,
+        frammentari: 0,
+        peso_conservato_g: 0.0,
+        peso_originale_stimato_g: 0.0,
+        esclusi_dalla_stima: 0,
+    };
+    for r in reperti {
+        let completo = r.e_completo();
+        if completo {
+            indice.completi += 1;
+        } else {
+            indice.frammentari += 1;
+        }
+        let Some(peso) = r.misurazioni.peso_grammi else { continue };
+        let originale = match r.completo_stimato_percento {
+            Some(p) => peso * 100.0 / p,
+            None if completo => peso,
+            None => {
+                indice.esclusi_dalla_stima += 1;
+                continue;
+            }
+        };
+        indice.peso_conservato_g += peso;
+        indice.peso_originale_stimato_g += originale;
+    }
+    indice
+}
+
+/// Conteggi dal piu alto, a parita in ordine alfabetico, con i nomi in
+/// colonna: la larghezza della colonna e quella del nome piu lungo
+fn righe_conteggio(conteggi: &BTreeMap<String, usize>, prefisso: &str) -> Vec<String> {
+    let mut voci: Vec<_> = conteggi.iter().collect();
+    voci.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let colonna = tui_text::colonna(voci.iter().map(|(nome, _)| nome.as_str()));
+    voci.iter()
+        .map(|(nome, n)| format!("  {}{} {:>3}", prefisso, tui_text::a_sinistra(nome, colonna), n))
+        .collect()
+}
+
+pub fn stampa_report(report: &ReportStatistiche) {
+    let mut riquadro = Riquadro::nuovo(Bordo::Doppio).margine(2).larghezza_minima(55);
+    riquadro.riga("STATISTICHE INVENTARIO").separatore();
+    riquadro.riga(format!("Totale reperti: {:>4}", report.totale_reperti));
+    riquadro.riga(format!("Peso totale: {:>8.0}g", report.peso_totale));
+    if let Some(medio) = report.peso_medio {
+        riquadro.riga(format!("Peso medio:  {:>8.1}g", medio));
+    }
+    riquadro.riga(format!("Conservazione media: {:.1}/5", report.punteggio_conservazione_medio));
+
+    let sezioni = [
+        ("PER SITO:", &report.per_sito),
+        ("PER CONSERVAZIONE:", &report.per_conservazione),
+        ("PER CLASSE FUNZIONALE:", &report.per_classe_funzionale),
+    ];
+    for (titolo, conteggi) in sezioni {
+        riquadro.separatore().riga(titolo);
+        for riga in righe_conteggio(conteggi, "") {
+            riquadro.riga(riga);
+        }
+    }
+
+    if !report.per_motivo_decorativo.is_empty() {
+        riquadro.separatore().riga("DECORAZIONI:");
+        let righe = righe_conteggio(&report.per_tecnica_decorativa, "tecnica ")
+            .into_iter()
+            .chain(righe_conteggio(&report.per_motivo_decorativo, "motivo  "));
+        for riga in righe {
+            riquadro.riga(riga);
+        }
+    }
+    schermo::stampa(&riquadro);
+
+    println!("\n  PERIODO x MATERIALE (numero di reperti):\n");
+    for riga in report.incrocio.testo(Misura::Conteggio).lines() {
+        println!("  {}", riga);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::inventario::Inventario;
+
+    fn inventario_di_prova() -> Inventario {
+        let mut inv = Inventario::nuovo();
+        for (nome, sito, peso) in [("Ascia", "Frattesina", 320.0), ("Spillone", "Frattesina", 12.5), ("Fibula", "Pontecagnano", 8.0)] {
+            let reperto = Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, sito)
+                .con_misurazioni(Misurazioni::nuove().con_peso(peso));
+            inv.aggiungi(reperto).unwrap();
+        }
+        inv
+    }
+
+    /// La cache deve dire sempre quello che direbbe un ricalcolo da zero
+    fn come_da_zero(inv: &mut Inventario) {
+        let da_zero = CacheStatistiche::da_reperti(inv.tutti());
+        assert!(inv.statistiche().coincide_con(&da_zero));
+    }
+
+    #[test]
+    fn aggiunta() {
+        let mut inv = inventario_di_prova();
+        assert_eq!(inv.statistiche().totale, 3);
+        inv.aggiungi(Reperto::nuovo("Vaso", Materiale::Ceramica, Periodo::BronzoFinale, Conservazione::Frammentario, "Pontecagnano"))
+            .unwrap();
+        let cache = inv.statistiche();
+        assert_eq!((cache.totale, cache.pesati), (4, 3));
+        assert_eq!(cache.per_sito.get("Pontecagnano"), Some(&2));
+        come_da_zero(&mut inv);
+    }
+
+    #[test]
+    fn modifica_di_un_reperto() {
+        let mut inv = inventario_di_prova();
+        assert_eq!(inv.statistiche().peso_totale, 340.5);
+        let fibula = inv.cerca_per_id_mut(3).unwrap();
+        fibula.sito = "Frattesina".to_string();
+        fibula.misurazioni.peso_grammi = Some(10.0);
+        let cache = inv.statistiche();
+        assert_eq!(cache.per_sito.get("Frattesina"), Some(&3));
+        assert!(!cache.per_sito.contains_key("Pontecagnano"));
+        assert_eq!(cache.peso_totale, 342.5);
+        come_da_zero(&mut inv);
+
+        // Due modifiche di fila prima di leggere contano una volta sola
+        inv.cerca_per_id_mut(1).unwrap().periodo = Periodo::BronzoFinale;
+        inv.cerca_per_id_mut(1).unwrap().misurazioni.peso_grammi = None;
+        assert_eq!(inv.statistiche().pesati, 2);
+        come_da_zero(&mut inv);
+    }
+
+    #[test]
+    fn modifica_di_tutti() {
+        let mut inv = inventario_di_prova();
+        inv.statistiche();
+        inv.modifica_tutti(|r| r.conservazione = Conservazione::Frammentario);
+        assert_eq!(inv.statistiche().per_conservazione.len(), 1);
+        come_da_zero(&mut inv);
+    }
+
+    #[test]
+    fn rimozione() {
+        let mut inv = inventario_di_prova();
+        inv.statistiche();
+        inv.rimuovi(3).unwrap();
+        let cache = inv.statistiche();
+        assert_eq!((cache.totale, cache.pesati), (2, 2));
+        assert!(!cache.per_sito.contains_key("Pontecagnano"));
+        come_da_zero(&mut inv);
+
+        // Rimosso dopo una modifica non ancora ricontata
+        inv.cerca_per_id_mut(2).unwrap().misurazioni.peso_grammi = Some(15.0);
+        inv.rimuovi(2).unwrap();
+        assert_eq!(inv.statistiche().peso_totale, 320.0);
+        come_da_zero(&mut inv);
+    }
+}
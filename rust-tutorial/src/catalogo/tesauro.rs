@@ -0,0 +1,587 @@
+// ============================================================================
+// MODULO: TESAURI
+// ============================================================================
+// Vocabolari controllati gerarchici per materiali, tipologie e
+// decorazioni: cercando un termine generico si trovano anche i reperti
+// descritti con quelli piu specifici. Si esportano in SKOS.
+// ============================================================================
+
+//! Tesauri gerarchici con ricerca per termine ed esportazione SKOS.
+
+use super::errori::ErroreInventario;
+use super::inventario::Inventario;
+use super::modelli::{Materiale, MotivoDecorativo, Reperto, TecnicaDecorativa};
+use std::collections::{BTreeMap, BTreeSet};
+
+const SKOS: &str = "http://www.w3.org/2004/02/skos/core#";
+
+/// Concetto del tesauro con i suoi legami gerarchici e associativi
+#[derive(Debug, Clone)]
+pub struct Concetto {
+    pub id: String,
+    pub etichetta: String,
+    pub sinonimi: Vec<String>,
+    /// Termini piu generici (SKOS broader): possono essere piu di uno
+    pub piu_generici: Vec<String>,
+    pub correlati: Vec<String>,
+    /// Identificativo Getty AAT equivalente (SKOS exactMatch)
+    pub aat: Option<String>,
+}
+
+/// Campo del reperto su cui interrogare un tesauro
+#[derive(Debug, Clone, Copy)]
+pub enum Campo {
+    Materiale,
+    Tipologia,
+    Decorazione,
+}
+
+/// Vocabolario controllato gerarchico, esportabile come schema SKOS
+#[derive(Debug, Clone)]
+pub struct Tesauro {
+    pub nome: String,
+    pub uri_base: String,
+    concetti: BTreeMap<String, Concetto>,
+}
+
+impl Tesauro {
+    pub fn nuovo(nome: &str, uri_base: &str) -> Self {
+        Tesauro { nome: nome.to_string(), uri_base: uri_base.to_string(), concetti: BTreeMap::new() }
+    }
+
+    /// Aggiunge un concetto sotto i termini piu generici indicati (gia presenti)
+    pub fn aggiungi(&mut self, id: &str, etichetta: &str, piu_generici: &[&str]) -> Result<(), ErroreInventario> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ErroreInventario::DatiNonValidi(format!("identificativo di concetto non valido: '{}'", id)));
+        }
+        if self.concetti.contains_key(id) {
+            return Err(ErroreInventario::DatiNonValidi(format!("concetto '{}' gia presente", id)));
+        }
+        for g in piu_generici {
+            self.cerca(g)?;
+        }
+        self.concetti.insert(id.to_string(), Concetto {
+            id: id.to_string(),
+            etichetta: etichetta.to_string(),
+            sinonimi: Vec::new(),
+            piu_generici: piu_generici.iter().map(|g| g.to_string()).collect(),
+            correlati: Vec::new(),
+            aat: None,
+        });
+        Ok(())
+    }
+
+    pub fn sinonimo(&mut self, id: &str, sinonimo: &str) -> Result<(), ErroreInventario> {
+        self.cerca(id)?;
+        if let Some(c) = self.concetti.get_mut(id) {
+            c.sinonimi.push(sinonimo.to_string());
+        }
+        Ok(())
+    }
+
+    /// Collega un concetto al suo equivalente Getty AAT
+    pub fn imposta_aat(&mut self, id: &str, aat: &str) -> Result<(), ErroreInventario> {
+        if aat.is_empty() || !aat.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ErroreInventario::DatiNonValidi(format!("identificativo AAT non valido: '{}'", aat)));
+        }
+        self.cerca(id)?;
+        if let Some(c) = self.concetti.get_mut(id) {
+            c.aat = Some(aat.to_string());
+        }
+        Ok(())
+    }
+
+    /// Legame associativo (SKOS related), simmetrico
+    pub fn correla(&mut self, a: &str, b: &str) -> Result<(), ErroreInventario> {
+        self.cerca(a)?;
+        self.cerca(b)?;
+        for (da, verso) in [(a, b), (b, a)] {
+            if let Some(c) = self.concetti.get_mut(da) {
+                if !c.correlati.iter().any(|x| x == verso) {
+                    c.correlati.push(verso.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn cerca(&self, id: &str) -> Result<&Concetto, ErroreInventario> {
+        self.concetti
+            .get(id)
+            .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("concetto '{}' non presente in '{}'", id, self.nome)))
+    }
+
+    pub fn concetti(&self) -> impl Iterator<Item = &Concetto> {
+        self.concetti.values()
+    }
+
+    /// Concetto per identificativo, etichetta o sinonimo (senza distinguere maiuscole)
+    pub fn risolvi(&self, testo: &str) -> Option<&Concetto> {
+        let testo = testo.trim().to_lowercase();
+        self.concetti.get(&testo).or_else(|| {
+            self.concetti.values().find(|c| {
+                c.etichetta.to_lowercase() == testo || c.sinonimi.iter().any(|s| s.to_lowercase() == testo)
+            })
+        })
+    }
+
+    /// Termini immediatamente piu specifici (SKOS narrower)
+    pub fn piu_specifici(&self, id: &str) -> Vec<&Concetto> {
+        self.concetti.values().filter(|c| c.piu_generici.iter().any(|g| g == id)).collect()
+    }
+
+    /// Il concetto e tutti quelli sotto di lui, a qualsiasi profondita
+    pub fn discendenti(&self, id: &str) -> BTreeSet<&str> {
+        let mut visti = BTreeSet::new();
+        let mut da_visitare: Vec<&str> = self.concetti.get(id).map(|c| c.id.as_str()).into_iter().collect();
+        while let Some(corrente) = da_visitare.pop() {
+            if visti.insert(corrente) {
+                da_visitare.extend(self.piu_specifici(corrente).into_iter().map(|c| c.id.as_str()));
+            }
+        }
+        visti
+    }
+
+    /// Reperti con almeno un valore del campo che ricade sotto il termine
+    /// indicato, es. tutte le tipologie piu specifiche di "asce"
+    pub fn reperti_sotto<'a>(&self, inv: &'a Inventario, campo: Campo, termine: &str) -> Result<Vec<&'a Reperto>, ErroreInventario> {
+        let radice = self
+            .risolvi(termine)
+            .ok_or_else(|| ErroreInventario::DatiNonValidi(format!("termine '{}' non presente in '{}'", termine, self.nome)))?;
+        let ammessi = self.discendenti(&radice.id);
+        let dentro = |valore: &str| self.risolvi(valore).is_some_and(|c| ammessi.contains(c.id.as_str()));
+        Ok(inv
+            .tutti()
+            .into_iter()
+            .filter(|r| match campo {
+                Campo::Materiale => r.componenti.iter().any(|c| dentro(&c.materiale.to_string())),
+                Campo::Tipologia => r.tipo.as_deref().is_some_and(dentro),
+                Campo::Decorazione => r
+                    .decorazioni
+                    .iter()
+                    .any(|d| dentro(&d.tecnica.to_string()) || dentro(&d.motivo.to_string())),
+            })
+            .collect())
+    }
+
+    /// Schema SKOS in Turtle
+    pub fn to_skos(&self) -> String {
+        let letterale = |s: &str| format!("\"{}\"@it", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut ttl = format!(
+            "@prefix skos: <{}> .\n@prefix : <{}> .\n\n:schema a skos:ConceptScheme ;\n    skos:prefLabel {} .\n",
+            SKOS,
+            self.uri_base,
+            letterale(&self.nome)
+        );
+        for c in self.concetti.values() {
+            ttl += &format!("\n:{} a skos:Concept ;\n    skos:inScheme :schema ;\n    skos:prefLabel {}", c.id, letterale(&c.etichetta));
+            for s in &c.sinonimi {
+                ttl += &format!(" ;\n    skos:altLabel {}", letterale(s));
+            }
+            if c.piu_generici.is_empty() {
+                ttl += " ;\n    skos:topConceptOf :schema";
+            }
+            for (proprieta, legati) in [
+                ("broader", c.piu_generici.clone()),
+                ("narrower", self.piu_specifici(&c.id).iter().map(|n| n.id.clone()).collect()),
+                ("related", c.correlati.clone()),
+            ] {
+                for l in legati {
+                    ttl += &format!(" ;\n    skos:{} :{}", proprieta, l);
+                }
+            }
+            if let Some(aat) = &c.aat {
+                ttl += &format!(" ;\n    skos:exactMatch <{}{}>", super::aat::URI_AAT, aat);
+            }
+            ttl += " .\n";
+        }
+        ttl
+    }
+
+    /// Legge uno schema SKOS in Turtle (prefissi, prefLabel, altLabel,
+    /// broader, narrower, related, exactMatch verso AAT). Le altre
+    /// proprieta vengono ignorate
+    pub fn da_skos(ttl: &str) -> Result<Tesauro, ErroreInventario> {
+        let non_valido = |msg: String| ErroreInventario::DatiNonValidi(format!("SKOS: {}", msg));
+        let token = tokenizza(ttl).map_err(non_valido)?;
+        let mut prefissi: BTreeMap<String, String> = BTreeMap::new();
+        let mut triple: Vec<(String, String, String)> = Vec::new();
+
+        let mut i = 0;
+        while i < token.len() {
+            if token[i] == "@prefix" {
+                let (Some(nome), Some(uri)) = (token.get(i + 1), token.get(i + 2)) else {
+                    return Err(non_valido("@prefix incompleto".to_string()));
+                };
+                prefissi.insert(nome.trim_end_matches(':').to_string(), uri.trim_matches(['<', '>']).to_string());
+                i += 4;
+                continue;
+            }
+            // soggetto (predicato oggetto (, oggetto)*)(; ...)* .
+            let soggetto = espandi(&token[i], &prefissi);
+            i += 1;
+            loop {
+                let predicato = token.get(i).map(|p| espandi(p, &prefissi)).ok_or_else(|| non_valido("tripla incompleta".to_string()))?;
+                i += 1;
+                loop {
+                    let oggetto = token.get(i).map(|o| espandi(o, &prefissi)).ok_or_else(|| non_valido("tripla incompleta".to_string()))?;
+                    triple.push((soggetto.clone(), predicato.clone(), oggetto));
+                    i += 1;
+                    if token.get(i).map(String::as_str) != Some(",") {
+                        break;
+                    }
+                    i += 1;
+                }
+                match token.get(i).map(String::as_str) {
+                    Some(";") => i += 1,
+                    Some(".") => {
+                        i += 1;
+                        break;
+                    }
+                    altro => return Err(non_valido(format!("atteso ';' o '.', trovato {:?}", altro))),
+                }
+            }
+        }
+
+        let skos = |locale: &str| format!("{}{}", SKOS, locale);
+        let schema = triple
+            .iter()
+            .find(|(_, p, o)| (p == "a" || p.ends_with("#type")) && *o == skos("ConceptScheme"))
+            .map(|(s, _, _)| s.clone());
+        let concetti: Vec<&String> = triple
+            .iter()
+            .filter(|(_, p, o)| (p == "a" || p.ends_with("#type")) && *o == skos("Concept"))
+            .map(|(s, _, _)| s)
+            .collect();
+        // URI base: la parte comune prima dell'identificativo locale
+        let uri_base = concetti
+            .first()
+            .and_then(|u| u.rfind(['/', '#']).map(|p| u[..=p].to_string()))
+            .unwrap_or_default();
+        let locale = |uri: &str| uri.strip_prefix(&uri_base).unwrap_or(uri).to_string();
+        let valori = |s: &str, p: &str| -> Vec<String> {
+            triple.iter().filter(|(ts, tp, _)| ts == s && *tp == skos(p)).map(|(_, _, o)| o.clone()).collect()
+        };
+        let nome = schema
+            .as_deref()
+            .and_then(|s| valori(s, "prefLabel").into_iter().next())
+            .map_or_else(|| "tesauro".to_string(), |n| letterale_da(&n));
+
+        let mut tesauro = Tesauro::nuovo(&nome, &uri_base);
+        // Prima tutti i concetti, poi i legami: l'ordine nel file e libero
+        for uri in &concetti {
+            let etichetta = valori(uri, "prefLabel").into_iter().next().map_or_else(|| locale(uri), |e| letterale_da(&e));
+            tesauro.aggiungi(&locale(uri), &etichetta, &[])?;
+            for s in valori(uri, "altLabel") {
+                tesauro.sinonimo(&locale(uri), &letterale_da(&s))?;
+            }
+        }
+        for uri in &concetti {
+            let id = locale(uri);
+            let mut generici: Vec<String> = valori(uri, "broader").iter().map(|b| locale(b)).collect();
+            generici.extend(
+                triple
+                    .iter()
+                    .filter(|(_, p, o)| *p == skos("narrower") && o == *uri)
+                    .map(|(s, _, _)| locale(s)),
+            );
+            for g in generici {
+                tesauro.cerca(&g)?;
+                if let Some(c) = tesauro.concetti.get_mut(&id) {
+                    if !c.piu_generici.contains(&g) {
+                        c.piu_generici.push(g);
+                    }
+                }
+            }
+            for r in valori(uri, "related") {
+                tesauro.correla(&id, &locale(&r))?;
+            }
+            for m in valori(uri, "exactMatch") {
+                if let Some(aat) = m.strip_prefix(super::aat::URI_AAT) {
+                    tesauro.imposta_aat(&id, aat)?;
+                }
+            }
+        }
+        Ok(tesauro)
+    }
+}
+
+/// Spezza il Turtle in token: URI, nomi con prefisso, letterali e punteggiatura
+fn tokenizza(ttl: &str) -> Result<Vec<String>, String> {
+    let mut token = Vec::new();
+    let mut caratteri = ttl.chars().peekable();
+    while let Some(&c) = caratteri.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                caratteri.next();
+            }
+            '#' => {
+                while caratteri.next().is_some_and(|c| c != '\n') {}
+            }
+            '<' => {
+                let mut uri = String::new();
+                for c in caratteri.by_ref() {
+                    uri.push(c);
+                    if c == '>' {
+                        break;
+                    }
+                }
+                token.push(uri);
+            }
+            '"' => {
+                let mut letterale = String::from(caratteri.next().unwrap_or('"'));
+                let mut chiuso = false;
+                while let Some(c) = caratteri.next() {
+                    letterale.push(c);
+                    if c == '\\' {
+                        letterale.extend(caratteri.next());
+                    } else if c == '"' {
+                        chiuso = true;
+                        break;
+                    }
+                }
+                if !chiuso {
+                    return Err("letterale non chiuso".to_string());
+                }
+                // Etichetta di lingua o tipo (@it, ^^xsd:string)
+                while caratteri.peek().is_some_and(|c| !c.is_whitespace() && !matches!(c, ';' | ',' | '.')) {
+                    letterale.extend(caratteri.next());
+                }
+                token.push(letterale);
+            }
+            ';' | ',' => {
+                token.push(c.to_string());
+                caratteri.next();
+            }
+            _ => {
+                let mut parola = String::new();
+                while let Some(&c) = caratteri.peek() {
+                    // Il punto chiude la tripla solo se seguito da spazio o fine
+                    if c.is_whitespace() || matches!(c, ';' | ',') {
+                        break;
+                    }
+                    if c == '.' {
+                        let mut avanti = caratteri.clone();
+                        avanti.next();
+                        if avanti.peek().is_none_or(|c| c.is_whitespace()) {
+                            break;
+                        }
+                    }
+                    parola.push(c);
+                    caratteri.next();
+                }
+                if parola.is_empty() {
+                    token.push(".".to_string());
+                    caratteri.next();
+                } else {
+                    token.push(parola);
+                }
+            }
+        }
+    }
+    Ok(token)
+}
+
+/// Da nome con prefisso o `<uri>` a URI completo; letterali invariati
+fn espandi(token: &str, prefissi: &BTreeMap<String, String>) -> String {
+    if let Some(uri) = token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+        return uri.to_string();
+    }
+    if token.starts_with('"') || token == "a" {
+        return token.to_string();
+    }
+    match token.split_once(':') {
+        Some((prefisso, locale)) => match prefissi.get(prefisso) {
+            Some(base) => format!("{}{}", base, locale),
+            None => token.to_string(),
+        },
+        None => token.to_string(),
+    }
+}
+
+/// Testo di un letterale Turtle (senza virgolette, lingua ed escape)
+fn letterale_da(token: &str) -> String {
+    let fine = token.rfind('"').unwrap_or(token.len());
+    token.get(1..fine).unwrap_or_default().replace("\\\"", "\"").replace("\\\\", "\\")
+}
+
+fn uri_base(nome: &str) -> String {
+    format!("https://w3id.org/bronzeaxe/tesauro/{}/", nome)
+}
+
+/// Tipologie dei manufatti, organizzate per classe funzionale
+pub fn tipologie() -> Result<Tesauro, ErroreInventario> {
+    let mut t = Tesauro::nuovo("Tipologie", &uri_base("tipologie"));
+    for (id, etichetta, generici) in [
+        ("utensili", "utensili", &[][..]),
+        ("armi", "armi", &[]),
+        ("ornamenti", "ornamenti", &[]),
+        ("asce", "asce", &["utensili"]),
+        ("asce-margini-rialzati", "asce a margini rialzati", &["asce"]),
+        ("ascia-savignano", "Ascia a margini rialzati tipo Savignano", &["asce-margini-rialzati"]),
+        ("ascia-tallone-appenninico", "Ascia a tallone tipo appenninico", &["asce"]),
+        ("ascia-alette", "Ascia ad alette", &["asce"]),
+        ("coltelli", "coltelli", &["utensili"]),
+        ("rasoi", "rasoi", &["utensili"]),
+        ("spade", "spade", &["armi"]),
+        ("spada-allerona", "Spada tipo Allerona", &["spade"]),
+        ("spada-lingua-presa", "Spada a lingua da presa", &["spade"]),
+        ("pugnali", "pugnali", &["armi"]),
+        ("punte-lancia", "punte di lancia", &["armi"]),
+        ("fibule", "fibule", &["ornamenti"]),
+        ("fibula-arco-semplice", "Fibula ad arco semplice", &["fibule"]),
+        ("fibula-arco-serpeggiante", "Fibula ad arco serpeggiante", &["fibule"]),
+        ("fibula-peschiera", "Fibula tipo Peschiera", &["fibule"]),
+    ] {
+        t.aggiungi(id, etichetta, generici)?;
+    }
+    t.sinonimo("asce", "ascia")?;
+    t.sinonimo("spade", "spada")?;
+    t.correla("pugnali", "spade")?;
+    Ok(t)
+}
+
+/// Materiali, con le etichette usate dalle schede
+pub fn materiali() -> Result<Tesauro, ErroreInventario> {
+    let mut t = Tesauro::nuovo("Materiali", &uri_base("materiali"));
+    t.aggiungi("inorganici", "materiali inorganici", &[])?;
+    t.aggiungi("organici", "materiali organici", &[])?;
+    t.aggiungi("metalli", "metalli", &["inorganici"])?;
+    t.aggiungi("metalli-preziosi", "metalli preziosi", &["metalli"])?;
+    for (materiale, generico) in [
+        (Materiale::Bronzo, "metalli"),
+        (Materiale::Ferro, "metalli"),
+        (Materiale::Oro, "metalli-preziosi"),
+        (Materiale::Argento, "metalli-preziosi"),
+        (Materiale::Ceramica, "inorganici"),
+        (Materiale::Pietra, "inorganici"),
+        (Materiale::Osso, "organici"),
+    ] {
+        let etichetta = materiale.to_string();
+        t.aggiungi(&etichetta.to_lowercase(), &etichetta, &[generico])?;
+    }
+    t.sinonimo("bronzo", "lega di rame")?;
+    Ok(t)
+}
+
+/// Tecniche e motivi decorativi del vocabolario delle schede
+pub fn decorazioni() -> Result<Tesauro, ErroreInventario> {
+    use MotivoDecorativo::*;
+    use TecnicaDecorativa::*;
+    let mut t = Tesauro::nuovo("Decorazioni", &uri_base("decorazioni"));
+    t.aggiungi("tecniche", "tecniche decorative", &[])?;
+    t.aggiungi("motivi", "motivi decorativi", &[])?;
+    t.aggiungi("geometrici", "motivi geometrici", &["motivi"])?;
+    t.aggiungi("curvilinei", "motivi curvilinei", &["motivi"])?;
+    t.aggiungi("plastici", "motivi plastici", &["motivi"])?;
+    t.aggiungi("figurati", "motivi figurati", &["motivi"])?;
+    for tecnica in [Incisione, Sbalzo, Punzonatura, Excisione, Impressione, Applicazione] {
+        let nome = tecnica.to_string();
+        t.aggiungi(&nome, &nome, &["tecniche"])?;
+    }
+    for (motivo, generici) in [
+        (Linee, &["geometrici"][..]),
+        (Zigzag, &["geometrici"]),
+        (Triangoli, &["geometrici"]),
+        (Puntini, &["geometrici"]),
+        (Spirali, &["curvilinei"]),
+        (Cerchielli, &["geometrici", "curvilinei"]),
+        (Cordoni, &["plastici"]),
+        (Barca, &["figurati"]),
+    ] {
+        let nome = motivo.to_string();
+        t.aggiungi(&nome.replace(' ', "-"), &nome, generici)?;
+    }
+    t.correla("incisione", "excisione")?;
+    t.correla("puntini", "punzonatura")?;
+    Ok(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Conservazione, Periodo};
+
+    fn reperto(nome: &str, materiale: Materiale, tipo: Option<&str>) -> Reperto {
+        let mut r = Reperto::nuovo(nome, materiale, Periodo::BronzoMedio, Conservazione::Buono, "Savignano");
+        r.tipo = tipo.map(String::from);
+        r
+    }
+
+    #[test]
+    fn risoluzione_per_id_etichetta_e_sinonimo() {
+        let t = tipologie().unwrap();
+        assert_eq!(t.risolvi("spade").map(|c| c.id.as_str()), Some("spade"));
+        assert_eq!(t.risolvi("  SPADA TIPO ALLERONA ").map(|c| c.id.as_str()), Some("spada-allerona"));
+        assert_eq!(t.risolvi("Ascia").map(|c| c.id.as_str()), Some("asce"));
+        assert!(t.risolvi("elmo").is_none());
+    }
+
+    #[test]
+    fn concetti_non_validi() {
+        let mut t = Tesauro::nuovo("Prova", "https://esempio.it/");
+        t.aggiungi("asce", "asce", &[]).unwrap();
+        assert!(t.aggiungi("asce", "doppione", &[]).is_err());
+        assert!(t.aggiungi("asce piatte", "asce piatte", &["asce"]).is_err());
+        assert!(t.aggiungi("asce-piatte", "asce piatte", &["utensili"]).is_err());
+        assert!(t.imposta_aat("asce", "aat:300").is_err());
+        assert!(t.correla("asce", "spade").is_err());
+    }
+
+    #[test]
+    fn discendenti_a_ogni_profondita() {
+        let t = tipologie().unwrap();
+        let sotto_asce = t.discendenti("asce");
+        assert!(sotto_asce.contains("asce") && sotto_asce.contains("ascia-savignano"));
+        assert_eq!(sotto_asce.len(), 5);
+        assert!(t.discendenti("inesistente").is_empty());
+
+        // Un concetto con due termini generici sta sotto tutti e due
+        let d = decorazioni().unwrap();
+        assert!(d.discendenti("geometrici").contains("cerchielli"));
+        assert!(d.discendenti("curvilinei").contains("cerchielli"));
+    }
+
+    #[test]
+    fn reperti_sotto_un_termine_generico() {
+        let mut inv = Inventario::nuovo();
+        inv.aggiungi(reperto("Ascia", Materiale::Bronzo, Some("Ascia a margini rialzati tipo Savignano"))).unwrap();
+        inv.aggiungi(reperto("Spada", Materiale::Bronzo, Some("Spada tipo Allerona"))).unwrap();
+        inv.aggiungi(reperto("Anello", Materiale::Oro, None)).unwrap();
+
+        let nomi = |reperti: Vec<&Reperto>| reperti.iter().map(|r| r.nome.clone()).collect::<Vec<_>>();
+        let t = tipologie().unwrap();
+        assert_eq!(nomi(t.reperti_sotto(&inv, Campo::Tipologia, "utensili").unwrap()), ["Ascia"]);
+        assert_eq!(nomi(t.reperti_sotto(&inv, Campo::Tipologia, "spada").unwrap()), ["Spada"]);
+        let m = materiali().unwrap();
+        assert_eq!(nomi(m.reperti_sotto(&inv, Campo::Materiale, "metalli preziosi").unwrap()), ["Anello"]);
+        assert_eq!(m.reperti_sotto(&inv, Campo::Materiale, "metalli").unwrap().len(), 3);
+        assert!(m.reperti_sotto(&inv, Campo::Materiale, "vetro").is_err());
+    }
+
+    #[test]
+    fn skos_andata_e_ritorno() {
+        let mut t = tipologie().unwrap();
+        t.imposta_aat("asce", "300024591").unwrap();
+        t.sinonimo("fibule", "spilla \"da veste\"").unwrap();
+        let riletto = Tesauro::da_skos(&t.to_skos()).unwrap();
+
+        assert_eq!((riletto.nome.as_str(), riletto.uri_base.as_str()), ("Tipologie", t.uri_base.as_str()));
+        assert_eq!(riletto.concetti().count(), t.concetti().count());
+        for originale in t.concetti() {
+            let c = riletto.risolvi(&originale.id).unwrap();
+            assert_eq!(c.etichetta, originale.etichetta);
+            assert_eq!(c.sinonimi, originale.sinonimi);
+            assert_eq!(c.piu_generici, originale.piu_generici);
+            assert_eq!(c.aat, originale.aat);
+        }
+        assert_eq!(riletto.risolvi("pugnali").unwrap().correlati, ["spade"]);
+    }
+
+    #[test]
+    fn skos_non_validi() {
+        assert!(Tesauro::da_skos("@prefix skos:").is_err());
+        assert!(Tesauro::da_skos(":a a skos:Concept ;").is_err());
+        assert!(Tesauro::da_skos(":a skos:prefLabel \"aperto .").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+// ============================================================================
+// MODULO: ASSISTENTE TIPOLOGICO
+// ============================================================================
+// Una chiave dicotomica pubblicata diventa un albero di domande, letto
+// da un file JSON: rispondendo si arriva al tipo proposto, con il
+// percorso che lo giustifica.
+// ============================================================================
+
+//! Classificazione guidata con chiavi tipologiche in JSON.
+
+use super::errori::ErroreInventario;
+use serde::Deserialize;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Chiave predefinita, inclusa nell'eseguibile
+pub const CHIAVE_ASCE_MARGINI_RIALZATI: &str =
+    include_str!("../../dati/tipologie/asce_margini_rialzati.json");
+
+/// Nodo dell'albero: una domanda diagnostica o un tipo proposto
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Nodo {
+    Domanda { domanda: String, risposte: Vec<Risposta> },
+    Tipo { tipo: String, riferimento: String },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Risposta {
+    pub etichetta: String,
+    pub nodo: Nodo,
+}
+
+/// Chiave tipologica pubblicata, codificata come albero decisionale
+#[derive(Debug, Deserialize)]
+pub struct ChiaveTipologica {
+    pub nome: String,
+    pub fonte: String,
+    #[serde(default)]
+    pub nota: Option<String>,
+    pub radice: Nodo,
+}
+
+/// Tipo proposto e percorso di domande e risposte che lo giustifica
+#[derive(Debug)]
+pub struct Proposta {
+    pub tipo: String,
+    pub riferimento: String,
+    pub percorso: Vec<(String, String)>,
+}
+
+impl ChiaveTipologica {
+    pub fn da_json(json: &str) -> Result<Self, ErroreInventario> {
+        let chiave: ChiaveTipologica = serde_json::from_str(json)?;
+        valida(&chiave.radice)?;
+        Ok(chiave)
+    }
+
+    pub fn da_file(percorso: &Path) -> Result<Self, ErroreInventario> {
+        Self::da_json(&std::fs::read_to_string(percorso)?)
+    }
+}
+
+fn valida(nodo: &Nodo) -> Result<(), ErroreInventario> {
+    match nodo {
+        Nodo::Tipo { .. } => Ok(()),
+        Nodo::Domanda { domanda, risposte } => {
+            if risposte.len() < 2 {
+                return Err(ErroreInventario::DatiNonValidi(format!(
+                    "la domanda '{}' deve avere almeno due risposte",
+                    domanda
+                )));
+            }
+            risposte.iter().try_for_each(|r| valida(&r.nodo))
+        }
+    }
+}
+
+/// Percorre la chiave ponendo le domande su `output` e leggendo le
+/// risposte (numero dell'opzione) da `input`. Restituisce None se
+/// l'input termina prima di arrivare a un tipo
+pub fn assistente<R: BufRead, W: Write>(
+    chiave: &ChiaveTipologica,
+    mut input: R,
+    output: &mut W,
+) -> Result<Option<Proposta>, ErroreInventario> {
+    writeln!(output, "{} ({})", chiave.nome, chiave.fonte)?;
+    if let Some(nota) = &chiave.nota {
+        writeln!(output, "Nota: {}", nota)?;
+    }
+    let mut percorso = Vec::new();
+    let mut nodo = &chiave.radice;
+    loop {
+        let (domanda, risposte) = match nodo {
+            Nodo::Tipo { tipo, riferimento } => {
+                return Ok(Some(Proposta {
+                    tipo: tipo.clone(),
+                    riferimento: riferimento.clone(),
+                    percorso,
+                }));
+            }
+            Nodo::Domanda { domanda, risposte } => (domanda, risposte),
+        };
+        writeln!(output, "\n{}", domanda)?;
+        for (i, r) in risposte.iter().enumerate() {
+            writeln!(output, "  {}) {}", i + 1, r.etichetta)?;
+        }
+        let scelta = loop {
+            write!(output, "> ")?;
+            output.flush()?;
+            let mut riga = String::new();
+            if input.read_line(&mut riga)? == 0 {
+                return Ok(None);
+            }
+            match riga.trim().parse::<usize>() {
+                Ok(n) if (1..=risposte.len()).contains(&n) => break &risposte[n - 1],
+                _ => writeln!(output, "Risposta non valida, indicare un numero da 1 a {}", risposte.len())?,
+            }
+        };
+        writeln!(output, "{}", scelta.etichetta)?;
+        percorso.push((domanda.clone(), scelta.etichetta.clone()));
+        nodo = &scelta.nodo;
+    }
+}
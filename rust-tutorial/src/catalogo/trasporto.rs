@@ -0,0 +1,234 @@
+// ============================================================================
+// MODULO: DISTINTA DI TRASPORTO
+// ============================================================================
+// Per un prestito, un restauro o un trasloco del deposito i reperti
+// viaggiano nelle loro casse. Chi imballa, chi trasporta e chi riceve
+// controllano la stessa distinta:
+//
+//   Cassa: Deposito A / Cassa 12 - 3 reperti, 0.659 kg - FRAGILE
+//     #3    SAV-0003  Ascia a margini rialzati  Bronzo    Buono         327.6 g
+//     #7              Fuseruola                 Ceramica  Frammentario    4.1 g  FRAGILE
+//           foto: foto/7_fronte.jpg
+//
+// Le casse sono le collocazioni dei reperti (quelli senza collocazione
+// finiscono in una voce a parte, da imballare). Il peso e quello dei
+// reperti, senza l'imballaggio; se a qualche reperto manca il peso, il
+// totale della cassa lo dice. Un reperto frammentario o in pessimo stato
+// e fragile, e lo e la cassa che lo contiene. Le foto elencate sono le
+// immagini allegate al reperto: all'arrivo si confronta lo stato con
+// quelle.
+// ============================================================================
+
+//! Distinte di trasporto per cassa, con pesi, reperti fragili e foto dello stato.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use super::modelli::{Conservazione, Reperto, TipoAllegato};
+
+/// Il nome della voce che raccoglie i reperti senza collocazione
+pub const SENZA_CASSA: &str = "(senza cassa, da imballare)";
+
+/// Un reperto nella distinta.
+#[derive(Debug, Clone)]
+pub struct VoceDistinta {
+    pub id: u32,
+    pub identificativo: Option<String>,
+    pub nome: String,
+    pub materiale: String,
+    pub conservazione: Conservazione,
+    pub peso_grammi: Option<f64>,
+    /// Le immagini allegate, per il confronto dello stato all'arrivo
+    pub foto: Vec<PathBuf>,
+}
+
+impl VoceDistinta {
+    fn da_reperto(reperto: &Reperto) -> Self {
+        VoceDistinta {
+            id: reperto.id,
+            identificativo: reperto.identificativo.clone(),
+            nome: reperto.nome.clone(),
+            materiale: reperto.materiale().map_or_else(|| "?".to_string(), |m| m.to_string()),
+            conservazione: reperto.conservazione.clone(),
+            peso_grammi: reperto.misurazioni.peso_grammi,
+            foto: reperto
+                .allegati
+                .iter()
+                .filter(|a| matches!(a.tipo, TipoAllegato::Immagine))
+                .map(|a| PathBuf::from(&a.percorso))
+                .collect(),
+        }
+    }
+
+    pub fn fragile(&self) -> bool {
+        self.conservazione.fragile()
+    }
+}
+
+/// Una cassa con il suo contenuto.
+#[derive(Debug, Clone)]
+pub struct Cassa {
+    pub nome: String,
+    pub reperti: Vec<VoceDistinta>,
+}
+
+impl Cassa {
+    /// Il peso dei reperti pesati
+    pub fn peso_grammi(&self) -> f64 {
+        self.reperti.iter().filter_map(|v| v.peso_grammi).sum()
+    }
+
+    /// Quanti reperti non hanno un peso: il peso della cassa e per difetto
+    pub fn senza_peso(&self) -> usize {
+        self.reperti.iter().filter(|v| v.peso_grammi.is_none()).count()
+    }
+
+    pub fn fragile(&self) -> bool {
+        self.reperti.iter().any(VoceDistinta::fragile)
+    }
+}
+
+/// La distinta di trasporto: le casse in ordine di nome, con i loro
+/// reperti in ordine di id.
+///
+/// ```
+/// use rust_tutorial::catalogo::modelli::{Conservazione, Materiale, Misurazioni, Periodo, Reperto};
+/// use rust_tutorial::catalogo::trasporto::DistintaTrasporto;
+///
+/// let mut ascia = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+/// ascia.misurazioni = Misurazioni::nuove().con_peso(327.6);
+/// ascia.collocazione = Some("Cassa 12".to_string());
+/// let mut fuseruola = Reperto::nuovo("Fuseruola", Materiale::Ceramica, Periodo::BronzoRecente, Conservazione::Frammentario, "Frattesina");
+/// fuseruola.collocazione = Some("Cassa 12".to_string());
+///
+/// let distinta = DistintaTrasporto::nuova([&ascia, &fuseruola]);
+/// let cassa = &distinta.casse[0];
+/// assert_eq!((cassa.nome.as_str(), cassa.peso_grammi(), cassa.senza_peso()), ("Cassa 12", 327.6, 1));
+/// assert!(cassa.fragile());
+/// assert!(distinta.to_string().contains("FRAGILE"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistintaTrasporto {
+    pub casse: Vec<Cassa>,
+}
+
+impl DistintaTrasporto {
+    pub fn nuova<'a>(reperti: impl IntoIterator<Item = &'a Reperto>) -> Self {
+        let mut per_cassa: BTreeMap<&str, Vec<&Reperto>> = BTreeMap::new();
+        for r in reperti {
+            per_cassa.entry(r.collocazione.as_deref().unwrap_or(SENZA_CASSA)).or_default().push(r);
+        }
+        let mut casse: Vec<Cassa> = per_cassa
+            .into_iter()
+            .map(|(nome, mut reperti)| {
+                reperti.sort_by_key(|r| r.id);
+                Cassa { nome: nome.to_string(), reperti: reperti.into_iter().map(VoceDistinta::da_reperto).collect() }
+            })
+            .collect();
+        // I reperti ancora da imballare in fondo, dove si notano
+        casse.sort_by_key(|c| c.nome == SENZA_CASSA);
+        DistintaTrasporto { casse }
+    }
+
+    pub fn reperti(&self) -> usize {
+        self.casse.iter().map(|c| c.reperti.len()).sum()
+    }
+
+    pub fn peso_grammi(&self) -> f64 {
+        self.casse.iter().map(Cassa::peso_grammi).sum()
+    }
+
+    pub fn senza_peso(&self) -> usize {
+        self.casse.iter().map(Cassa::senza_peso).sum()
+    }
+
+    pub fn fragili(&self) -> usize {
+        self.casse.iter().flat_map(|c| &c.reperti).filter(|v| v.fragile()).count()
+    }
+}
+
+/// Il peso in kg, con l'avviso se e per difetto
+fn peso(grammi: f64, senza_peso: usize) -> String {
+    match senza_peso {
+        0 => format!("{:.3} kg", grammi / 1000.0),
+        n => format!("{:.3} kg ({} senza peso)", grammi / 1000.0, n),
+    }
+}
+
+impl fmt::Display for DistintaTrasporto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "DISTINTA DI TRASPORTO")?;
+        writeln!(
+            f,
+            "{} casse, {} reperti, {} di reperti, {} fragili",
+            self.casse.len(),
+            self.reperti(),
+            peso(self.peso_grammi(), self.senza_peso()),
+            self.fragili()
+        )?;
+        let larghezza_nome = self.casse.iter().flat_map(|c| &c.reperti).map(|v| v.nome.chars().count()).max().unwrap_or(0);
+        for cassa in &self.casse {
+            writeln!(f)?;
+            write!(f, "Cassa: {} - {} reperti, {}", cassa.nome, cassa.reperti.len(), peso(cassa.peso_grammi(), cassa.senza_peso()))?;
+            writeln!(f, "{}", if cassa.fragile() { " - FRAGILE" } else { "" })?;
+            for voce in &cassa.reperti {
+                let peso = voce.peso_grammi.map_or_else(|| "-".to_string(), |p| format!("{:.1} g", p));
+                write!(
+                    f,
+                    "  #{:<4} {:<9} {:<w$}  {:<9} {:<13} {:>9}",
+                    voce.id,
+                    voce.identificativo.as_deref().unwrap_or(""),
+                    voce.nome,
+                    voce.materiale,
+                    voce.conservazione.to_string(),
+                    peso,
+                    w = larghezza_nome
+                )?;
+                writeln!(f, "{}", if voce.fragile() { "  FRAGILE" } else { "" })?;
+                if !voce.foto.is_empty() {
+                    let foto: Vec<String> = voce.foto.iter().map(|p| p.display().to_string()).collect();
+                    writeln!(f, "        foto: {}", foto.join(", "))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalogo::modelli::{Allegato, Materiale, Periodo};
+
+    #[test]
+    fn casse_in_ordine_e_foto_dello_stato() {
+        let reperto = |id, nome: &str, cassa: Option<&str>| {
+            let mut r = Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Pessimo, "Frattesina");
+            r.id = id;
+            r.collocazione = cassa.map(str::to_string);
+            r
+        };
+        let mut spada = reperto(4, "Spada", Some("Cassa B"));
+        for (percorso, tipo) in [("foto/4.jpg", TipoAllegato::Immagine), ("rilievo/4.pdf", TipoAllegato::Generico)] {
+            spada.allegati.push(Allegato {
+                percorso: percorso.into(),
+                tipo,
+                impronta_sha256: String::new(),
+                dimensione_byte: 0,
+                miniature: None,
+                diritti: None,
+                autore: None,
+            });
+        }
+        let reperti = [reperto(9, "Spillone", None), spada, reperto(2, "Ascia", Some("Cassa B")), reperto(5, "Ago", Some("Cassa A"))];
+
+        let distinta = DistintaTrasporto::nuova(&reperti);
+        let casse: Vec<&str> = distinta.casse.iter().map(|c| c.nome.as_str()).collect();
+        assert_eq!(casse, ["Cassa A", "Cassa B", SENZA_CASSA]);
+        assert_eq!(distinta.casse[1].reperti.iter().map(|v| v.id).collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(distinta.casse[1].reperti[1].foto, [PathBuf::from("foto/4.jpg")]);
+        assert_eq!((distinta.reperti(), distinta.senza_peso(), distinta.fragili()), (4, 4, 4));
+        assert!(distinta.to_string().contains("        foto: foto/4.jpg\n"));
+    }
+}
@@ -0,0 +1,184 @@
+// ============================================================================
+// MODULO: UNITA DI MISURA
+// ============================================================================
+// Le misure del reperto sono salvate in un'unita fissa (grammi, centimetri,
+// centimetri cubi), scritta nel nome del campo: `peso_grammi`,
+// `lunghezza_cm`. Chi cerca pero pensa anche in chili o in millimetri:
+//
+//   peso > 0.3kg        ->  peso_grammi > 300
+//   lunghezza < 25cm    ->  lunghezza_cm < 25
+//   volume >= 0.5 l     ->  volume >= 500
+//
+// Una quantita e un numero con un'unita; convertita nell'unita del campo,
+// si confronta come un numero qualsiasi. Un'unita dell'altra grandezza
+// (`peso > 25cm`) e un errore, non un confronto sempre falso.
+// ============================================================================
+
+//! Quantita con unita di misura e conversione nelle unita dei campi del reperto.
+
+use std::fmt;
+
+use thiserror::Error;
+
+/// La grandezza misurata da un campo o da un'unita.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimensione {
+    Massa,
+    Lunghezza,
+    Volume,
+    Percentuale,
+    /// Numero puro: id e campi calcolati
+    Numero,
+}
+
+impl fmt::Display for Dimensione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dimensione::Massa => write!(f, "una massa"),
+            Dimensione::Lunghezza => write!(f, "una lunghezza"),
+            Dimensione::Volume => write!(f, "un volume"),
+            Dimensione::Percentuale => write!(f, "una percentuale"),
+            Dimensione::Numero => write!(f, "un numero puro"),
+        }
+    }
+}
+
+/// Unita ammesse: simbolo, grandezza e quante unita del campo valgono.
+/// I campi usano g, cm, cm3 e %.
+const UNITA: [(&str, Dimensione, f64); 12] = [
+    ("mg", Dimensione::Massa, 0.001),
+    ("g", Dimensione::Massa, 1.0),
+    ("kg", Dimensione::Massa, 1000.0),
+    ("mm", Dimensione::Lunghezza, 0.1),
+    ("cm", Dimensione::Lunghezza, 1.0),
+    ("m", Dimensione::Lunghezza, 100.0),
+    ("mm3", Dimensione::Volume, 0.001),
+    ("cm3", Dimensione::Volume, 1.0),
+    ("ml", Dimensione::Volume, 1.0),
+    ("dm3", Dimensione::Volume, 1000.0),
+    ("l", Dimensione::Volume, 1000.0),
+    ("%", Dimensione::Percentuale, 1.0),
+];
+
+/// Campi del reperto con la loro grandezza, e il nome breve ammesso
+/// nelle ricerche (`peso` per `peso_grammi`).
+const CAMPI: [(&str, Option<&str>, Dimensione); 7] = [
+    ("id", None, Dimensione::Numero),
+    ("peso_grammi", Some("peso"), Dimensione::Massa),
+    ("lunghezza_cm", Some("lunghezza"), Dimensione::Lunghezza),
+    ("larghezza_cm", Some("larghezza"), Dimensione::Lunghezza),
+    ("altezza_cm", Some("altezza"), Dimensione::Lunghezza),
+    ("volume", None, Dimensione::Volume),
+    ("completo_stimato_percento", Some("completo"), Dimensione::Percentuale),
+];
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ErroreUnita {
+    #[error("unita '{0}' sconosciuta (ammesse: mg g kg, mm cm m, mm3 cm3 ml dm3 l, %)")]
+    Sconosciuta(String),
+    #[error("quantita non valida: '{0}'")]
+    QuantitaNonValida(String),
+    #[error("'{campo}' e {del_campo}, '{quantita}' e {della_quantita}")]
+    Incompatibili {
+        campo: String,
+        del_campo: Dimensione,
+        quantita: String,
+        della_quantita: Dimensione,
+    },
+}
+
+/// Il nome completo di un campo base (anche dal nome breve) e la sua
+/// grandezza. None per i campi che non sono del reperto.
+///
+/// ```
+/// use rust_tutorial::catalogo::unita::{campo, Dimensione};
+///
+/// assert_eq!(campo("peso"), Some(("peso_grammi", Dimensione::Massa)));
+/// assert_eq!(campo("lunghezza_cm"), Some(("lunghezza_cm", Dimensione::Lunghezza)));
+/// assert_eq!(campo("densita"), None);
+/// ```
+pub fn campo(nome: &str) -> Option<(&'static str, Dimensione)> {
+    CAMPI
+        .iter()
+        .find(|(completo, breve, _)| *completo == nome || *breve == Some(nome))
+        .map(|(completo, _, dimensione)| (*completo, *dimensione))
+}
+
+/// `true` per i nomi brevi (`peso`, `lunghezza`...): non si possono usare
+/// come nomi di campi calcolati.
+pub fn nome_breve(nome: &str) -> bool {
+    CAMPI.iter().any(|(_, breve, _)| *breve == Some(nome))
+}
+
+/// Un numero, con l'unita se indicata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantita {
+    pub valore: f64,
+    pub unita: Option<&'static str>,
+}
+
+impl Quantita {
+    /// `0.3kg`, `25 cm`, `300` (senza unita).
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::unita::{ErroreUnita, Quantita};
+    ///
+    /// assert_eq!(Quantita::da_testo("0.3kg").unwrap(), Quantita { valore: 0.3, unita: Some("kg") });
+    /// assert_eq!(Quantita::da_testo("300").unwrap().unita, None);
+    /// assert_eq!(Quantita::da_testo("3 libbre"), Err(ErroreUnita::Sconosciuta("libbre".into())));
+    /// ```
+    pub fn da_testo(testo: &str) -> Result<Quantita, ErroreUnita> {
+        let testo = testo.trim();
+        let fine_numero = testo
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+')))
+            .unwrap_or(testo.len());
+        let (numero, unita) = testo.split_at(fine_numero);
+        let valore = numero.parse().map_err(|_| ErroreUnita::QuantitaNonValida(testo.to_string()))?;
+        let unita = unita.trim();
+        if unita.is_empty() {
+            return Ok(Quantita { valore, unita: None });
+        }
+        // Il confronto ignora le maiuscole: "KG", "Kg" e "kg" sono la stessa unita
+        let Some((simbolo, _, _)) = UNITA.iter().find(|(s, _, _)| s.eq_ignore_ascii_case(unita)) else {
+            return Err(ErroreUnita::Sconosciuta(unita.to_string()));
+        };
+        Ok(Quantita { valore, unita: Some(simbolo) })
+    }
+
+    pub fn dimensione(&self) -> Option<Dimensione> {
+        let simbolo = self.unita?;
+        UNITA.iter().find(|(s, _, _)| *s == simbolo).map(|(_, d, _)| *d)
+    }
+
+    /// Il valore nell'unita del campo `nome`, che ha la grandezza indicata.
+    /// Senza unita il numero e gia nell'unita del campo.
+    ///
+    /// ```
+    /// use rust_tutorial::catalogo::unita::{Dimensione, Quantita};
+    ///
+    /// let q = Quantita::da_testo("0.3kg").unwrap();
+    /// assert_eq!(q.nell_unita_di("peso_grammi", Dimensione::Massa), Ok(300.0));
+    /// assert!(q.nell_unita_di("lunghezza_cm", Dimensione::Lunghezza).is_err());
+    /// ```
+    pub fn nell_unita_di(&self, nome: &str, dimensione: Dimensione) -> Result<f64, ErroreUnita> {
+        let Some(simbolo) = self.unita else {
+            return Ok(self.valore);
+        };
+        let (_, della_quantita, fattore) = UNITA.iter().find(|(s, _, _)| *s == simbolo).copied().expect("unita dalla tabella");
+        if della_quantita != dimensione {
+            return Err(ErroreUnita::Incompatibili {
+                campo: nome.to_string(),
+                del_campo: dimensione,
+                quantita: self.to_string(),
+                della_quantita,
+            });
+        }
+        Ok(self.valore * fattore)
+    }
+}
+
+impl fmt::Display for Quantita {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.valore, self.unita.unwrap_or(""))
+    }
+}
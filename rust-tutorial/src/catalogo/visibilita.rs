@@ -0,0 +1,274 @@
+// ============================================================================
+// MODULO: VISIBILITA
+// ============================================================================
+// Quali reperti e quali campi finiscono in un'esportazione: profilo
+// interno, pubblico e su misura.
+// ============================================================================
+
+//! Profili di visibilita delle esportazioni.
+
+use super::modelli::*;
+use chrono::NaiveDate;
+
+/// Profilo di visibilita: quali reperti e quali campi finiscono in
+/// un'esportazione. Le coordinate esatte e i valori economici si omettono
+/// nei dati pubblici per non agevolare gli scavi clandestini e i furti.
+#[derive(Debug, Clone)]
+pub struct ProfiloVisibilita {
+    pub nome: String,
+    /// Esporta solo le schede in stato `pubblicato`
+    pub solo_pubblicati: bool,
+    /// Esclude reperti e allegati sotto embargo
+    pub rispetta_embargo: bool,
+    pub coordinate: bool,
+    pub stime: bool,
+    /// Collocazione attuale e storico movimenti
+    pub collocazione: bool,
+    /// Riferimenti all'anagrafica delle persone
+    pub persone: bool,
+    /// Degrada la precisione delle coordinate esportate (se visibili)
+    pub offuscamento: Option<Offuscamento>,
+}
+
+/// Come ridurre la precisione delle coordinate nei dati pubblici
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Offuscamento {
+    /// Tronca a un numero fisso di decimali (2 decimali ~ 1 km)
+    Troncamento { decimali: u32 },
+    /// Sposta il punto di una distanza casuale entro il raggio indicato.
+    /// Lo spostamento dipende solo dall'ID del reperto e dal `seme`, cosi
+    /// esportazioni ripetute non permettono di risalire al punto esatto
+    /// mediando piu versioni.
+    Spostamento { raggio_m: f64, seme: u64 },
+}
+
+/// Metri per grado di latitudine (approssimazione sferica)
+const METRI_PER_GRADO: f64 = 111_320.0;
+
+/// Generatore pseudo-casuale splitmix64: deterministico e senza dipendenze
+pub fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Numero in [0, 1) derivato da un valore a 64 bit
+pub fn unitario(x: u64) -> f64 {
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl Offuscamento {
+    pub fn applica(&self, id_reperto: u32, c: &Coordinate) -> Coordinate {
+        match *self {
+            Offuscamento::Troncamento { decimali } => {
+                let fattore = 10f64.powi(decimali as i32);
+                Coordinate {
+                    latitudine: (c.latitudine * fattore).trunc() / fattore,
+                    longitudine: (c.longitudine * fattore).trunc() / fattore,
+                }
+            }
+            Offuscamento::Spostamento { raggio_m, seme } => {
+                let a = splitmix64(seme ^ id_reperto as u64);
+                let b = splitmix64(a);
+                // sqrt per una distribuzione uniforme sull'area del cerchio
+                let distanza = raggio_m * unitario(a).sqrt();
+                let angolo = unitario(b) * std::f64::consts::TAU;
+                let d_lat = distanza * angolo.cos() / METRI_PER_GRADO;
+                let d_lon = distanza * angolo.sin()
+                    / (METRI_PER_GRADO * c.latitudine.to_radians().cos());
+                Coordinate {
+                    latitudine: c.latitudine + d_lat,
+                    longitudine: c.longitudine + d_lon,
+                }
+            }
+        }
+    }
+}
+
+impl ProfiloVisibilita {
+    /// Esportazione interna: tutto visibile
+    pub fn interno() -> Self {
+        ProfiloVisibilita {
+            nome: "interno".to_string(),
+            solo_pubblicati: false,
+            rispetta_embargo: false,
+            coordinate: true,
+            stime: true,
+            collocazione: true,
+            persone: true,
+            offuscamento: None,
+        }
+    }
+
+    /// Esportazione pubblica: solo schede pubblicate, senza dati sensibili
+    pub fn pubblico() -> Self {
+        ProfiloVisibilita {
+            nome: "pubblico".to_string(),
+            solo_pubblicati: true,
+            rispetta_embargo: true,
+            coordinate: false,
+            stime: false,
+            collocazione: false,
+            persone: false,
+            offuscamento: None,
+        }
+    }
+
+    /// Profilo pubblico che mostra le coordinate in forma offuscata
+    pub fn pubblico_con_coordinate(offuscamento: Offuscamento) -> Self {
+        ProfiloVisibilita {
+            nome: "pubblico-geo".to_string(),
+            coordinate: true,
+            offuscamento: Some(offuscamento),
+            ..Self::pubblico()
+        }
+    }
+
+    /// Applica il profilo a un reperto: `None` se il reperto va escluso,
+    /// altrimenti una copia con i campi non visibili rimossi
+    pub fn applica(&self, reperto: &Reperto, oggi: NaiveDate) -> Option<Reperto> {
+        let in_embargo = |d: &Option<Diritti>| {
+            self.rispetta_embargo && d.as_ref().is_some_and(|d| d.in_embargo(oggi))
+        };
+        if self.solo_pubblicati && reperto.stato != StatoCatalogazione::Pubblicato {
+            return None;
+        }
+        if in_embargo(&reperto.diritti) {
+            return None;
+        }
+
+        let mut r = reperto.clone();
+        r.allegati.retain(|a| !in_embargo(&a.diritti));
+        if !self.coordinate {
+            r.coordinate = None;
+        } else if let (Some(offuscamento), Some(c)) = (&self.offuscamento, &r.coordinate) {
+            r.coordinate = Some(offuscamento.applica(r.id, c));
+        }
+        if !self.stime {
+            r.stime.clear();
+        }
+        if !self.collocazione {
+            r.collocazione = None;
+            r.movimenti.clear();
+        }
+        if !self.persone {
+            r.rinvenuto_da = None;
+            for allegato in &mut r.allegati {
+                allegato.autore = None;
+            }
+            for nota in &mut r.note {
+                nota.autore = None;
+            }
+        }
+        Some(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oggi() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    fn embargo_fino_al(anno: i32) -> Option<Diritti> {
+        Some(Diritti {
+            licenza: Licenza::CcBy,
+            titolare: "Soprintendenza".to_string(),
+            embargo_fino_al: NaiveDate::from_ymd_opt(anno, 1, 1),
+        })
+    }
+
+    fn allegato(nome: &str, diritti: Option<Diritti>) -> Allegato {
+        Allegato {
+            percorso: format!("foto/{}", nome),
+            tipo: TipoAllegato::Immagine,
+            impronta_sha256: String::new(),
+            dimensione_byte: 0,
+            miniature: None,
+            diritti,
+            autore: Some(2),
+        }
+    }
+
+    fn punto(c: &Coordinate) -> (f64, f64) {
+        (c.latitudine, c.longitudine)
+    }
+
+    fn ascia() -> Reperto {
+        let mut r = Reperto::nuovo("Ascia", Materiale::Bronzo, Periodo::BronzoMedio, Conservazione::Buono, "Frattesina");
+        r.id = 7;
+        r.stato = StatoCatalogazione::Pubblicato;
+        r.coordinate = Some(Coordinate { latitudine: 45.123_456, longitudine: 11.654_321 });
+        r.collocazione = Some("Vetrina 3".to_string());
+        r.rinvenuto_da = Some(1);
+        r.stime.push(Stima { valore: 1200.0, valuta: "EUR".to_string(), data: oggi(), perito: "M. Rossi".to_string() });
+        r.allegati = vec![allegato("libera.jpg", None), allegato("inedita.jpg", embargo_fino_al(2030))];
+        r
+    }
+
+    #[test]
+    fn l_interno_vede_tutto() {
+        let r = ProfiloVisibilita::interno().applica(&ascia(), oggi()).unwrap();
+        assert_eq!(r.coordinate.as_ref().map(punto), Some((45.123_456, 11.654_321)));
+        assert_eq!((r.stime.len(), r.allegati.len()), (1, 2));
+        assert_eq!((r.collocazione.as_deref(), r.rinvenuto_da), (Some("Vetrina 3"), Some(1)));
+    }
+
+    #[test]
+    fn il_pubblico_toglie_i_dati_sensibili() {
+        let r = ProfiloVisibilita::pubblico().applica(&ascia(), oggi()).unwrap();
+        assert!(r.coordinate.is_none() && r.stime.is_empty() && r.collocazione.is_none());
+        assert_eq!(r.rinvenuto_da, None);
+        // L'allegato sotto embargo sparisce, l'altro perde l'autore
+        assert_eq!(r.allegati.len(), 1);
+        assert_eq!((r.allegati[0].percorso.as_str(), r.allegati[0].autore), ("foto/libera.jpg", None));
+    }
+
+    #[test]
+    fn il_pubblico_esclude_schede_non_pubblicate_o_in_embargo() {
+        let pubblico = ProfiloVisibilita::pubblico();
+        let mut verificata = ascia();
+        verificata.stato = StatoCatalogazione::Verificato;
+        assert!(pubblico.applica(&verificata, oggi()).is_none());
+
+        let mut in_embargo = ascia();
+        in_embargo.diritti = embargo_fino_al(2026);
+        assert!(pubblico.applica(&in_embargo, oggi()).is_none());
+        // Scaduto l'embargo la scheda torna visibile
+        assert!(pubblico.applica(&in_embargo, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()).is_some());
+    }
+
+    #[test]
+    fn troncamento_delle_coordinate() {
+        let profilo = ProfiloVisibilita::pubblico_con_coordinate(Offuscamento::Troncamento { decimali: 2 });
+        let c = profilo.applica(&ascia(), oggi()).unwrap().coordinate.unwrap();
+        assert!((c.latitudine - 45.12).abs() < 1e-9);
+        assert!((c.longitudine - 11.65).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spostamento_entro_il_raggio_e_ripetibile() {
+        let originale = ascia().coordinate.unwrap();
+        let offuscamento = Offuscamento::Spostamento { raggio_m: 500.0, seme: 42 };
+        let spostato = offuscamento.applica(7, &originale);
+        assert_ne!(punto(&spostato), punto(&originale));
+        let d_lat = (spostato.latitudine - originale.latitudine) * METRI_PER_GRADO;
+        let d_lon = (spostato.longitudine - originale.longitudine) * METRI_PER_GRADO * originale.latitudine.to_radians().cos();
+        assert!(d_lat.hypot(d_lon) <= 500.0 + 1e-6);
+
+        // Stesso reperto e stesso seme: stesso punto a ogni esportazione
+        assert_eq!(punto(&offuscamento.applica(7, &originale)), punto(&spostato));
+        assert_ne!(punto(&offuscamento.applica(8, &originale)), punto(&spostato));
+    }
+
+    #[test]
+    fn unitario_resta_tra_zero_e_uno() {
+        for x in [0, 1, u64::MAX, splitmix64(7)] {
+            let u = unitario(splitmix64(x));
+            assert!((0.0..1.0).contains(&u));
+        }
+    }
+}
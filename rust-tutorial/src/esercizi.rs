@@ -0,0 +1,91 @@
+// ============================================================================
+// MODULO: ESERCIZI
+// ============================================================================
+// Gli esercizi dei capitoli, con due indizi ciascuno. Le soluzioni stanno in
+// `soluzioni`, compilato solo con `--features solutions`: chi studia vede
+// prima gli indizi e arriva alla soluzione solo se la chiede davvero.
+//
+//   cargo run -- esercizi                  elenco
+//   cargo run -- hint prima_parola 1       primo indizio (poi 2)
+//   cargo run --features solutions -- hint prima_parola 3   soluzione
+// ============================================================================
+
+//! Testi e indizi degli esercizi del tutorial.
+
+/// Un esercizio: testo, indizi graduati e capitolo di riferimento.
+#[derive(Debug)]
+pub struct Esercizio {
+    /// Nome breve, usato sulla riga di comando
+    pub id: &'static str,
+    pub capitolo: u32,
+    pub titolo: &'static str,
+    /// Cosa scrivere, firma della funzione compresa
+    pub testo: &'static str,
+    /// Dal piu vago al piu esplicito
+    pub indizi: [&'static str; 2],
+}
+
+/// Tutti gli esercizi, in ordine di capitolo.
+pub const ESERCIZI: [Esercizio; 4] = [
+    Esercizio {
+        id: "prima_parola",
+        capitolo: 2,
+        titolo: "La prima parola di un nome",
+        testo: "Scrivi `fn prima_parola(testo: &str) -> &str` che restituisce la prima \
+                parola (fino al primo spazio) senza allocare una nuova String. \
+                \"Ascia a margini rialzati\" -> \"Ascia\"; \"\" -> \"\".",
+        indizi: [
+            "Il risultato e una slice di `testo`: il lifetime del ritorno e quello dell'argomento.",
+            "`testo.split_whitespace().next()` restituisce Option<&str>; `unwrap_or(\"\")` copre il caso vuoto.",
+        ],
+    },
+    Esercizio {
+        id: "leggi_peso",
+        capitolo: 4,
+        titolo: "Leggere un peso scritto a mano",
+        testo: "Scrivi `fn leggi_peso(testo: &str) -> Result<f64, String>` che accetta \
+                \"327,6 g\", \"327.6\" o \" 41 g \" e rifiuta testo non numerico, \
+                zero e valori negativi con un messaggio d'errore.",
+        indizi: [
+            "Togli prima gli spazi e il suffisso \"g\" (`trim`, `strip_suffix`), poi sostituisci la virgola.",
+            "`parse::<f64>()` restituisce Result: con `map_err` trasformi l'errore in String e con `?` lo propaghi.",
+        ],
+    },
+    Esercizio {
+        id: "piu_pesante",
+        capitolo: 5,
+        titolo: "Il reperto piu pesante",
+        testo: "Scrivi `fn piu_pesante(reperti: &[Reperto]) -> Option<&Reperto>` che \
+                restituisce il reperto con il peso maggiore, None se la slice e vuota.",
+        indizi: [
+            "`max_by_key` non funziona: f64 non implementa Ord (per colpa di NaN).",
+            "Usa `iter().max_by(|a, b| a.peso_grammi.total_cmp(&b.peso_grammi))`.",
+        ],
+    },
+    Esercizio {
+        id: "peso_medio",
+        capitolo: 12,
+        titolo: "Peso medio di un periodo",
+        testo: "Scrivi `fn peso_medio(inv: &Inventario, periodo: Periodo) -> Option<f64>` \
+                con una sola catena di iteratori su `inv.iter()`; None se il periodo \
+                non ha reperti.",
+        indizi: [
+            "Somma e conteggio si possono accumulare insieme con `fold`, partendo da (0.0, 0).",
+            "`filter(|r| r.periodo == periodo).fold((0.0, 0), |(s, n), r| (s + r.peso_grammi, n + 1))`, \
+             poi `(n > 0).then(|| s / n as f64)`.",
+        ],
+    },
+];
+
+/// Cerca un esercizio per id.
+///
+/// ```
+/// use rust_tutorial::esercizi;
+///
+/// let es = esercizi::cerca("leggi_peso").unwrap();
+/// assert_eq!(es.capitolo, 4);
+/// assert!(esercizi::cerca("inesistente").is_none());
+/// ```
+pub fn cerca(id: &str) -> Option<&'static Esercizio> {
+    ESERCIZI.iter().find(|e| e.id == id)
+}
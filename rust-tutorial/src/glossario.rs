@@ -0,0 +1,346 @@
+// ============================================================================
+// MODULO: GLOSSARIO
+// ============================================================================
+// I termini del tutorial in italiano e in inglese, con una definizione
+// breve e i capitoli dove il concetto viene spiegato. La documentazione
+// di Rust, i messaggi del compilatore e le risposte online sono in
+// inglese: il glossario serve a passare da una lingua all'altra.
+//
+//   cargo run -- glossario                     tutti i termini
+//   cargo run -- glossario cerca prestito      ricerca (anche in inglese)
+// ============================================================================
+
+//! Glossario bilingue dei termini del tutorial.
+
+/// Un termine del glossario.
+#[derive(Debug)]
+pub struct Voce {
+    /// Il termine inglese, come lo usano documentazione e compilatore
+    pub termine: &'static str,
+    /// La traduzione usata nel tutorial
+    pub italiano: &'static str,
+    /// Definizione (italiano, inglese), da passare a `lingua::scegli`
+    pub definizione: (&'static str, &'static str),
+    /// Dove il concetto viene spiegato
+    pub capitoli: &'static [u32],
+    /// Altri termini del glossario (inglesi) collegati
+    pub vedi_anche: &'static [&'static str],
+}
+
+/// Tutti i termini, in ordine alfabetico (inglese).
+pub const GLOSSARIO: [Voce; 25] = [
+    Voce {
+        termine: "borrow",
+        italiano: "prestito",
+        definizione: (
+            "Accesso a un valore tramite un riferimento, senza diventarne owner: &T in lettura, &mut T in scrittura.",
+            "Access to a value through a reference without taking ownership: &T to read, &mut T to write.",
+        ),
+        capitoli: &[2],
+        vedi_anche: &["ownership", "reference", "lifetime"],
+    },
+    Voce {
+        termine: "borrow checker",
+        italiano: "verificatore dei prestiti",
+        definizione: (
+            "La parte del compilatore che controlla che ogni riferimento sia valido e che i prestiti mutabili siano esclusivi.",
+            "The part of the compiler that checks every reference is valid and mutable borrows are exclusive.",
+        ),
+        capitoli: &[2, 10],
+        vedi_anche: &["borrow", "lifetime"],
+    },
+    Voce {
+        termine: "closure",
+        italiano: "chiusura (closure)",
+        definizione: (
+            "Funzione anonima che cattura variabili dall'ambiente; implementa Fn, FnMut o FnOnce secondo come le usa.",
+            "Anonymous function that captures variables from its environment; implements Fn, FnMut or FnOnce depending on how it uses them.",
+        ),
+        capitoli: &[12],
+        vedi_anche: &["iterator", "move"],
+    },
+    Voce {
+        termine: "Copy",
+        italiano: "tipo Copy",
+        definizione: (
+            "Trait dei tipi copiati bit a bit all'assegnamento (interi, f64, bool, char, &T): l'originale resta valido.",
+            "Trait of types copied bit by bit on assignment (integers, f64, bool, char, &T): the original stays valid.",
+        ),
+        capitoli: &[2],
+        vedi_anche: &["move", "ownership"],
+    },
+    Voce {
+        termine: "crate",
+        italiano: "crate (pacchetto)",
+        definizione: (
+            "Unita di compilazione di Rust: una libreria o un eseguibile. Le dipendenze in Cargo.toml sono crate.",
+            "Rust's unit of compilation: a library or an executable. Dependencies in Cargo.toml are crates.",
+        ),
+        capitoli: &[7],
+        vedi_anche: &["module"],
+    },
+    Voce {
+        termine: "derive",
+        italiano: "derivazione",
+        definizione: (
+            "#[derive(...)] fa generare al compilatore l'implementazione di un trait (Debug, Clone, Serialize...).",
+            "#[derive(...)] makes the compiler generate a trait implementation (Debug, Clone, Serialize...).",
+        ),
+        capitoli: &[3, 17],
+        vedi_anche: &["trait", "macro"],
+    },
+    Voce {
+        termine: "Drop",
+        italiano: "rilascio (Drop)",
+        definizione: (
+            "Quello che succede quando l'owner esce dallo scope: la memoria viene liberata e viene chiamato Drop::drop.",
+            "What happens when the owner goes out of scope: memory is freed and Drop::drop is called.",
+        ),
+        capitoli: &[2, 11],
+        vedi_anche: &["ownership", "smart pointer"],
+    },
+    Voce {
+        termine: "enum",
+        italiano: "enumerazione",
+        definizione: (
+            "Tipo che vale una tra piu varianti, ognuna con i suoi dati; si legge con match.",
+            "Type that is one of several variants, each with its own data; read with match.",
+        ),
+        capitoli: &[3],
+        vedi_anche: &["pattern matching", "Option", "Result"],
+    },
+    Voce {
+        termine: "generic",
+        italiano: "generico",
+        definizione: (
+            "Codice scritto una volta per molti tipi (fn f<T: Trait>); il compilatore genera una versione per ogni tipo usato.",
+            "Code written once for many types (fn f<T: Trait>); the compiler generates one version per type used.",
+        ),
+        capitoli: &[6],
+        vedi_anche: &["trait", "trait object"],
+    },
+    Voce {
+        termine: "interior mutability",
+        italiano: "mutabilita interna",
+        definizione: (
+            "Modificare un valore attraverso un riferimento condiviso, con controlli a runtime: Cell, RefCell, Mutex.",
+            "Mutating a value through a shared reference, checked at runtime: Cell, RefCell, Mutex.",
+        ),
+        capitoli: &[8, 11],
+        vedi_anche: &["smart pointer", "borrow"],
+    },
+    Voce {
+        termine: "iterator",
+        italiano: "iteratore",
+        definizione: (
+            "Valore che produce elementi uno alla volta con next(); gli adattatori (map, filter) sono pigri.",
+            "Value that yields items one at a time through next(); adapters (map, filter) are lazy.",
+        ),
+        capitoli: &[5, 12],
+        vedi_anche: &["closure", "trait"],
+    },
+    Voce {
+        termine: "lifetime",
+        italiano: "durata (lifetime)",
+        definizione: (
+            "Il tratto di codice in cui un riferimento e valido; si scrive 'a quando il compilatore non lo deduce da solo.",
+            "The stretch of code where a reference is valid; written 'a when the compiler cannot infer it.",
+        ),
+        capitoli: &[10],
+        vedi_anche: &["borrow", "reference", "borrow checker"],
+    },
+    Voce {
+        termine: "macro",
+        italiano: "macro",
+        definizione: (
+            "Codice che genera codice a compilazione: println!, vec!, le macro_rules! e le derive.",
+            "Code that generates code at compile time: println!, vec!, macro_rules! and derives.",
+        ),
+        capitoli: &[1],
+        vedi_anche: &["derive"],
+    },
+    Voce {
+        termine: "module",
+        italiano: "modulo",
+        definizione: (
+            "Spazio di nomi dentro un crate (mod); decide cosa e pubblico (pub) e cosa resta privato.",
+            "Namespace inside a crate (mod); decides what is public (pub) and what stays private.",
+        ),
+        capitoli: &[7],
+        vedi_anche: &["crate"],
+    },
+    Voce {
+        termine: "move",
+        italiano: "spostamento (move)",
+        definizione: (
+            "Passaggio dell'ownership a un'altra variabile o funzione: il nome di prima non si puo piu usare.",
+            "Transfer of ownership to another variable or function: the old name can no longer be used.",
+        ),
+        capitoli: &[2],
+        vedi_anche: &["ownership", "Copy", "closure"],
+    },
+    Voce {
+        termine: "newtype",
+        italiano: "newtype (tipo involucro)",
+        definizione: (
+            "Struct con un solo campo (struct Grammi(f64)) che distingue valori con lo stesso tipo di base.",
+            "Single-field struct (struct Grammi(f64)) that tells apart values sharing the same base type.",
+        ),
+        capitoli: &[3, 18],
+        vedi_anche: &["typestate"],
+    },
+    Voce {
+        termine: "Option",
+        italiano: "valore opzionale",
+        definizione: (
+            "Some(valore) oppure None: il modo di Rust di dire \"potrebbe mancare\", al posto di null.",
+            "Some(value) or None: Rust's way of saying \"might be missing\", instead of null.",
+        ),
+        capitoli: &[4, 5],
+        vedi_anche: &["Result", "enum"],
+    },
+    Voce {
+        termine: "ownership",
+        italiano: "possesso (ownership)",
+        definizione: (
+            "Ogni valore ha un solo owner; quando l'owner esce dallo scope il valore viene liberato.",
+            "Every value has a single owner; when the owner goes out of scope the value is dropped.",
+        ),
+        capitoli: &[2],
+        vedi_anche: &["borrow", "move", "Drop"],
+    },
+    Voce {
+        termine: "pattern matching",
+        italiano: "confronto di pattern",
+        definizione: (
+            "match, if let e let-else scompongono un valore secondo la sua forma; match deve coprire tutti i casi.",
+            "match, if let and let-else take a value apart by its shape; match must cover every case.",
+        ),
+        capitoli: &[3],
+        vedi_anche: &["enum"],
+    },
+    Voce {
+        termine: "reference",
+        italiano: "riferimento",
+        definizione: (
+            "Puntatore sempre valido a un valore di qualcun altro (&T o &mut T); crearlo e un prestito.",
+            "Always-valid pointer to a value owned by someone else (&T or &mut T); creating one is a borrow.",
+        ),
+        capitoli: &[2],
+        vedi_anche: &["borrow", "lifetime"],
+    },
+    Voce {
+        termine: "Result",
+        italiano: "risultato",
+        definizione: (
+            "Ok(valore) oppure Err(errore): gli errori recuperabili; l'operatore ? li propaga al chiamante.",
+            "Ok(value) or Err(error): recoverable errors; the ? operator propagates them to the caller.",
+        ),
+        capitoli: &[4, 16],
+        vedi_anche: &["Option", "enum"],
+    },
+    Voce {
+        termine: "smart pointer",
+        italiano: "puntatore intelligente",
+        definizione: (
+            "Struct che possiede un valore e si comporta come un riferimento: Box, Rc, Arc, RefCell.",
+            "Struct that owns a value and behaves like a reference: Box, Rc, Arc, RefCell.",
+        ),
+        capitoli: &[11],
+        vedi_anche: &["interior mutability", "Drop"],
+    },
+    Voce {
+        termine: "trait",
+        italiano: "tratto (trait)",
+        definizione: (
+            "Insieme di metodi che un tipo promette di avere, come un'interfaccia; si usa come vincolo nei generici.",
+            "Set of methods a type promises to provide, like an interface; used as a bound on generics.",
+        ),
+        capitoli: &[6],
+        vedi_anche: &["generic", "trait object", "derive"],
+    },
+    Voce {
+        termine: "trait object",
+        italiano: "oggetto trait",
+        definizione: (
+            "dyn Trait dietro un puntatore (&dyn, Box<dyn>): tipi diversi nella stessa collezione, metodo scelto a runtime.",
+            "dyn Trait behind a pointer (&dyn, Box<dyn>): different types in one collection, method chosen at runtime.",
+        ),
+        capitoli: &[6, 18],
+        vedi_anche: &["trait", "generic", "smart pointer"],
+    },
+    Voce {
+        termine: "typestate",
+        italiano: "stato nel tipo (typestate)",
+        definizione: (
+            "Ogni stato di un oggetto e un tipo diverso: i metodi sbagliati per quello stato non compilano nemmeno.",
+            "Each state of an object is a different type: methods that are wrong for that state do not even compile.",
+        ),
+        capitoli: &[18],
+        vedi_anche: &["newtype", "generic"],
+    },
+];
+
+/// Cerca per termine inglese o italiano, senza badare alle maiuscole. Prima
+/// le voci il cui nome coincide, poi quelle che lo contengono, infine
+/// quelle che lo citano nella definizione.
+///
+/// ```
+/// use rust_tutorial::glossario;
+///
+/// let trovate = glossario::cerca("prestito");
+/// assert_eq!(trovate[0].termine, "borrow");
+/// assert!(glossario::cerca("monade").is_empty());
+/// ```
+pub fn cerca(testo: &str) -> Vec<&'static Voce> {
+    let testo = testo.trim().to_lowercase();
+    if testo.is_empty() {
+        return Vec::new();
+    }
+    let nomi = |v: &Voce| [v.termine.to_lowercase(), v.italiano.to_lowercase()];
+    let punteggio = |v: &Voce| {
+        if nomi(v).contains(&testo) {
+            Some(0)
+        } else if nomi(v).iter().any(|n| n.contains(&testo)) {
+            Some(1)
+        } else if [v.definizione.0, v.definizione.1].iter().any(|d| d.to_lowercase().contains(&testo)) {
+            Some(2)
+        } else {
+            None
+        }
+    };
+    let mut trovate: Vec<(u8, &'static Voce)> =
+        GLOSSARIO.iter().filter_map(|v| punteggio(v).map(|p| (p, v))).collect();
+    // sort_by_key e stabile: a parita di punteggio resta l'ordine alfabetico
+    trovate.sort_by_key(|(p, _)| *p);
+    trovate.into_iter().map(|(_, v)| v).collect()
+}
+
+/// La voce con esattamente questo termine inglese.
+pub fn voce(termine: &str) -> Option<&'static Voce> {
+    GLOSSARIO.iter().find(|v| v.termine.eq_ignore_ascii_case(termine))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn i_rimandi_portano_a_voci_esistenti() {
+        let mancanti: Vec<&str> = GLOSSARIO
+            .iter()
+            .flat_map(|v| v.vedi_anche.iter().copied())
+            .filter(|t| voce(t).is_none())
+            .collect();
+        assert!(mancanti.is_empty(), "{:?}", mancanti);
+    }
+
+    #[test]
+    fn prima_i_nomi_poi_le_definizioni() {
+        let termini: Vec<&str> = cerca("trait").iter().map(|v| v.termine).collect();
+        assert_eq!(&termini[..2], ["trait", "trait object"]);
+        // "derive" cita i trait solo nella definizione: viene dopo
+        assert!(termini.iter().position(|t| *t == "derive") > Some(1));
+        assert_eq!(cerca("MUTABILITA")[0].termine, "interior mutability");
+    }
+}
@@ -0,0 +1,347 @@
+// ============================================================================
+// MODULO: INVENTARIO
+// ============================================================================
+// Versione ridotta dell'inventario del capitolo 9: abbastanza piccola da
+// leggerla in un colpo d'occhio, abbastanza ricca da meritare dei test.
+//
+// Nell'inventario entrano solo schede validate (vedi inventario/scheda.rs):
+// `aggiungi` e una scorciatoia che valida e cataloga in un passo.
+// ============================================================================
+
+//! Inventario minimo dei reperti, con errori tipizzati.
+
+use std::collections::BTreeMap;
+use std::num::ParseFloatError;
+
+use thiserror::Error;
+
+use crate::schede::SchedaStampabile;
+
+pub mod scheda;
+
+pub use scheda::{Bozza, Catalogato, Scheda, SchedaRifiutata, Validato};
+
+// Reperto, Periodo e le regole di validazione vengono da bronzeaxe-core,
+// che non usa std: qui si aggiungono l'inventario e la scheda stampabile.
+pub use bronzeaxe_core::{ErroreValidazione, Periodo, Reperto};
+
+/// Scritta a mano: `#[derive(Scheda)]` va messo sulla definizione della
+/// struct, che sta in bronzeaxe-core e non conosce le schede. E il codice
+/// che il derive genererebbe con gli attributi nel commento.
+impl SchedaStampabile for Reperto {
+    // #[scheda(titolo = "Reperto")]
+    const TITOLO: &'static str = "Reperto";
+    // id: #[scheda(etichetta = "Id")], periodo: #[scheda(debug)],
+    // peso_grammi: #[scheda(etichetta = "Peso", unita = "g", precisione = 1)]
+    const ETICHETTE: &'static [&'static str] = &["Id", "Nome", "Periodo", "Peso"];
+
+    fn valori(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.nome.clone(),
+            format!("{:?}", self.periodo),
+            format!("{:.1} g", self.peso_grammi),
+        ]
+    }
+}
+
+/// Errori delle operazioni sull'inventario.
+///
+/// `thiserror` genera `Display` dai messaggi `#[error]` e `Error::source`
+/// dai campi marcati `#[source]` (capitolo 16).
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ErroreInventario {
+    #[error("Reperto #{0} non trovato")]
+    RepertoNonTrovato(u32),
+    #[error("Il nome del reperto non puo essere vuoto")]
+    NomeVuoto,
+    #[error("Peso non valido: {0} g")]
+    PesoNonValido(f64),
+    #[error("Riga {riga}: {motivo}")]
+    RigaNonValida { riga: usize, motivo: String },
+    #[error("Riga {riga}: peso illeggibile")]
+    PesoIllegibile {
+        riga: usize,
+        #[source]
+        causa: ParseFloatError,
+    },
+    /// Un reperto valido in se ma rifiutato dall'inventario
+    #[error("Riga {riga}: reperto rifiutato")]
+    RepertoRifiutato {
+        riga: usize,
+        #[source]
+        causa: Box<ErroreInventario>,
+    },
+}
+
+impl From<ErroreValidazione> for ErroreInventario {
+    fn from(errore: ErroreValidazione) -> Self {
+        match errore {
+            ErroreValidazione::NomeVuoto => ErroreInventario::NomeVuoto,
+            ErroreValidazione::PesoNonValido(peso) => ErroreInventario::PesoNonValido(peso),
+        }
+    }
+}
+
+/// Inventario dei reperti, ordinato per id.
+#[derive(Debug, Default)]
+pub struct Inventario {
+    reperti: BTreeMap<u32, Reperto>,
+    prossimo_id: u32,
+}
+
+impl Inventario {
+    /// Crea un inventario vuoto.
+    ///
+    /// ```
+    /// let inv = rust_tutorial::inventario::Inventario::nuovo();
+    /// assert!(inv.is_empty());
+    /// ```
+    pub fn nuovo() -> Self {
+        Inventario { reperti: BTreeMap::new(), prossimo_id: 1 }
+    }
+
+    /// Aggiunge un reperto e restituisce l'id assegnato: equivale a
+    /// `inv.cataloga(Scheda::from(reperto).valida()?)`.
+    ///
+    /// Il nome non puo essere vuoto e il peso deve essere positivo:
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// assert_eq!(inv.aggiungi(Reperto::nuovo("Pugnale", Periodo::Antico, 198.3)), Ok(1));
+    /// assert_eq!(inv.aggiungi(Reperto::nuovo("  ", Periodo::Antico, 10.0)), Err(ErroreInventario::NomeVuoto));
+    /// ```
+    pub fn aggiungi(&mut self, reperto: Reperto) -> Result<u32, ErroreInventario> {
+        let validata = Scheda::from(reperto).valida().map_err(|rifiutata| rifiutata.errore)?;
+        Ok(self.cataloga(validata).id())
+    }
+
+    /// Cerca un reperto per id.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{ErroreInventario, Inventario};
+    ///
+    /// let inv = Inventario::nuovo();
+    /// assert_eq!(inv.cerca_per_id(7), Err(ErroreInventario::RepertoNonTrovato(7)));
+    /// ```
+    pub fn cerca_per_id(&self, id: u32) -> Result<&Reperto, ErroreInventario> {
+        self.reperti.get(&id).ok_or(ErroreInventario::RepertoNonTrovato(id))
+    }
+
+    /// Tutti i reperti di un periodo, in ordine di id.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{Inventario, Periodo, Reperto};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// inv.aggiungi(Reperto::nuovo("Ascia", Periodo::Medio, 327.6)).unwrap();
+    /// inv.aggiungi(Reperto::nuovo("Spada", Periodo::Finale, 779.6)).unwrap();
+    /// assert_eq!(inv.cerca_per_periodo(Periodo::Finale).len(), 1);
+    /// ```
+    pub fn cerca_per_periodo(&self, periodo: Periodo) -> Vec<&Reperto> {
+        self.reperti.values().filter(|r| r.periodo == periodo).collect()
+    }
+
+    /// Rimuove un reperto e lo restituisce. Gli id non vengono riusati.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{Inventario, Periodo, Reperto};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// let id = inv.aggiungi(Reperto::nuovo("Fibula", Periodo::Recente, 26.9)).unwrap();
+    /// assert_eq!(inv.rimuovi(id).unwrap().nome, "Fibula");
+    /// assert!(inv.rimuovi(id).is_err());
+    /// ```
+    pub fn rimuovi(&mut self, id: u32) -> Result<Reperto, ErroreInventario> {
+        self.reperti.remove(&id).ok_or(ErroreInventario::RepertoNonTrovato(id))
+    }
+
+    /// Peso complessivo in grammi.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{Inventario, Periodo, Reperto};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// inv.aggiungi(Reperto::nuovo("A", Periodo::Antico, 100.0)).unwrap();
+    /// inv.aggiungi(Reperto::nuovo("B", Periodo::Antico, 50.5)).unwrap();
+    /// assert_eq!(inv.peso_totale(), 150.5);
+    /// ```
+    pub fn peso_totale(&self) -> f64 {
+        self.reperti.values().map(|r| r.peso_grammi).sum()
+    }
+
+    /// Numero di reperti.
+    ///
+    /// ```
+    /// let inv = rust_tutorial::inventario::Inventario::nuovo();
+    /// assert_eq!(inv.len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.reperti.len()
+    }
+
+    /// `true` se l'inventario non contiene reperti.
+    pub fn is_empty(&self) -> bool {
+        self.reperti.is_empty()
+    }
+
+    /// Iteratore sui reperti in ordine di id.
+    pub fn iter(&self) -> impl Iterator<Item = &Reperto> {
+        self.reperti.values()
+    }
+
+    /// Legge un inventario da righe `nome;periodo;peso`. Le righe vuote e
+    /// quelle che iniziano con `#` sono ignorate; le righe sono numerate da 1.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{ErroreInventario, Inventario};
+    ///
+    /// let inv = Inventario::da_csv("# nome;periodo;peso\nAscia;medio;327.6\n").unwrap();
+    /// assert_eq!(inv.len(), 1);
+    ///
+    /// let errore = Inventario::da_csv("Ascia;medio;tanto").unwrap_err();
+    /// assert!(matches!(errore, ErroreInventario::PesoIllegibile { riga: 1, .. }));
+    /// ```
+    pub fn da_csv(testo: &str) -> Result<Inventario, ErroreInventario> {
+        let mut inv = Inventario::nuovo();
+        for (i, linea) in testo.lines().enumerate() {
+            let riga = i + 1;
+            let linea = linea.trim();
+            if linea.is_empty() || linea.starts_with('#') {
+                continue;
+            }
+            let campi: Vec<&str> = linea.split(';').map(str::trim).collect();
+            let [nome, periodo, peso] = campi[..] else {
+                return Err(ErroreInventario::RigaNonValida {
+                    riga,
+                    motivo: format!("attesi 3 campi, trovati {}", campi.len()),
+                });
+            };
+            let periodo = Periodo::da_nome(periodo).ok_or_else(|| ErroreInventario::RigaNonValida {
+                riga,
+                motivo: format!("periodo '{}' sconosciuto", periodo),
+            })?;
+            let peso = peso.parse().map_err(|causa| ErroreInventario::PesoIllegibile { riga, causa })?;
+            inv.aggiungi(Reperto::nuovo(nome, periodo, peso))
+                .map_err(|e| ErroreInventario::RepertoRifiutato { riga, causa: Box::new(e) })?;
+        }
+        Ok(inv)
+    }
+}
+
+// ============================================================================
+// TEST UNITARI
+// ============================================================================
+// Stanno nello stesso file del codice e possono vedere anche i campi
+// privati (qui `prossimo_id`). `#[cfg(test)]` li compila solo con `cargo test`.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventario_di_prova() -> Inventario {
+        let mut inv = Inventario::nuovo();
+        inv.aggiungi(Reperto::nuovo("Pugnale triangolare", Periodo::Antico, 198.3)).unwrap();
+        inv.aggiungi(Reperto::nuovo("Ascia a margini rialzati", Periodo::Medio, 327.6)).unwrap();
+        inv.aggiungi(Reperto::nuovo("Fibula ad arco", Periodo::Recente, 26.9)).unwrap();
+        inv
+    }
+
+    #[test]
+    fn gli_id_partono_da_uno_e_crescono() {
+        let inv = inventario_di_prova();
+        let id: Vec<u32> = inv.iter().map(|r| r.id).collect();
+        assert_eq!(id, vec![1, 2, 3]);
+        assert_eq!(inv.prossimo_id, 4);
+    }
+
+    #[test]
+    fn inventario_default_assegna_comunque_id_uno() {
+        // Default mette prossimo_id a 0: aggiungi non deve mai usare lo 0
+        let mut inv = Inventario::default();
+        assert_eq!(inv.aggiungi(Reperto::nuovo("Spillone", Periodo::Recente, 12.2)), Ok(1));
+    }
+
+    #[test]
+    fn gli_id_rimossi_non_vengono_riusati() {
+        let mut inv = inventario_di_prova();
+        inv.rimuovi(3).unwrap();
+        let id = inv.aggiungi(Reperto::nuovo("Spada", Periodo::Finale, 779.6)).unwrap();
+        assert_eq!(id, 4);
+    }
+
+    #[test]
+    fn nome_vuoto_rifiutato() {
+        let mut inv = Inventario::nuovo();
+        let esito = inv.aggiungi(Reperto::nuovo("   ", Periodo::Medio, 10.0));
+        assert_eq!(esito, Err(ErroreInventario::NomeVuoto));
+        assert!(inv.is_empty());
+    }
+
+    #[test]
+    fn peso_zero_negativo_o_nan_rifiutati() {
+        let mut inv = Inventario::nuovo();
+        for peso in [0.0, -5.0, f64::NAN, f64::INFINITY] {
+            let esito = inv.aggiungi(Reperto::nuovo("Ascia", Periodo::Medio, peso));
+            assert!(matches!(esito, Err(ErroreInventario::PesoNonValido(_))), "peso {} accettato", peso);
+        }
+    }
+
+    #[test]
+    fn cerca_per_periodo_filtra() {
+        let inv = inventario_di_prova();
+        let medi = inv.cerca_per_periodo(Periodo::Medio);
+        assert_eq!(medi.len(), 1);
+        assert_eq!(medi[0].nome, "Ascia a margini rialzati");
+        assert!(inv.cerca_per_periodo(Periodo::Finale).is_empty());
+    }
+
+    #[test]
+    fn peso_totale_somma_i_reperti() {
+        let inv = inventario_di_prova();
+        // Con i float si confronta entro una tolleranza, mai con ==
+        assert!((inv.peso_totale() - 552.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn messaggi_di_errore_leggibili() {
+        assert_eq!(ErroreInventario::RepertoNonTrovato(9).to_string(), "Reperto #9 non trovato");
+    }
+
+    #[test]
+    fn da_csv_indica_la_riga_e_conserva_la_causa() {
+        use std::error::Error;
+
+        let testo = "Ascia;medio;327.6\n\nSpada;finale;779.6\nFibula;recente;-1";
+        let errore = Inventario::da_csv(testo).unwrap_err();
+        assert_eq!(errore.to_string(), "Riga 4: reperto rifiutato");
+        let causa = errore.source().expect("la causa deve essere esposta");
+        assert_eq!(causa.to_string(), "Peso non valido: -1 g");
+
+        let errore = Inventario::da_csv("Ascia;medio").unwrap_err();
+        assert_eq!(errore.to_string(), "Riga 1: attesi 3 campi, trovati 2");
+    }
+
+    #[test]
+    fn la_bozza_rifiutata_si_corregge_e_si_cataloga() {
+        let mut inv = inventario_di_prova();
+        let rifiutata = Scheda::bozza("  ", Periodo::Finale, 779.6).valida().unwrap_err();
+        assert_eq!(rifiutata.errore, ErroreInventario::NomeVuoto);
+        assert_eq!(inv.len(), 3);
+
+        let mut bozza = rifiutata.bozza;
+        bozza.reperto_mut().nome = "Spada".to_string();
+        let catalogata = inv.cataloga(bozza.valida().unwrap());
+        assert_eq!(catalogata.id(), 4);
+        assert_eq!(inv.cerca_per_id(4).unwrap().nome, "Spada");
+    }
+
+    #[test]
+    #[should_panic(expected = "RepertoNonTrovato")]
+    fn unwrap_su_id_mancante_va_in_panico() {
+        let inv = Inventario::nuovo();
+        inv.cerca_per_id(42).unwrap();
+    }
+}
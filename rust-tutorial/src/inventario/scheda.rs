@@ -0,0 +1,159 @@
+// ============================================================================
+// SCHEDA CON TYPESTATE: BOZZA -> VALIDATO -> CATALOGATO
+// ============================================================================
+// Lo stato della scheda fa parte del suo tipo. L'inventario accetta solo
+// `Scheda<Validato>`, e l'unico modo di ottenerne una e `valida()`: una
+// bozza non controllata non arriva nell'inventario perche il programma
+// non compila, non perche un `if` la ferma a runtime.
+//
+//   Scheda::bozza(..)      Scheda<Bozza>       si modifica liberamente
+//     .valida()?           Scheda<Validato>    non si modifica piu
+//   inv.cataloga(..)       Scheda<Catalogato>  ha un id assegnato
+//
+// Gli stati sono enum senza varianti: esistono solo come tipi, non
+// occupano memoria e non si possono costruire. Lo stesso schema, con
+// i dati dentro gli stati, e nel capitolo 18 (18.3).
+// ============================================================================
+
+//! Scheda di un reperto con lo stato nel tipo: bozza, validata, catalogata.
+
+use std::marker::PhantomData;
+
+use super::{ErroreInventario, Inventario, Periodo, Reperto};
+
+/// Stato: appena compilata, ancora da controllare
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bozza {}
+
+/// Stato: controllata, pronta per l'inventario
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validato {}
+
+/// Stato: registrata nell'inventario, con il suo id
+#[derive(Debug, Clone, PartialEq)]
+pub enum Catalogato {}
+
+/// Scheda di un reperto nello stato `S`.
+///
+/// I campi sono privati: fuori da questo modulo non si puo scrivere
+/// `Scheda::<Validato> { .. }` e saltare la validazione.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scheda<S> {
+    reperto: Reperto,
+    stato: PhantomData<S>,
+}
+
+/// Una bozza che non ha passato la validazione: torna indietro intera,
+/// insieme al motivo, e si puo correggere.
+#[derive(Debug)]
+pub struct SchedaRifiutata {
+    pub bozza: Scheda<Bozza>,
+    pub errore: ErroreInventario,
+}
+
+// Disponibile in tutti gli stati
+impl<S> Scheda<S> {
+    pub fn reperto(&self) -> &Reperto {
+        &self.reperto
+    }
+
+    /// Lo stesso reperto in un altro stato: privata, e l'unico passaggio
+    fn in_stato(reperto: Reperto) -> Self {
+        Scheda { reperto, stato: PhantomData }
+    }
+}
+
+impl Scheda<Bozza> {
+    pub fn bozza(nome: &str, periodo: Periodo, peso_grammi: f64) -> Self {
+        Scheda::in_stato(Reperto::nuovo(nome, periodo, peso_grammi))
+    }
+
+    /// Solo la bozza si modifica: una scheda validata resta valida.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{Periodo, Scheda};
+    ///
+    /// let mut bozza = Scheda::bozza("ascia", Periodo::Medio, 327.6);
+    /// bozza.reperto_mut().nome = "Ascia a margini rialzati".to_string();
+    /// assert!(bozza.valida().is_ok());
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use rust_tutorial::inventario::{Periodo, Scheda};
+    ///
+    /// let mut validata = Scheda::bozza("Ascia", Periodo::Medio, 327.6).valida().unwrap();
+    /// validata.reperto_mut().peso_grammi = -1.0; // nessun metodo reperto_mut su Scheda<Validato>
+    /// ```
+    pub fn reperto_mut(&mut self) -> &mut Reperto {
+        &mut self.reperto
+    }
+
+    /// Controlla nome e peso. Consuma la bozza: se e valida torna come
+    /// `Scheda<Validato>`, altrimenti torna indietro con il motivo.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{ErroreInventario, Periodo, Scheda};
+    ///
+    /// let rifiutata = Scheda::bozza("Fibula", Periodo::Recente, 0.0).valida().unwrap_err();
+    /// assert_eq!(rifiutata.errore, ErroreInventario::PesoNonValido(0.0));
+    ///
+    /// let mut bozza = rifiutata.bozza;
+    /// bozza.reperto_mut().peso_grammi = 26.9;
+    /// assert!(bozza.valida().is_ok());
+    /// ```
+    pub fn valida(self) -> Result<Scheda<Validato>, SchedaRifiutata> {
+        // Le regole stanno in bronzeaxe-core, insieme a Reperto
+        match self.reperto.valida() {
+            Ok(()) => Ok(Scheda::in_stato(self.reperto)),
+            Err(errore) => Err(SchedaRifiutata { bozza: self, errore: errore.into() }),
+        }
+    }
+}
+
+impl From<Reperto> for Scheda<Bozza> {
+    /// Un reperto qualsiasi e solo una bozza: l'id viene azzerato
+    fn from(mut reperto: Reperto) -> Self {
+        reperto.id = 0;
+        Scheda::in_stato(reperto)
+    }
+}
+
+impl Scheda<Catalogato> {
+    /// L'id assegnato dall'inventario, mai 0
+    pub fn id(&self) -> u32 {
+        self.reperto.id
+    }
+}
+
+impl Inventario {
+    /// Registra una scheda validata. Non puo fallire: i controlli sono gia
+    /// stati fatti da `valida()`, e il tipo lo garantisce. Restituisce la
+    /// scheda come e stata registrata, con l'id.
+    ///
+    /// ```
+    /// use rust_tutorial::inventario::{Inventario, Periodo, Scheda};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// let validata = Scheda::bozza("Pugnale", Periodo::Antico, 198.3).valida().unwrap();
+    /// let catalogata = inv.cataloga(validata);
+    /// assert_eq!(catalogata.id(), 1);
+    /// assert_eq!(inv.cerca_per_id(1).unwrap(), catalogata.reperto());
+    /// ```
+    ///
+    /// Una bozza non si puo catalogare:
+    ///
+    /// ```compile_fail
+    /// use rust_tutorial::inventario::{Inventario, Periodo, Scheda};
+    ///
+    /// let mut inv = Inventario::nuovo();
+    /// inv.cataloga(Scheda::bozza("Pugnale", Periodo::Antico, 198.3)); // attesa Scheda<Validato>
+    /// ```
+    pub fn cataloga(&mut self, scheda: Scheda<Validato>) -> Scheda<Catalogato> {
+        let mut reperto = scheda.reperto;
+        let id = self.prossimo_id.max(1);
+        self.prossimo_id = id + 1;
+        reperto.id = id;
+        self.reperti.insert(id, reperto.clone());
+        Scheda::in_stato(reperto)
+    }
+}
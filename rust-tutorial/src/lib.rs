@@ -0,0 +1,42 @@
+// ============================================================================
+// LIBRERIA DEL TUTORIAL
+// ============================================================================
+// Il codice dei capitoli vive in `examples/` ed e pensato per essere letto.
+// Qui teniamo le parti che vogliamo anche *verificare*: ogni elemento
+// pubblico ha un esempio nella documentazione (doctest), i moduli hanno
+// test unitari interni e `tests/` contiene i test di integrazione.
+//
+// Esegui i test con: cargo test
+// ============================================================================
+
+//! Libreria di supporto al tutorial Rust.
+//!
+//! Contiene una versione ridotta dell'inventario dei reperti del progetto
+//! finale, usata dal capitolo sui test (`cargo run --example cap_test`),
+//! e gli esercizi dei capitoli. Reperto e Periodo vengono dal crate
+//! `bronzeaxe-core`, che compila anche senza std. Le soluzioni si compilano solo con
+//! `--features solutions`.
+//!
+//! ```
+//! use rust_tutorial::inventario::{Inventario, Periodo, Reperto};
+//!
+//! let mut inv = Inventario::nuovo();
+//! let id = inv.aggiungi(Reperto::nuovo("Ascia a margini rialzati", Periodo::Medio, 327.6)).unwrap();
+//! assert_eq!(inv.cerca_per_id(id).unwrap().nome, "Ascia a margini rialzati");
+//! ```
+
+pub mod cabi;
+pub mod catalogo;
+pub mod esercizi;
+pub mod glossario;
+pub mod inventario;
+pub mod lingua;
+pub mod promemoria;
+pub mod scaffold;
+pub mod schede;
+pub mod schermo;
+pub mod snippet;
+pub mod tui_text;
+
+#[cfg(feature = "solutions")]
+pub mod soluzioni;
@@ -0,0 +1,158 @@
+// ============================================================================
+// MODULO: LINGUA
+// ============================================================================
+// I capitoli stampano in italiano; con TUTORIAL_LINGUA=en stampano in
+// inglese. Il codice resta uno solo: al posto di `println!` si usa
+// `tprintln!`, che sceglie il testo a runtime tra l'originale e la sua
+// traduzione.
+//
+// Le traduzioni stanno in un catalogo per file (cartella `lingua/`): una
+// macro `inglese!` con un ramo per ogni testo. Il catalogo e risolto a
+// compilazione, quindi anche la traduzione passa dai controlli di
+// `format!`: un `{}` in piu o in meno e un errore di compilazione, non
+// un testo sbagliato a runtime. Un testo senza traduzione resta in italiano.
+//
+//   // lingua/cap01.rs
+//   macro_rules! inglese {
+//       ("Nome: {}") => { "Name: {}" };
+//       ($testo:tt) => { $testo };      // tutto il resto: invariato
+//   }
+//
+//   // examples/cap01_basi.rs
+//   include!("../lingua/cap01.rs");
+//   tprintln!("Nome: {}", nome);
+//
+// Limite: il testo deve essere un letterale e gli argomenti vanno passati
+// esplicitamente (`"{}", nome`, non `"{nome}"`), perche `format!` non
+// cattura variabili da una stringa prodotta da una macro.
+// ============================================================================
+
+//! Scelta della lingua dei messaggi (italiano o inglese).
+
+use std::sync::OnceLock;
+
+/// Lingua dei messaggi del tutorial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lingua {
+    Italiano,
+    Inglese,
+}
+
+impl Lingua {
+    /// Lingua dal codice: "it" / "italiano" o "en" / "english" / "inglese".
+    ///
+    /// ```
+    /// use rust_tutorial::lingua::Lingua;
+    ///
+    /// assert_eq!(Lingua::da_codice("EN"), Some(Lingua::Inglese));
+    /// assert_eq!(Lingua::da_codice("fr"), None);
+    /// ```
+    pub fn da_codice(codice: &str) -> Option<Lingua> {
+        match codice.trim().to_lowercase().as_str() {
+            "it" | "italiano" => Some(Lingua::Italiano),
+            "en" | "english" | "inglese" => Some(Lingua::Inglese),
+            _ => None,
+        }
+    }
+
+    /// Codice da passare in TUTORIAL_LINGUA.
+    pub fn codice(self) -> &'static str {
+        match self {
+            Lingua::Italiano => "it",
+            Lingua::Inglese => "en",
+        }
+    }
+}
+
+/// La lingua scelta con TUTORIAL_LINGUA, letta una volta sola; italiano se
+/// la variabile manca o non e riconosciuta.
+pub fn lingua() -> Lingua {
+    static LINGUA: OnceLock<Lingua> = OnceLock::new();
+    *LINGUA.get_or_init(|| {
+        std::env::var("TUTORIAL_LINGUA")
+            .ok()
+            .and_then(|codice| Lingua::da_codice(&codice))
+            .unwrap_or(Lingua::Italiano)
+    })
+}
+
+/// Sceglie tra testo originale e traduzione secondo la lingua corrente.
+///
+/// ```
+/// use rust_tutorial::lingua::{self, Lingua};
+///
+/// let testo = lingua::scegli(("Capitoli", "Chapters"));
+/// assert_eq!(testo == "Chapters", lingua::lingua() == Lingua::Inglese);
+/// ```
+pub fn scegli(testi: (&'static str, &'static str)) -> &'static str {
+    match lingua() {
+        Lingua::Italiano => testi.0,
+        Lingua::Inglese => testi.1,
+    }
+}
+
+/// Come `println!`, ma usa la traduzione del catalogo `inglese!` se la
+/// lingua e l'inglese.
+#[macro_export]
+macro_rules! tprintln {
+    () => {
+        println!()
+    };
+    ($testo:tt $($argomenti:tt)*) => {
+        match $crate::lingua::lingua() {
+            $crate::lingua::Lingua::Italiano => println!($testo $($argomenti)*),
+            $crate::lingua::Lingua::Inglese => println!(inglese!($testo) $($argomenti)*),
+        }
+    };
+}
+
+/// Come `print!`, con la traduzione (vedi `tprintln!`).
+#[macro_export]
+macro_rules! tprint {
+    ($testo:tt $($argomenti:tt)*) => {
+        match $crate::lingua::lingua() {
+            $crate::lingua::Lingua::Italiano => print!($testo $($argomenti)*),
+            $crate::lingua::Lingua::Inglese => print!(inglese!($testo) $($argomenti)*),
+        }
+    };
+}
+
+/// Come `format!`, con la traduzione (vedi `tprintln!`).
+#[macro_export]
+macro_rules! tformat {
+    ($testo:tt $($argomenti:tt)*) => {
+        match $crate::lingua::lingua() {
+            $crate::lingua::Lingua::Italiano => format!($testo $($argomenti)*),
+            $crate::lingua::Lingua::Inglese => format!(inglese!($testo) $($argomenti)*),
+        }
+    };
+}
+
+/// Coppia (originale, traduzione) risolta a compilazione: utile nelle
+/// costanti, da passare poi a `lingua::scegli`.
+#[macro_export]
+macro_rules! bilingue {
+    ($testo:tt) => {
+        ($testo, inglese!($testo))
+    };
+}
+
+/// Un testo (non un formato) tradotto: per i dati stampati, come i nomi
+/// dei giorni o i giudizi scelti con un `match`.
+#[macro_export]
+macro_rules! tr {
+    ($testo:tt) => {
+        $crate::lingua::scegli(($testo, inglese!($testo)))
+    };
+}
+
+/// Come `eprintln!`, con la traduzione (vedi `tprintln!`).
+#[macro_export]
+macro_rules! teprintln {
+    ($testo:tt $($argomenti:tt)*) => {
+        match $crate::lingua::lingua() {
+            $crate::lingua::Lingua::Italiano => eprintln!($testo $($argomenti)*),
+            $crate::lingua::Lingua::Inglese => eprintln!(inglese!($testo) $($argomenti)*),
+        }
+    };
+}
@@ -3,101 +3,652 @@
 // ============================================================================
 // Benvenuto! Questo e il punto di ingresso del progetto tutorial.
 //
-// Per eseguire i singoli capitoli:
+// Per eseguire questo file (menu interattivo dei capitoli):
+//   cargo run
+//
+// Dal menu basta scrivere il numero del capitolo: il programma lancia
+// `cargo run --example ...` al posto tuo e al termine torna al menu.
+// Per aprire subito un capitolo senza passare dal menu:
+//   cargo run -- 5
+//
+// Installato con `cargo install --path .` il comando si chiama
+// `bronzeaxe-tutor`: `bronzeaxe-tutor 5` equivale a `cargo run -- 5`.
+//
+// Schede di riepilogo (ownership, Option/Result, iteratori):
+//   cargo run -- cheatsheet iteratori
+//   cargo run -- cheatsheet tutti --markdown > PROMEMORIA.md
+//
+// Glossario italiano/inglese, con il capitolo dove si spiega ogni termine:
+//   cargo run -- glossario cerca prestito
+//
+// Un nuovo progetto con la struttura del capitolo 7, per scrivere la
+// propria versione del progetto finale (vedi src/scaffold.rs):
+//   cargo run -- scaffold mio-catalogo
+//
+// Micro-esempi citati nei capitoli (snippets/), confrontati con l'uscita
+// attesa (vedi src/snippet.rs):
+//   cargo run -- run shadowing
+//
+// Esercizi con indizi graduati (le soluzioni solo con la feature):
+//   cargo run -- esercizi
+//   cargo run -- hint prima_parola 1
+//   cargo run --features solutions -- hint prima_parola 3
+//
+// Solo la libreria, senza axum, tokio, database e le altre dipendenze
+// pesanti (le feature sono descritte in Cargo.toml):
+//   cargo build --no-default-features
+//
+// In inglese (menu e capitoli lanciati dal menu):
+//   cargo run -- --lingua en
+// oppure TUTORIAL_LINGUA=en, che vale anche per `cargo run --example ...`
+//
+// Senza bordi ne simboli (vedi src/schermo.rs):
+//   cargo run -- --semplice
+// oppure NO_COLOR=1 / TUTORIAL_SEMPLICE=1
+//
+// I capitoli completati vengono ricordati in .progressi.json (vedi
+// src/progressi.rs) e il menu mostra a che punto sei. Per ricominciare:
+//   cargo run -- reset
+//
+// Per eseguire i singoli capitoli direttamente:
 //   cargo run --example cap01_basi
 //   cargo run --example cap02_ownership
-//   ... e cosi via fino a cap09_progetto_finale
+//   ... e cosi via fino a cap18_pattern (il 13 e cap_test)
 //
-// Per eseguire questo file:
-//   cargo run
+// Per eseguire i test (libreria in src/lib.rs, tests/ e doctest):
+//   cargo test
 // ============================================================================
 
+use std::io::{self, BufRead, Write};
+use std::process::Command;
+
+mod progressi;
+
+use progressi::Progressi;
+use rust_tutorial::esercizi::{self, Esercizio};
+use rust_tutorial::glossario::{self, Voce};
+use rust_tutorial::lingua::{self, Lingua};
+use rust_tutorial::promemoria::{self, Scheda};
+use rust_tutorial::scaffold;
+use rust_tutorial::schermo;
+use rust_tutorial::snippet::{self, Riga};
+use rust_tutorial::tui_text;
+use rust_tutorial::{bilingue, teprintln, tformat, tprint, tprintln, tr};
+
+include!("../lingua/menu.rs");
+
+/// Numero, titolo (italiano, inglese) e nome dell'esempio di ogni capitolo
+const CAPITOLI: [(u32, (&str, &str), &str); 18] = [
+    (1, bilingue!("Le Basi"), "cap01_basi"),
+    (2, bilingue!("Ownership"), "cap02_ownership"),
+    (3, bilingue!("Struct/Enum"), "cap03_strutture"),
+    (4, bilingue!("Gestione Errori"), "cap04_errori"),
+    (5, bilingue!("Collezioni"), "cap05_collezioni"),
+    (6, bilingue!("Traits/Generics"), "cap06_traits"),
+    (7, bilingue!("Moduli"), "cap07_moduli"),
+    (8, bilingue!("Concorrenza"), "cap08_concorrenza"),
+    (9, bilingue!("Progetto Finale"), "cap09_progetto_finale"),
+    (10, bilingue!("Lifetimes"), "cap10_lifetimes"),
+    (11, bilingue!("Smart Pointer"), "cap11_smart_pointers"),
+    (12, bilingue!("Closure/Iteratori"), "cap12_closures_iteratori"),
+    (13, bilingue!("Test Automatici"), "cap_test"),
+    (14, bilingue!("CLI con clap"), "cap14_cli"),
+    (15, bilingue!("Web con axum"), "cap15_web_axum"),
+    (16, bilingue!("thiserror/anyhow"), "cap16_thiserror_anyhow"),
+    (17, bilingue!("serde avanzato"), "cap17_serde"),
+    (18, bilingue!("Design pattern"), "cap18_pattern"),
+];
+
+/// Cosa ha chiesto l'utente al prompt del menu
+#[derive(Debug, PartialEq)]
+enum Scelta {
+    Capitolo(u32, &'static str),
+    Demo,
+    PercheRust,
+    Esercizi,
+    Azzera,
+    Esci,
+}
+
 fn main() {
-    println!("╔══════════════════════════════════════════════════════════╗");
-    println!("║                                                          ║");
-    println!("║          TUTORIAL RUST: DA ZERO A HERO                   ║");
-    println!("║                                                          ║");
-    println!("║   Una guida completa al linguaggio Rust                  ║");
-    println!("║   con esempi reali e funzionanti                         ║");
-    println!("║                                                          ║");
-    println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║                                                          ║");
-    println!("║   CAPITOLI DISPONIBILI:                                  ║");
-    println!("║                                                          ║");
-    println!("║   1. Le Basi            cargo run --example cap01_basi   ║");
-    println!("║   2. Ownership          cargo run --example cap02_owner~ ║");
-    println!("║   3. Struct/Enum        cargo run --example cap03_strut~ ║");
-    println!("║   4. Gestione Errori    cargo run --example cap04_errori ║");
-    println!("║   5. Collezioni         cargo run --example cap05_colle~ ║");
-    println!("║   6. Traits/Generics    cargo run --example cap06_traits ║");
-    println!("║   7. Moduli             cargo run --example cap07_moduli ║");
-    println!("║   8. Concorrenza        cargo run --example cap08_conco~ ║");
-    println!("║   9. Progetto Finale    cargo run --example cap09_proge~ ║");
-    println!("║                                                          ║");
-    println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║                                                          ║");
-    println!("║   PERCHE RUST?                                           ║");
-    println!("║                                                          ║");
-    println!("║   - Sicurezza della memoria senza garbage collector      ║");
-    println!("║   - Prestazioni pari a C/C++                             ║");
-    println!("║   - Concorrenza senza data race                          ║");
-    println!("║   - Compilatore che ti guida e ti protegge               ║");
-    println!("║   - Ecosistema moderno (Cargo, crates.io)                ║");
-    println!("║                                                          ║");
-    println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║                                                          ║");
-    println!("║   CHI USA RUST?                                          ║");
-    println!("║                                                          ║");
-    println!("║   Mozilla (Firefox), Google (Android), Microsoft,        ║");
-    println!("║   Amazon AWS, Meta, Cloudflare, Discord, Dropbox,        ║");
-    println!("║   Linux Kernel (secondo linguaggio ufficiale)            ║");
-    println!("║                                                          ║");
-    println!("╠══════════════════════════════════════════════════════════╣");
-    println!("║                                                          ║");
-    println!("║   Leggi TUTORIAL_RUST.md per la guida completa!          ║");
-    println!("║                                                          ║");
-    println!("╚══════════════════════════════════════════════════════════╝");
-
-    println!("\n  Versione Rust: {}", env!("CARGO_PKG_VERSION"));
-    println!("  Edizione: 2021");
+    let mut argomenti: Vec<String> = std::env::args().skip(1).collect();
+    // `--lingua en` e `--semplice` valgono per il menu e per i capitoli
+    // lanciati da qui, che le ricevono come variabili d'ambiente. Vanno
+    // impostate prima di qualsiasi testo: lingua e stile si leggono una volta sola
+    loop {
+        match argomenti.first().map(String::as_str) {
+            Some("--lingua" | "--lang") => {
+                let codice = argomenti.get(1).cloned().unwrap_or_default();
+                let Some(scelta) = Lingua::da_codice(&codice) else {
+                    eprintln!("Lingua '{}' sconosciuta: usa it o en", codice);
+                    std::process::exit(2);
+                };
+                std::env::set_var("TUTORIAL_LINGUA", scelta.codice());
+                argomenti.drain(..2);
+            }
+            Some("--semplice" | "--plain") => {
+                std::env::set_var("TUTORIAL_SEMPLICE", "1");
+                argomenti.remove(0);
+            }
+            _ => break,
+        }
+    }
+
+    let percorso = Progressi::percorso();
+    let mut progressi = Progressi::carica(&percorso).unwrap_or_else(|e| {
+        teprintln!("  Progressi illeggibili ({}): {}. Si riparte da zero.", percorso.display(), e);
+        Progressi::default()
+    });
+
+    let argomento = |i: usize| argomenti.get(i).map(String::as_str);
+    match argomento(0) {
+        Some("hint") => std::process::exit(indizio(argomento(1), argomento(2).unwrap_or("1"))),
+        Some("soluzione") => std::process::exit(indizio(argomento(1), "3")),
+        Some("cheatsheet" | "promemoria") => std::process::exit(stampa_promemoria(&argomenti[1..])),
+        Some("glossario" | "glossary") => std::process::exit(stampa_glossario(&argomenti[1..])),
+        Some("scaffold") => std::process::exit(crea_progetto(argomento(1))),
+        Some("run") => std::process::exit(esegui_snippet(&argomenti[1..])),
+        _ => {}
+    }
+
+    // `cargo run -- 5`: apre subito il capitolo 5
+    if let Some(argomento) = argomento(0) {
+        match interpreta(argomento) {
+            Some(Scelta::Capitolo(n, esempio)) => {
+                let codice = lancia(esempio);
+                if codice == 0 {
+                    registra(&mut progressi, &percorso, n);
+                }
+                std::process::exit(codice);
+            }
+            Some(Scelta::Demo) => demo(),
+            Some(Scelta::PercheRust) => perche_rust(),
+            Some(Scelta::Esercizi) => elenca_esercizi(),
+            Some(Scelta::Azzera) => azzera(&mut progressi, &percorso),
+            Some(Scelta::Esci) => {}
+            None => {
+                teprintln!("Capitolo '{}' sconosciuto (1-{})", argomento, CAPITOLI.len());
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+
+    schermo::intestazione(&[
+        tr!("TUTORIAL RUST: DA ZERO A HERO"),
+        "",
+        tr!("Una guida completa al linguaggio Rust"),
+        tr!("con esempi reali e funzionanti"),
+    ]);
+
+    let stdin = io::stdin();
+    let mut righe = stdin.lock().lines();
+    loop {
+        stampa_menu(&progressi);
+        tprint!("\n  Scelta: ");
+        // print! non va a capo: senza flush il prompt potrebbe non comparire
+        io::stdout().flush().ok();
+
+        // Fine dell'input (Ctrl-D o stdin chiuso): si esce come con 'q'
+        let Some(Ok(riga)) = righe.next() else {
+            tprintln!();
+            break;
+        };
+        match interpreta(&riga) {
+            Some(Scelta::Capitolo(n, esempio)) => {
+                if lancia(esempio) == 0 {
+                    registra(&mut progressi, &percorso, n);
+                }
+                tprint!("\n  Premi Invio per tornare al menu...");
+                io::stdout().flush().ok();
+                if !matches!(righe.next(), Some(Ok(_))) {
+                    tprintln!();
+                    break;
+                }
+            }
+            Some(Scelta::Demo) => demo(),
+            Some(Scelta::PercheRust) => perche_rust(),
+            Some(Scelta::Esercizi) => elenca_esercizi(),
+            Some(Scelta::Azzera) => {
+                tprint!("\n  Azzerare tutti i progressi? (s/N) ");
+                io::stdout().flush().ok();
+                match righe.next() {
+                    Some(Ok(risposta)) if ["s", "y"].iter().any(|si| risposta.trim().eq_ignore_ascii_case(si)) => azzera(&mut progressi, &percorso),
+                    Some(Ok(_)) => tprintln!("  Progressi lasciati come erano"),
+                    _ => break,
+                }
+            }
+            Some(Scelta::Esci) => break,
+            None if riga.trim().is_empty() => {}
+            None => tprintln!("\n  '{}' non e una scelta valida", riga.trim()),
+        }
+    }
+    tprintln!("  Alla prossima! Leggi TUTORIAL_RUST.md per la guida completa.");
+}
+
+fn stampa_menu(progressi: &Progressi) {
+    tprintln!("\n  CAPITOLI DISPONIBILI:          {} = completato\n", schermo::simboli("✓"));
+    // Due colonne: 1-9 a sinistra, 10-18 a destra
+    let voce = |n: u32, titolo: (&'static str, &'static str)| {
+        let segno = if progressi.completato(n) { '✓' } else { ' ' };
+        schermo::simboli(&format!("{} {:>2}. {}", segno, n, lingua::scegli(titolo)))
+    };
+    let meta = CAPITOLI.len().div_ceil(2);
+    for (i, (n, titolo, _)) in CAPITOLI.iter().enumerate().take(meta) {
+        let sinistra = voce(*n, *titolo);
+        match CAPITOLI.get(i + meta) {
+            Some((n, titolo, _)) => tprintln!("  {:<28}{}", sinistra, voce(*n, *titolo)),
+            None => tprintln!("  {}", sinistra),
+        }
+    }
+    tprintln!("\n  Progressi: {}", schermo::simboli(&progressi.barra(CAPITOLI.len(), 18)));
+    tprintln!("\n   e. Esercizi   d. Demo   p. Perche Rust?   r. Azzera progressi   q. Esci");
+}
+
+/// Interpreta quanto scritto al prompt; None se non corrisponde a nulla
+fn interpreta(testo: &str) -> Option<Scelta> {
+    match testo.trim().to_lowercase().as_str() {
+        "d" | "demo" => Some(Scelta::Demo),
+        "p" | "perche" => Some(Scelta::PercheRust),
+        "e" | "esercizi" => Some(Scelta::Esercizi),
+        "r" | "reset" => Some(Scelta::Azzera),
+        "q" | "esci" => Some(Scelta::Esci),
+        numero => {
+            let n: u32 = numero.parse().ok()?;
+            CAPITOLI.iter().find(|(c, _, _)| *c == n).map(|(c, _, esempio)| Scelta::Capitolo(*c, esempio))
+        }
+    }
+}
+
+fn elenca_esercizi() {
+    tprintln!("\n  ESERCIZI:\n");
+    for es in &esercizi::ESERCIZI {
+        tprintln!("  cap. {:>2}  {:<14} {}", es.capitolo, es.id, es.titolo);
+    }
+    tprintln!("\n  Testo e primo indizio:  cargo run -- hint <esercizio> 1");
+    tprintln!("  Indizio piu esplicito:  cargo run -- hint <esercizio> 2");
+    tprintln!("  Soluzione completa:     cargo run --features solutions -- hint <esercizio> 3");
+}
+
+/// `cheatsheet [argomento | tutti] [--markdown]`
+fn stampa_promemoria(argomenti: &[String]) -> i32 {
+    let markdown = argomenti.iter().any(|a| a == "--markdown" || a == "--md");
+    let argomento = argomenti.iter().map(String::as_str).find(|a| !a.starts_with("--"));
+    let schede: Vec<&Scheda> = match argomento {
+        None => {
+            tprintln!("\n  SCHEDE DI RIEPILOGO:\n");
+            for scheda in &promemoria::SCHEDE {
+                tprintln!("  {:<15} {}", scheda.argomento, scheda.titolo);
+            }
+            tprintln!("\n  Nel terminale:  cargo run -- cheatsheet <argomento>");
+            tprintln!("  In Markdown:    cargo run -- cheatsheet <argomento|tutti> --markdown > PROMEMORIA.md");
+            return 0;
+        }
+        Some("tutti" | "all") => promemoria::SCHEDE.iter().collect(),
+        Some(argomento) => match promemoria::cerca(argomento) {
+            Some(scheda) => vec![scheda],
+            None => {
+                let noti: Vec<&str> = promemoria::SCHEDE.iter().map(|s| s.argomento).collect();
+                teprintln!("Argomento '{}' sconosciuto. Argomenti: {}, tutti", argomento, noti.join(", "));
+                return 2;
+            }
+        },
+    };
+
+    if markdown && schede.len() > 1 {
+        println!("# Promemoria Rust\n");
+    }
+    for (i, scheda) in schede.iter().enumerate() {
+        if markdown {
+            if i > 0 {
+                println!();
+            }
+            print!("{}", scheda.markdown());
+        } else {
+            schermo::stampa(&scheda.riquadro());
+            println!();
+        }
+    }
+    0
+}
+
+/// `glossario [cerca] [termine]`: senza termine l'elenco completo
+fn stampa_glossario(argomenti: &[String]) -> i32 {
+    let parole = match argomenti.first().map(String::as_str) {
+        Some("cerca" | "search") => &argomenti[1..],
+        _ => argomenti,
+    };
+    // `cerca trait object` senza virgolette: le parole sono un solo termine
+    let testo = parole.join(" ");
+    if testo.trim().is_empty() {
+        tprintln!("\n  GLOSSARIO:\n");
+        for voce in &glossario::GLOSSARIO {
+            tprintln!("  {:<22} {}", voce.termine, voce.italiano);
+        }
+        tprintln!("\n  Cerca:  cargo run -- glossario cerca <termine>");
+        return 0;
+    }
+
+    let trovate = glossario::cerca(&testo);
+    if trovate.is_empty() {
+        teprintln!("Nessun termine per '{}'. Elenco completo: cargo run -- glossario", testo.trim());
+        return 1;
+    }
+    for voce in trovate {
+        stampa_voce(voce);
+    }
+    0
+}
+
+fn stampa_voce(voce: &Voce) {
+    tprintln!("\n  {} / {}", voce.termine, voce.italiano);
+    tprintln!("    {}", lingua::scegli(voce.definizione));
+    for capitolo in voce.capitoli {
+        if let Some((n, titolo, _)) = CAPITOLI.iter().find(|(n, _, _)| n == capitolo) {
+            tprintln!("    Capitolo {}: {}   (cargo run -- {})", n, lingua::scegli(*titolo), n);
+        }
+    }
+    if !voce.vedi_anche.is_empty() {
+        tprintln!("    Vedi anche: {}", voce.vedi_anche.join(", "));
+    }
+}
+
+/// `scaffold <nome>`: il progetto nasce nella cartella corrente
+fn crea_progetto(nome: Option<&str>) -> i32 {
+    let Some(nome) = nome else {
+        teprintln!("Uso: cargo run -- scaffold <nome-progetto>");
+        return 2;
+    };
+    match scaffold::genera(std::path::Path::new("."), nome) {
+        Ok(file) => {
+            tprintln!("\n  Creato il progetto '{}' ({} file):\n", nome, file.len());
+            for percorso in &file {
+                tprintln!("    {}", percorso.display());
+            }
+            tprintln!("\n  Per iniziare:  cd {} && cargo run", nome);
+            tprintln!("  Guida:         capitolo 7 (moduli) e capitolo 9 (progetto finale)");
+            0
+        }
+        Err(e @ scaffold::ErroreScaffold::NomeNonValido(_)) => {
+            eprintln!("{}", e);
+            2
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// `run [snippet] [--aggiorna]`: 0 se l'uscita e quella attesa
+fn esegui_snippet(argomenti: &[String]) -> i32 {
+    let aggiorna = argomenti.iter().any(|a| a == "--aggiorna" || a == "--bless");
+    let cartella = snippet::cartella();
+    let Some(nome) = argomenti.iter().map(String::as_str).find(|a| !a.starts_with("--")) else {
+        return match snippet::elenco(&cartella) {
+            Ok(elenco) => {
+                tprintln!("\n  SNIPPET:\n");
+                let colonna = tui_text::colonna(elenco.iter().map(|s| s.nome.as_str()));
+                for s in &elenco {
+                    tprintln!("  {}  {}", tui_text::a_sinistra(&s.nome, colonna), s.descrizione);
+                }
+                tprintln!("\n  Esegui:  cargo run -- run <snippet>");
+                0
+            }
+            Err(e) => {
+                teprintln!("Impossibile leggere {}: {}", cartella.display(), e);
+                1
+            }
+        };
+    };
+
+    let uscita = match snippet::cerca(&cartella, nome).and_then(|s| Ok((s.esegui()?, s))) {
+        Ok(uscita) => uscita,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let (ottenuto, s) = uscita;
+    tprintln!("\n  $ rustc snippets/{}.rs\n", s.nome);
+    print!("{}", ottenuto);
+
+    let file_atteso = s.atteso();
+    if aggiorna {
+        return match std::fs::write(&file_atteso, &ottenuto) {
+            Ok(()) => {
+                tprintln!("\n  Uscita salvata in {}", file_atteso.display());
+                0
+            }
+            Err(e) => {
+                teprintln!("Impossibile scrivere {}: {}", file_atteso.display(), e);
+                1
+            }
+        };
+    }
+    let Ok(atteso) = std::fs::read_to_string(&file_atteso) else {
+        tprintln!("\n  Manca {}: crealo con  cargo run -- run {} --aggiorna", file_atteso.display(), s.nome);
+        return 1;
+    };
+
+    let righe = snippet::differenze(&atteso, &ottenuto);
+    if righe.iter().all(|r| matches!(r, Riga::Uguale(_))) {
+        tprintln!("\n  {} Uscita uguale a {}.out", schermo::simboli("✓"), s.nome);
+        return 0;
+    }
+    tprintln!("\n  L'uscita e diversa da {}.out (- attesa, + ottenuta):\n", s.nome);
+    for riga in righe {
+        match riga {
+            Riga::Uguale(testo) => println!("    {}", testo),
+            Riga::Mancante(testo) => println!("  - {}", testo),
+            Riga::InPiu(testo) => println!("  + {}", testo),
+        }
+    }
+    1
+}
+
+/// `hint <esercizio> <livello>`: 1 e 2 sono indizi, 3 la soluzione
+fn indizio(id: Option<&str>, livello: &str) -> i32 {
+    let Some(id) = id else {
+        teprintln!("Uso: cargo run -- hint <esercizio> [1|2|3]");
+        return 2;
+    };
+    let Some(es) = esercizi::cerca(id) else {
+        let noti: Vec<&str> = esercizi::ESERCIZI.iter().map(|e| e.id).collect();
+        teprintln!("Esercizio '{}' sconosciuto. Esercizi: {}", id, noti.join(", "));
+        return 2;
+    };
+    let livello: usize = match livello.parse() {
+        Ok(n @ 1..=3) => n,
+        _ => {
+            teprintln!("Livello '{}' non valido: 1 e 2 sono indizi, 3 la soluzione", livello);
+            return 2;
+        }
+    };
+
+    tprintln!("\n  Esercizio {} (capitolo {}): {}\n", es.id, es.capitolo, es.titolo);
+    tprintln!("  {}\n", es.testo);
+    // Si vedono anche gli indizi precedenti: ognuno parte dal prima
+    for (i, testo) in es.indizi.iter().enumerate().take(livello.min(2)) {
+        tprintln!("  Indizio {}: {}", i + 1, testo);
+    }
+    match livello {
+        3 => mostra_soluzione(es),
+        n => tprintln!("\n  Ancora bloccato?  cargo run -- hint {} {}", es.id, n + 1),
+    }
+    0
+}
+
+#[cfg(feature = "solutions")]
+fn mostra_soluzione(es: &Esercizio) {
+    match rust_tutorial::soluzioni::sorgente(es.id) {
+        Some(sorgente) => {
+            tprintln!("\n  Soluzione (src/soluzioni/{}.rs):\n", es.id);
+            for riga in sorgente.lines() {
+                tprintln!("    {}", riga);
+            }
+        }
+        None => tprintln!("\n  Per questo esercizio non c'e ancora una soluzione"),
+    }
+}
+
+#[cfg(not(feature = "solutions"))]
+fn mostra_soluzione(es: &Esercizio) {
+    tprintln!("\n  Le soluzioni sono escluse da questa compilazione. Per vederle:");
+    tprintln!("    cargo run --features solutions -- hint {} 3", es.id);
+}
+
+/// Segna il capitolo come completato e salva subito il file
+fn registra(progressi: &mut Progressi, percorso: &std::path::Path, capitolo: u32) {
+    progressi.completa(capitolo);
+    if let Err(e) = progressi.salva(percorso) {
+        tprintln!("  Impossibile salvare i progressi in {}: {}", percorso.display(), e);
+    }
+}
+
+fn azzera(progressi: &mut Progressi, percorso: &std::path::Path) {
+    progressi.azzera();
+    match progressi.salva(percorso) {
+        Ok(()) => tprintln!("  Progressi azzerati"),
+        Err(e) => tprintln!("  Impossibile salvare i progressi in {}: {}", percorso.display(), e),
+    }
+}
+
+/// Le `required-features` dell'esempio in Cargo.toml, separate da virgole
+fn feature_richieste(esempio: &str) -> Option<String> {
+    let nome = format!("name = \"{}\"", esempio);
+    let blocco = include_str!("../Cargo.toml").split("[[example]]").find(|b| b.lines().any(|r| r == nome))?;
+    let elenco = blocco.lines().find_map(|r| r.strip_prefix("required-features = "))?;
+    Some(elenco.trim_matches(['[', ']']).replace(['"', ' '], ""))
+}
+
+/// Esegue `cargo run --example <esempio>`, con le feature che richiede, e
+/// ne restituisce il codice di uscita
+fn lancia(esempio: &str) -> i32 {
+    // Sotto `cargo run` la variabile CARGO indica il cargo in uso
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut argomenti = vec!["run".to_string(), "--quiet".to_string()];
+    if let Some(feature) = feature_richieste(esempio) {
+        argomenti.extend(["--features".to_string(), feature]);
+    }
+    argomenti.extend(["--example".to_string(), esempio.to_string()]);
+    tprintln!("\n  $ cargo {}\n", argomenti.iter().filter(|a| *a != "--quiet").cloned().collect::<Vec<_>>().join(" "));
+    match Command::new(cargo).args(&argomenti).status() {
+        Ok(stato) => {
+            if !stato.success() {
+                tprintln!("\n  Il capitolo e terminato con {}", stato);
+            }
+            stato.code().unwrap_or(1)
+        }
+        Err(e) => {
+            tprintln!("  Impossibile avviare cargo: {}", e);
+            1
+        }
+    }
+}
+
+fn perche_rust() {
+    tprintln!();
+    schermo::riquadro(
+        tr!("PERCHE RUST?"),
+        &[
+            tr!("- Sicurezza della memoria senza garbage collector"),
+            tr!("- Prestazioni pari a C/C++"),
+            tr!("- Concorrenza senza data race"),
+            tr!("- Compilatore che ti guida e ti protegge"),
+            tr!("- Ecosistema moderno (Cargo, crates.io)"),
+        ],
+    );
+    schermo::riquadro(
+        tr!("CHI USA RUST?"),
+        &[
+            "Mozilla (Firefox), Google (Android), Microsoft,",
+            "Amazon AWS, Meta, Cloudflare, Discord, Dropbox,",
+            tr!("Linux Kernel (secondo linguaggio ufficiale)"),
+        ],
+    );
+}
+
+fn demo() {
+    tprintln!("\n  Versione Rust: {}", env!("CARGO_PKG_VERSION"));
+    tprintln!("  Edizione: 2021");
 
     // Piccola demo: dimostriamo i concetti chiave di Rust in poche righe
 
-    println!("\n--- Demo rapida dei concetti chiave ---\n");
+    tprintln!("\n--- Demo rapida dei concetti chiave ---\n");
 
     // 1. Ownership
     let nome = String::from("Rust");
     let saluto = crea_saluto(&nome);  // borrowing: &nome
-    println!("  Ownership: {} -> {}", nome, saluto);
+    tprintln!("  Ownership: {} -> {}", nome, saluto);
 
     // 2. Pattern matching
     let voto = 85;
     let giudizio = match voto {
-        90..=100 => "Eccellente",
-        80..=89 => "Ottimo",
-        70..=79 => "Buono",
-        _ => "Da migliorare",
+        90..=100 => tr!("Eccellente"),
+        80..=89 => tr!("Ottimo"),
+        70..=79 => tr!("Buono"),
+        _ => tr!("Da migliorare"),
     };
-    println!("  Pattern matching: voto {} = {}", voto, giudizio);
+    tprintln!("  Pattern matching: voto {} = {}", voto, giudizio);
 
     // 3. Option (niente null!)
     let numeri = vec![10, 20, 30];
     let trovato = numeri.get(1);      // Some(&20)
     let non_trovato = numeri.get(99); // None
-    println!("  Option: get(1)={:?}, get(99)={:?}", trovato, non_trovato);
+    tprintln!("  Option: get(1)={:?}, get(99)={:?}", trovato, non_trovato);
 
     // 4. Iteratori
     let somma_quadrati: i32 = (1..=5).map(|n| n * n).sum();
-    println!("  Iteratori: somma quadrati 1..5 = {}", somma_quadrati);
+    tprintln!("  Iteratori: somma quadrati 1..5 = {}", somma_quadrati);
 
     // 5. Result (gestione errori)
     let ok: Result<i32, &str> = Ok(42);
-    let err: Result<i32, &str> = Err("errore!");
-    println!("  Result: ok={:?}, err={:?}", ok, err);
+    let err: Result<i32, &str> = Err(tr!("errore!"));
+    tprintln!("  Result: ok={:?}, err={:?}", ok, err);
 
-    println!("\n  Esegui i capitoli per approfondire ogni concetto!");
+    tprintln!("\n  Esegui i capitoli per approfondire ogni concetto!");
 }
 
 fn crea_saluto(nome: &str) -> String {
-    format!("Ciao, {}!", nome)
+    tformat!("Ciao, {}!", nome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ogni_capitolo_e_un_esempio_di_cargo() {
+        let manifesto = include_str!("../Cargo.toml");
+        for (n, _, esempio) in CAPITOLI {
+            assert!(manifesto.contains(&format!("name = \"{}\"", esempio)), "capitolo {}: {}", n, esempio);
+        }
+    }
+
+    #[test]
+    fn feature_dei_capitoli_da_cargo_toml() {
+        assert_eq!(feature_richieste("cap14_cli").as_deref(), Some("cli"));
+        assert_eq!(feature_richieste("cap15_web_axum").as_deref(), Some("json,server,net"));
+        assert_eq!(feature_richieste("cap01_basi"), None);
+    }
+
+    #[test]
+    fn il_glossario_rimanda_a_capitoli_esistenti() {
+        for voce in &glossario::GLOSSARIO {
+            for capitolo in voce.capitoli {
+                assert!(CAPITOLI.iter().any(|(n, _, _)| n == capitolo), "{}: capitolo {}", voce.termine, capitolo);
+            }
+        }
+    }
+
+    #[test]
+    fn interpreta_le_scelte() {
+        assert_eq!(interpreta(" 13 "), Some(Scelta::Capitolo(13, "cap_test")));
+        assert_eq!(interpreta("Q"), Some(Scelta::Esci));
+        assert_eq!(interpreta("reset"), Some(Scelta::Azzera));
+        assert_eq!(interpreta("e"), Some(Scelta::Esercizi));
+        assert_eq!(interpreta("0"), None);
+        assert_eq!(interpreta("cinque"), None);
+    }
 }
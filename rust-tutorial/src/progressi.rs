@@ -0,0 +1,116 @@
+// ============================================================================
+// MODULO: PROGRESSI
+// ============================================================================
+// Ricorda quali capitoli sono stati completati, in un piccolo file JSON
+// nella cartella del tutorial (con la feature json, predefinita). Un
+// capitolo conta come completato quando il suo esempio, lanciato dal
+// menu, termina senza errori.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Capitoli completati, con la data del completamento
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progressi {
+    capitoli: BTreeMap<u32, String>,
+}
+
+impl Progressi {
+    /// Dove salvare i progressi: la variabile TUTORIAL_PROGRESSI se impostata,
+    /// altrimenti `.progressi.json` accanto a Cargo.toml
+    pub fn percorso() -> PathBuf {
+        match std::env::var_os("TUTORIAL_PROGRESSI") {
+            Some(percorso) => PathBuf::from(percorso),
+            None => Path::new(env!("CARGO_MANIFEST_DIR")).join(".progressi.json"),
+        }
+    }
+
+    /// Legge i progressi; un file mancante significa "nessun progresso"
+    #[cfg(feature = "json")]
+    pub fn carica(percorso: &Path) -> io::Result<Progressi> {
+        match std::fs::read_to_string(percorso) {
+            Ok(testo) => serde_json::from_str(&testo).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Progressi::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn salva(&self, percorso: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(percorso, json + "\n")
+    }
+
+    // Senza la feature i progressi valgono solo per la sessione
+    #[cfg(not(feature = "json"))]
+    pub fn carica(_percorso: &Path) -> io::Result<Progressi> {
+        Ok(Progressi::default())
+    }
+
+    #[cfg(not(feature = "json"))]
+    pub fn salva(&self, _percorso: &Path) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "i progressi si salvano solo con la feature json"))
+    }
+
+    /// Segna il capitolo come completato; la data resta quella della prima volta
+    pub fn completa(&mut self, capitolo: u32) {
+        self.capitoli
+            .entry(capitolo)
+            .or_insert_with(|| chrono::Local::now().format("%Y-%m-%d").to_string());
+    }
+
+    pub fn completato(&self, capitolo: u32) -> bool {
+        self.capitoli.contains_key(&capitolo)
+    }
+
+    pub fn completati(&self) -> usize {
+        self.capitoli.len()
+    }
+
+    pub fn azzera(&mut self) {
+        self.capitoli.clear();
+    }
+
+    /// Barra di avanzamento, ad esempio "[████████░░░░░░░░] 9/18"
+    pub fn barra(&self, totale: usize, larghezza: usize) -> String {
+        let fatti = self.completati().min(totale);
+        let pieni = (fatti * larghezza).checked_div(totale).unwrap_or(0);
+        format!("[{}{}] {}/{}", "█".repeat(pieni), "░".repeat(larghezza - pieni), fatti, totale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn barra_proporzionale() {
+        let mut p = Progressi::default();
+        assert_eq!(p.barra(18, 6), "[░░░░░░] 0/18");
+        for capitolo in 1..=9 {
+            p.completa(capitolo);
+        }
+        assert_eq!(p.barra(18, 6), "[███░░░] 9/18");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn salva_e_ricarica() {
+        let percorso = std::env::temp_dir().join(format!("progressi_test_{}.json", std::process::id()));
+        assert_eq!(Progressi::carica(&percorso).unwrap(), Progressi::default());
+
+        let mut p = Progressi::default();
+        p.completa(3);
+        p.completa(13);
+        p.salva(&percorso).unwrap();
+        let riletti = Progressi::carica(&percorso).unwrap();
+        std::fs::remove_file(&percorso).unwrap();
+
+        assert_eq!(riletti, p);
+        assert!(riletti.completato(13) && !riletti.completato(4));
+    }
+}
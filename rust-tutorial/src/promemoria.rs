@@ -0,0 +1,227 @@
+// ============================================================================
+// MODULO: PROMEMORIA
+// ============================================================================
+// Schede di riepilogo da tenere accanto all'editor: le regole
+// dell'ownership, i metodi di Option e Result, gli adattatori degli
+// iteratori. Sono dati, non testo gia impaginato: la stessa scheda si
+// stampa nel terminale o si esporta in Markdown.
+//
+//   cargo run -- cheatsheet                          elenco
+//   cargo run -- cheatsheet iteratori                nel terminale
+//   cargo run -- cheatsheet tutti --markdown > PROMEMORIA.md
+// ============================================================================
+
+//! Schede di riepilogo (cheat sheet) per argomento.
+
+use crate::tui_text::{self, Bordo, Riquadro};
+
+/// Una scheda: titolo, capitoli di riferimento e sezioni.
+#[derive(Debug)]
+pub struct Scheda {
+    /// Nome usato sulla riga di comando
+    pub argomento: &'static str,
+    /// Altri nomi accettati per lo stesso argomento
+    pub alias: &'static [&'static str],
+    pub titolo: &'static str,
+    pub capitoli: &'static [u32],
+    pub sezioni: &'static [Sezione],
+}
+
+/// Un gruppo di voci: (codice, cosa fa).
+#[derive(Debug)]
+pub struct Sezione {
+    pub titolo: &'static str,
+    pub voci: &'static [(&'static str, &'static str)],
+}
+
+/// Tutte le schede.
+pub const SCHEDE: [Scheda; 3] = [
+    Scheda {
+        argomento: "ownership",
+        alias: &["borrowing", "prestiti"],
+        titolo: "Ownership e borrowing",
+        capitoli: &[2, 10],
+        sezioni: &[
+            Sezione {
+                titolo: "Le regole",
+                voci: &[
+                    ("let s = String::new();", "`s` e l'unico proprietario (owner) del valore"),
+                    ("}", "fine dello scope: l'owner esce e il valore viene liberato (Drop)"),
+                    ("&T oppure &mut T", "molti riferimenti in lettura OPPURE uno in scrittura"),
+                ],
+            },
+            Sezione {
+                titolo: "Move, copia, prestito",
+                voci: &[
+                    ("let b = a;", "move: `a` non si usa piu (se non e Copy)"),
+                    ("let b = a.clone();", "copia esplicita, anche dei dati nello heap"),
+                    ("i32, f64, bool, char, &T", "tipi Copy: l'assegnamento copia"),
+                    ("fn f(s: String)", "la funzione prende l'ownership"),
+                    ("fn f(s: &str)", "prestito in lettura: il chiamante resta owner"),
+                    ("fn f(v: &mut Vec<T>)", "prestito in scrittura, esclusivo"),
+                ],
+            },
+            Sezione {
+                titolo: "Quando il compilatore protesta",
+                voci: &[
+                    ("borrow of moved value", "valore usato dopo il move: presta (&) o clona"),
+                    ("cannot borrow as mutable", "c'e gia un prestito attivo: accorcia il suo scope"),
+                    ("does not live long enough", "il riferimento vive piu del valore: restituisci un valore posseduto"),
+                ],
+            },
+        ],
+    },
+    Scheda {
+        argomento: "option-result",
+        alias: &["option", "result", "errori"],
+        titolo: "Option e Result",
+        capitoli: &[4, 16],
+        sezioni: &[
+            Sezione {
+                titolo: "Leggere il valore",
+                voci: &[
+                    ("x?", "None / Err tornano subito al chiamante"),
+                    ("unwrap_or(d)", "il valore o un default"),
+                    ("unwrap_or_else(|| ...)", "default calcolato solo se serve"),
+                    ("unwrap_or_default()", "il valore o Default::default()"),
+                    ("expect(\"perche\")", "panic con messaggio: solo se davvero impossibile"),
+                    ("if let Some(x) = o", "un solo caso interessante"),
+                    ("let Some(x) = o else { ... }", "esce subito se manca"),
+                ],
+            },
+            Sezione {
+                titolo: "Trasformare",
+                voci: &[
+                    ("map(|x| ...)", "trasforma il valore, None / Err passano"),
+                    ("and_then(|x| ...)", "concatena un'altra operazione che puo fallire"),
+                    ("map_err(|e| ...)", "trasforma l'errore di un Result"),
+                    ("filter(|x| ...)", "Some solo se la condizione vale"),
+                    ("or_else(|| ...)", "un'alternativa se manca o fallisce"),
+                ],
+            },
+            Sezione {
+                titolo: "Convertire",
+                voci: &[
+                    ("ok()", "Result -> Option, l'errore si perde"),
+                    ("ok_or(e) / ok_or_else(|| e)", "Option -> Result"),
+                    ("transpose()", "Option<Result<T, E>> <-> Result<Option<T>, E>"),
+                    ("collect::<Result<Vec<_>, _>>()", "tutti i valori o il primo errore"),
+                ],
+            },
+        ],
+    },
+    Scheda {
+        argomento: "iteratori",
+        alias: &["iterators", "iter"],
+        titolo: "Iteratori",
+        capitoli: &[5, 12],
+        sezioni: &[
+            Sezione {
+                titolo: "Da dove partono",
+                voci: &[
+                    ("iter()", "&T: presta gli elementi"),
+                    ("iter_mut()", "&mut T: li modifica sul posto"),
+                    ("into_iter()", "T: consuma la collezione"),
+                    ("1..=n, chars(), lines()", "intervalli e testi"),
+                ],
+            },
+            Sezione {
+                titolo: "Adattatori (pigri: non fanno nulla finche non si consuma)",
+                voci: &[
+                    ("map(|x| ...)", "trasforma ogni elemento"),
+                    ("filter(|x| ...)", "tiene quelli che soddisfano la condizione"),
+                    ("filter_map(|x| ...)", "filtra e trasforma in un passo (Option)"),
+                    ("flat_map(|x| ...)", "ogni elemento diventa un iteratore, appiattito"),
+                    ("enumerate()", "coppie (indice, elemento)"),
+                    ("zip(altro)", "coppie da due iteratori"),
+                    ("take(n) / skip(n)", "i primi n / salta i primi n"),
+                    ("chain(altro)", "un iteratore dopo l'altro"),
+                    ("peekable()", "guarda il prossimo senza consumarlo"),
+                ],
+            },
+            Sezione {
+                titolo: "Consumatori",
+                voci: &[
+                    ("collect()", "in Vec, HashMap, String... (va indicato il tipo)"),
+                    ("sum() / count()", "somma e conteggio"),
+                    ("fold(iniziale, |acc, x| ...)", "accumula un risultato"),
+                    ("find(|x| ...) / position(...)", "il primo che soddisfa / il suo indice"),
+                    ("any(...) / all(...)", "almeno uno / tutti"),
+                    ("max_by(|a, b| a.total_cmp(b))", "il massimo tra f64, che non sono Ord"),
+                    ("for_each(|x| ...)", "un effetto su ogni elemento"),
+                ],
+            },
+        ],
+    },
+];
+
+/// Cerca una scheda per argomento o per alias, senza badare alle maiuscole.
+///
+/// ```
+/// use rust_tutorial::promemoria;
+///
+/// assert_eq!(promemoria::cerca("Result").unwrap().argomento, "option-result");
+/// assert!(promemoria::cerca("macro").is_none());
+/// ```
+pub fn cerca(argomento: &str) -> Option<&'static Scheda> {
+    let argomento = argomento.to_lowercase();
+    SCHEDE.iter().find(|s| s.argomento == argomento || s.alias.contains(&argomento.as_str()))
+}
+
+impl Scheda {
+    /// La scheda in un riquadro per il terminale, con i codici in colonna.
+    pub fn riquadro(&self) -> Riquadro {
+        let capitoli: Vec<String> = self.capitoli.iter().map(u32::to_string).collect();
+        let colonna = tui_text::colonna(self.sezioni.iter().flat_map(|s| s.voci.iter().map(|(codice, _)| *codice)));
+
+        let mut riquadro = Riquadro::nuovo(Bordo::Singolo);
+        riquadro.riga(format!("{} (capitoli {})", self.titolo.to_uppercase(), capitoli.join(", ")));
+        for sezione in self.sezioni {
+            riquadro.separatore().riga(sezione.titolo);
+            for (codice, significato) in sezione.voci {
+                riquadro.riga(format!("  {}  {}", tui_text::a_sinistra(codice, colonna), significato));
+            }
+        }
+        riquadro
+    }
+
+    /// La scheda in Markdown: un titolo e una tabella per sezione.
+    pub fn markdown(&self) -> String {
+        let capitoli: Vec<String> = self.capitoli.iter().map(|c| format!("capitolo {}", c)).collect();
+        let mut testo = format!("## {}\n\n_Vedi {}._\n", self.titolo, capitoli.join(", "));
+        for sezione in self.sezioni {
+            testo += &format!("\n### {}\n\n| Codice | Cosa fa |\n|---|---|\n", sezione.titolo);
+            for (codice, significato) in sezione.voci {
+                // `|` chiuderebbe la cella, anche dentro il codice delle closure
+                testo += &format!("| `{}` | {} |\n", codice.replace('|', "\\|"), significato.replace('|', "\\|"));
+            }
+        }
+        testo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argomenti_e_alias_non_si_sovrappongono() {
+        for scheda in &SCHEDE {
+            assert_eq!(cerca(scheda.argomento).unwrap().argomento, scheda.argomento);
+            for alias in scheda.alias {
+                assert_eq!(cerca(alias).unwrap().argomento, scheda.argomento, "alias '{}' ambiguo", alias);
+            }
+        }
+    }
+
+    #[test]
+    fn markdown_protegge_le_barre_delle_closure() {
+        let testo = cerca("iteratori").unwrap().markdown();
+        assert!(testo.starts_with("## Iteratori\n\n_Vedi capitolo 5, capitolo 12._\n"));
+        assert!(testo.contains("| `map(\\|x\\| ...)` | trasforma ogni elemento |\n"));
+        // Ogni riga della tabella ha esattamente tre separatori di cella
+        for riga in testo.lines().filter(|r| r.starts_with("| `")) {
+            assert_eq!(riga.replace("\\|", "").matches('|').count(), 3, "{}", riga);
+        }
+    }
+}
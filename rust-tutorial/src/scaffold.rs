@@ -0,0 +1,103 @@
+// ============================================================================
+// MODULO: SCAFFOLD
+// ============================================================================
+// Crea un nuovo progetto Cargo con la struttura del capitolo 7 (7.6), da
+// cui partire per scrivere la propria versione del progetto finale:
+//
+//   cargo run -- scaffold mio-catalogo
+//   cd mio-catalogo && cargo run
+//
+// I file generati stanno in scaffold/ e sono inclusi nel binario con
+// `include_str!`: si modificano come normali file Rust. tests/scaffold.rs
+// li compila e ne esegue i test insieme a quelli del tutorial.
+// ============================================================================
+
+//! Generatore dello scheletro di progetto per il capitolo 9.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+/// Segnaposto sostituito con il nome del progetto
+const SEGNAPOSTO: &str = "{{nome}}";
+
+/// Percorso nel nuovo progetto e contenuto di ogni file
+const FILE: [(&str, &str); 8] = [
+    ("Cargo.toml", include_str!("../scaffold/Cargo.toml.modello")),
+    (".gitignore", include_str!("../scaffold/gitignore.modello")),
+    ("src/main.rs", include_str!("../scaffold/src/main.rs")),
+    ("src/database.rs", include_str!("../scaffold/src/database.rs")),
+    ("src/analisi/mod.rs", include_str!("../scaffold/src/analisi/mod.rs")),
+    ("src/analisi/statistiche.rs", include_str!("../scaffold/src/analisi/statistiche.rs")),
+    ("src/report.rs", include_str!("../scaffold/src/report.rs")),
+    ("src/utils.rs", include_str!("../scaffold/src/utils.rs")),
+];
+
+#[derive(Debug, Error)]
+pub enum ErroreScaffold {
+    #[error("'{0}' non e un nome di progetto valido: usa lettere, cifre, '-' e '_', iniziando con una lettera")]
+    NomeNonValido(String),
+    #[error("{} esiste gia: scegli un altro nome o spostala", .0.display())]
+    CartellaEsistente(PathBuf),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Vero se Cargo accetta `nome` come nome di pacchetto.
+///
+/// ```
+/// use rust_tutorial::scaffold::nome_valido;
+///
+/// assert!(nome_valido("mio-catalogo"));
+/// assert!(!nome_valido("2-catalogo"));
+/// assert!(!nome_valido("mio catalogo"));
+/// ```
+pub fn nome_valido(nome: &str) -> bool {
+    nome.starts_with(|c: char| c.is_ascii_alphabetic())
+        && nome.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Crea il progetto `nome` dentro `cartella` e restituisce i file scritti.
+/// Non tocca mai una cartella che esiste gia.
+pub fn genera(cartella: &Path, nome: &str) -> Result<Vec<PathBuf>, ErroreScaffold> {
+    if !nome_valido(nome) {
+        return Err(ErroreScaffold::NomeNonValido(nome.to_string()));
+    }
+    let radice = cartella.join(nome);
+    if radice.exists() {
+        return Err(ErroreScaffold::CartellaEsistente(radice));
+    }
+
+    let mut scritti = Vec::new();
+    for (percorso, contenuto) in FILE {
+        let destinazione = radice.join(percorso);
+        if let Some(genitore) = destinazione.parent() {
+            std::fs::create_dir_all(genitore)?;
+        }
+        std::fs::write(&destinazione, contenuto.replace(SEGNAPOSTO, nome))?;
+        scritti.push(destinazione);
+    }
+    Ok(scritti)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genera_il_progetto_una_volta_sola() {
+        let cartella = std::env::temp_dir().join(format!("scaffold_test_{}", std::process::id()));
+        let scritti = genera(&cartella, "mio-catalogo").unwrap();
+        assert_eq!(scritti.len(), FILE.len());
+
+        let manifesto = std::fs::read_to_string(cartella.join("mio-catalogo/Cargo.toml")).unwrap();
+        assert!(manifesto.contains("name = \"mio-catalogo\""), "{}", manifesto);
+        assert!(cartella.join("mio-catalogo/src/analisi/statistiche.rs").is_file());
+
+        // La seconda volta la cartella c'e gia: nessun file viene sovrascritto
+        let errore = genera(&cartella, "mio-catalogo").unwrap_err();
+        assert!(matches!(errore, ErroreScaffold::CartellaEsistente(_)), "{}", errore);
+        std::fs::remove_dir_all(&cartella).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+// ============================================================================
+// MODULO: SCHEDE
+// ============================================================================
+// La scheda stampabile di un'entita (reperto, campione, persona...): un
+// titolo e una serie di coppie etichetta/valore. Invece di scrivere a mano
+// un `Display` o una riga di tabella per ogni esportazione, si descrive
+// la struct una volta con `#[derive(Scheda)]` (crate scheda_derive):
+//
+//   #[derive(Scheda)]
+//   #[scheda(titolo = "Campione")]
+//   pub struct Campione {
+//       #[scheda(etichetta = "Massa", unita = "mg", precisione = 2)]
+//       pub massa_mg: f64,
+//       ...
+//   }
+//
+// Attributi dei campi:
+//   etichetta = "..."   al posto del nome del campo ("peso_grammi" -> "Peso grammi")
+//   unita = "g"         aggiunta dopo il valore
+//   precisione = 1      cifre decimali
+//   ordine = 1          i campi con ordine vengono prima, dal piu piccolo
+//   debug               usa {:?} invece di {} (enum senza Display)
+//   salta               il campo non compare nella scheda
+// I campi `Option` valgono "-" quando sono None. Il derive va sulla
+// definizione della struct: per Reperto, che sta in bronzeaxe-core,
+// l'implementazione e scritta a mano in inventario.rs.
+// ============================================================================
+
+//! Schede stampabili generate con `#[derive(Scheda)]`.
+
+use crate::tui_text::{self, Bordo, Riquadro};
+
+/// Implementa `SchedaStampabile` a partire dagli attributi `#[scheda(...)]`.
+///
+/// Solo struct con campi con nome:
+///
+/// ```compile_fail
+/// use rust_tutorial::schede::Scheda;
+///
+/// #[derive(Scheda)]
+/// struct Grammi(f64);
+/// ```
+pub use scheda_derive::Scheda;
+
+/// Un'entita che si stampa come scheda: etichette e valori nello stesso
+/// ordine. Di solito non si implementa a mano ma con `#[derive(Scheda)]`.
+///
+/// ```
+/// use rust_tutorial::schede::{Scheda, SchedaStampabile};
+///
+/// #[derive(Scheda)]
+/// #[scheda(titolo = "Campione")]
+/// struct Campione {
+///     #[scheda(etichetta = "Codice", ordine = 1)]
+///     codice: String,
+///     #[scheda(unita = "mg", precisione = 2)]
+///     massa: f64,
+///     #[scheda(salta)]
+///     _interno: u8,
+///     laboratorio: Option<String>,
+/// }
+///
+/// let c = Campione { codice: "C-12".into(), massa: 3.456, _interno: 0, laboratorio: None };
+/// assert_eq!(Campione::ETICHETTE, ["Codice", "Massa", "Laboratorio"]);
+/// assert_eq!(c.valori(), ["C-12", "3.46 mg", "-"]);
+/// ```
+pub trait SchedaStampabile {
+    /// Titolo (`#[scheda(titolo = "...")]`, altrimenti il nome del tipo)
+    const TITOLO: &'static str;
+    /// Etichette dei campi, nell'ordine della scheda
+    const ETICHETTE: &'static [&'static str];
+
+    /// I valori gia formattati, con le unita, nell'ordine di `ETICHETTE`
+    fn valori(&self) -> Vec<String>;
+
+    /// Coppie (etichetta, valore)
+    fn voci(&self) -> Vec<(&'static str, String)> {
+        Self::ETICHETTE.iter().copied().zip(self.valori()).collect()
+    }
+
+    /// La scheda in un riquadro, con le etichette in colonna
+    fn riquadro(&self) -> Riquadro {
+        let colonna = tui_text::colonna(Self::ETICHETTE.iter().copied());
+        let mut riquadro = Riquadro::nuovo(Bordo::Singolo);
+        riquadro.riga(Self::TITOLO.to_uppercase()).separatore();
+        for (etichetta, valore) in self.voci() {
+            riquadro.riga(format!("{}  {}", tui_text::a_sinistra(etichetta, colonna), valore));
+        }
+        riquadro
+    }
+}
+
+/// Intestazione di una tabella Markdown con le colonne della scheda.
+pub fn intestazione_markdown<T: SchedaStampabile>() -> String {
+    let separatori = vec!["---"; T::ETICHETTE.len()];
+    format!("| {} |\n|{}|", T::ETICHETTE.join(" | "), separatori.join("|"))
+}
+
+/// Una riga di tabella Markdown; `|` nei valori viene protetto.
+pub fn riga_markdown<T: SchedaStampabile>(voce: &T) -> String {
+    let valori: Vec<String> = voce.valori().iter().map(|v| v.replace('|', "\\|")).collect();
+    format!("| {} |", valori.join(" | "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventario::{Periodo, Reperto};
+
+    #[test]
+    fn scheda_del_reperto() {
+        let reperto = Reperto { id: 7, nome: "Ascia | tipo Savignano".to_string(), periodo: Periodo::Medio, peso_grammi: 327.64 };
+        assert_eq!(reperto.voci()[3], ("Peso", "327.6 g".to_string()));
+        assert_eq!(intestazione_markdown::<Reperto>(), "| Id | Nome | Periodo | Peso |\n|---|---|---|---|");
+        assert_eq!(riga_markdown(&reperto), "| 7 | Ascia \\| tipo Savignano | Medio | 327.6 g |");
+
+        let testo = reperto.riquadro().testo_semplice();
+        assert!(testo.contains("REPERTO\n"), "{}", testo);
+        assert!(testo.contains("Periodo  Medio\n"), "{}", testo);
+    }
+}
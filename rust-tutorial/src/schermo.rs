@@ -0,0 +1,149 @@
+// ============================================================================
+// MODULO: SCHERMO
+// ============================================================================
+// Intestazioni e riquadri dei capitoli. La larghezza si calcola dal testo
+// (con `tui_text`), quindi non servono righe riempite di spazi a mano: basta
+// scrivere il contenuto.
+//
+// Alcuni terminali (certe console Windows, i log della CI) mostrano male i
+// caratteri per disegnare i bordi. In quel caso si usa l'uscita semplice,
+// solo ASCII:
+//
+//   NO_COLOR=1 cargo run --example cap02_ownership
+//   TUTORIAL_SEMPLICE=1 cargo run --example cap02_ownership
+//   cargo run -- --semplice          (menu e capitoli lanciati dal menu)
+// ============================================================================
+
+//! Intestazioni, riquadri e uscita semplice (senza bordi) per i terminali
+//! che non li mostrano bene.
+
+use std::sync::OnceLock;
+
+use crate::lingua::{self, Lingua};
+use crate::tui_text::{Bordo, Riquadro};
+
+/// Larghezza minima dello spazio interno di intestazioni e riquadri
+const LARGHEZZA_MINIMA: usize = 46;
+
+/// Vero se va usata l'uscita semplice: NO_COLOR o TUTORIAL_SEMPLICE
+/// impostate (e non vuote), oppure TERM=dumb. Letto una volta sola.
+pub fn semplice() -> bool {
+    static SEMPLICE: OnceLock<bool> = OnceLock::new();
+    *SEMPLICE.get_or_init(|| {
+        let impostata = |nome: &str| std::env::var_os(nome).is_some_and(|valore| !valore.is_empty());
+        impostata("NO_COLOR") || impostata("TUTORIAL_SEMPLICE") || std::env::var_os("TERM").is_some_and(|t| t == "dumb")
+    })
+}
+
+/// Stampa un riquadro, con i bordi o in solo ASCII secondo `semplice()`.
+pub fn stampa(riquadro: &Riquadro) {
+    if semplice() {
+        print!("{}", riquadro.testo_semplice());
+    } else {
+        print!("{}", riquadro.testo());
+    }
+}
+
+/// Intestazione di un capitolo, seguita da una riga vuota.
+///
+/// ```text
+/// ╔══════════════════════════════════════════════╗
+/// ║   CAPITOLO 10: LIFETIMES                     ║
+/// ╚══════════════════════════════════════════════╝
+/// ```
+pub fn intestazione(righe: &[&str]) {
+    stampa(&cornice_intestazione(righe));
+    println!();
+}
+
+/// Riquadro di riepilogo: un titolo e le sue righe. Le righe vuote restano
+/// come separatori.
+///
+/// ```text
+/// ┌──────────────────────────────────────────────┐
+/// │  LIFETIMES IN BREVE                          │
+/// ├──────────────────────────────────────────────┤
+/// │                                              │
+/// │  - 'a non allunga la vita a nessun valore    │
+/// │                                              │
+/// └──────────────────────────────────────────────┘
+/// ```
+pub fn riquadro(titolo: &str, righe: &[&str]) {
+    stampa(&cornice_riquadro(titolo, righe));
+}
+
+/// La riga finale di ogni capitolo.
+pub fn completato(capitolo: u32) {
+    let testo = match lingua::lingua() {
+        Lingua::Italiano => format!("Capitolo {} completato!", capitolo),
+        Lingua::Inglese => format!("Chapter {} completed!", capitolo),
+    };
+    let segno = if semplice() { "[OK]" } else { "✅" };
+    println!("\n{} {}", segno, testo);
+}
+
+/// Sostituisce i simboli usati nei menu (spunte, barre di avanzamento) con
+/// equivalenti ASCII quando l'uscita e semplice.
+///
+/// ```
+/// use rust_tutorial::schermo;
+///
+/// let barra = schermo::simboli("[██░░] 2/4");
+/// assert!(barra == "[██░░] 2/4" || barra == "[##..] 2/4");
+/// ```
+pub fn simboli(testo: &str) -> String {
+    if !semplice() {
+        return testo.to_string();
+    }
+    testo
+        .chars()
+        .map(|c| match c {
+            '█' => '#',
+            '░' => '.',
+            '✓' => 'x',
+            _ => c,
+        })
+        .collect()
+}
+
+fn cornice_intestazione(righe: &[&str]) -> Riquadro {
+    let mut cornice = Riquadro::nuovo(Bordo::Doppio).margine(3).larghezza_minima(LARGHEZZA_MINIMA);
+    for riga in righe {
+        cornice.riga(*riga);
+    }
+    cornice
+}
+
+fn cornice_riquadro(titolo: &str, righe: &[&str]) -> Riquadro {
+    let mut cornice = Riquadro::nuovo(Bordo::Singolo).margine(2).larghezza_minima(LARGHEZZA_MINIMA);
+    cornice.riga(titolo).separatore().riga("");
+    for riga in righe {
+        cornice.riga(*riga);
+    }
+    cornice.riga("");
+    cornice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui_text::larghezza;
+
+    #[test]
+    fn i_bordi_si_allargano_con_il_testo() {
+        let corto = cornice_intestazione(&["CAPITOLO 1"]).testo();
+        assert!(corto.lines().all(|r| larghezza(r) == LARGHEZZA_MINIMA + 2), "{}", corto);
+
+        let lungo = "x".repeat(60);
+        let riquadro = cornice_riquadro("TITOLO", &[&lungo, "", "breve"]).testo();
+        assert!(riquadro.lines().all(|r| larghezza(r) == 60 + 6), "{}", riquadro);
+    }
+
+    #[test]
+    fn uscita_semplice_solo_ascii() {
+        let testo = cornice_intestazione(&["CAPITOLO 2: OWNERSHIP"]).testo_semplice()
+            + &cornice_riquadro("RIEPILOGO", &["- 1 owner alla volta"]).testo_semplice();
+        assert!(testo.is_ascii(), "{}", testo);
+        assert!(testo.contains("RIEPILOGO\n---"));
+    }
+}
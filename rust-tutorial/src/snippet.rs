@@ -0,0 +1,189 @@
+// ============================================================================
+// MODULO: SNIPPET
+// ============================================================================
+// Micro-esempi citati nel testo dei capitoli, in snippets/. Ognuno e un
+// file `nome.rs` con il suo `main` e accanto `nome.out`, l'uscita attesa:
+//
+//   cargo run -- run                    elenco
+//   cargo run -- run shadowing          compila, esegue e confronta
+//   cargo run -- run shadowing --aggiorna   riscrive shadowing.out
+//
+// Gli snippet si compilano con `rustc` (niente dipendenze, solo std): la
+// prima riga di commento diventa la descrizione nell'elenco.
+// ============================================================================
+
+//! Esecuzione dei micro-esempi in snippets/ e confronto con l'uscita attesa.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use thiserror::Error;
+
+/// Un micro-esempio: `<nome>.rs` e `<nome>.out` nella stessa cartella.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub nome: String,
+    /// La prima riga `//` del sorgente, senza i due slash
+    pub descrizione: String,
+    pub sorgente: PathBuf,
+}
+
+#[derive(Debug, Error)]
+pub enum ErroreSnippet {
+    #[error("snippet '{0}' non trovato")]
+    NonTrovato(String),
+    #[error("la compilazione non e riuscita:\n{0}")]
+    Compilazione(String),
+    #[error("lo snippet e terminato con {stato}:\n{stderr}")]
+    Esecuzione { stato: ExitStatus, stderr: String },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Una riga del confronto tra uscita attesa e ottenuta.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Riga<'a> {
+    Uguale(&'a str),
+    /// Attesa ma non stampata
+    Mancante(&'a str),
+    /// Stampata ma non attesa
+    InPiu(&'a str),
+}
+
+/// La cartella degli snippet del tutorial.
+pub fn cartella() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("snippets")
+}
+
+/// Tutti gli snippet della cartella, in ordine di nome.
+pub fn elenco(cartella: &Path) -> io::Result<Vec<Snippet>> {
+    let mut snippet = Vec::new();
+    for voce in std::fs::read_dir(cartella)? {
+        let percorso = voce?.path();
+        if percorso.extension().is_some_and(|e| e == "rs") {
+            snippet.push(Snippet::da_file(percorso)?);
+        }
+    }
+    snippet.sort_by(|a, b| a.nome.cmp(&b.nome));
+    Ok(snippet)
+}
+
+/// Lo snippet `nome` della cartella.
+pub fn cerca(cartella: &Path, nome: &str) -> Result<Snippet, ErroreSnippet> {
+    let sorgente = cartella.join(format!("{}.rs", nome));
+    // Un nome con `/` o `..` uscirebbe dalla cartella degli snippet
+    if nome.contains(['/', '\\', '.']) || !sorgente.is_file() {
+        return Err(ErroreSnippet::NonTrovato(nome.to_string()));
+    }
+    Ok(Snippet::da_file(sorgente)?)
+}
+
+impl Snippet {
+    fn da_file(sorgente: PathBuf) -> io::Result<Snippet> {
+        let nome = sorgente.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let testo = std::fs::read_to_string(&sorgente)?;
+        let descrizione = testo
+            .lines()
+            .find_map(|r| r.trim().strip_prefix("//"))
+            .map(|r| r.trim().to_string())
+            .unwrap_or_default();
+        Ok(Snippet { nome, descrizione, sorgente })
+    }
+
+    /// Il file con l'uscita attesa
+    pub fn atteso(&self) -> PathBuf {
+        self.sorgente.with_extension("out")
+    }
+
+    /// Compila lo snippet in una cartella temporanea, lo esegue e
+    /// restituisce quello che ha stampato.
+    pub fn esegui(&self) -> Result<String, ErroreSnippet> {
+        let cartella = std::env::temp_dir().join(format!("snippet_{}_{}", self.nome, std::process::id()));
+        std::fs::create_dir_all(&cartella)?;
+        let risultato = self.compila_ed_esegui(&cartella);
+        std::fs::remove_dir_all(&cartella).ok();
+        risultato
+    }
+
+    fn compila_ed_esegui(&self, cartella: &Path) -> Result<String, ErroreSnippet> {
+        let eseguibile = cartella.join(&self.nome);
+        // Come per cargo: RUSTC se impostata, altrimenti quello nel PATH
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let compilazione = Command::new(rustc)
+            .args(["--edition", "2021", "-o"])
+            .arg(&eseguibile)
+            .arg(&self.sorgente)
+            .output()?;
+        if !compilazione.status.success() {
+            return Err(ErroreSnippet::Compilazione(String::from_utf8_lossy(&compilazione.stderr).into_owned()));
+        }
+
+        let esecuzione = Command::new(&eseguibile).output()?;
+        if !esecuzione.status.success() {
+            return Err(ErroreSnippet::Esecuzione {
+                stato: esecuzione.status,
+                stderr: String::from_utf8_lossy(&esecuzione.stderr).into_owned(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&esecuzione.stdout).into_owned())
+    }
+}
+
+/// Confronta due testi riga per riga, allineandoli sulla sottosequenza
+/// comune piu lunga: una riga in piu non sposta tutte le successive.
+///
+/// ```
+/// use rust_tutorial::snippet::{differenze, Riga};
+///
+/// let righe = differenze("a\nb\nc\n", "a\nx\nc\n");
+/// assert_eq!(righe, [Riga::Uguale("a"), Riga::Mancante("b"), Riga::InPiu("x"), Riga::Uguale("c")]);
+/// ```
+pub fn differenze<'a>(atteso: &'a str, ottenuto: &'a str) -> Vec<Riga<'a>> {
+    let a: Vec<&str> = atteso.lines().collect();
+    let b: Vec<&str> = ottenuto.lines().collect();
+    // comune[i][j]: lunghezza della sottosequenza comune di a[i..] e b[j..]
+    let mut comune = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            comune[i][j] = if a[i] == b[j] { comune[i + 1][j + 1] + 1 } else { comune[i + 1][j].max(comune[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut righe = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            righe.push(Riga::Uguale(a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && comune[i + 1][j] >= comune[i][j + 1]) {
+            righe.push(Riga::Mancante(a[i]));
+            i += 1;
+        } else {
+            righe.push(Riga::InPiu(b[j]));
+            j += 1;
+        }
+    }
+    righe
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn righe_aggiunte_e_tolte_in_coda() {
+        assert_eq!(differenze("a\nb\n", "a\nb\nc\n").last(), Some(&Riga::InPiu("c")));
+        assert_eq!(differenze("a\nb\n", "a\n"), [Riga::Uguale("a"), Riga::Mancante("b")]);
+        assert!(differenze("a\n", "a").iter().all(|r| matches!(r, Riga::Uguale(_))));
+    }
+
+    #[test]
+    fn nomi_fuori_dalla_cartella_rifiutati() {
+        for nome in ["../src/lib", "sotto/file", "inesistente"] {
+            assert!(matches!(cerca(&cartella(), nome), Err(ErroreSnippet::NonTrovato(_))), "{}", nome);
+        }
+        assert_eq!(cerca(&cartella(), "shadowing").unwrap().descrizione, "Capitolo 1: shadowing, un nuovo `let` con lo stesso nome");
+    }
+}
@@ -0,0 +1,13 @@
+/// Un peso in grammi scritto a mano: "327,6 g", "327.6", " 41 g "
+pub fn leggi_peso(testo: &str) -> Result<f64, String> {
+    let numero = testo.trim();
+    let numero = numero.strip_suffix('g').unwrap_or(numero).trim();
+    let peso: f64 = numero
+        .replace(',', ".")
+        .parse()
+        .map_err(|_| format!("'{}' non e un peso", testo.trim()))?;
+    if !peso.is_finite() || peso <= 0.0 {
+        return Err(format!("il peso deve essere positivo, non {}", peso));
+    }
+    Ok(peso)
+}
@@ -0,0 +1,78 @@
+// ============================================================================
+// MODULO: SOLUZIONI
+// ============================================================================
+// Compilato solo con `--features solutions`. Ogni soluzione sta in un file
+// a parte: cosi il codice viene compilato e testato, e lo stesso file viene
+// mostrato allo studente come testo (`include_str!`).
+// ============================================================================
+
+//! Soluzioni degli esercizi (feature `solutions`).
+
+mod leggi_peso;
+mod peso_medio;
+mod piu_pesante;
+mod prima_parola;
+
+pub use leggi_peso::leggi_peso;
+pub use peso_medio::peso_medio;
+pub use piu_pesante::piu_pesante;
+pub use prima_parola::prima_parola;
+
+/// Il sorgente della soluzione di un esercizio.
+///
+/// ```
+/// use rust_tutorial::{esercizi, soluzioni};
+///
+/// for es in &esercizi::ESERCIZI {
+///     assert!(soluzioni::sorgente(es.id).is_some(), "manca {}", es.id);
+/// }
+/// ```
+pub fn sorgente(id: &str) -> Option<&'static str> {
+    match id {
+        "prima_parola" => Some(include_str!("prima_parola.rs")),
+        "leggi_peso" => Some(include_str!("leggi_peso.rs")),
+        "piu_pesante" => Some(include_str!("piu_pesante.rs")),
+        "peso_medio" => Some(include_str!("peso_medio.rs")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventario::{Inventario, Periodo, Reperto};
+
+    #[test]
+    fn prima_parola_senza_allocare() {
+        assert_eq!(prima_parola("Ascia a margini rialzati"), "Ascia");
+        assert_eq!(prima_parola("  Fibula"), "Fibula");
+        assert_eq!(prima_parola(""), "");
+    }
+
+    #[test]
+    fn leggi_peso_accetta_virgola_e_unita() {
+        assert_eq!(leggi_peso("327,6 g"), Ok(327.6));
+        assert_eq!(leggi_peso(" 41 g "), Ok(41.0));
+        assert!(leggi_peso("circa 90").is_err());
+        assert!(leggi_peso("-3").is_err());
+        assert!(leggi_peso("0").is_err());
+    }
+
+    #[test]
+    fn piu_pesante_e_peso_medio() {
+        let reperti = [
+            Reperto::nuovo("Pugnale", Periodo::Antico, 198.3),
+            Reperto::nuovo("Spada", Periodo::Finale, 779.6),
+            Reperto::nuovo("Rasoio", Periodo::Finale, 40.4),
+        ];
+        assert_eq!(piu_pesante(&reperti).map(|r| r.nome.as_str()), Some("Spada"));
+        assert_eq!(piu_pesante(&[]), None);
+
+        let mut inv = Inventario::nuovo();
+        for r in reperti {
+            inv.aggiungi(r).unwrap();
+        }
+        assert_eq!(peso_medio(&inv, Periodo::Finale), Some(410.0));
+        assert_eq!(peso_medio(&inv, Periodo::Medio), None);
+    }
+}
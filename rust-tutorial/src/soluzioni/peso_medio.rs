@@ -0,0 +1,10 @@
+use crate::inventario::{Inventario, Periodo};
+
+/// Peso medio dei reperti del periodo; None se il periodo e vuoto
+pub fn peso_medio(inv: &Inventario, periodo: Periodo) -> Option<f64> {
+    let (somma, quanti) = inv
+        .iter()
+        .filter(|r| r.periodo == periodo)
+        .fold((0.0, 0), |(s, n), r| (s + r.peso_grammi, n + 1));
+    (quanti > 0).then(|| somma / quanti as f64)
+}
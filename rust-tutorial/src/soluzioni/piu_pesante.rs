@@ -0,0 +1,7 @@
+use crate::inventario::Reperto;
+
+/// Il reperto con il peso maggiore; None se non ce ne sono
+pub fn piu_pesante(reperti: &[Reperto]) -> Option<&Reperto> {
+    // f64 non e Ord: total_cmp da un ordine totale anche con NaN
+    reperti.iter().max_by(|a, b| a.peso_grammi.total_cmp(&b.peso_grammi))
+}
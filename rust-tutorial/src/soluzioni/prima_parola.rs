@@ -0,0 +1,4 @@
+/// La prima parola di `testo`, come slice: nessuna allocazione
+pub fn prima_parola(testo: &str) -> &str {
+    testo.split_whitespace().next().unwrap_or("")
+}
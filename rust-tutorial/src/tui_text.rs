@@ -0,0 +1,214 @@
+// ============================================================================
+// MODULO: TUI_TEXT
+// ============================================================================
+// `format!("{:<20}", nome)` riempie contando i caratteri, ma il terminale
+// conta le colonne: un emoji o un ideogramma ne occupa due, un accento
+// combinato (e + U+0301) nessuna. Con quei testi i bordi a destra dei
+// riquadri finiscono fuori posto. Qui la larghezza si misura con
+// `unicode-width`, la stessa tabella usata dai terminali.
+//
+// `Riquadro` raccoglie le righe e disegna il bordo solo alla fine, quando
+// conosce la riga piu larga: non serve contare gli spazi a mano.
+// ============================================================================
+
+//! Larghezza del testo sul terminale, allineamento in colonne e riquadri.
+
+use unicode_width::UnicodeWidthStr;
+
+/// Colonne occupate dal testo sul terminale.
+///
+/// ```
+/// use rust_tutorial::tui_text::larghezza;
+///
+/// assert_eq!(larghezza("Eta"), 3);
+/// assert_eq!(larghezza("Età"), 3);
+/// assert_eq!(larghezza("✅ ok"), 5);
+/// ```
+pub fn larghezza(testo: &str) -> usize {
+    testo.width()
+}
+
+/// Il testo seguito dagli spazi che servono ad arrivare a `colonne`; un
+/// testo piu largo resta intero.
+///
+/// ```
+/// use rust_tutorial::tui_text::a_sinistra;
+///
+/// assert_eq!(a_sinistra("✅", 4), "✅  ");
+/// assert_eq!(a_sinistra("troppo lungo", 4), "troppo lungo");
+/// ```
+pub fn a_sinistra(testo: &str, colonne: usize) -> String {
+    format!("{}{}", testo, " ".repeat(colonne.saturating_sub(larghezza(testo))))
+}
+
+/// Gli spazi che servono ad arrivare a `colonne`, seguiti dal testo.
+pub fn a_destra(testo: &str, colonne: usize) -> String {
+    format!("{}{}", " ".repeat(colonne.saturating_sub(larghezza(testo))), testo)
+}
+
+/// Larghezza della colonna che contiene tutti i testi.
+pub fn colonna<'a>(testi: impl IntoIterator<Item = &'a str>) -> usize {
+    testi.into_iter().map(larghezza).max().unwrap_or(0)
+}
+
+/// Il testo diviso in righe di al massimo `colonne`, andando a capo tra
+/// le parole; una parola piu lunga resta intera su una riga.
+///
+/// ```
+/// use rust_tutorial::tui_text::a_capo;
+///
+/// assert_eq!(a_capo("Il complesso comprende 4 reperti", 14), ["Il complesso", "comprende 4", "reperti"]);
+/// ```
+pub fn a_capo(testo: &str, colonne: usize) -> Vec<String> {
+    let mut righe: Vec<String> = Vec::new();
+    for parola in testo.split_whitespace() {
+        match righe.last_mut() {
+            Some(riga) if larghezza(riga) + 1 + larghezza(parola) <= colonne => {
+                riga.push(' ');
+                riga.push_str(parola);
+            }
+            _ => righe.push(parola.to_string()),
+        }
+    }
+    righe
+}
+
+/// Stile del bordo di un riquadro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bordo {
+    /// ┌─┐ │ └─┘
+    Singolo,
+    /// ╔═╗ ║ ╚═╝
+    Doppio,
+}
+
+impl Bordo {
+    /// Angoli, orizzontale, verticale e attacchi del separatore
+    fn simboli(self) -> [char; 8] {
+        match self {
+            Bordo::Singolo => ['┌', '┐', '└', '┘', '─', '│', '├', '┤'],
+            Bordo::Doppio => ['╔', '╗', '╚', '╝', '═', '║', '╠', '╣'],
+        }
+    }
+}
+
+/// Un riquadro costruito riga per riga.
+///
+/// ```
+/// use rust_tutorial::tui_text::{Bordo, Riquadro};
+///
+/// let mut riquadro = Riquadro::nuovo(Bordo::Singolo);
+/// riquadro.riga("Sito").separatore().riga("Savignano ✅");
+/// assert_eq!(
+///     riquadro.testo(),
+///     "┌──────────────┐\n\
+///      │ Sito         │\n\
+///      ├──────────────┤\n\
+///      │ Savignano ✅ │\n\
+///      └──────────────┘\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Riquadro {
+    bordo: Bordo,
+    margine: usize,
+    larghezza_minima: usize,
+    /// `None` e un separatore
+    righe: Vec<Option<String>>,
+}
+
+impl Riquadro {
+    pub fn nuovo(bordo: Bordo) -> Self {
+        Riquadro { bordo, margine: 1, larghezza_minima: 0, righe: Vec::new() }
+    }
+
+    /// Spazi tra il bordo e il testo, a sinistra e a destra (1 se non indicato)
+    pub fn margine(mut self, spazi: usize) -> Self {
+        self.margine = spazi;
+        self
+    }
+
+    /// Larghezza minima dello spazio dentro il bordo
+    pub fn larghezza_minima(mut self, colonne: usize) -> Self {
+        self.larghezza_minima = colonne;
+        self
+    }
+
+    pub fn riga(&mut self, testo: impl Into<String>) -> &mut Self {
+        self.righe.push(Some(testo.into()));
+        self
+    }
+
+    pub fn separatore(&mut self) -> &mut Self {
+        self.righe.push(None);
+        self
+    }
+
+    /// Colonne dentro il bordo: la riga piu larga piu i margini
+    fn interno(&self) -> usize {
+        let testo = colonna(self.righe.iter().flatten().map(String::as_str));
+        (testo + 2 * self.margine).max(self.larghezza_minima)
+    }
+
+    /// Il riquadro disegnato, una riga per ogni `\n`.
+    pub fn testo(&self) -> String {
+        let [alto_sx, alto_dx, basso_sx, basso_dx, orizzontale, verticale, attacco_sx, attacco_dx] =
+            self.bordo.simboli();
+        let interno = self.interno();
+        let linea = orizzontale.to_string().repeat(interno);
+        let mut testo = format!("{}{}{}\n", alto_sx, linea, alto_dx);
+        for riga in &self.righe {
+            match riga {
+                Some(riga) => {
+                    let contenuto = a_sinistra(riga, interno - self.margine);
+                    testo += &format!("{}{}{}{}\n", verticale, " ".repeat(self.margine), contenuto, verticale);
+                }
+                None => testo += &format!("{}{}{}\n", attacco_sx, linea, attacco_dx),
+            }
+        }
+        testo + &format!("{}{}{}\n", basso_sx, linea, basso_dx)
+    }
+
+    /// Lo stesso contenuto in solo ASCII: righe `=` al posto del bordo e
+    /// `-` al posto dei separatori.
+    pub fn testo_semplice(&self) -> String {
+        let interno = self.interno();
+        let mut testo = format!("{}\n", "=".repeat(interno));
+        for riga in &self.righe {
+            match riga {
+                Some(riga) => testo += &format!("{}\n", riga.trim_end()),
+                None => testo += &format!("{}\n", "-".repeat(interno)),
+            }
+        }
+        testo + &format!("{}\n", "=".repeat(interno))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ogni_riga_ha_la_stessa_larghezza() {
+        let mut riquadro = Riquadro::nuovo(Bordo::Doppio).margine(2);
+        riquadro
+            .riga("STATISTICHE")
+            .separatore()
+            .riga("Sito: Pontecagnano")
+            .riga("Nota: e\u{301} combinato")
+            .riga("Ideogrammi: 青銅")
+            .riga("Stato: ✅");
+        let larghezze: Vec<usize> = riquadro.testo().lines().map(larghezza).collect();
+        assert!(larghezze.iter().all(|&l| l == larghezze[0]), "{:?}\n{}", larghezze, riquadro.testo());
+        // La riga piu larga e "Sito: Pontecagnano": 18 colonne, 2 di margine per lato
+        assert_eq!(larghezze[0], 18 + 4 + 2);
+    }
+
+    #[test]
+    fn larghezza_minima_e_testo_semplice() {
+        let mut riquadro = Riquadro::nuovo(Bordo::Singolo).larghezza_minima(10);
+        riquadro.riga("ok").separatore();
+        assert!(riquadro.testo().starts_with("┌──────────┐\n│ ok       │\n├"));
+        assert_eq!(riquadro.testo_semplice(), "==========\nok\n----------\n==========\n");
+    }
+}
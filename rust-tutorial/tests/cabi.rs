@@ -0,0 +1,42 @@
+// ============================================================================
+// TEST DELL'INTESTAZIONE C (include/bronzeaxe.h)
+// ============================================================================
+// L'intestazione si rigenera solo con `--features cabi`: se qualcuno
+// aggiunge o rinomina una funzione in src/cabi.rs e non la rigenera, un
+// programma C non la trova. Qui confrontiamo i nomi senza cbindgen.
+//
+// Esegui con: cargo test --test cabi
+// ============================================================================
+
+const SORGENTE: &str = include_str!("../src/cabi.rs");
+const INTESTAZIONE: &str = include_str!("../include/bronzeaxe.h");
+
+/// I nomi delle funzioni `extern "C"` esportate dal modulo
+fn funzioni_esportate() -> Vec<&'static str> {
+    SORGENTE
+        .split("#[no_mangle]")
+        .skip(1)
+        .filter_map(|dopo| dopo.split("fn ").nth(1)?.split('(').next())
+        .collect()
+}
+
+#[test]
+fn l_intestazione_dichiara_tutte_le_funzioni() {
+    let funzioni = funzioni_esportate();
+    assert!(funzioni.contains(&"bronzeaxe_apri"), "{:?}", funzioni);
+    for funzione in funzioni {
+        assert!(
+            INTESTAZIONE.contains(&format!(" {}(", funzione)) || INTESTAZIONE.contains(&format!("*{}(", funzione)),
+            "{} manca in include/bronzeaxe.h: rigenera con cargo build --features cabi",
+            funzione
+        );
+    }
+}
+
+// L'API C apre l'inventario del catalogo: c'e solo con la feature json
+#[cfg(feature = "json")]
+#[test]
+fn versione_api_allineata() {
+    let versione = rust_tutorial::cabi::BRONZEAXE_VERSIONE_API;
+    assert!(INTESTAZIONE.contains(&format!("#define BRONZEAXE_VERSIONE_API {}\n", versione)));
+}
@@ -0,0 +1,75 @@
+// ============================================================================
+// TEST DEL CAPITOLO 3: STRUCT, ENUM E PATTERN MATCHING
+// ============================================================================
+// Il sorgente del capitolo viene incluso con `include!` (vedi tests/cap04.rs):
+// struct, enum e metodi privati dell'esempio si usano qui come se fossero
+// stati scritti in questo file.
+//
+// Esegui con: cargo test --test cap03
+// ============================================================================
+
+// Lo stile didattico del capitolo (match su un solo caso, `get(0)`, campi
+// mostrati ma mai letti) non e quello che clippy suggerisce
+#![allow(dead_code, clippy::single_match, clippy::get_first, clippy::useless_vec)]
+
+include!("../examples/cap03_strutture.rs");
+
+fn pezzo(id: u32, tipo: TipoReperto, periodo: PeriodoStorico, stato: StatoConservazione) -> Pezzo {
+    Pezzo { id, nome: format!("Pezzo {}", id), tipo, periodo, stato }
+}
+
+fn catalogo_di_prova() -> Catalogo {
+    let mut catalogo = Catalogo::nuovo();
+    catalogo.aggiungi(pezzo(1, TipoReperto::Utensile("Ascia".into()), PeriodoStorico::BronzoRecente, StatoConservazione::Buono));
+    catalogo.aggiungi(pezzo(2, TipoReperto::Arma("Spada".into(), 65.0), PeriodoStorico::BronzoFinale, StatoConservazione::Buono));
+    catalogo.aggiungi(pezzo(3, TipoReperto::Frammento, PeriodoStorico::BronzoFinale, StatoConservazione::Frammento));
+    catalogo.aggiungi(pezzo(
+        4,
+        TipoReperto::Frammento,
+        PeriodoStorico::Sconosciuto("Decontestualizzato".into()),
+        StatoConservazione::Frammento,
+    ));
+    catalogo
+}
+
+#[test]
+fn catalogo_trova_per_id() {
+    let catalogo = catalogo_di_prova();
+    assert_eq!(catalogo.totale(), 4);
+    assert_eq!(catalogo.trova_per_id(2).map(|p| p.nome.as_str()), Some("Pezzo 2"));
+    assert!(catalogo.trova_per_id(99).is_none());
+}
+
+#[test]
+fn catalogo_filtra_per_periodo_e_stato() {
+    let catalogo = catalogo_di_prova();
+    let finale: Vec<u32> = catalogo.cerca_per_periodo(&PeriodoStorico::BronzoFinale).iter().map(|p| p.id).collect();
+    assert_eq!(finale, [2, 3]);
+    assert_eq!(catalogo.conta_per_stato(&StatoConservazione::Frammento), 2);
+    assert_eq!(catalogo.conta_per_stato(&StatoConservazione::Ottimo), 0);
+}
+
+/// `#[derive(PartialEq)]` confronta anche i dati della variante
+#[test]
+fn periodi_sconosciuti_diversi_non_sono_uguali() {
+    let catalogo = catalogo_di_prova();
+    let uguale = PeriodoStorico::Sconosciuto("Decontestualizzato".into());
+    let diverso = PeriodoStorico::Sconosciuto("Sporadico".into());
+    assert_eq!(catalogo.cerca_per_periodo(&uguale).len(), 1);
+    assert!(catalogo.cerca_per_periodo(&diverso).is_empty());
+    assert_eq!(diverso.nome(), "Periodo sconosciuto");
+}
+
+#[test]
+fn sito_conta_i_reperti_e_misura_le_distanze() {
+    let mut sito = SitoArcheologico::nuovo("Pontecagnano", 40.64, 14.87);
+    assert_eq!(sito.distanza_da(40.64, 14.87), 0.0);
+    // Pontecagnano - Roma: circa 240 km in linea d'aria
+    let da_roma = sito.distanza_da(41.90, 12.49);
+    assert!((230.0..250.0).contains(&da_roma), "{}", da_roma);
+
+    sito.aggiungi_reperto("Spada");
+    sito.aggiungi_reperto("Fibula");
+    assert_eq!(sito.numero_reperti(), 2);
+    assert!(sito.descrizione().starts_with("Sito: Pontecagnano"));
+}
@@ -0,0 +1,66 @@
+// ============================================================================
+// TEST DEL CAPITOLO 4: GESTIONE DEGLI ERRORI
+// ============================================================================
+// Gli esempi non sono una libreria: le loro funzioni sono private e non si
+// possono importare con `use`. `include!` copia il sorgente del capitolo in
+// questo file, quindi le sue funzioni diventano funzioni di questo crate di
+// test. Il `main` del capitolo resta, ma non viene eseguito: con
+// `cargo test` il punto d'ingresso e quello generato da Cargo per i test.
+//
+// Esegui con: cargo test --test cap04
+// ============================================================================
+
+// Il capitolo scrive `vec![...]` e `a < x || x > b` per chiarezza didattica
+#![allow(clippy::useless_vec, clippy::manual_range_contains)]
+
+include!("../examples/cap04_errori.rs");
+
+#[test]
+fn leggi_eta_accetta_solo_interi_non_negativi() {
+    assert_eq!(leggi_eta("42"), Ok(42));
+    assert_eq!(leggi_eta("0"), Ok(0));
+    assert_eq!(leggi_eta("-3"), Err("L'eta non puo essere negativa: -3".to_string()));
+    // L'errore di parsing arriva come testo, grazie a map_err
+    assert!(leggi_eta("quaranta").is_err());
+}
+
+#[test]
+fn valida_reperto_riporta_il_primo_errore() {
+    assert_eq!(
+        valida_reperto("Ascia", "bronzo", "327.6", "1890").unwrap(),
+        "Ascia (bronzo) - 327.6g"
+    );
+    assert!(matches!(valida_reperto("", "bronzo", "1", "1890"), Err(ErroreReperto::NomeVuoto)));
+    assert!(matches!(
+        valida_reperto("Ascia", "bronzo", "tanto", "1890"),
+        Err(ErroreReperto::PesoNonValido(p)) if p == "tanto"
+    ));
+    assert!(matches!(
+        valida_reperto("Ascia", "bronzo", "-2", "1890"),
+        Err(ErroreReperto::PesoNegativo(p)) if p == -2.0
+    ));
+}
+
+/// `?` converte ParseIntError in ErroreReperto grazie all'impl From
+#[test]
+fn anno_non_numerico_diventa_anno_non_valido() {
+    let errore = valida_reperto("Ascia", "bronzo", "327.6", "ieri").unwrap_err();
+    assert!(matches!(errore, ErroreReperto::AnnoNonValido(_)));
+    assert!(errore.to_string().starts_with("Anno non valido"));
+}
+
+#[test]
+fn analizza_dato_distingue_interi_e_decimali() {
+    assert_eq!(analizza_dato("7").unwrap(), "Intero valido: 7");
+    assert_eq!(analizza_dato("2.5").unwrap(), "Decimale valido: 2.50");
+    assert!(analizza_dato("-1").is_err());
+    assert!(analizza_dato("abc").is_err());
+}
+
+#[test]
+fn processa_scavo_controlla_prima_i_dati() {
+    assert!(processa_scavo("", 2000, 5).is_err());
+    assert!(processa_scavo("Pontecagnano", 1850, 5).unwrap_err().contains("1850"));
+    assert!(processa_scavo("Pontecagnano", 2000, 0).is_err());
+    assert!(processa_scavo("Pontecagnano", 2000, 31).unwrap().contains("densita 1.0/anno"));
+}
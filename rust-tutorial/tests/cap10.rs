@@ -0,0 +1,76 @@
+// ============================================================================
+// TEST DEL CAPITOLO 10: LIFETIMES
+// ============================================================================
+// Il sorgente del capitolo viene incluso con `include!` (vedi tests/cap04.rs).
+// Alcuni test verificano i valori, altri soprattutto che il codice compili:
+// se una firma perdesse il suo lifetime, questi test smetterebbero di
+// compilare prima ancora di essere eseguiti.
+//
+// Esegui con: cargo test --test cap10
+// ============================================================================
+
+include!("../examples/cap10_lifetimes.rs");
+
+#[test]
+fn funzioni_sul_catalogo() {
+    let catalogo = crea_catalogo();
+    assert_eq!(piu_pesante(&catalogo[0], &catalogo[3]).id, 4);
+    assert_eq!(primi_n(&catalogo, 2).len(), 2);
+    assert_eq!(primi_n(&catalogo, 50).len(), catalogo.len());
+    assert_eq!(dello_stesso_sito(&catalogo, "Savignano").len(), 3);
+    assert_eq!(cerca_per_nome(&catalogo, "SPADA").map(|r| r.id), Some(4));
+    assert!(cerca_per_nome(&catalogo, "elmo").is_none());
+}
+
+/// Il risultato di `cerca_per_nome` dipende solo dal catalogo: il testo
+/// cercato puo sparire prima che il risultato venga usato
+#[test]
+fn il_testo_cercato_puo_morire_prima_del_risultato() {
+    let catalogo = crea_catalogo();
+    let trovato = {
+        let testo = String::from("fibula");
+        cerca_per_nome(&catalogo, &testo)
+    };
+    assert_eq!(trovato.unwrap().id, 1);
+}
+
+/// `Vetrina::piu_pesante` restituisce `&'a Reperto`: il reperto appartiene
+/// al catalogo e sopravvive alla vetrina
+#[test]
+fn il_reperto_sopravvive_alla_vetrina() {
+    let catalogo = crea_catalogo();
+    let titolo = String::from("Armi");
+    let reperto = {
+        let vetrina = Vetrina::nuova(&titolo, &catalogo[1..4]);
+        assert_eq!(vetrina.titolo(), "Armi");
+        vetrina.piu_pesante().unwrap()
+    };
+    assert_eq!(reperto.nome, "Spada a lingua da presa");
+    assert!(Vetrina::nuova("Vuota", &[]).piu_pesante().is_none());
+}
+
+#[test]
+fn etichette_e_sigle_sono_slice_del_testo() {
+    let scheda = String::from("  BR-12 : Fibula ad arco ");
+    let etichetta = Etichetta::da_scheda(&scheda);
+    assert_eq!((etichetta.codice, etichetta.descrizione), ("BR-12", "Fibula ad arco"));
+    assert_eq!(Etichetta::da_scheda("senza codice").codice, "");
+
+    assert_eq!(sigla("Ascia a margini rialzati"), "Ascia");
+    assert_eq!(sigla(""), "");
+    assert_eq!(periodo_canonico("bf"), "Bronzo Finale");
+    assert_eq!(periodo_canonico("??"), "Periodo sconosciuto");
+}
+
+#[test]
+fn indice_per_sito() {
+    let catalogo = crea_catalogo();
+    let indice = IndicePerSito::costruisci(&catalogo);
+    let siti: Vec<&str> = indice.siti().iter().map(|(sito, _)| *sito).collect();
+    assert_eq!(siti, ["Pontecagnano", "Savignano Irpino", "Savignano sul Panaro", "Toppo Daguzzo"]);
+
+    let mut pesanti: Vec<u32> = indice.piu_pesanti_di(200.0).map(|r| r.id).collect();
+    pesanti.sort();
+    assert_eq!(pesanti, [3, 4]);
+    assert_eq!(descrivi(&catalogo[1]), "#2 Pugnale triangolare (Toppo Daguzzo, Bronzo Antico)");
+}
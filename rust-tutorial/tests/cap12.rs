@@ -0,0 +1,71 @@
+// ============================================================================
+// TEST DEL CAPITOLO 12: CLOSURE E ITERATORI AVANZATI
+// ============================================================================
+// Il sorgente del capitolo viene incluso con `include!` (vedi tests/cap04.rs).
+// Gli iteratori scritti a mano sono il caso tipico in cui un test vale piu
+// dell'output a video: il primo e l'ultimo elemento e la fine della sequenza
+// sono i punti dove si sbaglia.
+//
+// Esegui con: cargo test --test cap12
+// ============================================================================
+
+include!("../examples/cap12_closures_iteratori.rs");
+
+#[test]
+fn numeri_inventario_progressivi() {
+    let numeri: Vec<String> = NumeriInventario::nuovo("SAV", 2024).take(3).collect();
+    assert_eq!(numeri, ["SAV-2024-0001", "SAV-2024-0002", "SAV-2024-0003"]);
+    assert_eq!(NumeriInventario::nuovo("PNT", 2023).nth(99).unwrap(), "PNT-2023-0100");
+}
+
+#[test]
+fn griglia_riga_per_riga_e_poi_finita() {
+    let mut griglia = Griglia::nuova(3, 2);
+    let quadrati: Vec<String> = griglia.by_ref().collect();
+    assert_eq!(quadrati, ["A1", "B1", "C1", "A2", "B2", "C2"]);
+    // Un iteratore finito continua a restituire None
+    assert_eq!(griglia.next(), None);
+    assert_eq!(Griglia::nuova(4, 0).count(), 0);
+}
+
+#[test]
+fn filtri_restituiti_da_funzioni() {
+    let inventario = crea_inventario();
+    let conta = |filtro: &dyn Fn(&Reperto) -> bool| inventario.iter().filter(|r| filtro(r)).count();
+
+    assert_eq!(conta(&filtro_periodo(Periodo::Recente)), 3);
+    assert_eq!(conta(&*filtro_da_menu("pesanti")), 3);
+    assert_eq!(conta(&*filtro_da_menu("leggeri")), 3);
+    assert_eq!(conta(&*filtro_da_menu("qualsiasi")), 7);
+    assert_eq!(conta(&entrambi(filtro_periodo(Periodo::Recente), filtro_da_menu("pesanti"))), 1);
+}
+
+#[test]
+fn closure_fn_e_fnmut() {
+    let inventario = crea_inventario();
+    let nomi = applica_a_ogni(&inventario, |r| r.nome.to_uppercase());
+    assert_eq!(nomi[0], "FIBULA AD ARCO");
+
+    let mut totale = 0.0;
+    inventario.per_ogni(|r| totale += r.peso_grammi);
+    assert!((totale - 1741.0).abs() < 1e-9);
+
+    let mut chiamate = Vec::new();
+    esegui_una_volta(|| chiamate.push("una volta"));
+    assert_eq!(chiamate, ["una volta"]);
+}
+
+#[test]
+fn adattatori_sui_reperti() {
+    let inventario = crea_inventario();
+    let medi: Vec<u32> = inventario.iter().del_periodo(Periodo::Medio).map(|r| r.id).collect();
+    assert_eq!(medi, [3, 5]);
+
+    // per_sito raggruppa solo i reperti consecutivi dello stesso sito
+    let gruppi: Vec<(String, usize)> = vec![&inventario.reperti[&2], &inventario.reperti[&5], &inventario.reperti[&4]]
+        .into_iter()
+        .per_sito()
+        .map(|(sito, reperti)| (sito, reperti.len()))
+        .collect();
+    assert_eq!(gruppi, [("Toppo Daguzzo".to_string(), 2), ("Pontecagnano".to_string(), 1)]);
+}
@@ -0,0 +1,245 @@
+// ============================================================================
+// TEST DEL CATALOGO (src/catalogo/)
+// ============================================================================
+// L'inventario del capitolo 9 usato da fuori, come lo userebbe un altro
+// programma: solo l'API pubblica di `rust_tutorial::catalogo`.
+//
+// Esegui con: cargo test --test catalogo
+// ============================================================================
+
+// Il catalogo esiste solo con la feature json (predefinita)
+#![cfg(feature = "json")]
+
+use rust_tutorial::catalogo::prelude::*;
+use rust_tutorial::catalogo::magazzino::Magazzino;
+use rust_tutorial::catalogo::modelli::{CampagnaScavo, RuoloUtente};
+use rust_tutorial::catalogo::statistiche::CacheStatistiche;
+
+fn inventario_di_prova() -> Inventario {
+    let mut inv = Inventario::nuovo();
+    let reperti = [
+        ("Ascia a margini rialzati", Materiale::Bronzo, Periodo::BronzoMedio, 327.6),
+        ("Spada a lingua da presa", Materiale::Bronzo, Periodo::BronzoFinale, 779.6),
+        ("Fuseruola", Materiale::Ceramica, Periodo::BronzoRecente, 4.1),
+    ];
+    for (nome, materiale, periodo, peso) in reperti {
+        let reperto = Reperto::nuovo(nome, materiale, periodo, Conservazione::Buono, "Frattesina")
+            .con_misurazioni(Misurazioni::nuove().con_peso(peso));
+        inv.aggiungi(reperto).unwrap();
+    }
+    inv
+}
+
+#[test]
+fn aggiungi_cerca_e_rimuovi() {
+    let mut inv = inventario_di_prova();
+    assert_eq!(inv.totale(), 3);
+    assert_eq!(inv.cerca_per_nome("SPADA")[0].id, 2);
+    assert_eq!(inv.cerca_per_materiale(&Materiale::Bronzo).len(), 2);
+
+    let vuoto = Reperto::nuovo("  ", Materiale::Bronzo, Periodo::Sconosciuto, Conservazione::Buono, "Frattesina");
+    assert!(matches!(inv.aggiungi(vuoto), Err(ErroreInventario::NomeVuoto)));
+    assert!(matches!(inv.cerca_per_id(9), Err(ErroreInventario::RepertoNonTrovato(9))));
+}
+
+#[test]
+fn statistiche_incrementali_uguali_al_ricalcolo() {
+    let mut inv = inventario_di_prova();
+    let da_zero = CacheStatistiche::da_reperti(inv.tutti());
+    let incrementali = inv.statistiche();
+    assert!(incrementali.coincide_con(&da_zero));
+    assert_eq!(incrementali.totale, 3);
+    assert!((incrementali.peso_totale - 1111.3).abs() < 1e-9);
+}
+
+#[test]
+fn l_esportazione_riconosce_il_proprio_catalogo() {
+    let mut inv = inventario_di_prova();
+    let esportazione = inv.to_json().unwrap();
+    assert!(inv.verifica_snapshot(&esportazione).unwrap());
+
+    inv.aggiungi_nota(1, "Restaurata nel 2019").unwrap();
+    assert!(!inv.verifica_snapshot(&esportazione).unwrap());
+}
+
+#[test]
+fn l_impronta_segue_anche_le_campagne_esportate() {
+    let mut inv = inventario_di_prova();
+    let campagna = |decreto: Option<&str>| CampagnaScavo {
+        codice: "FRT-2024".to_string(),
+        sito: "Frattesina".to_string(),
+        anno: 2024,
+        concessione: Some("Concessione 7/2024".to_string()),
+        autorizzazione_ministeriale: Some("Prot. 8831".to_string()),
+        decreto_deposito: decreto.map(String::from),
+    };
+    inv.registra_campagna_scavo(campagna(None)).unwrap();
+    inv.assegna_campagna_scavo(1, "FRT-2024").unwrap();
+    let esportazione = inv.to_json().unwrap();
+    assert!(inv.verifica_snapshot(&esportazione).unwrap());
+
+    // Le schede non cambiano, la campagna esportata con loro si
+    inv.registra_campagna_scavo(campagna(Some("Decreto 54/2025"))).unwrap();
+    assert!(!inv.verifica_snapshot(&esportazione).unwrap());
+}
+
+#[test]
+fn il_builder_controlla_i_campi_obbligatori() {
+    let pugnale = Reperto::builder()
+        .nome("Pugnale con manico in osso")
+        .componente(Componente::parte("lama", Materiale::Bronzo, Some(70.0)))
+        .componente(Componente::parte("manico", Materiale::Osso, Some(30.0)))
+        .conservazione(Conservazione::Discreto)
+        .sito("Frattesina");
+    let reperto = pugnale.clone().build().unwrap();
+    assert_eq!(reperto.periodo, Periodo::Sconosciuto);
+    assert_eq!(reperto.materiale(), Some(&Materiale::Bronzo));
+    assert_eq!(inventario_di_prova().aggiungi(reperto).unwrap(), 4);
+
+    assert!(matches!(Reperto::builder().sito("Frattesina").build(), Err(ErroreReperto::NomeVuoto)));
+    assert!(matches!(pugnale.clone().coordinate(91.0, 11.6).build(), Err(ErroreReperto::DatiNonValidi(_))));
+    // Le stesse regole di aggiungi: le parti non superano il 100%
+    let troppo = pugnale.componente(Componente::parte("rivetti", Materiale::Bronzo, Some(5.0)));
+    assert!(matches!(troppo.build(), Err(ErroreReperto::DatiNonValidi(_))));
+}
+
+#[test]
+fn gli_identificativi_globali_sopravvivono_all_unione() {
+    let scavo = |strategia, sito: &str| {
+        let mut inv = Inventario::nuovo_con_strategia(strategia);
+        for nome in ["Ascia", "Spillone"] {
+            inv.aggiungi(Reperto::nuovo(nome, Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Buono, sito)).unwrap();
+        }
+        inv
+    };
+    let savignano = scavo(StrategiaId::prefisso_sito(), "Savignano Irpino");
+    let frattesina = scavo(StrategiaId::prefisso_sito().con_prefisso("Frattesina", "FRT"), "Frattesina");
+    let codici = |inv: &Inventario| inv.tutti().iter().map(|r| r.identificativo.clone().unwrap()).collect::<Vec<_>>();
+    assert_eq!(codici(&savignano), ["SAV-0001", "SAV-0002"]);
+    assert_eq!(codici(&frattesina), ["FRT-0001", "FRT-0002"]);
+
+    // Entrambi hanno un reperto #1: nell'unione gli id cambiano, i codici no
+    let mut unione = Inventario::nuovo_con_strategia(StrategiaId::prefisso_sito());
+    for reperto in savignano.tutti().into_iter().chain(frattesina.tutti()) {
+        unione.aggiungi(reperto.clone()).unwrap();
+    }
+    assert_eq!(unione.cerca_per_identificativo("FRT-0001").unwrap().id, 3);
+    assert!(matches!(unione.aggiungi(savignano.tutti()[0].clone()), Err(ErroreInventario::DatiNonValidi(_))));
+    // La numerazione di SAV riprende dopo i codici arrivati con l'unione
+    let nuovo = Reperto::nuovo("Falcetto", Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Buono, "Savignano Irpino");
+    let id = unione.aggiungi(nuovo).unwrap();
+    assert_eq!(unione.cerca_per_id(id).unwrap().identificativo.as_deref(), Some("SAV-0003"));
+
+    let uuid = scavo(StrategiaId::Uuid, "Frattesina");
+    let [a, b] = codici(&uuid).try_into().unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a.len(), 36);
+    assert!(uuid.to_json().unwrap().contains(&a));
+
+    let strategia = StrategiaId::prefisso_sito().con_prefisso("Frattesina", "FRT");
+    let json = serde_json::to_string(&strategia).unwrap();
+    assert_eq!(json, r#"{"tipo":"prefisso_sito","prefissi":{"Frattesina":"FRT"},"cifre":4}"#);
+    assert_eq!(serde_json::from_str::<StrategiaId>(&json).unwrap(), strategia);
+    // Con la strategia di sempre non c'e nessun identificativo
+    assert!(inventario_di_prova().tutti().iter().all(|r| r.identificativo.is_none()));
+}
+
+#[test]
+fn gli_errori_conservano_la_causa() {
+    fn leggi(testo: &str) -> Result<Vec<Reperto>, ErroreInventario> {
+        Ok(serde_json::from_str(testo)?)
+    }
+    let errore = anyhow::Error::from(leggi("[{\"nome\": ").unwrap_err()).context("caricamento dell'archivio");
+    let catena: Vec<String> = errore.chain().map(|e| e.to_string()).collect();
+    assert_eq!(catena.len(), 3);
+    assert_eq!(catena[1], "Errore serializzazione");
+    // La causa compare una volta sola nella catena stampata
+    assert_eq!(format!("{:#}", errore).matches(&catena[2]).count(), 1);
+    assert!(errore.chain().nth(2).unwrap().is::<serde_json::Error>());
+    assert!(matches!(errore.downcast_ref(), Some(ErroreInventario::SerializzazioneErrore(_))));
+}
+
+/// Un magazzino diverso da quello predefinito: un Vec tenuto in ordine di id
+#[derive(Debug, Default)]
+struct Elenco(Vec<Reperto>);
+
+impl Magazzino for Elenco {
+    fn get(&self, id: u32) -> Option<&Reperto> {
+        self.0.iter().find(|r| r.id == id)
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Reperto> {
+        self.0.iter_mut().find(|r| r.id == id)
+    }
+
+    fn insert(&mut self, reperto: Reperto) {
+        match self.0.binary_search_by_key(&reperto.id, |r| r.id) {
+            Ok(i) => self.0[i] = reperto,
+            Err(i) => self.0.insert(i, reperto),
+        }
+    }
+
+    fn remove(&mut self, id: u32) -> Option<Reperto> {
+        let i = self.0.iter().position(|r| r.id == id)?;
+        Some(self.0.remove(i))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &Reperto> + '_> {
+        Box::new(self.0.iter())
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = &mut Reperto> + '_> {
+        Box::new(self.0.iter_mut())
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Ricerche e statistiche non dipendono da dove stanno i reperti
+#[test]
+fn lo_stesso_inventario_su_un_altro_magazzino() {
+    let mut inv = Inventario::con_magazzini(Elenco::default(), Elenco::default(), StrategiaId::prefisso_sito());
+    for reperto in inventario_di_prova().tutti() {
+        inv.aggiungi(reperto.clone()).unwrap();
+    }
+    inv.rimuovi(2).unwrap();
+    assert_eq!(inv.cerca_per_nome("fuseruola")[0].id, 3);
+    assert!((inv.statistiche().peso_totale - 331.7).abs() < 1e-9);
+
+    // Riaperto, numerazione e codici ripartono dopo quelli presenti
+    let (attivi, radiati) = inv.in_magazzini();
+    let mut riaperto = Inventario::con_magazzini(attivi, radiati, StrategiaId::prefisso_sito());
+    assert_eq!(riaperto.statistiche().totale, 2);
+    let ago = Reperto::nuovo("Ago", Materiale::Bronzo, Periodo::BronzoFinale, Conservazione::Buono, "Frattesina");
+    let id = riaperto.aggiungi(ago).unwrap();
+    assert_eq!((id, riaperto.cerca_per_id(id).unwrap().identificativo.as_deref()), (4, Some("FRA-0004")));
+}
+
+#[test]
+fn la_versione_sale_solo_sui_reperti_cambiati() {
+    let mut inv = inventario_di_prova();
+    inv.modifica_tutti(|r| {
+        if r.materiale() == Some(&Materiale::Ceramica) {
+            r.conservazione = Conservazione::Discreto;
+        }
+    });
+    let versioni: Vec<u64> = inv.tutti().iter().map(|r| r.versione).collect();
+    assert_eq!(versioni, [0, 0, 1]);
+    let da_zero = CacheStatistiche::da_reperti(inv.tutti());
+    assert!(inv.statistiche().coincide_con(&da_zero));
+}
+
+#[test]
+fn una_modifica_rifiutata_non_cambia_la_versione() {
+    let mut inv = inventario_di_prova();
+    inv.cambia_stato(1, StatoCatalogazione::Catalogato, RuoloUtente::Catalogatore).unwrap();
+    inv.cambia_stato(1, StatoCatalogazione::Verificato, RuoloUtente::Revisore).unwrap();
+    let prima = inv.cerca_per_id(1).unwrap().versione;
+
+    // Il catalogatore non tocca una scheda verificata e non la pubblica
+    assert!(inv.modifica_come(1, RuoloUtente::Catalogatore, |r| r.nome.clear()).is_err());
+    assert!(inv.cambia_stato(1, StatoCatalogazione::Pubblicato, RuoloUtente::Catalogatore).is_err());
+    assert_eq!(inv.cerca_per_id(1).unwrap().versione, prima);
+}
@@ -0,0 +1,153 @@
+// ============================================================================
+// TEST DELLE FEATURE DI CARGO
+// ============================================================================
+// Le dipendenze pesanti sono opzionali (vedi [features] in Cargo.toml). Se
+// un esempio usa un crate opzionale senza dichiararlo in
+// `required-features`, `cargo build --no-default-features` fallisce: qui
+// ce ne accorgiamo con un normale `cargo test`, leggendo Cargo.toml e i
+// sorgenti. La libreria e il menu li usano solo dietro
+// `#[cfg(feature = "...")]`; i file di una cartella valgono come il
+// modulo che la dichiara (src/catalogo/ esiste solo se esiste
+// src/catalogo.rs, che chiede `json`).
+//
+// Per compilare davvero le combinazioni principali:
+//   cargo build --no-default-features
+//   cargo build --no-default-features --features cli --example cap14_cli
+//   cargo test --features full
+//
+// Esegui con: cargo test --test feature
+// ============================================================================
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use toml::Table;
+
+fn manifesto() -> Table {
+    include_str!("../Cargo.toml").parse().expect("Cargo.toml non valido")
+}
+
+/// Nome della dipendenza opzionale -> nome del crate nel codice (`signal-hook` -> `signal_hook`)
+fn dipendenze_opzionali(manifesto: &Table) -> BTreeMap<String, String> {
+    manifesto["dependencies"]
+        .as_table()
+        .unwrap()
+        .iter()
+        .filter(|(_, d)| d.get("optional").and_then(|o| o.as_bool()) == Some(true))
+        .map(|(nome, _)| (nome.clone(), nome.replace('-', "_")))
+        .collect()
+}
+
+/// Le dipendenze attivate da una feature, seguendo le feature che include
+fn attivate(manifesto: &Table, feature: &str, dipendenze: &mut BTreeSet<String>) {
+    for voce in manifesto["features"][feature].as_array().unwrap() {
+        let voce = voce.as_str().unwrap();
+        match voce.strip_prefix("dep:") {
+            Some(dipendenza) => {
+                dipendenze.insert(dipendenza.to_string());
+            }
+            None => attivate(manifesto, voce, dipendenze),
+        }
+    }
+}
+
+/// Le dipendenze opzionali nominate nel sorgente (`clap::`, `use clap`)
+fn usate(sorgente: &str, opzionali: &BTreeMap<String, String>) -> BTreeSet<String> {
+    opzionali
+        .iter()
+        .filter(|(_, nel_codice)| {
+            sorgente.contains(&format!("{}::", nel_codice)) || sorgente.contains(&format!("use {}", nel_codice))
+        })
+        .map(|(nome, _)| nome.clone())
+        .collect()
+}
+
+#[test]
+fn ogni_esempio_dichiara_le_feature_che_usa() {
+    let manifesto = manifesto();
+    let opzionali = dipendenze_opzionali(&manifesto);
+    for esempio in manifesto["example"].as_array().unwrap() {
+        let nome = esempio["name"].as_str().unwrap();
+        let sorgente = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(esempio["path"].as_str().unwrap())).unwrap();
+        let mut disponibili = BTreeSet::new();
+        for feature in esempio.get("required-features").and_then(|f| f.as_array()).into_iter().flatten() {
+            attivate(&manifesto, feature.as_str().unwrap(), &mut disponibili);
+        }
+        let mancanti: Vec<String> = usate(&sorgente, &opzionali).difference(&disponibili).cloned().collect();
+        assert!(mancanti.is_empty(), "{}: usa {:?} senza la feature in required-features", nome, mancanti);
+    }
+}
+
+/// Le dipendenze attivate dalle feature nominate nei `#[cfg(feature = "...")]`
+/// del sorgente, e nel `#![cfg(feature = "...")]` di un modulo che esiste solo
+/// con una feature
+fn attivate_nel_sorgente(manifesto: &Table, sorgente: &str) -> BTreeSet<String> {
+    attivate_dopo(manifesto, &sorgente.replace("#![cfg(", "#[cfg("), "#[cfg(feature = \"")
+}
+
+/// Solo quelle del `#![cfg(feature = "...")]` del modulo: valgono per
+/// tutti i file della sua cartella
+fn attivate_dal_modulo(manifesto: &Table, sorgente: &str) -> BTreeSet<String> {
+    attivate_dopo(manifesto, sorgente, "#![cfg(feature = \"")
+}
+
+fn attivate_dopo(manifesto: &Table, sorgente: &str, attributo: &str) -> BTreeSet<String> {
+    let mut dipendenze = BTreeSet::new();
+    for pezzo in sorgente.split(attributo).skip(1) {
+        let feature = pezzo.split('"').next().unwrap();
+        attivate(manifesto, feature, &mut dipendenze);
+    }
+    dipendenze
+}
+
+#[test]
+fn libreria_e_menu_usano_le_opzionali_solo_dietro_cfg() {
+    let manifesto = manifesto();
+    let opzionali = dipendenze_opzionali(&manifesto);
+    let mut cartelle = vec![(Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), BTreeSet::new())];
+    while let Some((cartella, ereditate)) = cartelle.pop() {
+        for voce in std::fs::read_dir(&cartella).unwrap() {
+            let percorso = voce.unwrap().path();
+            if percorso.is_dir() {
+                let mut ereditate = ereditate.clone();
+                if let Ok(modulo) = std::fs::read_to_string(percorso.with_extension("rs")) {
+                    ereditate.extend(attivate_dal_modulo(&manifesto, &modulo));
+                }
+                cartelle.push((percorso, ereditate));
+            } else if percorso.extension().is_some_and(|e| e == "rs") {
+                let sorgente = std::fs::read_to_string(&percorso).unwrap();
+                let mut disponibili = attivate_nel_sorgente(&manifesto, &sorgente);
+                disponibili.extend(ereditate.iter().cloned());
+                let mancanti: Vec<String> = usate(&sorgente, &opzionali).difference(&disponibili).cloned().collect();
+                assert!(mancanti.is_empty(), "{} usa {:?} senza #[cfg(feature = ...)]", percorso.display(), mancanti);
+            }
+        }
+    }
+}
+
+#[test]
+fn full_attiva_tutte_le_dipendenze_opzionali() {
+    let manifesto = manifesto();
+    let mut con_full = BTreeSet::new();
+    attivate(&manifesto, "full", &mut con_full);
+    let tutte: BTreeSet<String> = dipendenze_opzionali(&manifesto).into_keys().collect();
+    assert_eq!(con_full, tutte);
+}
+
+#[test]
+fn di_default_solo_json() {
+    let manifesto = manifesto();
+    let predefinite: Vec<&str> =
+        manifesto["features"]["default"].as_array().unwrap().iter().map(|f| f.as_str().unwrap()).collect();
+    assert_eq!(predefinite, ["json"]);
+    let mut dipendenze = BTreeSet::new();
+    attivate(&manifesto, "default", &mut dipendenze);
+    assert_eq!(dipendenze, BTreeSet::from(["serde_json".to_string()]));
+    // Ogni feature di un sottosistema sta anche in `full`
+    let full = manifesto["features"]["full"].as_array().unwrap();
+    for feature in manifesto["features"].as_table().unwrap().keys() {
+        if !["default", "full", "solutions", "cabi"].contains(&feature.as_str()) {
+            assert!(full.iter().any(|f| f.as_str() == Some(feature)), "{} manca in full", feature);
+        }
+    }
+}
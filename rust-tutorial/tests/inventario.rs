@@ -0,0 +1,49 @@
+// ============================================================================
+// TEST DI INTEGRAZIONE: INVENTARIO
+// ============================================================================
+// Ogni file in `tests/` e un crate a parte: vede solo l'API pubblica della
+// libreria, esattamente come la vedrebbe chi la usa come dipendenza.
+//
+// Esegui con: cargo test --test inventario
+// ============================================================================
+
+use rust_tutorial::inventario::{ErroreInventario, Inventario, Periodo, Reperto};
+
+/// Un flusso completo: catalogazione, ricerca, correzione di un errore
+#[test]
+fn ciclo_di_vita_di_una_campagna() {
+    let mut inv = Inventario::nuovo();
+
+    let ascia = inv.aggiungi(Reperto::nuovo("Ascia a margini rialzati", Periodo::Medio, 327.6)).unwrap();
+    let spada = inv.aggiungi(Reperto::nuovo("Spada a lingua da presa", Periodo::Finale, 779.6)).unwrap();
+    let doppione = inv.aggiungi(Reperto::nuovo("Spada a lingua da presa", Periodo::Finale, 779.6)).unwrap();
+    assert_eq!(inv.len(), 3);
+
+    // Il doppione registrato per errore viene tolto
+    inv.rimuovi(doppione).unwrap();
+    assert_eq!(inv.len(), 2);
+    assert_eq!(inv.cerca_per_id(doppione), Err(ErroreInventario::RepertoNonTrovato(doppione)));
+
+    assert_eq!(inv.cerca_per_id(ascia).unwrap().periodo, Periodo::Medio);
+    assert_eq!(inv.cerca_per_periodo(Periodo::Finale)[0].id, spada);
+    assert!((inv.peso_totale() - 1107.2).abs() < 1e-9);
+}
+
+/// Gli errori si propagano con `?` come qualsiasi `std::error::Error`
+#[test]
+fn gli_errori_si_propagano_con_il_punto_interrogativo() -> Result<(), Box<dyn std::error::Error>> {
+    let mut inv = Inventario::nuovo();
+    let id = inv.aggiungi(Reperto::nuovo("Fibula ad arco", Periodo::Recente, 26.9))?;
+    let fibula = inv.cerca_per_id(id)?;
+    assert_eq!(fibula.nome, "Fibula ad arco");
+    Ok(())
+}
+
+#[test]
+fn un_reperto_non_valido_non_sporca_l_inventario() {
+    let mut inv = Inventario::nuovo();
+    assert!(inv.aggiungi(Reperto::nuovo("", Periodo::Antico, 10.0)).is_err());
+    assert!(inv.aggiungi(Reperto::nuovo("Pugnale", Periodo::Antico, -1.0)).is_err());
+    assert!(inv.is_empty());
+    assert_eq!(inv.peso_totale(), 0.0);
+}
@@ -0,0 +1,35 @@
+// ============================================================================
+// TEST DELLO SCHELETRO DI PROGETTO (scaffold/)
+// ============================================================================
+// I file che `cargo run -- scaffold <nome>` copia nel nuovo progetto
+// vengono compilati qui come moduli, con `#[path]`: se uno smettesse di
+// compilare ce ne accorgeremmo prima di chi lo genera. Insieme a questi
+// test girano anche quelli scritti dentro lo scheletro.
+//
+// Esegui con: cargo test --test scaffold
+// ============================================================================
+
+#[path = "../scaffold/src/analisi/mod.rs"]
+mod analisi;
+#[path = "../scaffold/src/database.rs"]
+mod database;
+#[path = "../scaffold/src/report.rs"]
+mod report;
+#[path = "../scaffold/src/utils.rs"]
+mod utils;
+
+use database::{Database, Reperto};
+
+/// Come in scaffold/src/main.rs
+#[test]
+fn il_report_dello_scheletro() {
+    let mut db = Database::nuovo();
+    db.aggiungi(Reperto::nuovo("ascia a margini rialzati", "bronzo", 327.6));
+    db.aggiungi(Reperto::nuovo("macina", " pietra", 4150.0));
+
+    let testo = report::testo(&analisi::statistiche::riepilogo(db.tutti()));
+    assert_eq!(
+        testo,
+        "CATALOGO\n  Reperti:     2\n  Peso medio:  2.24 kg\n  Bronzo       1\n  Pietra       1\n"
+    );
+}
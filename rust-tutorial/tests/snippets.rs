@@ -0,0 +1,25 @@
+// ============================================================================
+// TEST DEGLI SNIPPET (snippets/)
+// ============================================================================
+// Ogni micro-esempio deve compilare e stampare esattamente il suo file
+// .out: il testo dei capitoli li cita, quindi non devono restare indietro.
+// Serve `rustc` nel PATH, come per `cargo run -- run <snippet>`.
+//
+// Esegui con: cargo test --test snippets
+// ============================================================================
+
+use rust_tutorial::snippet::{self, Riga};
+
+#[test]
+fn ogni_snippet_stampa_la_sua_uscita_attesa() {
+    let elenco = snippet::elenco(&snippet::cartella()).unwrap();
+    assert!(!elenco.is_empty());
+    for s in elenco {
+        assert!(!s.descrizione.is_empty(), "{}: manca il commento iniziale", s.nome);
+        let atteso = std::fs::read_to_string(s.atteso()).unwrap_or_else(|e| panic!("{}: {}", s.nome, e));
+        let ottenuto = s.esegui().unwrap_or_else(|e| panic!("{}: {}", s.nome, e));
+        let diverse: Vec<Riga> =
+            snippet::differenze(&atteso, &ottenuto).into_iter().filter(|r| !matches!(r, Riga::Uguale(_))).collect();
+        assert!(diverse.is_empty(), "{}: {:?}", s.nome, diverse);
+    }
+}